@@ -0,0 +1,541 @@
+//! Parsers that turn an agent CLI's stdout into [`AgentEvent`]s.
+//!
+//! pi's `--mode json`, Claude Code's `--output-format stream-json` and aider's
+//! plain-text output all map onto the same events, so tool tracking, token and
+//! cost accounting and failure summaries work whichever agent runs a bean.
+//! The parser is picked by the agent preset's [`OutputFormat`].
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::agent_presets::OutputFormat;
+use crate::pi_output::{self, AgentEvent};
+
+/// Incremental parser for one agent process's stdout.
+pub trait AgentOutputParser: Send {
+    /// Parse one line of output. A line can yield several events (e.g. a
+    /// Claude message with text and two tool calls) or none at all.
+    fn parse_line(&mut self, line: &str) -> Vec<AgentEvent>;
+}
+
+/// Create a fresh parser for the given output format.
+pub fn parser_for(format: OutputFormat) -> Box<dyn AgentOutputParser> {
+    match format {
+        OutputFormat::PiJson => Box::new(PiParser),
+        OutputFormat::ClaudeStreamJson => Box::<ClaudeStreamParser>::default(),
+        OutputFormat::AiderText => Box::<AiderParser>::default(),
+        OutputFormat::PlainText => Box::new(PlainTextParser),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// pi
+// ---------------------------------------------------------------------------
+
+/// pi's `--mode json` events (see [`pi_output::parse_agent_event`]).
+pub struct PiParser;
+
+impl AgentOutputParser for PiParser {
+    fn parse_line(&mut self, line: &str) -> Vec<AgentEvent> {
+        serde_json::from_str::<Value>(line)
+            .ok()
+            .and_then(|raw| pi_output::parse_agent_event(&raw))
+            .into_iter()
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Claude Code
+// ---------------------------------------------------------------------------
+
+/// Claude Code's `--output-format stream-json` messages.
+///
+/// Each API response can be streamed as several `assistant` lines sharing a
+/// message id and usage block, so usage is only counted once per message.
+/// Per-message usage carries no cost; the total arrives with the `result` line.
+#[derive(Default)]
+pub struct ClaudeStreamParser {
+    counted_messages: HashSet<String>,
+}
+
+impl AgentOutputParser for ClaudeStreamParser {
+    fn parse_line(&mut self, line: &str) -> Vec<AgentEvent> {
+        let Ok(raw) = serde_json::from_str::<Value>(line) else {
+            return Vec::new();
+        };
+        match raw.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => self.parse_assistant(&raw),
+            Some("user") => parse_tool_results(&raw),
+            Some("result") => {
+                let usage = raw.get("usage");
+                let total_tokens =
+                    usage_field(usage, "input_tokens") + usage_field(usage, "output_tokens");
+                let cost = raw
+                    .get("total_cost_usd")
+                    .and_then(|c| c.as_f64())
+                    .unwrap_or(0.0);
+                vec![AgentEvent::Finished { total_tokens, cost }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl ClaudeStreamParser {
+    fn parse_assistant(&mut self, raw: &Value) -> Vec<AgentEvent> {
+        let Some(message) = raw.get("message") else {
+            return Vec::new();
+        };
+        let mut events = Vec::new();
+
+        for block in content_blocks(message) {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("thinking") => {
+                    if let Some(text) = block.get("thinking").and_then(|t| t.as_str()) {
+                        events.push(AgentEvent::Thinking {
+                            text: text.to_string(),
+                        });
+                    }
+                }
+                Some("text") => {
+                    if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
+                        events.push(AgentEvent::Text {
+                            text: text.to_string(),
+                        });
+                    }
+                }
+                Some("tool_use") => {
+                    let name = block
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let id = block
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    // Tool input arrives complete, so start and end together
+                    events.push(AgentEvent::ToolStart {
+                        name: name.clone(),
+                        id,
+                    });
+                    events.push(AgentEvent::ToolEnd {
+                        name,
+                        arguments: block.get("input").cloned().unwrap_or(Value::Null),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let first_sighting = match message.get("id").and_then(|i| i.as_str()) {
+            Some(id) => self.counted_messages.insert(id.to_string()),
+            None => true,
+        };
+        if first_sighting {
+            let usage = message.get("usage");
+            let input_tokens = usage_field(usage, "input_tokens");
+            let output_tokens = usage_field(usage, "output_tokens");
+            if input_tokens > 0 || output_tokens > 0 {
+                events.push(AgentEvent::TokenUpdate {
+                    input_tokens,
+                    output_tokens,
+                    cache_read: usage_field(usage, "cache_read_input_tokens"),
+                    cache_write: usage_field(usage, "cache_creation_input_tokens"),
+                    cost: 0.0,
+                });
+            }
+        }
+
+        events
+    }
+}
+
+/// `tool_result` blocks from a `user` message.
+fn parse_tool_results(raw: &Value) -> Vec<AgentEvent> {
+    let Some(message) = raw.get("message") else {
+        return Vec::new();
+    };
+    content_blocks(message)
+        .iter()
+        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+        .map(|block| {
+            let id = block
+                .get("tool_use_id")
+                .and_then(|i| i.as_str())
+                .unwrap_or("")
+                .to_string();
+            // Content is either a string or a list of text blocks
+            let output = match block.get("content") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Array(parts)) => parts
+                    .iter()
+                    .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            AgentEvent::ToolResult { id, output }
+        })
+        .collect()
+}
+
+fn content_blocks(message: &Value) -> &[Value] {
+    message
+        .get("content")
+        .and_then(|c| c.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn usage_field(usage: Option<&Value>, key: &str) -> u64 {
+    usage
+        .and_then(|u| u.get(key))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+// ---------------------------------------------------------------------------
+// aider
+// ---------------------------------------------------------------------------
+
+/// aider's plain-text console output (best run with `--no-pretty`).
+///
+/// Recognizes the per-message `Tokens: ... Cost: ...` report and
+/// `Applied edit to <file>` lines; everything else is passed on as text.
+#[derive(Default)]
+pub struct AiderParser {
+    edits: usize,
+}
+
+impl AgentOutputParser for AiderParser {
+    fn parse_line(&mut self, line: &str) -> Vec<AgentEvent> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Vec::new();
+        }
+        if let Some(update) = parse_aider_tokens(line) {
+            return vec![update];
+        }
+        if let Some(path) = line.strip_prefix("Applied edit to ") {
+            self.edits += 1;
+            return vec![
+                AgentEvent::ToolStart {
+                    name: "Edit".to_string(),
+                    id: format!("aider-edit-{}", self.edits),
+                },
+                AgentEvent::ToolEnd {
+                    name: "Edit".to_string(),
+                    arguments: serde_json::json!({ "path": path.trim() }),
+                },
+            ];
+        }
+        vec![AgentEvent::Text {
+            text: line.to_string(),
+        }]
+    }
+}
+
+/// Parse `Tokens: 2.3k sent, 145 received. Cost: $0.0093 message, $0.02 session.`
+fn parse_aider_tokens(line: &str) -> Option<AgentEvent> {
+    let rest = line.strip_prefix("Tokens: ")?;
+    let (counts, cost_part) = match rest.split_once("Cost:") {
+        Some((counts, cost)) => (counts, Some(cost)),
+        None => (rest, None),
+    };
+
+    let mut input_tokens = 0;
+    let mut output_tokens = 0;
+    let mut cache_write = 0;
+    let mut cache_read = 0;
+    // Items are separated by ", "; bare commas are thousands separators
+    for part in counts.split(", ") {
+        let part = part.trim().trim_end_matches('.');
+        let Some((amount, label)) = part.split_once(' ') else {
+            continue;
+        };
+        let Some(amount) = parse_token_amount(amount) else {
+            continue;
+        };
+        match label.trim() {
+            "sent" => input_tokens = amount,
+            "received" => output_tokens = amount,
+            "cache write" => cache_write = amount,
+            "cache hit" => cache_read = amount,
+            _ => {}
+        }
+    }
+    if input_tokens == 0 && output_tokens == 0 {
+        return None;
+    }
+
+    // The first amount is this message's cost; the second is the session total
+    let cost = cost_part
+        .and_then(|c| c.trim().strip_prefix('$'))
+        .and_then(|c| c.split_whitespace().next())
+        .and_then(|c| c.parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    Some(AgentEvent::TokenUpdate {
+        input_tokens,
+        output_tokens,
+        cache_read,
+        cache_write,
+        cost,
+    })
+}
+
+/// Parse aider's abbreviated counts: `145`, `2,345`, `2.3k`, `1.1M`.
+fn parse_token_amount(s: &str) -> Option<u64> {
+    let s = s.replace(',', "");
+    let (number, scale) = if let Some(n) = s.strip_suffix('k') {
+        (n, 1_000.0)
+    } else if let Some(n) = s.strip_suffix('M') {
+        (n, 1_000_000.0)
+    } else {
+        (s.as_str(), 1.0)
+    };
+    let value: f64 = number.parse().ok()?;
+    Some((value * scale).round() as u64)
+}
+
+// ---------------------------------------------------------------------------
+// Plain text
+// ---------------------------------------------------------------------------
+
+/// Agents without structured output: every non-blank line is text.
+pub struct PlainTextParser;
+
+impl AgentOutputParser for PlainTextParser {
+    fn parse_line(&mut self, line: &str) -> Vec<AgentEvent> {
+        if line.trim().is_empty() {
+            return Vec::new();
+        }
+        vec![AgentEvent::Text {
+            text: line.to_string(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_all(parser: &mut dyn AgentOutputParser, lines: &[Value]) -> Vec<AgentEvent> {
+        lines
+            .iter()
+            .flat_map(|l| parser.parse_line(&l.to_string()))
+            .collect()
+    }
+
+    // ── pi ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn pi_parser_wraps_parse_agent_event() {
+        let mut parser = parser_for(OutputFormat::PiJson);
+        let line = json!({ "result": { "totalTokens": 10, "cost": 0.5 } }).to_string();
+        assert_eq!(
+            parser.parse_line(&line),
+            vec![AgentEvent::Finished {
+                total_tokens: 10,
+                cost: 0.5
+            }]
+        );
+        assert!(parser.parse_line("not json").is_empty());
+    }
+
+    // ── Claude Code ────────────────────────────────────────────────
+
+    #[test]
+    fn claude_assistant_message_yields_text_tools_and_tokens() {
+        let mut parser = parser_for(OutputFormat::ClaudeStreamJson);
+        let events = parse_all(
+            parser.as_mut(),
+            &[json!({
+                "type": "assistant",
+                "message": {
+                    "id": "msg_01",
+                    "content": [
+                        { "type": "thinking", "thinking": "plan" },
+                        { "type": "text", "text": "Reading the file" },
+                        {
+                            "type": "tool_use",
+                            "id": "toolu_01",
+                            "name": "Read",
+                            "input": { "file_path": "src/lib.rs" }
+                        }
+                    ],
+                    "usage": {
+                        "input_tokens": 1200,
+                        "output_tokens": 80,
+                        "cache_read_input_tokens": 5000,
+                        "cache_creation_input_tokens": 300
+                    }
+                }
+            })],
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                AgentEvent::Thinking {
+                    text: "plan".into()
+                },
+                AgentEvent::Text {
+                    text: "Reading the file".into()
+                },
+                AgentEvent::ToolStart {
+                    name: "Read".into(),
+                    id: "toolu_01".into()
+                },
+                AgentEvent::ToolEnd {
+                    name: "Read".into(),
+                    arguments: json!({ "file_path": "src/lib.rs" })
+                },
+                AgentEvent::TokenUpdate {
+                    input_tokens: 1200,
+                    output_tokens: 80,
+                    cache_read: 5000,
+                    cache_write: 300,
+                    cost: 0.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn claude_usage_counted_once_per_message_id() {
+        let mut parser = parser_for(OutputFormat::ClaudeStreamJson);
+        let chunk = |text: &str| {
+            json!({
+                "type": "assistant",
+                "message": {
+                    "id": "msg_02",
+                    "content": [{ "type": "text", "text": text }],
+                    "usage": { "input_tokens": 100, "output_tokens": 10 }
+                }
+            })
+        };
+        let events = parse_all(parser.as_mut(), &[chunk("a"), chunk("b")]);
+        let token_updates = events
+            .iter()
+            .filter(|e| matches!(e, AgentEvent::TokenUpdate { .. }))
+            .count();
+        assert_eq!(token_updates, 1);
+    }
+
+    #[test]
+    fn claude_tool_results_and_final_result() {
+        let mut parser = parser_for(OutputFormat::ClaudeStreamJson);
+        let events = parse_all(
+            parser.as_mut(),
+            &[
+                json!({
+                    "type": "user",
+                    "message": {
+                        "content": [{
+                            "type": "tool_result",
+                            "tool_use_id": "toolu_01",
+                            "content": [{ "type": "text", "text": "fn main() {}" }]
+                        }]
+                    }
+                }),
+                json!({
+                    "type": "result",
+                    "subtype": "success",
+                    "total_cost_usd": 0.42,
+                    "usage": { "input_tokens": 3000, "output_tokens": 400 }
+                }),
+                json!({ "type": "system", "subtype": "init" }),
+            ],
+        );
+
+        assert_eq!(
+            events,
+            vec![
+                AgentEvent::ToolResult {
+                    id: "toolu_01".into(),
+                    output: "fn main() {}".into()
+                },
+                AgentEvent::Finished {
+                    total_tokens: 3400,
+                    cost: 0.42
+                },
+            ]
+        );
+    }
+
+    // ── aider ──────────────────────────────────────────────────────
+
+    #[test]
+    fn aider_token_report_becomes_token_update() {
+        let mut parser = parser_for(OutputFormat::AiderText);
+        let events = parser.parse_line(
+            "Tokens: 2.3k sent, 1,024 cache write, 145 received. Cost: $0.0093 message, $0.02 session.",
+        );
+        assert_eq!(
+            events,
+            vec![AgentEvent::TokenUpdate {
+                input_tokens: 2300,
+                output_tokens: 145,
+                cache_read: 0,
+                cache_write: 1024,
+                cost: 0.0093
+            }]
+        );
+    }
+
+    #[test]
+    fn aider_applied_edit_becomes_tool_events() {
+        let mut parser = parser_for(OutputFormat::AiderText);
+        let events = parser.parse_line("Applied edit to src/parser.rs");
+        assert_eq!(events.len(), 2);
+        match &events[1] {
+            AgentEvent::ToolEnd { name, arguments } => {
+                assert_eq!(name, "Edit");
+                assert_eq!(
+                    pi_output::extract_file_path(name, arguments).as_deref(),
+                    Some("src/parser.rs")
+                );
+            }
+            other => unreachable!("expected ToolEnd, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn aider_other_lines_are_text() {
+        let mut parser = parser_for(OutputFormat::AiderText);
+        assert!(parser.parse_line("   ").is_empty());
+        assert_eq!(
+            parser.parse_line("Tokens: none yet"),
+            vec![AgentEvent::Text {
+                text: "Tokens: none yet".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn plain_text_lines_are_text() {
+        let mut parser = parser_for(OutputFormat::PlainText);
+        assert!(parser.parse_line("").is_empty());
+        assert_eq!(
+            parser.parse_line("working on it"),
+            vec![AgentEvent::Text {
+                text: "working on it".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_token_amount_handles_suffixes() {
+        assert_eq!(parse_token_amount("145"), Some(145));
+        assert_eq!(parse_token_amount("2,345"), Some(2345));
+        assert_eq!(parse_token_amount("2.3k"), Some(2300));
+        assert_eq!(parse_token_amount("1.1M"), Some(1_100_000));
+        assert_eq!(parse_token_amount("lots"), None);
+    }
+}
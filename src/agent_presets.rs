@@ -1,12 +1,134 @@
 //! Agent presets and detection for known coding-agent CLIs.
 //!
-//! Provides built-in presets (pi, claude, aider) with run/plan templates,
-//! and runtime detection of which agents are available on PATH.
+//! Provides built-in presets (pi, claude, aider, codex) with run/plan templates,
+//! how to launch each agent in direct mode, the output format it emits, and
+//! runtime detection of which agents are available on PATH.
 
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// A known agent preset with command templates.
+use crate::prompt::PromptResult;
+
+/// Structured output format an agent emits on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// pi `--mode json` event stream.
+    PiJson,
+    /// Claude Code `--output-format stream-json` messages.
+    ClaudeStreamJson,
+    /// aider's plain-text console output.
+    AiderText,
+    /// Unstructured text; every line is treated as agent output.
+    PlainText,
+}
+
+/// How a direct-mode agent receives its prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptDelivery {
+    /// As the last positional argument.
+    Arg,
+    /// Written to the agent's stdin, which is then closed.
+    Stdin,
+    /// Written to a file whose path follows the given flag (e.g. `--message-file`).
+    File(&'static str),
+}
+
+/// How a direct-mode agent is pointed at the bean file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeanFileRef {
+    /// pi-style `@path` positional argument.
+    At,
+    /// Passed as a read-only file with the given flag (e.g. aider's `--read`).
+    Flag(&'static str),
+    /// Named in the prompt; the agent reads it with its own tools.
+    Mention,
+}
+
+/// How to launch an agent directly (no shell) in non-interactive mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectLaunch {
+    /// Binary to execute.
+    pub program: &'static str,
+    /// Arguments for non-interactive runs with structured output.
+    pub args: &'static [&'static str],
+    /// Flag that appends to the agent's system prompt. Without one, the bean
+    /// context is prepended to the user message instead.
+    pub system_prompt_flag: Option<&'static str>,
+    /// How the bean file is referenced.
+    pub bean_file: BeanFileRef,
+    /// How the prompt is delivered.
+    pub prompt: PromptDelivery,
+}
+
+/// A fully-resolved direct-mode command line.
 #[derive(Debug, Clone, PartialEq)]
+pub struct DirectInvocation {
+    pub program: &'static str,
+    pub args: Vec<String>,
+    /// Prompt to write to the agent's stdin.
+    pub stdin: Option<String>,
+    /// Prompt to write to this file (already named in `args`) before spawning.
+    pub prompt_file: Option<(PathBuf, String)>,
+}
+
+impl DirectLaunch {
+    /// Resolve the command line for a built prompt.
+    ///
+    /// `prompt_file` is only used with [`PromptDelivery::File`].
+    #[must_use]
+    pub fn invocation(&self, prompt: &PromptResult, prompt_file: &Path) -> DirectInvocation {
+        let mut args: Vec<String> = self.args.iter().map(|a| a.to_string()).collect();
+        let bean_path = prompt.file_ref.trim_start_matches('@');
+
+        let mut message = String::new();
+        match self.system_prompt_flag {
+            Some(flag) if !prompt.system_prompt.is_empty() => {
+                args.push(flag.to_string());
+                args.push(prompt.system_prompt.clone());
+            }
+            _ => {
+                if !prompt.system_prompt.is_empty() {
+                    message.push_str(&prompt.system_prompt);
+                    message.push_str("\n\n---\n\n");
+                }
+            }
+        }
+
+        if !bean_path.is_empty() {
+            match self.bean_file {
+                BeanFileRef::At => args.push(prompt.file_ref.clone()),
+                BeanFileRef::Flag(flag) => {
+                    args.push(flag.to_string());
+                    args.push(bean_path.to_string());
+                }
+                BeanFileRef::Mention => {
+                    message.push_str(&format!("The bean spec is in {}.\n\n", bean_path));
+                }
+            }
+        }
+        message.push_str(&prompt.user_message);
+
+        let mut invocation = DirectInvocation {
+            program: self.program,
+            args,
+            stdin: None,
+            prompt_file: None,
+        };
+        match self.prompt {
+            PromptDelivery::Arg => invocation.args.push(message),
+            PromptDelivery::Stdin => invocation.stdin = Some(message),
+            PromptDelivery::File(flag) => {
+                invocation.args.push(flag.to_string());
+                invocation.args.push(prompt_file.display().to_string());
+                invocation.prompt_file = Some((prompt_file.to_path_buf(), message));
+            }
+        }
+        invocation
+    }
+}
+
+/// A known agent preset with command templates.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AgentPreset {
     /// Agent name (e.g. "pi", "claude", "aider").
     pub name: &'static str,
@@ -16,6 +138,10 @@ pub struct AgentPreset {
     pub plan_template: &'static str,
     /// Command to check the agent version (e.g. `pi --version`).
     pub version_cmd: &'static str,
+    /// Format of the agent's stdout, used to pick an output parser.
+    pub output_format: OutputFormat,
+    /// How `bn run` launches the agent in direct mode.
+    pub direct: DirectLaunch,
 }
 
 /// An agent detected on the current system.
@@ -39,18 +165,63 @@ const PRESETS: &[AgentPreset] = &[
         run_template: "pi @.beans/{id}-*.md \"implement and bn close {id}\"",
         plan_template: "pi @.beans/{id}-*.md \"plan into children with bn create --parent {id}\"",
         version_cmd: "pi --version",
+        output_format: OutputFormat::PiJson,
+        direct: DirectLaunch {
+            program: "pi",
+            args: &["--mode", "json", "--print", "--no-session"],
+            system_prompt_flag: Some("--append-system-prompt"),
+            bean_file: BeanFileRef::At,
+            prompt: PromptDelivery::Arg,
+        },
     },
     AgentPreset {
         name: "claude",
         run_template: "claude -p \"implement bean {id} and run bn close {id}\"",
         plan_template: "claude -p \"bean {id} is too large, split with bn create --parent {id}\"",
         version_cmd: "claude --version",
+        output_format: OutputFormat::ClaudeStreamJson,
+        direct: DirectLaunch {
+            program: "claude",
+            args: &[
+                "-p",
+                "--output-format",
+                "stream-json",
+                "--verbose",
+                "--permission-mode",
+                "acceptEdits",
+            ],
+            system_prompt_flag: Some("--append-system-prompt"),
+            bean_file: BeanFileRef::Mention,
+            prompt: PromptDelivery::Stdin,
+        },
     },
     AgentPreset {
         name: "aider",
         run_template: "aider --message \"implement bean {id}, verify with bn close {id}\"",
         plan_template: "aider --message \"plan bean {id} into children with bn create\"",
         version_cmd: "aider --version",
+        output_format: OutputFormat::AiderText,
+        direct: DirectLaunch {
+            program: "aider",
+            args: &["--yes-always", "--no-pretty", "--no-stream"],
+            system_prompt_flag: None,
+            bean_file: BeanFileRef::Flag("--read"),
+            prompt: PromptDelivery::File("--message-file"),
+        },
+    },
+    AgentPreset {
+        name: "codex",
+        run_template: "codex exec --full-auto \"implement bean {id} and run bn close {id}\"",
+        plan_template: "codex exec --full-auto \"split bean {id} with bn create --parent {id}\"",
+        version_cmd: "codex --version",
+        output_format: OutputFormat::PlainText,
+        direct: DirectLaunch {
+            program: "codex",
+            args: &["exec", "--full-auto"],
+            system_prompt_flag: None,
+            bean_file: BeanFileRef::Mention,
+            prompt: PromptDelivery::Arg,
+        },
     },
 ];
 
@@ -98,6 +269,12 @@ impl AgentPreset {
     pub fn plan_cmd(&self, id: &str) -> String {
         self.plan_template.replace("{id}", id)
     }
+
+    /// Whether the agent's direct-mode binary is on PATH.
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        which_binary(self.direct.program).is_some()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -223,6 +400,103 @@ mod tests {
         assert!(!cmd.contains("{id}"));
     }
 
+    #[test]
+    fn presets_declare_their_output_format() {
+        assert_eq!(
+            get_preset("pi").unwrap().output_format,
+            OutputFormat::PiJson
+        );
+        assert_eq!(
+            get_preset("claude").unwrap().output_format,
+            OutputFormat::ClaudeStreamJson
+        );
+        assert_eq!(
+            get_preset("aider").unwrap().output_format,
+            OutputFormat::AiderText
+        );
+    }
+
+    fn sample_prompt() -> PromptResult {
+        PromptResult {
+            system_prompt: "SYSTEM".to_string(),
+            user_message: "Implement bean 3".to_string(),
+            file_ref: "@.beans/3-task.md".to_string(),
+        }
+    }
+
+    #[test]
+    fn pi_invocation_passes_everything_as_args() {
+        let inv = get_preset("pi")
+            .unwrap()
+            .direct
+            .invocation(&sample_prompt(), Path::new("/tmp/p.md"));
+        assert_eq!(inv.program, "pi");
+        assert_eq!(
+            inv.args,
+            vec![
+                "--mode",
+                "json",
+                "--print",
+                "--no-session",
+                "--append-system-prompt",
+                "SYSTEM",
+                "@.beans/3-task.md",
+                "Implement bean 3",
+            ]
+        );
+        assert!(inv.stdin.is_none());
+        assert!(inv.prompt_file.is_none());
+    }
+
+    #[test]
+    fn claude_invocation_writes_prompt_to_stdin() {
+        let inv = get_preset("claude")
+            .unwrap()
+            .direct
+            .invocation(&sample_prompt(), Path::new("/tmp/p.md"));
+        assert_eq!(inv.program, "claude");
+        assert!(inv.args.contains(&"stream-json".to_string()));
+        assert_eq!(
+            inv.args[inv.args.len() - 2..],
+            ["--append-system-prompt", "SYSTEM"]
+        );
+        assert_eq!(
+            inv.stdin.as_deref(),
+            Some("The bean spec is in .beans/3-task.md.\n\nImplement bean 3")
+        );
+    }
+
+    #[test]
+    fn aider_invocation_uses_message_file() {
+        let inv = get_preset("aider")
+            .unwrap()
+            .direct
+            .invocation(&sample_prompt(), Path::new("/tmp/p.md"));
+        assert!(inv.args.ends_with(&[
+            "--read".to_string(),
+            ".beans/3-task.md".to_string(),
+            "--message-file".to_string(),
+            "/tmp/p.md".to_string(),
+        ]));
+        let (path, message) = inv.prompt_file.unwrap();
+        assert_eq!(path, Path::new("/tmp/p.md"));
+        // No system prompt flag: the context leads the message instead
+        assert_eq!(message, "SYSTEM\n\n---\n\nImplement bean 3");
+    }
+
+    #[test]
+    fn codex_invocation_mentions_bean_file_in_prompt() {
+        let inv = get_preset("codex")
+            .unwrap()
+            .direct
+            .invocation(&sample_prompt(), Path::new("/tmp/p.md"));
+        assert_eq!(&inv.args[..2], ["exec", "--full-auto"]);
+        assert_eq!(
+            inv.args[2],
+            "SYSTEM\n\n---\n\nThe bean spec is in .beans/3-task.md.\n\nImplement bean 3"
+        );
+    }
+
     #[test]
     fn detect_agents_returns_vec() {
         // Smoke test — just ensure it doesn't panic. Actual results
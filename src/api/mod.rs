@@ -7,9 +7,13 @@
 //!
 //! - **Types** — Core data structures (`Bean`, `Index`, `Status`, etc.)
 //! - **Discovery** — Find `.beans/` directories and bean files
-//! - **Query** — Read-only operations (list, get, tree, status, graph)
-//! - **Mutations** — Write operations (create, update, close, delete)
-//! - **Orchestration** — Agent dispatch, monitoring, and control
+//! - **Query** — Read-only operations (list, get, status, stats, dependencies)
+//! - **Mutations** — Write operations (create, update, close, claim, dependencies)
+//! - **Orchestration** — Agent dispatch with an event channel
+//!
+//! Query, mutation and orchestration functions return [`Result`], whose
+//! [`BeansError`] names the cases a caller may want to handle (not found,
+//! cycle, verify already passes, ...) instead of a formatted message.
 //!
 //! ## Quick Start
 //!
@@ -25,6 +29,16 @@
 //! // Get a specific bean
 //! let bean = get_bean(&beans_dir, "1").unwrap();
 //! println!("{}: {}", bean.id, bean.title);
+//!
+//! // Create, claim and close one
+//! let mut params = CreateParams::new("Fix the parser");
+//! params.verify = Some("cargo test parser".to_string());
+//! let bean = create_bean(&beans_dir, params).unwrap();
+//! claim(&beans_dir, &bean.id, ClaimParams::default()).unwrap();
+//! match close_bean(&beans_dir, &bean.id, CloseParams::default()).unwrap() {
+//!     CloseOutcome::Closed { .. } => println!("closed"),
+//!     other => println!("not closed: {:?}", other),
+//! }
 //! ```
 //!
 //! ## Design Principles
@@ -38,9 +52,7 @@
 //! - **Composable** — Functions take `&Path` (beans_dir) and return owned data.
 //!   No global state, no singletons.
 
-use std::path::Path;
-
-use anyhow::Result;
+use std::path::{Path, PathBuf};
 
 // ---------------------------------------------------------------------------
 // Re-exported core types
@@ -64,7 +76,8 @@ pub use crate::discovery::{
 
 // Graph functions
 pub use crate::graph::{
-    build_dependency_tree, build_full_graph, count_subtree_attempts, detect_cycle, find_all_cycles,
+    build_dependency_tree, build_full_graph, count_subtree_attempts, cycle_path, detect_cycle,
+    find_all_cycles,
 };
 
 // Utility
@@ -83,7 +96,7 @@ pub use crate::bean::validate_priority;
 /// - Bean ID is invalid
 /// - No bean file found for the given ID
 /// - File cannot be parsed
-pub fn get_bean(beans_dir: &Path, id: &str) -> Result<Bean> {
+pub fn get_bean(beans_dir: &Path, id: &str) -> anyhow::Result<Bean> {
     let path = find_bean_file(beans_dir, id)?;
     Bean::from_file(&path)
 }
@@ -93,7 +106,7 @@ pub fn get_bean(beans_dir: &Path, id: &str) -> Result<Bean> {
 /// # Errors
 /// - Bean ID not found in archive
 /// - File cannot be parsed
-pub fn get_archived_bean(beans_dir: &Path, id: &str) -> Result<Bean> {
+pub fn get_archived_bean(beans_dir: &Path, id: &str) -> anyhow::Result<Bean> {
     let path = find_archived_bean(beans_dir, id)?;
     Bean::from_file(&path)
 }
@@ -102,14 +115,29 @@ pub fn get_archived_bean(beans_dir: &Path, id: &str) -> Result<Bean> {
 ///
 /// This is the main entry point for reading bean metadata.
 /// The index is a YAML cache that's faster than reading every bean file.
-pub fn load_index(beans_dir: &Path) -> Result<Index> {
+pub fn load_index(beans_dir: &Path) -> anyhow::Result<Index> {
     Index::load_or_rebuild(beans_dir)
 }
 
+/// Check that `id` is a valid ID of an active bean and return its file.
+fn check_id(beans_dir: &Path, id: &str) -> Result<PathBuf> {
+    Ok(find_bean_file(beans_dir, id)?)
+}
+
 // ---------------------------------------------------------------------------
-// Submodules (added as they are implemented)
+// Submodules
 // ---------------------------------------------------------------------------
 
-// pub mod query;         // Phase 1: 88.2.2
-// pub mod mutations;     // Phase 1: 88.2.5, 88.2.6, 88.2.7
-// pub mod orchestration; // Phase 1: 88.2.4
+pub mod mutations;
+pub mod orchestration;
+pub mod query;
+
+pub use crate::error::{BeansError, Result};
+pub use mutations::{
+    add_dependency, claim, close_bean, create_bean, release, remove_dependency, update_bean,
+    ClaimParams, CloseParams, CreateParams, UpdateParams,
+};
+pub use orchestration::{run_dispatch, RunEvent, RunHandle, RunParams};
+pub use query::{dependencies, list_beans, ready_beans, stats, status, ListFilter};
+
+pub use crate::commands::close::CloseOutcome;
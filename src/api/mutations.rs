@@ -0,0 +1,208 @@
+//! Write operations: create, update, close, claim, dependencies.
+//!
+//! These run the same validation and hooks as the CLI commands and rebuild
+//! the index afterwards, but print nothing: the CLI's progress messages and
+//! warnings are discarded.
+
+use std::path::Path;
+
+use super::CloseOutcome;
+use super::{check_id, Bean, OnFailAction};
+use super::{BeansError, Result};
+use crate::commands::create::CreateArgs;
+use crate::commands::update::BeanUpdate;
+use crate::index::Index;
+
+/// Fields for [`create_bean`]. Only `title` is required.
+#[derive(Debug, Clone, Default)]
+pub struct CreateParams {
+    pub title: String,
+    pub description: Option<String>,
+    pub acceptance: Option<String>,
+    pub notes: Option<String>,
+    pub design: Option<String>,
+    pub verify: Option<String>,
+    pub priority: Option<u8>,
+    pub labels: Vec<String>,
+    pub assignee: Option<String>,
+    pub dependencies: Vec<String>,
+    /// Create as a child of this bean (gets ID `{parent}.{n}`).
+    pub parent: Option<String>,
+    pub produces: Vec<String>,
+    pub requires: Vec<String>,
+    pub paths: Vec<String>,
+    pub on_fail: Option<OnFailAction>,
+    /// Skip the fail-first check (the verify command may already pass).
+    pub pass_ok: bool,
+    /// Claim the bean right after creating it.
+    pub claim: bool,
+    /// Who claims it, when `claim` is set (defaults to the configured identity).
+    pub claimed_by: Option<String>,
+    pub verify_timeout: Option<u64>,
+    /// Product feature: only a human can close it.
+    pub feature: bool,
+}
+
+impl CreateParams {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Field changes for [`update_bean`]. `None` leaves a field unchanged;
+/// `notes` is appended rather than replaced.
+pub type UpdateParams = BeanUpdate;
+
+/// Options for [`close_bean`].
+#[derive(Debug, Clone, Default)]
+pub struct CloseParams {
+    pub reason: Option<String>,
+    /// Close without running the verify command.
+    pub force: bool,
+}
+
+/// Options for [`claim`].
+#[derive(Debug, Clone, Default)]
+pub struct ClaimParams {
+    /// Who is claiming (defaults to the configured identity).
+    pub by: Option<String>,
+    /// Skip the verify-before-claim check.
+    pub force: bool,
+}
+
+/// Join a list the way the CLI's comma-separated flags expect it.
+fn joined(values: Vec<String>) -> Option<String> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(","))
+    }
+}
+
+fn check_priority(priority: Option<u8>) -> Result<()> {
+    if let Some(p) = priority {
+        crate::bean::validate_priority(p)?;
+    }
+    Ok(())
+}
+
+/// Create a bean and return it.
+///
+/// Unless `pass_ok` is set, the verify command must fail first
+/// ([`BeansError::VerifyAlreadyPasses`] otherwise).
+pub fn create_bean(beans_dir: &Path, params: CreateParams) -> Result<Bean> {
+    check_priority(params.priority)?;
+    if let Some(ref parent) = params.parent {
+        check_id(beans_dir, parent)?;
+    }
+
+    let args = CreateArgs {
+        title: params.title,
+        description: params.description,
+        acceptance: params.acceptance,
+        notes: params.notes,
+        design: params.design,
+        verify: params.verify,
+        priority: params.priority,
+        labels: joined(params.labels),
+        assignee: params.assignee,
+        deps: joined(params.dependencies),
+        parent: params.parent,
+        produces: joined(params.produces),
+        requires: joined(params.requires),
+        paths: joined(params.paths),
+        on_fail: params.on_fail,
+        pass_ok: params.pass_ok,
+        claim: params.claim,
+        by: params.claimed_by,
+        verify_timeout: params.verify_timeout,
+        feature: params.feature,
+    };
+    Ok(crate::commands::create::create_bean(
+        beans_dir,
+        args,
+        &mut std::io::sink(),
+    )?)
+}
+
+/// Apply field changes to an active bean and return it.
+pub fn update_bean(beans_dir: &Path, id: &str, params: UpdateParams) -> Result<Bean> {
+    check_id(beans_dir, id)?;
+    check_priority(params.priority)?;
+    if let Some(ref status) = params.status {
+        if crate::util::parse_status(status).is_none() {
+            return Err(BeansError::Invalid(format!("Invalid status: {}", status)));
+        }
+    }
+    Ok(crate::commands::update::update_bean(
+        beans_dir,
+        id,
+        params,
+        &mut std::io::sink(),
+    )?)
+}
+
+/// Close a bean: run its pre-close hook and verify command, then archive it.
+///
+/// A failed verify or a rejecting hook is not an error; it is reported in
+/// the [`CloseOutcome`]. Feature beans are never closed here and come back
+/// as [`CloseOutcome::NeedsReview`].
+pub fn close_bean(beans_dir: &Path, id: &str, params: CloseParams) -> Result<CloseOutcome> {
+    check_id(beans_dir, id)?;
+    let outcome = crate::commands::close::close_bean(
+        beans_dir,
+        id,
+        params.reason,
+        params.force,
+        false,
+        &mut std::io::sink(),
+        &mut std::io::sink(),
+    )?;
+    // Worktree cleanup can remove the beans dir
+    if beans_dir.exists() {
+        Index::build(beans_dir)?.save(beans_dir)?;
+    }
+    Ok(outcome)
+}
+
+/// Claim an open bean for work and return it.
+pub fn claim(beans_dir: &Path, id: &str, params: ClaimParams) -> Result<Bean> {
+    check_id(beans_dir, id)?;
+    Ok(crate::commands::claim::claim_bean(
+        beans_dir,
+        id,
+        params.by,
+        params.force,
+        &mut std::io::sink(),
+    )?)
+}
+
+/// Release a claim, returning the bean to open.
+pub fn release(beans_dir: &Path, id: &str) -> Result<Bean> {
+    check_id(beans_dir, id)?;
+    Ok(crate::commands::claim::release_bean(
+        beans_dir,
+        id,
+        &mut std::io::sink(),
+    )?)
+}
+
+/// Make `id` depend on `depends_on`.
+pub fn add_dependency(beans_dir: &Path, id: &str, depends_on: &str) -> Result<()> {
+    check_id(beans_dir, id)?;
+    check_id(beans_dir, depends_on)?;
+    Ok(crate::commands::dep::add_dependency(
+        beans_dir, id, depends_on,
+    )?)
+}
+
+/// Drop `depends_on` from the dependencies of `id`.
+pub fn remove_dependency(beans_dir: &Path, id: &str, depends_on: &str) -> Result<()> {
+    check_id(beans_dir, id)?;
+    Ok(crate::commands::dep::remove_dependency(
+        beans_dir, id, depends_on,
+    )?)
+}
@@ -0,0 +1,128 @@
+//! Agent dispatch.
+//!
+//! [`run_dispatch`] starts the same scheduler as `bn run` on a background
+//! thread and hands back its progress as [`RunEvent`]s instead of JSON lines.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+
+use super::{BeansError, Result};
+use crate::commands::run::RunArgs;
+
+pub use crate::stream::{BeanInfo, FileOverlapInfo, RoundPlan, StreamEvent as RunEvent};
+
+/// Options for [`run_dispatch`]. The defaults match `bn run`.
+#[derive(Debug, Clone)]
+pub struct RunParams {
+    /// Run only this bean (or this parent's ready children).
+    pub id: Option<String>,
+    /// Maximum parallel agents.
+    pub jobs: u32,
+    /// Plan the run and report it without spawning agents.
+    pub dry_run: bool,
+    /// Keep dispatching until nothing is ready.
+    pub loop_mode: bool,
+    /// Plan large beans before dispatching them.
+    pub auto_plan: bool,
+    /// Continue past failures.
+    pub keep_going: bool,
+    /// Total agent timeout in minutes.
+    pub timeout_minutes: u32,
+    /// Kill an agent after this many minutes without output.
+    pub idle_timeout_minutes: u32,
+    /// Run an adversarial review after each successful close.
+    pub review: bool,
+    /// Only dispatch beans matching this filter expression.
+    pub filter: Option<String>,
+}
+
+impl Default for RunParams {
+    fn default() -> Self {
+        Self {
+            id: None,
+            jobs: 4,
+            dry_run: false,
+            loop_mode: false,
+            auto_plan: false,
+            keep_going: false,
+            timeout_minutes: 30,
+            idle_timeout_minutes: 5,
+            review: false,
+            filter: None,
+        }
+    }
+}
+
+/// A run in progress. Events arrive on [`events`](RunHandle::events) until
+/// the run finishes, then the channel closes.
+pub struct RunHandle {
+    events: Receiver<RunEvent>,
+    thread: JoinHandle<anyhow::Result<()>>,
+}
+
+impl RunHandle {
+    /// The run's event stream.
+    pub fn events(&self) -> &Receiver<RunEvent> {
+        &self.events
+    }
+
+    /// Whether the run has finished.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Wait for the run to finish and return its result. Events not yet
+    /// received are dropped.
+    pub fn wait(self) -> Result<()> {
+        drop(self.events);
+        match self.thread.join() {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(BeansError::Other(anyhow::anyhow!("run thread panicked"))),
+        }
+    }
+}
+
+/// Start dispatching ready beans to agents.
+///
+/// Only one run per process can be in progress, since run events are
+/// process-wide ([`BeansError::RunInProgress`] otherwise). Agents started from a
+/// `run` template inherit the process's stdout.
+pub fn run_dispatch(beans_dir: &Path, params: RunParams) -> Result<RunHandle> {
+    if let Some(ref id) = params.id {
+        super::check_id(beans_dir, id)?;
+    }
+    if params.jobs == 0 {
+        return Err(BeansError::Invalid("jobs must be at least 1".to_string()));
+    }
+    if let Some(ref filter) = params.filter {
+        crate::filter::Filter::load(beans_dir, filter)?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let guard = crate::stream::redirect(tx).ok_or(BeansError::RunInProgress)?;
+
+    let beans_dir: PathBuf = beans_dir.to_path_buf();
+    let args = RunArgs {
+        id: params.id,
+        jobs: params.jobs,
+        dry_run: params.dry_run,
+        loop_mode: params.loop_mode,
+        watch: false,
+        auto_plan: params.auto_plan,
+        keep_going: params.keep_going,
+        timeout: params.timeout_minutes,
+        idle_timeout: params.idle_timeout_minutes,
+        json_stream: true,
+        review: params.review,
+        resume: None,
+        filter: params.filter,
+    };
+    let thread = std::thread::spawn(move || {
+        // Dropping the guard at the end closes the event channel
+        let _guard = guard;
+        crate::commands::run::cmd_run(&beans_dir, args)
+    });
+
+    Ok(RunHandle { events: rx, thread })
+}
@@ -0,0 +1,38 @@
+//! Read-only operations.
+
+use std::path::Path;
+
+use super::Result;
+use super::{check_id, IndexEntry};
+
+pub use crate::commands::dep::{DepRef, DependencyList};
+pub use crate::commands::list::ListFilter;
+pub use crate::commands::stats::StatsOutput;
+pub use crate::commands::status::{BlockedEntry, StatusEntry, StatusOutput};
+
+/// Index entries matching `filter`, as `bn list` would show them.
+pub fn list_beans(beans_dir: &Path, filter: &ListFilter) -> Result<Vec<IndexEntry>> {
+    Ok(crate::commands::list::list_entries(beans_dir, filter)?)
+}
+
+/// Beans an agent can pick up now: open, with a verify command, and not
+/// waiting on dependencies. Same as the "ready" section of `bn status`.
+pub fn ready_beans(beans_dir: &Path) -> Result<Vec<IndexEntry>> {
+    Ok(status(beans_dir)?.ready)
+}
+
+/// Claimed, in-review, ready, goal and blocked beans, as `bn status` shows them.
+pub fn status(beans_dir: &Path) -> Result<StatusOutput> {
+    Ok(crate::commands::status::build_status(beans_dir)?)
+}
+
+/// Project statistics, as `bn stats` shows them.
+pub fn stats(beans_dir: &Path) -> Result<StatsOutput> {
+    Ok(crate::commands::stats::build_stats(beans_dir)?)
+}
+
+/// A bean's dependencies and the beans that depend on it.
+pub fn dependencies(beans_dir: &Path, id: &str) -> Result<DependencyList> {
+    check_id(beans_dir, id)?;
+    Ok(crate::commands::dep::list_dependencies(beans_dir, id)?)
+}
@@ -0,0 +1,501 @@
+//! Three-way field merge for concurrent bean edits.
+//!
+//! Implements the field-level merge described in
+//! `docs/design/CONFLICT_RESOLUTION.md`. Every write path loads a bean,
+//! modifies it, and calls [`save_merged`] with the version it originally read.
+//! If the file changed in the meantime, non-overlapping field changes and
+//! appended notes are merged automatically; fields changed differently on both
+//! sides are recorded in `Bean::conflicts` for `bn resolve`.
+
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde_json::{Map, Value};
+
+use super::{Bean, ConflictResolution, ConflictVersion, FieldConflict};
+
+/// Fields merged element-wise: additions and removals from both sides apply.
+const SET_FIELDS: &[&str] = &[
+    "labels",
+    "dependencies",
+    "produces",
+    "requires",
+    "paths",
+    "on_close",
+    "history",
+];
+
+/// Fields where both sides append text to a common prefix.
+const APPEND_FIELDS: &[&str] = &["notes"];
+
+/// Fields that never conflict and are handled separately.
+const SKIP_FIELDS: &[&str] = &["updated_at", "conflicts", "attempt_log"];
+
+/// A field changed to different values on both sides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictingField {
+    pub field: String,
+    pub ours: Value,
+    pub theirs: Value,
+}
+
+/// Result of a three-way bean merge.
+#[derive(Debug, Clone)]
+pub struct MergeOutcome {
+    /// Merged bean. Conflicting fields keep `ours`' value.
+    pub bean: Bean,
+    pub conflicts: Vec<ConflictingField>,
+}
+
+/// Three-way merge `ours` and `theirs` against their common ancestor `base`.
+///
+/// - Scalar fields: a side that changed wins; both changing differently conflicts.
+/// - `notes`: when both sides appended to the base text, both additions are kept.
+/// - Lists (`labels`, `dependencies`, `history`, ...): element-wise union of
+///   additions, minus elements either side removed.
+/// - `attempt_log`: merged by attempt number.
+/// - `updated_at`: the later of the two.
+pub fn three_way_merge(base: &Bean, ours: &Bean, theirs: &Bean) -> Result<MergeOutcome> {
+    let base_map = to_map(base)?;
+    let ours_map = to_map(ours)?;
+    let theirs_map = to_map(theirs)?;
+
+    let mut keys: Vec<&String> = base_map
+        .keys()
+        .chain(ours_map.keys())
+        .chain(theirs_map.keys())
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    merged.insert(
+        "updated_at".to_string(),
+        serde_json::to_value(ours.updated_at.max(theirs.updated_at))?,
+    );
+
+    for key in keys {
+        if SKIP_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let b = base_map.get(key).unwrap_or(&Value::Null);
+        let o = ours_map.get(key).unwrap_or(&Value::Null);
+        let t = theirs_map.get(key).unwrap_or(&Value::Null);
+
+        let value = if o == t || b == t {
+            Some(o.clone())
+        } else if b == o {
+            Some(t.clone())
+        } else if SET_FIELDS.contains(&key.as_str()) {
+            Some(Value::Array(merge_lists(
+                as_array(b),
+                as_array(o),
+                as_array(t),
+            )))
+        } else if APPEND_FIELDS.contains(&key.as_str()) {
+            merge_appended(b.as_str().unwrap_or(""), o.as_str(), t.as_str()).map(Value::String)
+        } else {
+            None
+        };
+
+        match value {
+            Some(v) => {
+                if !v.is_null() {
+                    merged.insert(key.clone(), v);
+                }
+            }
+            None => {
+                conflicts.push(ConflictingField {
+                    field: key.clone(),
+                    ours: o.clone(),
+                    theirs: t.clone(),
+                });
+                if !o.is_null() {
+                    merged.insert(key.clone(), o.clone());
+                }
+            }
+        }
+    }
+
+    let mut bean: Bean = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| anyhow!("Merged bean is invalid: {}", e))?;
+
+    match merge_attempt_log(base, ours, theirs) {
+        Some(log) => bean.attempt_log = log,
+        None => {
+            conflicts.push(ConflictingField {
+                field: "attempt_log".to_string(),
+                ours: serde_json::to_value(&ours.attempt_log)?,
+                theirs: serde_json::to_value(&theirs.attempt_log)?,
+            });
+            bean.attempt_log = ours.attempt_log.clone();
+        }
+    }
+
+    bean.conflicts = theirs.conflicts.clone();
+    for c in &ours.conflicts {
+        if !bean.conflicts.contains(c) {
+            bean.conflicts.push(c.clone());
+        }
+    }
+
+    Ok(MergeOutcome { bean, conflicts })
+}
+
+/// Record merge conflicts on `bean` so they can be resolved with `bn resolve`.
+///
+/// Replaces any pending conflict already recorded for the same field.
+pub fn record_conflicts(
+    bean: &mut Bean,
+    conflicts: &[ConflictingField],
+    ours_agent: &str,
+    theirs_agent: &str,
+) {
+    let now = Utc::now();
+    for c in conflicts {
+        bean.conflicts.retain(|existing| existing.field != c.field);
+        bean.conflicts.push(FieldConflict {
+            field: c.field.clone(),
+            versions: vec![
+                ConflictVersion {
+                    value: c.ours.to_string(),
+                    agent: ours_agent.to_string(),
+                    timestamp: now,
+                },
+                ConflictVersion {
+                    value: c.theirs.to_string(),
+                    agent: theirs_agent.to_string(),
+                    timestamp: now,
+                },
+            ],
+            resolution: ConflictResolution::Pending,
+        });
+    }
+}
+
+/// Write `bean` to `path`, merging in changes made on disk since `base` was read.
+///
+/// If the file is unchanged since `base` was loaded, `bean` is written as-is.
+/// Otherwise the three versions are merged, `bean` is replaced by the merged
+/// result, and any conflicts are recorded on it.
+///
+/// Returns the names of conflicting fields (empty if the write merged cleanly).
+pub fn save_merged(
+    bean: &mut Bean,
+    base: &Bean,
+    path: &Path,
+    agent: Option<&str>,
+) -> Result<Vec<String>> {
+    let current = match Bean::from_file(path) {
+        Ok(current) => current,
+        Err(_) => {
+            // File vanished or is unreadable — nothing to merge with.
+            bean.to_file(path)?;
+            return Ok(Vec::new());
+        }
+    };
+
+    if current.hash() == base.hash() {
+        bean.to_file(path)?;
+        return Ok(Vec::new());
+    }
+
+    let outcome = three_way_merge(base, bean, &current)?;
+    let mut merged = outcome.bean;
+    let theirs_agent = current.claimed_by.as_deref().unwrap_or("unknown");
+    record_conflicts(
+        &mut merged,
+        &outcome.conflicts,
+        agent.unwrap_or("unknown"),
+        theirs_agent,
+    );
+
+    merged.to_file(path)?;
+    *bean = merged;
+
+    Ok(outcome.conflicts.into_iter().map(|c| c.field).collect())
+}
+
+/// Write a warning to `err` for the conflicting fields left by a merged write.
+pub fn warn_conflicts(err: &mut dyn Write, id: &str, fields: &[String]) {
+    if fields.is_empty() {
+        return;
+    }
+    let _ = writeln!(
+        err,
+        "! Bean {} was modified concurrently; conflicting field(s): {}",
+        id,
+        fields.join(", ")
+    );
+    let _ = writeln!(err, "  Run `bn resolve {}` to choose values.", id);
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn to_map(bean: &Bean) -> Result<Map<String, Value>> {
+    match serde_json::to_value(bean)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(anyhow!("Bean did not serialize to an object")),
+    }
+}
+
+fn as_array(v: &Value) -> &[Value] {
+    v.as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Element-wise three-way list merge.
+///
+/// Keeps `theirs`' order, drops elements `ours` removed, then appends
+/// elements `ours` added.
+pub(crate) fn merge_lists<T: PartialEq + Clone>(base: &[T], ours: &[T], theirs: &[T]) -> Vec<T> {
+    let mut result: Vec<T> = theirs
+        .iter()
+        .filter(|x| ours.contains(x) || !base.contains(x))
+        .cloned()
+        .collect();
+    for x in ours {
+        if !base.contains(x) && !result.contains(x) {
+            result.push(x.clone());
+        }
+    }
+    result
+}
+
+/// Merge text both sides appended to. Returns `None` if either side rewrote
+/// the base text rather than appending to it.
+pub(crate) fn merge_appended(
+    base: &str,
+    ours: Option<&str>,
+    theirs: Option<&str>,
+) -> Option<String> {
+    let ours = ours.unwrap_or("");
+    let theirs = theirs.unwrap_or("");
+    let ours_added = ours.strip_prefix(base)?;
+    theirs.strip_prefix(base)?;
+
+    let mut merged = theirs.to_string();
+    let addition = ours_added.trim_start_matches('\n');
+    if !addition.is_empty() {
+        if !merged.is_empty() {
+            merged.push('\n');
+        }
+        merged.push_str(addition);
+    }
+    Some(merged)
+}
+
+/// Merge attempt logs by attempt number. Returns `None` if both sides changed
+/// the same attempt differently.
+fn merge_attempt_log(base: &Bean, ours: &Bean, theirs: &Bean) -> Option<Vec<super::AttemptRecord>> {
+    let mut result = theirs.attempt_log.clone();
+    for o in &ours.attempt_log {
+        let b = base.attempt_log.iter().find(|a| a.num == o.num);
+        match result.iter_mut().find(|a| a.num == o.num) {
+            Some(t) if t == o => {}
+            Some(t) if Some(&*t) == b => *t = o.clone(),
+            Some(_) if Some(o) == b => {}
+            Some(_) => return None,
+            None if b.is_some() => {} // theirs removed it
+            None => result.push(o.clone()),
+        }
+    }
+    result.sort_by_key(|a| a.num);
+    Some(result)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::{AttemptOutcome, AttemptRecord, Status};
+    use tempfile::TempDir;
+
+    fn base_bean() -> Bean {
+        let mut bean = Bean::new("5", "Example");
+        bean.description = Some("old".to_string());
+        bean.labels = vec!["backend".to_string()];
+        bean
+    }
+
+    #[test]
+    fn non_overlapping_fields_merge() {
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.status = Status::InProgress;
+        let mut theirs = base.clone();
+        theirs.description = Some("new".to_string());
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.bean.status, Status::InProgress);
+        assert_eq!(outcome.bean.description.as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn overlapping_scalar_conflicts_and_keeps_ours() {
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.status = Status::Closed;
+        let mut theirs = base.clone();
+        theirs.status = Status::InProgress;
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].field, "status");
+        assert_eq!(outcome.conflicts[0].theirs, Value::from("in_progress"));
+        assert_eq!(outcome.bean.status, Status::Closed);
+    }
+
+    #[test]
+    fn same_change_on_both_sides_is_not_a_conflict() {
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.priority = 0;
+        let theirs = ours.clone();
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.bean.priority, 0);
+    }
+
+    #[test]
+    fn appended_notes_are_combined() {
+        let mut base = base_bean();
+        base.notes = Some("start".to_string());
+        let mut ours = base.clone();
+        ours.notes = Some("start\nDone part 1".to_string());
+        let mut theirs = base.clone();
+        theirs.notes = Some("start\nDone part 2".to_string());
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(
+            outcome.bean.notes.as_deref(),
+            Some("start\nDone part 2\nDone part 1")
+        );
+    }
+
+    #[test]
+    fn notes_appended_to_empty_base_are_combined() {
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.notes = Some("mine".to_string());
+        let mut theirs = base.clone();
+        theirs.notes = Some("yours".to_string());
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.bean.notes.as_deref(), Some("yours\nmine"));
+    }
+
+    #[test]
+    fn rewritten_notes_conflict() {
+        let mut base = base_bean();
+        base.notes = Some("start".to_string());
+        let mut ours = base.clone();
+        ours.notes = Some("rewritten".to_string());
+        let mut theirs = base.clone();
+        theirs.notes = Some("start\nmore".to_string());
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].field, "notes");
+    }
+
+    #[test]
+    fn labels_merge_as_sets() {
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.labels = vec!["backend".to_string(), "urgent".to_string()];
+        let mut theirs = base.clone();
+        theirs.labels = vec!["api".to_string()];
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.bean.labels, vec!["api", "urgent"]);
+    }
+
+    #[test]
+    fn attempt_log_merges_by_number() {
+        let now = Utc::now();
+        let attempt = |num, outcome, finished: bool| AttemptRecord {
+            num,
+            outcome,
+            notes: None,
+            agent: None,
+            started_at: Some(now),
+            finished_at: finished.then_some(now),
+            verify: None,
+            base: None,
+        };
+        let mut base = base_bean();
+        base.attempt_log = vec![attempt(1, AttemptOutcome::Abandoned, false)];
+        let mut ours = base.clone();
+        ours.attempt_log = vec![attempt(1, AttemptOutcome::Success, true)];
+        let mut theirs = base.clone();
+        theirs.attempt_log = vec![
+            attempt(1, AttemptOutcome::Abandoned, false),
+            attempt(2, AttemptOutcome::Abandoned, false),
+        ];
+
+        let outcome = three_way_merge(&base, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.bean.attempt_log.len(), 2);
+        assert_eq!(outcome.bean.attempt_log[0].outcome, AttemptOutcome::Success);
+    }
+
+    #[test]
+    fn save_merged_writes_directly_when_unchanged() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("5-example.md");
+        let base = base_bean();
+        base.to_file(&path).unwrap();
+
+        let mut ours = base.clone();
+        ours.title = "Renamed".to_string();
+        let conflicts = save_merged(&mut ours, &base, &path, Some("alice")).unwrap();
+
+        assert!(conflicts.is_empty());
+        assert_eq!(Bean::from_file(&path).unwrap().title, "Renamed");
+    }
+
+    #[test]
+    fn save_merged_detects_concurrent_write() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("5-example.md");
+        let base = base_bean();
+        base.to_file(&path).unwrap();
+
+        // Someone else changes the file after we read it
+        let mut theirs = base.clone();
+        theirs.status = Status::InProgress;
+        theirs.claimed_by = Some("bob".to_string());
+        theirs.priority = 0;
+        theirs.to_file(&path).unwrap();
+
+        let mut ours = base.clone();
+        ours.status = Status::Closed;
+        ours.title = "Renamed".to_string();
+        let conflicts = save_merged(&mut ours, &base, &path, Some("alice")).unwrap();
+
+        assert_eq!(conflicts, vec!["status"]);
+        let on_disk = Bean::from_file(&path).unwrap();
+        assert_eq!(on_disk.title, "Renamed");
+        assert_eq!(on_disk.priority, 0);
+        assert_eq!(on_disk.status, Status::Closed);
+        assert_eq!(on_disk.conflicts.len(), 1);
+        let conflict = &on_disk.conflicts[0];
+        assert_eq!(conflict.versions[0].agent, "alice");
+        assert_eq!(conflict.versions[1].agent, "bob");
+        assert_eq!(conflict.versions[1].value, "\"in_progress\"");
+        assert_eq!(ours, on_disk);
+    }
+}
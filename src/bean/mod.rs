@@ -4,8 +4,10 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::BeansError;
 use crate::util::{atomic_write, validate_bean_id};
 
+pub mod merge;
 pub mod types;
 pub use types::*;
 
@@ -16,10 +18,11 @@ pub use types::*;
 /// Validate that priority is in the valid range (0-4, P0-P4).
 pub fn validate_priority(priority: u8) -> Result<()> {
     if priority > 4 {
-        return Err(anyhow::anyhow!(
+        return Err(BeansError::Invalid(format!(
             "Invalid priority: {}. Priority must be in range 0-4 (P0-P4)",
             priority
-        ));
+        ))
+        .into());
     }
     Ok(())
 }
@@ -58,6 +61,9 @@ pub struct Bean {
     pub closed_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub close_reason: Option<String>,
+    /// Why the bean is parked (set with status `blocked`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_reason: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
@@ -164,6 +170,10 @@ pub struct Bean {
     /// Whether this bean is a feature (product-level goal, human-only close).
     #[serde(default, skip_serializing_if = "is_false")]
     pub feature: bool,
+
+    /// Unresolved concurrent-edit conflicts (see `bn resolve`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<FieldConflict>,
 }
 
 fn default_priority() -> u8 {
@@ -218,6 +228,7 @@ impl Bean {
             assignee: None,
             closed_at: None,
             close_reason: None,
+            blocked_reason: None,
             parent: None,
             dependencies: Vec::new(),
             verify: None,
@@ -243,6 +254,7 @@ impl Bean {
             paths: Vec::new(),
             attempt_log: Vec::new(),
             created_by: None,
+            conflicts: Vec::new(),
         })
     }
 
@@ -290,7 +302,18 @@ impl Bean {
             return Err(anyhow::anyhow!("Not markdown frontmatter format"));
         };
 
-        let second_delimiter_pos = after_first_delimiter.find("---").ok_or_else(|| {
+        // The closing delimiter is a line of its own: notes written by
+        // `bn update --note` contain indented `---` separators.
+        let mut second_delimiter_pos = None;
+        let mut offset = 0;
+        for line in after_first_delimiter.split_inclusive('\n') {
+            if line.trim_end_matches(['\r', '\n']) == "---" {
+                second_delimiter_pos = Some(offset);
+                break;
+            }
+            offset += line.len();
+        }
+        let second_delimiter_pos = second_delimiter_pos.ok_or_else(|| {
             anyhow::anyhow!("Markdown frontmatter is missing closing delimiter (---)")
         })?;
         let frontmatter = &after_first_delimiter[..second_delimiter_pos];
@@ -343,8 +366,15 @@ impl Bean {
     pub fn to_file(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = path.as_ref();
         let is_md = path.extension().and_then(|e| e.to_str()) == Some("md");
+        atomic_write(path, &self.to_content(is_md)?)
+    }
 
-        if is_md && self.description.is_some() {
+    /// Serialize this bean as it would be written to disk.
+    ///
+    /// With `markdown`, a bean with a description is rendered as YAML frontmatter
+    /// followed by the description as the markdown body; otherwise pure YAML.
+    pub fn to_content(&self, markdown: bool) -> Result<String> {
+        if markdown && self.description.is_some() {
             // Write frontmatter format: YAML metadata + markdown body
             let mut frontmatter_bean = self.clone();
             let description = frontmatter_bean.description.take(); // Remove from YAML
@@ -359,12 +389,10 @@ impl Bean {
                     content.push('\n');
                 }
             }
-            atomic_write(path, &content)?;
+            Ok(content)
         } else {
-            let yaml = serde_yml::to_string(self)?;
-            atomic_write(path, &yaml)?;
+            Ok(serde_yml::to_string(self)?)
         }
-        Ok(())
     }
 
     /// Calculate SHA256 hash of canonical form.
@@ -423,6 +451,7 @@ impl Bean {
             "requires" => self.requires = serde_json::from_str(json_value)?,
             "claimed_by" => self.claimed_by = serde_json::from_str(json_value)?,
             "close_reason" => self.close_reason = serde_json::from_str(json_value)?,
+            "blocked_reason" => self.blocked_reason = serde_json::from_str(json_value)?,
             "on_fail" => self.on_fail = serde_json::from_str(json_value)?,
             "outputs" => self.outputs = serde_json::from_str(json_value)?,
             "max_loops" => self.max_loops = serde_json::from_str(json_value)?,
@@ -430,6 +459,20 @@ impl Bean {
             "last_verified" => self.last_verified = serde_json::from_str(json_value)?,
             "stale_after" => self.stale_after = serde_json::from_str(json_value)?,
             "paths" => self.paths = serde_json::from_str(json_value)?,
+            "slug" => self.slug = serde_json::from_str(json_value)?,
+            "closed_at" => self.closed_at = serde_json::from_str(json_value)?,
+            "fail_first" => self.fail_first = serde_json::from_str(json_value)?,
+            "checkpoint" => self.checkpoint = serde_json::from_str(json_value)?,
+            "attempts" => self.attempts = serde_json::from_str(json_value)?,
+            "max_attempts" => self.max_attempts = serde_json::from_str(json_value)?,
+            "claimed_at" => self.claimed_at = serde_json::from_str(json_value)?,
+            "is_archived" => self.is_archived = serde_json::from_str(json_value)?,
+            "on_close" => self.on_close = serde_json::from_str(json_value)?,
+            "history" => self.history = serde_json::from_str(json_value)?,
+            "verify_timeout" => self.verify_timeout = serde_json::from_str(json_value)?,
+            "attempt_log" => self.attempt_log = serde_json::from_str(json_value)?,
+            "created_by" => self.created_by = serde_json::from_str(json_value)?,
+            "feature" => self.feature = serde_json::from_str(json_value)?,
             _ => return Err(anyhow::anyhow!("Unknown field: {}", field)),
         }
         self.updated_at = Utc::now();
@@ -478,6 +521,7 @@ mod tests {
             assignee: Some("alice".to_string()),
             closed_at: Some(now),
             close_reason: Some("Done".to_string()),
+            blocked_reason: None,
             parent: Some("3.2".to_string()),
             dependencies: vec!["3.1".to_string()],
             verify: Some("cargo test".to_string()),
@@ -513,6 +557,7 @@ mod tests {
             paths: Vec::new(),
             attempt_log: Vec::new(),
             created_by: Some("alice".to_string()),
+            conflicts: Vec::new(),
         };
 
         let yaml = serde_yml::to_string(&bean).unwrap();
@@ -743,6 +788,31 @@ More content after a horizontal rule.
         assert!(body.contains("horizontal rule"));
     }
 
+    #[test]
+    fn test_parse_md_frontmatter_with_notes_containing_dashes() {
+        let content = r#"---
+id: "4"
+title: Noted
+status: open
+priority: 2
+created_at: "2026-01-01T00:00:00Z"
+updated_at: "2026-01-01T00:00:00Z"
+notes: |-
+  ---
+  2026-01-01T00:00:00+00:00
+  Tried the cookie fix
+labels:
+- auth
+---
+
+Body text.
+"#;
+        let bean = Bean::from_string(content).unwrap();
+        assert!(bean.notes.unwrap().contains("Tried the cookie fix"));
+        assert_eq!(bean.labels, vec!["auth".to_string()]);
+        assert_eq!(bean.description.as_deref(), Some("Body text."));
+    }
+
     #[test]
     fn test_parse_md_frontmatter_with_whitespace_in_body() {
         let content = r#"---
@@ -1075,6 +1145,9 @@ on_close:
                 tokens: None,
                 cost: None,
                 output_snippet: Some("error: test failed".to_string()),
+                reason: None,
+                cached: false,
+                tests: None,
             },
             RunRecord {
                 attempt: 2,
@@ -1087,6 +1160,9 @@ on_close:
                 tokens: Some(12000),
                 cost: Some(0.05),
                 output_snippet: None,
+                reason: None,
+                cached: false,
+                tests: None,
             },
         ];
 
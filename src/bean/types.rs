@@ -10,7 +10,21 @@ use serde::{Deserialize, Serialize};
 pub enum Status {
     Open,
     InProgress,
+    /// Work is done and waiting for review before it can be closed.
+    InReview,
+    /// Manually parked; the reason lives in `Bean::blocked_reason`.
+    Blocked,
     Closed,
+    /// Won't do. Terminal like `Closed`, but dependents stay blocked since the
+    /// work they depend on will never happen.
+    Cancelled,
+}
+
+impl Status {
+    /// Whether the bean is finished (closed or cancelled) and can be archived.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Status::Closed | Status::Cancelled)
+    }
 }
 
 impl std::fmt::Display for Status {
@@ -18,7 +32,10 @@ impl std::fmt::Display for Status {
         match self {
             Status::Open => write!(f, "open"),
             Status::InProgress => write!(f, "in_progress"),
+            Status::InReview => write!(f, "in_review"),
+            Status::Blocked => write!(f, "blocked"),
             Status::Closed => write!(f, "closed"),
+            Status::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -57,6 +74,62 @@ pub struct RunRecord {
     pub cost: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_snippet: Option<String>,
+    /// Why the run was cut short (e.g. a budget limit), for `Cancelled` runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The result was replayed from the verify cache instead of running the
+    /// command again.
+    #[serde(default, skip_serializing_if = "super::is_false")]
+    pub cached: bool,
+    /// Test counts parsed from the verify output, when the test runner was
+    /// recognised (see [`crate::test_results`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tests: Option<TestSummary>,
+}
+
+/// Structured result of a test run, parsed from verify output or JUnit XML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestSummary {
+    pub passed: u32,
+    pub failed: u32,
+    #[serde(default)]
+    pub ignored: u32,
+    /// Names of the failing tests, in the order the runner reported them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failing: Vec<String>,
+    /// The first assertion or panic message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl TestSummary {
+    /// Tests that ran (ignored tests excluded).
+    pub fn total(&self) -> u32 {
+        self.passed + self.failed
+    }
+}
+
+/// One line: `3 of 120 tests failed: a, b, c` or `120 tests passed`.
+impl std::fmt::Display for TestSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const SHOWN: usize = 5;
+        if self.failed == 0 {
+            write!(f, "{} tests passed", self.passed)?;
+        } else {
+            write!(f, "{} of {} tests failed", self.failed, self.total())?;
+        }
+        if self.ignored > 0 {
+            write!(f, " ({} ignored)", self.ignored)?;
+        }
+        if !self.failing.is_empty() {
+            let names = self.failing.iter().take(SHOWN).cloned().collect::<Vec<_>>();
+            write!(f, ": {}", names.join(", "))?;
+            if self.failing.len() > SHOWN {
+                write!(f, ", +{} more", self.failing.len() - SHOWN)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -120,6 +193,47 @@ pub struct AttemptRecord {
     pub started_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finished_at: Option<DateTime<Utc>>,
+    /// The bean's verify command when the attempt was claimed, checked by
+    /// `bn close` so an agent can't weaken its own gate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<String>,
+    /// Git HEAD when the attempt was claimed; files the verify command
+    /// refers to are compared against it on close.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// FieldConflict (concurrent edit conflicts awaiting `bn resolve`)
+// ---------------------------------------------------------------------------
+
+/// Resolution state of a field conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Pending,
+    Resolved,
+    Discarded,
+}
+
+/// One competing value for a conflicted field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictVersion {
+    /// JSON-serialized field value (as accepted by `Bean::apply_value`).
+    pub value: String,
+    pub agent: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A field that two writers changed to different values.
+///
+/// `versions[0]` is "ours" (the write that detected the conflict and whose
+/// value was kept); `versions[1]` is "theirs" (the value that was on disk).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldConflict {
+    pub field: String,
+    pub versions: Vec<ConflictVersion>,
+    pub resolution: ConflictResolution,
 }
 
 // ---------------------------------------------------------------------------
@@ -141,6 +255,30 @@ mod tests {
         assert_eq!(closed.trim(), "closed");
     }
 
+    #[test]
+    fn new_statuses_round_trip() {
+        for (status, text) in [
+            (Status::InReview, "in_review"),
+            (Status::Blocked, "blocked"),
+            (Status::Cancelled, "cancelled"),
+        ] {
+            assert_eq!(serde_yml::to_string(&status).unwrap().trim(), text);
+            assert_eq!(status.to_string(), text);
+            let restored: Status = serde_yml::from_str(text).unwrap();
+            assert_eq!(restored, status);
+        }
+    }
+
+    #[test]
+    fn terminal_statuses() {
+        assert!(Status::Closed.is_terminal());
+        assert!(Status::Cancelled.is_terminal());
+        assert!(!Status::Open.is_terminal());
+        assert!(!Status::InProgress.is_terminal());
+        assert!(!Status::InReview.is_terminal());
+        assert!(!Status::Blocked.is_terminal());
+    }
+
     #[test]
     fn run_result_serializes_as_snake_case() {
         assert_eq!(
@@ -175,6 +313,9 @@ mod tests {
             tokens: None,
             cost: None,
             output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: None,
         };
 
         let yaml = serde_yml::to_string(&record).unwrap();
@@ -205,6 +346,9 @@ mod tests {
             tokens: Some(5000),
             cost: Some(0.03),
             output_snippet: Some("FAILED: assertion error".to_string()),
+            reason: None,
+            cached: false,
+            tests: None,
         };
 
         let yaml = serde_yml::to_string(&record).unwrap();
@@ -212,6 +356,31 @@ mod tests {
         assert_eq!(record, restored);
     }
 
+    #[test]
+    fn field_conflict_round_trip() {
+        let conflict = FieldConflict {
+            field: "status".to_string(),
+            versions: vec![
+                ConflictVersion {
+                    value: "\"closed\"".to_string(),
+                    agent: "agent-a".to_string(),
+                    timestamp: Utc::now(),
+                },
+                ConflictVersion {
+                    value: "\"in_progress\"".to_string(),
+                    agent: "agent-b".to_string(),
+                    timestamp: Utc::now(),
+                },
+            ],
+            resolution: ConflictResolution::Pending,
+        };
+
+        let yaml = serde_yml::to_string(&conflict).unwrap();
+        assert!(yaml.contains("resolution: pending"));
+        let restored: FieldConflict = serde_yml::from_str(&yaml).unwrap();
+        assert_eq!(conflict, restored);
+    }
+
     #[test]
     fn history_with_cancelled_result() {
         let now = Utc::now();
@@ -226,11 +395,15 @@ mod tests {
             tokens: None,
             cost: None,
             output_snippet: None,
+            reason: Some("bean cost $1.20 exceeds limit $1.00".to_string()),
+            cached: false,
+            tests: None,
         };
 
         let yaml = serde_yml::to_string(&record).unwrap();
         assert!(yaml.contains("cancelled"));
         let restored: RunRecord = serde_yml::from_str(&yaml).unwrap();
         assert_eq!(restored.result, RunResult::Cancelled);
+        assert_eq!(restored.reason, record.reason);
     }
 }
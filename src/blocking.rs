@@ -20,6 +20,10 @@ pub const MAX_PATHS: usize = 5;
 /// Why a bean cannot be dispatched right now.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BlockReason {
+    /// The bean itself has status `blocked`, with an optional reason.
+    Parked(Option<String>),
+    /// One or more dependency beans were cancelled and will never close.
+    CancelledDeps(Vec<String>),
     /// One or more dependency beans are not yet closed.
     WaitingOn(Vec<String>),
 }
@@ -34,6 +38,11 @@ pub enum ScopeWarning {
 impl fmt::Display for BlockReason {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            BlockReason::Parked(Some(reason)) => write!(f, "blocked: {}", reason),
+            BlockReason::Parked(None) => write!(f, "blocked"),
+            BlockReason::CancelledDeps(ids) => {
+                write!(f, "depends on cancelled {}", ids.join(", "))
+            }
             BlockReason::WaitingOn(ids) => {
                 write!(f, "waiting on {}", ids.join(", "))
             }
@@ -56,16 +65,25 @@ impl fmt::Display for ScopeWarning {
 /// Check whether `entry` is blocked, returning the reason if so.
 ///
 /// Checks in priority order:
-/// 1. **Explicit dependencies** — any dep that isn't closed (or doesn't exist).
-/// 2. **Requires/produces** — sibling beans that produce a required artifact
+/// 1. **Status** — the bean itself is `blocked`.
+/// 2. **Explicit dependencies** — any dep that isn't closed (or doesn't exist).
+///    A cancelled dep blocks for good and is reported separately, so someone
+///    can drop the dependency or reopen the dep.
+/// 3. **Requires/produces** — sibling beans that produce a required artifact
 ///    but aren't closed yet.
 pub fn check_blocked(entry: &IndexEntry, index: &Index) -> Option<BlockReason> {
+    if entry.status == Status::Blocked {
+        return Some(BlockReason::Parked(entry.blocked_reason.clone()));
+    }
+
     let mut waiting_on = Vec::new();
+    let mut cancelled = Vec::new();
 
     // Explicit dependencies
     for dep_id in &entry.dependencies {
         match index.beans.iter().find(|e| e.id == *dep_id) {
             Some(dep) if dep.status == Status::Closed => {}
+            Some(dep) if dep.status == Status::Cancelled => cancelled.push(dep_id.clone()),
             _ => waiting_on.push(dep_id.clone()),
         }
     }
@@ -77,12 +95,19 @@ pub fn check_blocked(entry: &IndexEntry, index: &Index) -> Option<BlockReason> {
             .iter()
             .find(|e| e.id != entry.id && e.parent == entry.parent && e.produces.contains(required))
         {
-            if producer.status != Status::Closed && !waiting_on.contains(&producer.id) {
+            if producer.status == Status::Cancelled {
+                if !cancelled.contains(&producer.id) {
+                    cancelled.push(producer.id.clone());
+                }
+            } else if producer.status != Status::Closed && !waiting_on.contains(&producer.id) {
                 waiting_on.push(producer.id.clone());
             }
         }
     }
 
+    if !cancelled.is_empty() {
+        return Some(BlockReason::CancelledDeps(cancelled));
+    }
     if !waiting_on.is_empty() {
         return Some(BlockReason::WaitingOn(waiting_on));
     }
@@ -127,6 +152,7 @@ mod tests {
             claimed_by: None,
             attempts: 0,
             paths: vec![],
+            blocked_reason: None,
         }
     }
 
@@ -257,6 +283,71 @@ mod tests {
         }
     }
 
+    // -- Blocked / cancelled statuses --
+
+    #[test]
+    fn blocking_parked_bean_reports_reason() {
+        let mut entry = make_entry("1");
+        entry.status = Status::Blocked;
+        entry.blocked_reason = Some("waiting on vendor API key".into());
+
+        let index = make_index(vec![entry.clone()]);
+        let reason = check_blocked(&entry, &index).unwrap();
+        assert_eq!(
+            reason,
+            BlockReason::Parked(Some("waiting on vendor API key".into()))
+        );
+        assert_eq!(reason.to_string(), "blocked: waiting on vendor API key");
+    }
+
+    #[test]
+    fn blocking_cancelled_dep_blocks_dependents() {
+        let mut dep = make_entry("1");
+        dep.status = Status::Cancelled;
+        let open_dep = make_entry("3");
+
+        let mut entry = make_entry("2");
+        entry.dependencies = vec!["1".into(), "3".into()];
+
+        let index = make_index(vec![dep, entry.clone(), open_dep]);
+        let reason = check_blocked(&entry, &index).unwrap();
+        assert_eq!(reason, BlockReason::CancelledDeps(vec!["1".into()]));
+        assert_eq!(reason.to_string(), "depends on cancelled 1");
+    }
+
+    #[test]
+    fn blocking_cancelled_producer_blocks_consumer() {
+        let mut producer = make_entry("5.1");
+        producer.parent = Some("5".into());
+        producer.produces = vec!["UserType".into()];
+        producer.status = Status::Cancelled;
+
+        let mut consumer = make_entry("5.2");
+        consumer.parent = Some("5".into());
+        consumer.requires = vec!["UserType".into()];
+
+        let index = make_index(vec![producer, consumer.clone()]);
+        assert_eq!(
+            check_blocked(&consumer, &index),
+            Some(BlockReason::CancelledDeps(vec!["5.1".into()]))
+        );
+    }
+
+    #[test]
+    fn blocking_in_review_dep_still_waits() {
+        let mut dep = make_entry("1");
+        dep.status = Status::InReview;
+
+        let mut entry = make_entry("2");
+        entry.dependencies = vec!["1".into()];
+
+        let index = make_index(vec![dep, entry.clone()]);
+        assert_eq!(
+            check_blocked(&entry, &index),
+            Some(BlockReason::WaitingOn(vec!["1".into()]))
+        );
+    }
+
     // -- Scope warnings (non-blocking) --
 
     #[test]
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 /// Parse priority from "P0"-"P4", "p0"-"p4", or "0"-"4".
@@ -38,6 +40,7 @@ Commands:
     verify       Run a bean's verify command without closing
     reopen       Reopen a closed bean
     delete       Delete a bean and clean up references
+    undo         Revert recent bean changes recorded in the event log
     move         Move beans from another .beans/ directory into this project
 
   QUERY
@@ -46,6 +49,7 @@ Commands:
     graph        Display dependency graph
     context      Output context for a bean, or memory context (no args)
     trace        Walk bean lineage and dependency chain
+    log          Show the audit trail of bean changes
 
   MEMORY
     fact         Create a verified fact (requires --verify)
@@ -75,6 +79,7 @@ Commands:
     trust        Manage hook trust (enable/disable hook execution)
     unarchive    Unarchive a bean (move from archive back to main beans directory)
     locks        View and manage file locks for concurrent agents
+    resolve      Resolve conflicting concurrent edits to a bean
 
   SHELL
     completions  Generate shell completions (bash, zsh, fish, powershell)
@@ -135,6 +140,10 @@ Examples:
         /// Skip agent setup
         #[arg(long)]
         no_agent: bool,
+
+        /// Install the git merge driver for bean files (.gitattributes + git config)
+        #[arg(long)]
+        git_merge_driver: bool,
     },
 
     /// Create a new bean
@@ -209,8 +218,18 @@ Examples:
   bn ls --status in_progress         Only claimed beans
   bn ls --label bug --priority 0     High-priority bugs
   bn ls --parent 5                   Children of bean 5
+  bn ls -q 'priority<=1 and label:backend and not claimed and updated<7d'
+                                     Filter expression
+  bn ls -q '@stale'                  Named view (bn config set view.stale '...')
   bn ls --ids | xargs -I{} bn show {}   Pipe to other commands
-  bn ls --format '{id}\\t{title}'     Custom output format"
+  bn ls --format '{id}\\t{title}'     Custom output format
+
+Filter expressions:
+  field:value  field=value  field!=value  field~regex  field<n  field>=n ...
+  A bare field tests that it is set (claimed, has_verify); values with * are globs
+  (path:src/api/**). Timestamps compare with dates or durations ago, so < means
+  earlier: updated<7d = not updated for 7 days, updated>1h = updated within the
+  last hour. Combine with and/or/not and parentheses."
     )]
     List {
         /// Filter by status (open, in_progress, closed)
@@ -233,6 +252,10 @@ Examples:
         #[arg(long)]
         assignee: Option<String>,
 
+        /// Filter expression, e.g. 'priority<=1 and label:backend and not claimed'
+        #[arg(long, short = 'q')]
+        query: Option<String>,
+
         /// Show only beans claimed by or created by the current user
         #[arg(long)]
         mine: bool,
@@ -299,10 +322,14 @@ Examples:
         #[arg(long)]
         design: Option<String>,
 
-        /// New status (open, in_progress, closed)
+        /// New status (open, in_progress, in_review, blocked, cancelled, closed)
         #[arg(long)]
         status: Option<String>,
 
+        /// Why the bean is blocked (with --status blocked)
+        #[arg(long)]
+        reason: Option<String>,
+
         /// New priority (P0-P4 or 0-4)
         #[arg(long, value_parser = parse_priority)]
         priority: Option<u8>,
@@ -347,7 +374,7 @@ Examples:
         #[arg(long)]
         reason: Option<String>,
 
-        /// Skip verify command (force close)
+        /// Skip verify command and the tamper check (force close)
         #[arg(long, conflicts_with = "failed")]
         force: bool,
 
@@ -358,6 +385,10 @@ Examples:
         /// Read bean IDs from stdin (one per line)
         #[arg(long)]
         stdin: bool,
+
+        /// Run the verify command even if a cached result matches
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Run a bean's verify command without closing
@@ -373,6 +404,10 @@ Examples:
         /// Suppress informational output
         #[arg(long, short = 'q')]
         quiet: bool,
+
+        /// Run the verify command even if a cached result matches
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Reopen a closed bean
@@ -389,6 +424,26 @@ Examples:
         id: String,
     },
 
+    /// Revert recent bean changes recorded in the event log
+    ///
+    /// Each step reverts one command: a delete together with the dependency
+    /// cleanup it did, an adopt, a move (in both projects), a close and the
+    /// parents it auto-closed, ... Deleted and archived beans are restored.
+    /// Refuses, without changing anything, if a bean was changed since.
+    #[command(
+        display_order = 14,
+        after_help = "\
+Examples:
+  bn undo              Revert the last change
+  bn undo --steps 3    Revert the last three changes
+  bn log               See what would be reverted"
+    )]
+    Undo {
+        /// Number of changes to revert
+        #[arg(long, default_value = "1")]
+        steps: usize,
+    },
+
     // -- DEPENDENCIES --
     /// Manage dependencies between beans
     #[command(display_order = 30)]
@@ -473,6 +528,10 @@ Examples:
         #[arg(long)]
         dry_run: bool,
 
+        /// Only tidy beans matching this filter expression (see `bn list --help`)
+        #[arg(long)]
+        filter: Option<String>,
+
         /// Suppress informational output
         #[arg(long, short = 'q')]
         quiet: bool,
@@ -503,6 +562,10 @@ Examples:
         /// Force claim even if verify already passes
         #[arg(long)]
         force: bool,
+
+        /// Run the verify command even if a cached result matches
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Health check -- orphans, cycles, index freshness
@@ -523,6 +586,14 @@ Examples:
         /// Check current trust status
         #[arg(long)]
         check: bool,
+
+        /// List hooks and config commands with their approval status
+        #[arg(long, conflicts_with_all = ["revoke", "check", "diff"])]
+        list: bool,
+
+        /// Show changes to hooks and config commands pending approval
+        #[arg(long, conflicts_with_all = ["revoke", "check"])]
+        diff: bool,
     },
 
     /// Unarchive a bean (move from archive back to main beans directory)
@@ -532,6 +603,33 @@ Examples:
         id: String,
     },
 
+    /// Resolve conflicting concurrent edits to a bean
+    ///
+    /// When two writers change the same field of a bean at once, the later
+    /// write keeps its value and records the conflict. Pick which version
+    /// wins: `ours` is the value that was kept, `theirs` the one that was
+    /// overwritten. Without --take, prompts for each conflict.
+    #[command(
+        display_order = 47,
+        after_help = "\
+Examples:
+  bn resolve 5                                Choose interactively
+  bn resolve 5 --field status --take theirs   Keep the overwritten status
+  bn resolve 5 --take ours                    Keep current values for all fields"
+    )]
+    Resolve {
+        /// Bean ID
+        id: String,
+
+        /// Only resolve the conflict on this field
+        #[arg(long)]
+        field: Option<String>,
+
+        /// Version to keep: ours, theirs, or a version index
+        #[arg(long)]
+        take: Option<String>,
+    },
+
     /// View and manage file locks for concurrent agents
     #[command(display_order = 46)]
     Locks {
@@ -659,7 +757,10 @@ Examples:
   bn run              Dispatch all ready beans (up to -j 4 parallel)
   bn run 5            Dispatch a specific bean
   bn run --loop-mode  Keep going until no ready beans remain
+  bn run --watch      Stay up and dispatch new work as it becomes ready
+  bn run --resume latest   Pick up the last interrupted run
   bn run --dry-run    Preview what would be dispatched
+  bn run --filter 'label:backend and priority<=1'   Only matching beans
   bn run -j 8 --keep-going --timeout 60   High-throughput mode")]
     Run {
         /// Bean ID. Without ID, processes all ready beans.
@@ -677,6 +778,10 @@ Examples:
         #[arg(long, name = "loop")]
         loop_mode: bool,
 
+        /// Stay running: watch .beans/ and dispatch beans as they become ready (Ctrl-C to stop)
+        #[arg(long, conflicts_with = "loop")]
+        watch: bool,
+
         /// Also plan large beans autonomously
         #[arg(long)]
         auto_plan: bool,
@@ -700,6 +805,14 @@ Examples:
         /// Run adversarial review after each successful close
         #[arg(long)]
         review: bool,
+
+        /// Resume an interrupted run by ID (see .beans/runs/), or `latest`
+        #[arg(long, value_name = "RUN_ID", conflicts_with_all = ["watch", "dry_run"])]
+        resume: Option<String>,
+
+        /// Only dispatch beans matching this filter expression (see `bn list --help`)
+        #[arg(long)]
+        filter: Option<String>,
     },
 
     /// Interactively plan a large bean into children
@@ -769,6 +882,38 @@ Examples:
         all: bool,
     },
 
+    /// Show the audit trail of bean changes
+    ///
+    /// Every create, update, claim, release, close, reopen, delete, adopt and
+    /// move is recorded in .beans/events.jsonl with who made it, when, and the
+    /// old and new value of each changed field.
+    #[command(
+        display_order = 36,
+        after_help = "\
+Examples:
+  bn log                     All changes, oldest first
+  bn log 5                   Changes to bean 5
+  bn log 5 --since 3d        Changes to bean 5 in the last 3 days
+  bn log --actor alice       Changes made by alice
+  bn log --json              Machine-readable events"
+    )]
+    Log {
+        /// Only show changes to this bean
+        id: Option<String>,
+
+        /// Only show changes since a duration ago (30m, 2h, 3d, 1w), a date or a timestamp
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show changes made by this actor
+        #[arg(long)]
+        actor: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     // -- AGENTS --
     /// Manage project configuration
     #[command(display_order = 35)]
@@ -785,6 +930,14 @@ Examples:
         command: McpCommand,
     },
 
+    /// Serve the REST API (JSON over HTTP, OpenAPI at /v1/openapi.json)
+    #[command(display_order = 61)]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8765")]
+        addr: String,
+    },
+
     // -- MEMORY --
     /// Create a verified fact (requires --verify)
     ///
@@ -827,14 +980,26 @@ Examples:
 
     /// Search beans by keyword
     ///
-    /// Searches titles, descriptions, and notes. Use --all to include closed/archived beans.
+    /// Full-text search over titles, descriptions, notes, close reasons, paths,
+    /// labels and attempt notes, ranked by relevance. Every word must match.
+    /// Use --all to include closed/archived beans.
     #[command(
         display_order = 51,
         after_help = "\
+Query syntax:
+  parser error            Both words, in any field
+  \"parse error\"           The exact phrase
+  pars*                   Words starting with \"pars\"
+  title:parser            Only in one field (title, description, notes, reason, path, attempts)
+  status:closed label:bug type:fact
+                          Filters (a status filter also searches closed/archived beans)
+
 Examples:
-  bn recall \"auth\"           Search open beans
-  bn recall \"JWT\" --all      Include closed/archived
-  bn recall \"login\" --json   Machine-readable results"
+  bn recall \"auth\"                       Search open beans
+  bn recall \"JWT\" --all                  Include closed/archived
+  bn recall 'title:\"token refresh\"'      Phrase in the title
+  bn recall \"migration status:closed\"    Closed beans about migrations
+  bn recall \"login\" --json               Machine-readable results"
     )]
     Recall {
         /// Search query
@@ -851,7 +1016,11 @@ Examples:
 
     /// Re-verify all facts, detect staleness
     #[command(display_order = 52, name = "verify-facts")]
-    VerifyFacts,
+    VerifyFacts {
+        /// Run every verify command even if a cached result matches
+        #[arg(long)]
+        no_cache: bool,
+    },
 
     // -- TRACE --
     /// Walk bean lineage and dependency chain
@@ -939,6 +1108,26 @@ Install permanently:
         #[arg(value_enum)]
         shell: clap_complete::Shell,
     },
+
+    /// Git merge driver for bean files (invoked by git, not by hand)
+    ///
+    /// Merges bean files field by field and regenerates index.yaml. Install with
+    /// `bn init --git-merge-driver`, which registers:
+    ///   merge.beans.driver = bn merge-driver %O %A %B %P
+    #[command(name = "merge-driver", hide = true)]
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: PathBuf,
+
+        /// Current branch version; receives the result (%A)
+        ours: PathBuf,
+
+        /// Other branch version (%B)
+        theirs: PathBuf,
+
+        /// Path of the file in the repository (%P)
+        path: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -972,13 +1161,13 @@ pub enum DepCommand {
 pub enum ConfigCommand {
     /// Get a configuration value
     Get {
-        /// Config key (run, plan, max_concurrent, poll_interval, auto_close_parent, max_loops, rules_file, file_locking, verify_timeout, extends, on_close, on_fail, post_plan, review.run, review.max_reopens)
+        /// Config key (run, plan, max_concurrent, poll_interval, auto_close_parent, max_loops, rules_file, file_locking, verify_timeout, extends, on_close, on_fail, post_plan, review.run, review.max_reopens, view.<name>)
         key: String,
     },
 
     /// Set a configuration value
     Set {
-        /// Config key (run, plan, max_concurrent, poll_interval, auto_close_parent, max_loops, rules_file, file_locking, verify_timeout, extends, on_close, on_fail, post_plan, review.run, review.max_reopens)
+        /// Config key (run, plan, max_concurrent, poll_interval, auto_close_parent, max_loops, rules_file, file_locking, verify_timeout, extends, on_close, on_fail, post_plan, review.run, review.max_reopens, view.<name>)
         key: String,
 
         /// New value
@@ -989,7 +1178,12 @@ pub enum ConfigCommand {
 #[derive(Subcommand)]
 pub enum McpCommand {
     /// Start MCP server on stdio (JSON-RPC 2.0)
-    Serve,
+    Serve {
+        /// Serve streamable HTTP on ADDR (e.g. 127.0.0.1:8808) instead of stdio.
+        /// Set BEANS_MCP_TOKEN to require a bearer token (needed off loopback).
+        #[arg(long, value_name = "ADDR")]
+        http: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
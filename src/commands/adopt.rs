@@ -7,6 +7,7 @@ use chrono::Utc;
 
 use crate::bean::Bean;
 use crate::discovery::find_bean_file;
+use crate::events::{Action, Operation};
 use crate::index::Index;
 
 /// Find the next available child number for a parent.
@@ -82,6 +83,21 @@ pub fn cmd_adopt(
     beans_dir: &Path,
     parent_id: &str,
     child_ids: &[String],
+) -> Result<HashMap<String, String>> {
+    let id_map = adopt_beans(beans_dir, parent_id, child_ids)?;
+    for old_id in child_ids {
+        if let Some(new_id) = id_map.get(old_id) {
+            println!("Adopted {} -> {} (under {})", old_id, new_id, parent_id);
+        }
+    }
+    Ok(id_map)
+}
+
+/// The work behind [`cmd_adopt`], without printing anything.
+pub fn adopt_beans(
+    beans_dir: &Path,
+    parent_id: &str,
+    child_ids: &[String],
 ) -> Result<HashMap<String, String>> {
     // Validate parent exists
     let parent_path = find_bean_file(beans_dir, parent_id)
@@ -91,6 +107,7 @@ pub fn cmd_adopt(
 
     // Track ID mappings: old_id -> new_id
     let mut id_map: HashMap<String, String> = HashMap::new();
+    let op = Operation::new();
 
     // Find the starting child number
     let mut next_num = next_child_number(beans_dir, parent_id)?;
@@ -102,6 +119,7 @@ pub fn cmd_adopt(
             .with_context(|| format!("Child bean '{}' not found", old_id))?;
         let mut bean = Bean::from_file(&old_path)
             .with_context(|| format!("Failed to load child bean '{}'", old_id))?;
+        let before = bean.clone();
 
         // Compute new ID
         let new_id = format!("{}.{}", parent_id, next_num);
@@ -128,14 +146,21 @@ pub fn cmd_adopt(
             })?;
         }
 
+        op.record(
+            beans_dir,
+            Action::Adopt,
+            &new_id,
+            Some(&before),
+            Some(&bean),
+        );
+
         // Track the mapping
-        id_map.insert(old_id.clone(), new_id.clone());
-        println!("Adopted {} -> {} (under {})", old_id, new_id, parent_id);
+        id_map.insert(old_id.clone(), new_id);
     }
 
     // Update dependencies across all beans
     if !id_map.is_empty() {
-        update_all_dependencies(beans_dir, &id_map)?;
+        update_all_dependencies(beans_dir, &id_map, &op)?;
     }
 
     // Rebuild the index
@@ -149,7 +174,11 @@ pub fn cmd_adopt(
 ///
 /// Scans all bean files in the directory and replaces any dependency IDs
 /// that appear in the id_map with their new values.
-fn update_all_dependencies(beans_dir: &Path, id_map: &HashMap<String, String>) -> Result<()> {
+fn update_all_dependencies(
+    beans_dir: &Path,
+    id_map: &HashMap<String, String>,
+    op: &Operation,
+) -> Result<()> {
     let dir_entries = fs::read_dir(beans_dir)
         .with_context(|| format!("Failed to read directory: {}", beans_dir.display()))?;
 
@@ -179,6 +208,8 @@ fn update_all_dependencies(beans_dir: &Path, id_map: &HashMap<String, String>) -
             Err(_) => continue, // Skip files that can't be parsed
         };
 
+        let before = bean.clone();
+
         // Check if any dependencies need updating
         let mut modified = false;
         let mut new_deps = Vec::new();
@@ -206,6 +237,13 @@ fn update_all_dependencies(beans_dir: &Path, id_map: &HashMap<String, String>) -
             bean.updated_at = Utc::now();
             bean.to_file(&path)
                 .with_context(|| format!("Failed to update bean {}", path.display()))?;
+            op.record(
+                beans_dir,
+                Action::Update,
+                &bean.id,
+                Some(&before),
+                Some(&bean),
+            );
         }
     }
 
@@ -243,6 +281,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
@@ -84,7 +84,7 @@ pub fn save_agents(agents: &HashMap<String, AgentEntry>) -> Result<()> {
 /// Uses `kill(pid, 0)` which checks existence without signaling.
 /// Returns `true` if the process exists, even if owned by another user (EPERM).
 /// Returns `false` if the PID overflows `i32` (not a valid Unix PID).
-fn process_alive(pid: u32) -> bool {
+pub(crate) fn process_alive(pid: u32) -> bool {
     let Ok(pid_i32) = i32::try_from(pid) else {
         return false;
     };
@@ -100,6 +100,21 @@ fn process_alive(pid: u32) -> bool {
     std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
 }
 
+/// Start time of a process in clock ticks since boot (field 22 of
+/// `/proc/<pid>/stat`). `None` if it can't be read, e.g. off Linux.
+pub(crate) fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name (field 2) may contain spaces and parentheses
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Like [`process_alive`], but when `start_time` was recorded, also check
+/// the PID still belongs to that process and was not reused by another.
+pub(crate) fn same_process_alive(pid: u32, start_time: Option<u64>) -> bool {
+    process_alive(pid) && start_time.is_none_or(|start| process_start_time(pid) == Some(start))
+}
+
 /// Truncate a string to fit within `max_display_chars` characters, appending "…"
 /// if truncated. Works correctly with multi-byte UTF-8.
 fn truncate_title(title: &str, max_display_chars: usize) -> String {
@@ -338,6 +353,17 @@ mod tests {
         assert!(!process_alive(99_999_999));
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn same_process_alive_detects_reused_pid() {
+        let pid = std::process::id();
+        let start = process_start_time(pid);
+        assert!(start.is_some());
+        assert!(same_process_alive(pid, start));
+        assert!(same_process_alive(pid, None));
+        assert!(!same_process_alive(pid, start.map(|s| s + 1)));
+    }
+
     #[test]
     fn process_alive_returns_false_for_overflowed_pid() {
         // PID > i32::MAX should return false, not panic
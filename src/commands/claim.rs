@@ -1,13 +1,22 @@
+use std::fs;
+use std::io::Write;
 use std::path::Path;
 use std::process::Command as ShellCommand;
 
 use anyhow::{anyhow, Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
 
-use crate::bean::{AttemptOutcome, AttemptRecord, Bean, Status};
+use crate::bean::{merge, AttemptOutcome, AttemptRecord, Bean, Status};
 use crate::config::resolve_identity;
 use crate::discovery::find_bean_file;
+use crate::error::BeansError;
+use crate::events::{self, Action};
+use crate::hooks;
 use crate::index::Index;
+use crate::sandbox;
+use crate::util::ensure_gitignored;
+use crate::verify_cache;
 
 /// Try to get the current git HEAD SHA. Returns None if not in a git repo.
 fn git_head_sha(working_dir: &Path) -> Option<String> {
@@ -23,16 +32,92 @@ fn git_head_sha(working_dir: &Path) -> Option<String> {
 }
 
 /// Run the verify command and return whether it passed (exit 0).
-fn run_verify_check(verify_cmd: &str, project_root: &Path) -> Result<bool> {
-    let output = ShellCommand::new("sh")
-        .args(["-c", verify_cmd])
-        .current_dir(project_root)
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
+///
+/// Answers from the verify cache when it is enabled and the bean's inputs
+/// are unchanged.
+fn run_verify_check(
+    beans_dir: &Path,
+    project_root: &Path,
+    bean: &Bean,
+    verify_cmd: &str,
+) -> Result<bool> {
+    hooks::check_bean_verify(project_root, &bean.id, verify_cmd)?;
+    let key = verify_cache::key(beans_dir, &bean.paths, verify_cmd);
+    if let Some(entry) = key
+        .as_ref()
+        .and_then(|k| verify_cache::lookup(beans_dir, k))
+    {
+        return Ok(entry.passed());
+    }
+
+    let started_at = Utc::now();
+    let output = sandbox::shell(project_root, verify_cmd)
+        .output()
         .with_context(|| format!("Failed to execute verify command: {}", verify_cmd))?;
 
-    Ok(output.success())
+    if let Some(key) = key {
+        let entry = verify_cache::Entry::new(
+            verify_cmd,
+            output.status.success(),
+            output.status.code(),
+            started_at,
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        );
+        verify_cache::store(beans_dir, &key, entry);
+    }
+    Ok(output.status.success())
+}
+
+/// Take the exclusive lock on `.beans/claim.lock` that serializes claims.
+///
+/// Held from re-reading the bean until it is written, so two agents can't
+/// both see it open. Released when the returned file is dropped.
+fn lock_claims(beans_dir: &Path) -> Result<fs::File> {
+    let lock_path = beans_dir.join("claim.lock");
+    let lock_file = fs::File::create(&lock_path)
+        .with_context(|| format!("Failed to create lock file: {}", lock_path.display()))?;
+    ensure_gitignored(beans_dir, "claim.lock");
+    lock_file
+        .lock_exclusive()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(lock_file)
+}
+
+/// Start a new attempt in the bean's attempt log.
+///
+/// The verify command and git HEAD are recorded with it so `bn close` can
+/// tell whether the gate was weakened during the attempt. An attempt that
+/// follows a released or failed one keeps that attempt's record, so editing
+/// the verify command between release and re-claim is still caught.
+pub(crate) fn start_attempt(
+    bean: &mut Bean,
+    project_root: &Path,
+    agent: Option<String>,
+    now: DateTime<Utc>,
+) {
+    let previous = bean
+        .attempt_log
+        .last()
+        .filter(|a| a.outcome != AttemptOutcome::Success && a.verify.is_some());
+    let (verify, base) = match previous {
+        Some(attempt) => (attempt.verify.clone(), attempt.base.clone()),
+        None => {
+            let verify = bean.verify.clone().filter(|v| !v.trim().is_empty());
+            let base = verify.as_ref().and_then(|_| git_head_sha(project_root));
+            (verify, base)
+        }
+    };
+    bean.attempt_log.push(AttemptRecord {
+        num: bean.attempt_log.len() as u32 + 1,
+        outcome: AttemptOutcome::Abandoned, // default until close/release updates it
+        notes: None,
+        agent,
+        started_at: Some(now),
+        finished_at: None,
+        verify,
+        base,
+    });
 }
 
 /// Claim a bean for work.
@@ -45,47 +130,69 @@ fn run_verify_check(verify_cmd: &str, project_root: &Path) -> Result<bool> {
 /// If it fails, the claim is granted with `fail_first: true` and the current
 /// git HEAD SHA is stored as `checkpoint`.
 pub fn cmd_claim(beans_dir: &Path, id: &str, by: Option<String>, force: bool) -> Result<()> {
-    let bean_path = find_bean_file(beans_dir, id).map_err(|_| anyhow!("Bean not found: {}", id))?;
+    let bean = claim_bean(beans_dir, id, by, force, &mut std::io::stderr())?;
+    let claimer = bean.claimed_by.as_deref().unwrap_or("anonymous");
+    println!("Claimed bean {}: {} (by {})", id, bean.title, claimer);
+    Ok(())
+}
+
+/// Claim a bean without printing to stdout; see [`cmd_claim`].
+///
+/// The bean is re-read under `.beans/claim.lock` before it is written, and
+/// the claim is refused if another agent claimed it in the meantime.
+/// Warnings and progress go to `err` (stderr for the CLI).
+///
+/// Returns the claimed bean.
+pub fn claim_bean(
+    beans_dir: &Path,
+    id: &str,
+    by: Option<String>,
+    force: bool,
+    err: &mut dyn Write,
+) -> Result<Bean> {
+    let bean_path = find_bean_file(beans_dir, id)?;
 
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let base = bean.clone();
 
     if bean.status != Status::Open {
-        return Err(anyhow!(
-            "Bean {} is {} -- only open beans can be claimed",
-            id,
-            bean.status
-        ));
+        return Err(BeansError::NotClaimable {
+            id: id.to_string(),
+            status: bean.status,
+            claimed_by: bean.claimed_by,
+        }
+        .into());
     }
 
     // Warn if bean has no verify command (GOAL vs SPEC)
     let has_verify = bean.verify.as_ref().is_some_and(|v| !v.trim().is_empty());
     if !has_verify {
-        eprintln!(
+        let _ = writeln!(
+            err,
             "Warning: Claiming GOAL (no verify). Consider decomposing with: bn create \"spec\" --parent {} --verify \"test\"",
             id
         );
     }
 
+    let project_root = beans_dir
+        .parent()
+        .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
+
     // Verify-on-claim: run verify before granting claim (TDD enforcement)
     // Skip when fail_first is false (bean created with -p / pass-ok)
     if has_verify && !force && bean.fail_first {
-        let project_root = beans_dir
-            .parent()
-            .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
         let verify_cmd = bean.verify.as_ref().unwrap();
 
-        eprintln!("Running verify before claim: {}", verify_cmd);
-        let passed = run_verify_check(verify_cmd, project_root)?;
+        let _ = writeln!(err, "Running verify before claim: {}", verify_cmd);
+        let passed = run_verify_check(beans_dir, project_root, &bean, verify_cmd)?;
 
         if passed {
-            return Err(anyhow!(
-                "Cannot claim bean {}: verify already passes\n\n\
-                 The verify command succeeded before any work was done.\n\
-                 This means either the test is bogus or the work is already complete.\n\n\
-                 Use --force to override.",
-                id
-            ));
+            return Err(BeansError::VerifyAlreadyPasses {
+                id: Some(id.to_string()),
+                command: verify_cmd.clone(),
+            }
+            .into());
         }
 
         // Verify failed — good, this proves the test is meaningful
@@ -103,21 +210,26 @@ pub fn cmd_claim(beans_dir: &Path, id: &str, by: Option<String>, force: bool) ->
     bean.updated_at = now;
 
     // Start a new attempt in the attempt log (for memory system tracking)
-    let attempt_num = bean.attempt_log.len() as u32 + 1;
-    bean.attempt_log.push(AttemptRecord {
-        num: attempt_num,
-        outcome: AttemptOutcome::Abandoned, // default until close/release updates it
-        notes: None,
-        agent: resolved_by.clone(),
-        started_at: Some(now),
-        finished_at: None,
-    });
+    start_attempt(&mut bean, project_root, resolved_by.clone(), now);
 
-    bean.to_file(&bean_path)
-        .with_context(|| format!("Failed to save bean: {}", id))?;
+    // Someone else may have claimed the bean while verify ran. Check again
+    // under the lock rather than merging their claim away.
+    let _lock = lock_claims(beans_dir)?;
+    let current =
+        Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    if current.status != Status::Open || current.claimed_by != base.claimed_by {
+        return Err(BeansError::NotClaimable {
+            id: id.to_string(),
+            status: current.status,
+            claimed_by: current.claimed_by,
+        }
+        .into());
+    }
 
-    let claimer = resolved_by.as_deref().unwrap_or("anonymous");
-    println!("Claimed bean {}: {} (by {})", id, bean.title, claimer);
+    let conflicts = merge::save_merged(&mut bean, &base, &bean_path, resolved_by.as_deref())
+        .with_context(|| format!("Failed to save bean: {}", id))?;
+    merge::warn_conflicts(err, id, &conflicts);
+    events::record(beans_dir, Action::Claim, id, Some(&base), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
@@ -125,17 +237,28 @@ pub fn cmd_claim(beans_dir: &Path, id: &str, by: Option<String>, force: bool) ->
         .save(beans_dir)
         .with_context(|| "Failed to save index")?;
 
-    Ok(())
+    Ok(bean)
 }
 
 /// Release a claim on a bean.
 ///
 /// Clears claimed_by/claimed_at and sets status back to Open.
 pub fn cmd_release(beans_dir: &Path, id: &str) -> Result<()> {
-    let bean_path = find_bean_file(beans_dir, id).map_err(|_| anyhow!("Bean not found: {}", id))?;
+    let bean = release_bean(beans_dir, id, &mut std::io::stderr())?;
+    println!("Released claim on bean {}: {}", id, bean.title);
+    Ok(())
+}
+
+/// Release a claim without printing to stdout; see [`cmd_release`].
+/// Warnings go to `err`.
+///
+/// Returns the released bean.
+pub fn release_bean(beans_dir: &Path, id: &str, err: &mut dyn Write) -> Result<Bean> {
+    let bean_path = find_bean_file(beans_dir, id)?;
 
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let base = bean.clone();
 
     let now = Utc::now();
 
@@ -152,10 +275,11 @@ pub fn cmd_release(beans_dir: &Path, id: &str) -> Result<()> {
     bean.status = Status::Open;
     bean.updated_at = now;
 
-    bean.to_file(&bean_path)
+    let agent = resolve_identity(beans_dir);
+    let conflicts = merge::save_merged(&mut bean, &base, &bean_path, agent.as_deref())
         .with_context(|| format!("Failed to save bean: {}", id))?;
-
-    println!("Released claim on bean {}: {}", id, bean.title);
+    merge::warn_conflicts(err, id, &conflicts);
+    events::record(beans_dir, Action::Release, id, Some(&base), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
@@ -163,7 +287,7 @@ pub fn cmd_release(beans_dir: &Path, id: &str) -> Result<()> {
         .save(beans_dir)
         .with_context(|| "Failed to save index")?;
 
-    Ok(())
+    Ok(bean)
 }
 
 #[cfg(test)]
@@ -510,6 +634,8 @@ mod tests {
             agent: Some("agent-1".to_string()),
             started_at: Some(Utc::now()),
             finished_at: None,
+            verify: None,
+            base: None,
         });
         bean.to_file(beans_dir.join("1.yaml")).unwrap();
 
@@ -543,4 +669,46 @@ mod tests {
         assert_eq!(updated.attempt_log[1].agent, Some("agent-2".to_string()));
         assert!(updated.attempt_log[1].finished_at.is_none());
     }
+
+    #[test]
+    fn concurrent_claims_only_one_wins() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        // Both claimers run verify before either one writes the claim
+        let mut bean = Bean::new("1", "Contested");
+        bean.verify = Some("sleep 0.3; false".to_string());
+        bean.fail_first = true;
+        bean.to_file(beans_dir.join("1.yaml")).unwrap();
+
+        let handles: Vec<_> = ["agent-1", "agent-2"]
+            .into_iter()
+            .map(|agent| {
+                let beans_dir = beans_dir.clone();
+                std::thread::spawn(move || {
+                    claim_bean(
+                        &beans_dir,
+                        "1",
+                        Some(agent.to_string()),
+                        false,
+                        &mut std::io::sink(),
+                    )
+                })
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        let winners: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
+        assert_eq!(winners.len(), 1);
+        let loser = results.iter().find_map(|r| r.as_ref().err()).unwrap();
+        assert!(matches!(
+            crate::error::find(loser),
+            Some(BeansError::NotClaimable {
+                status: Status::InProgress,
+                ..
+            })
+        ));
+
+        let updated = Bean::from_file(beans_dir.join("1.yaml")).unwrap();
+        assert_eq!(updated.claimed_by, winners[0].claimed_by);
+        assert_eq!(updated.attempt_log.len(), 1);
+    }
 }
@@ -0,0 +1,257 @@
+//! Verify-gate tamper detection.
+//!
+//! An agent can make a bean "pass" by weakening its verify command or by
+//! deleting the test the command runs. Claiming a bean records the verify
+//! command and git HEAD in the attempt log; before running verify, close
+//! compares the bean against that record and flags:
+//!
+//! - a verify command that differs from the one at claim time
+//! - files the command refers to (test files, scripts, directories) that
+//!   were deleted, or shrank below three quarters of their size at claim
+//!
+//! A flagged bean is not closed. It is held in review for a human, who can
+//! close it with `--force` after checking the change.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use chrono::Utc;
+
+use crate::bean::{Bean, Status};
+
+/// Label added to beans held for review.
+const REVIEW_LABEL: &str = "needs-human-review";
+
+/// Compare the bean's verify gate with the one recorded when the current
+/// attempt was claimed. Returns one line per finding; empty if nothing
+/// looks tampered with, or if the claim recorded nothing to compare.
+pub(crate) fn check(project_root: &Path, bean: &Bean) -> Vec<String> {
+    let Some(attempt) = bean.attempt_log.last() else {
+        return Vec::new();
+    };
+    let Some(claimed) = attempt.verify.as_deref() else {
+        return Vec::new();
+    };
+    if attempt.finished_at.is_some() {
+        return Vec::new();
+    }
+
+    let mut findings = Vec::new();
+    let current = bean.verify.as_deref().unwrap_or_default();
+    if current.trim() != claimed.trim() {
+        findings.push(format!(
+            "verify command changed since claim: `{}` → `{}`",
+            claimed, current
+        ));
+    }
+
+    if let Some(base) = &attempt.base {
+        for (path, size) in referenced_files(project_root, base, claimed) {
+            match fs::metadata(project_root.join(&path)) {
+                Err(_) => findings.push(format!("{} was deleted", path)),
+                Ok(meta) if meta.len() * 4 < size * 3 => findings.push(format!(
+                    "{} shrank from {} to {} bytes",
+                    path,
+                    size,
+                    meta.len()
+                )),
+                Ok(_) => {}
+            }
+        }
+    }
+    findings
+}
+
+/// Hold the bean for human review: status `in_review`, the
+/// `needs-human-review` label, and the findings appended to its notes.
+pub(crate) fn flag_for_review(bean: &mut Bean, findings: &[String]) {
+    let note = format!(
+        "\n---\n**Verify gate tampering suspected** ({})\n\n{}\n\nClose with `bn close {} --force` once a human has checked the change.\n",
+        Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        findings
+            .iter()
+            .map(|f| format!("- {}", f))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        bean.id
+    );
+    match bean.notes {
+        Some(ref mut existing) => existing.push_str(&note),
+        None => bean.notes = Some(note),
+    }
+    if !bean.labels.iter().any(|l| l == REVIEW_LABEL) {
+        bean.labels.push(REVIEW_LABEL.to_string());
+    }
+    bean.status = Status::InReview;
+    bean.updated_at = Utc::now();
+}
+
+/// Files the verify command refers to, with their size at commit `base`.
+///
+/// Every word of the command that could be a path is looked up in `base`
+/// (directories expand to the files under them); words that name nothing
+/// there are ignored. `cargo test --test NAME` refers to `tests/NAME.rs`.
+fn referenced_files(project_root: &Path, base: &str, verify_cmd: &str) -> Vec<(String, u64)> {
+    let words: Vec<&str> = verify_cmd
+        .split(|c: char| c.is_whitespace() || ";|&()<>'\"`".contains(c))
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut candidates = BTreeSet::new();
+    for (i, word) in words.iter().enumerate() {
+        if *word == "--test" {
+            if let Some(name) = words.get(i + 1) {
+                candidates.insert(format!("tests/{}.rs", name));
+            }
+            continue;
+        }
+        if word.starts_with('-') || !(word.contains('/') || word.contains('.')) {
+            continue;
+        }
+        // pytest node IDs: tests/test_x.py::test_case
+        let path = word.split("::").next().unwrap_or(word);
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        if path.is_empty() || path == "." || path == ".." || path.starts_with('/') {
+            continue;
+        }
+        candidates.insert(path.to_string());
+    }
+
+    let mut files = Vec::new();
+    for path in candidates {
+        let Ok(output) = Command::new("git")
+            .args(["ls-tree", "-r", "-l", "-z", base, "--", &path])
+            .current_dir(project_root)
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        // "<mode> blob <sha> <size>\t<path>"
+        for record in String::from_utf8_lossy(&output.stdout).split('\0') {
+            let Some((meta, file)) = record.split_once('\t') else {
+                continue;
+            };
+            if file.starts_with(".beans/") {
+                continue;
+            }
+            if let Some(size) = meta.split_whitespace().nth(3).and_then(|s| s.parse().ok()) {
+                files.push((file.to_string(), size));
+            }
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::{AttemptOutcome, AttemptRecord};
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// A repo with a test file and a script, and a bean claimed at HEAD.
+    fn setup(verify: &str) -> (TempDir, Bean) {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        fs::create_dir_all(root.join("tests")).unwrap();
+        fs::write(
+            root.join("tests/parser.rs"),
+            "#[test]\nfn parses() {}\n".repeat(20),
+        )
+        .unwrap();
+        fs::write(root.join("check.sh"), "#!/bin/sh\ncargo test\n").unwrap();
+        git(root, &["add", "."]);
+        git(
+            root,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-qm",
+                "init",
+            ],
+        );
+        let head = git(root, &["rev-parse", "HEAD"]);
+
+        let mut bean = Bean::new("1", "Task");
+        bean.verify = Some(verify.to_string());
+        bean.attempt_log.push(AttemptRecord {
+            num: 1,
+            outcome: AttemptOutcome::Abandoned,
+            notes: None,
+            agent: None,
+            started_at: Some(Utc::now()),
+            finished_at: None,
+            verify: Some(verify.to_string()),
+            base: Some(head),
+        });
+        (dir, bean)
+    }
+
+    #[test]
+    fn untouched_gate_passes() {
+        let (dir, bean) = setup("cargo test --test parser && sh ./check.sh");
+        fs::write(dir.path().join("tests/parser.rs"), "// more\n".repeat(60)).unwrap();
+        assert!(check(dir.path(), &bean).is_empty());
+    }
+
+    #[test]
+    fn changed_verify_command_is_flagged() {
+        let (dir, mut bean) = setup("cargo test --test parser");
+        bean.verify = Some("true".to_string());
+        let findings = check(dir.path(), &bean);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].contains("verify command changed"));
+    }
+
+    #[test]
+    fn deleted_and_shrunk_files_are_flagged() {
+        let (dir, bean) = setup("cargo test --test parser && sh check.sh");
+        fs::remove_file(dir.path().join("check.sh")).unwrap();
+        fs::write(dir.path().join("tests/parser.rs"), "").unwrap();
+        let findings = check(dir.path(), &bean);
+        assert_eq!(
+            findings,
+            vec![
+                "check.sh was deleted".to_string(),
+                "tests/parser.rs shrank from 460 to 0 bytes".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn directories_cover_their_files() {
+        let (dir, bean) = setup("pytest tests/ -q");
+        fs::remove_file(dir.path().join("tests/parser.rs")).unwrap();
+        assert_eq!(
+            check(dir.path(), &bean),
+            vec!["tests/parser.rs was deleted"]
+        );
+    }
+
+    #[test]
+    fn flagged_bean_is_held_for_review() {
+        let mut bean = Bean::new("1", "Task");
+        flag_for_review(&mut bean, &["check.sh was deleted".to_string()]);
+        assert_eq!(bean.status, Status::InReview);
+        assert!(bean.labels.contains(&REVIEW_LABEL.to_string()));
+        assert!(bean.notes.unwrap().contains("- check.sh was deleted"));
+    }
+}
@@ -1,22 +1,28 @@
+pub(crate) mod gate;
 mod verify;
 
+use std::io::Write;
 use std::path::Path;
 
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 
-use crate::bean::{Bean, OnCloseAction, OnFailAction, RunRecord, RunResult, Status};
-use crate::config::Config;
+use crate::bean::{merge, Bean, OnCloseAction, OnFailAction, RunRecord, RunResult, Status};
+use crate::config::{resolve_identity, Config};
 use crate::discovery::{archive_path_for_bean, find_archived_bean, find_bean_file};
+use crate::error::BeansError;
+use crate::events::{self, Action, Operation};
 use crate::failure;
 use crate::hooks::{
-    current_git_branch, execute_config_hook, execute_hook, is_trusted, HookEvent, HookVars,
+    check_bean_on_close, check_bean_verify, current_git_branch, execute_approved_config_hook,
+    execute_hook, HookEvent, HookVars,
 };
 use crate::index::{ArchiveIndex, Index, IndexEntry};
+use crate::test_results;
 use crate::util::title_to_slug;
 use crate::worktree;
 
-use verify::{format_failure_note, run_verify, truncate_output};
+use verify::{format_failure_note, run_verify_cached, truncate_output};
 
 #[cfg(test)]
 use std::fs;
@@ -24,6 +30,16 @@ use std::fs;
 /// Maximum stdout size to capture as outputs (64 KB).
 const MAX_OUTPUT_BYTES: usize = 64 * 1024;
 
+/// `println!` to a close report sink, ignoring write errors.
+macro_rules! say {
+    ($out:expr) => {
+        let _ = writeln!($out);
+    };
+    ($out:expr, $($arg:tt)*) => {
+        let _ = writeln!($out, $($arg)*);
+    };
+}
+
 /// Find the largest byte index <= `max_bytes` that falls on a UTF-8 char boundary.
 ///
 /// Slicing a `&str` at an arbitrary byte offset panics if it lands inside a
@@ -39,11 +55,36 @@ fn truncate_to_char_boundary(s: &str, max_bytes: usize) -> usize {
     end
 }
 
+/// Write a bean loaded as `base`, merging with any concurrent edit on disk.
+///
+/// Conflicting fields keep our value and are recorded for `bn resolve`.
+/// On return `base` is updated to what was written, so the same bean can be
+/// saved again later in the command.
+fn save_bean(
+    beans_dir: &Path,
+    bean: &mut Bean,
+    base: &mut Bean,
+    bean_path: &Path,
+    err: &mut dyn Write,
+) -> Result<()> {
+    let agent = std::env::var("BEANS_AGENT")
+        .ok()
+        .or_else(|| resolve_identity(beans_dir));
+    let conflicts = merge::save_merged(bean, base, bean_path, agent.as_deref())
+        .with_context(|| format!("Failed to save bean: {}", bean.id))?;
+    merge::warn_conflicts(err, &bean.id, &conflicts);
+    *base = bean.clone();
+    Ok(())
+}
+
 /// Check if all children of a parent bean are closed (in archive or with status=closed).
 ///
 /// Returns true if:
 /// - The parent has no children, OR
 /// - All children are either in the archive (closed) or have status=closed
+///
+/// A cancelled child keeps its parent open, just as a cancelled dependency
+/// keeps its dependents blocked.
 fn all_children_closed(beans_dir: &Path, parent_id: &str) -> Result<bool> {
     // Always rebuild the index fresh - we can't rely on staleness check because
     // files may have just been moved to archive (which isn't tracked in staleness)
@@ -82,7 +123,12 @@ fn all_children_closed(beans_dir: &Path, parent_id: &str) -> Result<bool> {
 /// - Skips verify command (children already verified)
 /// - Sets close_reason to indicate auto-close
 /// - Recursively checks grandparent
-fn auto_close_parent(beans_dir: &Path, parent_id: &str) -> Result<()> {
+fn auto_close_parent(
+    beans_dir: &Path,
+    parent_id: &str,
+    op: &Operation,
+    out: &mut dyn Write,
+) -> Result<()> {
     // Find the parent bean
     let bean_path = match find_bean_file(beans_dir, parent_id) {
         Ok(path) => path,
@@ -94,9 +140,10 @@ fn auto_close_parent(beans_dir: &Path, parent_id: &str) -> Result<()> {
 
     let mut bean = Bean::from_file(&bean_path)
         .with_context(|| format!("Failed to load parent bean: {}", parent_id))?;
+    let before = bean.clone();
 
-    // Skip if already closed
-    if bean.status == Status::Closed {
+    // Skip if already closed or cancelled
+    if bean.status.is_terminal() {
         return Ok(());
     }
 
@@ -146,6 +193,13 @@ fn auto_close_parent(beans_dir: &Path, parent_id: &str) -> Result<()> {
     bean.is_archived = true;
     bean.to_file(&archive_path)
         .with_context(|| format!("Failed to save archived parent bean: {}", parent_id))?;
+    op.record(
+        beans_dir,
+        Action::Close,
+        parent_id,
+        Some(&before),
+        Some(&bean),
+    );
 
     // Append to archive index
     {
@@ -155,12 +209,12 @@ fn auto_close_parent(beans_dir: &Path, parent_id: &str) -> Result<()> {
         let _ = archive_index.save(beans_dir);
     }
 
-    println!("Auto-closed parent bean {}: {}", parent_id, bean.title);
+    say!(out, "Auto-closed parent bean {}: {}", parent_id, bean.title);
 
     // Recursively check if this bean's parent should also be auto-closed
     if let Some(grandparent_id) = &bean.parent {
         if all_children_closed(beans_dir, grandparent_id)? {
-            auto_close_parent(beans_dir, grandparent_id)?;
+            auto_close_parent(beans_dir, grandparent_id, op, out)?;
         }
     }
 
@@ -213,515 +267,704 @@ pub fn cmd_close(
         return Err(anyhow!("At least one bean ID is required"));
     }
 
-    let now = Utc::now();
     let mut any_closed = false;
     let mut rejected_beans = Vec::new();
+    let mut stdout = std::io::stdout();
+
+    for id in &ids {
+        match close_bean(
+            beans_dir,
+            id,
+            reason.clone(),
+            force,
+            true,
+            &mut stdout,
+            &mut std::io::stderr(),
+        )? {
+            CloseOutcome::Closed { .. } => any_closed = true,
+            CloseOutcome::RejectedByHook => rejected_beans.push(id.clone()),
+            CloseOutcome::MergeConflict { files } => {
+                return Err(BeansError::Conflict {
+                    id: id.clone(),
+                    files,
+                }
+                .into())
+            }
+            CloseOutcome::VerifyTampered { findings } => {
+                return Err(BeansError::VerifyTampered {
+                    id: id.clone(),
+                    findings,
+                }
+                .into())
+            }
+            _ => {}
+        }
+    }
+
+    // Report rejected beans
+    if !rejected_beans.is_empty() {
+        eprintln!(
+            "Failed to close {} bean(s) due to pre-close hook rejection: {}",
+            rejected_beans.len(),
+            rejected_beans.join(", ")
+        );
+    }
+
+    // Rebuild index once after all updates (even if some failed verification)
+    // Skip if beans_dir was removed by worktree cleanup
+    if (any_closed || !ids.is_empty()) && beans_dir.exists() {
+        let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
+        index
+            .save(beans_dir)
+            .with_context(|| "Failed to save index")?;
+    }
+
+    Ok(())
+}
+
+/// What happened to a bean passed to [`close_bean`].
+#[derive(Debug)]
+pub enum CloseOutcome {
+    /// Closed and archived.
+    Closed { bean: Box<Bean> },
+    /// The pre-close hook refused the close.
+    RejectedByHook,
+    /// The verify command failed; the bean stays open.
+    VerifyFailed {
+        /// Attempt count after this failure.
+        attempts: u32,
+        exit_code: Option<i32>,
+        timed_out: bool,
+        /// Combined stdout and stderr of the verify command.
+        output: String,
+    },
+    /// The verify command failed and the subtree has used up `max_loops`;
+    /// the bean was escalated to P0.
+    CircuitBreakerTripped {
+        subtree_attempts: u32,
+        max_loops: u32,
+        root_id: String,
+    },
+    /// Merging the bean's worktree into main conflicted.
+    MergeConflict { files: Vec<String> },
+    /// The verify command was changed, or files it runs were deleted or
+    /// shrunk, since the bean was claimed; the bean is held in review.
+    VerifyTampered { findings: Vec<String> },
+    /// A feature bean that needs a human to confirm the close.
+    NeedsReview,
+}
 
+/// Close a single bean, writing progress to `out` instead of stdout and
+/// warnings to `err` instead of stderr.
+///
+/// Does everything [`cmd_close`] does for one bean except rebuilding the
+/// index. Feature beans only close when `interactive` is set and a human
+/// confirms at the terminal; otherwise they come back as
+/// [`CloseOutcome::NeedsReview`].
+pub fn close_bean(
+    beans_dir: &Path,
+    id: &str,
+    reason: Option<String>,
+    force: bool,
+    interactive: bool,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<CloseOutcome> {
+    let now = Utc::now();
     let project_root = beans_dir
         .parent()
         .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
-
     let config = Config::load(beans_dir).ok();
 
-    for id in &ids {
-        let bean_path =
-            find_bean_file(beans_dir, id).with_context(|| format!("Bean not found: {}", id))?;
+    let bean_path =
+        find_bean_file(beans_dir, id).with_context(|| format!("Bean not found: {}", id))?;
+
+    let mut bean =
+        Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let mut base = bean.clone();
+    let before = bean.clone();
 
-        let mut bean =
-            Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let pre_close_result = execute_hook(HookEvent::PreClose, &bean, project_root, reason.clone());
 
-        let pre_close_result =
-            execute_hook(HookEvent::PreClose, &bean, project_root, reason.clone());
+    let pre_close_passed = match pre_close_result {
+        Ok(hook_passed) => {
+            // Hook executed successfully, use its result
+            hook_passed
+        }
+        Err(e) => {
+            // Hook execution failed (not executable, timeout, etc.), log but don't block
+            say!(err, "Bean {} pre-close hook error: {}", id, e);
+            true // Silently pass (allow close to proceed)
+        }
+    };
 
-        let pre_close_passed = match pre_close_result {
-            Ok(hook_passed) => {
-                // Hook executed successfully, use its result
-                hook_passed
+    if !pre_close_passed {
+        say!(err, "Bean {} rejected by pre-close hook", id);
+        return Ok(CloseOutcome::RejectedByHook);
+    }
+
+    // Refuse a verify gate that was weakened since the claim
+    if !force {
+        let findings = gate::check(project_root, &bean);
+        if !findings.is_empty() {
+            gate::flag_for_review(&mut bean, &findings);
+            save_bean(beans_dir, &mut bean, &mut base, &bean_path, err)?;
+            events::record_with_note(
+                beans_dir,
+                Action::Update,
+                id,
+                Some(&before),
+                Some(&bean),
+                Some("verify gate tampering suspected".to_string()),
+            );
+            say!(out, "✗ Verify gate changed since bean {} was claimed:", id);
+            for finding in &findings {
+                say!(out, "  - {}", finding);
             }
-            Err(e) => {
-                // Hook execution failed (not executable, timeout, etc.), log but don't block
-                eprintln!("Bean {} pre-close hook error: {}", id, e);
-                true // Silently pass (allow close to proceed)
+            say!(out, "Bean {} is held for human review.", id);
+            return Ok(CloseOutcome::VerifyTampered { findings });
+        }
+    }
+
+    // Check if bean has a verify command (runs AFTER pre-close hook passes)
+    if let Some(verify_cmd) = bean.verify.clone() {
+        if verify_cmd.trim().is_empty() {
+            say!(
+                err,
+                "Warning: bean {} has empty verify command, skipping",
+                id
+            );
+        } else if force {
+            say!(out, "Skipping verify for bean {} (--force)", id);
+        } else {
+            // Record timing for history
+            let started_at = Utc::now();
+
+            // Compute effective timeout: bean-level overrides config-level.
+            let timeout_secs =
+                bean.effective_verify_timeout(config.as_ref().and_then(|c| c.verify_timeout));
+
+            // Run the verify command, or replay it if its inputs are unchanged
+            check_bean_verify(project_root, id, &verify_cmd)?;
+            say!(out, "Running verify: {}", verify_cmd);
+            let (verify_result, cached) =
+                run_verify_cached(beans_dir, &bean, &verify_cmd, timeout_secs)?;
+            if cached {
+                say!(out, "Inputs unchanged, using cached verify result");
+            }
+            let test_reports = config
+                .as_ref()
+                .map(|c| c.test_reports.as_slice())
+                .unwrap_or_default();
+            let tests = test_results::collect(
+                project_root,
+                test_reports,
+                started_at.into(),
+                &verify_result.output,
+            );
+            if let Some(ref tests) = tests {
+                say!(out, "{}", tests);
             }
-        };
 
-        if !pre_close_passed {
-            eprintln!("Bean {} rejected by pre-close hook", id);
-            rejected_beans.push(id.clone());
-            continue;
-        }
+            let finished_at = Utc::now();
+            let duration_secs = (finished_at - started_at).num_milliseconds() as f64 / 1000.0;
 
-        // Check if bean has a verify command (runs AFTER pre-close hook passes)
-        if let Some(ref verify_cmd) = bean.verify {
-            if verify_cmd.trim().is_empty() {
-                eprintln!("Warning: bean {} has empty verify command, skipping", id);
-            } else if force {
-                println!("Skipping verify for bean {} (--force)", id);
-            } else {
-                // Record timing for history
-                let started_at = Utc::now();
-
-                // Compute effective timeout: bean-level overrides config-level.
-                let timeout_secs =
-                    bean.effective_verify_timeout(config.as_ref().and_then(|c| c.verify_timeout));
-
-                // Run the verify command
-                let verify_result = run_verify(beans_dir, verify_cmd, timeout_secs)?;
-
-                let finished_at = Utc::now();
-                let duration_secs = (finished_at - started_at).num_milliseconds() as f64 / 1000.0;
-
-                // Read agent name from env var (deli/bw set this when spawning)
-                let agent = std::env::var("BEANS_AGENT").ok();
-
-                if !verify_result.success {
-                    // Increment attempts
-                    bean.attempts += 1;
-                    bean.updated_at = Utc::now();
-
-                    // Surface timeout prominently
-                    if verify_result.timed_out {
-                        let secs = timeout_secs.unwrap_or(0);
-                        println!("Verify timed out after {}s for bean {}", secs, id);
-                    }
+            // Read agent name from env var (deli/bw set this when spawning)
+            let agent = std::env::var("BEANS_AGENT").ok();
 
-                    // Append failure to notes for future agents (backward compat)
-                    let failure_note = format_failure_note(
-                        bean.attempts,
-                        verify_result.exit_code,
-                        &verify_result.output,
-                    );
-                    match &mut bean.notes {
-                        Some(notes) => notes.push_str(&failure_note),
-                        None => bean.notes = Some(failure_note),
-                    }
+            if !verify_result.success {
+                // Increment attempts
+                bean.attempts += 1;
+                bean.updated_at = Utc::now();
 
-                    // Record structured history entry
-                    let output_snippet = if verify_result.output.is_empty() {
-                        None
-                    } else {
-                        Some(truncate_output(&verify_result.output, 20))
-                    };
-                    bean.history.push(RunRecord {
-                        attempt: bean.attempts,
-                        started_at,
-                        finished_at: Some(finished_at),
-                        duration_secs: Some(duration_secs),
-                        agent: agent.clone(),
-                        result: if verify_result.timed_out {
-                            RunResult::Timeout
-                        } else {
-                            RunResult::Fail
-                        },
-                        exit_code: verify_result.exit_code,
-                        tokens: None,
-                        cost: None,
-                        output_snippet,
-                    });
-
-                    // Circuit breaker: check if subtree attempts exceed max_loops
-                    let root_id = find_root_parent(beans_dir, &bean)?;
-                    let config_max = config.as_ref().map(|c| c.max_loops).unwrap_or(10);
-                    let max_loops_limit = if root_id == bean.id {
-                        bean.effective_max_loops(config_max)
+                // Surface timeout prominently
+                if verify_result.timed_out {
+                    let secs = timeout_secs.unwrap_or(0);
+                    say!(out, "Verify timed out after {}s for bean {}", secs, id);
+                }
+
+                // Append failure to notes for future agents (backward compat)
+                let failure_note = format_failure_note(
+                    bean.attempts,
+                    verify_result.exit_code,
+                    &verify_result.output,
+                );
+                match &mut bean.notes {
+                    Some(notes) => notes.push_str(&failure_note),
+                    None => bean.notes = Some(failure_note),
+                }
+
+                // Record structured history entry
+                let output_snippet = if verify_result.output.is_empty() {
+                    None
+                } else {
+                    Some(truncate_output(&verify_result.output, 20))
+                };
+                bean.history.push(RunRecord {
+                    attempt: bean.attempts,
+                    started_at,
+                    finished_at: Some(finished_at),
+                    duration_secs: Some(duration_secs),
+                    agent: agent.clone(),
+                    result: if verify_result.timed_out {
+                        RunResult::Timeout
                     } else {
-                        let root_path = find_bean_file(beans_dir, &root_id)
-                            .or_else(|_| find_archived_bean(beans_dir, &root_id));
-                        match root_path {
-                            Ok(p) => Bean::from_file(&p)
-                                .map(|b| b.effective_max_loops(config_max))
-                                .unwrap_or(config_max),
-                            Err(_) => config_max,
-                        }
-                    };
-
-                    if max_loops_limit > 0 {
-                        // Save bean first so subtree count is accurate
-                        bean.to_file(&bean_path)
-                            .with_context(|| format!("Failed to save bean: {}", id))?;
-
-                        let subtree_total =
-                            crate::graph::count_subtree_attempts(beans_dir, &root_id)?;
-                        if subtree_total >= max_loops_limit {
-                            // Trip circuit breaker
-                            if !bean.labels.contains(&"circuit-breaker".to_string()) {
-                                bean.labels.push("circuit-breaker".to_string());
-                            }
-                            bean.priority = 0;
-                            bean.to_file(&bean_path)
-                                .with_context(|| format!("Failed to save bean: {}", id))?;
-
-                            eprintln!(
-                                "⚡ Circuit breaker tripped for bean {} \
-                                 (subtree total {} >= max_loops {} across root {})",
-                                id, subtree_total, max_loops_limit, root_id
-                            );
-                            eprintln!(
-                                "Bean {} escalated to P0 with 'circuit-breaker' label. \
-                                 Manual intervention required.",
-                                id
-                            );
-                            continue;
+                        RunResult::Fail
+                    },
+                    exit_code: verify_result.exit_code,
+                    tokens: None,
+                    cost: None,
+                    output_snippet,
+                    reason: None,
+                    cached,
+                    tests,
+                });
+
+                // Circuit breaker: check if subtree attempts exceed max_loops
+                let root_id = find_root_parent(beans_dir, &bean)?;
+                let config_max = config.as_ref().map(|c| c.max_loops).unwrap_or(10);
+                let max_loops_limit = if root_id == bean.id {
+                    bean.effective_max_loops(config_max)
+                } else {
+                    let root_path = find_bean_file(beans_dir, &root_id)
+                        .or_else(|_| find_archived_bean(beans_dir, &root_id));
+                    match root_path {
+                        Ok(p) => Bean::from_file(&p)
+                            .map(|b| b.effective_max_loops(config_max))
+                            .unwrap_or(config_max),
+                        Err(_) => config_max,
+                    }
+                };
+
+                if max_loops_limit > 0 {
+                    // Save bean first so subtree count is accurate
+                    save_bean(beans_dir, &mut bean, &mut base, &bean_path, err)?;
+
+                    let subtree_total = crate::graph::count_subtree_attempts(beans_dir, &root_id)?;
+                    if subtree_total >= max_loops_limit {
+                        // Trip circuit breaker
+                        if !bean.labels.contains(&"circuit-breaker".to_string()) {
+                            bean.labels.push("circuit-breaker".to_string());
                         }
+                        bean.priority = 0;
+                        save_bean(beans_dir, &mut bean, &mut base, &bean_path, err)?;
+
+                        say!(
+                            err,
+                            "⚡ Circuit breaker tripped for bean {} \
+                             (subtree total {} >= max_loops {} across root {})",
+                            id,
+                            subtree_total,
+                            max_loops_limit,
+                            root_id
+                        );
+                        say!(
+                            err,
+                            "Bean {} escalated to P0 with 'circuit-breaker' label. \
+                             Manual intervention required.",
+                            id
+                        );
+                        return Ok(CloseOutcome::CircuitBreakerTripped {
+                            subtree_attempts: subtree_total,
+                            max_loops: max_loops_limit,
+                            root_id,
+                        });
                     }
+                }
 
-                    // Process on_fail action
-                    if let Some(ref on_fail) = bean.on_fail {
-                        match on_fail {
-                            OnFailAction::Retry { max, delay_secs } => {
-                                let max_retries = max.unwrap_or(bean.max_attempts);
-                                if bean.attempts < max_retries {
-                                    println!(
-                                        "on_fail: will retry (attempt {}/{})",
-                                        bean.attempts, max_retries
+                // Process on_fail action
+                if let Some(ref on_fail) = bean.on_fail {
+                    match on_fail {
+                        OnFailAction::Retry { max, delay_secs } => {
+                            let max_retries = max.unwrap_or(bean.max_attempts);
+                            if bean.attempts < max_retries {
+                                say!(
+                                    out,
+                                    "on_fail: will retry (attempt {}/{})",
+                                    bean.attempts,
+                                    max_retries
+                                );
+                                if let Some(delay) = delay_secs {
+                                    say!(
+                                        out,
+                                        "on_fail: retry delay {}s (enforced by orchestrator)",
+                                        delay
                                     );
-                                    if let Some(delay) = delay_secs {
-                                        println!(
-                                            "on_fail: retry delay {}s (enforced by orchestrator)",
-                                            delay
-                                        );
-                                    }
-                                    // Release claim so bw/deli can pick it up
-                                    bean.claimed_by = None;
-                                    bean.claimed_at = None;
-                                } else {
-                                    println!("on_fail: max retries ({}) exhausted", max_retries);
                                 }
+                                // Release claim so bw/deli can pick it up
+                                bean.claimed_by = None;
+                                bean.claimed_at = None;
+                            } else {
+                                say!(out, "on_fail: max retries ({}) exhausted", max_retries);
                             }
-                            OnFailAction::Escalate { priority, message } => {
-                                if let Some(p) = priority {
-                                    let old_priority = bean.priority;
-                                    bean.priority = *p;
-                                    println!(
-                                        "on_fail: escalated priority P{} → P{}",
-                                        old_priority, p
-                                    );
-                                }
-                                if let Some(msg) = message {
-                                    // Append escalation message to notes
-                                    let note = format!(
-                                        "\n## Escalated — {}\n{}",
-                                        Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
-                                        msg
-                                    );
-                                    match &mut bean.notes {
-                                        Some(notes) => notes.push_str(&note),
-                                        None => bean.notes = Some(note),
-                                    }
-                                    println!("on_fail: {}", msg);
-                                }
-                                // Add escalated label
-                                if !bean.labels.contains(&"escalated".to_string()) {
-                                    bean.labels.push("escalated".to_string());
+                        }
+                        OnFailAction::Escalate { priority, message } => {
+                            if let Some(p) = priority {
+                                let old_priority = bean.priority;
+                                bean.priority = *p;
+                                say!(
+                                    out,
+                                    "on_fail: escalated priority P{} → P{}",
+                                    old_priority,
+                                    p
+                                );
+                            }
+                            if let Some(msg) = message {
+                                // Append escalation message to notes
+                                let note = format!(
+                                    "\n## Escalated — {}\n{}",
+                                    Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+                                    msg
+                                );
+                                match &mut bean.notes {
+                                    Some(notes) => notes.push_str(&note),
+                                    None => bean.notes = Some(note),
                                 }
+                                say!(out, "on_fail: {}", msg);
+                            }
+                            // Add escalated label
+                            if !bean.labels.contains(&"escalated".to_string()) {
+                                bean.labels.push("escalated".to_string());
                             }
                         }
                     }
+                }
 
-                    bean.to_file(&bean_path)
-                        .with_context(|| format!("Failed to save bean: {}", id))?;
+                save_bean(beans_dir, &mut bean, &mut base, &bean_path, err)?;
 
-                    // Display detailed failure feedback
-                    if verify_result.timed_out {
-                        println!("✗ Verify timed out for bean {}", id);
-                    } else {
-                        println!("✗ Verify failed for bean {}", id);
-                    }
-                    println!();
-                    println!("Command: {}", verify_cmd);
-                    if verify_result.timed_out {
-                        println!("Timed out after {}s", timeout_secs.unwrap_or(0));
-                    } else if let Some(code) = verify_result.exit_code {
-                        println!("Exit code: {}", code);
-                    }
-                    if !verify_result.output.is_empty() {
-                        println!("Output:");
-                        for line in verify_result.output.lines() {
-                            println!("  {}", line);
-                        }
+                // Display detailed failure feedback
+                if verify_result.timed_out {
+                    say!(out, "✗ Verify timed out for bean {}", id);
+                } else {
+                    say!(out, "✗ Verify failed for bean {}", id);
+                }
+                say!(out);
+                say!(out, "Command: {}", verify_cmd);
+                if verify_result.timed_out {
+                    say!(out, "Timed out after {}s", timeout_secs.unwrap_or(0));
+                } else if let Some(code) = verify_result.exit_code {
+                    say!(out, "Exit code: {}", code);
+                }
+                if !verify_result.output.is_empty() {
+                    say!(out, "Output:");
+                    for line in verify_result.output.lines() {
+                        say!(out, "  {}", line);
                     }
-                    println!();
-                    println!("Attempt {}. Bean remains open.", bean.attempts);
-                    println!("Tip: Run `bn verify {}` to test without closing.", id);
-                    println!("Tip: Use `bn close {} --force` to skip verify.", id);
-
-                    // Fire on_fail config hook (async, non-blocking)
-                    if let Some(ref config) = config {
-                        if let Some(ref on_fail_template) = config.on_fail {
-                            let output_text = &verify_result.output;
-                            let vars = HookVars {
-                                id: Some(id.clone()),
-                                title: Some(bean.title.clone()),
-                                status: Some(format!("{}", bean.status)),
-                                attempt: Some(bean.attempts),
-                                output: Some(output_text.clone()),
-                                branch: current_git_branch(),
-                                ..Default::default()
-                            };
-                            execute_config_hook("on_fail", on_fail_template, &vars, project_root);
-                        }
+                }
+                say!(out);
+                say!(out, "Attempt {}. Bean remains open.", bean.attempts);
+                say!(out, "Tip: Run `bn verify {}` to test without closing.", id);
+                say!(out, "Tip: Use `bn close {} --force` to skip verify.", id);
+
+                // Fire on_fail config hook (async, non-blocking)
+                if let Some(ref config) = config {
+                    if let Some(ref on_fail_template) = config.on_fail {
+                        let output_text = &verify_result.output;
+                        let vars = HookVars {
+                            id: Some(id.to_string()),
+                            title: Some(bean.title.clone()),
+                            status: Some(format!("{}", bean.status)),
+                            attempt: Some(bean.attempts),
+                            output: Some(output_text.clone()),
+                            branch: current_git_branch(),
+                            ..Default::default()
+                        };
+                        execute_approved_config_hook(
+                            "on_fail",
+                            on_fail_template,
+                            &vars,
+                            project_root,
+                            err,
+                        );
                     }
-
-                    continue;
                 }
 
-                // Record success in history
-                bean.history.push(RunRecord {
-                    attempt: bean.attempts + 1,
-                    started_at,
-                    finished_at: Some(finished_at),
-                    duration_secs: Some(duration_secs),
-                    agent,
-                    result: RunResult::Pass,
+                return Ok(CloseOutcome::VerifyFailed {
+                    attempts: bean.attempts,
                     exit_code: verify_result.exit_code,
-                    tokens: None,
-                    cost: None,
-                    output_snippet: None,
+                    timed_out: verify_result.timed_out,
+                    output: verify_result.output,
                 });
+            }
 
-                // Capture stdout as bean outputs
-                let stdout = &verify_result.stdout;
-                if !stdout.is_empty() {
-                    if stdout.len() > MAX_OUTPUT_BYTES {
-                        let end = truncate_to_char_boundary(stdout, MAX_OUTPUT_BYTES);
-                        let truncated = &stdout[..end];
-                        eprintln!(
-                            "Warning: verify stdout ({} bytes) exceeds 64KB, truncating",
-                            stdout.len()
-                        );
-                        bean.outputs = Some(serde_json::json!({
-                            "text": truncated,
-                            "truncated": true,
-                            "original_bytes": stdout.len()
-                        }));
-                    } else {
-                        match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
-                            Ok(json) => {
-                                bean.outputs = Some(json);
-                            }
-                            Err(_) => {
-                                bean.outputs = Some(serde_json::json!({
-                                    "text": stdout.trim()
-                                }));
-                            }
+            // Record success in history
+            bean.history.push(RunRecord {
+                attempt: bean.attempts + 1,
+                started_at,
+                finished_at: Some(finished_at),
+                duration_secs: Some(duration_secs),
+                agent,
+                result: RunResult::Pass,
+                exit_code: verify_result.exit_code,
+                tokens: None,
+                cost: None,
+                output_snippet: None,
+                reason: None,
+                cached,
+                tests,
+            });
+
+            // Capture stdout as bean outputs
+            let stdout = &verify_result.stdout;
+            if !stdout.is_empty() {
+                if stdout.len() > MAX_OUTPUT_BYTES {
+                    let end = truncate_to_char_boundary(stdout, MAX_OUTPUT_BYTES);
+                    let truncated = &stdout[..end];
+                    say!(
+                        err,
+                        "Warning: verify stdout ({} bytes) exceeds 64KB, truncating",
+                        stdout.len()
+                    );
+                    bean.outputs = Some(serde_json::json!({
+                        "text": truncated,
+                        "truncated": true,
+                        "original_bytes": stdout.len()
+                    }));
+                } else {
+                    match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
+                        Ok(json) => {
+                            bean.outputs = Some(json);
+                        }
+                        Err(_) => {
+                            bean.outputs = Some(serde_json::json!({
+                                "text": stdout.trim()
+                            }));
                         }
                     }
                 }
-
-                println!("Verify passed for bean {}", id);
             }
+
+            say!(out, "Verify passed for bean {}", id);
         }
+    }
 
-        // Handle worktree merge (after verify passes, before archiving)
-        //
-        // detect_worktree() uses the process-global CWD. If CWD was deleted or
-        // points to an unrelated directory (e.g. during parallel test execution),
-        // we gracefully skip worktree operations. We also validate that the
-        // detected worktree actually contains this project's root — this prevents
-        // acting on a foreign repository when CWD is polluted.
-        let worktree_info = worktree::detect_worktree().unwrap_or(None);
-        let worktree_info = worktree_info.filter(|wt_info| {
-            let canonical_root =
-                std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
-            canonical_root.starts_with(&wt_info.worktree_path)
-        });
-        if let Some(ref wt_info) = worktree_info {
-            // Commit any uncommitted changes
-            worktree::commit_worktree_changes(&format!("Close bean {}: {}", id, bean.title))?;
-
-            // Merge to main
-            match worktree::merge_to_main(wt_info, id)? {
-                worktree::MergeResult::Success | worktree::MergeResult::NothingToCommit => {
-                    // Continue to archive
-                }
-                worktree::MergeResult::Conflict { files } => {
-                    eprintln!("Merge conflict in files: {:?}", files);
-                    eprintln!("Resolve conflicts and run `bn close {}` again", id);
-                    return Ok(()); // Don't archive yet
-                }
+    // Handle worktree merge (after verify passes, before archiving)
+    //
+    // detect_worktree() uses the process-global CWD. If CWD was deleted or
+    // points to an unrelated directory (e.g. during parallel test execution),
+    // we gracefully skip worktree operations. We also validate that the
+    // detected worktree actually contains this project's root — this prevents
+    // acting on a foreign repository when CWD is polluted.
+    let worktree_info = worktree::detect_worktree().unwrap_or(None);
+    let worktree_info = worktree_info.filter(|wt_info| {
+        let canonical_root =
+            std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
+        canonical_root.starts_with(&wt_info.worktree_path)
+    });
+    if let Some(ref wt_info) = worktree_info {
+        // Commit any uncommitted changes
+        worktree::commit_worktree_changes(&format!("Close bean {}: {}", id, bean.title))?;
+
+        // Merge to main
+        match worktree::merge_to_main(wt_info, id)? {
+            worktree::MergeResult::Success | worktree::MergeResult::NothingToCommit => {
+                // Continue to archive
+            }
+            worktree::MergeResult::Conflict { files } => {
+                say!(err, "Resolve conflicts and run `bn close {}` again", id);
+                return Ok(CloseOutcome::MergeConflict { files }); // Don't archive yet
             }
         }
+    }
 
-        // Feature beans require human confirmation via TTY
-        if bean.feature {
-            use std::io::IsTerminal;
-            if !std::io::stdin().is_terminal() {
-                println!(
-                    "Feature \"{}\" requires human review to close.",
-                    bean.title
-                );
-                continue; // Skip this bean, exit 0 (not an error)
-            }
-            // TTY available — ask for confirmation
-            eprintln!(
-                "Feature: \"{}\" — mark as complete? [y/N] ",
+    // Feature beans require human confirmation via TTY
+    if bean.feature {
+        use std::io::IsTerminal;
+        if !interactive || !std::io::stdin().is_terminal() {
+            say!(
+                out,
+                "Feature \"{}\" requires human review to close.",
                 bean.title
             );
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).unwrap_or(0);
-            if !input.trim().eq_ignore_ascii_case("y") {
-                println!("Skipped feature \"{}\"", bean.title);
-                continue;
-            }
+            return Ok(CloseOutcome::NeedsReview); // Skip this bean, exit 0 (not an error)
         }
-
-        // Close the bean
-        bean.status = crate::bean::Status::Closed;
-        bean.closed_at = Some(now);
-        bean.close_reason = reason.clone();
-        bean.updated_at = now;
-
-        // Finalize the current attempt as success (memory system tracking)
-        if let Some(attempt) = bean.attempt_log.last_mut() {
-            if attempt.finished_at.is_none() {
-                attempt.outcome = crate::bean::AttemptOutcome::Success;
-                attempt.finished_at = Some(now);
-                attempt.notes = reason.clone();
-            }
+        // TTY available — ask for confirmation
+        say!(
+            err,
+            "Feature: \"{}\" — mark as complete? [y/N] ",
+            bean.title
+        );
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input).unwrap_or(0);
+        if !input.trim().eq_ignore_ascii_case("y") {
+            say!(out, "Skipped feature \"{}\"", bean.title);
+            return Ok(CloseOutcome::NeedsReview);
         }
+    }
 
-        // Update last_verified for facts (staleness tracking)
-        if bean.bean_type == "fact" {
-            bean.last_verified = Some(now);
-        }
+    // Close the bean
+    bean.status = crate::bean::Status::Closed;
+    bean.closed_at = Some(now);
+    bean.close_reason = reason.clone();
+    bean.updated_at = now;
 
-        bean.to_file(&bean_path)
-            .with_context(|| format!("Failed to save bean: {}", id))?;
-
-        // Archive the closed bean
-        let slug = bean
-            .slug
-            .clone()
-            .unwrap_or_else(|| title_to_slug(&bean.title));
-        let ext = bean_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("md");
-        let today = chrono::Local::now().naive_local().date();
-        let archive_path = archive_path_for_bean(beans_dir, id, &slug, ext, today);
-
-        // Create archive directories if needed
-        if let Some(parent) = archive_path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create archive directories for bean {}", id))?;
+    // Finalize the current attempt as success (memory system tracking)
+    if let Some(attempt) = bean.attempt_log.last_mut() {
+        if attempt.finished_at.is_none() {
+            attempt.outcome = crate::bean::AttemptOutcome::Success;
+            attempt.finished_at = Some(now);
+            attempt.notes = reason.clone();
         }
+    }
 
-        // Move the bean file to archive
-        std::fs::rename(&bean_path, &archive_path)
-            .with_context(|| format!("Failed to move bean {} to archive", id))?;
+    // Update last_verified for facts (staleness tracking)
+    if bean.bean_type == "fact" {
+        bean.last_verified = Some(now);
+    }
 
-        // Update bean metadata to mark as archived
-        bean.is_archived = true;
-        bean.to_file(&archive_path)
-            .with_context(|| format!("Failed to save archived bean: {}", id))?;
+    save_bean(beans_dir, &mut bean, &mut base, &bean_path, err)?;
 
-        // Append to archive index
-        {
-            let mut archive_index =
-                ArchiveIndex::load(beans_dir).unwrap_or(ArchiveIndex { beans: Vec::new() });
-            archive_index.append(IndexEntry::from(&bean));
-            let _ = archive_index.save(beans_dir);
-        }
+    // Archive the closed bean
+    let slug = bean
+        .slug
+        .clone()
+        .unwrap_or_else(|| title_to_slug(&bean.title));
+    let ext = bean_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("md");
+    let today = chrono::Local::now().naive_local().date();
+    let archive_path = archive_path_for_bean(beans_dir, id, &slug, ext, today);
 
-        println!("Closed bean {}: {}", id, bean.title);
-        any_closed = true;
+    // Create archive directories if needed
+    if let Some(parent) = archive_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create archive directories for bean {}", id))?;
+    }
 
-        // Fire post-close hook (failure warns but does NOT revert the close)
-        match execute_hook(HookEvent::PostClose, &bean, project_root, reason.clone()) {
-            Ok(false) => {
-                eprintln!("Warning: post-close hook returned non-zero for bean {}", id);
-            }
-            Err(e) => {
-                eprintln!("Warning: post-close hook error for bean {}: {}", id, e);
-            }
-            Ok(true) => {}
+    // Move the bean file to archive
+    std::fs::rename(&bean_path, &archive_path)
+        .with_context(|| format!("Failed to move bean {} to archive", id))?;
+
+    // Update bean metadata to mark as archived
+    bean.is_archived = true;
+    bean.to_file(&archive_path)
+        .with_context(|| format!("Failed to save archived bean: {}", id))?;
+    let op = Operation::new();
+    op.record(beans_dir, Action::Close, id, Some(&before), Some(&bean));
+
+    // Append to archive index
+    {
+        let mut archive_index =
+            ArchiveIndex::load(beans_dir).unwrap_or(ArchiveIndex { beans: Vec::new() });
+        archive_index.append(IndexEntry::from(&bean));
+        let _ = archive_index.save(beans_dir);
+    }
+
+    say!(out, "Closed bean {}: {}", id, bean.title);
+
+    // Fire post-close hook (failure warns but does NOT revert the close)
+    match execute_hook(HookEvent::PostClose, &bean, project_root, reason.clone()) {
+        Ok(false) => {
+            say!(
+                err,
+                "Warning: post-close hook returned non-zero for bean {}",
+                id
+            );
         }
+        Err(e) => {
+            say!(err, "Warning: post-close hook error for bean {}: {}", id, e);
+        }
+        Ok(true) => {}
+    }
 
-        // Process on_close actions (after post-close hook)
-        for action in &bean.on_close {
-            match action {
-                OnCloseAction::Run { command } => {
-                    if !is_trusted(project_root) {
-                        eprintln!(
-                            "on_close: skipping `{}` (not trusted — run `bn trust` to enable)",
-                            command
-                        );
-                        continue;
-                    }
-                    eprintln!("on_close: running `{}`", command);
-                    let status = std::process::Command::new("sh")
-                        .args(["-c", command.as_str()])
-                        .current_dir(project_root)
-                        .status();
-                    match status {
-                        Ok(s) if !s.success() => {
-                            eprintln!("on_close run command failed: {}", command)
+    // Process on_close actions (after post-close hook)
+    let run_allowed = check_bean_on_close(project_root, &bean);
+    for action in &bean.on_close {
+        match action {
+            OnCloseAction::Run { command } => {
+                if let Err(ref reason) = run_allowed {
+                    say!(err, "on_close: skipping `{}` ({})", command, reason);
+                    continue;
+                }
+                say!(err, "on_close: running `{}`", command);
+                // Captured so the command can't write into stdout, which may
+                // be the MCP protocol stream
+                match crate::sandbox::shell(project_root, command).output() {
+                    Ok(output) => {
+                        let _ = err.write_all(&output.stdout);
+                        let _ = err.write_all(&output.stderr);
+                        if !output.status.success() {
+                            say!(err, "on_close run command failed: {}", command);
                         }
-                        Err(e) => eprintln!("on_close run command error: {}", e),
-                        _ => {}
                     }
-                }
-                OnCloseAction::Notify { message } => {
-                    println!("[bean {}] {}", id, message);
+                    Err(e) => {
+                        say!(err, "on_close run command error: {}", e);
+                    }
                 }
             }
-        }
-
-        // Fire on_close config hook (async, non-blocking)
-        if let Some(ref config) = config {
-            if let Some(ref on_close_template) = config.on_close {
-                let vars = HookVars {
-                    id: Some(id.clone()),
-                    title: Some(bean.title.clone()),
-                    status: Some("closed".into()),
-                    branch: current_git_branch(),
-                    ..Default::default()
-                };
-                execute_config_hook("on_close", on_close_template, &vars, project_root);
+            OnCloseAction::Notify { message } => {
+                say!(out, "[bean {}] {}", id, message);
             }
         }
+    }
 
-        // Clean up worktree after successful close
-        if let Some(ref wt_info) = worktree_info {
-            if let Err(e) = worktree::cleanup_worktree(wt_info) {
-                eprintln!("Warning: failed to clean up worktree: {}", e);
-            }
+    // Fire on_close config hook (async, non-blocking)
+    if let Some(ref config) = config {
+        if let Some(ref on_close_template) = config.on_close {
+            let vars = HookVars {
+                id: Some(id.to_string()),
+                title: Some(bean.title.clone()),
+                status: Some("closed".into()),
+                branch: current_git_branch(),
+                ..Default::default()
+            };
+            execute_approved_config_hook("on_close", on_close_template, &vars, project_root, err);
         }
+    }
 
-        // Check if parent should be auto-closed
-        // (skip if beans_dir was removed by worktree cleanup)
-        if beans_dir.exists() {
-            if let Some(parent_id) = &bean.parent {
-                // Check config for auto_close_parent setting
-                let auto_close_enabled =
-                    config.as_ref().map(|c| c.auto_close_parent).unwrap_or(true); // Default to true
-
-                if auto_close_enabled && all_children_closed(beans_dir, parent_id)? {
-                    auto_close_parent(beans_dir, parent_id)?;
+    // Clean up worktree after successful close
+    if let Some(ref wt_info) = worktree_info {
+        let main_beans_dir = worktree::main_beans_dir(wt_info, beans_dir);
+        if let Err(e) = worktree::carry_over_events(beans_dir, &main_beans_dir) {
+            say!(err, "Warning: failed to copy events to main: {}", e);
+        }
+        // The archived bean was written after the merge above; merge it too
+        let merged = worktree::commit_worktree_changes(&format!("Archive bean {}", id))
+            .and_then(|_| worktree::merge_to_main(wt_info, id));
+        match merged {
+            Ok(worktree::MergeResult::Conflict { files }) => {
+                say!(
+                    err,
+                    "Warning: archiving bean {} on main conflicts in {}; worktree kept at {}",
+                    id,
+                    files.join(", "),
+                    wt_info.worktree_path.display()
+                );
+            }
+            Err(e) => {
+                say!(
+                    err,
+                    "Warning: failed to merge archived bean {}: {}; worktree kept at {}",
+                    id,
+                    e,
+                    wt_info.worktree_path.display()
+                );
+            }
+            Ok(_) => {
+                if let Err(e) = worktree::cleanup_worktree(wt_info) {
+                    say!(err, "Warning: failed to clean up worktree: {}", e);
                 }
             }
         }
     }
 
-    // Report rejected beans
-    if !rejected_beans.is_empty() {
-        eprintln!(
-            "Failed to close {} bean(s) due to pre-close hook rejection: {}",
-            rejected_beans.len(),
-            rejected_beans.join(", ")
-        );
-    }
+    // Check if parent should be auto-closed
+    // (skip if beans_dir was removed by worktree cleanup)
+    if beans_dir.exists() {
+        if let Some(parent_id) = &bean.parent {
+            // Check config for auto_close_parent setting
+            let auto_close_enabled = config.as_ref().map(|c| c.auto_close_parent).unwrap_or(true); // Default to true
 
-    // Rebuild index once after all updates (even if some failed verification)
-    // Skip if beans_dir was removed by worktree cleanup
-    if (any_closed || !ids.is_empty()) && beans_dir.exists() {
-        let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
-        index
-            .save(beans_dir)
-            .with_context(|| "Failed to save index")?;
+            if auto_close_enabled && all_children_closed(beans_dir, parent_id)? {
+                auto_close_parent(beans_dir, parent_id, &op, out)?;
+            }
+        }
     }
 
-    Ok(())
+    Ok(CloseOutcome::Closed {
+        bean: Box::new(bean),
+    })
 }
 
 /// Mark an attempt as explicitly failed.
@@ -733,70 +976,8 @@ pub fn cmd_close_failed(beans_dir: &Path, ids: Vec<String>, reason: Option<Strin
         return Err(anyhow!("At least one bean ID is required"));
     }
 
-    let now = Utc::now();
-
     for id in &ids {
-        let bean_path =
-            find_bean_file(beans_dir, id).with_context(|| format!("Bean not found: {}", id))?;
-
-        let mut bean =
-            Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
-
-        // Finalize the current attempt as failed
-        if let Some(attempt) = bean.attempt_log.last_mut() {
-            if attempt.finished_at.is_none() {
-                attempt.outcome = crate::bean::AttemptOutcome::Failed;
-                attempt.finished_at = Some(now);
-                attempt.notes = reason.clone();
-            }
-        }
-
-        // Release the claim (bean stays open for retry)
-        bean.claimed_by = None;
-        bean.claimed_at = None;
-        bean.status = Status::Open;
-        bean.updated_at = now;
-
-        // Generate structured failure summary and append to notes.
-        // This is a lightweight summary (no tool logs or token data from the CLI
-        // path), but still captures duration, error, and suggestions for the
-        // next agent.
-        {
-            let attempt_num = bean.attempt_log.len() as u32;
-            let duration_secs = bean
-                .attempt_log
-                .last()
-                .and_then(|a| a.started_at)
-                .map(|started| (now - started).num_seconds().max(0) as u64)
-                .unwrap_or(0);
-
-            let ctx = failure::FailureContext {
-                bean_id: id.clone(),
-                bean_title: bean.title.clone(),
-                attempt: attempt_num.max(1),
-                duration_secs,
-                tool_count: 0,
-                turns: 0,
-                input_tokens: 0,
-                output_tokens: 0,
-                cost: 0.0,
-                error: reason.clone(),
-                tool_log: vec![],
-                verify_command: bean.verify.clone(),
-            };
-            let summary = failure::build_failure_summary(&ctx);
-
-            match &mut bean.notes {
-                Some(notes) => {
-                    notes.push('\n');
-                    notes.push_str(&summary);
-                }
-                None => bean.notes = Some(summary),
-            }
-        }
-
-        bean.to_file(&bean_path)
-            .with_context(|| format!("Failed to save bean: {}", id))?;
+        let bean = mark_failed(beans_dir, id, reason.clone())?;
 
         let attempt_count = bean.attempt_log.len();
         println!(
@@ -818,6 +999,97 @@ pub fn cmd_close_failed(beans_dir: &Path, ids: Vec<String>, reason: Option<Strin
     Ok(())
 }
 
+/// Record a failed attempt on one bean: finalize its open attempt, release
+/// the claim, and append a failure summary to its notes.
+///
+/// Returns the saved bean. Does not rebuild the index.
+pub fn mark_failed(beans_dir: &Path, id: &str, reason: Option<String>) -> Result<Bean> {
+    let now = Utc::now();
+
+    let bean_path =
+        find_bean_file(beans_dir, id).with_context(|| format!("Bean not found: {}", id))?;
+
+    let mut bean =
+        Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let mut base = bean.clone();
+    let before = bean.clone();
+
+    // Finalize the current attempt as failed
+    if let Some(attempt) = bean.attempt_log.last_mut() {
+        if attempt.finished_at.is_none() {
+            attempt.outcome = crate::bean::AttemptOutcome::Failed;
+            attempt.finished_at = Some(now);
+            attempt.notes = reason.clone();
+        }
+    }
+
+    // Release the claim (bean stays open for retry)
+    bean.claimed_by = None;
+    bean.claimed_at = None;
+    bean.status = Status::Open;
+    bean.updated_at = now;
+
+    // Generate structured failure summary and append to notes.
+    // This is a lightweight summary (no tool logs or token data from the CLI
+    // path), but still captures duration, error, and suggestions for the
+    // next agent.
+    {
+        let attempt_num = bean.attempt_log.len() as u32;
+        let duration_secs = bean
+            .attempt_log
+            .last()
+            .and_then(|a| a.started_at)
+            .map(|started| (now - started).num_seconds().max(0) as u64)
+            .unwrap_or(0);
+
+        let ctx = failure::FailureContext {
+            bean_id: id.to_string(),
+            bean_title: bean.title.clone(),
+            attempt: attempt_num.max(1),
+            duration_secs,
+            tool_count: 0,
+            turns: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cost: 0.0,
+            error: reason.clone(),
+            tool_log: vec![],
+            verify_command: bean.verify.clone(),
+            tests: bean
+                .history
+                .last()
+                .filter(|r| r.result != RunResult::Pass)
+                .and_then(|r| r.tests.clone()),
+        };
+        let summary = failure::build_failure_summary(&ctx);
+
+        match &mut bean.notes {
+            Some(notes) => {
+                notes.push('\n');
+                notes.push_str(&summary);
+            }
+            None => bean.notes = Some(summary),
+        }
+    }
+
+    save_bean(
+        beans_dir,
+        &mut bean,
+        &mut base,
+        &bean_path,
+        &mut std::io::stderr(),
+    )?;
+    events::record_with_note(
+        beans_dir,
+        Action::Release,
+        id,
+        Some(&before),
+        Some(&bean),
+        Some("attempt failed".to_string()),
+    );
+    Ok(bean)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1153,6 +1425,22 @@ mod tests {
         assert_eq!(updated.attempts, 0); // No attempts recorded
     }
 
+    #[test]
+    fn close_bean_writes_warnings_to_err() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        let mut bean = Bean::new("1", "Task with empty verify");
+        bean.verify = Some("".to_string());
+        bean.to_file(beans_dir.join("1-task-with-empty-verify.md"))
+            .unwrap();
+
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        close_bean(&beans_dir, "1", None, false, false, &mut out, &mut err).unwrap();
+
+        let err = String::from_utf8(err).unwrap();
+        assert!(err.contains("has empty verify command"), "{}", err);
+        assert!(!String::from_utf8(out).unwrap().contains("has empty verify"));
+    }
+
     #[test]
     fn test_close_with_whitespace_verify_still_closes() {
         let (_dir, beans_dir) = setup_test_beans_dir();
@@ -1219,9 +1507,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hooks execute - pass project root, not .beans dir
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a pre-close hook that passes (exits 0)
         let hook_path = hooks_dir.join("pre-close");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
@@ -1237,6 +1522,9 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
+        // Enable trust so hooks execute - pass project root, not .beans dir
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Close should succeed
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -1254,9 +1542,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hooks execute - pass project root, not .beans dir
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a pre-close hook that fails (exits 1)
         let hook_path = hooks_dir.join("pre-close");
         fs::write(&hook_path, "#!/bin/bash\nexit 1").unwrap();
@@ -1272,6 +1557,9 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
+        // Enable trust so hooks execute - pass project root, not .beans dir
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Close should still succeed (returns Ok), but bean not closed
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -1290,9 +1578,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hooks execute - pass project root, not .beans dir
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a pre-close hook that passes
         let hook_path = hooks_dir.join("pre-close");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
@@ -1320,6 +1605,9 @@ mod tests {
             .to_file(beans_dir.join(format!("3-{}.md", slug3)))
             .unwrap();
 
+        // Enable trust so hooks execute - pass project root, not .beans dir
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Close all three (hook passes for all)
         cmd_close(
             &beans_dir,
@@ -1401,9 +1689,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust - pass project root, not .beans dir
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a simple passing hook
         let hook_path = hooks_dir.join("pre-close");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
@@ -1419,6 +1704,9 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
+        // Enable trust - pass project root, not .beans dir
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Close with a reason
         cmd_close(
             &beans_dir,
@@ -1442,9 +1730,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust - pass project root, not .beans dir
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a hook that checks bean ID - reject ID 2
         // Use dd with timeout to consume stdin and check content
         let hook_path = hooks_dir.join("pre-close");
@@ -1473,6 +1758,9 @@ mod tests {
             .to_file(beans_dir.join(format!("3-{}.md", slug3)))
             .unwrap();
 
+        // Enable trust - pass project root, not .beans dir
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Try to close all three
         cmd_close(
             &beans_dir,
@@ -1512,9 +1800,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a post-close hook that writes a marker file
         let marker = project_root.join("post-close-fired");
         let hook_path = hooks_dir.join("post-close");
@@ -1535,6 +1820,9 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
+        // Enable trust
+        crate::hooks::create_trust(project_root).unwrap();
+
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
         // Marker file should exist, proving the post-close hook fired
@@ -1554,9 +1842,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust
-        crate::hooks::create_trust(project_root).unwrap();
-
         // Create a post-close hook that FAILS (exits 1)
         let hook_path = hooks_dir.join("post-close");
         fs::write(&hook_path, "#!/bin/bash\nexit 1").unwrap();
@@ -1572,6 +1857,9 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
+        // Enable trust
+        crate::hooks::create_trust(project_root).unwrap();
+
         // Close should succeed even though post-close hook fails
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -1612,6 +1900,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
@@ -1704,6 +2003,29 @@ mod tests {
         assert_eq!(parent_bean.status, Status::Open);
     }
 
+    #[test]
+    fn test_no_auto_close_when_a_child_is_cancelled() {
+        let (_dir, beans_dir) = setup_test_beans_dir_with_config();
+
+        Bean::new("1", "Parent Task")
+            .to_file(beans_dir.join("1-parent-task.md"))
+            .unwrap();
+        let mut child1 = Bean::new("1.1", "Child 1");
+        child1.parent = Some("1".to_string());
+        child1.to_file(beans_dir.join("1.1-child-1.md")).unwrap();
+        let mut child2 = Bean::new("1.2", "Child 2");
+        child2.parent = Some("1".to_string());
+        child2.status = Status::Cancelled;
+        child2.to_file(beans_dir.join("1.2-child-2.md")).unwrap();
+
+        cmd_close(&beans_dir, vec!["1.1".to_string()], None, false).unwrap();
+
+        // A cancelled child keeps the parent open, like a cancelled dependency
+        let parent_path = crate::discovery::find_bean_file(&beans_dir, "1").unwrap();
+        let parent_bean = Bean::from_file(parent_path).unwrap();
+        assert_eq!(parent_bean.status, Status::Open);
+    }
+
     #[test]
     fn test_auto_close_disabled_via_config() {
         let dir = TempDir::new().unwrap();
@@ -1731,6 +2053,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
@@ -2090,7 +2423,6 @@ mod tests {
     fn on_close_run_action_executes_command() {
         let (dir, beans_dir) = setup_test_beans_dir();
         let project_root = dir.path();
-        crate::hooks::create_trust(project_root).unwrap();
         let marker = project_root.join("on_close_ran");
 
         let mut bean = Bean::new("1", "Task with on_close run");
@@ -2100,6 +2432,8 @@ mod tests {
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
+        // Approves the bean's on_close commands
+        crate::hooks::create_trust(project_root).unwrap();
 
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -2154,7 +2488,6 @@ mod tests {
     fn on_close_multiple_actions_all_run() {
         let (dir, beans_dir) = setup_test_beans_dir();
         let project_root = dir.path();
-        crate::hooks::create_trust(project_root).unwrap();
         let marker1 = project_root.join("on_close_1");
         let marker2 = project_root.join("on_close_2");
 
@@ -2173,6 +2506,8 @@ mod tests {
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
+        // Approves the bean's on_close commands
+        crate::hooks::create_trust(project_root).unwrap();
 
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -2211,11 +2546,57 @@ mod tests {
     }
 
     #[test]
-    fn on_close_runs_in_project_root() {
+    fn on_close_run_skipped_when_changed_since_trust() {
+        let (dir, beans_dir) = setup_test_beans_dir();
+        let project_root = dir.path();
+        let marker = project_root.join("on_close_should_not_exist");
+
+        let mut bean = Bean::new("1", "Task with edited on_close");
+        bean.on_close = vec![OnCloseAction::Run {
+            command: "true".to_string(),
+        }];
+        let path = beans_dir.join(format!("1-{}.md", title_to_slug(&bean.title)));
+        bean.to_file(&path).unwrap();
+        crate::hooks::create_trust(project_root).unwrap();
+
+        // A later (pulled) change to the command is not approved
+        bean.on_close = vec![OnCloseAction::Run {
+            command: format!("touch {}", marker.display()),
+        }];
+        bean.to_file(&path).unwrap();
+
+        cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
+        assert!(!marker.exists(), "changed on_close run must not execute");
+        assert!(crate::discovery::find_archived_bean(&beans_dir, "1").is_ok());
+    }
+
+    #[test]
+    fn verify_refused_when_changed_since_trust() {
         let (dir, beans_dir) = setup_test_beans_dir();
         let project_root = dir.path();
+        let marker = project_root.join("verify_should_not_run");
+
+        let mut bean = Bean::new("1", "Task with edited verify");
+        bean.verify = Some("true".to_string());
+        let path = beans_dir.join(format!("1-{}.md", title_to_slug(&bean.title)));
+        bean.to_file(&path).unwrap();
         crate::hooks::create_trust(project_root).unwrap();
 
+        bean.verify = Some(format!("touch {}", marker.display()));
+        bean.to_file(&path).unwrap();
+
+        let err = cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap_err();
+        assert!(err.to_string().contains("not approved"), "{}", err);
+        assert!(!marker.exists(), "unapproved verify must not execute");
+        let bean = Bean::from_file(&path).unwrap();
+        assert_eq!(bean.status, Status::Open);
+    }
+
+    #[test]
+    fn on_close_runs_in_project_root() {
+        let (dir, beans_dir) = setup_test_beans_dir();
+        let project_root = dir.path();
+
         let mut bean = Bean::new("1", "Task with pwd check");
         // Write the working directory to a file so we can verify it
         let pwd_file = project_root.join("on_close_pwd");
@@ -2225,6 +2606,8 @@ mod tests {
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
+        // Approves the bean's on_close commands
+        crate::hooks::create_trust(project_root).unwrap();
 
         cmd_close(&beans_dir, vec!["1".to_string()], None, false).unwrap();
 
@@ -2890,6 +3273,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
@@ -3174,6 +3568,8 @@ mod tests {
             agent: Some("agent-1".to_string()),
             started_at: Some(Utc::now()),
             finished_at: None,
+            verify: None,
+            base: None,
         });
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
@@ -3214,6 +3610,8 @@ mod tests {
             agent: None,
             started_at: Some(Utc::now()),
             finished_at: None,
+            verify: None,
+            base: None,
         });
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
@@ -3244,6 +3642,8 @@ mod tests {
             agent: None,
             started_at: Some(Utc::now()),
             finished_at: None,
+            verify: None,
+            base: None,
         });
         let slug = title_to_slug(&bean.title);
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
@@ -3404,7 +3804,12 @@ mod tests {
             std::env::set_current_dir(worktree_dir).unwrap();
 
             // Close should detect conflict, abort merge, and leave bean open
-            cmd_close(&worktree_beans_dir, vec!["1".to_string()], None, false).unwrap();
+            let err =
+                cmd_close(&worktree_beans_dir, vec!["1".to_string()], None, false).unwrap_err();
+            assert!(matches!(
+                crate::error::find(&err),
+                Some(BeansError::Conflict { .. })
+            ));
 
             // Bean should NOT be closed — merge conflict prevents archiving
             let bean_file = crate::discovery::find_bean_file(&worktree_beans_dir, "1").unwrap();
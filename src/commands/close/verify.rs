@@ -1,11 +1,15 @@
 use std::io::Read;
 use std::path::Path;
-use std::process::{Command as ShellCommand, Stdio};
+use std::process::Stdio;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 
+use crate::bean::Bean;
+use crate::sandbox;
+use crate::verify_cache;
+
 /// Result of running a verify command
 pub(super) struct VerifyResult {
     pub(super) success: bool,
@@ -33,11 +37,7 @@ pub(super) fn run_verify(
         .parent()
         .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
 
-    println!("Running verify: {}", verify_cmd);
-
-    let mut child = ShellCommand::new("sh")
-        .args(["-c", verify_cmd])
-        .current_dir(project_root)
+    let mut child = sandbox::shell(project_root, verify_cmd)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -123,6 +123,57 @@ pub(super) fn run_verify(
     })
 }
 
+/// Run a bean's verify command, answering from the verify cache when its
+/// inputs haven't changed since a previous run.
+///
+/// Returns the result and whether it came from the cache. Timeouts are
+/// never cached.
+pub(super) fn run_verify_cached(
+    beans_dir: &Path,
+    bean: &Bean,
+    verify_cmd: &str,
+    timeout_secs: Option<u64>,
+) -> Result<(VerifyResult, bool)> {
+    let key = verify_cache::key(beans_dir, &bean.paths, verify_cmd);
+    if let Some(entry) = key
+        .as_ref()
+        .and_then(|k| verify_cache::lookup(beans_dir, k))
+    {
+        let stdout = entry.stdout.trim().to_string();
+        let stderr = entry.stderr.trim().to_string();
+        let output = [stdout.as_str(), stderr.as_str()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = VerifyResult {
+            success: entry.passed(),
+            exit_code: entry.record.exit_code,
+            stdout,
+            stderr,
+            output,
+            timed_out: false,
+        };
+        return Ok((result, true));
+    }
+
+    let started_at = Utc::now();
+    let result = run_verify(beans_dir, verify_cmd, timeout_secs)?;
+    if let (Some(key), false) = (key, result.timed_out) {
+        let entry = verify_cache::Entry::new(
+            verify_cmd,
+            result.success,
+            result.exit_code,
+            started_at,
+            &result.stdout,
+            &result.stderr,
+        );
+        verify_cache::store(beans_dir, &key, entry);
+    }
+    Ok((result, false))
+}
+
 /// Truncate output to first N + last N lines.
 /// If output has fewer than 2*N lines, return it unchanged.
 pub(super) fn truncate_output(output: &str, max_lines: usize) -> String {
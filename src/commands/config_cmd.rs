@@ -2,7 +2,9 @@ use std::path::Path;
 
 use anyhow::{anyhow, Result};
 
+use crate::agent_presets::{all_presets, get_preset};
 use crate::config::{Config, GlobalConfig};
+use crate::filter::Filter;
 
 /// Get a configuration value by key
 pub fn cmd_config_get(beans_dir: &Path, key: &str) -> Result<()> {
@@ -14,12 +16,19 @@ pub fn cmd_config_get(beans_dir: &Path, key: &str) -> Result<()> {
         "auto_close_parent" => config.auto_close_parent.to_string(),
         "run" => config.run.unwrap_or_default(),
         "plan" => config.plan.unwrap_or_default(),
+        "agent" => config.agent.unwrap_or_default(),
         "max_concurrent" => config.max_concurrent.to_string(),
         "poll_interval" => config.poll_interval.to_string(),
         "rules_file" => config.rules_file.unwrap_or_else(|| "RULES.md".to_string()),
         "on_close" => config.on_close.unwrap_or_default(),
         "on_fail" => config.on_fail.unwrap_or_default(),
         "post_plan" => config.post_plan.unwrap_or_default(),
+        "max_cost_per_bean" => opt_to_string(config.max_cost_per_bean),
+        "max_cost_per_run" => opt_to_string(config.max_cost_per_run),
+        "max_cost_per_day" => opt_to_string(config.max_cost_per_day),
+        "max_tokens_per_bean" => opt_to_string(config.max_tokens_per_bean),
+        "max_tokens_per_run" => opt_to_string(config.max_tokens_per_run),
+        "max_tokens_per_day" => opt_to_string(config.max_tokens_per_day),
         "user" => {
             if let Some(user) = config.user {
                 user
@@ -38,7 +47,10 @@ pub fn cmd_config_get(beans_dir: &Path, key: &str) -> Result<()> {
                 String::new()
             }
         }
-        _ => return Err(anyhow!("Unknown config key: {}", key)),
+        _ => match key.strip_prefix("view.") {
+            Some(name) => config.views.get(name).cloned().unwrap_or_default(),
+            None => return Err(anyhow!("Unknown config key: {}", key)),
+        },
     };
 
     println!("{}", value);
@@ -80,6 +92,20 @@ pub fn cmd_config_set(beans_dir: &Path, key: &str, value: &str) -> Result<()> {
                 config.plan = Some(value.to_string());
             }
         }
+        "agent" => {
+            if is_unset(value) {
+                config.agent = None;
+            } else if let Some(preset) = get_preset(value) {
+                config.agent = Some(preset.name.to_string());
+            } else {
+                let known: Vec<&str> = all_presets().iter().map(|p| p.name).collect();
+                return Err(anyhow!(
+                    "Unknown agent: {} (expected one of: {})",
+                    value,
+                    known.join(", ")
+                ));
+            }
+        }
         "max_concurrent" => {
             config.max_concurrent = value.parse().map_err(|_| {
                 anyhow!(
@@ -124,6 +150,12 @@ pub fn cmd_config_set(beans_dir: &Path, key: &str, value: &str) -> Result<()> {
                 config.post_plan = Some(value.to_string());
             }
         }
+        "max_cost_per_bean" => config.max_cost_per_bean = parse_cost_limit(key, value)?,
+        "max_cost_per_run" => config.max_cost_per_run = parse_cost_limit(key, value)?,
+        "max_cost_per_day" => config.max_cost_per_day = parse_cost_limit(key, value)?,
+        "max_tokens_per_bean" => config.max_tokens_per_bean = parse_token_limit(key, value)?,
+        "max_tokens_per_run" => config.max_tokens_per_run = parse_token_limit(key, value)?,
+        "max_tokens_per_day" => config.max_tokens_per_day = parse_token_limit(key, value)?,
         "user" => {
             if value.is_empty() || value == "none" || value == "unset" {
                 config.user = None;
@@ -138,7 +170,21 @@ pub fn cmd_config_set(beans_dir: &Path, key: &str, value: &str) -> Result<()> {
                 config.user_email = Some(value.to_string());
             }
         }
-        _ => return Err(anyhow!("Unknown config key: {}", key)),
+        _ => match key.strip_prefix("view.") {
+            Some(name) if is_unset(value) => {
+                config.views.remove(name);
+            }
+            Some(name) => {
+                if name.is_empty() {
+                    return Err(anyhow!("View name cannot be empty: use view.<name>"));
+                }
+                let mut views = config.views.clone();
+                views.insert(name.to_string(), value.to_string());
+                Filter::parse(value, &views)?;
+                config.views = views;
+            }
+            None => return Err(anyhow!("Unknown config key: {}", key)),
+        },
     }
 
     config.save(beans_dir)?;
@@ -146,6 +192,44 @@ pub fn cmd_config_set(beans_dir: &Path, key: &str, value: &str) -> Result<()> {
     Ok(())
 }
 
+fn opt_to_string<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn is_unset(value: &str) -> bool {
+    value.is_empty() || value == "none" || value == "unset"
+}
+
+/// Parse a USD budget; `none`/`unset` removes the limit.
+fn parse_cost_limit(key: &str, value: &str) -> Result<Option<f64>> {
+    if is_unset(value) {
+        return Ok(None);
+    }
+    match value.trim_start_matches('$').parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(Some(v)),
+        _ => Err(anyhow!(
+            "Invalid value for {}: {} (expected a positive amount in USD, or none)",
+            key,
+            value
+        )),
+    }
+}
+
+/// Parse a token budget; `none`/`unset` removes the limit.
+fn parse_token_limit(key: &str, value: &str) -> Result<Option<u64>> {
+    if is_unset(value) {
+        return Ok(None);
+    }
+    match value.parse::<u64>() {
+        Ok(v) if v > 0 => Ok(Some(v)),
+        _ => Err(anyhow!(
+            "Invalid value for {}: {} (expected positive integer, or none)",
+            key,
+            value
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,4 +305,59 @@ mod tests {
         let config = Config::load(dir.path()).unwrap();
         assert_eq!(config.run, None);
     }
+
+    #[test]
+    fn set_budget_limits() {
+        let dir = setup_test_dir();
+        cmd_config_set(dir.path(), "max_cost_per_run", "$5").unwrap();
+        cmd_config_set(dir.path(), "max_tokens_per_bean", "200000").unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.max_cost_per_run, Some(5.0));
+        assert_eq!(config.max_tokens_per_bean, Some(200_000));
+
+        cmd_config_set(dir.path(), "max_cost_per_run", "none").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.max_cost_per_run, None);
+    }
+
+    #[test]
+    fn set_budget_rejects_invalid_values() {
+        let dir = setup_test_dir();
+        assert!(cmd_config_set(dir.path(), "max_cost_per_day", "lots").is_err());
+        assert!(cmd_config_set(dir.path(), "max_cost_per_day", "-1").is_err());
+        assert!(cmd_config_set(dir.path(), "max_tokens_per_run", "0").is_err());
+    }
+
+    #[test]
+    fn set_agent_validates_preset_name() {
+        let dir = setup_test_dir();
+        cmd_config_set(dir.path(), "agent", "claude").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.agent.as_deref(), Some("claude"));
+
+        let err = cmd_config_set(dir.path(), "agent", "gpt-shell").unwrap_err();
+        assert!(err.to_string().contains("pi, claude, aider, codex"));
+
+        cmd_config_set(dir.path(), "agent", "none").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.agent, None);
+    }
+
+    #[test]
+    fn set_view_validates_filter() {
+        let dir = setup_test_dir();
+        cmd_config_set(dir.path(), "view.backend", "label:backend").unwrap();
+        cmd_config_set(dir.path(), "view.urgent", "@backend and priority<=1").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.views["urgent"], "@backend and priority<=1");
+
+        assert!(cmd_config_set(dir.path(), "view.bad", "colour:red").is_err());
+        assert!(cmd_config_set(dir.path(), "view.self", "@self").is_err());
+        assert!(cmd_config_get(dir.path(), "view.backend").is_ok());
+
+        cmd_config_set(dir.path(), "view.urgent", "none").unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert!(!config.views.contains_key("urgent"));
+    }
 }
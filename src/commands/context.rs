@@ -468,6 +468,7 @@ pub fn cmd_context(
             beans_dir: beans_dir.to_path_buf(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
         let result = build_agent_prompt(&bean, &options)?;
         println!("{}", result.system_prompt);
@@ -761,6 +762,8 @@ mod tests {
                 agent: Some("pi-agent".to_string()),
                 started_at: None,
                 finished_at: None,
+                verify: None,
+                base: None,
             },
             AttemptRecord {
                 num: 2,
@@ -769,6 +772,8 @@ mod tests {
                 agent: None,
                 started_at: None,
                 finished_at: None,
+                verify: None,
+                base: None,
             },
         ];
         bean
@@ -793,6 +798,8 @@ mod tests {
             agent: None,
             started_at: None,
             finished_at: None,
+            verify: None,
+            base: None,
         }];
         let result = format_attempt_notes_section(&bean);
         assert!(result.is_none());
@@ -841,6 +848,8 @@ mod tests {
             agent: None,
             started_at: None,
             finished_at: None,
+            verify: None,
+            base: None,
         }];
         let result = format_attempt_notes_section(&bean);
         assert!(
@@ -1,15 +1,18 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command as ShellCommand;
 
 use anyhow::{anyhow, Context, Result};
 
 use crate::bean::{validate_priority, Bean, OnFailAction};
-use crate::commands::claim::cmd_claim;
+use crate::commands::claim::claim_bean;
 use crate::config::Config;
-use crate::hooks::{execute_hook, HookEvent};
+use crate::error::BeansError;
+use crate::events::{self, Action};
+use crate::hooks::{self, execute_hook, HookEvent};
 use crate::index::Index;
 use crate::project::suggest_verify_command;
+use crate::sandbox;
 use crate::util::title_to_slug;
 
 /// Create arguments structure for organizing all the parameters passed to create.
@@ -151,6 +154,25 @@ pub fn parse_on_fail(s: &str) -> Result<OnFailAction> {
 /// Otherwise, use the next sequential ID from config and increment it.
 /// Returns the created bean ID on success.
 pub fn cmd_create(beans_dir: &Path, args: CreateArgs) -> Result<String> {
+    let claim = args.claim;
+    let bean = create_bean(beans_dir, args, &mut std::io::stderr())?;
+    // Commands typed at the terminal are approved as the user wrote them;
+    // beans created over REST, MCP or the library API wait for `bn trust`
+    if let Some(project_dir) = beans_dir.parent() {
+        hooks::trust_bean_commands(project_dir, &bean);
+    }
+    if claim {
+        let claimer = bean.claimed_by.as_deref().unwrap_or("anonymous");
+        println!("Claimed bean {}: {} (by {})", bean.id, bean.title, claimer);
+    }
+    Ok(bean.id)
+}
+
+/// Create a bean without printing to stdout; see [`cmd_create`].
+///
+/// Progress, tips and warnings go to `err` (stderr for the CLI). Returns the
+/// created bean (claimed, if `args.claim` is set).
+pub fn create_bean(beans_dir: &Path, args: CreateArgs, err: &mut dyn Write) -> Result<Bean> {
     // Validate priority if provided
     if let Some(priority) = args.priority {
         validate_priority(priority)?;
@@ -159,10 +181,12 @@ pub fn cmd_create(beans_dir: &Path, args: CreateArgs) -> Result<String> {
     // When --claim is used without --parent, require validation criteria
     // (same as bn quick). Parent/goal beans (no --claim) remain exempt.
     if args.claim && args.parent.is_none() && args.acceptance.is_none() && args.verify.is_none() {
-        anyhow::bail!(
+        return Err(BeansError::Invalid(
             "Bean must have validation criteria: provide --acceptance or --verify (or both)\n\
              Hint: parent/goal beans (without --claim) don't require this."
-        );
+                .to_string(),
+        )
+        .into());
     }
 
     // Fail-first check (default): verify command must FAIL before bean can be created
@@ -174,27 +198,25 @@ pub fn cmd_create(beans_dir: &Path, args: CreateArgs) -> Result<String> {
                 .parent()
                 .ok_or_else(|| anyhow!("Cannot determine project root"))?;
 
-            eprintln!("Running verify (must fail): {}", verify_cmd);
+            let _ = writeln!(err, "Running verify (must fail): {}", verify_cmd);
 
-            let status = ShellCommand::new("sh")
-                .args(["-c", verify_cmd])
-                .current_dir(project_root)
-                .status()
+            // Send the command's output to `err`: stdout may be the MCP
+            // protocol stream when this runs inside `bn mcp serve`.
+            let output = sandbox::shell(project_root, verify_cmd)
+                .output()
                 .with_context(|| format!("Failed to execute verify command: {}", verify_cmd))?;
+            let _ = err.write_all(&output.stdout);
+            let _ = err.write_all(&output.stderr);
 
-            if status.success() {
-                anyhow::bail!(
-                    "Cannot create bean: verify command already passes!\n\n\
-                     The test must FAIL on current code to prove it tests something real.\n\
-                     Either:\n\
-                     - The test doesn't actually test the new behavior\n\
-                     - The feature is already implemented\n\
-                     - The test is a no-op (assert True)\n\n\
-                     Use --pass-ok / -p to skip this check."
-                );
+            if output.status.success() {
+                return Err(BeansError::VerifyAlreadyPasses {
+                    id: None,
+                    command: verify_cmd.clone(),
+                }
+                .into());
             }
 
-            eprintln!("✓ Verify failed as expected - test is real");
+            let _ = writeln!(err, "✓ Verify failed as expected - test is real");
         }
     }
 
@@ -307,23 +329,25 @@ pub fn cmd_create(beans_dir: &Path, args: CreateArgs) -> Result<String> {
         .context("Pre-create hook execution failed")?;
 
     if !pre_passed {
-        return Err(anyhow!("Pre-create hook rejected bean creation"));
+        return Err(BeansError::HookRejected { hook: "pre-create" }.into());
     }
 
     // Write the bean file with new naming convention: {id}-{slug}.md
     let bean_path = beans_dir.join(format!("{}-{}.md", bean_id, slug));
     bean.to_file(&bean_path)?;
+    events::record(beans_dir, Action::Create, &bean_id, None, Some(&bean));
 
     // Update the index by rebuilding from disk (includes the bean we just wrote)
     let index = Index::build(beans_dir)?;
     index.save(beans_dir)?;
 
-    eprintln!("Created bean {}: {}", bean_id, args.title);
+    let _ = writeln!(err, "Created bean {}: {}", bean_id, args.title);
 
     // Suggest verify command if none was provided
     if !has_verify {
         if let Some(suggested) = suggest_verify_command(project_dir) {
-            eprintln!(
+            let _ = writeln!(
+                err,
                 "Tip: Consider adding a verify command: --verify \"{}\"",
                 suggested
             );
@@ -332,15 +356,15 @@ pub fn cmd_create(beans_dir: &Path, args: CreateArgs) -> Result<String> {
 
     // Call post-create hook (non-blocking - log warning if it fails)
     if let Err(e) = execute_hook(HookEvent::PostCreate, &bean, project_dir, None) {
-        eprintln!("Warning: post-create hook failed: {}", e);
+        let _ = writeln!(err, "Warning: post-create hook failed: {}", e);
     }
 
     // If --claim was passed, claim the bean immediately (skip verify-on-claim check)
     if args.claim {
-        cmd_claim(beans_dir, &bean_id, args.by, true)?;
+        return claim_bean(beans_dir, &bean_id, args.by, true, err);
     }
 
-    Ok(bean_id)
+    Ok(bean)
 }
 
 /// Create a new bean that automatically depends on @latest (the most recently updated bean).
@@ -416,12 +440,58 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
         (dir, beans_dir)
     }
 
+    #[test]
+    fn only_the_cli_approves_typed_commands() {
+        let (dir, beans_dir) = setup_beans_dir_with_config();
+        crate::hooks::create_trust(dir.path()).unwrap();
+        let args = |title: &str| CreateArgs {
+            title: title.to_string(),
+            description: None,
+            acceptance: None,
+            notes: None,
+            design: None,
+            verify: Some("echo ok".to_string()),
+            priority: None,
+            labels: None,
+            assignee: None,
+            deps: None,
+            parent: None,
+            produces: None,
+            requires: None,
+            paths: None,
+            on_fail: None,
+            pass_ok: true,
+            feature: false,
+            claim: false,
+            by: None,
+            verify_timeout: None,
+        };
+
+        // REST, MCP and the library API go through create_bean
+        create_bean(&beans_dir, args("Remote"), &mut std::io::sink()).unwrap();
+        assert!(crate::hooks::check_bean_verify(dir.path(), "1", "echo ok").is_err());
+
+        cmd_create(&beans_dir, args("Typed")).unwrap();
+        assert!(crate::hooks::check_bean_verify(dir.path(), "2", "echo ok").is_ok());
+    }
+
     #[test]
     fn create_minimal_bean() {
         let (_dir, beans_dir) = setup_beans_dir_with_config();
@@ -885,15 +955,15 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust and create a pre-create hook that succeeds
-        crate::hooks::create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
 
         #[cfg(unix)]
         fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
 
+        // Enable trust now that the hook is written
+        crate::hooks::create_trust(project_dir).unwrap();
+
         let args = CreateArgs {
             title: "Bean with accepting hook".to_string(),
             description: None,
@@ -937,15 +1007,15 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust and create a pre-create hook that fails
-        crate::hooks::create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 1").unwrap();
 
         #[cfg(unix)]
         fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
 
+        // Enable trust now that the hook is written
+        crate::hooks::create_trust(project_dir).unwrap();
+
         let args = CreateArgs {
             title: "Bean with rejecting hook".to_string(),
             description: None,
@@ -999,9 +1069,6 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust and create a post-create hook that writes to a file
-        crate::hooks::create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("post-create");
         let marker_file = project_dir.join("hook-executed.txt");
         let marker_file_str = marker_file.to_string_lossy().to_string();
@@ -1016,6 +1083,9 @@ mod tests {
         #[cfg(unix)]
         fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
 
+        // Enable trust now that the hook is written
+        crate::hooks::create_trust(project_dir).unwrap();
+
         let args = CreateArgs {
             title: "Bean with post-create hook".to_string(),
             description: None,
@@ -1062,15 +1132,15 @@ mod tests {
         let hooks_dir = beans_dir.join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust and create a post-create hook that fails
-        crate::hooks::create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("post-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 1").unwrap();
 
         #[cfg(unix)]
         fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
 
+        // Enable trust now that the hook is written
+        crate::hooks::create_trust(project_dir).unwrap();
+
         let args = CreateArgs {
             title: "Bean with failing post-create hook".to_string(),
             description: None,
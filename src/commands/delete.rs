@@ -6,6 +6,7 @@ use anyhow::Result;
 
 use crate::bean::Bean;
 use crate::discovery::find_bean_file;
+use crate::events::{Action, Operation};
 use crate::index::Index;
 
 /// Delete a bean and clean up all references to it in other beans' dependencies.
@@ -25,9 +26,11 @@ pub fn cmd_delete(beans_dir: &Path, id: &str) -> Result<()> {
 
     // Delete the bean file
     fs::remove_file(&bean_path).with_context(|| format!("Failed to delete bean file: {}", id))?;
+    let op = Operation::new();
+    op.record(beans_dir, Action::Delete, id, Some(&bean), None);
 
     // Clean up dependency references
-    cleanup_dep_references(beans_dir, id)
+    cleanup_dep_references(beans_dir, id, &op)
         .with_context(|| format!("Failed to clean up dependency references for: {}", id))?;
 
     // Rebuild index
@@ -41,7 +44,7 @@ pub fn cmd_delete(beans_dir: &Path, id: &str) -> Result<()> {
 }
 
 /// Helper: scan all beans and remove deleted_id from their dependencies lists.
-fn cleanup_dep_references(beans_dir: &Path, deleted_id: &str) -> Result<()> {
+fn cleanup_dep_references(beans_dir: &Path, deleted_id: &str, op: &Operation) -> Result<()> {
     let dir_entries = fs::read_dir(beans_dir)
         .with_context(|| format!("Failed to read directory: {}", beans_dir.display()))?;
 
@@ -73,6 +76,8 @@ fn cleanup_dep_references(beans_dir: &Path, deleted_id: &str) -> Result<()> {
 
         // Load the bean
         if let Ok(mut bean) = Bean::from_file(&path) {
+            let before = bean.clone();
+
             // Remove deleted_id from dependencies if present
             let original_len = bean.dependencies.len();
             bean.dependencies.retain(|dep| dep != deleted_id);
@@ -80,6 +85,13 @@ fn cleanup_dep_references(beans_dir: &Path, deleted_id: &str) -> Result<()> {
             // Only write if we actually removed something
             if bean.dependencies.len() < original_len {
                 bean.to_file(&path)?;
+                op.record(
+                    beans_dir,
+                    Action::Update,
+                    &bean.id,
+                    Some(&before),
+                    Some(&bean),
+                );
             }
         }
     }
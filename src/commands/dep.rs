@@ -1,51 +1,53 @@
 use std::collections::HashMap;
 use std::path::Path;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 use chrono::Utc;
+use serde::Serialize;
 
-use crate::bean::Bean;
+use crate::bean::{Bean, Status};
 use crate::discovery::find_bean_file;
-use crate::graph::detect_cycle;
+use crate::error::BeansError;
+use crate::events::{self, Action};
+use crate::graph::cycle_path;
 use crate::index::Index;
 
 /// Add a dependency: `bn dep add <id> <depends-on-id>`
 /// Sets id.dependencies to include depends-on-id.
 /// Checks for cycles before adding.
 pub fn cmd_dep_add(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()> {
-    // Verify both beans exist (supports both .md and legacy .yaml formats)
-    let bean_path = find_bean_file(beans_dir, id).map_err(|_| anyhow!("Bean {} not found", id))?;
-
-    find_bean_file(beans_dir, depends_on_id)
-        .map_err(|_| anyhow!("Bean {} not found", depends_on_id))?;
+    add_dependency(beans_dir, id, depends_on_id)?;
+    println!("{} now depends on {}", id, depends_on_id);
+    Ok(())
+}
 
-    // Check for self-dependency
-    if id == depends_on_id {
-        return Err(anyhow!(
-            "Cannot add self-dependency: {} cannot depend on itself",
-            id
-        ));
-    }
+/// Make `id` depend on `depends_on_id` and rebuild the index.
+///
+/// Fails if either bean is missing, the edge already exists, or it would
+/// create a cycle.
+pub fn add_dependency(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()> {
+    // Verify both beans exist (supports both .md and legacy .yaml formats)
+    let bean_path = find_bean_file(beans_dir, id)?;
+    find_bean_file(beans_dir, depends_on_id)?;
 
-    // Load index once for cycle detection
+    // Check for self-dependency and cycles
     let index = Index::load_or_rebuild(beans_dir)?;
-
-    // Check for cycles
-    if detect_cycle(&index, id, depends_on_id)? {
-        return Err(anyhow!(
-            "Dependency cycle detected: adding {} -> {} would create a cycle. Edge not added.",
-            id,
-            depends_on_id
-        ));
+    if let Some(path) = cycle_path(&index, id, depends_on_id) {
+        return Err(BeansError::Cycle { path }.into());
     }
 
     // Load the bean and add dependency
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let before = bean.clone();
 
     // Check if already dependent
     if bean.dependencies.contains(&depends_on_id.to_string()) {
-        return Err(anyhow!("Bean {} already depends on {}", id, depends_on_id));
+        return Err(BeansError::DependencyExists {
+            id: id.to_string(),
+            depends_on: depends_on_id.to_string(),
+        }
+        .into());
     }
 
     bean.dependencies.push(depends_on_id.to_string());
@@ -53,6 +55,7 @@ pub fn cmd_dep_add(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()
 
     bean.to_file(&bean_path)
         .with_context(|| format!("Failed to save bean: {}", id))?;
+    events::record(beans_dir, Action::Update, id, Some(&before), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
@@ -60,28 +63,39 @@ pub fn cmd_dep_add(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()
         .save(beans_dir)
         .with_context(|| "Failed to save index")?;
 
-    println!("{} now depends on {}", id, depends_on_id);
-
     Ok(())
 }
 
 /// Remove a dependency: `bn dep remove <id> <depends-on-id>`
 pub fn cmd_dep_remove(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()> {
-    let bean_path = find_bean_file(beans_dir, id).map_err(|_| anyhow!("Bean {} not found", id))?;
+    remove_dependency(beans_dir, id, depends_on_id)?;
+    println!("{} no longer depends on {}", id, depends_on_id);
+    Ok(())
+}
+
+/// Drop `depends_on_id` from the dependencies of `id` and rebuild the index.
+pub fn remove_dependency(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result<()> {
+    let bean_path = find_bean_file(beans_dir, id)?;
 
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let before = bean.clone();
 
     let original_len = bean.dependencies.len();
     bean.dependencies.retain(|d| d != depends_on_id);
 
     if bean.dependencies.len() == original_len {
-        return Err(anyhow!("Bean {} does not depend on {}", id, depends_on_id));
+        return Err(BeansError::NoSuchDependency {
+            id: id.to_string(),
+            depends_on: depends_on_id.to_string(),
+        }
+        .into());
     }
 
     bean.updated_at = Utc::now();
     bean.to_file(&bean_path)
         .with_context(|| format!("Failed to save bean: {}", id))?;
+    events::record(beans_dir, Action::Update, id, Some(&before), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
@@ -89,8 +103,6 @@ pub fn cmd_dep_remove(beans_dir: &Path, id: &str, depends_on_id: &str) -> Result
         .save(beans_dir)
         .with_context(|| "Failed to save index")?;
 
-    println!("{} no longer depends on {}", id, depends_on_id);
-
     Ok(())
 }
 
@@ -102,7 +114,10 @@ pub fn cmd_dep_list(beans_dir: &Path, id: &str) -> Result<()> {
         .beans
         .iter()
         .find(|e| e.id == id)
-        .ok_or_else(|| anyhow!("Bean {} not found", id))?;
+        .ok_or_else(|| BeansError::NotFound {
+            id: id.to_string(),
+            archived: false,
+        })?;
 
     // Create id -> entry map
     let id_map: HashMap<String, &crate::index::IndexEntry> =
@@ -141,6 +156,56 @@ pub fn cmd_dep_list(beans_dir: &Path, id: &str) -> Result<()> {
     Ok(())
 }
 
+/// A bean on either side of a dependency edge.
+#[derive(Debug, Clone, Serialize)]
+pub struct DepRef {
+    pub id: String,
+    /// `None` when the bean no longer exists.
+    pub title: Option<String>,
+    pub status: Option<Status>,
+}
+
+/// What a bean depends on and what depends on it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyList {
+    pub id: String,
+    pub dependencies: Vec<DepRef>,
+    pub dependents: Vec<DepRef>,
+}
+
+/// The data behind `bn dep list`, without printing it.
+pub fn list_dependencies(beans_dir: &Path, id: &str) -> Result<DependencyList> {
+    let index = Index::load_or_rebuild(beans_dir)?;
+    let entry = index
+        .beans
+        .iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| BeansError::NotFound {
+            id: id.to_string(),
+            archived: false,
+        })?;
+
+    let lookup = |dep_id: &str| {
+        let found = index.beans.iter().find(|e| e.id == dep_id);
+        DepRef {
+            id: dep_id.to_string(),
+            title: found.map(|e| e.title.clone()),
+            status: found.map(|e| e.status),
+        }
+    };
+
+    Ok(DependencyList {
+        id: id.to_string(),
+        dependencies: entry.dependencies.iter().map(|d| lookup(d)).collect(),
+        dependents: index
+            .beans
+            .iter()
+            .filter(|e| e.dependencies.iter().any(|d| d == id))
+            .map(|e| lookup(&e.id))
+            .collect(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,6 +17,7 @@ use std::process::Command;
 
 use crate::bean::Bean;
 use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
 use crate::index::Index;
 
 /// Validate bean content and persist it to disk with updated timestamp.
@@ -272,6 +273,7 @@ pub fn cmd_edit(beans_dir: &Path, id: &str) -> Result<()> {
     // Step 2: Load the current bean content as backup
     let backup = load_backup(&bean_path)
         .with_context(|| format!("Failed to load bean for editing: {}", id))?;
+    let before = Bean::from_file(&bean_path).ok();
 
     // Step 3: Open editor for user to modify the file
     loop {
@@ -284,6 +286,16 @@ pub fn cmd_edit(beans_dir: &Path, id: &str) -> Result<()> {
                 // Step 5: Validate and save the edited content (updates timestamp)
                 match validate_and_save(&bean_path, &edited_content) {
                     Ok(()) => {
+                        if let Ok(after) = Bean::from_file(&bean_path) {
+                            events::record(
+                                beans_dir,
+                                Action::Update,
+                                id,
+                                before.as_ref(),
+                                Some(&after),
+                            );
+                        }
+
                         // Step 6: Rebuild the index to reflect changes
                         rebuild_index_after_edit(beans_dir)
                             .with_context(|| "Failed to rebuild index after edit")?;
@@ -2,11 +2,17 @@ use std::path::Path;
 
 use anyhow::{anyhow, Result};
 use chrono::{Duration, Utc};
+use serde::Serialize;
 
 use crate::bean::Bean;
 use crate::commands::create::{cmd_create, CreateArgs};
 use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
+use crate::hooks;
 use crate::index::Index;
+use crate::sandbox;
+use crate::util::natural_cmp;
+use crate::verify_cache;
 
 /// Default TTL for facts: 30 days.
 const DEFAULT_TTL_DAYS: i64 = 30;
@@ -61,6 +67,7 @@ pub fn cmd_fact(
     // Now patch the bean to set fact-specific fields
     let bean_path = find_bean_file(beans_dir, &bean_id)?;
     let mut bean = Bean::from_file(&bean_path)?;
+    let before = bean.clone();
 
     bean.bean_type = "fact".to_string();
 
@@ -78,6 +85,13 @@ pub fn cmd_fact(
     }
 
     bean.to_file(&bean_path)?;
+    events::record(
+        beans_dir,
+        Action::Update,
+        &bean_id,
+        Some(&before),
+        Some(&bean),
+    );
 
     // Rebuild index
     let index = Index::build(beans_dir)?;
@@ -87,6 +101,32 @@ pub fn cmd_fact(
     Ok(bean_id)
 }
 
+/// A fact named in a [`FactReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct FactRef {
+    pub id: String,
+    pub title: String,
+}
+
+/// A fact whose verify command failed or could not run.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailingFact {
+    pub id: String,
+    pub title: String,
+    pub error: String,
+}
+
+/// Outcome of re-verifying every fact.
+#[derive(Debug, Default, Serialize)]
+pub struct FactReport {
+    pub total: usize,
+    pub verified: Vec<FactRef>,
+    pub stale: Vec<FactRef>,
+    pub failing: Vec<FailingFact>,
+    /// Facts that require an artifact produced by a stale or failing fact.
+    pub suspect: Vec<FactRef>,
+}
+
 /// Verify all facts and report staleness.
 ///
 /// Re-runs verify commands for all beans with bean_type=fact.
@@ -96,8 +136,49 @@ pub fn cmd_fact(
 /// Suspect propagation: facts that require artifacts from failing/stale facts
 /// are marked as suspect (up to depth 3).
 pub fn cmd_verify_facts(beans_dir: &Path) -> Result<()> {
+    let report = verify_facts(beans_dir)?;
+
+    for fact in &report.stale {
+        eprintln!("⚠ STALE: [{}] \"{}\"", fact.id, fact.title);
+    }
+    for fact in &report.verified {
+        println!("  ✓ [{}] \"{}\"", fact.id, fact.title);
+    }
+    for fact in &report.failing {
+        eprintln!(
+            "  ✗ FAILING: [{}] \"{}\" — {}",
+            fact.id, fact.title, fact.error
+        );
+    }
+    for fact in &report.suspect {
+        eprintln!(
+            "  ⚠ SUSPECT: [{}] \"{}\" — requires artifact from invalid fact",
+            fact.id, fact.title
+        );
+    }
+
+    println!();
+    println!(
+        "Facts: {} total, {} verified, {} stale, {} failing, {} suspect",
+        report.total,
+        report.verified.len(),
+        report.stale.len(),
+        report.failing.len(),
+        report.suspect.len()
+    );
+
+    if !report.failing.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Re-run every fact's verify command and collect the results.
+///
+/// Facts that pass get `last_verified` (and a fresh TTL) written back.
+pub fn verify_facts(beans_dir: &Path) -> Result<FactReport> {
     use std::collections::{HashMap, HashSet};
-    use std::process::Command as ShellCommand;
 
     let project_root = beans_dir
         .parent()
@@ -108,11 +189,7 @@ pub fn cmd_verify_facts(beans_dir: &Path) -> Result<()> {
     let archived = Index::collect_archived(beans_dir).unwrap_or_default();
 
     let now = Utc::now();
-    let mut stale_count = 0;
-    let mut failing_count = 0;
-    let mut verified_count = 0;
-    let mut total_facts = 0;
-    let mut suspect_count = 0;
+    let mut report = FactReport::default();
 
     // Collect all facts and their states for suspect propagation
     let mut invalid_artifacts: HashSet<String> = HashSet::new();
@@ -141,18 +218,21 @@ pub fn cmd_verify_facts(beans_dir: &Path) -> Result<()> {
             continue;
         }
 
-        total_facts += 1;
+        report.total += 1;
         fact_titles.insert(bean.id.clone(), bean.title.clone());
         if !bean.requires.is_empty() {
             fact_requires.insert(bean.id.clone(), bean.requires.clone());
         }
+        let fact = FactRef {
+            id: bean.id.clone(),
+            title: bean.title.clone(),
+        };
 
         // Check staleness
         let is_stale = bean.stale_after.map(|sa| now > sa).unwrap_or(false);
 
         if is_stale {
-            stale_count += 1;
-            eprintln!("⚠ STALE: [{}] \"{}\"", bean.id, bean.title);
+            report.stale.push(fact.clone());
             // Stale facts invalidate their produced artifacts
             for prod in &bean.produces {
                 invalid_artifacts.insert(prod.clone());
@@ -161,41 +241,56 @@ pub fn cmd_verify_facts(beans_dir: &Path) -> Result<()> {
 
         // Re-run verify command
         if let Some(ref verify_cmd) = bean.verify {
-            let output = ShellCommand::new("sh")
-                .args(["-c", verify_cmd])
-                .current_dir(project_root)
-                .output();
-
-            match output {
-                Ok(o) if o.status.success() => {
-                    verified_count += 1;
+            let key = verify_cache::key(beans_dir, &bean.paths, verify_cmd);
+            let cached = key
+                .as_ref()
+                .and_then(|k| verify_cache::lookup(beans_dir, k))
+                .map(|entry| entry.passed());
+            let approved = hooks::check_bean_verify(project_root, &bean.id, verify_cmd);
+            let output = match (approved, cached) {
+                (Err(e), _) => Err(std::io::Error::other(e.to_string())),
+                (Ok(()), Some(passed)) => Ok(passed),
+                (Ok(()), None) => {
+                    let started_at = Utc::now();
+                    let output = sandbox::shell(project_root, verify_cmd).output();
+                    if let (Some(key), Ok(o)) = (&key, &output) {
+                        let entry = verify_cache::Entry::new(
+                            verify_cmd,
+                            o.status.success(),
+                            o.status.code(),
+                            started_at,
+                            &String::from_utf8_lossy(&o.stdout),
+                            &String::from_utf8_lossy(&o.stderr),
+                        );
+                        verify_cache::store(beans_dir, key, entry);
+                    }
+                    output.map(|o| o.status.success())
+                }
+            };
+
+            let error = match output {
+                Ok(true) => {
                     bean.last_verified = Some(now);
                     // Reset stale_after from now
                     if bean.stale_after.is_some() {
                         bean.stale_after = Some(now + Duration::days(DEFAULT_TTL_DAYS));
                     }
                     bean.to_file(&bean_path)?;
-                    println!("  ✓ [{}] \"{}\"", bean.id, bean.title);
-                }
-                Ok(_) => {
-                    failing_count += 1;
-                    // Failing facts invalidate their produced artifacts
-                    for prod in &bean.produces {
-                        invalid_artifacts.insert(prod.clone());
-                    }
-                    eprintln!(
-                        "  ✗ FAILING: [{}] \"{}\" — verify command returned non-zero",
-                        bean.id, bean.title
-                    );
-                }
-                Err(e) => {
-                    failing_count += 1;
-                    for prod in &bean.produces {
-                        invalid_artifacts.insert(prod.clone());
-                    }
-                    eprintln!("  ✗ ERROR: [{}] \"{}\" — {}", bean.id, bean.title, e);
+                    report.verified.push(fact);
+                    continue;
                 }
+                Ok(false) => "verify command returned non-zero".to_string(),
+                Err(e) => e.to_string(),
+            };
+            // Failing facts invalidate their produced artifacts
+            for prod in &bean.produces {
+                invalid_artifacts.insert(prod.clone());
             }
+            report.failing.push(FailingFact {
+                id: fact.id,
+                title: fact.title,
+                error,
+            });
         }
     }
 
@@ -247,29 +342,16 @@ pub fn cmd_verify_facts(beans_dir: &Path) -> Result<()> {
         }
 
         for suspect_id in &suspect_ids {
-            suspect_count += 1;
-            let title = fact_titles
-                .get(suspect_id)
-                .map(|s| s.as_str())
-                .unwrap_or("?");
-            eprintln!(
-                "  ⚠ SUSPECT: [{}] \"{}\" — requires artifact from invalid fact",
-                suspect_id, title
-            );
+            let title = fact_titles.get(suspect_id).cloned().unwrap_or_default();
+            report.suspect.push(FactRef {
+                id: suspect_id.clone(),
+                title,
+            });
         }
+        report.suspect.sort_by(|a, b| natural_cmp(&a.id, &b.id));
     }
 
-    println!();
-    println!(
-        "Facts: {} total, {} verified, {} stale, {} failing, {} suspect",
-        total_facts, verified_count, stale_count, failing_count, suspect_count
-    );
-
-    if failing_count > 0 {
-        std::process::exit(1);
-    }
-
-    Ok(())
+    Ok(report)
 }
 
 #[cfg(test)]
@@ -304,6 +386,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
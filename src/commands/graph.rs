@@ -305,7 +305,10 @@ fn render_tree<'a>(
 fn format_node(entry: &IndexEntry, index: &Index) -> String {
     let status_icon = match entry.status {
         Status::Closed => "[✓]",
+        Status::Cancelled => "[✗]",
         Status::InProgress => "[●]",
+        Status::InReview => "[◐]",
+        Status::Blocked => "[!]",
         Status::Open => {
             if check_blocked(entry, index).is_some() {
                 "[!]"
@@ -6,6 +6,7 @@ use std::process::Command;
 
 use anyhow::{Context, Result};
 
+use crate::commands::merge_driver::install_git_merge_driver;
 use crate::config::Config;
 
 /// Known agent presets with their run/plan templates and detection info.
@@ -56,6 +57,7 @@ pub struct InitArgs {
     pub plan: Option<String>,
     pub setup: bool,
     pub no_agent: bool,
+    pub git_merge_driver: bool,
 }
 
 /// Find a preset by name (case-insensitive).
@@ -223,6 +225,11 @@ pub fn cmd_init(path: Option<&Path>, args: InitArgs) -> Result<()> {
     // Re-init without --setup: show current config and hint
     if already_exists && !args.setup && args.agent.is_none() && args.run.is_none() {
         if let Ok(config) = Config::load(&beans_dir) {
+            if args.git_merge_driver {
+                install_git_merge_driver(&cwd)?;
+                eprintln!("Installed git merge driver for .beans/ (see .gitattributes)");
+                return Ok(());
+            }
             eprintln!("Project: {}", config.project);
             match &config.run {
                 Some(run) => eprintln!("Run: {}", run),
@@ -294,6 +301,17 @@ pub fn cmd_init(path: Option<&Path>, args: InitArgs) -> Result<()> {
         review: None,
         user: None,
         user_email: None,
+        max_cost_per_bean: None,
+        max_cost_per_run: None,
+        max_cost_per_day: None,
+        max_tokens_per_bean: None,
+        max_tokens_per_run: None,
+        max_tokens_per_day: None,
+        agent: None,
+        views: Default::default(),
+        sandbox: None,
+        verify_cache: None,
+        test_reports: Vec::new(),
     };
 
     config.save(&beans_dir)?;
@@ -335,11 +353,16 @@ pub fn cmd_init(path: Option<&Path>, args: InitArgs) -> Result<()> {
     if !gitignore_path.exists() {
         fs::write(
             &gitignore_path,
-            "# Regenerable cache — rebuilt automatically by bn sync\nindex.yaml\narchive.yaml\n\n# File lock\nindex.lock\n",
+            "# Regenerable cache — rebuilt automatically by bn sync\nindex.yaml\narchive.yaml\nsearch.json\n\n# File locks\nindex.lock\nclaim.lock\n\n# Run journals (bn run --resume)\nruns/\n\n# Hook approvals (bn trust) are per checkout\n.hooks-trusted\n\n# Verify results (verify_cache)\nverify-cache.json\n",
         )
         .with_context(|| format!("Failed to create .gitignore at {}", gitignore_path.display()))?;
     }
 
+    if args.git_merge_driver {
+        install_git_merge_driver(&cwd)?;
+        eprintln!("Installed git merge driver for .beans/ (see .gitattributes)");
+    }
+
     if already_exists && args.setup {
         eprintln!("Reconfigured beans in .beans/");
     } else if !already_exists {
@@ -433,6 +456,7 @@ mod tests {
             plan: None,
             setup: false,
             no_agent: true, // Skip interactive in tests
+            git_merge_driver: false,
         }
     }
 
@@ -748,4 +772,23 @@ mod tests {
         let config2 = Config::load(&beans_dir).unwrap();
         assert_eq!(config2.next_id, 42);
     }
+
+    #[test]
+    fn init_git_merge_driver_on_existing_project() {
+        let dir = TempDir::new().unwrap();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        cmd_init(Some(dir.path()), default_args()).unwrap();
+
+        let mut args = default_args();
+        args.git_merge_driver = true;
+        cmd_init(Some(dir.path()), args).unwrap();
+
+        let attributes = fs::read_to_string(dir.path().join(".gitattributes")).unwrap();
+        assert!(attributes.contains(".beans/**/*.md merge=beans"));
+        assert!(attributes.contains(".beans/index.yaml merge=beans"));
+    }
 }
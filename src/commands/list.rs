@@ -6,9 +6,49 @@ use anyhow::Result;
 use crate::bean::Status;
 use crate::blocking::check_blocked;
 use crate::config::resolve_identity;
+use crate::filter::Filter;
 use crate::index::{Index, IndexEntry};
 use crate::util::{natural_cmp, parse_status};
 
+/// Filters for [`list_entries`], mirroring the `bn list` flags.
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    /// Only beans with this status (e.g. `open`, `in_progress`, `closed`).
+    pub status: Option<String>,
+    pub priority: Option<u8>,
+    /// Only children of this parent.
+    pub parent: Option<String>,
+    pub label: Option<String>,
+    pub assignee: Option<String>,
+    /// Only beans claimed by or assigned to the current user.
+    pub mine: bool,
+    /// Include closed and cancelled beans.
+    pub all: bool,
+    /// Filter expression (see [`crate::filter`]), e.g.
+    /// `priority<=1 and label:backend and not claimed`.
+    pub query: Option<String>,
+}
+
+impl ListFilter {
+    /// Whether archived beans are part of the result.
+    fn includes_archived(&self, beans_dir: &Path) -> bool {
+        self.all
+            || self.status_filter().is_some_and(Status::is_terminal)
+            || self.query_decides_status(beans_dir)
+    }
+
+    /// Whether the query replaces the default of hiding closed beans.
+    fn query_decides_status(&self, beans_dir: &Path) -> bool {
+        self.query
+            .as_deref()
+            .is_some_and(|q| Filter::load(beans_dir, q).is_ok_and(|f| f.decides_status()))
+    }
+
+    fn status_filter(&self) -> Option<Status> {
+        self.status.as_deref().and_then(parse_status)
+    }
+}
+
 /// List beans with optional filtering.
 /// - Default: tree-format with status indicators
 /// - --status: filter by status (open, in_progress, closed)
@@ -20,7 +60,10 @@ use crate::util::{natural_cmp, parse_status};
 /// - --json: JSON array output
 /// - Shows [!] for blocked beans
 ///
-/// When --status closed is specified, also searches archived beans.
+/// - -q/--query: filter expression, see [`crate::filter`]
+///
+/// When --status closed is specified (or the query tests the status),
+/// also searches archived beans.
 #[allow(clippy::too_many_arguments)]
 pub fn cmd_list(
     status_filter: Option<&str>,
@@ -28,6 +71,7 @@ pub fn cmd_list(
     parent_filter: Option<&str>,
     label_filter: Option<&str>,
     assignee_filter: Option<&str>,
+    query: Option<&str>,
     mine: bool,
     all: bool,
     json: bool,
@@ -35,13 +79,82 @@ pub fn cmd_list(
     format_str: Option<&str>,
     beans_dir: &Path,
 ) -> Result<()> {
+    let filter = ListFilter {
+        status: status_filter.map(str::to_string),
+        priority: priority_filter,
+        parent: parent_filter.map(str::to_string),
+        label: label_filter.map(str::to_string),
+        assignee: assignee_filter.map(str::to_string),
+        mine,
+        all,
+        query: query.map(str::to_string),
+    };
+    let include_archived = filter.includes_archived(beans_dir);
+    let filtered = list_entries(beans_dir, &filter)?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&filtered)?;
+        println!("{}", json_str);
+    } else if ids {
+        // Just IDs, one per line — ideal for piping
+        for entry in &filtered {
+            println!("{}", entry.id);
+        }
+    } else if let Some(fmt) = format_str {
+        // Custom format string: {id}, {title}, {status}, {priority}, {parent}
+        for entry in &filtered {
+            let line = fmt
+                .replace("{id}", &entry.id)
+                .replace("{title}", &entry.title)
+                .replace("{status}", &format!("{}", entry.status))
+                .replace("{priority}", &format!("P{}", entry.priority))
+                .replace("{parent}", entry.parent.as_deref().unwrap_or(""))
+                .replace("{assignee}", entry.assignee.as_deref().unwrap_or(""))
+                .replace("{labels}", &entry.labels.join(","))
+                .replace("\\t", "\t")
+                .replace("\\n", "\n");
+            println!("{}", line);
+        }
+    } else {
+        // Build combined index for tree rendering (includes archived if needed)
+        let index = Index::load_or_rebuild(beans_dir)?;
+        let combined_index = if include_archived {
+            let mut all_beans = index.beans.clone();
+            if let Ok(archived) = Index::collect_archived(beans_dir) {
+                all_beans.extend(archived);
+            }
+            Index { beans: all_beans }
+        } else {
+            index
+        };
+
+        // Tree format with status indicators
+        let tree = render_tree(&filtered, &combined_index);
+        println!("{}", tree);
+        println!("Legend: [ ] open  [-] in_progress  [x] closed  [!] blocked");
+    }
+
+    Ok(())
+}
+
+/// The index entries `bn list` shows for `filter`, in index order.
+///
+/// When the filter asks for closed or cancelled beans (or `all`), archived
+/// beans are included.
+pub fn list_entries(beans_dir: &Path, filter: &ListFilter) -> Result<Vec<IndexEntry>> {
     let index = Index::load_or_rebuild(beans_dir)?;
 
-    // Parse status filter
-    let status_filter = status_filter.and_then(parse_status);
+    let status_filter = filter.status_filter();
+    let all = filter.all;
+    let query = filter
+        .query
+        .as_deref()
+        .map(|q| Filter::load(beans_dir, q))
+        .transpose()?;
+    let query_decides_status = filter.query_decides_status(beans_dir);
 
     // Resolve current user for --mine filter
-    let current_user = if mine {
+    let current_user = if filter.mine {
         let user = resolve_identity(beans_dir);
         if user.is_none() {
             anyhow::bail!(
@@ -55,11 +168,10 @@ pub fn cmd_list(
     };
 
     // Start with beans from the main index
-    let mut filtered = index.beans.clone();
+    let mut filtered = index.beans;
 
-    // Include archived beans when querying for closed status or using --all
-    let include_archived = status_filter == Some(Status::Closed) || all;
-    if include_archived {
+    // Include archived beans when querying for closed/cancelled status or using --all
+    if filter.includes_archived(beans_dir) {
         if let Ok(archived) = Index::collect_archived(beans_dir) {
             filtered.extend(archived);
         }
@@ -68,8 +180,9 @@ pub fn cmd_list(
     // Apply filters
     filtered.retain(|entry| {
         // Status filter
-        // By default, exclude closed beans (unless --all or --status closed)
-        if !all && status_filter != Some(Status::Closed) && entry.status == Status::Closed {
+        // By default, exclude closed and cancelled beans (unless --all or
+        // --status asks for them)
+        if !all && status_filter.is_none() && !query_decides_status && entry.status.is_terminal() {
             return false;
         }
         if let Some(status) = status_filter {
@@ -79,28 +192,28 @@ pub fn cmd_list(
         }
 
         // Priority filter
-        if let Some(priority) = priority_filter {
+        if let Some(priority) = filter.priority {
             if entry.priority != priority {
                 return false;
             }
         }
 
         // Parent filter
-        if let Some(parent) = parent_filter {
-            if entry.parent.as_deref() != Some(parent) {
+        if let Some(ref parent) = filter.parent {
+            if entry.parent.as_ref() != Some(parent) {
                 return false;
             }
         }
 
         // Label filter
-        if let Some(label) = label_filter {
-            if !entry.labels.contains(&label.to_string()) {
+        if let Some(ref label) = filter.label {
+            if !entry.labels.contains(label) {
                 return false;
             }
         }
 
         // Assignee filter
-        if let Some(_assignee) = assignee_filter {
+        if let Some(ref _assignee) = filter.assignee {
             // We need to load the full bean to check assignee (not in index)
             // For now, skip this optimization and check during rendering
             return true;
@@ -118,57 +231,24 @@ pub fn cmd_list(
             }
         }
 
-        true
-    });
-
-    if json {
-        let json_str = serde_json::to_string_pretty(&filtered)?;
-        println!("{}", json_str);
-    } else if ids {
-        // Just IDs, one per line — ideal for piping
-        for entry in &filtered {
-            println!("{}", entry.id);
-        }
-    } else if let Some(fmt) = format_str {
-        // Custom format string: {id}, {title}, {status}, {priority}, {parent}
-        for entry in &filtered {
-            let line = fmt
-                .replace("{id}", &entry.id)
-                .replace("{title}", &entry.title)
-                .replace("{status}", &format!("{}", entry.status))
-                .replace("{priority}", &format!("P{}", entry.priority))
-                .replace("{parent}", entry.parent.as_deref().unwrap_or(""))
-                .replace("{assignee}", entry.assignee.as_deref().unwrap_or(""))
-                .replace("{labels}", &entry.labels.join(","))
-                .replace("\\t", "\t")
-                .replace("\\n", "\n");
-            println!("{}", line);
-        }
-    } else {
-        // Build combined index for tree rendering (includes archived if needed)
-        let combined_index = if include_archived {
-            let mut all_beans = index.beans.clone();
-            if let Ok(archived) = Index::collect_archived(beans_dir) {
-                all_beans.extend(archived);
+        // Filter expression (last: it may need to load the bean file)
+        if let Some(ref query) = query {
+            if !query.matches(entry, beans_dir) {
+                return false;
             }
-            Index { beans: all_beans }
-        } else {
-            index.clone()
-        };
+        }
 
-        // Tree format with status indicators
-        let tree = render_tree(&filtered, &combined_index);
-        println!("{}", tree);
-        println!("Legend: [ ] open  [-] in_progress  [x] closed  [!] blocked");
-    }
+        true
+    });
 
-    Ok(())
+    Ok(filtered)
 }
 
 /// Render beans as a hierarchical tree.
 /// - Root beans have no parent
 /// - Children indented 2 spaces per level
-/// - Status: [ ] open, [-] in_progress, [x] closed, [!] blocked
+/// - Status: [ ] open, [-] in_progress, [~] in_review, [x] closed,
+///   [/] cancelled, [!] blocked
 fn render_tree(entries: &[IndexEntry], index: &Index) -> String {
     let mut output = String::new();
 
@@ -228,7 +308,10 @@ fn get_status_indicator(entry: &IndexEntry, index: &Index) -> (String, String) {
         let indicator = match entry.status {
             Status::Open => "[ ]",
             Status::InProgress => "[-]",
+            Status::InReview => "[~]",
+            Status::Blocked => "[!]",
             Status::Closed => "[x]",
+            Status::Cancelled => "[/]",
         };
         // Scope warnings are non-blocking annotations
         let suffix = crate::blocking::check_scope_warning(entry)
@@ -331,6 +414,7 @@ mod tests {
             claimed_by: None,
             attempts: 0,
             paths: vec!["src/test.rs".to_string()],
+            blocked_reason: None,
         }
     }
 
@@ -0,0 +1,220 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde_json::Value;
+
+use crate::events::{load_events, Event, FieldChange};
+
+/// Handle `bn log [id]` command.
+///
+/// Prints the bean mutation events from `.beans/events.jsonl`, oldest first,
+/// optionally limited to one bean, to events after `since` (a duration such
+/// as `2h`/`3d`/`1w`, a date, or an RFC 3339 timestamp) and to one actor.
+pub fn cmd_log(
+    beans_dir: &Path,
+    id: Option<&str>,
+    since: Option<&str>,
+    actor: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let since = since.map(|s| parse_since(s, Utc::now())).transpose()?;
+    let events = filter_events(load_events(beans_dir), id, since, actor);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&events)?);
+        return Ok(());
+    }
+
+    if events.is_empty() {
+        println!("No events.");
+        return Ok(());
+    }
+    for event in &events {
+        print_event(event);
+    }
+    Ok(())
+}
+
+/// Keep the events about bean `id` made by `actor` at or after `since`.
+pub fn filter_events(
+    events: Vec<Event>,
+    id: Option<&str>,
+    since: Option<DateTime<Utc>>,
+    actor: Option<&str>,
+) -> Vec<Event> {
+    events
+        .into_iter()
+        .filter(|e| id.is_none_or(|id| e.touches(id)))
+        .filter(|e| since.is_none_or(|since| e.timestamp >= since))
+        .filter(|e| actor.is_none_or(|actor| e.actor.as_deref() == Some(actor)))
+        .collect()
+}
+
+/// Parse a `--since` value relative to `now`.
+///
+/// Accepts `<n>m`, `<n>h`, `<n>d` or `<n>w`, a `YYYY-MM-DD` date (midnight
+/// UTC) or an RFC 3339 timestamp.
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let invalid = || {
+        anyhow!(
+            "Invalid --since value '{}': use a duration (30m, 2h, 3d, 1w), a date (2026-01-31) or an RFC 3339 timestamp",
+            value
+        )
+    };
+    let split = value.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(now - duration)
+}
+
+fn print_event(event: &Event) {
+    let actor = event.actor.as_deref().unwrap_or("unknown");
+    let mut line = format!(
+        "{}  {:<7}  {}  by {}",
+        event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        event.action,
+        event.id,
+        actor
+    );
+    if let Some(note) = &event.note {
+        line.push_str(&format!("  ({})", note));
+    }
+    println!("{}", line);
+
+    // A create or delete lists every field; the title is enough here.
+    if event.created() || event.removed() {
+        if let Some(title) = event.change("title") {
+            println!("    {}", format_change(title));
+        }
+        return;
+    }
+    for change in &event.changes {
+        println!("    {}", format_change(change));
+    }
+}
+
+fn format_change(change: &FieldChange) -> String {
+    match (&change.old, &change.new) {
+        (None, Some(new)) => format!("{}: {}", change.field, format_value(new)),
+        (Some(old), None) => format!("{}: {} -> (unset)", change.field, format_value(old)),
+        (Some(old), Some(new)) => format!(
+            "{}: {} -> {}",
+            change.field,
+            format_value(old),
+            format_value(new)
+        ),
+        (None, None) => change.field.clone(),
+    }
+}
+
+/// Render a field value on one line, shortening long text.
+fn format_value(value: &Value) -> String {
+    const MAX_CHARS: usize = 60;
+    let text = value.to_string().replace("\\n", " ");
+    if text.chars().count() > MAX_CHARS {
+        let short: String = text.chars().take(MAX_CHARS).collect();
+        format!("{}...", short)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::Bean;
+    use crate::events::{diff, Action};
+    use chrono::TimeZone;
+
+    fn event(action: Action, id: &str, actor: &str, at: DateTime<Utc>) -> Event {
+        Event {
+            timestamp: at,
+            actor: Some(actor.to_string()),
+            action,
+            id: id.to_string(),
+            op: None,
+            peer: None,
+            undoes: Vec::new(),
+            note: None,
+            changes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parse_since_durations_and_dates() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        assert_eq!(
+            parse_since("2h", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 10, 10, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_since("1w", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 3, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_since("2026-03-01", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_since("2026-03-01T08:30:00Z", now).unwrap(),
+            Utc.with_ymd_and_hms(2026, 3, 1, 8, 30, 0).unwrap()
+        );
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("", now).is_err());
+    }
+
+    #[test]
+    fn filter_by_id_since_and_actor() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let t1 = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        let events = vec![
+            event(Action::Create, "1", "alice", t0),
+            event(Action::Update, "1", "bob", t1),
+            event(Action::Claim, "2", "alice", t1),
+        ];
+
+        let only_1 = filter_events(events.clone(), Some("1"), None, None);
+        assert_eq!(only_1.len(), 2);
+
+        let recent = filter_events(events.clone(), None, Some(t1), None);
+        assert_eq!(recent.len(), 2);
+
+        let alice = filter_events(events, Some("1"), None, Some("alice"));
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].action, Action::Create);
+    }
+
+    #[test]
+    fn format_change_shows_old_and_new() {
+        let before = Bean::new("1", "Task");
+        let mut after = before.clone();
+        after.verify = Some("cargo test".to_string());
+        after.title = "Renamed".to_string();
+        let changes = diff(Some(&before), Some(&after));
+
+        let lines: Vec<String> = changes.iter().map(format_change).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "title: \"Task\" -> \"Renamed\"".to_string(),
+                "verify: \"cargo test\"".to_string(),
+            ]
+        );
+    }
+}
@@ -88,7 +88,7 @@ pub fn cmd_logs(beans_dir: &Path, id: &str, follow: bool, all: bool) -> Result<(
 }
 
 /// Try to find a log path — first from agents.json, then from filesystem search.
-fn find_log_path(bean_id: &str) -> Result<Option<PathBuf>> {
+pub fn find_log_path(bean_id: &str) -> Result<Option<PathBuf>> {
     // Check agents.json for a log_path hint
     if let Ok(agents) = super::agents::load_agents() {
         if let Some(entry) = agents.get(bean_id) {
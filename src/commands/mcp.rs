@@ -1,14 +1,55 @@
 //! MCP server command: `bn mcp serve`
 
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 
-/// Start the MCP server on stdio.
+use crate::mcp::http::{HttpServer, ENDPOINT};
+
+/// Environment variable holding the bearer token for `bn mcp serve --http`.
+/// Not a config key: `.beans/config.yaml` is committed with the beans.
+const TOKEN_ENV: &str = "BEANS_MCP_TOKEN";
+
+/// Start the MCP server on stdio, or on HTTP when `http` gives an address.
+///
+/// Over stdio, reads JSON-RPC 2.0 messages from stdin, dispatches to beans
+/// operations, and writes responses to stdout. Designed for use with MCP
+/// clients like Cursor, Windsurf, Claude Desktop, and Cline.
 ///
-/// Reads JSON-RPC 2.0 messages from stdin, dispatches to beans operations,
-/// and writes responses to stdout. Designed for use with MCP clients like
-/// Cursor, Windsurf, Claude Desktop, and Cline.
-pub fn cmd_mcp_serve(beans_dir: &Path) -> Result<()> {
-    crate::mcp::server::run(beans_dir)
+/// Over HTTP, serves the streamable HTTP transport at `/mcp` so several
+/// clients can share one server. Requests must carry `$BEANS_MCP_TOKEN` as a
+/// bearer token, if it is set; without one, only loopback addresses are
+/// served.
+pub fn cmd_mcp_serve(beans_dir: &Path, http: Option<&str>) -> Result<()> {
+    let addr = match http {
+        Some(addr) => addr,
+        None => return crate::mcp::server::run(beans_dir),
+    };
+
+    let token = std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty());
+    if token.is_none() && !is_loopback(addr)? {
+        bail!(
+            "{} is reachable from other machines; set {} to require a bearer token, \
+             or bind 127.0.0.1",
+            addr,
+            TOKEN_ENV
+        );
+    }
+    let server = HttpServer::bind(addr)?;
+    eprintln!(
+        "beans MCP server listening on http://{}{}",
+        server.local_addr()?,
+        ENDPOINT
+    );
+    server.run(beans_dir, token)
+}
+
+/// Whether every address `addr` resolves to is a loopback address.
+fn is_loopback(addr: &str) -> Result<bool> {
+    let addrs: Vec<SocketAddr> = addr
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid address: {}", addr))?
+        .collect();
+    Ok(!addrs.is_empty() && addrs.iter().all(|a| a.ip().is_loopback()))
 }
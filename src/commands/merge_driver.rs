@@ -0,0 +1,397 @@
+//! Git merge driver for bean files and the index.
+//!
+//! Git invokes `bn merge-driver %O %A %B %P` for paths marked `merge=beans` in
+//! `.gitattributes`. Bean files are merged field by field; `index.yaml` and
+//! `archive.yaml` are regenerated from the bean files instead of being merged
+//! as text. `bn init --git-merge-driver` installs the driver.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::bean::merge::{record_conflicts, three_way_merge};
+use crate::bean::Bean;
+use crate::index::{ArchiveIndex, Index};
+
+/// Name of the merge driver in git config (`merge.beans.*`).
+const DRIVER_NAME: &str = "beans";
+
+/// `.gitattributes` lines routing bean files and caches through the driver.
+const GITATTRIBUTES_LINES: &[&str] = &[
+    ".beans/**/*.md merge=beans",
+    ".beans/index.yaml merge=beans",
+    ".beans/archive.yaml merge=beans",
+];
+
+/// Merge three versions of a bean file (or index) for git.
+///
+/// `base`, `ours` and `theirs` are the `%O`, `%A` and `%B` temp files; the
+/// result is written to `ours`. `path` is the path in the repository (`%P`),
+/// used to recognize the index and pick the output format.
+///
+/// Returns `false` if fields conflicted. The merged bean is still written, with
+/// the conflicts recorded for `bn resolve`.
+pub fn cmd_merge_driver(
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    path: Option<&Path>,
+) -> Result<bool> {
+    let file_name = path.and_then(|p| p.file_name()).and_then(|n| n.to_str());
+    if let Some(name @ ("index.yaml" | "archive.yaml")) = file_name {
+        let beans_dir = path
+            .and_then(|p| p.parent())
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or(Path::new(".beans"));
+        regenerate_index(beans_dir, name, ours)?;
+        return Ok(true);
+    }
+
+    let ours_content =
+        fs::read_to_string(ours).with_context(|| format!("Failed to read {}", ours.display()))?;
+    let theirs_content = fs::read_to_string(theirs)
+        .with_context(|| format!("Failed to read {}", theirs.display()))?;
+    let base_content = fs::read_to_string(base).unwrap_or_default();
+
+    let ours_bean = Bean::from_string(&ours_content).context("Failed to parse our version")?;
+    let theirs_bean =
+        Bean::from_string(&theirs_content).context("Failed to parse their version")?;
+    // Added on both sides: there is no common ancestor, so diff against a
+    // blank bean — lists and notes still union, other differences conflict.
+    let base_bean = if base_content.trim().is_empty() {
+        Bean::new(&ours_bean.id, &ours_bean.title)
+    } else {
+        Bean::from_string(&base_content).context("Failed to parse base version")?
+    };
+
+    let outcome = three_way_merge(&base_bean, &ours_bean, &theirs_bean)?;
+    let mut merged = outcome.bean;
+    let mut conflicts = outcome.conflicts;
+
+    // Notes are append-only: if either side rewrote them, keep both texts.
+    if let Some(pos) = conflicts.iter().position(|c| c.field == "notes") {
+        conflicts.remove(pos);
+        merged.notes = append_notes(ours_bean.notes.as_deref(), theirs_bean.notes.as_deref());
+    }
+
+    record_conflicts(&mut merged, &conflicts, "ours", "theirs");
+
+    let markdown = match path {
+        Some(p) => p.extension().and_then(|e| e.to_str()) == Some("md"),
+        None => ours_content.starts_with("---"),
+    };
+    fs::write(ours, merged.to_content(markdown)?)
+        .with_context(|| format!("Failed to write {}", ours.display()))?;
+
+    if !conflicts.is_empty() {
+        let fields: Vec<&str> = conflicts.iter().map(|c| c.field.as_str()).collect();
+        eprintln!(
+            "Bean {}: conflicting field(s): {}",
+            merged.id,
+            fields.join(", ")
+        );
+        eprintln!(
+            "  Run `bn resolve {}`, then `git add` the bean file.",
+            merged.id
+        );
+    }
+
+    Ok(conflicts.is_empty())
+}
+
+/// Rebuild `index.yaml` or `archive.yaml` from the working tree into `out`.
+///
+/// Bean files merged later in the same merge make the index stale again by
+/// mtime, so the next `bn` command rebuilds it.
+fn regenerate_index(beans_dir: &Path, name: &str, out: &Path) -> Result<()> {
+    if !beans_dir.is_dir() {
+        return Err(anyhow!(
+            "Cannot regenerate {}: {} is not a directory",
+            name,
+            beans_dir.display()
+        ));
+    }
+    let yaml = if name == "archive.yaml" {
+        serde_yml::to_string(&ArchiveIndex::build(beans_dir)?)?
+    } else {
+        serde_yml::to_string(&Index::build(beans_dir)?)?
+    };
+    fs::write(out, yaml).with_context(|| format!("Failed to write {}", out.display()))?;
+    Ok(())
+}
+
+/// Keep both notes texts, ours first, without duplicating shared text.
+fn append_notes(ours: Option<&str>, theirs: Option<&str>) -> Option<String> {
+    match (ours, theirs) {
+        (Some(o), Some(t)) if o.contains(t) => Some(o.to_string()),
+        (Some(o), Some(t)) if t.contains(o) => Some(t.to_string()),
+        (Some(o), Some(t)) => Some(format!("{}\n\n{}", o.trim_end(), t)),
+        (Some(o), None) => Some(o.to_string()),
+        (None, t) => t.map(str::to_string),
+    }
+}
+
+/// Register the merge driver in git config and `.gitattributes`.
+///
+/// Idempotent: existing `.gitattributes` lines are left alone.
+pub fn install_git_merge_driver(project_root: &Path) -> Result<()> {
+    let git_config = |key: &str, value: &str| -> Result<()> {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(project_root)
+            .args(["config", key, value])
+            .status()
+            .context("Failed to run git config")?;
+        if !status.success() {
+            return Err(anyhow!(
+                "git config {} failed (is {} a git repository?)",
+                key,
+                project_root.display()
+            ));
+        }
+        Ok(())
+    };
+    git_config(
+        &format!("merge.{}.name", DRIVER_NAME),
+        "beans field-level merge",
+    )?;
+    git_config(
+        &format!("merge.{}.driver", DRIVER_NAME),
+        "bn merge-driver %O %A %B %P",
+    )?;
+
+    let attributes_path = project_root.join(".gitattributes");
+    let mut contents = fs::read_to_string(&attributes_path).unwrap_or_default();
+    let missing: Vec<&str> = GITATTRIBUTES_LINES
+        .iter()
+        .copied()
+        .filter(|line| !contents.lines().any(|l| l.trim() == *line))
+        .collect();
+    if !missing.is_empty() {
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        for line in missing {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+        fs::write(&attributes_path, contents)
+            .with_context(|| format!("Failed to write {}", attributes_path.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::{AttemptOutcome, AttemptRecord, ConflictResolution, Status};
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, bean: &Bean) -> std::path::PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, bean.to_content(true).unwrap()).unwrap();
+        path
+    }
+
+    fn base_bean() -> Bean {
+        let mut bean = Bean::new("3", "Merge me");
+        bean.description = Some("Body text".to_string());
+        bean.labels = vec!["core".to_string()];
+        bean.notes = Some("first".to_string());
+        bean
+    }
+
+    fn attempt(num: u32) -> AttemptRecord {
+        AttemptRecord {
+            num,
+            outcome: AttemptOutcome::Failed,
+            notes: None,
+            agent: None,
+            started_at: None,
+            finished_at: None,
+            verify: None,
+            base: None,
+        }
+    }
+
+    #[test]
+    fn merges_bean_fields_from_both_branches() {
+        let dir = TempDir::new().unwrap();
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.status = Status::InProgress;
+        ours.labels.push("ours".to_string());
+        ours.notes = Some("first\nfrom ours".to_string());
+        ours.attempt_log.push(attempt(1));
+        let mut theirs = base.clone();
+        theirs.priority = 0;
+        theirs.labels.push("theirs".to_string());
+        theirs.dependencies.push("1".to_string());
+        theirs.notes = Some("first\nfrom theirs".to_string());
+        theirs.attempt_log.push(attempt(1));
+        theirs.attempt_log.push(attempt(2));
+
+        let o = write(dir.path(), "O", &base);
+        let a = write(dir.path(), "A", &ours);
+        let b = write(dir.path(), "B", &theirs);
+
+        let clean = cmd_merge_driver(&o, &a, &b, Some(Path::new(".beans/3-merge-me.md"))).unwrap();
+        assert!(clean);
+
+        let merged = Bean::from_file(&a).unwrap();
+        assert_eq!(merged.status, Status::InProgress);
+        assert_eq!(merged.priority, 0);
+        assert_eq!(merged.labels, vec!["core", "theirs", "ours"]);
+        assert_eq!(merged.dependencies, vec!["1"]);
+        let notes = merged.notes.unwrap();
+        assert!(notes.contains("from ours") && notes.contains("from theirs"));
+        assert_eq!(merged.attempt_log.len(), 2);
+        assert_eq!(merged.description.as_deref(), Some("Body text"));
+        assert!(fs::read_to_string(&a).unwrap().starts_with("---\n"));
+    }
+
+    #[test]
+    fn conflicting_fields_are_recorded() {
+        let dir = TempDir::new().unwrap();
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.title = "Ours".to_string();
+        let mut theirs = base.clone();
+        theirs.title = "Theirs".to_string();
+
+        let o = write(dir.path(), "O", &base);
+        let a = write(dir.path(), "A", &ours);
+        let b = write(dir.path(), "B", &theirs);
+
+        let clean = cmd_merge_driver(&o, &a, &b, Some(Path::new(".beans/3-merge-me.md"))).unwrap();
+        assert!(!clean);
+
+        let merged = Bean::from_file(&a).unwrap();
+        assert_eq!(merged.title, "Ours");
+        assert_eq!(merged.conflicts.len(), 1);
+        assert_eq!(merged.conflicts[0].field, "title");
+        assert_eq!(merged.conflicts[0].resolution, ConflictResolution::Pending);
+        assert_eq!(merged.conflicts[0].versions[1].value, "\"Theirs\"");
+    }
+
+    #[test]
+    fn rewritten_notes_are_appended_not_conflicted() {
+        let dir = TempDir::new().unwrap();
+        let base = base_bean();
+        let mut ours = base.clone();
+        ours.notes = Some("rewritten by ours".to_string());
+        let mut theirs = base.clone();
+        theirs.notes = Some("rewritten by theirs".to_string());
+
+        let o = write(dir.path(), "O", &base);
+        let a = write(dir.path(), "A", &ours);
+        let b = write(dir.path(), "B", &theirs);
+
+        assert!(cmd_merge_driver(&o, &a, &b, None).unwrap());
+        let merged = Bean::from_file(&a).unwrap();
+        assert_eq!(
+            merged.notes.as_deref(),
+            Some("rewritten by ours\n\nrewritten by theirs")
+        );
+        assert!(merged.conflicts.is_empty());
+    }
+
+    #[test]
+    fn added_on_both_sides_without_base() {
+        let dir = TempDir::new().unwrap();
+        let mut ours = Bean::new("4", "New");
+        ours.labels = vec!["a".to_string()];
+        let mut theirs = ours.clone();
+        theirs.labels = vec!["b".to_string()];
+
+        let o = dir.path().join("O");
+        fs::write(&o, "").unwrap();
+        let a = write(dir.path(), "A", &ours);
+        let b = write(dir.path(), "B", &theirs);
+
+        assert!(cmd_merge_driver(&o, &a, &b, Some(Path::new(".beans/4-new.md"))).unwrap());
+        let merged = Bean::from_file(&a).unwrap();
+        assert_eq!(merged.labels, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn index_is_regenerated_from_bean_files() {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        Bean::new("1", "One")
+            .to_file(beans_dir.join("1-one.md"))
+            .unwrap();
+        Bean::new("2", "Two")
+            .to_file(beans_dir.join("2-two.md"))
+            .unwrap();
+
+        let o = dir.path().join("O");
+        let a = dir.path().join("A");
+        let b = dir.path().join("B");
+        fs::write(&o, "beans: []\n").unwrap();
+        fs::write(&a, "<<<<<<< garbage").unwrap();
+        fs::write(&b, "beans: []\n").unwrap();
+
+        let index_path = beans_dir.join("index.yaml");
+        assert!(cmd_merge_driver(&o, &a, &b, Some(&index_path)).unwrap());
+
+        let index: Index = serde_yml::from_str(&fs::read_to_string(&a).unwrap()).unwrap();
+        let ids: Vec<&str> = index.beans.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn append_notes_skips_shared_text() {
+        assert_eq!(
+            append_notes(Some("a\nb"), Some("a")),
+            Some("a\nb".to_string())
+        );
+        assert_eq!(append_notes(None, Some("t")), Some("t".to_string()));
+        assert_eq!(
+            append_notes(Some("o"), Some("t")),
+            Some("o\n\nt".to_string())
+        );
+    }
+
+    #[test]
+    fn install_writes_gitattributes_and_config() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(root)
+            .status()
+            .unwrap();
+        fs::write(root.join(".gitattributes"), "*.png binary").unwrap();
+
+        install_git_merge_driver(root).unwrap();
+        install_git_merge_driver(root).unwrap();
+
+        let attributes = fs::read_to_string(root.join(".gitattributes")).unwrap();
+        assert!(attributes.starts_with("*.png binary\n"));
+        for line in GITATTRIBUTES_LINES {
+            assert_eq!(attributes.matches(line).count(), 1);
+        }
+
+        let output = Command::new("git")
+            .args(["config", "merge.beans.driver"])
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "bn merge-driver %O %A %B %P"
+        );
+    }
+
+    #[test]
+    fn install_fails_outside_git_repo() {
+        let dir = TempDir::new().unwrap();
+        assert!(install_git_merge_driver(dir.path()).is_err());
+        assert!(!dir.path().join(".gitattributes").exists());
+    }
+}
@@ -15,17 +15,21 @@ pub mod init;
 pub mod interactive;
 pub mod list;
 pub mod locks;
+pub mod log;
 pub mod logs;
 pub mod move_beans;
 pub mod mcp;
 pub mod memory_context;
+pub mod merge_driver;
 pub mod plan;
 pub mod quick;
 
 pub mod recall;
 pub mod reopen;
+pub mod resolve;
 pub mod review;
 pub mod run;
+pub mod serve;
 pub mod show;
 pub mod stats;
 pub mod status;
@@ -36,6 +40,7 @@ pub mod trace;
 pub mod tree;
 pub mod trust;
 pub mod unarchive;
+pub mod undo;
 pub mod update;
 pub mod verify;
 
@@ -55,17 +60,21 @@ pub use graph::cmd_graph;
 pub use init::{cmd_init, InitArgs};
 pub use list::cmd_list;
 pub use locks::{cmd_locks, cmd_locks_clear};
+pub use log::cmd_log;
 pub use logs::cmd_logs;
 pub use move_beans::{cmd_move_from, cmd_move_to};
 pub use mcp::cmd_mcp_serve;
 pub use memory_context::cmd_memory_context;
+pub use merge_driver::cmd_merge_driver;
 pub use plan::cmd_plan;
 pub use quick::cmd_quick;
 
 pub use recall::cmd_recall;
 pub use reopen::cmd_reopen;
+pub use resolve::cmd_resolve;
 pub use review::{cmd_review, ReviewArgs};
 pub use run::cmd_run;
+pub use serve::cmd_serve;
 pub use show::cmd_show;
 pub use stats::cmd_stats;
 pub use status::cmd_status;
@@ -75,5 +84,6 @@ pub use trace::cmd_trace;
 pub use tree::cmd_tree;
 pub use trust::cmd_trust;
 pub use unarchive::cmd_unarchive;
+pub use undo::cmd_undo;
 pub use update::cmd_update;
 pub use verify::cmd_verify;
@@ -8,6 +8,7 @@ use chrono::Utc;
 use crate::bean::Bean;
 use crate::config::Config;
 use crate::discovery::find_bean_file;
+use crate::events::{self, Action, Operation};
 use crate::index::Index;
 
 /// Resolve a path to a `.beans/` directory.
@@ -70,6 +71,7 @@ fn move_beans(
 
     let mut id_map: HashMap<String, String> = HashMap::new();
     let mut source_files_to_remove: Vec<PathBuf> = Vec::new();
+    let mut moved: Vec<(Bean, Bean)> = Vec::new();
 
     for old_id in ids {
         // Find and load the bean from source
@@ -77,6 +79,7 @@ fn move_beans(
             .with_context(|| format!("Bean '{}' not found in {}", old_id, source_dir.display()))?;
         let mut bean = Bean::from_file(&source_path)
             .with_context(|| format!("Failed to load bean '{}' from source", old_id))?;
+        let before = bean.clone();
 
         // Assign a new ID in the destination
         let new_id = dest_config.increment_id().to_string();
@@ -103,6 +106,7 @@ fn move_beans(
         id_map.insert(old_id.clone(), new_id.clone());
 
         eprintln!("Moved {} → {} ({})", old_id, new_id, bean.title);
+        moved.push((before, bean));
     }
 
     // Save updated destination config (with incremented next_id)
@@ -116,6 +120,25 @@ fn move_beans(
             .with_context(|| format!("Failed to remove source file: {}", path.display()))?;
     }
 
+    // Both halves of the move share one operation, so `bn undo` in either
+    // project can find the other
+    let op = Operation::new();
+    for (before, after) in &moved {
+        let mut out = op.event(source_dir, Action::Move, &before.id, Some(before), None);
+        out.peer = Some(dest_canonical.clone());
+        out.note = Some(format!("moved to {} as {}", dest_dir.display(), after.id));
+        let _ = events::append(source_dir, &out);
+
+        let mut incoming = op.event(dest_dir, Action::Move, &after.id, None, Some(after));
+        incoming.peer = Some(source_canonical.clone());
+        incoming.note = Some(format!(
+            "moved from {} (was {})",
+            source_dir.display(),
+            before.id
+        ));
+        let _ = events::append(dest_dir, &incoming);
+    }
+
     // Rebuild both indices
     let dest_index = Index::build(dest_dir)?;
     dest_index.save(dest_dir)?;
@@ -208,6 +231,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
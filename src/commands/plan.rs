@@ -226,7 +226,7 @@ fn run_shell_command(cmd: &str, id: &str, auto: bool) -> Result<()> {
 }
 
 /// Build a rich decomposition prompt that embeds the core planning wisdom.
-fn build_decomposition_prompt(id: &str, bean: &Bean, strategy: Option<&str>) -> String {
+pub(crate) fn build_decomposition_prompt(id: &str, bean: &Bean, strategy: Option<&str>) -> String {
     let strategy_guidance = match strategy {
         Some("feature") | Some("by-feature") => {
             "Split by feature — each child is a vertical slice (types + impl + tests for one feature)."
@@ -1,5 +1,4 @@
 use std::path::Path;
-use std::process::Command as ShellCommand;
 
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
@@ -7,9 +6,11 @@ use chrono::Utc;
 use crate::bean::{validate_priority, Bean, OnFailAction, Status};
 use crate::commands::create::assign_child_id;
 use crate::config::Config;
-use crate::hooks::{execute_hook, HookEvent};
+use crate::events::{self, Action};
+use crate::hooks::{self, execute_hook, HookEvent};
 use crate::index::Index;
 use crate::project::suggest_verify_command;
+use crate::sandbox;
 use crate::util::title_to_slug;
 
 /// Arguments for quick-create command.
@@ -61,9 +62,7 @@ pub fn cmd_quick(beans_dir: &Path, args: QuickArgs) -> Result<()> {
 
             println!("Running verify (must fail): {}", verify_cmd);
 
-            let status = ShellCommand::new("sh")
-                .args(["-c", verify_cmd])
-                .current_dir(project_root)
+            let status = sandbox::shell(project_root, verify_cmd)
                 .status()
                 .with_context(|| format!("Failed to execute verify command: {}", verify_cmd))?;
 
@@ -172,6 +171,9 @@ pub fn cmd_quick(beans_dir: &Path, args: QuickArgs) -> Result<()> {
     // Write the bean file with naming convention: {id}-{slug}.md
     let bean_path = beans_dir.join(format!("{}-{}.md", bean_id, slug));
     bean.to_file(&bean_path)?;
+    events::record(beans_dir, Action::Create, &bean_id, None, Some(&bean));
+    // Commands typed in here are approved as the user wrote them
+    hooks::trust_bean_commands(project_dir, &bean);
 
     // Update the index by rebuilding from disk
     let index = Index::build(beans_dir)?;
@@ -232,6 +234,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(&beans_dir).unwrap();
 
@@ -3,65 +3,17 @@ use std::path::Path;
 use anyhow::Result;
 
 use crate::bean::{Bean, Status};
-use crate::discovery::{find_archived_bean, find_bean_file};
-use crate::index::Index;
+use crate::search::{parse_query, SearchIndex};
 
-/// Search beans by substring matching (MVP — no embeddings).
+/// Search beans with the full-text index.
 ///
-/// Searches title, description, notes, close_reason, and paths.
-/// Returns matching beans sorted by relevance (title match first, then recency).
+/// Searches title, description, notes, close_reason, paths, labels and
+/// attempt notes, with phrase (`"..."`), prefix (`pars*`), field
+/// (`title:parser`) and filter (`status:closed label:bug type:fact`) queries.
+/// Returns matching beans ranked by BM25 (title matches weigh most), then
+/// recency.
 pub fn cmd_recall(beans_dir: &Path, query: &str, all: bool, json: bool) -> Result<()> {
-    let query_lower = query.to_lowercase();
-    let index = Index::load_or_rebuild(beans_dir)?;
-
-    let mut matches: Vec<(Bean, u32)> = Vec::new(); // (bean, score)
-
-    // Search active beans
-    for entry in &index.beans {
-        if !all && entry.status == Status::Closed {
-            continue;
-        }
-
-        let bean_path = match find_bean_file(beans_dir, &entry.id) {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-
-        let bean = match Bean::from_file(&bean_path) {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-
-        if let Some(score) = score_match(&bean, &query_lower) {
-            matches.push((bean, score));
-        }
-    }
-
-    // Search archived beans too
-    if all {
-        let archived = Index::collect_archived(beans_dir).unwrap_or_default();
-        for entry in &archived {
-            let bean_path = match find_archived_bean(beans_dir, &entry.id) {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-
-            let bean = match Bean::from_file(&bean_path) {
-                Ok(b) => b,
-                Err(_) => continue,
-            };
-
-            if let Some(score) = score_match(&bean, &query_lower) {
-                matches.push((bean, score));
-            }
-        }
-    }
-
-    // Sort by score (descending), then by recency (descending)
-    matches.sort_by(|a, b| {
-        b.1.cmp(&a.1)
-            .then_with(|| b.0.updated_at.cmp(&a.0.updated_at))
-    });
+    let matches = search_beans(beans_dir, query, all)?;
 
     if json {
         let results: Vec<serde_json::Value> = matches
@@ -92,7 +44,10 @@ pub fn cmd_recall(beans_dir: &Path, query: &str, all: bool, json: bool) -> Resul
             } else {
                 match bean.status {
                     Status::Closed => "✓",
+                    Status::Cancelled => "✗",
                     Status::InProgress => "►",
+                    Status::InReview => "◐",
+                    Status::Blocked => "■",
                     Status::Open => "○",
                 }
             };
@@ -137,120 +92,104 @@ pub fn cmd_recall(beans_dir: &Path, query: &str, all: bool, json: bool) -> Resul
     Ok(())
 }
 
-/// Score how well a bean matches a query. Returns None if no match.
-fn score_match(bean: &Bean, query_lower: &str) -> Option<u32> {
-    let mut score = 0u32;
-
-    // Title match (highest weight)
-    if bean.title.to_lowercase().contains(query_lower) {
-        score += 10;
-    }
-
-    // Description match
-    if let Some(ref desc) = bean.description {
-        if desc.to_lowercase().contains(query_lower) {
-            score += 5;
-        }
-    }
-
-    // Notes match
-    if let Some(ref notes) = bean.notes {
-        if notes.to_lowercase().contains(query_lower) {
-            score += 3;
-        }
-    }
-
-    // Close reason match
-    if let Some(ref reason) = bean.close_reason {
-        if reason.to_lowercase().contains(query_lower) {
-            score += 3;
-        }
-    }
-
-    // Path match
-    for path in &bean.paths {
-        if path.to_lowercase().contains(query_lower) {
-            score += 4;
-            break;
-        }
-    }
-
-    // Labels match
-    for label in &bean.labels {
-        if label.to_lowercase().contains(query_lower) {
-            score += 2;
-            break;
-        }
-    }
-
-    // Attempt notes match (negative memory search)
-    for attempt in &bean.attempt_log {
-        if let Some(ref notes) = attempt.notes {
-            if notes.to_lowercase().contains(query_lower) {
-                score += 4;
-                break;
-            }
-        }
-    }
-
-    if score > 0 {
-        Some(score)
-    } else {
-        None
-    }
+/// Find beans matching `query`, best match first, with their BM25 scores.
+///
+/// Queries go through the full-text index in `.beans/search.json` (see
+/// [`crate::search`]), which is brought up to date first. Closed and
+/// archived beans are only searched when `all` is set or the query has a
+/// `status:` filter.
+pub fn search_beans(beans_dir: &Path, query: &str, all: bool) -> Result<Vec<(Bean, f64)>> {
+    let query = parse_query(query)?;
+    let index = SearchIndex::load_or_update(beans_dir)?;
+
+    Ok(index
+        .search(&query, all)
+        .into_iter()
+        .filter_map(|hit| {
+            let bean = Bean::from_file(beans_dir.join(&hit.path)).ok()?;
+            Some((bean, (hit.score * 1000.0).round() / 1000.0))
+        })
+        .collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::title_to_slug;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn search(beans: Vec<Bean>, query: &str) -> Vec<(String, f64)> {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        for bean in beans {
+            let path = beans_dir.join(format!("{}-{}.md", bean.id, title_to_slug(&bean.title)));
+            bean.to_file(path).unwrap();
+        }
+        search_beans(&beans_dir, query, false)
+            .unwrap()
+            .into_iter()
+            .map(|(bean, score)| (bean.id, score))
+            .collect()
+    }
 
-    fn make_bean(id: &str, title: &str) -> Bean {
-        Bean::new(id, title)
+    fn matches(bean: Bean, query: &str) -> bool {
+        !search(vec![bean], query).is_empty()
     }
 
     #[test]
-    fn score_match_title() {
-        let bean = make_bean("1", "Auth uses RS256");
-        assert!(score_match(&bean, "rs256").is_some());
-        assert!(score_match(&bean, "auth").is_some());
-        assert!(score_match(&bean, "xyz").is_none());
+    fn search_title() {
+        let bean = Bean::new("1", "Auth uses RS256");
+        assert!(matches(bean.clone(), "rs256"));
+        assert!(matches(bean.clone(), "auth"));
+        assert!(!matches(bean, "xyz"));
     }
 
     #[test]
-    fn score_match_description() {
-        let mut bean = make_bean("1", "Config");
+    fn search_description() {
+        let mut bean = Bean::new("1", "Config");
         bean.description = Some("Uses YAML format for configuration".to_string());
-        assert!(score_match(&bean, "yaml").is_some());
+        assert!(matches(bean, "yaml"));
     }
 
     #[test]
-    fn score_match_paths() {
-        let mut bean = make_bean("1", "Config");
+    fn search_paths() {
+        let mut bean = Bean::new("1", "Config");
         bean.paths = vec!["src/auth.rs".to_string()];
-        assert!(score_match(&bean, "auth").is_some());
+        assert!(matches(bean.clone(), "auth"));
+        assert!(matches(bean, "src/auth.rs"));
     }
 
     #[test]
-    fn score_match_notes() {
-        let mut bean = make_bean("1", "Task");
+    fn search_notes() {
+        let mut bean = Bean::new("1", "Task");
         bean.notes = Some("Blocked by database migration".to_string());
-        assert!(score_match(&bean, "migration").is_some());
+        assert!(matches(bean, "migration"));
     }
 
     #[test]
-    fn score_match_close_reason() {
-        let mut bean = make_bean("1", "Task");
+    fn search_close_reason() {
+        let mut bean = Bean::new("1", "Task");
         bean.close_reason = Some("Superseded by new approach".to_string());
-        assert!(score_match(&bean, "superseded").is_some());
+        assert!(matches(bean, "superseded"));
     }
 
     #[test]
     fn title_scores_higher_than_description() {
-        let mut bean = make_bean("1", "Auth module");
-        bean.description = Some("Auth is important".to_string());
+        let in_title = Bean::new("1", "Auth module");
+        let mut in_description = Bean::new("2", "Login module");
+        in_description.description = Some("Auth is important".to_string());
 
-        let score = score_match(&bean, "auth").unwrap();
-        // Title (10) + Description (5) = 15
-        assert_eq!(score, 15);
+        let results = search(vec![in_description, in_title], "auth");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "1");
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn invalid_status_filter_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        assert!(search_beans(dir.path(), "status:finished", true).is_err());
     }
 }
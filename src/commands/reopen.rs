@@ -5,11 +5,12 @@ use chrono::Utc;
 
 use crate::bean::Bean;
 use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
 use crate::index::Index;
 
 /// Reopen a closed bean.
 ///
-/// Sets status=open, clears closed_at and close_reason.
+/// Sets status=open, clears closed_at, close_reason and blocked_reason.
 /// Updates updated_at and rebuilds index.
 pub fn cmd_reopen(beans_dir: &Path, id: &str) -> Result<()> {
     let bean_path =
@@ -17,14 +18,17 @@ pub fn cmd_reopen(beans_dir: &Path, id: &str) -> Result<()> {
 
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let before = bean.clone();
 
     bean.status = crate::bean::Status::Open;
     bean.closed_at = None;
     bean.close_reason = None;
+    bean.blocked_reason = None;
     bean.updated_at = Utc::now();
 
     bean.to_file(&bean_path)
         .with_context(|| format!("Failed to save bean: {}", id))?;
+    events::record(beans_dir, Action::Reopen, id, Some(&before), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
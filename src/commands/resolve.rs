@@ -0,0 +1,294 @@
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::bean::{Bean, ConflictResolution, FieldConflict};
+use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
+use crate::index::Index;
+
+/// Resolve concurrent-edit conflicts recorded on a bean.
+///
+/// `take` selects the version to keep: `ours` (the value that was kept when the
+/// conflict was detected), `theirs` (the value that was overwritten), or a
+/// version index. Without `field`, the choice applies to every pending
+/// conflict. Without `take`, prompts for each conflict when stdin is a TTY.
+pub fn cmd_resolve(
+    beans_dir: &Path,
+    id: &str,
+    field: Option<&str>,
+    take: Option<&str>,
+) -> Result<()> {
+    let bean_path =
+        find_bean_file(beans_dir, id).with_context(|| format!("Bean not found: {}", id))?;
+
+    let mut bean =
+        Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let before = bean.clone();
+
+    let pending: Vec<usize> = bean
+        .conflicts
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.resolution == ConflictResolution::Pending)
+        .filter(|(_, c)| field.is_none_or(|f| c.field == f))
+        .map(|(i, _)| i)
+        .collect();
+
+    if pending.is_empty() {
+        match field {
+            Some(f) => return Err(anyhow!("Bean {} has no pending conflict on '{}'", id, f)),
+            None => {
+                println!("Bean {} has no pending conflicts.", id);
+                return Ok(());
+            }
+        }
+    }
+
+    let mut choices = Vec::with_capacity(pending.len());
+    match take {
+        Some(take) => {
+            for &i in &pending {
+                choices.push((i, parse_choice(take, &bean.conflicts[i])?));
+            }
+        }
+        None if std::io::stdin().is_terminal() => {
+            for &i in &pending {
+                choices.push((i, prompt_choice(&bean.conflicts[i])?));
+            }
+        }
+        None => {
+            for &i in &pending {
+                print!("{}", format_conflict(&bean.conflicts[i]));
+            }
+            return Err(anyhow!(
+                "Choose a version with: bn resolve {} [--field <name>] --take ours|theirs",
+                id
+            ));
+        }
+    }
+
+    for (i, choice) in choices {
+        let conflict = &bean.conflicts[i];
+        let field_name = conflict.field.clone();
+        let value = conflict.versions[choice].value.clone();
+        bean.apply_value(&field_name, &value)
+            .with_context(|| format!("Failed to apply value for '{}'", field_name))?;
+        bean.conflicts[i].resolution = ConflictResolution::Resolved;
+        println!("Resolved {} on bean {}: {}", field_name, id, value);
+    }
+
+    bean.conflicts.retain(|c| c.resolution == ConflictResolution::Pending);
+    bean.updated_at = Utc::now();
+
+    bean.to_file(&bean_path)
+        .with_context(|| format!("Failed to save bean: {}", id))?;
+    events::record_with_note(
+        beans_dir,
+        Action::Update,
+        id,
+        Some(&before),
+        Some(&bean),
+        Some("resolve conflicts".to_string()),
+    );
+
+    // Rebuild index
+    let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
+    index
+        .save(beans_dir)
+        .with_context(|| "Failed to save index")?;
+
+    Ok(())
+}
+
+/// Map `ours`/`theirs`/an index to a version index of `conflict`.
+fn parse_choice(take: &str, conflict: &FieldConflict) -> Result<usize> {
+    let idx = match take {
+        "ours" => 0,
+        "theirs" => 1,
+        other => other
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Invalid --take '{}': use ours, theirs, or an index", other))?,
+    };
+    if idx >= conflict.versions.len() {
+        return Err(anyhow!(
+            "Conflict on '{}' has {} version(s); cannot take {}",
+            conflict.field,
+            conflict.versions.len(),
+            take
+        ));
+    }
+    Ok(idx)
+}
+
+fn prompt_choice(conflict: &FieldConflict) -> Result<usize> {
+    use dialoguer::theme::ColorfulTheme;
+    use dialoguer::Select;
+
+    let items: Vec<String> = conflict
+        .versions
+        .iter()
+        .map(|v| {
+            format!(
+                "{} ({} @ {})",
+                v.value,
+                v.agent,
+                v.timestamp.format("%Y-%m-%d %H:%M:%S")
+            )
+        })
+        .collect();
+
+    let idx = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Field '{}' has competing values", conflict.field))
+        .items(&items)
+        .default(0)
+        .interact()?;
+    Ok(idx)
+}
+
+/// Render a conflict and its versions, one per line.
+pub fn format_conflict(conflict: &FieldConflict) -> String {
+    let mut out = format!("! Field '{}' has competing values:\n", conflict.field);
+    for (i, version) in conflict.versions.iter().enumerate() {
+        let label = match i {
+            0 => " ours",
+            1 => " theirs",
+            _ => "",
+        };
+        out.push_str(&format!(
+            "  [{}]{} {} ({} @ {})\n",
+            i,
+            label,
+            version.value,
+            version.agent,
+            version.timestamp.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::{ConflictVersion, Status};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_test_beans_dir() -> (TempDir, std::path::PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        (dir, beans_dir)
+    }
+
+    fn version(value: &str, agent: &str) -> ConflictVersion {
+        ConflictVersion {
+            value: value.to_string(),
+            agent: agent.to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn conflicted_bean(beans_dir: &Path) {
+        let mut bean = Bean::new("1", "Task");
+        bean.status = Status::Closed;
+        bean.priority = 1;
+        bean.conflicts = vec![
+            FieldConflict {
+                field: "status".to_string(),
+                versions: vec![version("\"closed\"", "a"), version("\"in_progress\"", "b")],
+                resolution: ConflictResolution::Pending,
+            },
+            FieldConflict {
+                field: "priority".to_string(),
+                versions: vec![version("1", "a"), version("3", "b")],
+                resolution: ConflictResolution::Pending,
+            },
+        ];
+        bean.to_file(beans_dir.join("1-task.md")).unwrap();
+    }
+
+    #[test]
+    fn resolve_single_field_theirs() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        conflicted_bean(&beans_dir);
+
+        cmd_resolve(&beans_dir, "1", Some("status"), Some("theirs")).unwrap();
+
+        let bean = Bean::from_file(beans_dir.join("1-task.md")).unwrap();
+        assert_eq!(bean.status, Status::InProgress);
+        assert_eq!(bean.priority, 1);
+        assert_eq!(bean.conflicts.len(), 1);
+        assert_eq!(bean.conflicts[0].field, "priority");
+    }
+
+    #[test]
+    fn resolve_all_fields_ours() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        conflicted_bean(&beans_dir);
+
+        cmd_resolve(&beans_dir, "1", None, Some("ours")).unwrap();
+
+        let bean = Bean::from_file(beans_dir.join("1-task.md")).unwrap();
+        assert_eq!(bean.status, Status::Closed);
+        assert_eq!(bean.priority, 1);
+        assert!(bean.conflicts.is_empty());
+    }
+
+    #[test]
+    fn resolve_by_index() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        conflicted_bean(&beans_dir);
+
+        cmd_resolve(&beans_dir, "1", Some("priority"), Some("1")).unwrap();
+
+        let bean = Bean::from_file(beans_dir.join("1-task.md")).unwrap();
+        assert_eq!(bean.priority, 3);
+    }
+
+    #[test]
+    fn resolve_unknown_field_errors() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        conflicted_bean(&beans_dir);
+
+        let result = cmd_resolve(&beans_dir, "1", Some("title"), Some("ours"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolve_invalid_take_errors() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        conflicted_bean(&beans_dir);
+
+        assert!(cmd_resolve(&beans_dir, "1", Some("status"), Some("mine")).is_err());
+        assert!(cmd_resolve(&beans_dir, "1", Some("status"), Some("5")).is_err());
+
+        let bean = Bean::from_file(beans_dir.join("1-task.md")).unwrap();
+        assert_eq!(bean.conflicts.len(), 2);
+    }
+
+    #[test]
+    fn resolve_without_conflicts_is_noop() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        Bean::new("1", "Task")
+            .to_file(beans_dir.join("1-task.md"))
+            .unwrap();
+
+        cmd_resolve(&beans_dir, "1", None, Some("ours")).unwrap();
+    }
+
+    #[test]
+    fn format_conflict_labels_versions() {
+        let conflict = FieldConflict {
+            field: "status".to_string(),
+            versions: vec![version("\"closed\"", "a"), version("\"open\"", "b")],
+            resolution: ConflictResolution::Pending,
+        };
+        let out = format_conflict(&conflict);
+        assert!(out.contains("Field 'status'"));
+        assert!(out.contains("[0] ours \"closed\" (a @"));
+        assert!(out.contains("[1] theirs \"open\" (b @"));
+    }
+}
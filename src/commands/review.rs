@@ -15,7 +15,7 @@
 //! ## Verdicts
 //! - `approve` — implementation correct; adds `reviewed` label
 //! - `request-changes` — issues found; reopens bean with notes, adds `review-failed`
-//! - `flag` — needs human attention; adds `needs-human-review` label, stays closed
+//! - `flag` — needs human attention; adds `needs-human-review` label and moves the bean to `in_review`
 
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -25,8 +25,10 @@ use chrono::Utc;
 
 use crate::bean::{Bean, Status};
 use crate::config::Config;
-use crate::discovery::find_bean_file;
-use crate::index::Index;
+use crate::discovery::{find_archived_bean, find_bean_file};
+use crate::events::{self, Action};
+use crate::index::{ArchiveIndex, Index};
+use crate::util::title_to_slug;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -62,6 +64,7 @@ pub fn cmd_review(beans_dir: &Path, args: ReviewArgs) -> Result<()> {
     let config = Config::load_with_extends(beans_dir)?;
 
     let bean_path = find_bean_file(beans_dir, &args.id)
+        .or_else(|_| find_archived_bean(beans_dir, &args.id))
         .with_context(|| format!("Bean not found: {}", args.id))?;
     let bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", args.id))?;
@@ -263,6 +266,12 @@ pub fn apply_verdict(
 ) -> Result<()> {
     let mut bean =
         Bean::from_file(bean_path).with_context(|| format!("Failed to reload bean: {}", id))?;
+    let before = bean.clone();
+    let note = match verdict {
+        ReviewVerdict::Approve => "review: approve",
+        ReviewVerdict::RequestChanges(_) => "review: request-changes",
+        ReviewVerdict::Flag(_) => "review: flag",
+    };
 
     match verdict {
         ReviewVerdict::Approve => {
@@ -272,9 +281,13 @@ pub fn apply_verdict(
             }
             // Remove review-failed if it was set from a previous review cycle
             bean.labels.retain(|l| l != "review-failed");
+            // A bean held for human review is done once approved
+            if bean.status == Status::InReview {
+                bean.status = Status::Closed;
+                bean.closed_at.get_or_insert_with(Utc::now);
+                bean.labels.retain(|l| l != "needs-human-review");
+            }
             bean.updated_at = Utc::now();
-            bean.to_file(bean_path)
-                .with_context(|| format!("Failed to save bean: {}", id))?;
         }
 
         ReviewVerdict::RequestChanges(ref notes) => {
@@ -303,8 +316,6 @@ pub fn apply_verdict(
             bean.closed_at = None;
             bean.close_reason = None;
             bean.updated_at = Utc::now();
-            bean.to_file(bean_path)
-                .with_context(|| format!("Failed to save bean: {}", id))?;
         }
 
         ReviewVerdict::Flag(ref notes) => {
@@ -321,12 +332,30 @@ pub fn apply_verdict(
                 Some(ref mut existing) => existing.push_str(&review_note),
                 None => bean.notes = Some(review_note),
             }
+            // Hold the bean in review until a human approves, closes, or reopens it
+            bean.status = Status::InReview;
             bean.updated_at = Utc::now();
-            bean.to_file(bean_path)
-                .with_context(|| format!("Failed to save bean: {}", id))?;
         }
     }
 
+    // A reopened or flagged bean must be back in .beans/ to be listed again
+    let bean_path = if bean.is_archived && bean.status != Status::Closed {
+        restore_from_archive(beans_dir, &mut bean, bean_path)?
+    } else {
+        bean_path.clone()
+    };
+
+    bean.to_file(&bean_path)
+        .with_context(|| format!("Failed to save bean: {}", id))?;
+    events::record_with_note(
+        beans_dir,
+        Action::Update,
+        id,
+        Some(&before),
+        Some(&bean),
+        Some(note.to_string()),
+    );
+
     // Rebuild index so status/labels are reflected immediately
     let index = Index::build(beans_dir).context("Failed to rebuild index after review")?;
     index
@@ -336,6 +365,30 @@ pub fn apply_verdict(
     Ok(())
 }
 
+/// Move an archived bean back to `.beans/<id>-<slug>.md` and drop it from the
+/// archive index. Returns the new path.
+fn restore_from_archive(
+    beans_dir: &Path,
+    bean: &mut Bean,
+    archived_path: &Path,
+) -> Result<PathBuf> {
+    let slug = bean
+        .slug
+        .clone()
+        .unwrap_or_else(|| title_to_slug(&bean.title));
+    let target = beans_dir.join(format!("{}-{}.md", bean.id, slug));
+    std::fs::rename(archived_path, &target)
+        .with_context(|| format!("Failed to move bean {} out of the archive", bean.id))?;
+    bean.is_archived = false;
+
+    let mut archive_index =
+        ArchiveIndex::load(beans_dir).unwrap_or(ArchiveIndex { beans: Vec::new() });
+    archive_index.remove(&bean.id);
+    let _ = archive_index.save(beans_dir);
+
+    Ok(target)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -462,6 +515,28 @@ mod tests {
         assert!(updated.notes.unwrap().contains("Review failed"));
     }
 
+    #[test]
+    fn apply_verdict_records_an_event() {
+        let (_dir, beans_dir) = setup();
+        let mut bean = Bean::new("1", "Test bean");
+        bean.status = Status::Closed;
+        let path = beans_dir.join("1-test-bean.md");
+        bean.to_file(&path).unwrap();
+
+        apply_verdict(
+            &beans_dir,
+            "1",
+            &path,
+            ReviewVerdict::RequestChanges("Fix it".to_string()),
+        )
+        .unwrap();
+
+        let events = crate::events::load_events(&beans_dir);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, Action::Update);
+        assert_eq!(events[0].note.as_deref(), Some("review: request-changes"));
+    }
+
     #[test]
     fn apply_verdict_request_changes_injects_notes() {
         let (_dir, beans_dir) = setup();
@@ -488,7 +563,7 @@ mod tests {
     fn apply_verdict_flag_adds_needs_human_review_label() {
         let (_dir, beans_dir) = setup();
         let mut bean = Bean::new("1", "Test bean");
-        // Flag keeps bean in its current state — test with Closed to show it stays closed
+        // Flag moves a closed bean into review rather than reopening it
         bean.status = Status::Closed;
         bean.closed_at = Some(Utc::now());
         let slug = title_to_slug(&bean.title);
@@ -505,7 +580,54 @@ mod tests {
 
         let updated = Bean::from_file(&path).unwrap();
         assert!(updated.labels.contains(&"needs-human-review".to_string()));
-        assert_eq!(updated.status, Status::Closed); // not reopened — stays as-is
+        assert_eq!(updated.status, Status::InReview); // held for review, not reopened
+        assert!(updated.closed_at.is_some());
+    }
+
+    #[test]
+    fn apply_verdict_flag_restores_archived_bean() {
+        let (_dir, beans_dir) = setup();
+        let archive_dir = beans_dir.join("archive/2026/01");
+        fs::create_dir_all(&archive_dir).unwrap();
+        let mut bean = Bean::new("1", "Test bean");
+        bean.status = Status::Closed;
+        bean.is_archived = true;
+        let archived = archive_dir.join("1-test-bean.md");
+        bean.to_file(&archived).unwrap();
+
+        apply_verdict(
+            &beans_dir,
+            "1",
+            &archived,
+            ReviewVerdict::Flag("Check this".to_string()),
+        )
+        .unwrap();
+
+        assert!(!archived.exists());
+        let updated = Bean::from_file(beans_dir.join("1-test-bean.md")).unwrap();
+        assert_eq!(updated.status, Status::InReview);
+        assert!(!updated.is_archived);
+        let index = Index::load(&beans_dir).unwrap();
+        assert!(index.beans.iter().any(|b| b.id == "1"));
+    }
+
+    #[test]
+    fn apply_verdict_approve_closes_bean_in_review() {
+        let (_dir, beans_dir) = setup();
+        let mut bean = Bean::new("1", "Test bean");
+        bean.status = Status::InReview;
+        bean.labels = vec!["needs-human-review".to_string()];
+        let slug = title_to_slug(&bean.title);
+        let path = beans_dir.join(format!("1-{}.md", slug));
+        bean.to_file(&path).unwrap();
+
+        apply_verdict(&beans_dir, "1", &path, ReviewVerdict::Approve).unwrap();
+
+        let updated = Bean::from_file(&path).unwrap();
+        assert_eq!(updated.status, Status::Closed);
+        assert!(updated.closed_at.is_some());
+        assert!(!updated.labels.contains(&"needs-human-review".to_string()));
+        assert!(updated.labels.contains(&"reviewed".to_string()));
     }
 
     #[test]
@@ -0,0 +1,402 @@
+//! Token and cost budgets for `bn run`.
+//!
+//! Limits come from config (`max_cost_per_bean`, `max_tokens_per_run`, ...).
+//! Direct-mode agents report usage as they stream events: an agent that
+//! reaches a limit is killed and its bean gets a `cancelled` history record,
+//! and once the run or day budget is spent no further beans are dispatched.
+//! Template-mode agents (`run:` commands) report no usage, so budgets are not
+//! enforced for them; `bn run` warns when limits are set in that mode.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::bean::{merge, Bean, RunRecord, RunResult};
+use crate::config::{resolve_identity, Config};
+use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
+use crate::history;
+
+/// Prefix of the error and history reason for an agent killed over budget.
+pub(super) const BUDGET_EXCEEDED: &str = "Budget exceeded";
+
+/// Which budget a limit belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    /// A single agent working on one bean.
+    Bean,
+    /// All agents of one `bn run` pass.
+    Run,
+    /// Everything spent today (UTC), including earlier runs.
+    Day,
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetScope::Bean => write!(f, "bean"),
+            BudgetScope::Run => write!(f, "run"),
+            BudgetScope::Day => write!(f, "day"),
+        }
+    }
+}
+
+/// Tokens and dollars spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Spend {
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+impl Spend {
+    fn plus(self, other: Spend) -> Spend {
+        Spend {
+            tokens: self.tokens + other.tokens,
+            cost: self.cost + other.cost,
+        }
+    }
+}
+
+/// A token and/or cost ceiling. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limit {
+    pub tokens: Option<u64>,
+    pub cost: Option<f64>,
+}
+
+impl Limit {
+    fn is_unlimited(&self) -> bool {
+        self.tokens.is_none() && self.cost.is_none()
+    }
+
+    /// Describe how `spend` reaches this limit, if it does.
+    fn check(&self, scope: BudgetScope, spend: Spend) -> Option<BudgetExceeded> {
+        if let Some(max) = self.cost {
+            if spend.cost >= max {
+                return Some(BudgetExceeded {
+                    scope,
+                    reason: format!(
+                        "{} cost ${:.2} reached limit ${:.2}",
+                        scope, spend.cost, max
+                    ),
+                });
+            }
+        }
+        if let Some(max) = self.tokens {
+            if spend.tokens >= max {
+                return Some(BudgetExceeded {
+                    scope,
+                    reason: format!("{} tokens {} reached limit {}", scope, spend.tokens, max),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// A budget limit that has been reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    pub reason: String,
+}
+
+/// Live spend tracking for one run, shared by all of its agent threads.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BudgetTracker {
+    bean: Limit,
+    run: Limit,
+    day: Limit,
+    /// Spent today before this run started (from `agent_history.jsonl`).
+    earlier_today: Spend,
+    /// Spent by this run so far.
+    spent: Arc<Mutex<Spend>>,
+}
+
+impl BudgetTracker {
+    /// Build a tracker from the configured limits.
+    pub fn new(beans_dir: &Path, config: &Config) -> Self {
+        let day = Limit {
+            tokens: config.max_tokens_per_day,
+            cost: config.max_cost_per_day,
+        };
+        let earlier_today = if day.is_unlimited() {
+            Spend::default()
+        } else {
+            spent_on_day(beans_dir, Utc::now())
+        };
+        Self {
+            bean: Limit {
+                tokens: config.max_tokens_per_bean,
+                cost: config.max_cost_per_bean,
+            },
+            run: Limit {
+                tokens: config.max_tokens_per_run,
+                cost: config.max_cost_per_run,
+            },
+            day,
+            earlier_today,
+            spent: Arc::default(),
+        }
+    }
+
+    /// Add usage reported by an agent.
+    pub fn record(&self, delta: Spend) {
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        *spent = spent.plus(delta);
+    }
+
+    /// Total spent by this run so far.
+    pub fn spent(&self) -> Spend {
+        *self.spent.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The run or day limit that has been reached, if any.
+    pub fn exhausted(&self) -> Option<BudgetExceeded> {
+        let spent = self.spent();
+        let today = self.earlier_today.plus(spent);
+        self.run
+            .check(BudgetScope::Run, spent)
+            .or_else(|| self.day.check(BudgetScope::Day, today))
+    }
+
+    /// Check a running agent's own usage against the per-bean limit, then
+    /// the run and day limits.
+    pub fn check_agent(&self, agent: Spend) -> Option<BudgetExceeded> {
+        self.bean
+            .check(BudgetScope::Bean, agent)
+            .or_else(|| self.exhausted())
+    }
+}
+
+/// Whether config sets any token or cost limit.
+pub(super) fn is_configured(config: &Config) -> bool {
+    let tokens = [
+        config.max_tokens_per_bean,
+        config.max_tokens_per_run,
+        config.max_tokens_per_day,
+    ];
+    let costs = [
+        config.max_cost_per_bean,
+        config.max_cost_per_run,
+        config.max_cost_per_day,
+    ];
+    tokens.iter().any(Option::is_some) || costs.iter().any(Option::is_some)
+}
+
+/// Sum the usage recorded in `agent_history.jsonl` on the UTC day of `now`.
+fn spent_on_day(beans_dir: &Path, now: DateTime<Utc>) -> Spend {
+    let today = now.date_naive();
+    history::load_history(beans_dir)
+        .iter()
+        .filter(|entry| {
+            DateTime::parse_from_rfc3339(&entry.timestamp)
+                .map(|ts| ts.with_timezone(&Utc).date_naive() == today)
+                .unwrap_or(false)
+        })
+        .fold(Spend::default(), |total, entry| {
+            total.plus(Spend {
+                tokens: entry.tokens,
+                cost: entry.cost,
+            })
+        })
+}
+
+/// Record a budget kill in the bean's history as a `cancelled` run.
+pub(super) fn record_cancelled(
+    beans_dir: &Path,
+    bean_id: &str,
+    agent: &str,
+    started_at: DateTime<Utc>,
+    spend: Spend,
+    reason: &str,
+) -> anyhow::Result<()> {
+    let path = find_bean_file(beans_dir, bean_id)?;
+    let mut bean = Bean::from_file(&path)?;
+    let base = bean.clone();
+    let finished_at = Utc::now();
+    bean.history.push(RunRecord {
+        attempt: bean.attempts + 1,
+        started_at,
+        finished_at: Some(finished_at),
+        duration_secs: Some((finished_at - started_at).num_milliseconds() as f64 / 1000.0),
+        agent: Some(agent.to_string()),
+        result: RunResult::Cancelled,
+        exit_code: None,
+        tokens: Some(spend.tokens),
+        cost: Some(spend.cost),
+        output_snippet: None,
+        reason: Some(format!("{}: {}", BUDGET_EXCEEDED, reason)),
+        cached: false,
+        tests: None,
+    });
+    bean.updated_at = finished_at;
+
+    let identity = resolve_identity(beans_dir);
+    let conflicts = merge::save_merged(&mut bean, &base, &path, identity.as_deref())?;
+    merge::warn_conflicts(&mut std::io::stderr(), bean_id, &conflicts);
+    events::record_with_note(
+        beans_dir,
+        Action::Update,
+        bean_id,
+        Some(&base),
+        Some(&bean),
+        Some(format!("{}: {}", BUDGET_EXCEEDED, reason)),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{append_history, AgentHistoryEntry};
+    use tempfile::TempDir;
+
+    fn spend(tokens: u64, cost: f64) -> Spend {
+        Spend { tokens, cost }
+    }
+
+    fn history_entry(timestamp: &str, tokens: u64, cost: f64) -> AgentHistoryEntry {
+        AgentHistoryEntry {
+            bean_id: "1".to_string(),
+            title: "Task".to_string(),
+            attempt: 1,
+            success: true,
+            duration_secs: 10,
+            tokens,
+            cost,
+            tool_count: 0,
+            error: None,
+            model: "default".to_string(),
+            timestamp: timestamp.to_string(),
+        }
+    }
+
+    #[test]
+    fn limit_check_reports_cost_then_tokens() {
+        let limit = Limit {
+            tokens: Some(1000),
+            cost: Some(1.0),
+        };
+        assert!(limit.check(BudgetScope::Bean, spend(999, 0.99)).is_none());
+
+        let hit = limit.check(BudgetScope::Bean, spend(10, 1.5)).unwrap();
+        assert_eq!(hit.scope, BudgetScope::Bean);
+        assert_eq!(hit.reason, "bean cost $1.50 reached limit $1.00");
+
+        let hit = limit.check(BudgetScope::Run, spend(1000, 0.1)).unwrap();
+        assert_eq!(hit.reason, "run tokens 1000 reached limit 1000");
+    }
+
+    #[test]
+    fn unlimited_tracker_never_trips() {
+        let tracker = BudgetTracker::default();
+        tracker.record(spend(u64::MAX / 2, 1e9));
+        assert!(tracker.exhausted().is_none());
+        assert!(tracker.check_agent(spend(u64::MAX / 2, 1e9)).is_none());
+    }
+
+    #[test]
+    fn run_budget_is_shared_across_clones() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            max_cost_per_run: Some(1.0),
+            max_cost_per_bean: Some(0.8),
+            ..Config::default()
+        };
+        let tracker = BudgetTracker::new(dir.path(), &config);
+        let other = tracker.clone();
+
+        tracker.record(spend(100, 0.6));
+        assert!(other.check_agent(spend(100, 0.6)).is_none());
+        other.record(spend(100, 0.5));
+
+        assert_eq!(tracker.spent(), spend(200, 1.1));
+        let hit = tracker.exhausted().unwrap();
+        assert_eq!(hit.scope, BudgetScope::Run);
+        // A fresh agent is stopped too once the run budget is gone.
+        assert_eq!(
+            tracker.check_agent(spend(0, 0.0)).unwrap().scope,
+            BudgetScope::Run
+        );
+    }
+
+    #[test]
+    fn bean_limit_takes_precedence_for_agents() {
+        let config = Config {
+            max_tokens_per_bean: Some(500),
+            max_tokens_per_run: Some(10_000),
+            ..Config::default()
+        };
+        let tracker = BudgetTracker::new(Path::new("/nonexistent"), &config);
+        let hit = tracker.check_agent(spend(600, 0.0)).unwrap();
+        assert_eq!(hit.scope, BudgetScope::Bean);
+        assert!(tracker.exhausted().is_none());
+    }
+
+    #[test]
+    fn is_configured_sees_any_limit() {
+        assert!(!is_configured(&Config::default()));
+        let config = Config {
+            max_tokens_per_day: Some(1000),
+            ..Config::default()
+        };
+        assert!(is_configured(&config));
+    }
+
+    #[test]
+    fn day_budget_counts_todays_history_only() {
+        let dir = TempDir::new().unwrap();
+        let now = Utc::now();
+        let yesterday = (now - chrono::Duration::days(1)).to_rfc3339();
+        append_history(dir.path(), &history_entry(&now.to_rfc3339(), 300, 2.0));
+        append_history(dir.path(), &history_entry(&yesterday, 900, 9.0));
+        append_history(dir.path(), &history_entry("not a date", 900, 9.0));
+
+        assert_eq!(spent_on_day(dir.path(), now), spend(300, 2.0));
+
+        let config = Config {
+            max_cost_per_day: Some(3.0),
+            ..Config::default()
+        };
+        let tracker = BudgetTracker::new(dir.path(), &config);
+        assert!(tracker.exhausted().is_none());
+        tracker.record(spend(50, 1.0));
+        assert_eq!(tracker.exhausted().unwrap().scope, BudgetScope::Day);
+    }
+
+    #[test]
+    fn record_cancelled_appends_history() {
+        let dir = TempDir::new().unwrap();
+        let bean = Bean::new("7", "Runaway");
+        bean.to_file(dir.path().join("7-runaway.md")).unwrap();
+
+        record_cancelled(
+            dir.path(),
+            "7",
+            "claude",
+            Utc::now(),
+            spend(1200, 1.25),
+            "bean cost $1.25 reached limit $1.00",
+        )
+        .unwrap();
+
+        let bean = Bean::from_file(dir.path().join("7-runaway.md")).unwrap();
+        assert_eq!(bean.history.len(), 1);
+        let record = &bean.history[0];
+        assert_eq!(record.result, RunResult::Cancelled);
+        assert_eq!(record.tokens, Some(1200));
+        assert_eq!(record.agent.as_deref(), Some("claude"));
+        assert_eq!(
+            record.reason.as_deref(),
+            Some("Budget exceeded: bean cost $1.25 reached limit $1.00")
+        );
+
+        let events = events::load_events(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, Action::Update);
+        assert_eq!(events[0].id, "7");
+    }
+}
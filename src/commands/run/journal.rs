@@ -0,0 +1,601 @@
+//! Run journal — persisted state of a `bn run` so it can be resumed.
+//!
+//! Every dispatching `bn run` writes `.beans/runs/<run-id>.json` with the
+//! dispatch plan and each bean's state, agent PID, log path and usage. The
+//! journal is rewritten as agents start and finish, so if the `bn run`
+//! process dies (laptop sleep, SSH drop) `bn run --resume <run-id>` can
+//! reattach to agents that are still alive, re-queue the ones that died, and
+//! continue with the rest of the plan.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bean::{Bean, Status};
+use crate::commands::agents::{process_start_time, same_process_alive};
+use crate::discovery::{find_archived_bean, find_bean_file};
+use crate::util::{atomic_write, ensure_gitignored};
+
+use super::plan::DispatchPlan;
+use super::AgentResult;
+
+/// Directory under `.beans/` holding run journals.
+const RUNS_DIR: &str = "runs";
+
+/// Lifecycle of a bean within a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeanRunState {
+    /// Planned but not yet started.
+    Pending,
+    /// An agent was spawned and had not finished when last recorded.
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One bean's entry in a run journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalBean {
+    pub id: String,
+    pub title: String,
+    /// Wave the bean was planned in (1-based).
+    pub round: usize,
+    pub state: BeanRunState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Start time of the agent process (see [`process_start_time`]), so a
+    /// reused PID is not mistaken for the agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid_start: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Persisted state of a single `bn run` dispatch pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunJournal {
+    pub id: String,
+    /// PID of the `bn run` process that owns the journal.
+    pub pid: u32,
+    /// Start time of the `bn run` process (see [`process_start_time`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid_start: Option<u64>,
+    /// Bean ID passed to `bn run`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set once the run finished normally; finished runs cannot be resumed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub beans: Vec<JournalBean>,
+}
+
+/// What `reconcile` found when resuming a run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Reconciled {
+    /// Beans whose agent is still alive: (bean id, pid).
+    pub surviving: Vec<(String, u32)>,
+    /// Beans whose agent died before finishing; released and queued again.
+    pub requeued: Vec<String>,
+    /// Beans whose agent died after closing the bean.
+    pub recovered: Vec<String>,
+}
+
+impl RunJournal {
+    /// Start a journal for a dispatch plan. Every planned bean begins `Pending`.
+    pub fn new(beans_dir: &Path, target: Option<&str>, plan: &DispatchPlan) -> Self {
+        let now = Utc::now();
+        let beans = plan
+            .waves
+            .iter()
+            .enumerate()
+            .flat_map(|(i, wave)| {
+                wave.beans.iter().map(move |b| JournalBean {
+                    id: b.id.clone(),
+                    title: b.title.clone(),
+                    round: i + 1,
+                    state: BeanRunState::Pending,
+                    pid: None,
+                    pid_start: None,
+                    log_path: None,
+                    started_at: None,
+                    finished_at: None,
+                    tokens: None,
+                    cost: None,
+                    error: None,
+                })
+            })
+            .collect();
+
+        RunJournal {
+            id: new_run_id(beans_dir, now),
+            pid: std::process::id(),
+            pid_start: process_start_time(std::process::id()),
+            target: target.map(str::to_string),
+            started_at: now,
+            updated_at: now,
+            finished_at: None,
+            beans,
+        }
+    }
+
+    /// Path of the journal file for `run_id`.
+    pub fn path(beans_dir: &Path, run_id: &str) -> PathBuf {
+        beans_dir.join(RUNS_DIR).join(format!("{}.json", run_id))
+    }
+
+    /// Load a journal by run ID. `latest` picks the most recent unfinished run.
+    pub fn load(beans_dir: &Path, run_id: &str) -> Result<Self> {
+        if run_id == "latest" {
+            return list(beans_dir)?
+                .into_iter()
+                .rev()
+                .find(|j| j.finished_at.is_none())
+                .ok_or_else(|| anyhow!("No unfinished runs to resume in .beans/{}/", RUNS_DIR));
+        }
+        let path = Self::path(beans_dir, run_id);
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Run not found: {} ({})", run_id, path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse run journal: {}", path.display()))
+    }
+
+    /// Write the journal to `.beans/runs/<id>.json`.
+    pub fn save(&self, beans_dir: &Path) -> Result<()> {
+        let dir = beans_dir.join(RUNS_DIR);
+        if !dir.exists() {
+            // Projects initialized before run journals existed don't ignore them
+            ensure_gitignored(beans_dir, &format!("{}/", RUNS_DIR));
+        }
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize run journal")?;
+        atomic_write(&Self::path(beans_dir, &self.id), &json)
+    }
+
+    fn entry_mut(&mut self, id: &str) -> Option<&mut JournalBean> {
+        self.beans.iter_mut().find(|b| b.id == id)
+    }
+
+    /// Record that an agent was spawned for a bean.
+    pub fn mark_running(&mut self, id: &str, pid: u32, log_path: Option<PathBuf>) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.state = BeanRunState::Running;
+            entry.pid = Some(pid);
+            entry.pid_start = process_start_time(pid);
+            entry.log_path = log_path;
+            entry.started_at = Some(Utc::now());
+            entry.finished_at = None;
+            entry.error = None;
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Record an agent's outcome, including its token and cost usage.
+    pub(super) fn mark_done(&mut self, result: &AgentResult) {
+        if let Some(entry) = self.entry_mut(&result.id) {
+            entry.state = if result.success {
+                BeanRunState::Succeeded
+            } else {
+                BeanRunState::Failed
+            };
+            entry.finished_at = Some(Utc::now());
+            entry.tokens = result.total_tokens;
+            entry.cost = result.total_cost;
+            entry.error = result.error.clone();
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Mark the run as finished normally.
+    pub fn finish(&mut self) {
+        let now = Utc::now();
+        self.finished_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// Bean IDs that still need an agent.
+    pub fn pending_ids(&self) -> Vec<String> {
+        self.beans
+            .iter()
+            .filter(|b| b.state == BeanRunState::Pending)
+            .map(|b| b.id.clone())
+            .collect()
+    }
+
+    /// Bring a journal loaded from disk up to date before resuming it.
+    ///
+    /// A `Running` bean whose agent is still alive (`alive` is given the PID
+    /// and its recorded start time) is left running and reported as
+    /// surviving. If its agent is gone, the bean is marked
+    /// `Succeeded` when the agent managed to close it; otherwise its claim is
+    /// released (if still held) and the bean goes back to `Pending`.
+    pub fn reconcile(
+        &mut self,
+        beans_dir: &Path,
+        alive: impl Fn(u32, Option<u64>) -> bool,
+    ) -> Reconciled {
+        let mut out = Reconciled::default();
+        for entry in self.beans.iter_mut() {
+            if entry.state != BeanRunState::Running {
+                continue;
+            }
+            if let Some(pid) = entry.pid.filter(|&pid| alive(pid, entry.pid_start)) {
+                out.surviving.push((entry.id.clone(), pid));
+                continue;
+            }
+            match bean_status(beans_dir, &entry.id) {
+                Some(Status::Closed) => {
+                    entry.state = BeanRunState::Succeeded;
+                    entry.finished_at = Some(Utc::now());
+                    out.recovered.push(entry.id.clone());
+                }
+                status => {
+                    if status == Some(Status::InProgress) {
+                        let _ = crate::commands::claim::cmd_release(beans_dir, &entry.id);
+                    }
+                    entry.state = BeanRunState::Pending;
+                    entry.pid = None;
+                    entry.pid_start = None;
+                    entry.started_at = None;
+                    out.requeued.push(entry.id.clone());
+                }
+            }
+        }
+        self.updated_at = Utc::now();
+        out
+    }
+}
+
+/// Current status of a bean, looking in the archive too.
+fn bean_status(beans_dir: &Path, id: &str) -> Option<Status> {
+    let path = find_bean_file(beans_dir, id)
+        .or_else(|_| find_archived_bean(beans_dir, id))
+        .ok()?;
+    Bean::from_file(path).ok().map(|b| b.status)
+}
+
+/// Pick a run ID from the start time, unique within `.beans/runs/`.
+fn new_run_id(beans_dir: &Path, now: DateTime<Utc>) -> String {
+    let base = now.format("%Y%m%d-%H%M%S").to_string();
+    let mut id = base.clone();
+    let mut n = 2;
+    while RunJournal::path(beans_dir, &id).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// All journals in `.beans/runs/`, oldest first.
+pub fn list(beans_dir: &Path) -> Result<Vec<RunJournal>> {
+    let dir = beans_dir.join(RUNS_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut journals: Vec<RunJournal> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|contents| serde_json::from_str(&contents).ok())
+        .collect();
+    journals.sort_by_key(|j: &RunJournal| j.started_at);
+    Ok(journals)
+}
+
+/// Block until every surviving agent from a resumed run exits, recording each
+/// outcome (the bean being closed counts as success).
+pub(super) fn wait_for_survivors(
+    beans_dir: &Path,
+    journal: &JournalHandle,
+    surviving: &[(String, u32)],
+    json_stream: bool,
+) {
+    let snapshot = journal.snapshot();
+    let mut waiting: Vec<(String, u32, Option<u64>)> = surviving
+        .iter()
+        .map(|(id, pid)| {
+            let start = snapshot
+                .beans
+                .iter()
+                .find(|b| b.id == *id)
+                .and_then(|b| b.pid_start);
+            (id.clone(), *pid, start)
+        })
+        .collect();
+    while !waiting.is_empty() {
+        waiting.retain(|(id, pid, start)| {
+            if same_process_alive(*pid, *start) {
+                return true;
+            }
+            let success = bean_status(beans_dir, id) == Some(Status::Closed);
+            journal.update(|j| {
+                if let Some(entry) = j.entry_mut(id) {
+                    entry.state = if success {
+                        BeanRunState::Succeeded
+                    } else {
+                        BeanRunState::Failed
+                    };
+                    entry.finished_at = Some(Utc::now());
+                    if !success {
+                        entry.error = Some("Agent exited without closing the bean".to_string());
+                    }
+                }
+            });
+            if !json_stream {
+                let mark = if success { "✓" } else { "✗" };
+                eprintln!("  {} {}  (reattached pid {})", mark, id, pid);
+            }
+            false
+        });
+        if !waiting.is_empty() {
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    }
+}
+
+/// Shared, thread-safe handle to the journal of the current run.
+///
+/// Each update rewrites the journal on disk. Write failures are reported but
+/// never abort the run — the journal only exists to make resuming possible.
+#[derive(Clone)]
+pub(crate) struct JournalHandle {
+    beans_dir: PathBuf,
+    inner: Arc<Mutex<RunJournal>>,
+}
+
+impl JournalHandle {
+    pub(super) fn new(beans_dir: &Path, journal: RunJournal) -> Self {
+        let handle = JournalHandle {
+            beans_dir: beans_dir.to_path_buf(),
+            inner: Arc::new(Mutex::new(journal)),
+        };
+        handle.update(|_| {});
+        handle
+    }
+
+    pub(super) fn id(&self) -> String {
+        self.inner.lock().unwrap().id.clone()
+    }
+
+    /// Copy of the journal's current state.
+    pub(super) fn snapshot(&self) -> RunJournal {
+        self.inner.lock().unwrap().clone()
+    }
+
+    /// Apply `f` to the journal and persist it.
+    pub(super) fn update(&self, f: impl FnOnce(&mut RunJournal)) {
+        let mut journal = self.inner.lock().unwrap();
+        f(&mut journal);
+        if let Err(e) = journal.save(&self.beans_dir) {
+            eprintln!("Warning: failed to write run journal: {}", e);
+        }
+    }
+
+    pub(super) fn mark_running(&self, id: &str, pid: u32, log_path: Option<PathBuf>) {
+        self.update(|j| j.mark_running(id, pid, log_path));
+    }
+
+    pub(super) fn mark_done(&self, result: &AgentResult) {
+        self.update(|j| j.mark_done(result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::run::plan::SizedBean;
+    use crate::commands::run::wave::Wave;
+    use crate::commands::run::BeanAction;
+    use crate::index::Index;
+    use tempfile::TempDir;
+
+    fn sized(id: &str) -> SizedBean {
+        SizedBean {
+            id: id.to_string(),
+            title: format!("Bean {}", id),
+            action: BeanAction::Implement,
+            priority: 2,
+            dependencies: vec![],
+            parent: None,
+            produces: vec![],
+            requires: vec![],
+            paths: vec![],
+        }
+    }
+
+    fn plan(waves: Vec<Vec<&str>>) -> DispatchPlan {
+        let waves: Vec<Wave> = waves
+            .into_iter()
+            .map(|ids| Wave {
+                beans: ids.into_iter().map(sized).collect(),
+            })
+            .collect();
+        let all_beans = waves.iter().flat_map(|w| w.beans.clone()).collect();
+        DispatchPlan {
+            waves,
+            skipped: vec![],
+            warnings: vec![],
+            all_beans,
+            index: Index { beans: vec![] },
+        }
+    }
+
+    fn result(id: &str, success: bool) -> AgentResult {
+        AgentResult {
+            id: id.to_string(),
+            title: format!("Bean {}", id),
+            success,
+            duration: Duration::from_secs(3),
+            total_tokens: Some(1200),
+            total_cost: Some(0.05),
+            error: (!success).then(|| "Exit code 1".to_string()),
+            tool_count: 0,
+            turns: 0,
+            failure_summary: None,
+        }
+    }
+
+    fn setup() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        (dir, beans_dir)
+    }
+
+    #[test]
+    fn new_journal_records_plan_rounds() {
+        let (_dir, beans_dir) = setup();
+        let journal = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1", "2"], vec!["3"]]));
+        assert_eq!(journal.beans.len(), 3);
+        assert_eq!(journal.beans[2].round, 2);
+        assert!(journal
+            .beans
+            .iter()
+            .all(|b| b.state == BeanRunState::Pending));
+        assert_eq!(journal.pending_ids(), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let (_dir, beans_dir) = setup();
+        let mut journal = RunJournal::new(&beans_dir, Some("5"), &plan(vec![vec!["1", "2"]]));
+        journal.mark_running("1", 4242, Some(PathBuf::from("/tmp/1.log")));
+        journal.mark_done(&result("2", true));
+        journal.save(&beans_dir).unwrap();
+
+        let loaded = RunJournal::load(&beans_dir, &journal.id).unwrap();
+        assert_eq!(loaded.target.as_deref(), Some("5"));
+        assert_eq!(loaded.beans[0].state, BeanRunState::Running);
+        assert_eq!(loaded.beans[0].pid, Some(4242));
+        assert_eq!(loaded.beans[1].state, BeanRunState::Succeeded);
+        assert_eq!(loaded.beans[1].tokens, Some(1200));
+        assert_eq!(loaded.beans[1].cost, Some(0.05));
+
+        let gitignore = fs::read_to_string(beans_dir.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|line| line == "runs/"));
+    }
+
+    #[test]
+    fn run_ids_are_unique() {
+        let (_dir, beans_dir) = setup();
+        let first = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        first.save(&beans_dir).unwrap();
+        let second = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        assert_ne!(first.id, second.id);
+    }
+
+    #[test]
+    fn load_latest_skips_finished_runs() {
+        let (_dir, beans_dir) = setup();
+        let open = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        open.save(&beans_dir).unwrap();
+        let mut done = RunJournal::new(&beans_dir, None, &plan(vec![vec!["2"]]));
+        done.started_at = open.started_at + chrono::Duration::seconds(1);
+        done.finish();
+        done.save(&beans_dir).unwrap();
+
+        let latest = RunJournal::load(&beans_dir, "latest").unwrap();
+        assert_eq!(latest.id, open.id);
+    }
+
+    #[test]
+    fn load_missing_run_errors() {
+        let (_dir, beans_dir) = setup();
+        assert!(RunJournal::load(&beans_dir, "nope").is_err());
+        assert!(RunJournal::load(&beans_dir, "latest").is_err());
+    }
+
+    #[test]
+    fn failed_result_is_recorded() {
+        let (_dir, beans_dir) = setup();
+        let mut journal = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        journal.mark_done(&result("1", false));
+        assert_eq!(journal.beans[0].state, BeanRunState::Failed);
+        assert_eq!(journal.beans[0].error.as_deref(), Some("Exit code 1"));
+        assert!(journal.pending_ids().is_empty());
+    }
+
+    #[test]
+    fn reconcile_classifies_running_beans() {
+        let (_dir, beans_dir) = setup();
+
+        let mut closed = Bean::new("1", "Closed by agent");
+        closed.status = Status::Closed;
+        closed
+            .to_file(beans_dir.join("1-closed-by-agent.md"))
+            .unwrap();
+        let mut orphaned = Bean::new("2", "Orphaned");
+        orphaned.status = Status::InProgress;
+        orphaned.claimed_by = Some("agent-1".to_string());
+        orphaned.to_file(beans_dir.join("2-orphaned.md")).unwrap();
+        Bean::new("3", "Alive")
+            .to_file(beans_dir.join("3-alive.md"))
+            .unwrap();
+
+        let mut journal = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1", "2", "3"]]));
+        journal.mark_running("1", 101, None);
+        journal.mark_running("2", 102, None);
+        journal.mark_running("3", 103, None);
+
+        let reconciled = journal.reconcile(&beans_dir, |pid, _| pid == 103);
+        assert_eq!(reconciled.surviving, vec![("3".to_string(), 103)]);
+        assert_eq!(reconciled.recovered, vec!["1"]);
+        assert_eq!(reconciled.requeued, vec!["2"]);
+
+        assert_eq!(journal.beans[0].state, BeanRunState::Succeeded);
+        assert_eq!(journal.beans[1].state, BeanRunState::Pending);
+        assert_eq!(journal.beans[2].state, BeanRunState::Running);
+
+        // The orphaned claim is released so the bean can be dispatched again
+        let released = Bean::from_file(beans_dir.join("2-orphaned.md")).unwrap();
+        assert_eq!(released.status, Status::Open);
+        assert!(released.claimed_by.is_none());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn reconcile_treats_reused_pid_as_dead() {
+        let (_dir, beans_dir) = setup();
+        let mut orphaned = Bean::new("1", "Orphaned");
+        orphaned.status = Status::InProgress;
+        orphaned.to_file(beans_dir.join("1-orphaned.md")).unwrap();
+
+        // The agent's PID now belongs to an unrelated process (this one)
+        let mut journal = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        journal.mark_running("1", std::process::id(), None);
+        journal.beans[0].pid_start = journal.beans[0].pid_start.map(|s| s + 1);
+
+        let reconciled = journal.reconcile(&beans_dir, same_process_alive);
+        assert!(reconciled.surviving.is_empty());
+        assert_eq!(reconciled.requeued, vec!["1"]);
+    }
+
+    #[test]
+    fn handle_persists_updates() {
+        let (_dir, beans_dir) = setup();
+        let journal = RunJournal::new(&beans_dir, None, &plan(vec![vec!["1"]]));
+        let handle = JournalHandle::new(&beans_dir, journal);
+        handle.mark_running("1", 77, None);
+
+        let loaded = RunJournal::load(&beans_dir, &handle.id()).unwrap();
+        assert_eq!(loaded.beans[0].pid, Some(77));
+    }
+}
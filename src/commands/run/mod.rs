@@ -8,20 +8,31 @@
 //! - `bn run 5.1` — dispatch a single bean (or its ready children if parent)
 //! - `bn run --dry-run` — show plan without spawning
 //! - `bn run --loop` — keep running until no ready beans remain
+//! - `bn run --watch` — stay up and dispatch beans as they become ready (Ctrl-C to stop)
+//! - `bn run --resume <run-id>` — pick up an interrupted run from its journal
 //! - `bn run --json-stream` — emit JSON stream events to stdout
 //!
 //! Spawning modes:
-//! - **Template mode** (backward compat): If `config.run` is set, spawn via `sh -c <template>`.
-//! - **Direct mode**: If no template is configured but `pi` is on PATH, spawn pi directly
-//!   with `--mode json --print --no-session`, monitoring with timeouts and parsing events.
-
+//! - **Direct mode**: If `config.agent` names a preset (pi, claude, aider, codex), spawn that
+//!   agent's CLI directly as described by its [`AgentPreset`], monitoring with timeouts and
+//!   parsing its output into events.
+//! - **Template mode** (backward compat): Otherwise, if `config.run` is set, spawn via
+//!   `sh -c <template>`.
+//! - With neither set, direct mode with pi is used.
+
+mod budget;
+mod journal;
 mod plan;
 mod ready_queue;
+mod retry;
+mod watch;
 mod wave;
 
+pub use journal::{BeanRunState, JournalBean, RunJournal};
 pub use plan::{DispatchPlan, SizedBean};
 pub use wave::Wave;
 
+use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -29,29 +40,44 @@ use std::time::{Duration, Instant};
 
 use anyhow::Result;
 
+use crate::agent_presets::{get_preset, AgentPreset};
+use crate::commands::agents::{process_start_time, same_process_alive};
 use crate::commands::review::{cmd_review, ReviewArgs};
 use crate::config::Config;
 use crate::stream::{self, StreamEvent};
 
+use budget::BudgetTracker;
+use journal::{wait_for_survivors, JournalHandle};
 use plan::{plan_dispatch, print_plan, print_plan_json};
 use ready_queue::run_ready_queue_direct;
+use watch::run_watch;
 use wave::run_wave;
 
 /// Shared config passed to wave/ready-queue runners.
+#[derive(Clone)]
 pub(super) struct RunConfig {
     pub max_jobs: usize,
     pub timeout_minutes: u32,
     pub idle_timeout_minutes: u32,
     pub json_stream: bool,
     pub file_locking: bool,
+    /// Give each agent its own git worktree (config `worktree: true`).
+    pub worktree: bool,
+    /// Journal of the current run, updated as agents start and finish.
+    pub journal: Option<JournalHandle>,
+    /// Token/cost limits, shared by every agent of the run.
+    pub budget: BudgetTracker,
 }
 
 /// Arguments for cmd_run, matching the CLI definition.
+#[derive(Clone)]
 pub struct RunArgs {
     pub id: Option<String>,
     pub jobs: u32,
     pub dry_run: bool,
     pub loop_mode: bool,
+    /// Keep a long-lived scheduler running that reacts to bean changes.
+    pub watch: bool,
     pub auto_plan: bool,
     pub keep_going: bool,
     pub timeout: u32,
@@ -59,6 +85,10 @@ pub struct RunArgs {
     pub json_stream: bool,
     /// If true, run adversarial review after each successful bean close.
     pub review: bool,
+    /// Resume an interrupted run from its journal (`latest` for the most recent).
+    pub resume: Option<String>,
+    /// Only dispatch beans matching this filter expression.
+    pub filter: Option<String>,
 }
 
 /// What action to take for a bean.
@@ -80,7 +110,6 @@ impl fmt::Display for BeanAction {
 struct AgentResult {
     id: String,
     title: String,
-    action: BeanAction,
     success: bool,
     duration: Duration,
     total_tokens: Option<u64>,
@@ -99,22 +128,32 @@ enum SpawnMode {
         run_template: String,
         plan_template: Option<String>,
     },
-    /// Spawn pi directly with JSON output and monitoring.
-    Direct,
+    /// Spawn the preset's CLI directly with structured output and monitoring.
+    Direct { agent: &'static AgentPreset },
 }
 
 /// Execute the `bn run` command.
 pub fn cmd_run(beans_dir: &Path, args: RunArgs) -> Result<()> {
     // Determine spawn mode
     let config = Config::load_with_extends(beans_dir)?;
-    let spawn_mode = determine_spawn_mode(&config);
-
-    if spawn_mode == SpawnMode::Direct && !pi_available() {
+    let spawn_mode = determine_spawn_mode(&config)?;
+
+    if let SpawnMode::Direct { agent } = spawn_mode {
+        if config.agent.is_some() && !agent.is_available() {
+            anyhow::bail!(
+                "Agent `{}` is configured but `{}` was not found on PATH.",
+                agent.name,
+                agent.direct.program
+            );
+        }
+    }
+    if spawn_mode == direct_pi() && !pi_available() {
         anyhow::bail!(
             "No agent configured and `pi` not found on PATH.\n\n\
              Either:\n  \
                1. Install pi: npm i -g @anthropic/pi\n  \
-               2. Set a run template: bn config set run \"<command>\"\n\n\
+               2. Pick an agent preset: bn config set agent claude\n  \
+               3. Set a run template: bn config set run \"<command>\"\n\n\
              The command template uses {{id}} as a placeholder for the bean ID.\n\n\
              Examples:\n  \
                bn config set run \"pi @.beans/{{id}}-*.md 'implement and bn close {{id}}'\"\n  \
@@ -128,24 +167,52 @@ pub fn cmd_run(beans_dir: &Path, args: RunArgs) -> Result<()> {
     {
         // Validate template exists (kept for backward compat error message)
         let _ = run_template;
+        if budget::is_configured(&config) {
+            eprintln!(
+                "Warning: budget limits are only enforced for agent presets (`agent:`), \
+                 not `run:` templates, which report no token usage"
+            );
+        }
     }
 
-    if args.loop_mode {
+    if let Some(ref run_id) = args.resume {
+        run_resume(beans_dir, &config, &args, &spawn_mode, run_id)
+    } else if args.watch && !args.dry_run {
+        run_watch(beans_dir, config, &args)
+    } else if args.loop_mode {
         run_loop(beans_dir, &config, &args, &spawn_mode)
     } else {
-        run_once(beans_dir, &config, &args, &spawn_mode)
+        run_once(beans_dir, &config, &args, &spawn_mode, None)
     }
 }
 
 /// Determine the spawn mode based on config.
-fn determine_spawn_mode(config: &Config) -> SpawnMode {
-    if let Some(ref run) = config.run {
-        SpawnMode::Template {
+///
+/// An `agent` preset wins over a `run` template; with neither, pi is
+/// spawned directly.
+fn determine_spawn_mode(config: &Config) -> Result<SpawnMode> {
+    if let Some(ref name) = config.agent {
+        let agent = get_preset(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown agent preset `{}` in config (expected pi, claude, aider or codex)",
+                name
+            )
+        })?;
+        Ok(SpawnMode::Direct { agent })
+    } else if let Some(ref run) = config.run {
+        Ok(SpawnMode::Template {
             run_template: run.clone(),
             plan_template: config.plan.clone(),
-        }
+        })
     } else {
-        SpawnMode::Direct
+        Ok(direct_pi())
+    }
+}
+
+/// The default direct mode: pi.
+fn direct_pi() -> SpawnMode {
+    SpawnMode::Direct {
+        agent: get_preset("pi").expect("pi preset is built in"),
     }
 }
 
@@ -161,21 +228,40 @@ fn pi_available() -> bool {
 }
 
 /// Single dispatch pass: plan → print/execute → report.
+///
+/// Every pass that spawns agents keeps a run journal. When `resumed` is set,
+/// only the journal's pending beans are dispatched and the journal is reused.
 fn run_once(
     beans_dir: &Path,
     config: &Config,
     args: &RunArgs,
     spawn_mode: &SpawnMode,
+    resumed: Option<RunJournal>,
 ) -> Result<()> {
-    let plan = plan_dispatch(
+    let mut plan = plan_dispatch(
         beans_dir,
         config,
         args.id.as_deref(),
+        args.filter.as_deref(),
         args.auto_plan,
         args.dry_run,
     )?;
 
+    if let Some(ref journal) = resumed {
+        let pending: HashSet<String> = journal.pending_ids().into_iter().collect();
+        plan.all_beans.retain(|b| pending.contains(&b.id));
+        plan.skipped.retain(|b| pending.contains(&b.id));
+        for wave in &mut plan.waves {
+            wave.beans.retain(|b| pending.contains(&b.id));
+        }
+        plan.waves.retain(|w| !w.beans.is_empty());
+    }
+
     if plan.waves.is_empty() && plan.skipped.is_empty() {
+        if let Some(mut journal) = resumed {
+            journal.finish();
+            journal.save(beans_dir)?;
+        }
         if args.json_stream {
             stream::emit_error("No ready beans");
         } else {
@@ -227,12 +313,27 @@ fn run_once(
         });
     }
 
+    let journal = JournalHandle::new(
+        beans_dir,
+        resumed.unwrap_or_else(|| RunJournal::new(beans_dir, args.id.as_deref(), &plan)),
+    );
+    if !args.json_stream {
+        let run_id = journal.id();
+        eprintln!(
+            "Run {} (if interrupted: bn run --resume {})",
+            run_id, run_id
+        );
+    }
+
     let run_cfg = RunConfig {
         max_jobs: args.jobs.min(config.max_concurrent) as usize,
         timeout_minutes: args.timeout,
         idle_timeout_minutes: args.idle_timeout,
         json_stream: args.json_stream,
         file_locking: config.file_locking,
+        worktree: config.worktree,
+        journal: Some(journal.clone()),
+        budget: BudgetTracker::new(beans_dir, config),
     };
     let run_start = Instant::now();
     let total_done;
@@ -244,7 +345,7 @@ fn run_once(
     let mut successful_ids: Vec<String> = Vec::new();
 
     match spawn_mode {
-        SpawnMode::Direct => {
+        SpawnMode::Direct { agent } => {
             if !args.json_stream {
                 eprintln!("Dispatching {} bean(s)...", total_beans);
             }
@@ -255,6 +356,7 @@ fn run_once(
                 beans_dir,
                 &plan.all_beans,
                 &plan.index,
+                agent,
                 &run_cfg,
                 args.keep_going,
             )?;
@@ -393,6 +495,8 @@ fn run_once(
         }
     }
 
+    journal.update(|j| j.finish());
+
     // Trigger adversarial review for each successfully closed bean if --review is set.
     // Review runs synchronously after all beans in this pass complete.
     if args.review && !successful_ids.is_empty() {
@@ -466,7 +570,14 @@ fn run_loop(
             eprintln!("\n--- Loop iteration {} ---\n", iteration + 1);
         }
 
-        let plan = plan_dispatch(beans_dir, config, args.id.as_deref(), args.auto_plan, false)?;
+        let plan = plan_dispatch(
+            beans_dir,
+            config,
+            args.id.as_deref(),
+            args.filter.as_deref(),
+            args.auto_plan,
+            false,
+        )?;
 
         if plan.waves.is_empty() {
             if !args.json_stream {
@@ -485,18 +596,21 @@ fn run_loop(
             jobs: args.jobs,
             dry_run: false,
             loop_mode: false,
+            watch: false,
             auto_plan: args.auto_plan,
             keep_going: args.keep_going,
             timeout: args.timeout,
             idle_timeout: args.idle_timeout,
             json_stream: args.json_stream,
             review: args.review,
+            resume: None,
+            filter: args.filter.clone(),
         };
 
         // Reload config each iteration (agents may have changed beans)
         let config = Config::load_with_extends(beans_dir)?;
-        let spawn_mode = determine_spawn_mode(&config);
-        match run_once(beans_dir, &config, &inner_args, &spawn_mode) {
+        let spawn_mode = determine_spawn_mode(&config)?;
+        match run_once(beans_dir, &config, &inner_args, &spawn_mode, None) {
             Ok(()) => {}
             Err(e) => {
                 if args.keep_going {
@@ -512,6 +626,55 @@ fn run_loop(
     Ok(())
 }
 
+/// Resume an interrupted run: reattach to agents that are still alive,
+/// re-queue the ones that died, then dispatch the rest of the plan.
+fn run_resume(
+    beans_dir: &Path,
+    config: &Config,
+    args: &RunArgs,
+    spawn_mode: &SpawnMode,
+    run_id: &str,
+) -> Result<()> {
+    let mut journal = RunJournal::load(beans_dir, run_id)?;
+    if journal.finished_at.is_some() {
+        anyhow::bail!("Run {} already finished; nothing to resume", journal.id);
+    }
+    if journal.pid != std::process::id() && same_process_alive(journal.pid, journal.pid_start) {
+        anyhow::bail!(
+            "Run {} is still active (bn run pid {})",
+            journal.id,
+            journal.pid
+        );
+    }
+
+    journal.pid = std::process::id();
+    journal.pid_start = process_start_time(journal.pid);
+    let reconciled = journal.reconcile(beans_dir, same_process_alive);
+    if !args.json_stream {
+        eprintln!(
+            "Resuming run {}: {} agent(s) still running, {} re-queued, {} pending",
+            journal.id,
+            reconciled.surviving.len(),
+            reconciled.requeued.len(),
+            journal.pending_ids().len(),
+        );
+        for id in &reconciled.recovered {
+            eprintln!("  ✓ {}  (closed while detached)", id);
+        }
+    }
+
+    let handle = JournalHandle::new(beans_dir, journal);
+    wait_for_survivors(beans_dir, &handle, &reconciled.surviving, args.json_stream);
+    let journal = handle.snapshot();
+
+    let inner_args = RunArgs {
+        id: journal.target.clone(),
+        resume: None,
+        ..args.clone()
+    };
+    run_once(beans_dir, config, &inner_args, spawn_mode, Some(journal))
+}
+
 /// Format a duration as M:SS.
 pub(super) fn format_duration(d: Duration) -> String {
     let secs = d.as_secs();
@@ -554,12 +717,15 @@ mod tests {
             jobs: 4,
             dry_run: false,
             loop_mode: false,
+            watch: false,
             auto_plan: false,
             keep_going: false,
             timeout: 30,
             idle_timeout: 5,
             json_stream: false,
             review: false,
+            resume: None,
+            filter: None,
         }
     }
 
@@ -655,8 +821,19 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
-        let mode = determine_spawn_mode(&config);
+        let mode = determine_spawn_mode(&config).unwrap();
         assert_eq!(
             mode,
             SpawnMode::Template {
@@ -688,9 +865,39 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
+        };
+        let mode = determine_spawn_mode(&config).unwrap();
+        assert_eq!(mode, direct_pi());
+    }
+
+    #[test]
+    fn determine_spawn_mode_agent_overrides_run() {
+        let config = Config {
+            run: Some("echo {id}".to_string()),
+            agent: Some("claude".to_string()),
+            ..Config::default()
+        };
+        match determine_spawn_mode(&config).unwrap() {
+            SpawnMode::Direct { agent } => assert_eq!(agent.name, "claude"),
+            other => panic!("expected direct mode, got {:?}", other),
+        }
+
+        let config = Config {
+            agent: Some("nope".to_string()),
+            ..Config::default()
         };
-        let mode = determine_spawn_mode(&config);
-        assert_eq!(mode, SpawnMode::Direct);
+        assert!(determine_spawn_mode(&config).is_err());
     }
 
     #[test]
@@ -698,7 +905,6 @@ mod tests {
         let result = AgentResult {
             id: "1".to_string(),
             title: "Test".to_string(),
-            action: BeanAction::Implement,
             success: true,
             duration: Duration::from_secs(10),
             total_tokens: Some(5000),
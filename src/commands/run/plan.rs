@@ -5,6 +5,7 @@ use anyhow::Result;
 use crate::bean::Status;
 use crate::blocking::{check_blocked, check_scope_warning, BlockReason, ScopeWarning};
 use crate::config::Config;
+use crate::filter::Filter;
 use crate::index::{ArchiveIndex, Index, IndexEntry};
 use crate::stream::{self, StreamEvent};
 
@@ -47,10 +48,13 @@ pub struct DispatchPlan {
 }
 
 /// Plan dispatch: get ready beans, filter by scope, compute waves.
+///
+/// `query` is a filter expression (see [`Filter`]) that candidates must match.
 pub(super) fn plan_dispatch(
     beans_dir: &Path,
-    _config: &Config,
+    config: &Config,
     filter_id: Option<&str>,
+    query: Option<&str>,
     _auto_plan: bool,
     simulate: bool,
 ) -> Result<DispatchPlan> {
@@ -86,6 +90,11 @@ pub(super) fn plan_dispatch(
         }
     }
 
+    if let Some(query) = query {
+        let query = Filter::parse(query, &config.views)?;
+        candidate_entries.retain(|e| query.matches(e, beans_dir));
+    }
+
     // Partition into dispatchable vs blocked.
     // In simulate mode, skip blocking checks — we want to show the full plan.
     // In normal mode, dependency blocking is already handled by all_deps_closed above,
@@ -215,7 +224,7 @@ mod tests {
         write_config(&beans_dir, Some("echo {id}"));
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, None, false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, None, None, false, false).unwrap();
 
         assert!(plan.waves.is_empty());
         assert!(plan.skipped.is_empty());
@@ -239,7 +248,7 @@ mod tests {
         bean2.to_file(beans_dir.join("2-task-two.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, None, false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, None, None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 2);
@@ -263,13 +272,45 @@ mod tests {
         bean2.to_file(beans_dir.join("2-task-two.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 1);
         assert_eq!(plan.waves[0].beans[0].id, "1");
     }
 
+    #[test]
+    fn plan_dispatch_filters_by_query() {
+        let (_dir, beans_dir) = make_beans_dir();
+        write_config(&beans_dir, Some("echo {id}"));
+
+        let mut bean = crate::bean::Bean::new("1", "Task one");
+        bean.verify = Some("echo ok".to_string());
+        bean.paths = vec!["src/api/x.rs".to_string()];
+        bean.to_file(beans_dir.join("1-task-one.md")).unwrap();
+
+        let mut bean2 = crate::bean::Bean::new("2", "Task two");
+        bean2.verify = Some("echo ok".to_string());
+        bean2.paths = vec!["src/cli/y.rs".to_string()];
+        bean2.to_file(beans_dir.join("2-task-two.md")).unwrap();
+
+        let config = Config::load_with_extends(&beans_dir).unwrap();
+        let plan = plan_dispatch(
+            &beans_dir,
+            &config,
+            None,
+            Some("path:src/api/*"),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(plan.all_beans.len(), 1);
+        assert_eq!(plan.all_beans[0].id, "1");
+
+        assert!(plan_dispatch(&beans_dir, &config, None, Some("and"), false, false).is_err());
+    }
+
     #[test]
     fn plan_dispatch_parent_id_gets_children() {
         let (_dir, beans_dir) = make_beans_dir();
@@ -293,7 +334,7 @@ mod tests {
         child2.to_file(beans_dir.join("1.2-child-two.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 2);
@@ -317,7 +358,7 @@ mod tests {
         bean.to_file(beans_dir.join("1-oversized.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, None, false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, None, None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 1);
@@ -337,7 +378,7 @@ mod tests {
         bean.to_file(beans_dir.join("1-unscoped.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, None, false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, None, None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 1);
@@ -357,7 +398,7 @@ mod tests {
         bean.to_file(beans_dir.join("1-well-scoped.md")).unwrap();
 
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, None, false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, None, None, false, false).unwrap();
 
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 1);
@@ -398,13 +439,13 @@ mod tests {
 
         // Without simulate: only wave 1 (1.1) is ready
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, false).unwrap();
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans.len(), 1);
         assert_eq!(plan.waves[0].beans[0].id, "1.1");
 
         // With simulate: all 3 waves shown
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, true).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, true).unwrap();
         assert_eq!(plan.waves.len(), 3);
         assert_eq!(plan.waves[0].beans[0].id, "1.1");
         assert_eq!(plan.waves[1].beans[0].id, "1.2");
@@ -436,12 +477,12 @@ mod tests {
 
         // Without simulate: only 1.1 is ready (1.2 blocked on requires)
         let config = Config::load_with_extends(&beans_dir).unwrap();
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, false).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, false).unwrap();
         assert_eq!(plan.waves.len(), 1);
         assert_eq!(plan.waves[0].beans[0].id, "1.1");
 
         // With simulate: both shown in correct wave order
-        let plan = plan_dispatch(&beans_dir, &config, Some("1"), false, true).unwrap();
+        let plan = plan_dispatch(&beans_dir, &config, Some("1"), None, false, true).unwrap();
         assert_eq!(plan.waves.len(), 2);
         assert_eq!(plan.waves[0].beans[0].id, "1.1");
         assert_eq!(plan.waves[1].beans[0].id, "1.2");
@@ -1,12 +1,17 @@
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::ops::ControlFlow;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 
-use crate::bean::{Bean, Status};
+use crate::agent_output;
+use crate::agent_presets::AgentPreset;
+use crate::bean::{Bean, RunResult, Status};
 use crate::failure;
 use crate::history::{self, AgentHistoryEntry};
 use crate::index::{ArchiveIndex, Index, IndexEntry};
@@ -16,15 +21,19 @@ use crate::stream::{self, StreamEvent};
 use crate::timeout::{self, MonitorResult, TimeoutConfig};
 use crate::util::natural_cmp;
 
+use super::budget::{self, BudgetExceeded, BudgetTracker, Spend};
+use super::journal::JournalHandle;
 use super::plan::SizedBean;
+use super::retry::{self, RetryDecision};
 use super::wave::compute_waves;
 use super::{format_duration, AgentResult};
 
 /// Check if all dependencies of an index entry are closed.
 ///
 /// Checks both the active index and the archive index. A dependency found in
-/// the archive is considered satisfied (archived means closed). A dependency
-/// found in neither index is treated as unsatisfied (catches typos).
+/// the archive is considered satisfied (archived means closed) unless it was
+/// cancelled — cancelled work never lands, so its dependents stay blocked.
+/// A dependency found in neither index is treated as unsatisfied (catches typos).
 pub(super) fn all_deps_closed(entry: &IndexEntry, index: &Index, archive: &ArchiveIndex) -> bool {
     for dep_id in &entry.dependencies {
         match index.beans.iter().find(|e| e.id == *dep_id) {
@@ -32,8 +41,9 @@ pub(super) fn all_deps_closed(entry: &IndexEntry, index: &Index, archive: &Archi
             Some(_) => return false, // Found in active index but not closed
             None => {
                 // Not in active index — check archive (archived = closed)
-                if !archive.beans.iter().any(|e| e.id == *dep_id) {
-                    return false; // Not found in either index
+                match archive.beans.iter().find(|e| e.id == *dep_id) {
+                    Some(dep) if dep.status != Status::Cancelled => {}
+                    _ => return false, // Cancelled, or not found in either index
                 }
             }
         }
@@ -132,6 +142,7 @@ pub(super) fn run_ready_queue_direct(
     beans_dir: &Path,
     all_beans: &[SizedBean],
     index: &Index,
+    agent: &'static AgentPreset,
     cfg: &super::RunConfig,
     keep_going: bool,
 ) -> Result<(Vec<AgentResult>, bool)> {
@@ -140,6 +151,7 @@ pub(super) fn run_ready_queue_direct(
     let idle_timeout_minutes = cfg.idle_timeout_minutes;
     let json_stream = cfg.json_stream;
     let file_locking = cfg.file_locking;
+    let worktree = cfg.worktree;
     let all_bean_ids: HashSet<String> = all_beans.iter().map(|b| b.id.clone()).collect();
 
     // Already-closed beans count as completed (same logic as compute_waves)
@@ -158,6 +170,15 @@ pub(super) fn run_ready_queue_direct(
     let mut results: Vec<AgentResult> = Vec::new();
     let mut running_count: usize = 0;
     let mut any_failed = false;
+    // Set once the run or day budget is spent; nothing new is dispatched after.
+    let mut budget_stop: Option<BudgetExceeded> = None;
+    // Retry bookkeeping: dispatches per bean, the failure summary handed to
+    // the next attempt, usage of failed attempts (folded into the bean's final
+    // result) and beans waiting out their retry delay.
+    let mut tries: HashMap<String, u32> = HashMap::new();
+    let mut last_failure: HashMap<String, String> = HashMap::new();
+    let mut earlier_usage: HashMap<String, Spend> = HashMap::new();
+    let mut delayed: Vec<(Instant, SizedBean)> = Vec::new();
 
     // Channel for completed agents to report back
     let (tx, rx) = mpsc::channel::<AgentResult>();
@@ -176,6 +197,14 @@ pub(super) fn run_ready_queue_direct(
     };
 
     loop {
+        // Retries whose delay has passed go back in the queue
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = delayed.into_iter().partition(|(at, _)| *at <= now);
+        delayed = waiting;
+        for (_, sb) in due {
+            remaining.insert(sb.id.clone(), sb);
+        }
+
         // Find beans that are ready and we have capacity for
         let mut newly_started = 0;
         let ready_ids: Vec<String> = remaining
@@ -199,10 +228,29 @@ pub(super) fn run_ready_queue_direct(
             if running_count >= max_jobs {
                 break;
             }
+            if budget_stop.is_none() {
+                budget_stop = cfg.budget.exhausted();
+                if let Some(ref hit) = budget_stop {
+                    report_budget_stop(hit, json_stream);
+                    // Pending retries won't run either
+                    for (_, sb) in delayed.drain(..) {
+                        remaining.insert(sb.id.clone(), sb);
+                    }
+                }
+            }
+            if budget_stop.is_some() {
+                break;
+            }
 
             remaining.remove(&sb.id);
             running_count += 1;
             let round = wave_map.get(&sb.id).copied().unwrap_or(1);
+            let attempt = {
+                let n = tries.entry(sb.id.clone()).or_insert(0);
+                *n += 1;
+                *n
+            };
+            let previous_failure = last_failure.remove(&sb.id);
 
             if json_stream {
                 stream::emit(&StreamEvent::BeanStart {
@@ -210,9 +258,11 @@ pub(super) fn run_ready_queue_direct(
                     title: sb.title.clone(),
                     round,
                     file_overlaps: None,
-                    attempt: None,
+                    attempt: (attempt > 1).then_some(attempt),
                     priority: None,
                 });
+            } else if attempt > 1 {
+                eprintln!("  ▸ {}  {}  (attempt {})", sb.id, sb.title, attempt);
             } else {
                 eprintln!("  ▸ {}  {}", sb.id, sb.title);
             }
@@ -221,24 +271,44 @@ pub(super) fn run_ready_queue_direct(
             let tx = tx.clone();
             let timeout_min = timeout_minutes;
             let idle_min = idle_timeout_minutes;
+            let journal = cfg.journal.clone();
+            let budget = cfg.budget.clone();
 
             std::thread::spawn(move || {
                 let result = run_single_direct(
                     &beans_dir,
                     &sb,
+                    agent,
+                    previous_failure,
                     timeout_min,
                     idle_min,
                     json_stream,
                     file_locking,
+                    worktree,
+                    &budget,
+                    journal.as_ref(),
                 );
+                if let Some(ref journal) = journal {
+                    journal.mark_done(&result);
+                }
                 let _ = tx.send(result);
             });
             newly_started += 1;
         }
 
+        let next_retry = delayed.iter().map(|(at, _)| *at).min();
+
         // If nothing is running and nothing can start, we're done (or stuck)
         if running_count == 0 && newly_started == 0 {
-            if !remaining.is_empty() {
+            if let Some(at) = next_retry {
+                std::thread::sleep(at.saturating_duration_since(Instant::now()));
+                continue;
+            }
+            if budget_stop.is_some() {
+                if !remaining.is_empty() && !json_stream {
+                    eprintln!("Budget exhausted: {} bean(s) not started", remaining.len());
+                }
+            } else if !remaining.is_empty() {
                 // Remaining beans have unresolvable deps
                 if json_stream {
                     stream::emit_error(&format!(
@@ -261,9 +331,23 @@ pub(super) fn run_ready_queue_direct(
         // If nothing is running (but we just started some), loop to check for
         // more readiness after spawning
         if running_count > 0 {
-            // Wait for any one agent to complete
-            let result = rx.recv().expect("channel closed unexpectedly");
+            // Wait for any one agent to complete, or for a retry to come due
+            let mut result = match next_retry {
+                Some(at) => match rx.recv_timeout(at.saturating_duration_since(Instant::now())) {
+                    Ok(result) => result,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        bail!("Agent result channel closed unexpectedly")
+                    }
+                },
+                None => rx
+                    .recv()
+                    .map_err(|_| anyhow!("Agent result channel closed unexpectedly"))?,
+            };
             running_count -= 1;
+            if let Some(earlier) = earlier_usage.remove(&result.id) {
+                add_usage(&mut result, earlier);
+            }
 
             let success = result.success;
             let bean_id = result.id.clone();
@@ -273,6 +357,49 @@ pub(super) fn run_ready_queue_direct(
                 print_result_line(&result);
             }
 
+            // A failed bean may get another attempt from its on_fail policy
+            // (unless it was stopped by the budget, which a retry can't fix)
+            let budget_killed = result
+                .error
+                .as_deref()
+                .is_some_and(|e| e.starts_with(budget::BUDGET_EXCEEDED));
+            if !success && !budget_killed && budget_stop.is_none() {
+                let attempts = tries.get(&bean_id).copied().unwrap_or(1);
+                let retry = match retry::after_failure(beans_dir, &bean_id, attempts) {
+                    RetryDecision::Retry {
+                        next_attempt,
+                        max,
+                        delay,
+                    } => Some((next_attempt, max, delay, None)),
+                    RetryDecision::Escalate {
+                        next_attempt,
+                        max,
+                        priority,
+                    } => Some((next_attempt, max, Duration::ZERO, Some(priority))),
+                    RetryDecision::GiveUp => None,
+                };
+                let bean = all_beans.iter().find(|b| b.id == bean_id);
+                if let (Some((next_attempt, max, delay, priority)), Some(bean)) = (retry, bean) {
+                    report_retry(&result, next_attempt, max, delay, priority, json_stream);
+                    let mut bean = bean.clone();
+                    if let Some(p) = priority {
+                        bean.priority = p;
+                    }
+                    if let Some(summary) = result.failure_summary.take() {
+                        last_failure.insert(bean_id.clone(), summary);
+                    }
+                    earlier_usage.insert(
+                        bean_id,
+                        Spend {
+                            tokens: result.total_tokens.unwrap_or(0),
+                            cost: result.total_cost.unwrap_or(0.0),
+                        },
+                    );
+                    delayed.push((Instant::now() + delay, bean));
+                    continue;
+                }
+            }
+
             if success {
                 completed.insert(bean_id.clone());
             } else {
@@ -307,16 +434,99 @@ pub(super) fn run_ready_queue_direct(
     Ok((results, any_failed))
 }
 
-/// Run a single bean by spawning pi directly.
+/// Add the usage of a bean's earlier, failed attempts to its latest result.
+fn add_usage(result: &mut AgentResult, earlier: Spend) {
+    if earlier.tokens > 0 {
+        result.total_tokens = Some(result.total_tokens.unwrap_or(0) + earlier.tokens);
+    }
+    if earlier.cost > 0.0 {
+        result.total_cost = Some(result.total_cost.unwrap_or(0.0) + earlier.cost);
+    }
+}
+
+/// Tell the user that a failed bean will be dispatched again.
+fn report_retry(
+    result: &AgentResult,
+    next_attempt: u32,
+    max: u32,
+    delay: Duration,
+    priority: Option<u8>,
+    json_stream: bool,
+) {
+    if json_stream {
+        stream::emit(&StreamEvent::BeanRetry {
+            id: result.id.clone(),
+            attempt: next_attempt,
+            max_attempts: max,
+            delay_secs: delay.as_secs(),
+            priority,
+            error: result.error.clone(),
+        });
+        return;
+    }
+    let when = if delay.is_zero() {
+        String::new()
+    } else {
+        format!(" in {}", format_duration(delay))
+    };
+    match priority {
+        Some(p) => eprintln!(
+            "  ↻ {}  escalated to P{}, attempt {}/{}{}",
+            result.id, p, next_attempt, max, when
+        ),
+        None => eprintln!(
+            "  ↻ {}  retrying, attempt {}/{}{}",
+            result.id, next_attempt, max, when
+        ),
+    }
+}
+
+/// Tell the user that the run or day budget stopped dispatch.
+fn report_budget_stop(hit: &BudgetExceeded, json_stream: bool) {
+    if json_stream {
+        stream::emit(&StreamEvent::BudgetExceeded {
+            scope: hit.scope.to_string(),
+            id: None,
+            reason: hit.reason.clone(),
+        });
+    } else {
+        eprintln!(
+            "  ⚠ Budget exhausted ({}) — not dispatching more beans",
+            hit.reason
+        );
+    }
+}
+
+/// Run a single bean by spawning its agent directly.
+///
+/// With `worktree` set, the agent runs in a dedicated git worktree which is
+/// merged back (or discarded on failure) once the agent exits. The agent's
+/// output is copied to a log file (see `bn logs`), and its PID and log path
+/// are recorded in the run journal, if any.
+///
+/// `agent` decides the command line, how the prompt is delivered and how its
+/// output is parsed. `previous_failure` is the failure summary of the bean's
+/// last attempt when this run is retrying it.
+///
+/// Token usage is reported to `budget` as it streams in; if the agent reaches
+/// a budget limit it is killed and a `cancelled` run is added to the bean's
+/// history.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn run_single_direct(
     beans_dir: &Path,
     sb: &SizedBean,
+    agent: &AgentPreset,
+    previous_failure: Option<String>,
     timeout_minutes: u32,
     idle_timeout_minutes: u32,
     json_stream: bool,
     file_locking: bool,
+    worktree: bool,
+    budget: &BudgetTracker,
+    journal: Option<&JournalHandle>,
 ) -> AgentResult {
     let started = Instant::now();
+    let started_at = chrono::Utc::now();
 
     // Pre-emptive file locking: lock files listed in the bean's `paths` field.
     if file_locking && !sb.paths.is_empty() {
@@ -350,7 +560,6 @@ pub(super) fn run_single_direct(
             return AgentResult {
                 id: sb.id.clone(),
                 title: sb.title.clone(),
-                action: sb.action,
                 success: false,
                 duration: started.elapsed(),
                 total_tokens: None,
@@ -369,7 +578,6 @@ pub(super) fn run_single_direct(
             return AgentResult {
                 id: sb.id.clone(),
                 title: sb.title.clone(),
-                action: sb.action,
                 success: false,
                 duration: started.elapsed(),
                 total_tokens: None,
@@ -387,6 +595,7 @@ pub(super) fn run_single_direct(
         beans_dir: beans_dir.to_path_buf(),
         instructions: None,
         concurrent_overlaps: None,
+        previous_failure,
     };
 
     let prompt_result = match build_agent_prompt(&bean, &prompt_options) {
@@ -395,7 +604,6 @@ pub(super) fn run_single_direct(
             return AgentResult {
                 id: sb.id.clone(),
                 title: sb.title.clone(),
-                action: sb.action,
                 success: false,
                 duration: started.elapsed(),
                 total_tokens: None,
@@ -408,41 +616,108 @@ pub(super) fn run_single_direct(
         }
     };
 
-    // Build pi command using structured prompt fields
-    let mut cmd = Command::new("pi");
-    cmd.args(["--mode", "json", "--print", "--no-session"]);
+    // Isolate the agent in its own worktree so parallel agents don't share files
+    let agent_worktree = if worktree {
+        match crate::worktree::prepare_agent_worktree(beans_dir, &sb.id) {
+            Ok(wt) => Some(wt),
+            Err(e) => {
+                return AgentResult {
+                    id: sb.id.clone(),
+                    title: sb.title.clone(),
+                    success: false,
+                    duration: started.elapsed(),
+                    total_tokens: None,
+                    total_cost: None,
+                    error: Some(format!("Failed to create worktree: {}", e)),
+                    tool_count: 0,
+                    turns: 0,
+                    failure_summary: Some(format!("Failed to create worktree: {}", e)),
+                };
+            }
+        }
+    } else {
+        None
+    };
 
-    if !prompt_result.system_prompt.is_empty() {
-        cmd.args(["--append-system-prompt", &prompt_result.system_prompt]);
+    // Build the agent command from its preset and the structured prompt
+    let prompt_path =
+        std::env::temp_dir().join(format!("bn-prompt-{}-{}.md", sb.id, std::process::id()));
+    let invocation = agent.direct.invocation(&prompt_result, &prompt_path);
+    if let Some((ref path, ref message)) = invocation.prompt_file {
+        if let Err(e) = std::fs::write(path, message) {
+            if let Some(ref wt) = agent_worktree {
+                let _ = crate::worktree::finish_agent_worktree(beans_dir, wt, &sb.id, false);
+            }
+            return AgentResult {
+                id: sb.id.clone(),
+                title: sb.title.clone(),
+                success: false,
+                duration: started.elapsed(),
+                total_tokens: None,
+                total_cost: None,
+                error: Some(format!("Failed to write prompt file: {}", e)),
+                tool_count: 0,
+                turns: 0,
+                failure_summary: Some(format!("Failed to write prompt file: {}", e)),
+            };
+        }
     }
 
-    if !prompt_result.file_ref.is_empty() {
-        cmd.arg(&prompt_result.file_ref);
-    }
-    cmd.arg(&prompt_result.user_message);
+    let mut cmd = Command::new(invocation.program);
+    cmd.args(&invocation.args);
+    cmd.stdin(if invocation.stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
+    if let Some(ref wt) = agent_worktree {
+        cmd.current_dir(&wt.agent_dir);
+    }
 
     // Spawn the process
     let mut child = match cmd.spawn() {
         Ok(c) => c,
         Err(e) => {
+            if let Some((ref path, _)) = invocation.prompt_file {
+                let _ = std::fs::remove_file(path);
+            }
+            if let Some(ref wt) = agent_worktree {
+                let _ = crate::worktree::finish_agent_worktree(beans_dir, wt, &sb.id, false);
+            }
             return AgentResult {
                 id: sb.id.clone(),
                 title: sb.title.clone(),
-                action: sb.action,
                 success: false,
                 duration: started.elapsed(),
                 total_tokens: None,
                 total_cost: None,
-                error: Some(format!("Failed to spawn pi: {}", e)),
+                error: Some(format!("Failed to spawn {}: {}", invocation.program, e)),
                 tool_count: 0,
                 turns: 0,
-                failure_summary: Some(format!("Failed to spawn pi: {}", e)),
+                failure_summary: Some(format!("Failed to spawn {}: {}", invocation.program, e)),
             };
         }
     };
 
+    // Feed the prompt on a separate thread so a chatty agent can't deadlock
+    // on a full stdout pipe while we're still writing.
+    if let (Some(input), Some(mut stdin)) = (invocation.stdin, child.stdin.take()) {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
+    // Copy agent output to a log file; logging is best-effort
+    let log_path = crate::spawner::build_log_path(&sb.id).ok();
+    let mut log_file = log_path.as_ref().and_then(|p| File::create(p).ok());
+    let log_path = log_path.filter(|_| log_file.is_some());
+
+    if let Some(journal) = journal {
+        journal.mark_running(&sb.id, child.id(), log_path);
+    }
+
     // Take stdout for monitoring
     let stdout = match child.stdout.take() {
         Some(s) => s,
@@ -451,7 +726,6 @@ pub(super) fn run_single_direct(
             return AgentResult {
                 id: sb.id.clone(),
                 title: sb.title.clone(),
-                action: sb.action,
                 success: false,
                 duration: started.elapsed(),
                 total_tokens: None,
@@ -480,93 +754,122 @@ pub(super) fn run_single_direct(
     let mut turns: usize = 0;
     let bean_id = sb.id.clone();
     let mut shown_thinking = false;
+    let mut parser = agent_output::parser_for(agent.output_format);
+    // Usage already reported to the budget, and the limit that stopped the agent.
+    let mut reported = Spend::default();
+    let mut budget_hit: Option<BudgetExceeded> = None;
 
-    // Monitor the process, parsing JSON events
+    // Monitor the process, parsing agent events
     let monitor_result = timeout::monitor_process(&mut child, stdout, &timeout_config, |line| {
-        // Try to parse each line as a JSON event from pi
-        if let Ok(raw) = serde_json::from_str::<serde_json::Value>(line) {
-            if let Some(event) = pi_output::parse_agent_event(&raw) {
-                match event {
-                    AgentEvent::Thinking { ref text } => {
-                        if json_stream {
-                            stream::emit(&StreamEvent::BeanThinking {
-                                id: bean_id.clone(),
-                                text: text.clone(),
-                            });
-                        } else if !shown_thinking {
-                            eprintln!("  {}  thinking...", bean_id);
-                            shown_thinking = true;
-                        }
-                    }
-                    AgentEvent::ToolStart { ref name, .. } => {
-                        tool_count += 1;
-                        if json_stream {
-                            stream::emit(&StreamEvent::BeanTool {
-                                id: bean_id.clone(),
-                                tool_name: name.clone(),
-                                tool_count,
-                                file_path: None,
-                            });
-                        }
+        if let Some(ref mut log) = log_file {
+            let _ = writeln!(log, "{}", line);
+        }
+        let mut finished = false;
+        for event in parser.parse_line(line) {
+            match event {
+                AgentEvent::Thinking { ref text } => {
+                    if json_stream {
+                        stream::emit(&StreamEvent::BeanThinking {
+                            id: bean_id.clone(),
+                            text: text.clone(),
+                        });
+                    } else if !shown_thinking {
+                        eprintln!("  {}  thinking...", bean_id);
+                        shown_thinking = true;
                     }
-                    AgentEvent::ToolEnd {
-                        ref name,
-                        ref arguments,
-                    } => {
-                        let file_path = pi_output::extract_file_path(name, arguments);
-                        tool_log.push(format!(
-                            "[tool] {} {}",
-                            name,
-                            file_path.as_deref().unwrap_or("")
-                        ));
-                        if json_stream {
-                            stream::emit(&StreamEvent::BeanTool {
-                                id: bean_id.clone(),
-                                tool_name: name.clone(),
-                                tool_count,
-                                file_path,
-                            });
-                        } else {
-                            match file_path {
-                                Some(ref p) => eprintln!("  {}  ⚙ {} {}", bean_id, name, p),
-                                None => eprintln!("  {}  ⚙ {}", bean_id, name),
-                            }
-                        }
+                }
+                AgentEvent::ToolStart { ref name, .. } => {
+                    tool_count += 1;
+                    if json_stream {
+                        stream::emit(&StreamEvent::BeanTool {
+                            id: bean_id.clone(),
+                            tool_name: name.clone(),
+                            tool_count,
+                            file_path: None,
+                        });
                     }
-                    AgentEvent::TokenUpdate {
-                        input_tokens,
-                        output_tokens,
-                        cache_read,
-                        cache_write,
-                        cost,
-                    } => {
-                        cumulative_tokens += input_tokens + output_tokens;
-                        cumulative_input_tokens += input_tokens;
-                        cumulative_output_tokens += output_tokens;
-                        cumulative_cost += cost;
-                        turns += 1;
-                        if json_stream {
-                            stream::emit(&StreamEvent::BeanTokens {
-                                id: bean_id.clone(),
-                                input_tokens,
-                                output_tokens,
-                                cache_read,
-                                cache_write,
-                                cost,
-                            });
+                }
+                AgentEvent::ToolEnd {
+                    ref name,
+                    ref arguments,
+                } => {
+                    let file_path = pi_output::extract_file_path(name, arguments);
+                    tool_log.push(format!(
+                        "[tool] {} {}",
+                        name,
+                        file_path.as_deref().unwrap_or("")
+                    ));
+                    if json_stream {
+                        stream::emit(&StreamEvent::BeanTool {
+                            id: bean_id.clone(),
+                            tool_name: name.clone(),
+                            tool_count,
+                            file_path,
+                        });
+                    } else {
+                        match file_path {
+                            Some(ref p) => eprintln!("  {}  ⚙ {} {}", bean_id, name, p),
+                            None => eprintln!("  {}  ⚙ {}", bean_id, name),
                         }
                     }
-                    AgentEvent::Finished { total_tokens, cost } => {
-                        cumulative_tokens = total_tokens;
-                        cumulative_cost = cost;
+                }
+                AgentEvent::TokenUpdate {
+                    input_tokens,
+                    output_tokens,
+                    cache_read,
+                    cache_write,
+                    cost,
+                } => {
+                    cumulative_tokens += input_tokens + output_tokens;
+                    cumulative_input_tokens += input_tokens;
+                    cumulative_output_tokens += output_tokens;
+                    cumulative_cost += cost;
+                    turns += 1;
+                    if json_stream {
+                        stream::emit(&StreamEvent::BeanTokens {
+                            id: bean_id.clone(),
+                            input_tokens,
+                            output_tokens,
+                            cache_read,
+                            cache_write,
+                            cost,
+                        });
                     }
-                    _ => {}
                 }
+                AgentEvent::Finished { total_tokens, cost } => {
+                    cumulative_tokens = total_tokens;
+                    cumulative_cost = cost;
+                    finished = true;
+                }
+                _ => {}
+            }
+        }
+
+        let spent = Spend {
+            tokens: cumulative_tokens.max(reported.tokens),
+            cost: cumulative_cost.max(reported.cost),
+        };
+        budget.record(Spend {
+            tokens: spent.tokens - reported.tokens,
+            cost: spent.cost - reported.cost,
+        });
+        reported = spent;
+
+        // An agent that already finished keeps its result, even if over budget
+        if !finished {
+            if let Some(hit) = budget.check_agent(spent) {
+                budget_hit = Some(hit);
+                return ControlFlow::Break(());
             }
         }
+        ControlFlow::Continue(())
     });
 
     let duration = started.elapsed();
+    // The agent has exited, so its prompt file is no longer needed
+    if let Some((ref path, _)) = invocation.prompt_file {
+        let _ = std::fs::remove_file(path);
+    }
 
     // Determine success
     let (success, error) = match monitor_result {
@@ -589,13 +892,49 @@ pub(super) fn run_single_direct(
             false,
             Some(format!("Idle timeout exceeded ({}m)", idle_timeout_minutes)),
         ),
+        MonitorResult::Stopped => {
+            let reason = budget_hit
+                .as_ref()
+                .map(|hit| hit.reason.clone())
+                .unwrap_or_else(|| "stopped".to_string());
+            (
+                false,
+                Some(format!("{}: {}", budget::BUDGET_EXCEEDED, reason)),
+            )
+        }
     };
 
+    if let Some(ref hit) = budget_hit {
+        if json_stream {
+            stream::emit(&StreamEvent::BudgetExceeded {
+                scope: hit.scope.to_string(),
+                id: Some(sb.id.clone()),
+                reason: hit.reason.clone(),
+            });
+        }
+        if let Err(e) = budget::record_cancelled(
+            beans_dir,
+            &sb.id,
+            agent.name,
+            started_at,
+            reported,
+            &hit.reason,
+        ) {
+            eprintln!("  ⚠ Failed to record budget cancel for {}: {}", sb.id, e);
+        }
+    }
+
     // Release all file locks held by this bean.
     if file_locking {
         let _ = crate::locks::release_all_for_bean(beans_dir, &sb.id);
     }
 
+    // Merge the agent's worktree back (or discard it on failure)
+    let (success, error) = match agent_worktree {
+        Some(ref wt) => finish_worktree(beans_dir, wt, &sb.id, success, error),
+        None => (success, error),
+    };
+
     // Log to agent_history.jsonl (fire-and-forget)
     history::append_history(
         beans_dir,
@@ -633,6 +972,11 @@ pub(super) fn run_single_direct(
                     error: error.clone(),
                     tool_log,
                     verify_command: fresh_bean.verify.clone(),
+                    tests: fresh_bean
+                        .history
+                        .last()
+                        .filter(|r| r.result != RunResult::Pass)
+                        .and_then(|r| r.tests.clone()),
                 };
                 let summary = failure::build_failure_summary(&ctx);
 
@@ -655,7 +999,6 @@ pub(super) fn run_single_direct(
     AgentResult {
         id: sb.id.clone(),
         title: sb.title.clone(),
-        action: sb.action,
         success,
         duration,
         total_tokens: if cumulative_tokens > 0 {
@@ -675,6 +1018,35 @@ pub(super) fn run_single_direct(
     }
 }
 
+/// Finalize an agent worktree, folding merge problems into the agent's result.
+///
+/// A merge conflict turns a successful agent run into a failure, since the work
+/// did not land on main.
+pub(super) fn finish_worktree(
+    beans_dir: &Path,
+    wt: &crate::worktree::AgentWorktree,
+    bean_id: &str,
+    success: bool,
+    error: Option<String>,
+) -> (bool, Option<String>) {
+    match crate::worktree::finish_agent_worktree(beans_dir, wt, bean_id, success) {
+        Ok(Some(crate::worktree::MergeResult::Conflict { files })) => (
+            false,
+            Some(format!(
+                "Merge conflict in {} — resolve in {}",
+                files.join(", "),
+                wt.info.worktree_path.display()
+            )),
+        ),
+        Ok(_) => (success, error),
+        Err(e) if success => (false, Some(format!("Worktree merge failed: {:#}", e))),
+        Err(e) => {
+            eprintln!("  ⚠ Worktree for {}: {:#}", bean_id, e);
+            (success, error)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -856,6 +1228,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
         let result = build_agent_prompt(&bean, &options);
         assert!(result.is_ok());
@@ -880,6 +1253,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
         let result = build_agent_prompt(&bean, &options).unwrap();
         assert!(result.system_prompt.contains("Project Rules"));
@@ -912,6 +1286,7 @@ mod tests {
             claimed_by: None,
             attempts: 0,
             paths: vec![],
+            blocked_reason: None,
         }
     }
 
@@ -1019,4 +1394,30 @@ mod tests {
 
         assert!(all_deps_closed(&entry_c, &index, &archive));
     }
+
+    #[test]
+    fn all_deps_closed_with_cancelled_dep() {
+        let entry_a = make_index_entry("A", Status::Cancelled, vec![], None, vec![], vec![]);
+        let entry_b = make_index_entry("B", Status::Open, vec!["A"], None, vec![], vec![]);
+        let index = Index {
+            beans: vec![entry_a, entry_b.clone()],
+        };
+        let archive = ArchiveIndex { beans: vec![] };
+
+        assert!(!all_deps_closed(&entry_b, &index, &archive));
+    }
+
+    #[test]
+    fn all_deps_closed_with_archived_cancelled_dep() {
+        let entry_b = make_index_entry("B", Status::Open, vec!["A"], None, vec![], vec![]);
+        let index = Index {
+            beans: vec![entry_b.clone()],
+        };
+        let archived_a = make_index_entry("A", Status::Cancelled, vec![], None, vec![], vec![]);
+        let archive = ArchiveIndex {
+            beans: vec![archived_a],
+        };
+
+        assert!(!all_deps_closed(&entry_b, &index, &archive));
+    }
 }
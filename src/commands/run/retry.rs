@@ -0,0 +1,310 @@
+//! Retry policy for failed agents in `bn run`.
+//!
+//! A bean whose agent fails is re-dispatched according to its `on_fail`
+//! action. `retry` puts it back in the queue after `delay_secs`, doubling the
+//! delay for every further attempt. `escalate` raises its priority so it goes
+//! to the front of the queue and dispatches it again right away. Both stop once
+//! the bean has used `max` (or its `max_attempts`) attempts.
+//!
+//! A bean is escalated once: if it already carries the `escalated` label
+//! (from an earlier failure in this run, or from `bn close` applying the same
+//! policy), it is re-dispatched at its current priority.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::bean::{merge, Bean, OnFailAction};
+use crate::config::resolve_identity;
+use crate::discovery::find_bean_file;
+use crate::events::{self, Action};
+
+/// Longest delay between two attempts, however many attempts have failed.
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// What to do with a bean whose agent just failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum RetryDecision {
+    /// Dispatch again once `delay` has passed.
+    Retry {
+        next_attempt: u32,
+        max: u32,
+        delay: Duration,
+    },
+    /// Raise the bean to `priority` and dispatch it again right away.
+    Escalate {
+        next_attempt: u32,
+        max: u32,
+        priority: u8,
+    },
+    /// No `on_fail` policy, or no attempts left.
+    GiveUp,
+}
+
+/// Decide how to handle a failure after `attempts` attempts (including the
+/// one that just failed).
+pub(super) fn decide(bean: &Bean, attempts: u32) -> RetryDecision {
+    match bean.on_fail {
+        Some(OnFailAction::Retry { max, delay_secs }) => {
+            let max = max.unwrap_or(bean.max_attempts);
+            if attempts >= max {
+                return RetryDecision::GiveUp;
+            }
+            RetryDecision::Retry {
+                next_attempt: attempts + 1,
+                max,
+                delay: backoff(delay_secs.unwrap_or(0), attempts),
+            }
+        }
+        Some(OnFailAction::Escalate { priority, .. }) => {
+            let max = bean.max_attempts;
+            if attempts >= max {
+                return RetryDecision::GiveUp;
+            }
+            RetryDecision::Escalate {
+                next_attempt: attempts + 1,
+                max,
+                // Without an explicit priority, move up one level
+                priority: priority.unwrap_or(bean.priority.saturating_sub(1)),
+            }
+        }
+        None => RetryDecision::GiveUp,
+    }
+}
+
+/// Decide what happens to a bean whose agent failed after `tries` dispatches
+/// in this run, and apply an escalation to the bean file if there is one.
+///
+/// Attempts are the larger of `tries` and the bean's own `attempts` counter,
+/// which `bn close` bumps on every failed verify.
+pub(super) fn after_failure(beans_dir: &Path, bean_id: &str, tries: u32) -> RetryDecision {
+    let bean = match find_bean_file(beans_dir, bean_id).and_then(|p| Bean::from_file(&p)) {
+        Ok(bean) => bean,
+        Err(_) => return RetryDecision::GiveUp,
+    };
+    let mut decision = decide(&bean, bean.attempts.max(tries));
+    if let RetryDecision::Escalate {
+        ref mut priority, ..
+    } = decision
+    {
+        if is_escalated(&bean) {
+            *priority = bean.priority;
+        } else if let Err(e) = escalate(beans_dir, bean_id, *priority) {
+            eprintln!("  ⚠ Failed to escalate bean {}: {}", bean_id, e);
+        }
+    }
+    decision
+}
+
+/// Whether the bean was already escalated, by `bn run` or by `bn close`.
+fn is_escalated(bean: &Bean) -> bool {
+    bean.labels.iter().any(|l| l == "escalated")
+}
+
+/// Exponential backoff:`base`, `2 × base`, `4 × base`, ... capped at
+/// [`MAX_BACKOFF`]. `failures` counts the attempts that have failed so far.
+fn backoff(base_secs: u64, failures: u32) -> Duration {
+    let factor = 1u64
+        .checked_shl(failures.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+    Duration::from_secs(base_secs.saturating_mul(factor)).min(MAX_BACKOFF)
+}
+
+/// Apply an escalation to the bean on disk: set its priority, label it
+/// `escalated`, and record the policy's message in its notes. Does nothing
+/// if the bean is already escalated.
+pub(super) fn escalate(beans_dir: &Path, bean_id: &str, priority: u8) -> Result<()> {
+    let path = find_bean_file(beans_dir, bean_id)?;
+    let mut bean = Bean::from_file(&path)?;
+    if is_escalated(&bean) {
+        return Ok(());
+    }
+    let base = bean.clone();
+    bean.priority = priority;
+    bean.labels.push("escalated".to_string());
+    if let Some(OnFailAction::Escalate {
+        message: Some(ref message),
+        ..
+    }) = bean.on_fail
+    {
+        let note = format!(
+            "\n## Escalated — {}\n{}",
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            message
+        );
+        bean.notes.get_or_insert_with(String::new).push_str(&note);
+    }
+    bean.updated_at = Utc::now();
+
+    let agent = resolve_identity(beans_dir);
+    let conflicts = merge::save_merged(&mut bean, &base, &path, agent.as_deref())?;
+    merge::warn_conflicts(&mut std::io::stderr(), bean_id, &conflicts);
+    events::record_with_note(
+        beans_dir,
+        Action::Update,
+        bean_id,
+        Some(&base),
+        Some(&bean),
+        Some("on_fail: escalate".to_string()),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn bean_with(on_fail: Option<OnFailAction>) -> Bean {
+        let mut bean = Bean::new("1", "Flaky");
+        bean.on_fail = on_fail;
+        bean
+    }
+
+    #[test]
+    fn no_policy_gives_up() {
+        assert_eq!(decide(&bean_with(None), 1), RetryDecision::GiveUp);
+    }
+
+    #[test]
+    fn retry_backs_off_exponentially_until_max() {
+        let bean = bean_with(Some(OnFailAction::Retry {
+            max: Some(4),
+            delay_secs: Some(10),
+        }));
+        let delays: Vec<RetryDecision> = (1..=4).map(|n| decide(&bean, n)).collect();
+        assert_eq!(
+            delays[0],
+            RetryDecision::Retry {
+                next_attempt: 2,
+                max: 4,
+                delay: Duration::from_secs(10),
+            }
+        );
+        assert_eq!(
+            delays[2],
+            RetryDecision::Retry {
+                next_attempt: 4,
+                max: 4,
+                delay: Duration::from_secs(40),
+            }
+        );
+        assert_eq!(delays[3], RetryDecision::GiveUp);
+    }
+
+    #[test]
+    fn retry_defaults_to_max_attempts_and_no_delay() {
+        let mut bean = bean_with(Some(OnFailAction::Retry {
+            max: None,
+            delay_secs: None,
+        }));
+        bean.max_attempts = 2;
+        assert!(matches!(
+            decide(&bean, 1),
+            RetryDecision::Retry { delay, max: 2, .. } if delay == Duration::ZERO
+        ));
+        assert_eq!(decide(&bean, 2), RetryDecision::GiveUp);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff(30, 1), Duration::from_secs(30));
+        assert_eq!(backoff(30, 2), Duration::from_secs(60));
+        assert_eq!(backoff(30, 40), MAX_BACKOFF);
+        assert_eq!(backoff(u64::MAX, 3), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn escalate_bumps_priority() {
+        let mut bean = bean_with(Some(OnFailAction::Escalate {
+            priority: None,
+            message: None,
+        }));
+        bean.priority = 2;
+        assert_eq!(
+            decide(&bean, 1),
+            RetryDecision::Escalate {
+                next_attempt: 2,
+                max: 3,
+                priority: 1,
+            }
+        );
+
+        bean.on_fail = Some(OnFailAction::Escalate {
+            priority: Some(0),
+            message: None,
+        });
+        assert!(matches!(
+            decide(&bean, 1),
+            RetryDecision::Escalate { priority: 0, .. }
+        ));
+        assert_eq!(decide(&bean, 3), RetryDecision::GiveUp);
+    }
+
+    #[test]
+    fn escalate_updates_bean_on_disk() {
+        let dir = TempDir::new().unwrap();
+        let mut bean = bean_with(Some(OnFailAction::Escalate {
+            priority: Some(0),
+            message: Some("Needs a human".to_string()),
+        }));
+        bean.priority = 3;
+        bean.to_file(dir.path().join("1-flaky.md")).unwrap();
+
+        escalate(dir.path(), "1", 0).unwrap();
+
+        let bean = Bean::from_file(dir.path().join("1-flaky.md")).unwrap();
+        assert_eq!(bean.priority, 0);
+        assert!(bean.labels.contains(&"escalated".to_string()));
+        assert!(bean.notes.unwrap().contains("Needs a human"));
+    }
+
+    #[test]
+    fn escalates_only_once() {
+        let dir = TempDir::new().unwrap();
+        let mut bean = bean_with(Some(OnFailAction::Escalate {
+            priority: None,
+            message: Some("Needs a human".to_string()),
+        }));
+        bean.priority = 3;
+        bean.to_file(dir.path().join("1-flaky.md")).unwrap();
+
+        assert!(matches!(
+            after_failure(dir.path(), "1", 1),
+            RetryDecision::Escalate { priority: 2, .. }
+        ));
+        assert!(matches!(
+            after_failure(dir.path(), "1", 2),
+            RetryDecision::Escalate { priority: 2, .. }
+        ));
+
+        let bean = Bean::from_file(dir.path().join("1-flaky.md")).unwrap();
+        assert_eq!(bean.priority, 2);
+        assert_eq!(bean.notes.unwrap().matches("## Escalated").count(), 1);
+        let events = events::load_events(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, Action::Update);
+    }
+
+    #[test]
+    fn close_time_escalation_is_not_repeated() {
+        let dir = TempDir::new().unwrap();
+        let mut bean = bean_with(Some(OnFailAction::Escalate {
+            priority: None,
+            message: None,
+        }));
+        // `bn close` already applied the policy
+        bean.priority = 1;
+        bean.labels.push("escalated".to_string());
+        bean.to_file(dir.path().join("1-flaky.md")).unwrap();
+
+        assert!(matches!(
+            after_failure(dir.path(), "1", 1),
+            RetryDecision::Escalate { priority: 1, .. }
+        ));
+        let bean = Bean::from_file(dir.path().join("1-flaky.md")).unwrap();
+        assert_eq!(bean.priority, 1);
+    }
+}
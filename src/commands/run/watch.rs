@@ -0,0 +1,331 @@
+//! `bn run --watch` — long-lived scheduler that reacts to bean changes.
+//!
+//! Unlike `--loop`, watch mode does not stop when nothing is ready. It polls
+//! `.beans/` for new or edited beans and, whenever a slot is free, dispatches
+//! newly-ready work through a [`Spawner`] (up to `max_concurrent` agents).
+//! Ctrl-C (or SIGTERM) kills the running agents and releases their claims
+//! via [`Spawner::kill_all`] before exiting.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::index::Index;
+use crate::spawner::{AgentAction, CompletedAgent, Spawner};
+use crate::stream::{self, StreamEvent};
+
+use super::plan::{plan_dispatch, SizedBean};
+use super::{format_duration, RunArgs};
+
+/// How often to check for finished agents and changed beans.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Set by the signal handler; checked once per poll.
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_shutdown(_signal: libc::c_int) {
+    SHUTDOWN.store(true, Ordering::SeqCst);
+}
+
+/// Route SIGINT and SIGTERM to [`request_shutdown`].
+fn install_signal_handlers() {
+    let handler = request_shutdown as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+/// Watch mode: dispatch ready beans as they appear until interrupted.
+pub(super) fn run_watch(beans_dir: &Path, mut config: Config, args: &RunArgs) -> Result<()> {
+    if config.run.is_none() {
+        anyhow::bail!(
+            "`bn run --watch` spawns agents from the run template.\n\n\
+             Set one with: bn config set run \"<command>\""
+        );
+    }
+
+    SHUTDOWN.store(false, Ordering::SeqCst);
+    install_signal_handlers();
+
+    let max_jobs = args.jobs.min(config.max_concurrent).max(1);
+    let mut spawner = Spawner::new();
+    // Beans whose agent failed this session — not re-dispatched until restart.
+    let mut failed: HashSet<String> = HashSet::new();
+    let mut total_done = 0usize;
+    let mut total_failed = 0usize;
+    let run_start = Instant::now();
+
+    let mut rescan = true;
+    let mut last_seen = latest_mtime(beans_dir);
+
+    if !args.json_stream {
+        eprintln!(
+            "Watching {} (up to {} agent(s)). Press Ctrl-C to stop.",
+            beans_dir.display(),
+            max_jobs
+        );
+    }
+
+    while !SHUTDOWN.load(Ordering::SeqCst) {
+        let completed = spawner.check_completed();
+        for agent in &completed {
+            report_done(agent, args.json_stream);
+            if agent.success {
+                total_done += 1;
+            } else {
+                total_failed += 1;
+                failed.insert(agent.bean_id.clone());
+            }
+        }
+        if !completed.is_empty() {
+            rescan = true;
+        }
+
+        // Beans created via `bn` keep the index fresh, so also compare mtimes.
+        let mtime = latest_mtime(beans_dir);
+        if mtime > last_seen || Index::is_stale(beans_dir).unwrap_or(true) {
+            last_seen = mtime;
+            rescan = true;
+        }
+
+        if rescan && spawner.can_spawn(max_jobs) {
+            rescan = false;
+            // Pick up config edits (e.g. a new run template) between dispatches
+            if let Ok(fresh) = Config::load_with_extends(beans_dir) {
+                if fresh.run.is_some() {
+                    config = fresh;
+                }
+            }
+            match plan_dispatch(
+                beans_dir,
+                &config,
+                args.id.as_deref(),
+                args.filter.as_deref(),
+                args.auto_plan,
+                false,
+            ) {
+                Ok(plan) => {
+                    let running: HashSet<String> = spawner
+                        .list_running()
+                        .iter()
+                        .map(|p| p.bean_id.clone())
+                        .collect();
+                    let slots = max_jobs as usize - spawner.running_count();
+                    for bean in next_dispatch(&plan.all_beans, &running, &failed, slots) {
+                        match spawner.spawn(
+                            &bean.id,
+                            &bean.title,
+                            AgentAction::Implement,
+                            &config,
+                            Some(beans_dir),
+                        ) {
+                            Ok(()) => report_start(bean, args.json_stream),
+                            Err(e) => {
+                                if args.json_stream {
+                                    stream::emit_error(&e.to_string());
+                                } else {
+                                    eprintln!("  ✗ {}  {}  ({})", bean.id, bean.title, e);
+                                }
+                                total_failed += 1;
+                                failed.insert(bean.id.clone());
+                            }
+                        }
+                    }
+                }
+                // A half-written bean can fail to parse; retry on the next change.
+                Err(e) => eprintln!("Warning: failed to scan beans: {}", e),
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let running = spawner.running_count();
+    if running > 0 && !args.json_stream {
+        eprintln!(
+            "\nStopping: killing {} agent(s) and releasing their claims...",
+            running
+        );
+    }
+    spawner.kill_all();
+
+    if args.json_stream {
+        stream::emit(&StreamEvent::RunEnd {
+            total_success: total_done,
+            total_failed,
+            duration_secs: run_start.elapsed().as_secs(),
+        });
+    } else {
+        eprintln!(
+            "\nDone: {} succeeded, {} failed, {} interrupted  ({})",
+            total_done,
+            total_failed,
+            running,
+            format_duration(run_start.elapsed())
+        );
+    }
+
+    Ok(())
+}
+
+/// Pick up to `slots` ready beans to start, highest priority first,
+/// skipping beans that are already running or failed this session.
+fn next_dispatch<'a>(
+    ready: &'a [SizedBean],
+    running: &HashSet<String>,
+    failed: &HashSet<String>,
+    slots: usize,
+) -> Vec<&'a SizedBean> {
+    let mut candidates: Vec<&SizedBean> = ready
+        .iter()
+        .filter(|b| !running.contains(&b.id) && !failed.contains(&b.id))
+        .collect();
+    candidates.sort_by_key(|b| b.priority);
+    candidates.truncate(slots);
+    candidates
+}
+
+/// Most recent modification time of anything directly under `.beans/`.
+fn latest_mtime(beans_dir: &Path) -> Option<SystemTime> {
+    fs::read_dir(beans_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .max()
+}
+
+fn report_start(bean: &SizedBean, json_stream: bool) {
+    if json_stream {
+        stream::emit(&StreamEvent::BeanStart {
+            id: bean.id.clone(),
+            title: bean.title.clone(),
+            round: 1,
+            file_overlaps: None,
+            attempt: None,
+            priority: Some(bean.priority),
+        });
+    } else {
+        eprintln!("  ▸ {}  {}", bean.id, bean.title);
+    }
+}
+
+fn report_done(agent: &CompletedAgent, json_stream: bool) {
+    let error = (!agent.success).then(|| match agent.exit_code {
+        Some(code) => format!("exit code {}", code),
+        None => "killed".to_string(),
+    });
+    if json_stream {
+        stream::emit(&StreamEvent::BeanDone {
+            id: agent.bean_id.clone(),
+            success: agent.success,
+            duration_secs: agent.duration.as_secs(),
+            error,
+            total_tokens: None,
+            total_cost: None,
+            tool_count: None,
+            turns: None,
+            failure_summary: None,
+        });
+    } else {
+        let duration = format_duration(agent.duration);
+        match error {
+            None => eprintln!("  ✓ {}  {}  {}", agent.bean_id, agent.bean_title, duration),
+            Some(err) => eprintln!(
+                "  ✗ {}  {}  {} ({}, log: {})",
+                agent.bean_id,
+                agent.bean_title,
+                duration,
+                err,
+                agent.log_path.display()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::run::BeanAction;
+    use tempfile::TempDir;
+
+    fn sized(id: &str, priority: u8) -> SizedBean {
+        SizedBean {
+            id: id.to_string(),
+            title: format!("Bean {}", id),
+            action: BeanAction::Implement,
+            priority,
+            dependencies: vec![],
+            parent: None,
+            produces: vec![],
+            requires: vec![],
+            paths: vec![],
+        }
+    }
+
+    #[test]
+    fn next_dispatch_orders_by_priority_and_fills_slots() {
+        let ready = vec![sized("1", 3), sized("2", 0), sized("3", 1)];
+        let picked = next_dispatch(&ready, &HashSet::new(), &HashSet::new(), 2);
+        let ids: Vec<&str> = picked.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn next_dispatch_skips_running_and_failed() {
+        let ready = vec![sized("1", 2), sized("2", 2), sized("3", 2)];
+        let running: HashSet<String> = ["1".to_string()].into();
+        let failed: HashSet<String> = ["2".to_string()].into();
+        let picked = next_dispatch(&ready, &running, &failed, 4);
+        let ids: Vec<&str> = picked.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["3"]);
+    }
+
+    #[test]
+    fn next_dispatch_with_no_slots_is_empty() {
+        let ready = vec![sized("1", 2)];
+        assert!(next_dispatch(&ready, &HashSet::new(), &HashSet::new(), 0).is_empty());
+    }
+
+    #[test]
+    fn latest_mtime_tracks_new_files() {
+        let dir = TempDir::new().unwrap();
+        assert!(latest_mtime(dir.path()).is_none());
+
+        fs::write(dir.path().join("1-task.md"), "x").unwrap();
+        let first = latest_mtime(dir.path());
+        assert!(first.is_some());
+
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(dir.path().join("2-task.md"), "y").unwrap();
+        assert!(latest_mtime(dir.path()) > first);
+    }
+
+    #[test]
+    fn watch_requires_run_template() {
+        let dir = TempDir::new().unwrap();
+        let config = Config::default();
+        let args = RunArgs {
+            id: None,
+            jobs: 1,
+            dry_run: false,
+            loop_mode: false,
+            watch: true,
+            auto_plan: false,
+            keep_going: false,
+            timeout: 30,
+            idle_timeout: 5,
+            json_stream: false,
+            review: false,
+            resume: None,
+            filter: None,
+        };
+        let err = run_watch(dir.path(), config, &args).unwrap_err();
+        assert!(err.to_string().contains("run template"));
+    }
+}
@@ -6,13 +6,17 @@ use std::time::{Duration, Instant};
 
 use anyhow::Result;
 
+use crate::agent_presets::AgentPreset;
 use crate::bean::Status;
 use crate::index::Index;
 use crate::stream::{self, StreamEvent};
 use crate::util::natural_cmp;
+use crate::worktree::AgentWorktree;
 
+use super::budget::BudgetTracker;
+use super::journal::JournalHandle;
 use super::plan::SizedBean;
-use super::ready_queue::run_single_direct;
+use super::ready_queue::{finish_worktree, run_single_direct};
 use super::{AgentResult, BeanAction, SpawnMode};
 
 /// A wave of beans that can be dispatched in parallel.
@@ -108,35 +112,53 @@ pub(super) fn run_wave(
             run_template,
             plan_template,
         } => run_wave_template(
+            beans_dir,
             beans,
             run_template,
             plan_template.as_deref(),
             cfg.max_jobs,
             cfg.timeout_minutes,
+            cfg.worktree,
+            cfg.journal.as_ref(),
         ),
-        SpawnMode::Direct => run_wave_direct(
+        SpawnMode::Direct { agent } => run_wave_direct(
             beans_dir,
             beans,
+            agent,
             cfg.max_jobs,
             cfg.timeout_minutes,
             cfg.idle_timeout_minutes,
             cfg.json_stream,
             wave_number,
             cfg.file_locking,
+            cfg.worktree,
+            &cfg.budget,
+            cfg.journal.as_ref(),
         ),
     }
 }
 
 /// Template mode: spawn agents via `sh -c <template>` (backward compat).
+///
+/// With `worktree` set, each command runs inside its bean's own git worktree.
+#[allow(clippy::too_many_arguments)]
 fn run_wave_template(
+    beans_dir: &Path,
     beans: &[SizedBean],
     run_template: &str,
     _plan_template: Option<&str>,
     max_jobs: usize,
     _timeout_minutes: u32,
+    worktree: bool,
+    journal: Option<&JournalHandle>,
 ) -> Result<Vec<AgentResult>> {
     let mut results = Vec::new();
-    let mut children: Vec<(SizedBean, std::process::Child, Instant)> = Vec::new();
+    let mut children: Vec<(
+        SizedBean,
+        std::process::Child,
+        Instant,
+        Option<AgentWorktree>,
+    )> = Vec::new();
 
     let mut pending: Vec<&SizedBean> = beans.iter().collect();
 
@@ -148,17 +170,52 @@ fn run_wave_template(
                 BeanAction::Implement => run_template,
             };
 
+            let agent_worktree = if worktree {
+                match crate::worktree::prepare_agent_worktree(beans_dir, &sb.id) {
+                    Ok(wt) => Some(wt),
+                    Err(e) => {
+                        eprintln!("  Failed to create worktree for {}: {}", sb.id, e);
+                        results.push(AgentResult {
+                            id: sb.id.clone(),
+                            title: sb.title.clone(),
+                            success: false,
+                            duration: Duration::ZERO,
+                            total_tokens: None,
+                            total_cost: None,
+                            error: Some(format!("Failed to create worktree: {}", e)),
+                            tool_count: 0,
+                            turns: 0,
+                            failure_summary: None,
+                        });
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+
             let cmd = template.replace("{id}", &sb.id);
-            match Command::new("sh").args(["-c", &cmd]).spawn() {
+            let mut command = Command::new("sh");
+            command.args(["-c", &cmd]);
+            if let Some(ref wt) = agent_worktree {
+                command.current_dir(&wt.agent_dir);
+            }
+            match command.spawn() {
                 Ok(child) => {
-                    children.push((sb.clone(), child, Instant::now()));
+                    if let Some(journal) = journal {
+                        journal.mark_running(&sb.id, child.id(), None);
+                    }
+                    children.push((sb.clone(), child, Instant::now(), agent_worktree));
                 }
                 Err(e) => {
                     eprintln!("  Failed to spawn agent for {}: {}", sb.id, e);
+                    if let Some(ref wt) = agent_worktree {
+                        let _ =
+                            crate::worktree::finish_agent_worktree(beans_dir, wt, &sb.id, false);
+                    }
                     results.push(AgentResult {
                         id: sb.id.clone(),
                         title: sb.title.clone(),
-                        action: sb.action,
                         success: false,
                         duration: Duration::ZERO,
                         total_tokens: None,
@@ -178,7 +235,7 @@ fn run_wave_template(
 
         // Poll for completions
         let mut still_running = Vec::new();
-        for (sb, mut child, started) in children {
+        for (sb, mut child, started, agent_worktree) in children {
             match child.try_wait() {
                 Ok(Some(status)) => {
                     let err = if status.success() {
@@ -186,11 +243,16 @@ fn run_wave_template(
                     } else {
                         Some(format!("Exit code {}", status.code().unwrap_or(-1)))
                     };
-                    results.push(AgentResult {
+                    let (success, err) = match agent_worktree {
+                        Some(ref wt) => {
+                            finish_worktree(beans_dir, wt, &sb.id, status.success(), err)
+                        }
+                        None => (status.success(), err),
+                    };
+                    let result = AgentResult {
                         id: sb.id.clone(),
                         title: sb.title.clone(),
-                        action: sb.action,
-                        success: status.success(),
+                        success,
                         duration: started.elapsed(),
                         total_tokens: None,
                         total_cost: None,
@@ -198,17 +260,24 @@ fn run_wave_template(
                         tool_count: 0,
                         turns: 0,
                         failure_summary: None,
-                    });
+                    };
+                    if let Some(journal) = journal {
+                        journal.mark_done(&result);
+                    }
+                    results.push(result);
                 }
                 Ok(None) => {
-                    still_running.push((sb, child, started));
+                    still_running.push((sb, child, started, agent_worktree));
                 }
                 Err(e) => {
                     eprintln!("  Error checking agent for {}: {}", sb.id, e);
+                    if let Some(ref wt) = agent_worktree {
+                        let _ =
+                            crate::worktree::finish_agent_worktree(beans_dir, wt, &sb.id, false);
+                    }
                     results.push(AgentResult {
                         id: sb.id.clone(),
                         title: sb.title.clone(),
-                        action: sb.action,
                         success: false,
                         duration: started.elapsed(),
                         total_tokens: None,
@@ -231,17 +300,21 @@ fn run_wave_template(
     Ok(results)
 }
 
-/// Direct mode: spawn pi directly with JSON output and monitoring.
+/// Direct mode: spawn the agent preset directly with structured output and monitoring.
 #[allow(clippy::too_many_arguments)]
 fn run_wave_direct(
     beans_dir: &Path,
     beans: &[SizedBean],
+    agent: &'static AgentPreset,
     max_jobs: usize,
     timeout_minutes: u32,
     idle_timeout_minutes: u32,
     json_stream: bool,
     wave_number: usize,
     file_locking: bool,
+    worktree: bool,
+    budget: &BudgetTracker,
+    journal: Option<&JournalHandle>,
 ) -> Result<Vec<AgentResult>> {
     let results = Arc::new(Mutex::new(Vec::new()));
     let mut pending: Vec<SizedBean> = beans.to_vec();
@@ -250,6 +323,11 @@ fn run_wave_direct(
     while !pending.is_empty() || !handles.is_empty() {
         // Spawn up to max_jobs threads
         while handles.len() < max_jobs && !pending.is_empty() {
+            // Out of budget: let running agents finish, start nothing new
+            if budget.exhausted().is_some() {
+                pending.clear();
+                break;
+            }
             let sb = pending.remove(0);
             let beans_dir = beans_dir.to_path_buf();
             let results = Arc::clone(&results);
@@ -267,15 +345,26 @@ fn run_wave_direct(
                 });
             }
 
+            let journal = journal.cloned();
+            let budget = budget.clone();
+
             let handle = std::thread::spawn(move || {
                 let result = run_single_direct(
                     &beans_dir,
                     &sb,
+                    agent,
+                    None,
                     timeout_min,
                     idle_min,
                     json_stream,
                     file_locking,
+                    worktree,
+                    &budget,
+                    journal.as_ref(),
                 );
+                if let Some(ref journal) = journal {
+                    journal.mark_done(&result);
+                }
                 results.lock().unwrap().push(result);
             });
             handles.push(handle);
@@ -462,7 +551,17 @@ mod tests {
             paths: vec![],
         }];
 
-        let results = run_wave_template(&beans, "echo {id}", None, 4, 30).unwrap();
+        let results = run_wave_template(
+            Path::new("."),
+            &beans,
+            "echo {id}",
+            None,
+            4,
+            30,
+            false,
+            None,
+        )
+        .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].success);
         assert_eq!(results[0].id, "1");
@@ -482,7 +581,17 @@ mod tests {
             paths: vec![],
         }];
 
-        let results = run_wave_template(&beans, "echo {id}", None, 4, 30).unwrap();
+        let results = run_wave_template(
+            Path::new("."),
+            &beans,
+            "echo {id}",
+            None,
+            4,
+            30,
+            false,
+            None,
+        )
+        .unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].success);
         assert_eq!(results[0].id, "1");
@@ -502,7 +611,8 @@ mod tests {
             paths: vec![],
         }];
 
-        let results = run_wave_template(&beans, "false", None, 4, 30).unwrap();
+        let results =
+            run_wave_template(Path::new("."), &beans, "false", None, 4, 30, false, None).unwrap();
         assert_eq!(results.len(), 1);
         assert!(!results[0].success);
         assert!(results[0].error.is_some());
@@ -0,0 +1,55 @@
+//! REST API server command: `bn serve`
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::rest::RestServer;
+
+/// Serve the REST API on `addr` until interrupted.
+///
+/// Endpoints live under `/v1`; the OpenAPI document is at `/v1/openapi.json`.
+/// There is no authentication, so the default address is loopback only.
+pub fn cmd_serve(beans_dir: &Path, addr: &str) -> Result<()> {
+    let server = RestServer::bind(addr)?;
+    let local = server.local_addr()?;
+    eprintln!("beans API listening on http://{}/v1", local);
+    if !local.ip().is_loopback() {
+        eprintln!(
+            "Warning: {} is reachable from other machines and the API has no authentication.",
+            local.ip()
+        );
+    }
+    server.run(beans_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+
+    fn parse_addr(args: &[&str]) -> String {
+        match Cli::try_parse_from(args).unwrap().command {
+            Command::Serve { addr } => addr,
+            _ => unreachable!("expected the serve command"),
+        }
+    }
+
+    #[test]
+    fn addr_defaults_to_loopback() {
+        assert_eq!(parse_addr(&["bn", "serve"]), "127.0.0.1:8765");
+        assert_eq!(
+            parse_addr(&["bn", "serve", "--addr", "0.0.0.0:9000"]),
+            "0.0.0.0:9000"
+        );
+        assert!(Cli::try_parse_from(["bn", "serve", "--addr"]).is_err());
+    }
+
+    #[test]
+    fn unusable_addr_is_an_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let err = cmd_serve(dir.path(), "not an address").unwrap_err();
+        assert!(err.to_string().contains("Failed to bind"), "{}", err);
+    }
+}
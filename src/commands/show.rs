@@ -100,6 +100,15 @@ fn render_bean(bean: &Bean, show_all_history: bool) -> Result<()> {
         println!("```");
     }
 
+    // Print unresolved concurrent-edit conflicts
+    if !bean.conflicts.is_empty() {
+        println!("\n**Conflicts**");
+        for conflict in &bean.conflicts {
+            print!("{}", crate::commands::resolve::format_conflict(conflict));
+        }
+        println!("Run `bn resolve {}` to choose values.", bean.id);
+    }
+
     // Print history section if non-empty
     if !bean.history.is_empty() {
         let limit = if show_all_history {
@@ -155,6 +164,10 @@ fn render_metadata_header(bean: &Bean) -> String {
         details.push(format!("Close reason: {}", reason));
     }
 
+    if let Some(reason) = &bean.blocked_reason {
+        details.push(format!("Blocked: {}", reason));
+    }
+
     // Show claim information
     if let Some(claimed_by) = &bean.claimed_by {
         details.push(format!("Claimed by: {}", claimed_by));
@@ -280,6 +293,15 @@ fn render_history(history: &[RunRecord], limit: usize) -> String {
             "  {} {}  {}  {}  {}  {}  {}\n",
             attempt, result, duration_col, agent_col, exit_col, tokens_col, cost
         ));
+        if let Some(ref reason) = record.reason {
+            out.push_str(&format!("      ↳ {}\n", reason));
+        }
+        if let Some(ref tests) = record.tests {
+            out.push_str(&format!("      ↳ {}\n", tests));
+            if let Some(message) = tests.message.as_deref().and_then(|m| m.lines().next()) {
+                out.push_str(&format!("        {}\n", message));
+            }
+        }
     }
 
     // Totals
@@ -540,6 +562,9 @@ mod tests {
             tokens: Some(tokens),
             cost: Some(cost),
             output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: None,
         }
     }
 
@@ -553,6 +578,33 @@ mod tests {
         assert!(rendered.contains("0 attempts"));
     }
 
+    #[test]
+    fn history_shows_cancel_reason() {
+        let mut record = make_record(1, RunResult::Cancelled, 30.0, "pi", 0, 90000, 1.2);
+        record.reason = Some("Budget exceeded: bean cost $1.20 reached limit $1.00".to_string());
+
+        let rendered = render_history(&[record], 10);
+        assert!(rendered.contains("cancelled"));
+        assert!(rendered.contains("↳ Budget exceeded: bean cost $1.20"));
+    }
+
+    #[test]
+    fn history_shows_test_summary() {
+        let mut record = make_record(1, RunResult::Fail, 4.0, "pi", 101, 0, 0.0);
+        record.tests = Some(crate::bean::TestSummary {
+            passed: 117,
+            failed: 3,
+            ignored: 0,
+            failing: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            message: Some("assertion `left == right` failed\n  left: 1".to_string()),
+        });
+
+        let rendered = render_history(&[record], 10);
+        assert!(rendered.contains("↳ 3 of 120 tests failed: a, b, c\n"));
+        assert!(rendered.contains("        assertion `left == right` failed\n"));
+        assert!(!rendered.contains("left: 1"));
+    }
+
     #[test]
     fn history_displays_formatted_table() {
         let records = vec![
@@ -664,6 +716,9 @@ mod tests {
             tokens: None,
             cost: None,
             output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: None,
         };
 
         let rendered = render_history(&[record], 10);
@@ -5,7 +5,9 @@ use anyhow::Result;
 use serde::Serialize;
 
 use crate::bean::{Bean, RunResult, Status};
+use crate::blocking::check_blocked;
 use crate::index::Index;
+use crate::verify_cache::{self, CacheStats};
 
 // ---------------------------------------------------------------------------
 // Output types (used for both text rendering and JSON serialization)
@@ -40,11 +42,17 @@ pub struct StatsOutput {
     pub total: usize,
     pub open: usize,
     pub in_progress: usize,
+    pub in_review: usize,
     pub closed: usize,
+    pub cancelled: usize,
     pub blocked: usize,
+    /// Closed beans as a percentage of all beans except cancelled ones.
     pub completion_pct: f64,
     pub priority_counts: [usize; 5],
     pub cost: Option<CostStats>,
+    /// Present once the verify cache has been used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_cache: Option<CacheStats>,
 }
 
 // ---------------------------------------------------------------------------
@@ -101,7 +109,8 @@ fn aggregate_cost(beans: &[Bean]) -> Option<CostStats> {
     let mut most_retried: Option<(&Bean, usize)> = None;
 
     for bean in beans {
-        if bean.history.is_empty() {
+        // Cancelled work neither passed nor failed; keep it out of the rates
+        if bean.history.is_empty() || bean.status == Status::Cancelled {
             continue;
         }
 
@@ -194,88 +203,40 @@ fn aggregate_cost(beans: &[Bean]) -> Option<CostStats> {
 /// Show project statistics: counts by status, priority, and completion percentage.
 /// When `--json` is passed, emits machine-readable JSON instead.
 pub fn cmd_stats(beans_dir: &Path, json: bool) -> Result<()> {
-    let index = Index::load_or_rebuild(beans_dir)?;
-
-    // Count by status
-    let total = index.beans.len();
-    let open = index
-        .beans
-        .iter()
-        .filter(|e| e.status == Status::Open)
-        .count();
-    let in_progress = index
-        .beans
-        .iter()
-        .filter(|e| e.status == Status::InProgress)
-        .count();
-    let closed = index
-        .beans
-        .iter()
-        .filter(|e| e.status == Status::Closed)
-        .count();
-
-    // Count blocked (open with unresolved dependencies)
-    let blocked = index
-        .beans
-        .iter()
-        .filter(|e| {
-            if e.status != Status::Open {
-                return false;
-            }
-            for dep_id in &e.dependencies {
-                if let Some(dep) = index.beans.iter().find(|d| &d.id == dep_id) {
-                    if dep.status != Status::Closed {
-                        return true;
-                    }
-                } else {
-                    return true;
-                }
-            }
-            false
-        })
-        .count();
-
-    // Count by priority
-    let mut priority_counts = [0usize; 5];
-    for entry in &index.beans {
-        if (entry.priority as usize) < 5 {
-            priority_counts[entry.priority as usize] += 1;
-        }
-    }
-
-    // Calculate completion percentage
-    let completion_pct = if total > 0 {
-        (closed as f64 / total as f64) * 100.0
-    } else {
-        0.0
-    };
-
-    // Aggregate cost/token data from full bean files
-    let all_beans = load_all_beans(beans_dir);
-    let cost = aggregate_cost(&all_beans);
+    let stats = build_stats(beans_dir)?;
 
     if json {
-        let output = StatsOutput {
-            total,
-            open,
-            in_progress,
-            closed,
-            blocked,
-            completion_pct,
-            priority_counts,
-            cost,
-        };
-        println!("{}", serde_json::to_string_pretty(&output)?);
+        println!("{}", serde_json::to_string_pretty(&stats)?);
         return Ok(());
     }
 
+    let StatsOutput {
+        total,
+        open,
+        in_progress,
+        in_review,
+        closed,
+        cancelled,
+        blocked,
+        completion_pct,
+        priority_counts,
+        cost,
+        verify_cache,
+    } = stats;
+
     // Human-readable output
     println!("=== Bean Statistics ===");
     println!();
     println!("Total:        {}", total);
     println!("Open:         {}", open);
     println!("In Progress:  {}", in_progress);
+    if in_review > 0 {
+        println!("In Review:    {}", in_review);
+    }
     println!("Closed:       {}", closed);
+    if cancelled > 0 {
+        println!("Cancelled:    {}", cancelled);
+    }
     println!("Blocked:      {}", blocked);
     println!();
     println!("Completion:   {:.1}%", completion_pct);
@@ -315,9 +276,96 @@ pub fn cmd_stats(beans_dir: &Path, json: bool) -> Result<()> {
         }
     }
 
+    if let Some(cache) = verify_cache {
+        println!();
+        println!("=== Verify Cache ===");
+        println!();
+        println!("Entries:          {}", cache.entries);
+        println!("Hits:             {}", cache.hits);
+        println!("Misses:           {}", cache.misses);
+        println!("Hit rate:         {:.1}%", cache.hit_rate() * 100.0);
+    }
+
     Ok(())
 }
 
+/// Compute the counts, completion and cost figures `bn stats` reports.
+pub fn build_stats(beans_dir: &Path) -> Result<StatsOutput> {
+    let index = Index::load_or_rebuild(beans_dir)?;
+
+    // Count by status
+    let total = index.beans.len();
+    let open = index
+        .beans
+        .iter()
+        .filter(|e| e.status == Status::Open)
+        .count();
+    let in_progress = index
+        .beans
+        .iter()
+        .filter(|e| e.status == Status::InProgress)
+        .count();
+    let in_review = index
+        .beans
+        .iter()
+        .filter(|e| e.status == Status::InReview)
+        .count();
+    let closed = index
+        .beans
+        .iter()
+        .filter(|e| e.status == Status::Closed)
+        .count();
+    let cancelled = index
+        .beans
+        .iter()
+        .filter(|e| e.status == Status::Cancelled)
+        .count();
+
+    // Count blocked (status blocked, or open with unresolved dependencies)
+    let blocked = index
+        .beans
+        .iter()
+        .filter(|e| {
+            matches!(e.status, Status::Open | Status::Blocked)
+                && check_blocked(e, &index).is_some()
+        })
+        .count();
+
+    // Count by priority
+    let mut priority_counts = [0usize; 5];
+    for entry in &index.beans {
+        if (entry.priority as usize) < 5 {
+            priority_counts[entry.priority as usize] += 1;
+        }
+    }
+
+    // Calculate completion percentage (cancelled beans are out of scope)
+    let in_scope = total - cancelled;
+    let completion_pct = if in_scope > 0 {
+        (closed as f64 / in_scope as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    // Aggregate cost/token data from full bean files
+    let all_beans = load_all_beans(beans_dir);
+    let cost = aggregate_cost(&all_beans);
+
+    Ok(StatsOutput {
+        total,
+        open,
+        in_progress,
+        in_review,
+        closed,
+        cancelled,
+        blocked,
+        completion_pct,
+        priority_counts,
+        cost,
+        verify_cache: verify_cache::stats(beans_dir),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,6 +451,31 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn stats_handles_new_statuses() {
+        let (_dir, beans_dir) = setup_test_beans();
+
+        let mut b6 = Bean::new("6", "Cancelled");
+        b6.status = Status::Cancelled;
+        b6.to_file(beans_dir.join("6.yaml")).unwrap();
+        let mut b7 = Bean::new("7", "Parked");
+        b7.status = Status::Blocked;
+        b7.to_file(beans_dir.join("7.yaml")).unwrap();
+
+        let index = Index::load_or_rebuild(&beans_dir).unwrap();
+        let blocked = index
+            .beans
+            .iter()
+            .filter(|e| {
+                matches!(e.status, Status::Open | Status::Blocked)
+                    && check_blocked(e, &index).is_some()
+            })
+            .count();
+        assert_eq!(blocked, 2); // 5 waits on 1, 7 is parked
+
+        assert!(cmd_stats(&beans_dir, true).is_ok());
+    }
+
     #[test]
     fn empty_project() {
         let dir = TempDir::new().unwrap();
@@ -441,6 +514,9 @@ mod tests {
             tokens: Some(1000),
             cost: Some(0.05),
             output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: None,
         }];
 
         let stats = aggregate_cost(&[bean]).unwrap();
@@ -467,6 +543,9 @@ mod tests {
             tokens: Some(tokens),
             cost: None,
             output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: None,
         };
 
         let mut cheap = Bean::new("1", "Cheap bean");
@@ -48,12 +48,12 @@ fn format_agent_status(entry: &IndexEntry) -> String {
 }
 
 /// Entry with agent status for JSON output
-#[derive(Serialize)]
-struct StatusEntry {
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusEntry {
     #[serde(flatten)]
-    entry: IndexEntry,
+    pub entry: IndexEntry,
     #[serde(skip_serializing_if = "Option::is_none")]
-    agent: Option<AgentStatus>,
+    pub agent: Option<AgentStatus>,
 }
 
 impl StatusEntry {
@@ -64,28 +64,100 @@ impl StatusEntry {
 }
 
 /// Blocked entry with reason for JSON output
-#[derive(Serialize)]
-struct BlockedEntry {
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedEntry {
     #[serde(flatten)]
-    entry: IndexEntry,
-    block_reason: String,
+    pub entry: IndexEntry,
+    pub block_reason: String,
 }
 
 /// JSON output structure for status command
-#[derive(Serialize)]
-struct StatusOutput {
-    claimed: Vec<StatusEntry>,
-    ready: Vec<IndexEntry>,
-    goals: Vec<IndexEntry>,
-    blocked: Vec<BlockedEntry>,
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusOutput {
+    pub claimed: Vec<StatusEntry>,
+    pub in_review: Vec<IndexEntry>,
+    pub ready: Vec<IndexEntry>,
+    pub goals: Vec<IndexEntry>,
+    pub blocked: Vec<BlockedEntry>,
 }
 
 /// Show complete work picture: claimed, ready, goals (need decomposition), and blocked beans
 pub fn cmd_status(json: bool, beans_dir: &Path) -> Result<()> {
+    let output = build_status(beans_dir)?;
+
+    if json {
+        let json_str = serde_json::to_string_pretty(&output)?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    println!("## Claimed ({})", output.claimed.len());
+    if output.claimed.is_empty() {
+        println!("  (none)");
+    } else {
+        for claimed in &output.claimed {
+            let entry = &claimed.entry;
+            let agent_str = format_agent_status(entry);
+            println!("  {} [-] {} ({})", entry.id, entry.title, agent_str);
+        }
+    }
+    println!();
+
+    if !output.in_review.is_empty() {
+        println!("## In review ({})", output.in_review.len());
+        for entry in &output.in_review {
+            println!("  {} [~] {}", entry.id, entry.title);
+        }
+        println!();
+    }
+
+    println!("## Ready ({})", output.ready.len());
+    if output.ready.is_empty() {
+        println!("  (none)");
+    } else {
+        for entry in &output.ready {
+            let warning = check_scope_warning(entry)
+                .map(|w| format!("  (⚠ {})", w))
+                .unwrap_or_default();
+            println!("  {} [ ] {}{}", entry.id, entry.title, warning);
+        }
+    }
+    println!();
+
+    println!("## Goals (need decomposition) ({})", output.goals.len());
+    if output.goals.is_empty() {
+        println!("  (none)");
+    } else {
+        for entry in &output.goals {
+            println!("  {} [?] {}", entry.id, entry.title);
+        }
+    }
+    println!();
+
+    println!("## Blocked ({})", output.blocked.len());
+    if output.blocked.is_empty() {
+        println!("  (none)");
+    } else {
+        for blocked in &output.blocked {
+            let entry = &blocked.entry;
+            println!(
+                "  {} [!] {}  ({})",
+                entry.id, entry.title, blocked.block_reason
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Sort the active beans into claimed, in review, ready, goals (need
+/// decomposition) and blocked, each ordered by priority then ID.
+pub fn build_status(beans_dir: &Path) -> Result<StatusOutput> {
     let index = Index::load_or_rebuild(beans_dir)?;
 
     // Separate beans into categories
     let mut claimed: Vec<&IndexEntry> = Vec::new();
+    let mut in_review: Vec<&IndexEntry> = Vec::new();
     let mut ready: Vec<&IndexEntry> = Vec::new();
     let mut goals: Vec<&IndexEntry> = Vec::new();
     let mut blocked: Vec<(&IndexEntry, BlockReason)> = Vec::new();
@@ -95,7 +167,10 @@ pub fn cmd_status(json: bool, beans_dir: &Path) -> Result<()> {
             Status::InProgress => {
                 claimed.push(entry);
             }
-            Status::Open => {
+            Status::InReview => {
+                in_review.push(entry);
+            }
+            Status::Open | Status::Blocked => {
                 if let Some(reason) = check_blocked(entry, &index) {
                     blocked.push((entry, reason));
                 } else if entry.has_verify {
@@ -104,11 +179,12 @@ pub fn cmd_status(json: bool, beans_dir: &Path) -> Result<()> {
                     goals.push(entry);
                 }
             }
-            Status::Closed => {}
+            Status::Closed | Status::Cancelled => {}
         }
     }
 
     sort_beans(&mut claimed);
+    sort_beans(&mut in_review);
     sort_beans(&mut ready);
     sort_beans(&mut goals);
     blocked.sort_by(|(a, _), (b, _)| match a.priority.cmp(&b.priority) {
@@ -116,71 +192,23 @@ pub fn cmd_status(json: bool, beans_dir: &Path) -> Result<()> {
         other => other,
     });
 
-    if json {
-        let output = StatusOutput {
-            claimed: claimed
-                .into_iter()
-                .cloned()
-                .map(StatusEntry::from_entry)
-                .collect(),
-            ready: ready.into_iter().cloned().collect(),
-            goals: goals.into_iter().cloned().collect(),
-            blocked: blocked
-                .iter()
-                .map(|(e, reason)| BlockedEntry {
-                    entry: (*e).clone(),
-                    block_reason: reason.to_string(),
-                })
-                .collect(),
-        };
-        let json_str = serde_json::to_string_pretty(&output)?;
-        println!("{}", json_str);
-    } else {
-        println!("## Claimed ({})", claimed.len());
-        if claimed.is_empty() {
-            println!("  (none)");
-        } else {
-            for entry in claimed {
-                let agent_str = format_agent_status(entry);
-                println!("  {} [-] {} ({})", entry.id, entry.title, agent_str);
-            }
-        }
-        println!();
-
-        println!("## Ready ({})", ready.len());
-        if ready.is_empty() {
-            println!("  (none)");
-        } else {
-            for entry in ready {
-                let warning = check_scope_warning(entry)
-                    .map(|w| format!("  (⚠ {})", w))
-                    .unwrap_or_default();
-                println!("  {} [ ] {}{}", entry.id, entry.title, warning);
-            }
-        }
-        println!();
-
-        println!("## Goals (need decomposition) ({})", goals.len());
-        if goals.is_empty() {
-            println!("  (none)");
-        } else {
-            for entry in goals {
-                println!("  {} [?] {}", entry.id, entry.title);
-            }
-        }
-        println!();
-
-        println!("## Blocked ({})", blocked.len());
-        if blocked.is_empty() {
-            println!("  (none)");
-        } else {
-            for (entry, reason) in &blocked {
-                println!("  {} [!] {}  ({})", entry.id, entry.title, reason);
-            }
-        }
-    }
-
-    Ok(())
+    Ok(StatusOutput {
+        claimed: claimed
+            .into_iter()
+            .cloned()
+            .map(StatusEntry::from_entry)
+            .collect(),
+        in_review: in_review.into_iter().cloned().collect(),
+        ready: ready.into_iter().cloned().collect(),
+        goals: goals.into_iter().cloned().collect(),
+        blocked: blocked
+            .into_iter()
+            .map(|(e, reason)| BlockedEntry {
+                entry: e.clone(),
+                block_reason: reason.to_string(),
+            })
+            .collect(),
+    })
 }
 
 fn sort_beans(beans: &mut Vec<&IndexEntry>) {
@@ -3,6 +3,7 @@ use std::path::Path;
 use anyhow::Result;
 
 use crate::index::{count_bean_formats, ArchiveIndex, Index};
+use crate::search::SearchIndex;
 
 /// Force rebuild index unconditionally from YAML files
 pub fn cmd_sync(beans_dir: &Path) -> Result<()> {
@@ -20,6 +21,9 @@ pub fn cmd_sync(beans_dir: &Path) -> Result<()> {
         archive_index.save(beans_dir)?;
     }
 
+    // Rebuild the full-text search index used by bn recall
+    SearchIndex::build(beans_dir)?.save(beans_dir)?;
+
     println!("Index rebuilt: {} beans indexed.", count);
     if archive_count > 0 {
         println!(
@@ -76,6 +80,7 @@ mod tests {
 
         // Verify index was created
         assert!(beans_dir.join("index.yaml").exists());
+        assert!(beans_dir.join(crate::search::SEARCH_FILE).exists());
 
         // Verify index contains both beans
         let index = Index::load(&beans_dir).unwrap();
@@ -5,6 +5,8 @@ use chrono::Utc;
 
 use crate::bean::{Bean, Status};
 use crate::discovery::{archive_path_for_bean, find_bean_file};
+use crate::events::{self, Action};
+use crate::filter::Filter;
 use crate::index::{ArchiveIndex, Index};
 use crate::output::Output;
 use crate::util::title_to_slug;
@@ -98,9 +100,12 @@ fn has_running_agents() -> bool {
 /// Tidy the beans directory: archive closed beans, release stale in-progress
 /// beans, and rebuild the index.
 ///
+/// `filter` is an optional filter expression (see [`Filter`]) restricting
+/// which beans are archived or released.
+///
 /// Delegates to `cmd_tidy_inner` with the real agent-detection function.
-pub fn cmd_tidy(beans_dir: &Path, dry_run: bool, out: &Output) -> Result<()> {
-    cmd_tidy_inner(beans_dir, dry_run, has_running_agents, out)
+pub fn cmd_tidy(beans_dir: &Path, dry_run: bool, filter: Option<&str>, out: &Output) -> Result<()> {
+    cmd_tidy_inner(beans_dir, dry_run, filter, has_running_agents, out)
 }
 
 /// Inner implementation of tidy, with an injectable agent-check function
@@ -136,16 +141,22 @@ pub fn cmd_tidy(beans_dir: &Path, dry_run: bool, out: &Output) -> Result<()> {
 fn cmd_tidy_inner(
     beans_dir: &Path,
     dry_run: bool,
+    filter: Option<&str>,
     check_agents: fn() -> bool,
     out: &Output,
 ) -> Result<()> {
+    let filter = filter.map(|f| Filter::load(beans_dir, f)).transpose()?;
+    let selected = |entry: &crate::index::IndexEntry| {
+        filter.as_ref().is_none_or(|f| f.matches(entry, beans_dir))
+    };
+
     // Step 1 — Build a fresh index so we're working from the truth on disk,
     // not a potentially stale cache.
     let index = Index::build(beans_dir).context("Failed to build index")?;
 
     // Step 2 — Find every closed bean that's still in the main directory.
     // We filter on two things:
-    //   • status == Closed  (the bean is done)
+    //   • status is terminal  (the bean is closed or cancelled)
     //   • find_bean_file succeeds (the file is still in .beans/, not archive/)
     //
     // We also skip beans that have open children — archiving them would
@@ -154,7 +165,7 @@ fn cmd_tidy_inner(
     let closed: Vec<&crate::index::IndexEntry> = index
         .beans
         .iter()
-        .filter(|entry| entry.status == Status::Closed)
+        .filter(|entry| entry.status.is_terminal() && selected(entry))
         .collect();
 
     let mut tidied: Vec<TidiedBean> = Vec::new();
@@ -185,7 +196,7 @@ fn cmd_tidy_inner(
         let has_open_children = index
             .beans
             .iter()
-            .any(|b| b.parent.as_deref() == Some(entry.id.as_str()) && b.status != Status::Closed);
+            .any(|b| b.parent.as_deref() == Some(entry.id.as_str()) && !b.status.is_terminal());
 
         if has_open_children {
             skipped_parent_ids.push(entry.id.clone());
@@ -246,9 +257,18 @@ fn cmd_tidy_inner(
         // Mark the bean as archived and persist. This sets is_archived = true
         // in the YAML front-matter so other commands (unarchive, list --all)
         // know this bean lives in the archive.
+        let before = bean.clone();
         bean.is_archived = true;
         bean.to_file(&archive_path)
             .with_context(|| format!("Failed to save archived bean: {}", entry.id))?;
+        events::record_with_note(
+            beans_dir,
+            Action::Update,
+            &entry.id,
+            Some(&before),
+            Some(&bean),
+            Some("tidy: archive".to_string()),
+        );
     }
 
     // Step 4 — Release stale in-progress beans.
@@ -263,7 +283,7 @@ fn cmd_tidy_inner(
     let in_progress: Vec<&crate::index::IndexEntry> = index
         .beans
         .iter()
-        .filter(|entry| entry.status == Status::InProgress)
+        .filter(|entry| entry.status == Status::InProgress && selected(entry))
         .collect();
 
     let mut released: Vec<ReleasedBean> = Vec::new();
@@ -311,6 +331,7 @@ fn cmd_tidy_inner(
                 }
 
                 // Release the bean: set status to Open, clear claim fields.
+                let before = bean.clone();
                 let now = Utc::now();
                 bean.status = Status::Open;
                 bean.claimed_by = None;
@@ -319,6 +340,14 @@ fn cmd_tidy_inner(
 
                 bean.to_file(&bean_path)
                     .with_context(|| format!("Failed to release stale bean: {}", entry.id))?;
+                events::record_with_note(
+                    beans_dir,
+                    Action::Release,
+                    &entry.id,
+                    Some(&before),
+                    Some(&bean),
+                    Some("tidy: stale claim".to_string()),
+                );
             }
         }
     }
@@ -433,7 +462,7 @@ mod tests {
         bean.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Should no longer be in main directory
         assert!(find_bean_file(&beans_dir, "1").is_err());
@@ -444,6 +473,39 @@ mod tests {
         assert!(archived_bean.is_archived);
     }
 
+    #[test]
+    fn tidy_archives_cancelled_beans() {
+        let (_dir, beans_dir) = setup();
+
+        let mut bean = Bean::new("1", "Dropped task");
+        bean.status = Status::Cancelled;
+        write_bean(&beans_dir, &bean);
+
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
+
+        assert!(find_bean_file(&beans_dir, "1").is_err());
+        assert!(crate::discovery::find_archived_bean(&beans_dir, "1").is_ok());
+    }
+
+    #[test]
+    fn tidy_leaves_blocked_and_in_review_beans_alone() {
+        let (_dir, beans_dir) = setup();
+
+        let mut blocked = Bean::new("1", "Blocked task");
+        blocked.status = Status::Blocked;
+        write_bean(&beans_dir, &blocked);
+        let mut review = Bean::new("2", "Review task");
+        review.status = Status::InReview;
+        write_bean(&beans_dir, &review);
+
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
+
+        let b1 = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
+        assert_eq!(b1.status, Status::Blocked);
+        let b2 = Bean::from_file(find_bean_file(&beans_dir, "2").unwrap()).unwrap();
+        assert_eq!(b2.status, Status::InReview);
+    }
+
     #[test]
     fn tidy_leaves_open_beans_alone() {
         let (_dir, beans_dir) = setup();
@@ -451,7 +513,7 @@ mod tests {
         let bean = Bean::new("1", "Open task");
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Should still be in main directory
         assert!(find_bean_file(&beans_dir, "1").is_ok());
@@ -467,9 +529,9 @@ mod tests {
         write_bean(&beans_dir, &bean);
 
         // First tidy archives it
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
         // Second tidy should be a no-op (no panic, no error)
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         let archived = crate::discovery::find_archived_bean(&beans_dir, "1");
         assert!(archived.is_ok());
@@ -486,7 +548,7 @@ mod tests {
         bean.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, true, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, true, None, no_agents, &Output::new()).unwrap();
 
         // File should still be in main directory (dry-run)
         assert!(find_bean_file(&beans_dir, "1").is_ok());
@@ -509,7 +571,7 @@ mod tests {
         child.parent = Some("1".to_string());
         write_bean(&beans_dir, &child);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Parent should NOT be archived because child is still open
         assert!(find_bean_file(&beans_dir, "1").is_ok());
@@ -534,7 +596,7 @@ mod tests {
         child.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &child);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Both should be archived
         assert!(find_bean_file(&beans_dir, "1").is_err());
@@ -559,7 +621,7 @@ mod tests {
         );
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         let archived = crate::discovery::find_archived_bean(&beans_dir, "1").unwrap();
         // The archive path should contain 2025/06 (from closed_at)
@@ -590,7 +652,7 @@ mod tests {
         write_bean(&beans_dir, &in_progress);
 
         // With no agents running, in_progress beans get released
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Open bean untouched
         let b1 = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
@@ -605,6 +667,47 @@ mod tests {
         assert_eq!(b3.status, Status::Open);
     }
 
+    #[test]
+    fn tidy_filter_limits_affected_beans() {
+        let (_dir, beans_dir) = setup();
+
+        let mut backend = Bean::new("1", "Backend done");
+        backend.status = Status::Closed;
+        backend.labels = vec!["backend".to_string()];
+        write_bean(&beans_dir, &backend);
+
+        let mut frontend = Bean::new("2", "Frontend done");
+        frontend.status = Status::Closed;
+        write_bean(&beans_dir, &frontend);
+
+        let mut in_progress = Bean::new("3", "Frontend wip");
+        in_progress.status = Status::InProgress;
+        write_bean(&beans_dir, &in_progress);
+
+        cmd_tidy_inner(
+            &beans_dir,
+            false,
+            Some("label:backend"),
+            no_agents,
+            &Output::new(),
+        )
+        .unwrap();
+
+        assert!(crate::discovery::find_archived_bean(&beans_dir, "1").is_ok());
+        assert!(find_bean_file(&beans_dir, "2").is_ok());
+        let b3 = Bean::from_file(find_bean_file(&beans_dir, "3").unwrap()).unwrap();
+        assert_eq!(b3.status, Status::InProgress);
+
+        let err = cmd_tidy_inner(
+            &beans_dir,
+            true,
+            Some("(priority<1"),
+            no_agents,
+            &Output::new(),
+        );
+        assert!(err.is_err());
+    }
+
     #[test]
     fn tidy_skips_in_progress_when_agents_running() {
         let (_dir, beans_dir) = setup();
@@ -615,7 +718,7 @@ mod tests {
         write_bean(&beans_dir, &bean);
 
         // With agents running, in_progress beans are NOT released
-        cmd_tidy_inner(&beans_dir, false, agents_running, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, agents_running, &Output::new()).unwrap();
 
         let updated = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
         assert_eq!(updated.status, Status::InProgress);
@@ -638,7 +741,7 @@ mod tests {
         );
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Bean should be released back to open
         let updated = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
@@ -657,7 +760,7 @@ mod tests {
         // No claimed_at, no claimed_by — definitely stale
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         let updated = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
         assert_eq!(updated.status, Status::Open);
@@ -672,7 +775,7 @@ mod tests {
         bean.claimed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, true, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, true, None, no_agents, &Output::new()).unwrap();
 
         // Bean should still be in_progress (dry-run)
         let updated = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
@@ -700,7 +803,7 @@ mod tests {
         stale_bean.claimed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &stale_bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Open bean untouched
         let b1 = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
@@ -728,7 +831,7 @@ mod tests {
         bean.claimed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         let updated = Bean::from_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
         assert_eq!(updated.status, Status::Open);
@@ -742,7 +845,7 @@ mod tests {
     fn tidy_empty_project() {
         let (_dir, beans_dir) = setup();
         // Should succeed with nothing to do
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
     }
 
     // ── Index is rebuilt ───────────────────────────────────────────
@@ -759,7 +862,7 @@ mod tests {
         closed_bean.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &closed_bean);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // Index should only contain the open bean (closed was archived)
         let index = Index::load(&beans_dir).unwrap();
@@ -782,7 +885,7 @@ mod tests {
         bean2.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean2);
 
-        cmd_tidy_inner(&beans_dir, false, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, false, None, no_agents, &Output::new()).unwrap();
 
         // archive.yaml should exist and contain both archived beans
         assert!(beans_dir.join("archive.yaml").exists());
@@ -802,7 +905,7 @@ mod tests {
         bean.closed_at = Some(chrono::Utc::now());
         write_bean(&beans_dir, &bean);
 
-        cmd_tidy_inner(&beans_dir, true, no_agents, &Output::new()).unwrap();
+        cmd_tidy_inner(&beans_dir, true, None, no_agents, &Output::new()).unwrap();
 
         // archive.yaml should NOT be created in dry-run mode
         assert!(!beans_dir.join("archive.yaml").exists());
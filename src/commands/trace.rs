@@ -49,6 +49,19 @@ pub struct AttemptSummary {
 /// direct children, dependencies (what this bean waits on), dependents
 /// (what waits on this bean), produces/requires artifacts, and attempt history.
 pub fn cmd_trace(id: &str, json: bool, beans_dir: &Path) -> Result<()> {
+    let output = build_trace(id, beans_dir)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print_trace(&output);
+    }
+
+    Ok(())
+}
+
+/// Collect the trace for a bean without printing it.
+pub fn build_trace(id: &str, beans_dir: &Path) -> Result<TraceOutput> {
     let index = Index::load_or_rebuild(beans_dir)?;
 
     let entry = index
@@ -130,13 +143,7 @@ pub fn cmd_trace(id: &str, json: bool, beans_dir: &Path) -> Result<()> {
         attempts,
     };
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&output)?);
-    } else {
-        print_trace(&output);
-    }
-
-    Ok(())
+    Ok(output)
 }
 
 // ---------------------------------------------------------------------------
@@ -337,6 +344,8 @@ mod tests {
             agent: None,
             started_at: None,
             finished_at: None,
+            verify: None,
+            base: None,
         }];
         write_bean(beans_dir, &bean);
 
@@ -385,6 +394,8 @@ mod tests {
                 agent: None,
                 started_at: None,
                 finished_at: None,
+                verify: None,
+                base: None,
             },
             AttemptRecord {
                 num: 2,
@@ -393,6 +404,8 @@ mod tests {
                 agent: None,
                 started_at: None,
                 finished_at: None,
+                verify: None,
+                base: None,
             },
         ];
         write_bean(beans_dir, &main_bean);
@@ -67,7 +67,10 @@ fn print_tree_node(
         let status_indicator = match entry.status {
             Status::Open => "[ ]",
             Status::InProgress => "[-]",
+            Status::InReview => "[~]",
+            Status::Blocked => "[!]",
             Status::Closed => "[x]",
+            Status::Cancelled => "[/]",
         };
 
         println!(
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 use std::path::Path;
 
-use crate::hooks::{create_trust, is_trusted, revoke_trust};
+use crate::hooks::{create_trust, diff_lines, is_trusted, revoke_trust, trust_items, TrustItem};
 
 /// Manage hook trust status.
 ///
@@ -9,17 +9,29 @@ use crate::hooks::{create_trust, is_trusted, revoke_trust};
 /// `bn trust` to enable hook execution. This is a security measure to ensure
 /// users review .beans/hooks/ scripts before allowing execution.
 ///
+/// Trust records the SHA-256 of each hook script and of the `on_close`,
+/// `on_fail` and `post_plan` config commands. When one changes (for example
+/// after a pull) it is pending until approved by running `bn trust` again.
+///
 /// # Arguments
 ///
 /// * `beans_dir` - The .beans/ directory path
 /// * `revoke` - If true, disable hooks (remove trust file)
 /// * `check` - If true, display current trust status without changing it
+/// * `list` - If true, list every hook and command with its approval status
+/// * `diff` - If true, show what changed in each pending hook or command
 ///
 /// # Returns
 ///
 /// * `Ok(())` on success
 /// * `Err` if file operations fail
-pub fn cmd_trust(beans_dir: &Path, revoke: bool, check: bool) -> Result<()> {
+pub fn cmd_trust(
+    beans_dir: &Path,
+    revoke: bool,
+    check: bool,
+    list: bool,
+    diff: bool,
+) -> Result<()> {
     // hooks functions expect the project root (parent of .beans/)
     let project_dir = beans_dir
         .parent()
@@ -29,12 +41,50 @@ pub fn cmd_trust(beans_dir: &Path, revoke: bool, check: bool) -> Result<()> {
     if check {
         if is_trusted(project_dir) {
             println!("Hooks are enabled");
+            let pending = pending_items(project_dir);
+            if !pending.is_empty() {
+                println!(
+                    "{} hook(s) or command(s) pending approval (bn trust --list)",
+                    pending.len()
+                );
+            }
         } else {
             println!("Hooks are disabled");
         }
         return Ok(());
     }
 
+    // If --list: every hook and command with its status
+    if list {
+        let items = trust_items(project_dir);
+        if items.is_empty() {
+            println!("No hooks or config commands");
+        }
+        for item in &items {
+            println!("{} {:<7}  {}", status_mark(item), item.kind, item.name);
+        }
+        if !is_trusted(project_dir) {
+            println!("Hooks are disabled; run `bn trust` to enable them");
+        }
+        return Ok(());
+    }
+
+    // If --diff: the changes awaiting approval
+    if diff {
+        let pending = pending_items(project_dir);
+        if pending.is_empty() {
+            println!("Nothing pending approval");
+        }
+        for item in &pending {
+            println!("{} {} ({})", item.kind, item.name, status_label(item));
+            let old = item.approved.as_ref().map_or("", |a| a.content.as_str());
+            for line in diff_lines(old, &item.content) {
+                println!("  {}", line);
+            }
+        }
+        return Ok(());
+    }
+
     // If --revoke: disable hooks
     if revoke {
         revoke_trust(project_dir)?;
@@ -42,12 +92,46 @@ pub fn cmd_trust(beans_dir: &Path, revoke: bool, check: bool) -> Result<()> {
         return Ok(());
     }
 
-    // Otherwise: enable hooks
+    // Otherwise: enable hooks, approving everything as it is now
+    let pending = pending_items(project_dir);
     create_trust(project_dir)?;
+    for item in &pending {
+        println!(
+            "Approved {} {} ({})",
+            item.kind,
+            item.name,
+            status_label(item)
+        );
+    }
     println!("Hooks enabled. Review .beans/hooks before running commands");
     Ok(())
 }
 
+/// Hooks and commands that would not run without approval.
+fn pending_items(project_dir: &Path) -> Vec<TrustItem> {
+    let trusted = is_trusted(project_dir);
+    trust_items(project_dir)
+        .into_iter()
+        .filter(|item| !trusted || item.is_pending())
+        .collect()
+}
+
+fn status_label(item: &TrustItem) -> &'static str {
+    match &item.approved {
+        Some(_) if !item.is_pending() => "approved",
+        Some(_) => "changed",
+        None => "new",
+    }
+}
+
+fn status_mark(item: &TrustItem) -> String {
+    if item.is_pending() {
+        format!("✗ {:<8}", status_label(item))
+    } else {
+        format!("✓ {:<8}", status_label(item))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -75,7 +159,7 @@ mod tests {
         assert!(!is_trusted(project_dir));
 
         // Enable trust (cmd_trust receives .beans/ path, like main.rs)
-        cmd_trust(&beans_dir, false, false).unwrap();
+        cmd_trust(&beans_dir, false, false, false, false).unwrap();
 
         // Verify trust is now enabled
         assert!(is_trusted(project_dir));
@@ -91,7 +175,7 @@ mod tests {
         fs::create_dir_all(&beans_dir).unwrap();
 
         // Check status when disabled - should not error
-        let result = cmd_trust(&beans_dir, false, true);
+        let result = cmd_trust(&beans_dir, false, true, false, false);
         assert!(result.is_ok());
     }
 
@@ -105,10 +189,10 @@ mod tests {
         fs::create_dir_all(&beans_dir).unwrap();
 
         // Enable trust first
-        cmd_trust(&beans_dir, false, false).unwrap();
+        cmd_trust(&beans_dir, false, false, false, false).unwrap();
 
         // Check status when enabled - should not error
-        let result = cmd_trust(&beans_dir, false, true);
+        let result = cmd_trust(&beans_dir, false, true, false, false);
         assert!(result.is_ok());
     }
 
@@ -122,11 +206,11 @@ mod tests {
         fs::create_dir_all(&beans_dir).unwrap();
 
         // Enable trust first
-        cmd_trust(&beans_dir, false, false).unwrap();
+        cmd_trust(&beans_dir, false, false, false, false).unwrap();
         assert!(is_trusted(project_dir));
 
         // Revoke trust
-        cmd_trust(&beans_dir, true, false).unwrap();
+        cmd_trust(&beans_dir, true, false, false, false).unwrap();
 
         // Verify trust is disabled
         assert!(!is_trusted(project_dir));
@@ -142,10 +226,31 @@ mod tests {
         fs::create_dir_all(&beans_dir).unwrap();
 
         // Enable trust first
-        cmd_trust(&beans_dir, false, false).unwrap();
+        cmd_trust(&beans_dir, false, false, false, false).unwrap();
 
         // Revoke with check - should report disabled
-        let result = cmd_trust(&beans_dir, true, true);
+        let result = cmd_trust(&beans_dir, true, true, false, false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_cmd_trust_approves_pending_changes() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        let beans_dir = project_dir.join(".beans");
+        fs::create_dir_all(beans_dir.join("hooks")).unwrap();
+        fs::write(beans_dir.join("hooks").join("pre-close"), "exit 0\n").unwrap();
+
+        assert_eq!(pending_items(project_dir).len(), 1);
+        cmd_trust(&beans_dir, false, false, true, false).unwrap();
+        cmd_trust(&beans_dir, false, false, false, true).unwrap();
+
+        cmd_trust(&beans_dir, false, false, false, false).unwrap();
+        assert!(pending_items(project_dir).is_empty());
+
+        fs::write(beans_dir.join("hooks").join("pre-close"), "exit 1\n").unwrap();
+        let pending = pending_items(project_dir);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(status_label(&pending[0]), "changed");
+    }
 }
@@ -5,6 +5,7 @@ use chrono::Utc;
 
 use crate::bean::Bean;
 use crate::discovery::find_archived_bean;
+use crate::events::{self, Action};
 use crate::index::{ArchiveIndex, Index};
 
 /// Unarchive a bean by moving it from `.beans/archive/**/` back to `.beans/`.
@@ -55,12 +56,21 @@ pub fn cmd_unarchive(beans_dir: &Path, id: &str) -> Result<()> {
         .with_context(|| format!("Failed to move bean {} from archive to main directory", id))?;
 
     // Update bean metadata
+    let before = bean.clone();
     bean.is_archived = false;
     bean.updated_at = Utc::now();
 
     // Save the bean to its new location
     bean.to_file(&target_path)
         .with_context(|| format!("Failed to save unarchived bean: {}", id))?;
+    events::record_with_note(
+        beans_dir,
+        Action::Update,
+        id,
+        Some(&before),
+        Some(&bean),
+        Some("unarchive".to_string()),
+    );
 
     // Remove from archive index
     {
@@ -137,6 +147,19 @@ mod tests {
         assert!(!unarchived_bean.is_archived);
     }
 
+    #[test]
+    fn test_unarchive_records_event() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        create_archived_bean(&beans_dir, "1", "Task", "2026", "01");
+
+        cmd_unarchive(&beans_dir, "1").unwrap();
+
+        let events = crate::events::load_events(&beans_dir);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, "1");
+        assert_eq!(events[0].note.as_deref(), Some("unarchive"));
+    }
+
     #[test]
     fn test_unarchive_preserves_slug() {
         let (_dir, beans_dir) = setup_test_beans_dir();
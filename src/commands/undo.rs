@@ -0,0 +1,625 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde_json::{Map, Value};
+
+use crate::bean::Bean;
+use crate::config::resolve_identity;
+use crate::discovery::{archive_path_for_bean, find_archived_bean, find_bean_file};
+use crate::error::BeansError;
+use crate::events::{self, diff_maps, load_events, to_map, Action, Event, Operation};
+use crate::index::{ArchiveIndex, Index, LockedIndex};
+use crate::util::{natural_cmp, title_to_slug};
+
+/// A bean as recorded in the event log: its fields as a JSON object.
+type Fields = Map<String, Value>;
+
+/// One operation reverted by [`undo`].
+#[derive(Debug, Clone)]
+pub struct UndoneOperation {
+    /// The operation's events in this project, oldest first.
+    pub events: Vec<Event>,
+}
+
+/// Handle `bn undo` command.
+///
+/// Reverts the last `steps` operations recorded in `.beans/events.jsonl`.
+pub fn cmd_undo(beans_dir: &Path, steps: usize) -> Result<()> {
+    for undone in undo(beans_dir, steps)? {
+        let Some(first) = undone.events.first() else {
+            continue;
+        };
+        let mut line = format!(
+            "Undid {} of bean {} ({}",
+            first.action,
+            first.id,
+            first.timestamp.format("%Y-%m-%d %H:%M:%S")
+        );
+        if let Some(actor) = &first.actor {
+            line.push_str(&format!(", by {}", actor));
+        }
+        line.push(')');
+        let related = undone.events.len() - 1;
+        if related > 0 {
+            line.push_str(&format!(
+                " and {} related change{}",
+                related,
+                if related == 1 { "" } else { "s" }
+            ));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Revert the last `steps` operations from the event log, newest first.
+///
+/// Each field an event changed is set back to its old value; deleted,
+/// moved-out and archived beans are restored to `.beans/` and created or
+/// moved-in beans are removed. Moves are undone in both projects. Nothing is
+/// written if any bean was changed since by a later event or outside `bn`
+/// ([`BeansError::UndoConflict`]). The revert is itself logged as `undo`
+/// events; undo operations are never undone.
+pub fn undo(beans_dir: &Path, steps: usize) -> Result<Vec<UndoneOperation>> {
+    if steps == 0 {
+        return Err(BeansError::Invalid("--steps must be at least 1".to_string()).into());
+    }
+
+    let mut locks = vec![LockedIndex::acquire(beans_dir)?];
+    let log = load_events(beans_dir);
+    let ops = last_operations(&log, steps);
+    if ops.is_empty() {
+        bail!("Nothing to undo");
+    }
+    let op_set: HashSet<&str> = ops.iter().map(String::as_str).collect();
+
+    // A move is logged in both projects; undo it in both
+    let mut projects = vec![Project::new(beans_dir.to_path_buf(), log)];
+    let peers: Vec<PathBuf> = projects[0]
+        .events_of(&op_set)
+        .filter_map(|(_, e)| e.peer.clone())
+        .collect();
+    for peer in peers {
+        if projects.iter().any(|p| same_dir(&p.dir, &peer)) {
+            continue;
+        }
+        if !peer.join("config.yaml").exists() {
+            bail!(
+                "Cannot undo move: project {} no longer exists",
+                peer.display()
+            );
+        }
+        locks.push(LockedIndex::acquire(&peer)?);
+        let log = load_events(&peer);
+        projects.push(Project::new(peer, log));
+    }
+
+    for project in &projects {
+        project.check_later_events(&op_set)?;
+    }
+    for op in &ops {
+        for project in &mut projects {
+            project.revert(op)?;
+        }
+    }
+
+    let undo_op = Operation::new();
+    for project in &projects {
+        project.write(&undo_op, &ops)?;
+    }
+    for (project, mut lock) in projects.iter().zip(locks) {
+        lock.index = Index::build(&project.dir)?;
+        lock.save_and_release()?;
+        if project.dir.join("archive").is_dir() {
+            ArchiveIndex::build(&project.dir)?.save(&project.dir)?;
+        }
+    }
+
+    let main = &projects[0];
+    Ok(ops
+        .iter()
+        .map(|op| UndoneOperation {
+            events: main
+                .log
+                .iter()
+                .enumerate()
+                .filter(|(i, e)| op_key(*i, e) == *op)
+                .map(|(_, e)| e.clone())
+                .collect(),
+        })
+        .filter(|undone| !undone.events.is_empty())
+        .collect())
+}
+
+/// The operation an event belongs to. Events without one stand alone.
+fn op_key(index: usize, event: &Event) -> String {
+    event.op.clone().unwrap_or_else(|| format!("#{}", index))
+}
+
+/// Operations that are undo operations or have been undone.
+fn settled_operations(log: &[Event]) -> HashSet<String> {
+    let mut settled = HashSet::new();
+    for (i, event) in log.iter().enumerate() {
+        if event.action == Action::Undo {
+            settled.insert(op_key(i, event));
+            settled.extend(event.undoes.iter().cloned());
+        }
+    }
+    settled
+}
+
+/// The last `steps` operations that can still be undone, newest first.
+fn last_operations(log: &[Event], steps: usize) -> Vec<String> {
+    let settled = settled_operations(log);
+    let mut ops: Vec<String> = Vec::new();
+    for (i, event) in log.iter().enumerate().rev() {
+        let key = op_key(i, event);
+        if settled.contains(&key) || ops.contains(&key) {
+            continue;
+        }
+        if ops.len() == steps {
+            break;
+        }
+        ops.push(key);
+    }
+    ops
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn conflict(id: &str, reason: String) -> anyhow::Error {
+    BeansError::UndoConflict {
+        id: id.to_string(),
+        reason,
+    }
+    .into()
+}
+
+/// The bean files of one project as they are on disk and as they will be
+/// once the undo is applied.
+struct Project {
+    dir: PathBuf,
+    log: Vec<Event>,
+    /// Bean ID -> file and fields before the undo (`None`: no such bean).
+    on_disk: HashMap<String, Option<(PathBuf, Fields)>>,
+    /// Bean ID -> fields after the undo.
+    state: HashMap<String, Option<Fields>>,
+}
+
+impl Project {
+    fn new(dir: PathBuf, log: Vec<Event>) -> Self {
+        Project {
+            dir,
+            log,
+            on_disk: HashMap::new(),
+            state: HashMap::new(),
+        }
+    }
+
+    /// Events of the given operations, with their position in the log.
+    fn events_of<'a>(
+        &'a self,
+        ops: &'a HashSet<&str>,
+    ) -> impl Iterator<Item = (usize, &'a Event)> + 'a {
+        self.log
+            .iter()
+            .enumerate()
+            .filter(move |(i, e)| ops.contains(op_key(*i, e).as_str()))
+    }
+
+    /// Refuse if an event after one of `ops` changed a field it changed.
+    /// Operations that were undone, and the undos themselves, don't count.
+    fn check_later_events(&self, ops: &HashSet<&str>) -> Result<()> {
+        let settled = settled_operations(&self.log);
+        for (i, event) in self.events_of(ops) {
+            let ids: Vec<&str> = std::iter::once(event.id.as_str())
+                .chain(event.old_id())
+                .collect();
+            for (j, later) in self.log.iter().enumerate().skip(i + 1) {
+                let key = op_key(j, later);
+                if ops.contains(key.as_str()) || settled.contains(&key) {
+                    continue;
+                }
+                if !ids.iter().any(|id| later.touches(id)) {
+                    continue;
+                }
+                if let Some(field) = later
+                    .changes
+                    .iter()
+                    .map(|c| c.field.as_str())
+                    .find(|field| event.change(field).is_some())
+                {
+                    return Err(conflict(
+                        &event.id,
+                        format!(
+                            "a later {} changed {} at {}{}",
+                            later.action,
+                            field,
+                            later.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                            later
+                                .actor
+                                .as_ref()
+                                .map(|a| format!(" (by {})", a))
+                                .unwrap_or_default()
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The bean's fields as they will be after the undo so far.
+    fn get(&mut self, id: &str) -> Result<Option<Fields>> {
+        if !self.state.contains_key(id) {
+            let path = find_bean_file(&self.dir, id)
+                .or_else(|_| find_archived_bean(&self.dir, id))
+                .ok();
+            let file = match path {
+                Some(path) => {
+                    let bean = Bean::from_file(&path)
+                        .with_context(|| format!("Failed to load bean: {}", id))?;
+                    Some((path, to_map(&bean)))
+                }
+                None => None,
+            };
+            self.state
+                .insert(id.to_string(), file.as_ref().map(|(_, f)| f.clone()));
+            self.on_disk.insert(id.to_string(), file);
+        }
+        Ok(self.state[id].clone())
+    }
+
+    /// Revert this project's events of `op`, newest first.
+    fn revert(&mut self, op: &str) -> Result<()> {
+        let events: Vec<Event> = self
+            .log
+            .iter()
+            .enumerate()
+            .filter(|(i, e)| op_key(*i, e) == op)
+            .map(|(_, e)| e.clone())
+            .collect();
+        for event in events.iter().rev() {
+            self.revert_event(event)?;
+        }
+        Ok(())
+    }
+
+    fn revert_event(&mut self, event: &Event) -> Result<()> {
+        let current = self.get(&event.id)?;
+
+        // The bean must still look the way the event left it
+        let mut fields = match (event.removed(), current) {
+            (true, None) => Fields::new(),
+            (true, Some(_)) => {
+                return Err(conflict(
+                    &event.id,
+                    format!("it was {}d, but exists again", event.action),
+                ))
+            }
+            (false, None) => {
+                return Err(conflict(&event.id, "it no longer exists".to_string()));
+            }
+            (false, Some(fields)) => {
+                // updated_at is bumped by every write, including undone ones
+                if let Some(change) = event
+                    .changes
+                    .iter()
+                    .filter(|c| c.field != "updated_at")
+                    .find(|c| fields.get(&c.field) != c.new.as_ref())
+                {
+                    return Err(conflict(
+                        &event.id,
+                        format!("{} was changed outside bn since", change.field),
+                    ));
+                }
+                fields
+            }
+        };
+
+        let restored = if event.created() {
+            None
+        } else {
+            for change in &event.changes {
+                match &change.old {
+                    Some(old) => fields.insert(change.field.clone(), old.clone()),
+                    None => fields.remove(&change.field),
+                };
+            }
+            Some(fields)
+        };
+
+        // An adopt renamed the bean; put it back under its old ID
+        match event.old_id() {
+            Some(old_id) if old_id != event.id => {
+                if self.get(old_id)?.is_some() {
+                    return Err(conflict(
+                        &event.id,
+                        format!("another bean now has its old ID {}", old_id),
+                    ));
+                }
+                self.state.insert(event.id.clone(), None);
+                self.state.insert(old_id.to_string(), restored);
+            }
+            _ => {
+                self.state.insert(event.id.clone(), restored);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the reverted beans and log the undo.
+    fn write(&self, undo_op: &Operation, ops: &[String]) -> Result<()> {
+        let mut changed: Vec<(&String, Option<&Fields>, Option<&Fields>)> = self
+            .state
+            .iter()
+            .map(|(id, after)| {
+                let before = self.on_disk[id].as_ref().map(|(_, f)| f);
+                (id, before, after.as_ref())
+            })
+            .filter(|(_, before, after)| before != after)
+            .collect();
+        changed.sort_by(|a, b| natural_cmp(a.0, b.0));
+
+        // Remove first: a bean may move to a path another one frees
+        for (id, _, _) in &changed {
+            if let Some((path, _)) = &self.on_disk[*id] {
+                fs::remove_file(path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        }
+
+        for (id, before, after) in &changed {
+            if let Some(fields) = after {
+                let mut bean: Bean = serde_json::from_value(Value::Object((*fields).clone()))
+                    .with_context(|| format!("Failed to restore bean {}", id))?;
+                if before.is_some() {
+                    bean.updated_at = Utc::now();
+                }
+                let path = self.path_for(&bean);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                bean.to_file(&path)
+                    .with_context(|| format!("Failed to save bean: {}", id))?;
+            }
+
+            let event = Event {
+                timestamp: Utc::now(),
+                actor: resolve_identity(&self.dir),
+                action: Action::Undo,
+                id: id.to_string(),
+                op: Some(undo_op.id().to_string()),
+                peer: None,
+                undoes: ops.to_vec(),
+                note: None,
+                changes: diff_maps(*before, *after),
+            };
+            let _ = events::append(&self.dir, &event);
+        }
+        Ok(())
+    }
+
+    /// Where a restored bean goes: back to its archived file, a new archive
+    /// file if it is archived but was not before, or `.beans/<id>-<slug>.md`.
+    fn path_for(&self, bean: &Bean) -> PathBuf {
+        let slug = bean
+            .slug
+            .clone()
+            .unwrap_or_else(|| title_to_slug(&bean.title));
+        if !bean.is_archived {
+            return self.dir.join(format!("{}-{}.md", bean.id, slug));
+        }
+        let archived = self.on_disk.get(&bean.id).and_then(|file| {
+            file.as_ref()
+                .map(|(path, _)| path.clone())
+                .filter(|path| path.starts_with(self.dir.join("archive")))
+        });
+        archived.unwrap_or_else(|| {
+            let date = bean
+                .closed_at
+                .map(|at| at.date_naive())
+                .unwrap_or_else(|| Utc::now().date_naive());
+            archive_path_for_bean(&self.dir, &bean.id, &slug, "md", date)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::Status;
+    use crate::commands::claim::claim_bean;
+    use crate::commands::close::close_bean;
+    use crate::commands::create::{create_bean, CreateArgs};
+    use crate::commands::update::{update_bean, BeanUpdate};
+    use std::io;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        fs::write(
+            beans_dir.join("config.yaml"),
+            "project: undo-test\nnext_id: 1\n",
+        )
+        .unwrap();
+        (dir, beans_dir)
+    }
+
+    fn create(beans_dir: &Path, title: &str) -> Bean {
+        create_bean(
+            beans_dir,
+            CreateArgs {
+                title: title.to_string(),
+                description: None,
+                acceptance: None,
+                notes: None,
+                design: None,
+                verify: Some("true".to_string()),
+                priority: None,
+                labels: None,
+                assignee: None,
+                deps: None,
+                parent: None,
+                produces: None,
+                requires: None,
+                paths: None,
+                on_fail: None,
+                pass_ok: true,
+                claim: false,
+                by: None,
+                verify_timeout: None,
+                feature: false,
+            },
+            &mut io::sink(),
+        )
+        .unwrap()
+    }
+
+    fn retitle(beans_dir: &Path, id: &str, title: &str) {
+        update_bean(
+            beans_dir,
+            id,
+            BeanUpdate {
+                title: Some(title.to_string()),
+                ..Default::default()
+            },
+            &mut io::sink(),
+        )
+        .unwrap();
+    }
+
+    fn load(beans_dir: &Path, id: &str) -> Bean {
+        Bean::from_file(find_bean_file(beans_dir, id).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn undo_reverts_an_update() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Original");
+        retitle(&beans_dir, "1", "Renamed");
+
+        let undone = undo(&beans_dir, 1).unwrap();
+        assert_eq!(undone.len(), 1);
+        assert_eq!(undone[0].events[0].action, Action::Update);
+        assert_eq!(load(&beans_dir, "1").title, "Original");
+
+        let log = load_events(&beans_dir);
+        assert_eq!(log.last().unwrap().action, Action::Undo);
+    }
+
+    #[test]
+    fn undo_steps_reverts_several_operations() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Original");
+        retitle(&beans_dir, "1", "Second");
+        claim_bean(
+            &beans_dir,
+            "1",
+            Some("agent".to_string()),
+            true,
+            &mut io::sink(),
+        )
+        .unwrap();
+
+        undo(&beans_dir, 2).unwrap();
+        let bean = load(&beans_dir, "1");
+        assert_eq!(bean.title, "Original");
+        assert_eq!(bean.status, Status::Open);
+        assert!(bean.claimed_by.is_none());
+        assert!(bean.attempt_log.is_empty());
+    }
+
+    #[test]
+    fn undo_skips_what_was_already_undone() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Original");
+        retitle(&beans_dir, "1", "Second");
+        retitle(&beans_dir, "1", "Third");
+
+        undo(&beans_dir, 1).unwrap();
+        assert_eq!(load(&beans_dir, "1").title, "Second");
+        undo(&beans_dir, 1).unwrap();
+        assert_eq!(load(&beans_dir, "1").title, "Original");
+        undo(&beans_dir, 1).unwrap();
+        assert!(find_bean_file(&beans_dir, "1").is_err());
+        assert!(undo(&beans_dir, 1).is_err());
+    }
+
+    #[test]
+    fn undo_close_restores_the_archived_bean() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Task");
+        close_bean(
+            &beans_dir,
+            "1",
+            None,
+            true,
+            false,
+            &mut io::sink(),
+            &mut io::sink(),
+        )
+        .unwrap();
+        assert!(find_bean_file(&beans_dir, "1").is_err());
+
+        undo(&beans_dir, 1).unwrap();
+        let bean = load(&beans_dir, "1");
+        assert_eq!(bean.status, Status::Open);
+        assert!(!bean.is_archived);
+        assert!(bean.closed_at.is_none());
+        assert!(find_archived_bean(&beans_dir, "1").is_err());
+
+        let index = Index::load(&beans_dir).unwrap();
+        assert!(index.beans.iter().any(|b| b.id == "1"));
+    }
+
+    #[test]
+    fn undo_refuses_when_a_later_event_changed_the_field() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Original");
+        retitle(&beans_dir, "1", "Second");
+
+        // Undo the create while the later update still stands
+        let log = load_events(&beans_dir);
+        let create_op = log[0].op.clone().unwrap();
+        let project = Project::new(beans_dir.clone(), log);
+        let ops: HashSet<&str> = [create_op.as_str()].into_iter().collect();
+        let err = project.check_later_events(&ops).unwrap_err();
+        assert!(matches!(
+            crate::error::find(&err),
+            Some(BeansError::UndoConflict { .. })
+        ));
+    }
+
+    #[test]
+    fn undo_refuses_when_the_bean_changed_outside_bn() {
+        let (_dir, beans_dir) = setup();
+        create(&beans_dir, "Original");
+        retitle(&beans_dir, "1", "Renamed");
+
+        let path = find_bean_file(&beans_dir, "1").unwrap();
+        let mut bean = Bean::from_file(&path).unwrap();
+        bean.title = "Hand edited".to_string();
+        bean.to_file(&path).unwrap();
+
+        let err = undo(&beans_dir, 1).unwrap_err();
+        assert!(err.to_string().contains("title was changed outside bn"));
+        assert_eq!(load(&beans_dir, "1").title, "Hand edited");
+    }
+
+    #[test]
+    fn steps_must_be_positive() {
+        let (_dir, beans_dir) = setup();
+        assert!(undo(&beans_dir, 0).is_err());
+    }
+}
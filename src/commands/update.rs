@@ -1,17 +1,39 @@
+use std::io::Write;
 use std::path::Path;
 
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 
-use crate::bean::Bean;
+use crate::bean::{merge, Bean, Status};
+use crate::config::resolve_identity;
 use crate::discovery::find_bean_file;
+use crate::error::BeansError;
+use crate::events::{self, Action};
 use crate::hooks::{execute_hook, HookEvent};
 use crate::index::Index;
 use crate::util::parse_status;
 
+/// Field changes for [`update_bean`]. `None` leaves the field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct BeanUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub acceptance: Option<String>,
+    /// Appended to the existing notes with a timestamp separator.
+    pub notes: Option<String>,
+    pub design: Option<String>,
+    pub status: Option<String>,
+    pub blocked_reason: Option<String>,
+    pub priority: Option<u8>,
+    pub assignee: Option<String>,
+    pub add_label: Option<String>,
+    pub remove_label: Option<String>,
+}
+
 /// Update a bean's fields based on provided flags.
 ///
 /// - title, description, acceptance, design, priority, assignee, status: replace
+/// - blocked_reason: set alongside `--status blocked`; cleared when leaving blocked
 /// - notes: append with timestamp separator
 /// - labels: add/remove operations
 /// - updates updated_at and rebuilds index
@@ -25,11 +47,58 @@ pub fn cmd_update(
     notes: Option<String>,
     design: Option<String>,
     status: Option<String>,
+    blocked_reason: Option<String>,
     priority: Option<u8>,
     assignee: Option<String>,
     add_label: Option<String>,
     remove_label: Option<String>,
 ) -> Result<()> {
+    let bean = update_bean(
+        beans_dir,
+        id,
+        BeanUpdate {
+            title,
+            description,
+            acceptance,
+            notes,
+            design,
+            status,
+            blocked_reason,
+            priority,
+            assignee,
+            add_label,
+            remove_label,
+        },
+        &mut std::io::stderr(),
+    )?;
+    println!("Updated bean {}: {}", id, bean.title);
+    Ok(())
+}
+
+/// Apply `update` to a bean, run the update hooks, and rebuild the index.
+///
+/// Returns the updated bean. Warnings go to `err` (stderr for the CLI);
+/// nothing is printed to stdout, so it is safe to call from the MCP server.
+pub fn update_bean(
+    beans_dir: &Path,
+    id: &str,
+    update: BeanUpdate,
+    err: &mut dyn Write,
+) -> Result<Bean> {
+    let BeanUpdate {
+        title,
+        description,
+        acceptance,
+        notes,
+        design,
+        status,
+        blocked_reason,
+        priority,
+        assignee,
+        add_label,
+        remove_label,
+    } = update;
+
     // Validate priority if provided
     if let Some(p) = priority {
         crate::bean::validate_priority(p)?;
@@ -41,6 +110,7 @@ pub fn cmd_update(
 
     let mut bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
+    let base = bean.clone();
 
     // Get project root for hooks (parent of .beans)
     let project_root = beans_dir
@@ -52,7 +122,7 @@ pub fn cmd_update(
         .context("Pre-update hook execution failed")?;
 
     if !pre_passed {
-        return Err(anyhow!("Pre-update hook rejected bean update"));
+        return Err(BeansError::HookRejected { hook: "pre-update" }.into());
     }
 
     // Apply updates
@@ -85,6 +155,18 @@ pub fn cmd_update(
     if let Some(new_status) = status {
         bean.status =
             parse_status(&new_status).ok_or_else(|| anyhow!("Invalid status: {}", new_status))?;
+        if bean.status != Status::Blocked {
+            bean.blocked_reason = None;
+        }
+    }
+
+    if let Some(reason) = blocked_reason {
+        if bean.status != Status::Blocked {
+            return Err(anyhow!(
+                "--reason only applies to blocked beans (use --status blocked)"
+            ));
+        }
+        bean.blocked_reason = Some(reason);
     }
 
     if let Some(new_priority) = priority {
@@ -108,9 +190,13 @@ pub fn cmd_update(
     // Update timestamp
     bean.updated_at = Utc::now();
 
-    // Write back to the discovered path (preserves slug)
-    bean.to_file(&bean_path)
+    // Write back to the discovered path (preserves slug), merging with any
+    // concurrent edit made since we loaded the bean
+    let agent = resolve_identity(beans_dir);
+    let conflicts = merge::save_merged(&mut bean, &base, &bean_path, agent.as_deref())
         .with_context(|| format!("Failed to save bean: {}", id))?;
+    merge::warn_conflicts(err, id, &conflicts);
+    events::record(beans_dir, Action::Update, id, Some(&base), Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir).with_context(|| "Failed to rebuild index")?;
@@ -118,14 +204,12 @@ pub fn cmd_update(
         .save(beans_dir)
         .with_context(|| "Failed to save index")?;
 
-    println!("Updated bean {}: {}", id, bean.title);
-
     // Call post-update hook (non-blocking - log warning if it fails)
     if let Err(e) = execute_hook(HookEvent::PostUpdate, &bean, project_root, None) {
-        eprintln!("Warning: post-update hook failed: {}", e);
+        let _ = writeln!(err, "Warning: post-update hook failed: {}", e);
     }
 
-    Ok(())
+    Ok(bean)
 }
 
 #[cfg(test)]
@@ -164,6 +248,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -194,6 +279,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -226,6 +312,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -258,6 +345,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -266,6 +354,89 @@ mod tests {
         assert_eq!(updated.status, Status::InProgress);
     }
 
+    #[test]
+    fn test_update_blocked_reason() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        let bean = Bean::new("1", "Test");
+        let slug = title_to_slug(&bean.title);
+        bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
+            .unwrap();
+
+        cmd_update(
+            &beans_dir,
+            "1",
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("blocked".to_string()),
+            Some("waiting on vendor API key".to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let updated =
+            Bean::from_file(crate::discovery::find_bean_file(&beans_dir, "1").unwrap()).unwrap();
+        assert_eq!(updated.status, Status::Blocked);
+        assert_eq!(
+            updated.blocked_reason.as_deref(),
+            Some("waiting on vendor API key")
+        );
+
+        // Leaving blocked clears the reason
+        cmd_update(
+            &beans_dir,
+            "1",
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("open".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let updated =
+            Bean::from_file(crate::discovery::find_bean_file(&beans_dir, "1").unwrap()).unwrap();
+        assert_eq!(updated.status, Status::Open);
+        assert!(updated.blocked_reason.is_none());
+    }
+
+    #[test]
+    fn test_update_reason_requires_blocked_status() {
+        let (_dir, beans_dir) = setup_test_beans_dir();
+        let bean = Bean::new("1", "Test");
+        let slug = title_to_slug(&bean.title);
+        bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
+            .unwrap();
+
+        let result = cmd_update(
+            &beans_dir,
+            "1",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("no reason".to_string()),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_update_priority() {
         let (_dir, beans_dir) = setup_test_beans_dir();
@@ -283,6 +454,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             Some(1),
             None,
             None,
@@ -314,6 +486,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             Some("urgent".to_string()),
             None,
         )
@@ -345,6 +518,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             Some("urgent".to_string()),
         )
         .unwrap();
@@ -371,6 +545,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(result.is_err());
     }
@@ -392,6 +567,7 @@ mod tests {
             None,
             None,
             Some("closed".to_string()),
+            None,
             Some(0),
             None,
             None,
@@ -431,6 +607,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -459,6 +636,7 @@ mod tests {
             None,
             None,
             None,
+            None,
             Some(5),
             None,
             None,
@@ -490,6 +668,7 @@ mod tests {
                 None,
                 None,
                 None,
+                None,
                 Some(priority),
                 None,
                 None,
@@ -530,6 +709,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(
             result.is_ok(),
@@ -553,8 +733,6 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
-        // Enable trust and create failing hook
-        create_trust(project_root).unwrap();
         let hooks_dir = project_root.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
         let hook_path = hooks_dir.join("pre-update");
@@ -565,6 +743,9 @@ mod tests {
             fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
         }
 
+        // Enable trust now that the hook is written
+        create_trust(project_root).unwrap();
+
         // Update should fail
         let result = cmd_update(
             &beans_dir,
@@ -579,6 +760,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(
             result.is_err(),
@@ -604,8 +786,6 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
-        // Enable trust and create passing hook
-        create_trust(project_root).unwrap();
         let hooks_dir = project_root.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
         let hook_path = hooks_dir.join("pre-update");
@@ -616,6 +796,9 @@ mod tests {
             fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
         }
 
+        // Enable trust now that the hook is written
+        create_trust(project_root).unwrap();
+
         // Update should succeed
         let result = cmd_update(
             &beans_dir,
@@ -630,6 +813,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(
             result.is_ok(),
@@ -654,8 +838,6 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
-        // Enable trust and create post-update hook that writes a marker file
-        create_trust(project_root).unwrap();
         let hooks_dir = project_root.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
         let hook_path = hooks_dir.join("post-update");
@@ -676,6 +858,9 @@ mod tests {
             fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
         }
 
+        // Enable trust now that the hook is written
+        create_trust(project_root).unwrap();
+
         // Update bean
         cmd_update(
             &beans_dir,
@@ -690,6 +875,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         )
         .unwrap();
 
@@ -714,8 +900,6 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
-        // Enable trust and create failing post-update hook
-        create_trust(project_root).unwrap();
         let hooks_dir = project_root.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
         let hook_path = hooks_dir.join("post-update");
@@ -726,6 +910,9 @@ mod tests {
             fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
         }
 
+        // Enable trust now that the hook is written
+        create_trust(project_root).unwrap();
+
         // Update should still succeed even though post-hook fails
         let result = cmd_update(
             &beans_dir,
@@ -740,6 +927,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(
             result.is_ok(),
@@ -764,8 +952,6 @@ mod tests {
         bean.to_file(beans_dir.join(format!("1-{}.md", slug)))
             .unwrap();
 
-        // Enable trust and create hooks
-        create_trust(project_root).unwrap();
         let hooks_dir = project_root.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
@@ -781,6 +967,9 @@ mod tests {
             fs::set_permissions(&post_hook, fs::Permissions::from_mode(0o755)).unwrap();
         }
 
+        // Enable trust now that the hook is written
+        create_trust(project_root).unwrap();
+
         // Update multiple fields
         let result = cmd_update(
             &beans_dir,
@@ -795,6 +984,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
         assert!(result.is_ok());
 
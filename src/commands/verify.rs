@@ -1,22 +1,27 @@
 use std::io::Read;
 use std::path::Path;
-use std::process::{Command as ShellCommand, Stdio};
+use std::process::Stdio;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 
 use crate::bean::Bean;
 use crate::config::Config;
 use crate::discovery::find_bean_file;
+use crate::hooks;
 use crate::output::Output;
+use crate::sandbox;
+use crate::verify_cache;
 
 /// Run the verify command for a bean without closing it.
 ///
 /// Returns `Ok(true)` if the command exits 0, `Ok(false)` if non-zero or timed out.
 /// If no verify command is set, prints a message and returns `Ok(true)`.
-/// Respects `verify_timeout` from the bean or project config.
+/// Respects `verify_timeout` from the bean or project config, and answers
+/// from the verify cache when it is enabled and the inputs are unchanged.
 pub fn cmd_verify(beans_dir: &Path, id: &str, out: &Output) -> Result<bool> {
-    let bean_path = find_bean_file(beans_dir, id).map_err(|_| anyhow!("Bean not found: {}", id))?;
+    let bean_path = find_bean_file(beans_dir, id)?;
 
     let bean =
         Bean::from_file(&bean_path).with_context(|| format!("Failed to load bean: {}", id))?;
@@ -38,15 +43,29 @@ pub fn cmd_verify(beans_dir: &Path, id: &str, out: &Output) -> Result<bool> {
     let project_root = beans_dir
         .parent()
         .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
+    hooks::check_bean_verify(project_root, &bean.id, &verify_cmd)?;
+
+    let key = verify_cache::key(beans_dir, &bean.paths, &verify_cmd);
+    if let Some(entry) = key
+        .as_ref()
+        .and_then(|k| verify_cache::lookup(beans_dir, k))
+    {
+        out.info(&format!("Cached: {} (inputs unchanged)", verify_cmd));
+        if !entry.stdout.trim().is_empty() {
+            print!("{}", entry.stdout);
+        }
+        if !entry.stderr.trim().is_empty() {
+            eprint!("{}", entry.stderr);
+        }
+        return Ok(report(id, entry.passed(), out));
+    }
 
     out.info(&format!("Running: {}", verify_cmd));
     if let Some(secs) = timeout_secs {
         out.info(&format!("Timeout: {}s", secs));
     }
 
-    let mut child = ShellCommand::new("sh")
-        .args(["-c", &verify_cmd])
-        .current_dir(project_root)
+    let mut child = sandbox::shell(project_root, &verify_cmd)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -73,6 +92,7 @@ pub fn cmd_verify(beans_dir: &Path, id: &str, out: &Output) -> Result<bool> {
     };
 
     let timeout = timeout_secs.map(Duration::from_secs);
+    let started_at = Utc::now();
     let start = Instant::now();
 
     let (timed_out, exit_status) = loop {
@@ -116,11 +136,25 @@ pub fn cmd_verify(beans_dir: &Path, id: &str, out: &Output) -> Result<bool> {
     }
 
     let status = exit_status.expect("exit_status is Some when not timed_out");
-    if status.success() {
+    if let Some(key) = key {
+        let entry = verify_cache::Entry::new(
+            &verify_cmd,
+            status.success(),
+            status.code(),
+            started_at,
+            &stdout_str,
+            &stderr_str,
+        );
+        verify_cache::store(beans_dir, &key, entry);
+    }
+    Ok(report(id, status.success(), out))
+}
+
+fn report(id: &str, passed: bool, out: &Output) -> bool {
+    if passed {
         out.success(id, "Verify passed");
-        Ok(true)
     } else {
         out.error(&format!("Verify failed for bean {}", id));
-        Ok(false)
     }
+    passed
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -6,6 +6,8 @@ use std::process::Command;
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::error::BeansError;
+
 /// Configuration for the adversarial review feature (`bn review` / `bn run --review`).
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ReviewConfig {
@@ -31,6 +33,43 @@ impl Default for ReviewConfig {
     }
 }
 
+/// Sandbox profile for verify commands, fact checks and hooks (see
+/// [`crate::sandbox`]). Configuring `sandbox:` turns it on.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct SandboxConfig {
+    /// Paths that stay writable inside the otherwise read-only project root,
+    /// relative to the project root (e.g. `target`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable: Vec<String>,
+    /// Allow network access (default: false).
+    #[serde(default, skip_serializing_if = "is_false_bool")]
+    pub network: bool,
+    /// CPU time limit in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_secs: Option<u64>,
+    /// Address space limit in MiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// Limit on processes for the user (RLIMIT_NPROC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+    /// Environment variables passed through besides the basic ones
+    /// (`PATH`, `HOME`, `USER`, `LANG`, `TERM`, `TMPDIR`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+/// Verify-result cache (see [`crate::verify_cache`]). Configuring
+/// `verify_cache:` turns it on.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct VerifyCacheConfig {
+    /// Globs, relative to the project root, hashed into every cache key
+    /// together with the bean's `paths` (e.g. `src/**`, `Cargo.lock`). With
+    /// neither, the key covers the whole git working tree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Config {
     pub project: String,
@@ -47,6 +86,10 @@ pub struct Config {
     /// If unset, plan operations will print an error asking the user to configure it.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub plan: Option<String>,
+    /// Agent preset for direct-mode `bn run` (`pi`, `claude`, `aider`, `codex`).
+    /// Takes precedence over `run`; when both are unset, `bn run` uses pi.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
     /// Maximum agent loops before stopping (default: 10, 0 = unlimited)
     #[serde(default = "default_max_loops")]
     pub max_loops: u32,
@@ -105,6 +148,44 @@ pub struct Config {
     /// User email (e.g., "alice@co"). Optional, for git integration.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_email: Option<String>,
+    /// Maximum cost in USD a single agent may spend (default: None = no limit).
+    /// `bn run` kills an agent that crosses it. Like the other budget limits,
+    /// only enforced for `agent:` presets, which report their usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_per_bean: Option<f64>,
+    /// Maximum cost in USD across all agents of one `bn run` pass.
+    /// Once exhausted, running agents are killed and nothing new is dispatched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_per_run: Option<f64>,
+    /// Maximum cost in USD per UTC day, counting earlier runs in
+    /// `.beans/agent_history.jsonl`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_per_day: Option<f64>,
+    /// Token equivalent of `max_cost_per_bean`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_bean: Option<u64>,
+    /// Token equivalent of `max_cost_per_run`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_run: Option<u64>,
+    /// Token equivalent of `max_cost_per_day`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_per_day: Option<u64>,
+    /// Named filter expressions, used as `@name` in `bn list -q`,
+    /// `bn run --filter` and `bn tidy --filter`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub views: BTreeMap<String, String>,
+    /// Sandbox for verify commands, fact checks and hooks. Optional — they
+    /// run unconfined if not configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxConfig>,
+    /// Cache verify outcomes keyed by the command and its inputs. Optional —
+    /// verify commands always run if not configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_cache: Option<VerifyCacheConfig>,
+    /// JUnit XML files written by the verify command (globs relative to the
+    /// project root). Read after each verify run for structured test results.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_reports: Vec<String>,
 }
 
 fn default_auto_close_parent() -> bool {
@@ -149,6 +230,17 @@ impl Default for Config {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         }
     }
 }
@@ -159,8 +251,11 @@ impl Config {
         let path = beans_dir.join("config.yaml");
         let contents = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read config at {}", path.display()))?;
-        let config: Config = serde_yml::from_str(&contents)
-            .with_context(|| format!("Failed to parse config at {}", path.display()))?;
+        let config: Config =
+            serde_yml::from_str(&contents).map_err(|e| BeansError::ConfigInvalid {
+                path: path.clone(),
+                message: e.to_string(),
+            })?;
         Ok(config)
     }
 
@@ -194,9 +289,11 @@ impl Config {
             let contents = fs::read_to_string(&canonical).with_context(|| {
                 format!("Failed to read extends config: {}", canonical.display())
             })?;
-            let parent: Config = serde_yml::from_str(&contents).with_context(|| {
-                format!("Failed to parse extends config: {}", canonical.display())
-            })?;
+            let parent: Config =
+                serde_yml::from_str(&contents).map_err(|e| BeansError::ConfigInvalid {
+                    path: canonical.clone(),
+                    message: e.to_string(),
+                })?;
 
             for ext in &parent.extends {
                 stack.push(ext.clone());
@@ -256,6 +353,42 @@ impl Config {
             if config.user_email.is_none() {
                 config.user_email = parent.user_email.clone();
             }
+            if config.max_cost_per_bean.is_none() {
+                config.max_cost_per_bean = parent.max_cost_per_bean;
+            }
+            if config.max_cost_per_run.is_none() {
+                config.max_cost_per_run = parent.max_cost_per_run;
+            }
+            if config.max_cost_per_day.is_none() {
+                config.max_cost_per_day = parent.max_cost_per_day;
+            }
+            if config.max_tokens_per_bean.is_none() {
+                config.max_tokens_per_bean = parent.max_tokens_per_bean;
+            }
+            if config.max_tokens_per_run.is_none() {
+                config.max_tokens_per_run = parent.max_tokens_per_run;
+            }
+            if config.max_tokens_per_day.is_none() {
+                config.max_tokens_per_day = parent.max_tokens_per_day;
+            }
+            if config.agent.is_none() {
+                config.agent = parent.agent.clone();
+            }
+            if config.sandbox.is_none() {
+                config.sandbox = parent.sandbox.clone();
+            }
+            if config.verify_cache.is_none() {
+                config.verify_cache = parent.verify_cache.clone();
+            }
+            if config.test_reports.is_empty() {
+                config.test_reports = parent.test_reports.clone();
+            }
+            for (name, filter) in &parent.views {
+                config
+                    .views
+                    .entry(name.clone())
+                    .or_insert_with(|| filter.clone());
+            }
             // Never inherit: project, next_id, extends
         }
 
@@ -437,6 +570,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
 
         config.save(dir.path()).unwrap();
@@ -467,6 +611,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
 
         assert_eq!(config.increment_id(), 1);
@@ -513,6 +668,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -558,6 +724,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -615,6 +792,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -648,6 +836,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -691,6 +890,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -908,6 +1118,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -966,6 +1187,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -996,6 +1228,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -1039,6 +1282,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -1082,6 +1336,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
         config.save(dir.path()).unwrap();
 
@@ -1107,6 +1372,29 @@ mod tests {
         assert_eq!(config.plan, Some("plan-cmd {id}".to_string()));
     }
 
+    #[test]
+    fn extends_merges_views_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir_all(&beans_dir).unwrap();
+
+        let parent_path = dir.path().join("shared.yaml");
+        write_yaml(
+            &parent_path,
+            "project: shared\nnext_id: 999\nviews:\n  backend: \"label:backend\"\n  urgent: \"priority<=1\"\n",
+        );
+
+        write_local_config(
+            &beans_dir,
+            &["shared.yaml"],
+            "views:\n  urgent: \"priority=0\"\n",
+        );
+
+        let config = Config::load_with_extends(&beans_dir).unwrap();
+        assert_eq!(config.views["backend"], "label:backend");
+        assert_eq!(config.views["urgent"], "priority=0");
+    }
+
     #[test]
     fn extends_inherits_max_concurrent() {
         let dir = tempfile::tempdir().unwrap();
@@ -1190,6 +1478,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: BTreeMap::new(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
 
         config.save(dir.path()).unwrap();
@@ -2,6 +2,8 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 
+use crate::error::BeansError;
+
 /// Walk up from `start` looking for a `.beans/` directory.
 /// Returns the path to the `.beans/` directory if found.
 /// Errors if no `.beans/` directory exists in any ancestor.
@@ -60,7 +62,11 @@ pub fn find_bean_file(beans_dir: &Path, id: &str) -> Result<PathBuf> {
         return Ok(yaml_path);
     }
 
-    Err(anyhow::anyhow!("Bean {} not found", id))
+    Err(BeansError::NotFound {
+        id: id.to_string(),
+        archived: false,
+    }
+    .into())
 }
 
 /// Compute the archive path for a bean given its ID, slug, and date.
@@ -130,11 +136,12 @@ pub fn find_archived_bean(beans_dir: &Path, id: &str) -> Result<PathBuf> {
     let archive_dir = beans_dir.join("archive");
 
     // If archive directory doesn't exist, bean is not archived
+    let not_found = || BeansError::NotFound {
+        id: id.to_string(),
+        archived: true,
+    };
     if !archive_dir.is_dir() {
-        bail!(
-            "Archived bean {} not found (archive directory does not exist)",
-            id
-        );
+        return Err(not_found().into());
     }
 
     // Recursively search through year subdirectories
@@ -170,7 +177,7 @@ pub fn find_archived_bean(beans_dir: &Path, id: &str) -> Result<PathBuf> {
         }
     }
 
-    Err(anyhow::anyhow!("Archived bean {} not found", id))
+    Err(not_found().into())
 }
 
 #[cfg(test)]
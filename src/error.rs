@@ -0,0 +1,359 @@
+//! Structured errors.
+//!
+//! Most functions return `anyhow::Result`; the failures a caller may want
+//! to tell apart are raised as a [`BeansError`] inside the `anyhow::Error`
+//! and recovered with [`BeansError::from`] or [`find`]. Their `Display` text
+//! is what the CLI prints. The library API returns [`BeansError`] directly.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::bean::Status;
+
+// Exit codes of `bn` (see [`BeansError::exit_code`]). 1 is any other error
+// and 2 a usage error, as for clap.
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NOT_FOUND: i32 = 3;
+pub const EXIT_LOCKED: i32 = 4;
+pub const EXIT_VERIFY_FAILED: i32 = 5;
+pub const EXIT_VERIFY_ALREADY_PASSES: i32 = 6;
+pub const EXIT_CYCLE: i32 = 7;
+pub const EXIT_CONFIG_INVALID: i32 = 8;
+pub const EXIT_CONFLICT: i32 = 9;
+pub const EXIT_HOOK_REJECTED: i32 = 10;
+pub const EXIT_VERIFY_TAMPERED: i32 = 11;
+
+/// Result alias for the library API.
+pub type Result<T, E = BeansError> = std::result::Result<T, E>;
+
+/// Why an operation failed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BeansError {
+    /// The ID is not a valid bean ID.
+    InvalidId { id: String, reason: String },
+    /// No bean with this ID (in the archive, if `archived`).
+    NotFound { id: String, archived: bool },
+    /// An argument failed validation (priority, status, on_fail, ...).
+    Invalid(String),
+    /// Another process holds `.beans/index.lock`.
+    Locked { waited_secs: u64 },
+    /// The verify command failed.
+    VerifyFailed {
+        id: String,
+        /// `None` if the command timed out or was killed by a signal.
+        exit_code: Option<i32>,
+        /// Combined stdout and stderr, possibly truncated.
+        output: String,
+    },
+    /// The verify command was changed, or files it runs were deleted or
+    /// shrunk, since the bean was claimed.
+    VerifyTampered { id: String, findings: Vec<String> },
+    /// Fail-first check: the verify command passes before any work was done.
+    /// `id` is `None` when the bean was being created.
+    VerifyAlreadyPasses { id: Option<String>, command: String },
+    /// Only open beans can be claimed. `claimed_by` is the current claimant,
+    /// if any.
+    NotClaimable {
+        id: String,
+        status: Status,
+        claimed_by: Option<String>,
+    },
+    /// Adding a dependency would close this cycle. The path starts and ends
+    /// with the bean the dependency was added to: `[a, b, ..., a]`.
+    Cycle { path: Vec<String> },
+    /// `id` already depends on `depends_on`.
+    DependencyExists { id: String, depends_on: String },
+    /// `id` does not depend on `depends_on`.
+    NoSuchDependency { id: String, depends_on: String },
+    /// A config file could not be parsed.
+    ConfigInvalid { path: PathBuf, message: String },
+    /// Merging the bean's worktree branch conflicted in these files.
+    Conflict { id: String, files: Vec<String> },
+    /// `bn undo` refused to revert a change to this bean because something
+    /// changed it since.
+    UndoConflict { id: String, reason: String },
+    /// A hook (`pre-create`, `pre-update`) refused the change.
+    HookRejected { hook: &'static str },
+    /// A run started by `run_dispatch` is still in progress in this process.
+    RunInProgress,
+    /// Anything else: I/O, parse errors, ...
+    Other(anyhow::Error),
+}
+
+impl BeansError {
+    /// Short snake_case name of the variant, for JSON error payloads.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BeansError::InvalidId { .. } => "invalid_id",
+            BeansError::NotFound { .. } => "not_found",
+            BeansError::Invalid(_) => "invalid",
+            BeansError::Locked { .. } => "locked",
+            BeansError::VerifyFailed { .. } => "verify_failed",
+            BeansError::VerifyTampered { .. } => "verify_tampered",
+            BeansError::VerifyAlreadyPasses { .. } => "verify_already_passes",
+            BeansError::NotClaimable { .. } => "not_claimable",
+            BeansError::Cycle { .. } => "cycle",
+            BeansError::DependencyExists { .. } => "dependency_exists",
+            BeansError::NoSuchDependency { .. } => "no_such_dependency",
+            BeansError::ConfigInvalid { .. } => "config_invalid",
+            BeansError::Conflict { .. } => "conflict",
+            BeansError::UndoConflict { .. } => "undo_conflict",
+            BeansError::HookRejected { .. } => "hook_rejected",
+            BeansError::RunInProgress => "run_in_progress",
+            BeansError::Other(_) => "other",
+        }
+    }
+
+    /// Process exit code for `bn` when a command fails with this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BeansError::Other(_) => EXIT_FAILURE,
+            BeansError::InvalidId { .. } | BeansError::Invalid(_) => EXIT_USAGE,
+            BeansError::NotFound { .. } | BeansError::NoSuchDependency { .. } => EXIT_NOT_FOUND,
+            BeansError::Locked { .. } => EXIT_LOCKED,
+            BeansError::VerifyFailed { .. } => EXIT_VERIFY_FAILED,
+            BeansError::VerifyTampered { .. } => EXIT_VERIFY_TAMPERED,
+            BeansError::VerifyAlreadyPasses { .. } => EXIT_VERIFY_ALREADY_PASSES,
+            BeansError::Cycle { .. } => EXIT_CYCLE,
+            BeansError::ConfigInvalid { .. } => EXIT_CONFIG_INVALID,
+            BeansError::Conflict { .. }
+            | BeansError::UndoConflict { .. }
+            | BeansError::NotClaimable { .. }
+            | BeansError::DependencyExists { .. }
+            | BeansError::RunInProgress => EXIT_CONFLICT,
+            BeansError::HookRejected { .. } => EXIT_HOOK_REJECTED,
+        }
+    }
+}
+
+impl fmt::Display for BeansError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BeansError::InvalidId { reason, .. } => write!(f, "{}", reason),
+            BeansError::NotFound {
+                id,
+                archived: false,
+            } => write!(f, "Bean {} not found", id),
+            BeansError::NotFound { id, archived: true } => {
+                write!(f, "Archived bean {} not found", id)
+            }
+            BeansError::Invalid(message) => write!(f, "{}", message),
+            BeansError::Locked { waited_secs } => write!(
+                f,
+                "Timed out after {}s waiting for .beans/index.lock — \
+                 another bn process may be running. \
+                 If no other process is active, delete .beans/index.lock and retry.",
+                waited_secs
+            ),
+            BeansError::VerifyFailed {
+                id,
+                exit_code,
+                output,
+            } => {
+                match exit_code {
+                    Some(code) => write!(f, "Verify failed for bean {} (exit code {})", id, code)?,
+                    None => write!(f, "Verify failed for bean {}", id)?,
+                }
+                if !output.is_empty() {
+                    write!(f, "\n{}", output)?;
+                }
+                Ok(())
+            }
+            BeansError::VerifyTampered { id, findings } => write!(
+                f,
+                "Verify gate for bean {} changed since it was claimed:\n  - {}\n\n\
+                 The bean is held for human review. Use --force to close it anyway.",
+                id,
+                findings.join("\n  - ")
+            ),
+            BeansError::VerifyAlreadyPasses { id: None, .. } => write!(
+                f,
+                "Cannot create bean: verify command already passes!\n\n\
+                 The test must FAIL on current code to prove it tests something real.\n\
+                 Either:\n\
+                 - The test doesn't actually test the new behavior\n\
+                 - The feature is already implemented\n\
+                 - The test is a no-op (assert True)\n\n\
+                 Use --pass-ok / -p to skip this check."
+            ),
+            BeansError::VerifyAlreadyPasses { id: Some(id), .. } => write!(
+                f,
+                "Cannot claim bean {}: verify already passes\n\n\
+                 The verify command succeeded before any work was done.\n\
+                 This means either the test is bogus or the work is already complete.\n\n\
+                 Use --force to override.",
+                id
+            ),
+            BeansError::NotClaimable {
+                id,
+                status,
+                claimed_by: Some(by),
+            } => write!(
+                f,
+                "Bean {} is {} (claimed by {}) -- only open beans can be claimed",
+                id, status, by
+            ),
+            BeansError::NotClaimable { id, status, .. } => write!(
+                f,
+                "Bean {} is {} -- only open beans can be claimed",
+                id, status
+            ),
+            BeansError::Cycle { path } if path.len() <= 2 => write!(
+                f,
+                "Cannot add self-dependency: {} cannot depend on itself",
+                path.first().map(String::as_str).unwrap_or_default()
+            ),
+            BeansError::Cycle { path } => write!(
+                f,
+                "Dependency cycle detected: adding {} -> {} would create a cycle ({}). Edge not added.",
+                path[0],
+                path[1],
+                path.join(" -> ")
+            ),
+            BeansError::DependencyExists { id, depends_on } => {
+                write!(f, "Bean {} already depends on {}", id, depends_on)
+            }
+            BeansError::NoSuchDependency { id, depends_on } => {
+                write!(f, "Bean {} does not depend on {}", id, depends_on)
+            }
+            BeansError::ConfigInvalid { path, message } => {
+                write!(f, "Failed to parse config at {}: {}", path.display(), message)
+            }
+            BeansError::Conflict { id, files } => write!(
+                f,
+                "Merge conflict closing bean {} in: {}",
+                id,
+                files.join(", ")
+            ),
+            BeansError::UndoConflict { id, reason } => {
+                write!(f, "Cannot undo changes to bean {}: {}", id, reason)
+            }
+            BeansError::HookRejected { hook: "pre-create" } => {
+                write!(f, "Pre-create hook rejected bean creation")
+            }
+            BeansError::HookRejected { hook: "pre-update" } => {
+                write!(f, "Pre-update hook rejected bean update")
+            }
+            BeansError::HookRejected { hook } => write!(f, "{} hook rejected the change", hook),
+            BeansError::RunInProgress => write!(f, "Another run is already in progress"),
+            BeansError::Other(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+impl std::error::Error for BeansError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BeansError::Other(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for BeansError {
+    /// Recover a [`BeansError`] raised inside a command function, or wrap
+    /// anything else as [`BeansError::Other`].
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<BeansError>() {
+            Ok(err) => err,
+            Err(err) => BeansError::Other(err),
+        }
+    }
+}
+
+/// The [`BeansError`] inside `err`, if any, looking through added context.
+pub fn find(err: &anyhow::Error) -> Option<&BeansError> {
+    err.downcast_ref::<BeansError>()
+        .or_else(|| err.chain().find_map(|e| e.downcast_ref::<BeansError>()))
+}
+
+/// Exit code for a failed `bn` command: the code of the [`BeansError`]
+/// inside `err`, or [`EXIT_FAILURE`].
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    find(err).map_or(EXIT_FAILURE, BeansError::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn typed_errors_survive_anyhow() {
+        let err: anyhow::Error = BeansError::Cycle {
+            path: vec!["1".to_string(), "2".to_string(), "1".to_string()],
+        }
+        .into();
+        assert!(err.to_string().starts_with("Dependency cycle detected"));
+        assert!(matches!(BeansError::from(err), BeansError::Cycle { .. }));
+
+        let err = BeansError::from(anyhow::anyhow!("disk full"));
+        assert!(matches!(err, BeansError::Other(_)));
+        assert_eq!(err.to_string(), "disk full");
+    }
+
+    #[test]
+    fn self_dependency_message() {
+        let err = BeansError::Cycle {
+            path: vec!["3".to_string(), "3".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Cannot add self-dependency: 3 cannot depend on itself"
+        );
+    }
+
+    #[test]
+    fn exit_code_looks_through_context() {
+        let err = Err::<(), _>(anyhow::Error::from(BeansError::NotFound {
+            id: "4".to_string(),
+            archived: false,
+        }))
+        .context("Failed to load bean: 4")
+        .context("Bean not found: 4")
+        .unwrap_err();
+        assert_eq!(exit_code(&err), 3);
+        assert_eq!(find(&err).map(BeansError::kind), Some("not_found"));
+
+        assert_eq!(exit_code(&anyhow::anyhow!("disk full")), 1);
+    }
+
+    #[test]
+    fn exit_codes_are_distinct_per_kind() {
+        let errors = [
+            BeansError::Invalid(String::new()),
+            BeansError::NotFound {
+                id: String::new(),
+                archived: false,
+            },
+            BeansError::Locked { waited_secs: 0 },
+            BeansError::VerifyFailed {
+                id: String::new(),
+                exit_code: None,
+                output: String::new(),
+            },
+            BeansError::VerifyAlreadyPasses {
+                id: None,
+                command: String::new(),
+            },
+            BeansError::Cycle { path: vec![] },
+            BeansError::ConfigInvalid {
+                path: PathBuf::new(),
+                message: String::new(),
+            },
+            BeansError::Conflict {
+                id: String::new(),
+                files: vec![],
+            },
+            BeansError::HookRejected { hook: "pre-close" },
+            BeansError::Other(anyhow::anyhow!("x")),
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(BeansError::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0));
+    }
+}
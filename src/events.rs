@@ -0,0 +1,395 @@
+//! Audit trail of bean mutations.
+//!
+//! Every create, update, claim, release, close, reopen, delete, adopt and
+//! move appends one [`Event`] to `.beans/events.jsonl`, recording who made
+//! the change, when, and the old and new value of each field it touched.
+//! The events written by one command share an [`Operation`] ID, so the old
+//! values are enough for `bn undo` to revert the whole command. `bn log`
+//! reads the log back.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::bean::Bean;
+use crate::config::resolve_identity;
+
+/// File name of the event log inside `.beans/`.
+pub const EVENTS_FILE: &str = "events.jsonl";
+
+/// What kind of mutation an event records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Create,
+    Update,
+    Claim,
+    Release,
+    Close,
+    Reopen,
+    Delete,
+    Adopt,
+    Move,
+    Undo,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Claim => "claim",
+            Action::Release => "release",
+            Action::Close => "close",
+            Action::Reopen => "reopen",
+            Action::Delete => "delete",
+            Action::Adopt => "adopt",
+            Action::Move => "move",
+            Action::Undo => "undo",
+        };
+        f.pad(name)
+    }
+}
+
+/// One field changed by a mutation. A missing value means the field was
+/// unset (or the bean did not exist) on that side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// One line of `.beans/events.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    pub action: Action,
+    /// The bean's ID after the mutation (before it, for deletes).
+    pub id: String,
+    /// The [`Operation`] this event belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub op: Option<String>,
+    /// For moves: the other project's `.beans/` directory, whose log holds
+    /// the other half of the operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer: Option<PathBuf>,
+    /// For undo events: the operations that were reverted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub undoes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<FieldChange>,
+}
+
+impl Event {
+    /// Whether this event concerns bean `id`, either as its current ID or
+    /// as the ID it had before an adopt.
+    pub fn touches(&self, id: &str) -> bool {
+        self.id == id || self.old_id() == Some(id)
+    }
+
+    /// The bean's ID before the mutation, if it changed.
+    pub fn old_id(&self) -> Option<&str> {
+        self.change("id")
+            .and_then(|c| c.old.as_ref())
+            .and_then(Value::as_str)
+    }
+
+    /// The change to `field`, if this event made one.
+    pub fn change(&self, field: &str) -> Option<&FieldChange> {
+        self.changes.iter().find(|c| c.field == field)
+    }
+
+    /// The bean did not exist before this event (created or moved in).
+    pub fn created(&self) -> bool {
+        self.change("id").is_some_and(|c| c.old.is_none())
+    }
+
+    /// The bean no longer exists after this event (deleted or moved out).
+    pub fn removed(&self) -> bool {
+        self.change("id").is_some_and(|c| c.new.is_none())
+    }
+}
+
+/// Groups the events written by one command, so `bn undo` reverts them
+/// together (a delete and the dependency cleanup it caused, an adopt of
+/// several beans, a close and the parents it auto-closed, ...).
+#[derive(Debug, Clone)]
+pub struct Operation {
+    id: String,
+}
+
+impl Operation {
+    pub fn new() -> Self {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+        Operation {
+            id: format!(
+                "{}-{}-{}",
+                Utc::now().timestamp_micros(),
+                std::process::id(),
+                SEQ.fetch_add(1, Ordering::Relaxed)
+            ),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// An event of this operation for a mutation of bean `id` from `before`
+    /// to `after`, by the actor [`resolve_identity`] finds for `beans_dir`.
+    pub fn event(
+        &self,
+        beans_dir: &Path,
+        action: Action,
+        id: &str,
+        before: Option<&Bean>,
+        after: Option<&Bean>,
+    ) -> Event {
+        Event {
+            timestamp: Utc::now(),
+            actor: resolve_identity(beans_dir),
+            action,
+            id: id.to_string(),
+            op: Some(self.id.clone()),
+            peer: None,
+            undoes: Vec::new(),
+            note: None,
+            changes: diff(before, after),
+        }
+    }
+
+    /// Append an event of this operation. Errors are swallowed — the
+    /// mutation itself has already happened and must not fail because of
+    /// the log.
+    pub fn record(
+        &self,
+        beans_dir: &Path,
+        action: Action,
+        id: &str,
+        before: Option<&Bean>,
+        after: Option<&Bean>,
+    ) {
+        let _ = append(beans_dir, &self.event(beans_dir, action, id, before, after));
+    }
+}
+
+impl Default for Operation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Field-by-field difference between two versions of a bean.
+///
+/// `None` stands for "no bean", so a create or delete lists every field.
+/// `updated_at` is left out when both versions exist since every mutation
+/// bumps it.
+pub fn diff(before: Option<&Bean>, after: Option<&Bean>) -> Vec<FieldChange> {
+    diff_maps(before.map(to_map).as_ref(), after.map(to_map).as_ref())
+}
+
+/// [`diff`] of two beans given as JSON objects.
+pub fn diff_maps(
+    before: Option<&Map<String, Value>>,
+    after: Option<&Map<String, Value>>,
+) -> Vec<FieldChange> {
+    let empty = Map::new();
+    let old = before.unwrap_or(&empty);
+    let new = after.unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter(|field| !(before.is_some() && after.is_some() && *field == "updated_at"))
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(|field| FieldChange {
+            field: field.clone(),
+            old: old.get(field).cloned(),
+            new: new.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// A bean as a JSON object, the form field changes are recorded in.
+pub fn to_map(bean: &Bean) -> Map<String, Value> {
+    match serde_json::to_value(bean) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Append an event for a mutation of bean `id` from `before` to `after`,
+/// as an operation of its own. See [`Operation::record`].
+pub fn record(
+    beans_dir: &Path,
+    action: Action,
+    id: &str,
+    before: Option<&Bean>,
+    after: Option<&Bean>,
+) {
+    Operation::new().record(beans_dir, action, id, before, after);
+}
+
+/// Like [`record`], with a free-text note.
+pub fn record_with_note(
+    beans_dir: &Path,
+    action: Action,
+    id: &str,
+    before: Option<&Bean>,
+    after: Option<&Bean>,
+    note: Option<String>,
+) {
+    let mut event = Operation::new().event(beans_dir, action, id, before, after);
+    event.note = note;
+    let _ = append(beans_dir, &event);
+}
+
+/// Append `event` to `.beans/events.jsonl`.
+pub fn append(beans_dir: &Path, event: &Event) -> anyhow::Result<()> {
+    let line = serde_json::to_string(event)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(beans_dir.join(EVENTS_FILE))?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Read all events, oldest first.
+///
+/// A missing file yields an empty list; malformed lines are skipped.
+pub fn load_events(beans_dir: &Path) -> Vec<Event> {
+    let Ok(contents) = fs::read_to_string(beans_dir.join(EVENTS_FILE)) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::Status;
+    use tempfile::TempDir;
+
+    #[test]
+    fn diff_lists_only_changed_fields() {
+        let before = Bean::new("1", "Task");
+        let mut after = before.clone();
+        after.verify = Some("cargo test".to_string());
+        after.status = Status::InProgress;
+        after.updated_at = Utc::now();
+
+        let changes = diff(Some(&before), Some(&after));
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["status", "verify"]);
+
+        let verify = &changes[1];
+        assert_eq!(verify.old, None);
+        assert_eq!(verify.new, Some(Value::from("cargo test")));
+    }
+
+    #[test]
+    fn diff_against_nothing_lists_every_field() {
+        let bean = Bean::new("1", "Task");
+        let changes = diff(None, Some(&bean));
+        assert!(changes.iter().any(|c| c.field == "title"));
+        assert!(changes.iter().any(|c| c.field == "updated_at"));
+        assert!(changes.iter().all(|c| c.old.is_none()));
+    }
+
+    #[test]
+    fn record_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path();
+        let before = Bean::new("1", "Task");
+        let mut after = before.clone();
+        after.title = "Renamed".to_string();
+
+        record(beans_dir, Action::Create, "1", None, Some(&before));
+        record(beans_dir, Action::Update, "1", Some(&before), Some(&after));
+
+        let events = load_events(beans_dir);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].action, Action::Create);
+        assert_eq!(events[1].action, Action::Update);
+        assert_eq!(
+            events[1].change("title").unwrap().old,
+            Some(Value::from("Task"))
+        );
+    }
+
+    #[test]
+    fn load_skips_malformed_lines() {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path();
+        record(
+            beans_dir,
+            Action::Delete,
+            "1",
+            Some(&Bean::new("1", "Task")),
+            None,
+        );
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(beans_dir.join(EVENTS_FILE))
+            .unwrap();
+        writeln!(file, "not json").unwrap();
+
+        let events = load_events(beans_dir);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, Action::Delete);
+    }
+
+    #[test]
+    fn touches_matches_old_id_after_adopt() {
+        let before = Bean::new("3", "Task");
+        let mut after = before.clone();
+        after.id = "1.1".to_string();
+        let event = Operation::new().event(
+            Path::new("."),
+            Action::Adopt,
+            "1.1",
+            Some(&before),
+            Some(&after),
+        );
+        assert!(event.touches("1.1"));
+        assert!(event.touches("3"));
+        assert!(!event.touches("2"));
+    }
+
+    #[test]
+    fn emptied_list_is_not_a_removal() {
+        let mut before = Bean::new("2", "Task");
+        before.dependencies = vec!["1".to_string()];
+        let mut after = before.clone();
+        after.dependencies.clear();
+        let event = Operation::new().event(
+            Path::new("."),
+            Action::Update,
+            "2",
+            Some(&before),
+            Some(&after),
+        );
+        assert_eq!(event.change("dependencies").unwrap().new, None);
+        assert!(!event.removed());
+        assert!(!event.created());
+    }
+}
@@ -7,6 +7,8 @@
 use std::collections::BTreeSet;
 use std::fmt::Write;
 
+use crate::bean::TestSummary;
+
 /// Everything needed to produce a failure summary.
 #[derive(Debug)]
 pub struct FailureContext {
@@ -23,6 +25,8 @@ pub struct FailureContext {
     /// Log lines in `[tool] ToolName path/or/args` format.
     pub tool_log: Vec<String>,
     pub verify_command: Option<String>,
+    /// Test results of the last verify run, if its output was recognised.
+    pub tests: Option<TestSummary>,
 }
 
 /// Build a structured markdown summary of a failed agent run.
@@ -50,6 +54,16 @@ pub fn build_failure_summary(ctx: &FailureContext) -> String {
     sections.push("### Why it failed".to_string());
     sections.push(build_failure_reason(ctx));
 
+    // Failing tests
+    if let Some(tests) = ctx.tests.as_ref().filter(|t| t.failed > 0) {
+        sections.push("### Failing tests".to_string());
+        let mut body = tests.to_string();
+        if let Some(ref message) = tests.message {
+            let _ = write!(body, "\n\n```\n{message}\n```");
+        }
+        sections.push(body);
+    }
+
     // Files touched
     let files = extract_files_from_logs(&ctx.tool_log);
     if !files.is_empty() {
@@ -306,6 +320,7 @@ mod tests {
             error: Some("idle timeout after 300s".into()),
             tool_log: sample_logs(),
             verify_command: Some("cargo test widget".into()),
+            tests: None,
         }
     }
 
@@ -508,6 +523,27 @@ mod tests {
         assert!(suggestion.is_none());
     }
 
+    #[test]
+    fn summary_includes_failing_tests() {
+        let ctx = FailureContext {
+            tests: Some(TestSummary {
+                passed: 117,
+                failed: 3,
+                ignored: 0,
+                failing: vec!["a".into(), "b".into(), "c".into()],
+                message: Some("assertion failed: ok".into()),
+            }),
+            ..sample_ctx()
+        };
+        let summary = build_failure_summary(&ctx);
+        assert!(summary.contains(
+            "### Failing tests\n\n3 of 120 tests failed: a, b, c\n\n```\nassertion failed: ok\n```"
+        ));
+
+        // Omitted without results, or when every test passed
+        assert!(!build_failure_summary(&sample_ctx()).contains("### Failing tests"));
+    }
+
     #[test]
     fn singular_bash_command() {
         let ctx = FailureContext {
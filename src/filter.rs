@@ -0,0 +1,794 @@
+//! Filter expressions over bean fields.
+//!
+//! Used by `bn list -q`, `bn run --filter`, `bn tidy --filter`, the MCP
+//! `list_beans` tool and named views in config:
+//!
+//! ```text
+//! priority<=1 and label:backend and not claimed and updated<7d
+//! (status:open or status:blocked) path:src/api/**
+//! title~"parse(r|d)" attempts>=2
+//! @stale and has_verify
+//! ```
+//!
+//! A comparison is `field op value`:
+//!
+//! - `:` / `=` — equal (case-insensitive); a value with `*`, `?` or `[` is a
+//!   glob. On lists (`labels`, `paths`, `dependencies`, `produces`,
+//!   `requires`) any element may match.
+//! - `!=` — not equal.
+//! - `~` — case-insensitive regex match.
+//! - `<`, `<=`, `>`, `>=` — numbers (`priority<=P1`), list lengths
+//!   (`dependencies>2`), and timestamps: against a date (`created>2026-01-31`)
+//!   or a duration ago, so `<` reads as "before" either way (`updated<7d`
+//!   means last updated more than seven days ago, `updated>1h` within the
+//!   last hour; units `m`, `h`, `d`, `w`).
+//!
+//! A bare field tests that it is set (`claimed`, `has_verify`, `paths`).
+//! Terms combine with `and`, `or`, `not` (or `&&`, `||`, `!`) and
+//! parentheses; adjacent terms are joined with `and`. `@name` expands the
+//! view `name` from the `views:` section of `.beans/config.yaml`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::bean::Bean;
+use crate::config::Config;
+use crate::discovery::{find_archived_bean, find_bean_file};
+use crate::error::BeansError;
+use crate::index::IndexEntry;
+use crate::util::natural_cmp;
+
+/// Fields available on [`IndexEntry`], so matching them needs no bean file.
+const ENTRY_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "status",
+    "priority",
+    "parent",
+    "dependencies",
+    "labels",
+    "assignee",
+    "updated_at",
+    "produces",
+    "requires",
+    "has_verify",
+    "claimed_by",
+    "attempts",
+    "paths",
+    "blocked_reason",
+];
+
+/// Fields only a full [`Bean`] has.
+const BEAN_FIELDS: &[&str] = &[
+    "slug",
+    "created_at",
+    "description",
+    "acceptance",
+    "notes",
+    "design",
+    "closed_at",
+    "close_reason",
+    "verify",
+    "fail_first",
+    "max_attempts",
+    "claimed_at",
+    "is_archived",
+    "bean_type",
+    "last_verified",
+    "stale_after",
+    "created_by",
+    "feature",
+];
+
+const TIMESTAMP_FIELDS: &[&str] = &[
+    "created_at",
+    "updated_at",
+    "closed_at",
+    "claimed_at",
+    "last_verified",
+    "stale_after",
+];
+
+/// Shorter names accepted for fields.
+fn canonical_field(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let name = match name.as_str() {
+        "updated" => "updated_at",
+        "created" => "created_at",
+        "closed" => "closed_at",
+        "claimed" => "claimed_by",
+        "label" => "labels",
+        "path" => "paths",
+        "dep" | "deps" => "dependencies",
+        "type" => "bean_type",
+        "archived" => "is_archived",
+        "reason" => "close_reason",
+        other => other,
+    };
+    ENTRY_FIELDS
+        .iter()
+        .chain(BEAN_FIELDS)
+        .find(|f| **f == name)
+        .copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Regex,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    /// A bare field: true when it is set.
+    Set(&'static str),
+    Compare {
+        field: &'static str,
+        op: Op,
+        value: String,
+        regex: Option<Regex>,
+    },
+}
+
+/// A parsed filter expression.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expr: Expr,
+}
+
+impl Filter {
+    /// Parse `input`, expanding `@name` from `views`.
+    pub fn parse(input: &str, views: &BTreeMap<String, String>) -> Result<Self> {
+        let expr = Parser::new(input, views, Vec::new())?.parse_all()?;
+        Ok(Filter { expr })
+    }
+
+    /// Parse `input` with the views configured for `beans_dir`.
+    pub fn load(beans_dir: &Path, input: &str) -> Result<Self> {
+        let views = Config::load_with_extends(beans_dir)
+            .map(|config| config.views)
+            .unwrap_or_default();
+        Self::parse(input, &views)
+    }
+
+    /// Whether the expression mentions `field` (by its canonical name).
+    pub fn references(&self, field: &str) -> bool {
+        fn walk(expr: &Expr, field: &str) -> bool {
+            match expr {
+                Expr::And(a, b) | Expr::Or(a, b) => walk(a, field) || walk(b, field),
+                Expr::Not(e) => walk(e, field),
+                Expr::Set(f) | Expr::Compare { field: f, .. } => *f == field,
+            }
+        }
+        walk(&self.expr, field)
+    }
+
+    /// Whether the expression tests the status (or a field only closed
+    /// beans have), replacing the usual default of hiding closed beans.
+    pub fn decides_status(&self) -> bool {
+        ["status", "closed_at", "close_reason", "is_archived"]
+            .iter()
+            .any(|field| self.references(field))
+    }
+
+    /// Whether the expression mentions a field that only bean files have.
+    pub fn needs_bean(&self) -> bool {
+        BEAN_FIELDS.iter().any(|f| self.references(f))
+    }
+
+    /// Evaluate against a full bean.
+    pub fn matches_bean(&self, bean: &Bean) -> bool {
+        let mut record = to_record(bean);
+        record.insert("has_verify".to_string(), Value::Bool(bean.verify.is_some()));
+        eval(&self.expr, &record, Utc::now())
+    }
+
+    /// Evaluate against an index entry, loading the bean file from
+    /// `beans_dir` (active or archived) when the expression needs it.
+    pub fn matches(&self, entry: &IndexEntry, beans_dir: &Path) -> bool {
+        if self.needs_bean() {
+            let bean = find_bean_file(beans_dir, &entry.id)
+                .or_else(|_| find_archived_bean(beans_dir, &entry.id))
+                .and_then(Bean::from_file);
+            if let Ok(bean) = bean {
+                return self.matches_bean(&bean);
+            }
+        }
+        eval(&self.expr, &to_record(entry), Utc::now())
+    }
+}
+
+fn to_record<T: serde::Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+fn eval(expr: &Expr, record: &Map<String, Value>, now: DateTime<Utc>) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, record, now) && eval(b, record, now),
+        Expr::Or(a, b) => eval(a, record, now) || eval(b, record, now),
+        Expr::Not(e) => !eval(e, record, now),
+        Expr::Set(field) => is_set(record.get(*field)),
+        Expr::Compare {
+            field,
+            op,
+            value,
+            regex,
+        } => {
+            let actual = record.get(*field).unwrap_or(&Value::Null);
+            match op {
+                Op::Eq => equals(actual, value),
+                Op::Ne => !equals(actual, value),
+                Op::Regex => any_scalar(actual, |s| regex.as_ref().is_some_and(|r| r.is_match(s))),
+                _ => compare(field, actual, value, now).is_some_and(|ord| match op {
+                    Op::Lt => ord.is_lt(),
+                    Op::Le => ord.is_le(),
+                    Op::Gt => ord.is_gt(),
+                    Op::Ge => ord.is_ge(),
+                    _ => false,
+                }),
+            }
+        }
+    }
+}
+
+fn is_set(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(b)) => *b,
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(a)) => !a.is_empty(),
+        Some(Value::Number(n)) => n.as_f64() != Some(0.0),
+        Some(Value::Object(o)) => !o.is_empty(),
+    }
+}
+
+/// Apply `test` to the value as text, or to each element of a list.
+fn any_scalar(value: &Value, test: impl Fn(&str) -> bool) -> bool {
+    any_scalar_dyn(value, &test)
+}
+
+fn any_scalar_dyn(value: &Value, test: &dyn Fn(&str) -> bool) -> bool {
+    match value {
+        Value::Array(items) => items.iter().any(|item| any_scalar_dyn(item, test)),
+        Value::String(s) => test(s),
+        Value::Number(n) => test(&n.to_string()),
+        Value::Bool(b) => test(&b.to_string()),
+        _ => false,
+    }
+}
+
+fn equals(actual: &Value, expected: &str) -> bool {
+    if let Some(expected) = parse_number(expected) {
+        if let Some(actual) = actual.as_f64() {
+            return actual == expected;
+        }
+    }
+    if let Value::Bool(b) = actual {
+        return parse_bool(expected) == Some(*b);
+    }
+    if expected.contains(['*', '?', '[']) {
+        if let Ok(pattern) = Pattern::new(expected) {
+            let options = MatchOptions {
+                case_sensitive: false,
+                ..MatchOptions::new()
+            };
+            return any_scalar(actual, |s| pattern.matches_with(s, options));
+        }
+    }
+    any_scalar(actual, |s| s.eq_ignore_ascii_case(expected))
+}
+
+fn compare(
+    field: &str,
+    actual: &Value,
+    expected: &str,
+    now: DateTime<Utc>,
+) -> Option<std::cmp::Ordering> {
+    if TIMESTAMP_FIELDS.contains(&field) {
+        let at = DateTime::parse_from_rfc3339(actual.as_str()?)
+            .ok()?
+            .with_timezone(&Utc);
+        if let Some(age) = parse_duration(expected) {
+            return Some(at.cmp(&(now - age)));
+        }
+        return Some(at.cmp(&parse_timestamp(expected)?));
+    }
+    match actual {
+        Value::Number(n) => n.as_f64()?.partial_cmp(&parse_number(expected)?),
+        Value::Array(items) => (items.len() as f64).partial_cmp(&parse_number(expected)?),
+        Value::String(s) => Some(natural_cmp(s, expected)),
+        _ => None,
+    }
+}
+
+/// A number, allowing the `P1` priority notation.
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .strip_prefix(['P', 'p'])
+        .unwrap_or(value)
+        .parse::<f64>()
+        .ok()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    let split = value.len().checked_sub(1)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    match unit {
+        "m" => Some(Duration::minutes(amount)),
+        "h" => Some(Duration::hours(amount)),
+        "d" => Some(Duration::days(amount)),
+        "w" => Some(Duration::weeks(amount)),
+        _ => None,
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    View(String),
+    Set(&'static str),
+    Compare {
+        field: &'static str,
+        op: Op,
+        value: String,
+    },
+}
+
+fn invalid(message: String) -> anyhow::Error {
+    BeansError::Invalid(message).into()
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let is_word_end = |c: Option<&char>| c.is_none_or(|c| c.is_whitespace() || "()".contains(*c));
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+                continue;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+                continue;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+                continue;
+            }
+            '&' | '|' if chars.get(i + 1) == Some(&c) => {
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+                i += 2;
+                continue;
+            }
+            '@' => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && (chars[i].is_alphanumeric() || "_-.".contains(chars[i])) {
+                    i += 1;
+                }
+                if i == start {
+                    return Err(invalid(format!(
+                        "Expected a view name after '@' at position {}",
+                        start
+                    )));
+                }
+                tokens.push(Token::View(chars[start..i].iter().collect()));
+                continue;
+            }
+            _ => {}
+        }
+
+        let start = i;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+        let name: String = chars[start..i].iter().collect();
+        if name.is_empty() {
+            return Err(invalid(format!(
+                "Unexpected '{}' at position {} in filter",
+                c, start
+            )));
+        }
+
+        let op = match (chars.get(i), chars.get(i + 1)) {
+            (Some('<'), Some('=')) => Some((Op::Le, 2)),
+            (Some('>'), Some('=')) => Some((Op::Ge, 2)),
+            (Some('!'), Some('=')) => Some((Op::Ne, 2)),
+            (Some('<'), _) => Some((Op::Lt, 1)),
+            (Some('>'), _) => Some((Op::Gt, 1)),
+            (Some(':'), _) | (Some('='), _) => Some((Op::Eq, 1)),
+            (Some('~'), _) => Some((Op::Regex, 1)),
+            _ => None,
+        };
+
+        let Some((op, len)) = op else {
+            if !is_word_end(chars.get(i)) {
+                return Err(invalid(format!(
+                    "Unexpected '{}' at position {} in filter",
+                    chars[i], i
+                )));
+            }
+            match name.to_lowercase().as_str() {
+                "and" => tokens.push(Token::And),
+                "or" => tokens.push(Token::Or),
+                "not" => tokens.push(Token::Not),
+                _ => tokens.push(Token::Set(field(&name)?)),
+            }
+            continue;
+        };
+        i += len;
+
+        let value: String = match chars.get(i) {
+            Some(&quote) if quote == '"' || quote == '\'' => {
+                let value_start = i + 1;
+                let end = chars[value_start..]
+                    .iter()
+                    .position(|&c| c == quote)
+                    .map(|p| value_start + p)
+                    .ok_or_else(|| {
+                        invalid(format!("Unterminated quote at position {} in filter", i))
+                    })?;
+                i = end + 1;
+                chars[value_start..end].iter().collect()
+            }
+            _ => {
+                let value_start = i;
+                while !is_word_end(chars.get(i)) {
+                    i += 1;
+                }
+                chars[value_start..i].iter().collect()
+            }
+        };
+        if value.is_empty() {
+            return Err(invalid(format!(
+                "Missing value for '{}' at position {} in filter",
+                name, i
+            )));
+        }
+        tokens.push(Token::Compare {
+            field: field(&name)?,
+            op,
+            value,
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn field(name: &str) -> Result<&'static str> {
+    canonical_field(name).ok_or_else(|| {
+        let mut known: Vec<&str> = ENTRY_FIELDS.iter().chain(BEAN_FIELDS).copied().collect();
+        known.sort_unstable();
+        invalid(format!(
+            "Unknown field '{}' in filter. Known fields: {}",
+            name,
+            known.join(", ")
+        ))
+    })
+}
+
+/// Recursive-descent parser: `or` binds loosest, then `and` (explicit or
+/// implied by adjacency), then `not`.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    views: &'a BTreeMap<String, String>,
+    /// Views being expanded, to reject cycles.
+    expanding: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(
+        input: &str,
+        views: &'a BTreeMap<String, String>,
+        expanding: Vec<String>,
+    ) -> Result<Self> {
+        Ok(Parser {
+            tokens: tokenize(input)?,
+            pos: 0,
+            views,
+            expanding,
+        })
+    }
+
+    fn parse_all(mut self) -> Result<Expr> {
+        if self.tokens.is_empty() {
+            return Err(invalid("Empty filter expression".to_string()));
+        }
+        let expr = self.parse_or()?;
+        if let Some(token) = self.tokens.get(self.pos) {
+            return Err(invalid(format!("Unexpected {} in filter", describe(token))));
+        }
+        Ok(expr)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                Some(_) => {}
+            }
+            let right = self.parse_not()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(invalid("Filter ends unexpectedly".to_string()));
+        };
+        self.pos += 1;
+        match token {
+            Token::LParen => {
+                let expr = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(invalid("Missing ')' in filter".to_string()));
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Token::Set(field) => Ok(Expr::Set(field)),
+            Token::Compare { field, op, value } => {
+                let regex = if op == Op::Regex {
+                    Some(Regex::new(&format!("(?i){}", value)).map_err(|e| {
+                        invalid(format!("Invalid regex '{}' in filter: {}", value, e))
+                    })?)
+                } else {
+                    None
+                };
+                Ok(Expr::Compare {
+                    field,
+                    op,
+                    value,
+                    regex,
+                })
+            }
+            Token::View(name) => self.expand_view(&name),
+            other => Err(invalid(format!(
+                "Unexpected {} in filter",
+                describe(&other)
+            ))),
+        }
+    }
+
+    fn expand_view(&self, name: &str) -> Result<Expr> {
+        if self.expanding.iter().any(|v| v == name) {
+            return Err(invalid(format!("View '@{}' refers to itself", name)));
+        }
+        let Some(text) = self.views.get(name) else {
+            return Err(invalid(format!(
+                "Unknown view '@{}'. Define it with: bn config set view.{} '<filter>'",
+                name, name
+            )));
+        };
+        let mut expanding = self.expanding.clone();
+        expanding.push(name.to_string());
+        Parser::new(text, self.views, expanding)?
+            .parse_all()
+            .map_err(|e| invalid(format!("In view '@{}': {}", name, e)))
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+        Token::And => "'and'".to_string(),
+        Token::Or => "'or'".to_string(),
+        Token::Not => "'not'".to_string(),
+        Token::View(name) => format!("'@{}'", name),
+        Token::Set(field) | Token::Compare { field, .. } => format!("'{}'", field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bean::Status;
+
+    fn filter(input: &str) -> Filter {
+        Filter::parse(input, &BTreeMap::new()).unwrap()
+    }
+
+    fn bean() -> Bean {
+        let mut bean = Bean::new("3", "Fix parser recovery");
+        bean.priority = 1;
+        bean.labels = vec!["backend".to_string(), "bug".to_string()];
+        bean.paths = vec!["src/api/routes.rs".to_string()];
+        bean.attempts = 2;
+        bean.verify = Some("cargo test".to_string());
+        bean.updated_at = Utc::now() - Duration::days(10);
+        bean
+    }
+
+    #[test]
+    fn example_from_docs() {
+        let f = filter("priority<=1 and label:backend and not claimed and updated<7d");
+        let mut bean = bean();
+        assert!(f.matches_bean(&bean));
+
+        bean.claimed_by = Some("alice".to_string());
+        assert!(!f.matches_bean(&bean));
+
+        let mut fresh = self::bean();
+        fresh.updated_at = Utc::now();
+        assert!(!f.matches_bean(&fresh));
+    }
+
+    #[test]
+    fn comparisons() {
+        let bean = bean();
+        for (input, expected) in [
+            ("priority=1", true),
+            ("priority:P1", true),
+            ("priority<1", false),
+            ("priority!=1", false),
+            ("attempts>=2", true),
+            ("has_verify", true),
+            ("not has_verify", false),
+            ("status:open", true),
+            ("status:OPEN", true),
+            ("label:bug", true),
+            ("label:frontend", false),
+            ("labels>1", true),
+            ("path:src/api/**", true),
+            ("path:src/cli/*", false),
+            ("title~\"pars(er|ing)\"", true),
+            ("title~lexer", false),
+            ("title:'Fix parser recovery'", true),
+            ("updated<7d", true),
+            ("updated>1d", false),
+            ("updated>2020-01-01", true),
+            ("created<2020-01-01", false),
+            ("id>=3 id<10", true),
+            ("description", false),
+            ("verify:'cargo test'", true),
+        ] {
+            assert_eq!(filter(input).matches_bean(&bean), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn boolean_precedence_and_grouping() {
+        let bean = bean();
+        assert!(filter("label:frontend or label:bug and priority=1").matches_bean(&bean));
+        assert!(!filter("(label:frontend or label:bug) and priority=0").matches_bean(&bean));
+        assert!(filter("!(claimed || status:closed)").matches_bean(&bean));
+        assert!(filter("not not has_verify").matches_bean(&bean));
+    }
+
+    #[test]
+    fn entry_fields_need_no_bean() {
+        let f = filter("priority<=1 label:backend");
+        assert!(!f.needs_bean());
+        assert!(f.references("labels"));
+        assert!(filter("type:fact").needs_bean());
+        assert!(filter("created>1d").needs_bean());
+
+        let entry = IndexEntry::from(&bean());
+        assert!(eval(&f.expr, &to_record(&entry), Utc::now()));
+    }
+
+    #[test]
+    fn views_expand_and_reject_cycles() {
+        let mut views = BTreeMap::new();
+        views.insert("backend".to_string(), "label:backend".to_string());
+        views.insert("urgent".to_string(), "@backend priority<=1".to_string());
+        views.insert("loop".to_string(), "@loop".to_string());
+
+        let f = Filter::parse("@urgent and status:open", &views).unwrap();
+        assert!(f.matches_bean(&bean()));
+        let mut other = bean();
+        other.status = Status::Closed;
+        assert!(!f.matches_bean(&other));
+
+        assert!(Filter::parse("@loop", &views).is_err());
+        assert!(Filter::parse("@missing", &views).is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for input in [
+            "",
+            "priority<=",
+            "colour:red",
+            "(label:bug",
+            "label:bug)",
+            "title~'('",
+            "title:\"open",
+            "and label:bug",
+            "label:bug or",
+        ] {
+            let err = Filter::parse(input, &BTreeMap::new()).unwrap_err();
+            assert!(
+                matches!(crate::error::find(&err), Some(BeansError::Invalid(_))),
+                "{}: {}",
+                input,
+                err
+            );
+        }
+    }
+}
@@ -47,6 +47,49 @@ pub fn detect_cycle(index: &Index, from_id: &str, to_id: &str) -> Result<bool> {
     Ok(false)
 }
 
+/// The cycle that adding the edge `from_id -> to_id` would close, if any.
+///
+/// Returns `[from_id, to_id, ..., from_id]`, following existing dependencies
+/// from `to_id` back to `from_id` by a shortest path.
+pub fn cycle_path(index: &Index, from_id: &str, to_id: &str) -> Option<Vec<String>> {
+    if from_id == to_id {
+        return Some(vec![from_id.to_string(), to_id.to_string()]);
+    }
+
+    let graph: HashMap<&str, &Vec<String>> = index
+        .beans
+        .iter()
+        .map(|entry| (entry.id.as_str(), &entry.dependencies))
+        .collect();
+
+    // BFS from to_id, remembering how each bean was reached
+    let mut came_from: HashMap<&str, &str> = HashMap::new();
+    let mut queue = std::collections::VecDeque::from([to_id]);
+    came_from.insert(to_id, to_id);
+
+    while let Some(current) = queue.pop_front() {
+        if current == from_id {
+            let mut path = vec![from_id.to_string()];
+            let mut step = current;
+            while step != to_id {
+                step = came_from[step];
+                path.push(step.to_string());
+            }
+            path.push(from_id.to_string());
+            path.reverse();
+            return Some(path);
+        }
+        for dep in graph.get(current).into_iter().flat_map(|deps| deps.iter()) {
+            if !came_from.contains_key(dep.as_str()) {
+                came_from.insert(dep, current);
+                queue.push_back(dep);
+            }
+        }
+    }
+
+    None
+}
+
 /// Build a dependency tree rooted at `id`.
 /// Returns a string representation with box-drawing characters.
 pub fn build_dependency_tree(index: &Index, id: &str) -> Result<String> {
@@ -332,6 +375,19 @@ mod tests {
         assert!(!detect_cycle(&index, "2", "3").unwrap());
     }
 
+    #[test]
+    fn cycle_path_follows_dependencies() {
+        let (_dir, beans_dir) =
+            setup_test_beans(vec![("1", vec!["2"]), ("2", vec!["3"]), ("3", vec![])]);
+        let index = Index::build(&beans_dir).unwrap();
+        assert_eq!(
+            cycle_path(&index, "3", "1").unwrap(),
+            vec!["3", "1", "2", "3"]
+        );
+        assert_eq!(cycle_path(&index, "2", "2").unwrap(), vec!["2", "2"]);
+        assert!(cycle_path(&index, "1", "3").is_none());
+    }
+
     // =====================================================================
     // Subtree Attempts Tests
     // =====================================================================
@@ -1,10 +1,10 @@
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::Path;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AgentHistoryEntry {
     pub bean_id: String,
     pub title: String,
@@ -26,6 +26,19 @@ pub fn append_history(beans_dir: &Path, entry: &AgentHistoryEntry) {
     let _ = try_append(beans_dir, entry);
 }
 
+/// Read all records from `.beans/agent_history.jsonl`.
+///
+/// A missing file yields an empty list; malformed lines are skipped.
+pub fn load_history(beans_dir: &Path) -> Vec<AgentHistoryEntry> {
+    let Ok(contents) = fs::read_to_string(beans_dir.join("agent_history.jsonl")) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
 fn try_append(
     beans_dir: &Path,
     entry: &AgentHistoryEntry,
@@ -134,4 +147,21 @@ mod tests {
         // Should not panic
         append_history(&bogus, &make_entry(true));
     }
+
+    #[test]
+    fn load_history_reads_entries_and_skips_garbage() {
+        let dir = TempDir::new().unwrap();
+        assert!(load_history(dir.path()).is_empty());
+
+        append_history(dir.path(), &make_entry(true));
+        let path = dir.path().join("agent_history.jsonl");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not json").unwrap();
+        append_history(dir.path(), &make_entry(false));
+
+        let entries = load_history(dir.path());
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].success);
+        assert!(!entries[1].success);
+    }
 }
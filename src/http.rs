@@ -0,0 +1,444 @@
+//! Minimal HTTP/1.1 plumbing for beans' local servers.
+//!
+//! Only what a loopback JSON server needs: one request per connection
+//! (`Connection: close`), bodies sized by `Content-Length`, and
+//! server-sent event streams. No chunked request bodies, no TLS.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Largest request head (request line plus headers) we accept.
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Largest request body we accept.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string, e.g. `/mcp`.
+    pub path: String,
+    /// Raw query string after `?`, if any.
+    pub query: Option<String>,
+    /// Header names are lowercased.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// First value of header `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Token from an `Authorization: Bearer <token>` header.
+    pub fn bearer_token(&self) -> Option<&str> {
+        let value = self.header("authorization")?;
+        let (scheme, token) = value.split_once(' ')?;
+        scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+    }
+
+    /// Decoded `key=value` pairs from the query string, in order.
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        self.query
+            .as_deref()
+            .unwrap_or("")
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect()
+    }
+
+    /// Whether the request carries the bearer `token`. Always true when no
+    /// token is configured.
+    pub fn is_authorized(&self, token: Option<&str>) -> bool {
+        match token {
+            None => true,
+            Some(expected) => self
+                .bearer_token()
+                .is_some_and(|given| constant_time_eq(given.as_bytes(), expected.as_bytes())),
+        }
+    }
+}
+
+/// Decode `%XX` escapes and `+` (as space) in a URL component. Malformed
+/// escapes are kept as-is.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Compare secrets without leaking the position of the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether a browser `Origin` header names this machine.
+///
+/// Local servers reject other origins so a website can't drive them through
+/// DNS rebinding or cross-site requests. Non-browser clients send no
+/// `Origin` and are always accepted.
+pub fn is_local_origin(origin: Option<&str>) -> bool {
+    let origin = match origin {
+        None => return true,
+        // Sandboxed iframes and file:// pages
+        Some("null") => return false,
+        Some(o) => o,
+    };
+    let host = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    let host = match host.rsplit_once(':') {
+        Some((h, port)) if port.chars().all(|c| c.is_ascii_digit()) => h,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Read a line of the request head, failing once the head exceeds
+/// `MAX_HEAD_BYTES` rather than buffering an unbounded line.
+fn read_head_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    head_bytes: &mut usize,
+) -> io::Result<usize> {
+    let limit = (MAX_HEAD_BYTES + 1 - *head_bytes) as u64;
+    let n = reader.by_ref().take(limit).read_line(line)?;
+    *head_bytes += n;
+    if *head_bytes > MAX_HEAD_BYTES {
+        return Err(invalid("request headers too large".to_string()));
+    }
+    Ok(n)
+}
+
+/// Read one request. Returns `Ok(None)` if the peer closed the connection
+/// before sending anything.
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Option<Request>> {
+    let mut head_bytes = 0;
+    let mut request_line = String::new();
+    loop {
+        request_line.clear();
+        let n = read_head_line(reader, &mut request_line, &mut head_bytes)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        // Tolerate stray blank lines before the request line (RFC 9112 §2.2)
+        if !request_line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), target)
+        }
+        _ => {
+            return Err(invalid(format!(
+                "bad request line: {}",
+                request_line.trim()
+            )))
+        }
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        let n = read_head_line(reader, &mut line, &mut head_bytes)?;
+        let line = line.trim_end_matches(['\r', '\n']);
+        if n == 0 || line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid(format!("bad header: {}", line)))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    let mut request = Request {
+        method,
+        path,
+        query,
+        headers,
+        body: Vec::new(),
+    };
+
+    if request
+        .header("transfer-encoding")
+        .is_some_and(|te| !te.eq_ignore_ascii_case("identity"))
+    {
+        return Err(invalid(
+            "chunked request bodies are not supported".to_string(),
+        ));
+    }
+    let length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| invalid(format!("bad Content-Length: {}", value)))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(invalid(format!(
+            "request body too large ({} bytes)",
+            length
+        )));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+
+    Ok(Some(request))
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// An HTTP response with a fully buffered body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// An empty response.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// A response with a JSON body.
+    pub fn json(status: u16, body: &impl serde::Serialize) -> Self {
+        let body = serde_json::to_vec(body).unwrap_or_else(|_| b"null".to_vec());
+        Self::new(status)
+            .with_header("Content-Type", "application/json")
+            .with_body(body)
+    }
+
+    /// A response with a plain-text body.
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self::new(status)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(body.into().into_bytes())
+    }
+
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn with_body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Write the status line, headers and body, then flush.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        head.push_str("Connection: close\r\n\r\n");
+        writer.write_all(head.as_bytes())?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+/// Start a `text/event-stream` response. The connection stays open; send
+/// events with [`write_event`] until the client goes away.
+pub fn start_event_stream(writer: &mut impl Write, headers: &[(&str, &str)]) -> io::Result<()> {
+    let mut head = String::from(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n",
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+    writer.write_all(head.as_bytes())?;
+    writer.flush()
+}
+
+/// Send one server-sent event. `data` may span several lines.
+pub fn write_event(writer: &mut impl Write, event: &str, data: &str) -> io::Result<()> {
+    let mut message = format!("event: {}\n", event);
+    for line in data.lines() {
+        message.push_str(&format!("data: {}\n", line));
+    }
+    message.push('\n');
+    writer.write_all(message.as_bytes())?;
+    writer.flush()
+}
+
+/// Send an SSE comment so idle streams notice a vanished client.
+pub fn write_keepalive(writer: &mut impl Write) -> io::Result<()> {
+    writer.write_all(b": keepalive\n\n")?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    fn parse(raw: &str) -> io::Result<Option<Request>> {
+        read_request(&mut BufReader::new(raw.as_bytes()))
+    }
+
+    #[test]
+    fn read_request_parses_line_headers_and_body() {
+        let request = parse(
+            "POST /mcp?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\
+             Mcp-Session-Id: abc\r\n\r\nbodyEXTRA",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/mcp");
+        assert_eq!(request.query.as_deref(), Some("x=1"));
+        assert_eq!(request.header("MCP-SESSION-ID"), Some("abc"));
+        assert_eq!(request.body, b"body");
+    }
+
+    #[test]
+    fn read_request_handles_eof_and_garbage() {
+        assert!(parse("").unwrap().is_none());
+        assert!(parse("hello\r\n\r\n").is_err());
+        assert!(parse("POST / HTTP/1.1\r\nContent-Length: nope\r\n\r\n").is_err());
+        assert!(parse("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn read_request_bounds_the_head() {
+        // An endless request line is cut off at the limit, not buffered whole
+        let mut endless = BufReader::new(io::repeat(b'a'));
+        let err = read_request(&mut endless).unwrap_err();
+        assert!(err.to_string().contains("too large"), "{}", err);
+
+        let header = format!("x: {}\r\n", "a".repeat(1024));
+        let raw = format!("GET / HTTP/1.1\r\n{}\r\n", header.repeat(64));
+        assert!(parse(&raw).is_err());
+    }
+
+    #[test]
+    fn query_pairs_are_decoded() {
+        let request =
+            parse("GET /v1/beans?label=needs%20review&all&q=a+b&bad=%zz HTTP/1.1\r\n\r\n")
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            request.query_pairs(),
+            vec![
+                ("label".to_string(), "needs review".to_string()),
+                ("all".to_string(), String::new()),
+                ("q".to_string(), "a b".to_string()),
+                ("bad".to_string(), "%zz".to_string()),
+            ]
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%E2%9C%93"), "✓");
+    }
+
+    #[test]
+    fn bearer_token_authorization() {
+        let request = parse("GET / HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.bearer_token(), Some("s3cret"));
+        assert!(request.is_authorized(None));
+        assert!(request.is_authorized(Some("s3cret")));
+        assert!(!request.is_authorized(Some("s3cre")));
+        assert!(!request.is_authorized(Some("other!")));
+
+        let anonymous = parse("GET / HTTP/1.1\r\n\r\n").unwrap().unwrap();
+        assert!(!anonymous.is_authorized(Some("s3cret")));
+    }
+
+    #[test]
+    fn only_local_origins_are_allowed() {
+        assert!(is_local_origin(None));
+        assert!(is_local_origin(Some("http://localhost:3000")));
+        assert!(is_local_origin(Some("http://127.0.0.1")));
+        assert!(is_local_origin(Some("https://[::1]:8443")));
+        assert!(!is_local_origin(Some("https://evil.example")));
+        assert!(!is_local_origin(Some("http://localhost.evil.example")));
+        assert!(!is_local_origin(Some("null")));
+    }
+
+    #[test]
+    fn response_write_to_sets_length_and_close() {
+        let mut out = Vec::new();
+        Response::text(404, "nope").write_to(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(out.contains("Content-Length: 4\r\n"));
+        assert!(out.contains("Connection: close\r\n"));
+        assert!(out.ends_with("\r\n\r\nnope"));
+    }
+
+    #[test]
+    fn write_event_prefixes_every_line() {
+        let mut out = Vec::new();
+        write_event(&mut out, "message", "a\nb").unwrap();
+        assert_eq!(out, b"event: message\ndata: a\ndata: b\n\n");
+    }
+}
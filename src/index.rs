@@ -9,6 +9,7 @@ use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 
 use crate::bean::{Bean, Status};
+use crate::error::BeansError;
 use crate::util::{atomic_write, natural_cmp};
 
 // ---------------------------------------------------------------------------
@@ -48,6 +49,9 @@ pub struct IndexEntry {
     /// File paths this bean touches (for scope-based blocking)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub paths: Vec<String>,
+    /// Why the bean is parked (status `blocked`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_reason: Option<String>,
 }
 
 impl From<&Bean> for IndexEntry {
@@ -68,6 +72,7 @@ impl From<&Bean> for IndexEntry {
             claimed_by: bean.claimed_by.clone(),
             attempts: bean.attempts,
             paths: bean.paths.clone(),
+            blocked_reason: bean.blocked_reason.clone(),
         }
     }
 }
@@ -85,7 +90,7 @@ pub struct Index {
 const EXCLUDED_FILES: &[&str] = &["config.yaml", "index.yaml", "bean.yaml", "archive.yaml"];
 
 /// Check if a filename represents a bean file (not a config/index/template file).
-fn is_bean_filename(filename: &str) -> bool {
+pub(crate) fn is_bean_filename(filename: &str) -> bool {
     if EXCLUDED_FILES.contains(&filename) {
         return false;
     }
@@ -506,12 +511,10 @@ impl LockedIndex {
                 Ok(()) => return Ok(()),
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     if start.elapsed() >= timeout {
-                        return Err(anyhow!(
-                            "Timed out after {}s waiting for .beans/index.lock — \
-                             another bn process may be running. \
-                             If no other process is active, delete .beans/index.lock and retry.",
-                            timeout.as_secs()
-                        ));
+                        return Err(BeansError::Locked {
+                            waited_secs: timeout.as_secs(),
+                        }
+                        .into());
                     }
                     std::thread::sleep(Duration::from_millis(50));
                 }
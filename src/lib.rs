@@ -1,3 +1,5 @@
+pub(crate) mod agent_output;
+pub mod agent_presets;
 pub mod api;
 pub mod bean;
 pub mod blocking;
@@ -6,9 +8,13 @@ pub mod commands;
 pub mod config;
 pub mod ctx_assembler;
 pub mod discovery;
+pub mod error;
+pub mod events;
 pub mod failure;
+pub mod filter;
 pub mod graph;
 pub mod history;
+pub(crate) mod http;
 pub(crate) mod hooks;
 pub mod index;
 pub(crate) mod locks;
@@ -18,7 +24,13 @@ pub(crate) mod pi_output;
 pub(crate) mod project;
 pub mod prompt;
 pub(crate) mod relevance;
+pub mod rest;
+pub mod sandbox;
+pub mod search;
+pub mod spawner;
 pub(crate) mod stream;
+pub mod test_results;
 pub(crate) mod timeout;
 pub mod util;
+pub mod verify_cache;
 pub(crate) mod worktree;
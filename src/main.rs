@@ -13,11 +13,11 @@ use bn::commands::quick::QuickArgs;
 use bn::commands::{
     cmd_adopt, cmd_agents, cmd_claim, cmd_close, cmd_config_get, cmd_config_set, cmd_context,
     cmd_create, cmd_delete, cmd_dep_add, cmd_dep_list, cmd_dep_remove, cmd_doctor, cmd_edit,
-    cmd_fact, cmd_graph, cmd_init, cmd_list, cmd_locks, cmd_locks_clear, cmd_logs, cmd_mcp_serve,
-    cmd_memory_context, cmd_move_from, cmd_move_to, cmd_plan, cmd_quick, cmd_recall,
-    cmd_release, cmd_reopen,
-    cmd_run, cmd_show, cmd_stats, cmd_status, cmd_sync, cmd_tidy, cmd_trace, cmd_tree, cmd_trust,
-    cmd_unarchive, cmd_update, cmd_verify, cmd_verify_facts,
+    cmd_fact, cmd_graph, cmd_init, cmd_list, cmd_locks, cmd_locks_clear, cmd_log, cmd_logs, cmd_mcp_serve,
+    cmd_memory_context, cmd_merge_driver, cmd_move_from, cmd_move_to, cmd_plan, cmd_quick, cmd_recall,
+    cmd_release, cmd_reopen, cmd_resolve,
+    cmd_run, cmd_serve, cmd_show, cmd_stats, cmd_status, cmd_sync, cmd_tidy, cmd_trace, cmd_tree, cmd_trust,
+    cmd_unarchive, cmd_undo, cmd_update, cmd_verify, cmd_verify_facts,
     review::{cmd_review, ReviewArgs},
 };
 use bn::discovery::find_beans_dir;
@@ -49,8 +49,16 @@ fn resolve_bean_ids(ids: Vec<String>, beans_dir: &std::path::Path) -> Result<Vec
         .collect()
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() {
+    if let Err(err) = run(Cli::parse()) {
+        // Same report as returning the error from main, with an exit code
+        // that says what kind of failure it was
+        eprintln!("Error: {:?}", err);
+        std::process::exit(bn::error::exit_code(&err));
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
 
     // Init is special - doesn't need beans_dir
     if let Command::Init {
@@ -60,6 +68,7 @@ fn main() -> Result<()> {
         plan,
         setup,
         no_agent,
+        git_merge_driver,
     } = cli.command
     {
         return cmd_init(
@@ -71,6 +80,7 @@ fn main() -> Result<()> {
                 plan,
                 setup,
                 no_agent,
+                git_merge_driver,
             },
         );
     }
@@ -82,12 +92,25 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // The merge driver runs inside `git merge` on temp files
+    if let Command::MergeDriver {
+        base,
+        ours,
+        theirs,
+        path,
+    } = cli.command
+    {
+        let clean = cmd_merge_driver(&base, &ours, &theirs, path.as_deref())?;
+        std::process::exit(if clean { 0 } else { 1 });
+    }
+
     // All other commands need beans_dir
     let beans_dir = find_beans_dir(&env::current_dir()?)?;
 
     match cli.command {
         Command::Init { .. } => unreachable!(),
         Command::Completions { .. } => unreachable!(),
+        Command::MergeDriver { .. } => unreachable!(),
 
         Command::Create { args } => {
             let CreateOpts {
@@ -393,6 +416,7 @@ fn main() -> Result<()> {
             parent,
             label,
             assignee,
+            query,
             all,
             mine,
             json,
@@ -405,6 +429,7 @@ fn main() -> Result<()> {
             parent.as_deref(),
             label.as_deref(),
             assignee.as_deref(),
+            query.as_deref(),
             mine,
             all,
             json,
@@ -421,6 +446,7 @@ fn main() -> Result<()> {
             notes,
             design,
             status,
+            reason,
             priority,
             assignee,
             add_label,
@@ -444,6 +470,7 @@ fn main() -> Result<()> {
                 notes,
                 design,
                 status,
+                reason,
                 priority,
                 assignee,
                 add_label,
@@ -457,7 +484,11 @@ fn main() -> Result<()> {
             force,
             failed,
             stdin,
+            no_cache,
         } => {
+            if no_cache {
+                bn::verify_cache::bypass();
+            }
             let ids = if stdin {
                 bn::commands::stdin::read_ids_from_stdin()?
             } else {
@@ -474,7 +505,12 @@ fn main() -> Result<()> {
             }
         }
 
-        Command::Verify { id, json, .. } => {
+        Command::Verify {
+            id, json, no_cache, ..
+        } => {
+            if no_cache {
+                bn::verify_cache::bypass();
+            }
             validate_bean_id(&id)?;
             let resolved_id = resolve_bean_id(&id, &beans_dir)?;
             let out = bn::output::Output::new();
@@ -486,7 +522,7 @@ fn main() -> Result<()> {
                 );
             }
             if !passed {
-                std::process::exit(1);
+                std::process::exit(bn::error::EXIT_VERIFY_FAILED);
             }
             Ok(())
         }
@@ -496,7 +532,11 @@ fn main() -> Result<()> {
             release,
             by,
             force,
+            no_cache,
         } => {
+            if no_cache {
+                bn::verify_cache::bypass();
+            }
             validate_bean_id(&id)?;
             let resolved_id = resolve_bean_id(&id, &beans_dir)?;
             if release {
@@ -518,6 +558,8 @@ fn main() -> Result<()> {
             cmd_delete(&beans_dir, &resolved_id)
         }
 
+        Command::Undo { steps } => cmd_undo(&beans_dir, steps),
+
         Command::Dep { command } => match command {
             DepCommand::Add { id, depends_on } => {
                 validate_bean_id(&id)?;
@@ -569,13 +611,20 @@ fn main() -> Result<()> {
         }
         Command::Graph { format } => cmd_graph(&beans_dir, &format),
         Command::Sync => cmd_sync(&beans_dir),
-        Command::Tidy { dry_run, .. } => {
+        Command::Tidy {
+            dry_run, filter, ..
+        } => {
             let out = bn::output::Output::new();
-            cmd_tidy(&beans_dir, dry_run, &out)
+            cmd_tidy(&beans_dir, dry_run, filter.as_deref(), &out)
         }
         Command::Stats { json } => cmd_stats(&beans_dir, json),
         Command::Doctor { fix } => cmd_doctor(&beans_dir, fix),
-        Command::Trust { revoke, check } => cmd_trust(&beans_dir, revoke, check),
+        Command::Trust {
+            revoke,
+            check,
+            list,
+            diff,
+        } => cmd_trust(&beans_dir, revoke, check, list, diff),
 
         Command::Unarchive { id } => {
             validate_bean_id(&id)?;
@@ -591,6 +640,12 @@ fn main() -> Result<()> {
             }
         }
 
+        Command::Resolve { id, field, take } => {
+            validate_bean_id(&id)?;
+            let resolved_id = resolve_bean_id(&id, &beans_dir)?;
+            cmd_resolve(&beans_dir, &resolved_id, field.as_deref(), take.as_deref())
+        }
+
         Command::Quick {
             title,
             description,
@@ -661,12 +716,15 @@ fn main() -> Result<()> {
             jobs,
             dry_run,
             loop_mode,
+            watch,
             auto_plan,
             keep_going,
             timeout,
             idle_timeout,
             json_stream,
             review,
+            resume,
+            filter,
         } => cmd_run(
             &beans_dir,
             bn::commands::run::RunArgs {
@@ -674,12 +732,15 @@ fn main() -> Result<()> {
                 jobs,
                 dry_run,
                 loop_mode,
+                watch,
                 auto_plan,
                 keep_going,
                 timeout,
                 idle_timeout,
                 json_stream,
                 review,
+                resume,
+                filter,
             },
         ),
 
@@ -717,6 +778,24 @@ fn main() -> Result<()> {
             cmd_logs(&beans_dir, &resolved_id, follow, all)
         }
 
+        Command::Log {
+            id,
+            since,
+            actor,
+            json,
+        } => {
+            if let Some(ref id) = id {
+                validate_bean_id(id)?;
+            }
+            cmd_log(
+                &beans_dir,
+                id.as_deref(),
+                since.as_deref(),
+                actor.as_deref(),
+                json,
+            )
+        }
+
         Command::Fact {
             title,
             verify,
@@ -731,7 +810,12 @@ fn main() -> Result<()> {
 
         Command::Recall { query, all, json } => cmd_recall(&beans_dir, &query, all, json),
 
-        Command::VerifyFacts => cmd_verify_facts(&beans_dir),
+        Command::VerifyFacts { no_cache } => {
+            if no_cache {
+                bn::verify_cache::bypass();
+            }
+            cmd_verify_facts(&beans_dir)
+        }
 
         Command::Config { command } => match command {
             ConfigCommand::Get { key } => cmd_config_get(&beans_dir, &key),
@@ -739,8 +823,9 @@ fn main() -> Result<()> {
         },
 
         Command::Mcp { command } => match command {
-            McpCommand::Serve => cmd_mcp_serve(&beans_dir),
+            McpCommand::Serve { http } => cmd_mcp_serve(&beans_dir, http.as_deref()),
         },
+        Command::Serve { addr } => cmd_serve(&beans_dir, &addr),
 
         Command::Trace { id, json } => {
             validate_bean_id(&id)?;
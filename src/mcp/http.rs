@@ -0,0 +1,287 @@
+//! MCP streamable HTTP transport: `bn mcp serve --http ADDR`.
+//!
+//! Everything goes through a single `/mcp` endpoint:
+//!
+//! - `POST` carries one JSON-RPC message or a batch. An `initialize` request
+//!   starts a session and the response carries its `Mcp-Session-Id`; every
+//!   other request must send that header back.
+//! - `GET` with `Accept: text/event-stream` opens a server-sent event stream
+//!   for the session's `notifications/resources/updated` messages.
+//! - `DELETE` ends the session.
+//!
+//! Requests are answered by [`server::handle_request`], the same handler the
+//! stdio transport uses. If a token is given (`$BEANS_MCP_TOKEN` for
+//! `bn mcp serve`), every request must carry `Authorization: Bearer <token>`.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::http::{self, Request, Response};
+use crate::mcp::protocol::{JsonRpcRequest, JsonRpcResponse, INVALID_REQUEST, PARSE_ERROR};
+use crate::mcp::server::{self, Session, SUBSCRIPTION_POLL_INTERVAL};
+
+/// The one endpoint of the transport.
+pub const ENDPOINT: &str = "/mcp";
+
+/// Header that carries the session ID.
+const SESSION_HEADER: &str = "Mcp-Session-Id";
+
+/// How often an idle event stream sends a keepalive comment.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A bound, not yet running, MCP HTTP server.
+pub struct HttpServer {
+    listener: TcpListener,
+}
+
+/// State shared by all connections.
+struct Shared {
+    beans_dir: PathBuf,
+    token: Option<String>,
+    sessions: Mutex<HashMap<String, Arc<Session>>>,
+}
+
+impl HttpServer {
+    /// Bind to `addr` (e.g. `127.0.0.1:8808`; port 0 picks a free port).
+    pub fn bind(addr: &str) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to bind {}", addr))?;
+        Ok(Self { listener })
+    }
+
+    /// The address actually bound.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accept connections forever, one thread per connection.
+    pub fn run(self, beans_dir: &Path, token: Option<String>) -> Result<()> {
+        let shared = Arc::new(Shared {
+            beans_dir: beans_dir.to_path_buf(),
+            token,
+            sessions: Mutex::new(HashMap::new()),
+        });
+
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &shared) {
+                    eprintln!("HTTP connection error: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(stream: TcpStream, shared: &Shared) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    let request = match http::read_request(&mut BufReader::new(stream)) {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            Response::text(400, format!("{}\n", e)).write_to(&mut writer)?;
+            return Ok(());
+        }
+    };
+
+    if request.path != ENDPOINT {
+        return Ok(Response::text(404, "Not found\n").write_to(&mut writer)?);
+    }
+    if !request.is_authorized(shared.token.as_deref()) {
+        let response = Response::text(401, "Missing or invalid bearer token\n")
+            .with_header("WWW-Authenticate", "Bearer");
+        return Ok(response.write_to(&mut writer)?);
+    }
+    if !http::is_local_origin(request.header("origin")) {
+        return Ok(Response::text(403, "Origin not allowed\n").write_to(&mut writer)?);
+    }
+
+    match request.method.as_str() {
+        "POST" => handle_post(&request, shared).write_to(&mut writer)?,
+        "GET" => handle_get(&request, shared, &mut writer)?,
+        "DELETE" => handle_delete(&request, shared).write_to(&mut writer)?,
+        _ => Response::text(405, "Method not allowed\n")
+            .with_header("Allow", "GET, POST, DELETE")
+            .write_to(&mut writer)?,
+    }
+    Ok(())
+}
+
+/// Answer a JSON-RPC message or batch.
+fn handle_post(request: &Request, shared: &Shared) -> Response {
+    let body: Value = match serde_json::from_slice(&request.body) {
+        Ok(body) => body,
+        Err(e) => {
+            let error =
+                JsonRpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e));
+            return Response::json(400, &error);
+        }
+    };
+    let (batch, messages) = match body {
+        Value::Array(messages) => (true, messages),
+        message => (false, vec![message]),
+    };
+    let messages: Vec<JsonRpcRequest> = match messages
+        .into_iter()
+        .map(serde_json::from_value)
+        .collect::<Result<_, _>>()
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            let error = JsonRpcResponse::error(
+                Value::Null,
+                INVALID_REQUEST,
+                format!("Invalid request: {}", e),
+            );
+            return Response::json(400, &error);
+        }
+    };
+
+    // `initialize` starts a new session; everything else needs an existing one
+    let (session_id, session) = if messages.iter().any(|m| m.method == "initialize") {
+        let id = new_session_id();
+        let session = Arc::new(Session::new());
+        lock(&shared.sessions).insert(id.clone(), Arc::clone(&session));
+        eprintln!("MCP session started: {}", id);
+        (Some(id), session)
+    } else {
+        match find_session(request, shared) {
+            Ok(session) => (None, session),
+            Err(response) => return response,
+        }
+    };
+
+    let responses: Vec<JsonRpcResponse> = messages
+        .into_iter()
+        .filter_map(|m| server::handle_request(m, &shared.beans_dir, &session))
+        .collect();
+
+    let response = if responses.is_empty() {
+        // Only notifications
+        Response::new(202)
+    } else if batch {
+        Response::json(200, &responses)
+    } else {
+        Response::json(200, &responses[0])
+    };
+    match session_id {
+        Some(id) => response.with_header(SESSION_HEADER, id),
+        None => response,
+    }
+}
+
+/// Stream resource update notifications for a session until the client
+/// disconnects or the session ends.
+fn handle_get(request: &Request, shared: &Shared, writer: &mut impl Write) -> Result<()> {
+    let accepts_events = request
+        .header("accept")
+        .is_some_and(|a| a.contains("text/event-stream"));
+    if !accepts_events {
+        Response::text(406, "GET needs Accept: text/event-stream\n").write_to(writer)?;
+        return Ok(());
+    }
+    let session = match find_session(request, shared) {
+        Ok(session) => session,
+        Err(response) => {
+            response.write_to(writer)?;
+            return Ok(());
+        }
+    };
+
+    http::start_event_stream(writer, &[])?;
+    let mut last_write = Instant::now();
+    let id = request.header(SESSION_HEADER).unwrap_or_default();
+    while lock(&shared.sessions).contains_key(id) {
+        std::thread::sleep(SUBSCRIPTION_POLL_INTERVAL);
+        for uri in session.changed_resources(&shared.beans_dir) {
+            let data = serde_json::to_string(&server::resource_updated(&uri))?;
+            http::write_event(writer, "message", &data)?;
+            last_write = Instant::now();
+        }
+        if last_write.elapsed() >= KEEPALIVE_INTERVAL {
+            http::write_keepalive(writer)?;
+            last_write = Instant::now();
+        }
+    }
+    Ok(())
+}
+
+/// End a session.
+fn handle_delete(request: &Request, shared: &Shared) -> Response {
+    let id = match request.header(SESSION_HEADER) {
+        Some(id) => id,
+        None => return Response::text(400, format!("Missing {} header\n", SESSION_HEADER)),
+    };
+    match lock(&shared.sessions).remove(id) {
+        Some(_) => {
+            eprintln!("MCP session ended: {}", id);
+            Response::new(204)
+        }
+        None => Response::text(404, "Unknown session\n"),
+    }
+}
+
+/// Look up the session named by the request's `Mcp-Session-Id` header.
+fn find_session(request: &Request, shared: &Shared) -> Result<Arc<Session>, Response> {
+    let id = request
+        .header(SESSION_HEADER)
+        .ok_or_else(|| Response::text(400, format!("Missing {} header\n", SESSION_HEADER)))?;
+    lock(&shared.sessions)
+        .get(id)
+        .cloned()
+        .ok_or_else(|| Response::text(404, "Unknown session\n"))
+}
+
+/// An unguessable session ID.
+fn new_session_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    // RandomState is seeded from the OS random source
+    let seed = RandomState::new().build_hasher().finish();
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update(nanos.to_le_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(COUNTER.fetch_add(1, Ordering::SeqCst).to_le_bytes());
+    format!("{:x}", hasher.finalize())[..32].to_string()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_ids_are_unique_hex() {
+        let a = new_session_id();
+        let b = new_session_id();
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -1,9 +1,11 @@
 //! MCP (Model Context Protocol) server for beans.
 //!
-//! Exposes beans operations as MCP tools over stdio transport,
-//! enabling integration with Cursor, Windsurf, Claude Desktop, Cline,
-//! and any MCP-compatible client.
+//! Exposes beans operations as MCP tools, resources and prompts over
+//! stdio or streamable HTTP, enabling integration with Cursor, Windsurf,
+//! Claude Desktop, Cline, and any MCP-compatible client.
 
+pub mod http;
+pub mod prompts;
 pub mod protocol;
 pub mod resources;
 pub mod server;
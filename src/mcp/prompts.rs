@@ -0,0 +1,171 @@
+//! MCP prompt definitions and handlers.
+//!
+//! Prompts expose the same text `bn run` and `bn plan` hand to their agents,
+//! so an IDE agent can work a bean with the full project context.
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::bean::Bean;
+use crate::commands::plan::build_decomposition_prompt;
+use crate::discovery::find_bean_file;
+use crate::mcp::protocol::{PromptArgument, PromptDefinition};
+use crate::prompt::{build_agent_prompt, PromptOptions};
+
+/// Return all MCP prompt definitions.
+pub fn prompt_definitions() -> Vec<PromptDefinition> {
+    vec![
+        PromptDefinition {
+            name: "implement_bean".to_string(),
+            description: "Full agent prompt for implementing a bean: project rules, parent context, referenced files, verify gate, and previous attempts".to_string(),
+            arguments: vec![PromptArgument {
+                name: "id".to_string(),
+                description: "Bean ID".to_string(),
+                required: true,
+            }],
+        },
+        PromptDefinition {
+            name: "plan_bean".to_string(),
+            description: "Decomposition prompt for splitting a large bean into smaller children".to_string(),
+            arguments: vec![
+                PromptArgument {
+                    name: "id".to_string(),
+                    description: "Bean ID".to_string(),
+                    required: true,
+                },
+                PromptArgument {
+                    name: "strategy".to_string(),
+                    description: "Split strategy: feature, layer, phase, file, or free text".to_string(),
+                    required: false,
+                },
+            ],
+        },
+    ]
+}
+
+/// Handle a `prompts/get` request. Returns the MCP `GetPromptResult`.
+pub fn handle_prompt_get(name: &str, args: &Value, beans_dir: &Path) -> Result<Value> {
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required argument: id"))?;
+    crate::util::validate_bean_id(id)?;
+    let bean = Bean::from_file(find_bean_file(beans_dir, id)?)?;
+
+    match name {
+        "implement_bean" => {
+            let prompt = build_agent_prompt(
+                &bean,
+                &PromptOptions {
+                    beans_dir: beans_dir.to_path_buf(),
+                    instructions: None,
+                    concurrent_overlaps: None,
+                    previous_failure: None,
+                },
+            )?;
+            // MCP prompts have no system role, so the context goes first as
+            // its own user message.
+            Ok(json!({
+                "description": format!("Implement bean {}: {}", id, bean.title),
+                "messages": [
+                    user_message(&prompt.system_prompt),
+                    user_message(&prompt.user_message),
+                ]
+            }))
+        }
+        "plan_bean" => {
+            let strategy = args.get("strategy").and_then(|v| v.as_str());
+            let prompt = build_decomposition_prompt(id, &bean, strategy);
+            Ok(json!({
+                "description": format!("Plan bean {}: {}", id, bean.title),
+                "messages": [user_message(&prompt)]
+            }))
+        }
+        _ => anyhow::bail!("Unknown prompt: {}", name),
+    }
+}
+
+fn user_message(text: &str) -> Value {
+    json!({
+        "role": "user",
+        "content": { "type": "text", "text": text }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, std::path::PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+        fs::write(beans_dir.join("config.yaml"), "project: test\nnext_id: 2\n").unwrap();
+        let mut bean = Bean::new("1", "Add pagination");
+        bean.verify = Some("cargo test page".to_string());
+        bean.to_file(beans_dir.join("1-add-pagination.md")).unwrap();
+        (dir, beans_dir)
+    }
+
+    fn texts(result: &Value) -> Vec<&str> {
+        result["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                assert_eq!(m["role"], "user");
+                m["content"]["text"].as_str().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn definitions_declare_required_id() {
+        let defs = prompt_definitions();
+        let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["implement_bean", "plan_bean"]);
+        for def in &defs {
+            assert!(def.arguments.iter().any(|a| a.name == "id" && a.required));
+        }
+        let strategy = defs[1].arguments.iter().find(|a| a.name == "strategy");
+        assert!(!strategy.unwrap().required);
+    }
+
+    #[test]
+    fn implement_bean_renders_context_then_task() {
+        let (_dir, beans_dir) = setup();
+        let result =
+            handle_prompt_get("implement_bean", &json!({ "id": "1" }), &beans_dir).unwrap();
+
+        assert_eq!(result["description"], "Implement bean 1: Add pagination");
+        let texts = texts(&result);
+        assert_eq!(texts.len(), 2);
+        assert!(texts.concat().contains("cargo test page"));
+    }
+
+    #[test]
+    fn plan_bean_passes_the_strategy() {
+        let (_dir, beans_dir) = setup();
+        let args = json!({ "id": "1", "strategy": "split by endpoint" });
+        let result = handle_prompt_get("plan_bean", &args, &beans_dir).unwrap();
+
+        let texts = texts(&result);
+        assert_eq!(texts.len(), 1);
+        assert!(texts[0].contains("split by endpoint"));
+        assert!(texts[0].contains("Add pagination"));
+    }
+
+    #[test]
+    fn bad_arguments_are_rejected() {
+        let (_dir, beans_dir) = setup();
+        assert!(handle_prompt_get("implement_bean", &json!({}), &beans_dir).is_err());
+        assert!(handle_prompt_get("implement_bean", &json!({ "id": 1 }), &beans_dir).is_err());
+        assert!(handle_prompt_get("implement_bean", &json!({ "id": "../1" }), &beans_dir).is_err());
+        assert!(handle_prompt_get("implement_bean", &json!({ "id": "9" }), &beans_dir).is_err());
+        assert!(handle_prompt_get("review_bean", &json!({ "id": "1" }), &beans_dir).is_err());
+    }
+}
@@ -1,7 +1,9 @@
 //! JSON-RPC 2.0 and MCP protocol types.
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+
+use crate::error::BeansError;
 
 // ---------------------------------------------------------------------------
 // JSON-RPC 2.0
@@ -55,6 +57,50 @@ pub const METHOD_NOT_FOUND: i64 = -32601;
 pub const INVALID_PARAMS: i64 = -32602;
 pub const INTERNAL_ERROR: i64 = -32603;
 
+// Server error codes for `BeansError`s (see `error_code`). -32002 is the
+// code MCP uses for a missing resource.
+pub const NOT_FOUND: i64 = -32002;
+pub const LOCKED: i64 = -32010;
+pub const VERIFY_FAILED: i64 = -32011;
+pub const VERIFY_ALREADY_PASSES: i64 = -32012;
+pub const CYCLE: i64 = -32013;
+pub const CONFIG_INVALID: i64 = -32014;
+pub const CONFLICT: i64 = -32015;
+pub const HOOK_REJECTED: i64 = -32016;
+pub const VERIFY_TAMPERED: i64 = -32017;
+
+/// JSON-RPC error code for a failed operation: by [`BeansError`] kind, or
+/// [`INTERNAL_ERROR`] for anything else.
+pub fn error_code(err: &anyhow::Error) -> i64 {
+    let Some(err) = crate::error::find(err) else {
+        return INTERNAL_ERROR;
+    };
+    match err {
+        BeansError::InvalidId { .. } | BeansError::Invalid(_) => INVALID_PARAMS,
+        BeansError::NotFound { .. } | BeansError::NoSuchDependency { .. } => NOT_FOUND,
+        BeansError::Locked { .. } => LOCKED,
+        BeansError::VerifyFailed { .. } => VERIFY_FAILED,
+        BeansError::VerifyTampered { .. } => VERIFY_TAMPERED,
+        BeansError::VerifyAlreadyPasses { .. } => VERIFY_ALREADY_PASSES,
+        BeansError::Cycle { .. } => CYCLE,
+        BeansError::ConfigInvalid { .. } => CONFIG_INVALID,
+        BeansError::Conflict { .. }
+        | BeansError::UndoConflict { .. }
+        | BeansError::NotClaimable { .. }
+        | BeansError::DependencyExists { .. }
+        | BeansError::RunInProgress => CONFLICT,
+        BeansError::HookRejected { .. } => HOOK_REJECTED,
+        BeansError::Other(_) => INTERNAL_ERROR,
+    }
+}
+
+/// `{"code", "kind"}` describing a failed operation, for error `data` and
+/// tool result `_meta`.
+pub fn error_data(err: &anyhow::Error) -> Value {
+    let kind = crate::error::find(err).map_or("other", BeansError::kind);
+    json!({ "code": error_code(err), "kind": kind })
+}
+
 impl JsonRpcResponse {
     /// Create a success response.
     pub fn success(id: Value, result: Value) -> Self {
@@ -79,6 +125,16 @@ impl JsonRpcResponse {
             id,
         }
     }
+
+    /// Create an error response for a failed operation, with its
+    /// [`error_code`] and the error kind in `data`.
+    pub fn from_error(id: Value, context: &str, err: &anyhow::Error) -> Self {
+        let mut response = Self::error(id, error_code(err), format!("{}: {}", context, err));
+        if let Some(ref mut error) = response.error {
+            error.data = Some(json!({ "kind": error_data(err)["kind"] }));
+        }
+        response
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -152,3 +208,52 @@ pub struct ResourceContent {
     pub mime_type: Option<String>,
     pub text: String,
 }
+
+/// MCP resource template (a parameterized URI such as `beans://bean/{id}`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    pub uri_template: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// MCP prompt definition.
+#[derive(Debug, Serialize)]
+pub struct PromptDefinition {
+    pub name: String,
+    pub description: String,
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// An argument accepted by an MCP prompt.
+#[derive(Debug, Serialize)]
+pub struct PromptArgument {
+    pub name: String,
+    pub description: String,
+    pub required: bool,
+}
+
+/// A JSON-RPC 2.0 notification sent by the server.
+#[derive(Debug, Serialize)]
+pub struct JsonRpcNotification {
+    /// Always "2.0"
+    pub jsonrpc: String,
+    /// Notification method, e.g. `notifications/resources/updated`
+    pub method: String,
+    pub params: Value,
+}
+
+impl JsonRpcNotification {
+    /// Create a notification.
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            method: method.into(),
+            params,
+        }
+    }
+}
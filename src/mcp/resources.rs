@@ -8,7 +8,7 @@ use serde_json::json;
 use crate::bean::Bean;
 use crate::discovery::find_bean_file;
 use crate::index::Index;
-use crate::mcp::protocol::{ResourceContent, ResourceDefinition};
+use crate::mcp::protocol::{ResourceContent, ResourceDefinition, ResourceTemplate};
 
 /// Return static resource definitions.
 pub fn resource_definitions() -> Vec<ResourceDefinition> {
@@ -30,6 +30,41 @@ pub fn resource_definitions() -> Vec<ResourceDefinition> {
     ]
 }
 
+/// Return the parameterized resources clients can read by bean ID.
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate {
+            uri_template: "beans://bean/{id}".to_string(),
+            name: "Bean".to_string(),
+            description: Some("Full bean as JSON".to_string()),
+            mime_type: Some("application/json".to_string()),
+        },
+        ResourceTemplate {
+            uri_template: "beans://context/{id}".to_string(),
+            name: "Bean Context".to_string(),
+            description: Some(
+                "Contents of the files referenced in a bean's description".to_string(),
+            ),
+            mime_type: Some("text/markdown".to_string()),
+        },
+        ResourceTemplate {
+            uri_template: "beans://logs/{id}".to_string(),
+            name: "Bean Agent Log".to_string(),
+            description: Some("Latest agent log for a bean".to_string()),
+            mime_type: Some("text/plain".to_string()),
+        },
+    ]
+}
+
+/// Whether `uri` names a resource this server can serve (the bean it refers
+/// to may not exist).
+pub fn is_known_uri(uri: &str) -> bool {
+    matches!(uri, "beans://status" | "beans://rules")
+        || ["beans://bean/", "beans://context/", "beans://logs/"]
+            .iter()
+            .any(|prefix| uri.strip_prefix(prefix).is_some_and(|id| !id.is_empty()))
+}
+
 /// Handle a resource read request.
 pub fn handle_resource_read(uri: &str, beans_dir: &Path) -> Result<Vec<ResourceContent>> {
     if uri == "beans://status" {
@@ -45,6 +80,16 @@ pub fn handle_resource_read(uri: &str, beans_dir: &Path) -> Result<Vec<ResourceC
         return read_bean_resource(id, beans_dir);
     }
 
+    // beans://context/{id}
+    if let Some(id) = uri.strip_prefix("beans://context/") {
+        return read_context_resource(id, beans_dir);
+    }
+
+    // beans://logs/{id}
+    if let Some(id) = uri.strip_prefix("beans://logs/") {
+        return read_logs_resource(id);
+    }
+
     anyhow::bail!("Unknown resource URI: {}", uri)
 }
 
@@ -56,11 +101,16 @@ fn read_status_resource(beans_dir: &Path) -> Result<Vec<ResourceContent>> {
     let mut goals = 0u32;
     let mut blocked = 0u32;
     let mut closed = 0u32;
+    let mut in_review = 0u32;
+    let mut cancelled = 0u32;
 
     for entry in &index.beans {
         match entry.status {
             crate::bean::Status::InProgress => claimed += 1,
+            crate::bean::Status::InReview => in_review += 1,
+            crate::bean::Status::Blocked => blocked += 1,
             crate::bean::Status::Closed => closed += 1,
+            crate::bean::Status::Cancelled => cancelled += 1,
             crate::bean::Status::Open => {
                 if entry.has_verify {
                     // Check if blocked
@@ -86,10 +136,12 @@ fn read_status_resource(beans_dir: &Path) -> Result<Vec<ResourceContent>> {
     let text = serde_json::to_string_pretty(&json!({
         "total": index.beans.len(),
         "claimed": claimed,
+        "in_review": in_review,
         "ready": ready,
         "goals": goals,
         "blocked": blocked,
         "closed": closed,
+        "cancelled": cancelled,
     }))?;
 
     Ok(vec![ResourceContent {
@@ -135,3 +187,42 @@ fn read_bean_resource(id: &str, beans_dir: &Path) -> Result<Vec<ResourceContent>
         text,
     }])
 }
+
+fn read_context_resource(id: &str, beans_dir: &Path) -> Result<Vec<ResourceContent>> {
+    crate::util::validate_bean_id(id)?;
+    let bean_path = find_bean_file(beans_dir, id)?;
+    let bean = Bean::from_file(&bean_path)?;
+
+    let project_dir = beans_dir
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine project root"))?;
+
+    let description = bean.description.as_deref().unwrap_or("");
+    let paths = crate::ctx_assembler::extract_paths(description);
+    let text = if paths.is_empty() {
+        format!("Bean {}: no file paths found in description", id)
+    } else {
+        crate::ctx_assembler::assemble_context(paths, project_dir)
+            .context("Failed to assemble context")?
+    };
+
+    Ok(vec![ResourceContent {
+        uri: format!("beans://context/{}", id),
+        mime_type: Some("text/markdown".to_string()),
+        text,
+    }])
+}
+
+fn read_logs_resource(id: &str) -> Result<Vec<ResourceContent>> {
+    crate::util::validate_bean_id(id)?;
+    let path = crate::commands::logs::find_log_path(id)?
+        .ok_or_else(|| anyhow::anyhow!("No logs for bean {}", id))?;
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    Ok(vec![ResourceContent {
+        uri: format!("beans://logs/{}", id),
+        mime_type: Some("text/plain".to_string()),
+        text,
+    }])
+}
@@ -1,32 +1,117 @@
 //! MCP stdio server: reads JSON-RPC 2.0 from stdin, writes responses to stdout.
+//!
+//! Clients can subscribe to resources. A watcher thread re-reads subscribed
+//! resources every second and sends `notifications/resources/updated` when
+//! their content changes.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::io::{self, BufRead, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
+use serde::Serialize;
 use serde_json::{json, Value};
 
+use crate::mcp::prompts;
 use crate::mcp::protocol::{
-    JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND, PARSE_ERROR,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, INVALID_PARAMS, METHOD_NOT_FOUND,
+    PARSE_ERROR,
 };
 use crate::mcp::resources;
 use crate::mcp::tools;
 
 /// Protocol version we support.
-const PROTOCOL_VERSION: &str = "2024-11-05";
+pub(crate) const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// How often subscribed resources are checked for changes.
+pub(crate) const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Per-client state: the resources a client subscribed to, each with a
+/// fingerprint of its last-seen content.
+///
+/// The stdio transport has a single session; the HTTP transport keeps one per
+/// `Mcp-Session-Id`.
+#[derive(Debug, Default)]
+pub struct Session {
+    subscriptions: Mutex<HashMap<String, Option<u64>>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-read subscribed resources and return the URIs whose content changed
+    /// since the last check (or since the subscription).
+    pub fn changed_resources(&self, beans_dir: &Path) -> Vec<String> {
+        let uris: Vec<String> = lock(&self.subscriptions).keys().cloned().collect();
+        let mut changed = Vec::new();
+        for uri in uris {
+            let current = fingerprint(&uri, beans_dir);
+            if let Some(seen) = lock(&self.subscriptions).get_mut(&uri) {
+                if *seen != current {
+                    *seen = current;
+                    changed.push(uri);
+                }
+            }
+        }
+        changed
+    }
+
+    fn subscribe(&self, uri: &str, beans_dir: &Path) {
+        lock(&self.subscriptions).insert(uri.to_string(), fingerprint(uri, beans_dir));
+    }
+
+    fn unsubscribe(&self, uri: &str) {
+        lock(&self.subscriptions).remove(uri);
+    }
+}
 
 /// Run the MCP server loop on stdin/stdout.
+pub fn run(beans_dir: &Path) -> anyhow::Result<()> {
+    serve(beans_dir, io::stdin().lock(), io::stdout())
+}
+
+/// Run the MCP server loop over any line reader and writer.
 ///
-/// Reads newline-delimited JSON-RPC 2.0 messages from stdin,
+/// Reads newline-delimited JSON-RPC 2.0 messages from `reader`,
 /// dispatches to the appropriate handler, and writes responses
-/// to stdout. Notifications (no `id`) do not get responses.
-pub fn run(beans_dir: &Path) -> anyhow::Result<()> {
-    let stdin = io::stdin();
-    let stdout = io::stdout();
-    let reader = stdin.lock();
-    let mut writer = stdout.lock();
+/// to `writer`. Notifications (no `id`) do not get responses.
+/// Returns when `reader` reaches end of input.
+pub fn serve(
+    beans_dir: &Path,
+    reader: impl BufRead,
+    writer: impl Write + Send,
+) -> anyhow::Result<()> {
+    let writer = Mutex::new(writer);
+    let session = Session::new();
+    let done = AtomicBool::new(false);
 
     eprintln!("beans MCP server started (protocol {})", PROTOCOL_VERSION);
 
+    std::thread::scope(|scope| {
+        scope.spawn(|| watch_subscriptions(beans_dir, &session, &writer, &done));
+
+        let result = read_requests(beans_dir, reader, &writer, &session);
+        done.store(true, Ordering::SeqCst);
+        result
+    })?;
+
+    eprintln!("beans MCP server shutting down");
+    Ok(())
+}
+
+/// Read and answer requests until end of input.
+fn read_requests(
+    beans_dir: &Path,
+    reader: impl BufRead,
+    writer: &Mutex<impl Write>,
+    session: &Session,
+) -> anyhow::Result<()> {
     for line in reader.lines() {
         let line = match line {
             Ok(l) => l,
@@ -47,27 +132,80 @@ pub fn run(beans_dir: &Path) -> anyhow::Result<()> {
                 // Parse error — respond with error if we can extract an id
                 let error_response =
                     JsonRpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e));
-                write_response(&mut writer, &error_response)?;
+                write_message(writer, &error_response)?;
                 continue;
             }
         };
 
-        // Notifications (no id) don't get responses
-        let id = match request.id {
-            Some(id) => id,
-            None => {
-                // Handle notification silently
-                handle_notification(&request.method);
-                continue;
+        if let Some(response) = handle_request(request, beans_dir, session) {
+            write_message(writer, &response)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Answer one JSON-RPC message for `session`.
+///
+/// Returns `None` for notifications (no `id`), which get no response.
+pub fn handle_request(
+    request: JsonRpcRequest,
+    beans_dir: &Path,
+    session: &Session,
+) -> Option<JsonRpcResponse> {
+    let id = match request.id {
+        Some(id) => id,
+        None => {
+            handle_notification(&request.method);
+            return None;
+        }
+    };
+
+    Some(match request.method.as_str() {
+        "resources/subscribe" => {
+            handle_resources_subscribe(&request.params, id, beans_dir, session)
+        }
+        "resources/unsubscribe" => handle_resources_unsubscribe(&request.params, id, session),
+        method => dispatch(method, &request.params, id, beans_dir),
+    })
+}
+
+/// The notification sent when a subscribed resource changes.
+pub fn resource_updated(uri: &str) -> JsonRpcNotification {
+    JsonRpcNotification::new("notifications/resources/updated", json!({ "uri": uri }))
+}
+
+/// Poll subscribed resources and notify the client when one changes.
+fn watch_subscriptions(
+    beans_dir: &Path,
+    session: &Session,
+    writer: &Mutex<impl Write>,
+    done: &AtomicBool,
+) {
+    while !done.load(Ordering::SeqCst) {
+        std::thread::sleep(SUBSCRIPTION_POLL_INTERVAL);
+
+        for uri in session.changed_resources(beans_dir) {
+            if let Err(e) = write_message(writer, &resource_updated(&uri)) {
+                eprintln!("Failed to send resource update: {}", e);
             }
-        };
+        }
+    }
+}
 
-        let response = dispatch(&request.method, &request.params, id.clone(), beans_dir);
-        write_response(&mut writer, &response)?;
+/// Hash of a resource's current content, or `None` if it cannot be read
+/// (e.g. the bean was deleted or archived).
+fn fingerprint(uri: &str, beans_dir: &Path) -> Option<u64> {
+    let contents = resources::handle_resource_read(uri, beans_dir).ok()?;
+    let mut hasher = DefaultHasher::new();
+    for content in &contents {
+        content.text.hash(&mut hasher);
     }
+    Some(hasher.finish())
+}
 
-    eprintln!("beans MCP server shutting down");
-    Ok(())
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
 }
 
 /// Dispatch a JSON-RPC request to the appropriate handler.
@@ -77,7 +215,10 @@ fn dispatch(method: &str, params: &Option<Value>, id: Value, beans_dir: &Path) -
         "tools/list" => handle_tools_list(id),
         "tools/call" => handle_tools_call(params, id, beans_dir),
         "resources/list" => handle_resources_list(id),
+        "resources/templates/list" => handle_resource_templates_list(id),
         "resources/read" => handle_resources_read(params, id, beans_dir),
+        "prompts/list" => handle_prompts_list(id),
+        "prompts/get" => handle_prompts_get(params, id, beans_dir),
         "ping" => JsonRpcResponse::success(id, json!({})),
         _ => JsonRpcResponse::error(id, METHOD_NOT_FOUND, format!("Unknown method: {}", method)),
     }
@@ -98,9 +239,10 @@ fn handle_notification(method: &str) {
     }
 }
 
-/// Write a JSON-RPC response as a single line to stdout.
-fn write_response(writer: &mut impl Write, response: &JsonRpcResponse) -> anyhow::Result<()> {
-    let json = serde_json::to_string(response)?;
+/// Write a JSON-RPC message as a single line to stdout.
+fn write_message(writer: &Mutex<impl Write>, message: &impl Serialize) -> anyhow::Result<()> {
+    let json = serde_json::to_string(message)?;
+    let mut writer = lock(writer);
     writeln!(writer, "{}", json)?;
     writer.flush()?;
     Ok(())
@@ -126,7 +268,8 @@ fn handle_initialize(params: &Option<Value>, id: Value) -> JsonRpcResponse {
             "protocolVersion": PROTOCOL_VERSION,
             "capabilities": {
                 "tools": {},
-                "resources": {}
+                "resources": { "subscribe": true },
+                "prompts": {}
             },
             "serverInfo": {
                 "name": "beans",
@@ -228,6 +371,89 @@ fn handle_resources_read(params: &Option<Value>, id: Value, beans_dir: &Path) ->
                 .collect();
             JsonRpcResponse::success(id, json!({ "contents": contents_json }))
         }
-        Err(e) => JsonRpcResponse::error(id, INTERNAL_ERROR, format!("Resource error: {}", e)),
+        Err(e) => JsonRpcResponse::from_error(id, "Resource error", &e),
+    }
+}
+
+fn handle_resource_templates_list(id: Value) -> JsonRpcResponse {
+    let templates = resources::resource_templates();
+    JsonRpcResponse::success(id, json!({ "resourceTemplates": templates }))
+}
+
+fn handle_resources_subscribe(
+    params: &Option<Value>,
+    id: Value,
+    beans_dir: &Path,
+    session: &Session,
+) -> JsonRpcResponse {
+    let uri = match params
+        .as_ref()
+        .and_then(|p| p.get("uri"))
+        .and_then(|u| u.as_str())
+    {
+        Some(u) => u,
+        None => {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing resource URI");
+        }
+    };
+
+    // Beans that don't exist yet can be subscribed to; unknown schemes can't
+    if !resources::is_known_uri(uri) {
+        return JsonRpcResponse::error(
+            id,
+            INVALID_PARAMS,
+            format!("Unknown resource URI: {}", uri),
+        );
+    }
+
+    eprintln!("Subscribed: {}", uri);
+    session.subscribe(uri, beans_dir);
+    JsonRpcResponse::success(id, json!({}))
+}
+
+fn handle_resources_unsubscribe(
+    params: &Option<Value>,
+    id: Value,
+    session: &Session,
+) -> JsonRpcResponse {
+    let uri = match params
+        .as_ref()
+        .and_then(|p| p.get("uri"))
+        .and_then(|u| u.as_str())
+    {
+        Some(u) => u,
+        None => {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing resource URI");
+        }
+    };
+
+    session.unsubscribe(uri);
+    JsonRpcResponse::success(id, json!({}))
+}
+
+fn handle_prompts_list(id: Value) -> JsonRpcResponse {
+    let prompts = prompts::prompt_definitions();
+    JsonRpcResponse::success(id, json!({ "prompts": prompts }))
+}
+
+fn handle_prompts_get(params: &Option<Value>, id: Value, beans_dir: &Path) -> JsonRpcResponse {
+    let params = match params {
+        Some(p) => p,
+        None => {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing params");
+        }
+    };
+
+    let name = match params.get("name").and_then(|n| n.as_str()) {
+        Some(n) => n,
+        None => {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing prompt name");
+        }
+    };
+
+    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+    match prompts::handle_prompt_get(name, &args, beans_dir) {
+        Ok(result) => JsonRpcResponse::success(id, result),
+        Err(e) => JsonRpcResponse::error(id, INVALID_PARAMS, format!("Prompt error: {}", e)),
     }
 }
@@ -1,35 +1,48 @@
 //! MCP tool definitions and handlers.
 //!
 //! Each tool maps to a beans operation. Handlers work directly with
-//! Bean/Index types to avoid stdout pollution from CLI commands.
+//! Bean/Index types, or call the non-printing core behind a CLI command
+//! (`update_bean`, `claim_bean`, `close_bean`, ...), to avoid stdout pollution from
+//! CLI commands. `run_bean` and `plan_bean` start `bn` in the background and
+//! return immediately.
 
 use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use chrono::Utc;
 use serde_json::{json, Value};
 
-use crate::bean::{Bean, Status};
+use crate::bean::{AttemptOutcome, Bean, Status};
 use crate::blocking::check_blocked;
+use crate::commands::update::{update_bean, BeanUpdate};
+use crate::commands::{adopt, claim, close, dep, fact, logs, recall, trace};
 use crate::config::Config;
 use crate::discovery::find_bean_file;
-use crate::index::{Index, IndexEntry};
-use crate::mcp::protocol::ToolDefinition;
+use crate::error::BeansError;
+use crate::events::{self, Action};
+use crate::filter::Filter;
+use crate::index::{Index, IndexEntry, LockedIndex};
+use crate::mcp::protocol::{error_data, ToolDefinition};
 use crate::util::{natural_cmp, title_to_slug};
 
+/// How long a tool that writes waits for another writer to release the
+/// index lock; long enough for a slow verify command.
+const MUTATION_LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+
 /// Return all MCP tool definitions.
 pub fn tool_definitions() -> Vec<ToolDefinition> {
     vec![
         ToolDefinition {
             name: "list_beans".to_string(),
-            description: "List beans with optional status and priority filters".to_string(),
+            description: "List beans with optional status, priority and filter-expression filters".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "status": {
                         "type": "string",
-                        "enum": ["open", "in_progress", "closed"],
-                        "description": "Filter by status"
+                        "enum": ["open", "in_progress", "in_review", "blocked", "closed", "cancelled"],
+                        "description": "Filter by status (closed and cancelled beans are excluded unless requested)"
                     },
                     "priority": {
                         "type": "integer",
@@ -40,6 +53,10 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
                     "parent": {
                         "type": "string",
                         "description": "Filter by parent bean ID"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "Filter expression, e.g. `priority<=1 and label:backend and not claimed and updated<7d`. Supports field:value, != ~ < <= > >=, and/or/not, parentheses and @view names from config"
                     }
                 }
             }),
@@ -119,6 +136,10 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
                     "by": {
                         "type": "string",
                         "description": "Who is claiming (agent name or user)"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Skip the verify-must-fail check on claim"
                     }
                 },
                 "required": ["id"]
@@ -196,6 +217,280 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
                 }
             }),
         },
+        ToolDefinition {
+            name: "update_bean".to_string(),
+            description: "Update bean fields. Notes are appended with a timestamp, never replaced.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID to update"
+                    },
+                    "title": {
+                        "type": "string",
+                        "description": "New title"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "New description (replaces the existing one)"
+                    },
+                    "acceptance": {
+                        "type": "string",
+                        "description": "New acceptance criteria"
+                    },
+                    "note": {
+                        "type": "string",
+                        "description": "Note to append (progress, findings, what was tried)"
+                    },
+                    "design": {
+                        "type": "string",
+                        "description": "New design notes"
+                    },
+                    "status": {
+                        "type": "string",
+                        "enum": ["open", "in_progress", "in_review", "blocked", "closed", "cancelled"],
+                        "description": "New status"
+                    },
+                    "blocked_reason": {
+                        "type": "string",
+                        "description": "Why the bean is blocked (only with status blocked)"
+                    },
+                    "priority": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": 4,
+                        "description": "New priority 0-4 (P0 highest)"
+                    },
+                    "assignee": {
+                        "type": "string",
+                        "description": "New assignee"
+                    },
+                    "add_label": {
+                        "type": "string",
+                        "description": "Label to add"
+                    },
+                    "remove_label": {
+                        "type": "string",
+                        "description": "Label to remove"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "add_dependency".to_string(),
+            description: "Make a bean depend on another (rejects cycles and self-dependencies)".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID that gains the dependency"
+                    },
+                    "depends_on": {
+                        "type": "string",
+                        "description": "Bean ID it should wait on"
+                    }
+                },
+                "required": ["id", "depends_on"]
+            }),
+        },
+        ToolDefinition {
+            name: "remove_dependency".to_string(),
+            description: "Remove a dependency between two beans".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID that loses the dependency"
+                    },
+                    "depends_on": {
+                        "type": "string",
+                        "description": "Bean ID to stop waiting on"
+                    }
+                },
+                "required": ["id", "depends_on"]
+            }),
+        },
+        ToolDefinition {
+            name: "list_dependencies".to_string(),
+            description: "List a bean's dependencies and the beans that depend on it".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "recall".to_string(),
+            description: "Full-text search over beans and facts (title, description, notes, close reason, paths, labels, attempt notes), ranked by relevance".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search words (all must match). Supports \"exact phrases\", prefix*, field:word for title/description/notes/reason/path/attempts, and status:, label:, type: filters"
+                    },
+                    "all": {
+                        "type": "boolean",
+                        "description": "Include closed and archived beans",
+                        "default": false
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "trace_bean".to_string(),
+            description: "Trace a bean's lineage: parent chain, children, dependencies, dependents, artifacts, and attempts".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "create_fact".to_string(),
+            description: "Record a verified project fact. The verify command proves the fact is still true.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "title": {
+                        "type": "string",
+                        "description": "The fact, stated in one line"
+                    },
+                    "verify": {
+                        "type": "string",
+                        "description": "Shell command that exits 0 while the fact holds"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "Supporting detail"
+                    },
+                    "paths": {
+                        "type": "string",
+                        "description": "Comma-separated file paths the fact is about"
+                    },
+                    "ttl_days": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Days until the fact goes stale (default 30)"
+                    },
+                    "pass_ok": {
+                        "type": "boolean",
+                        "description": "Skip the check that the verify command fails before the fact is recorded",
+                        "default": false
+                    }
+                },
+                "required": ["title", "verify"]
+            }),
+        },
+        ToolDefinition {
+            name: "verify_facts".to_string(),
+            description: "Re-run every fact's verify command and report verified, stale, failing, and suspect facts".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDefinition {
+            name: "fail_bean".to_string(),
+            description: "Mark the current attempt on a bean as failed. The claim is released and the bean stays open for retry.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID"
+                    },
+                    "reason": {
+                        "type": "string",
+                        "description": "Why the attempt failed (shown to the next agent)"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "bean_logs".to_string(),
+            description: "Show the tail of the latest agent log for a bean".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID"
+                    },
+                    "lines": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Number of lines from the end of the log",
+                        "default": 100
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "adopt_beans".to_string(),
+            description: "Move existing beans under a parent. Children get new IDs ({parent}.N) and dependency references are rewritten.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "parent": {
+                        "type": "string",
+                        "description": "Parent bean ID"
+                    },
+                    "children": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1,
+                        "description": "Bean IDs to adopt"
+                    }
+                },
+                "required": ["parent", "children"]
+            }),
+        },
+        ToolDefinition {
+            name: "run_bean".to_string(),
+            description: "Start `bn run` for a bean in the background. Returns immediately with the process ID and log path; use bean_logs to follow progress.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID to run"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
+        ToolDefinition {
+            name: "plan_bean".to_string(),
+            description: "Start `bn plan --auto` for a bean in the background to split it into children. Returns immediately with the process ID and log path.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Bean ID to plan"
+                    }
+                },
+                "required": ["id"]
+            }),
+        },
     ]
 }
 
@@ -205,7 +500,46 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
 
 /// Dispatch a tool call to the appropriate handler.
 pub fn handle_tool_call(name: &str, args: &Value, beans_dir: &Path) -> Value {
-    let result = match name {
+    let result = if mutates(name) {
+        // Writers hold the index lock, like REST mutations, so concurrent
+        // HTTP clients can't both take the same next ID
+        LockedIndex::acquire_with_timeout(beans_dir, MUTATION_LOCK_TIMEOUT)
+            .and_then(|_lock| call_tool(name, args, beans_dir))
+    } else {
+        call_tool(name, args, beans_dir)
+    };
+
+    match result {
+        Ok(text) => json!({
+            "content": [{ "type": "text", "text": text }]
+        }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": format!("Error: {}", e) }],
+            "isError": true,
+            "_meta": { "error": error_data(&e) }
+        }),
+    }
+}
+
+/// Whether a tool writes to `.beans/`.
+fn mutates(name: &str) -> bool {
+    matches!(
+        name,
+        "create_bean"
+            | "claim_bean"
+            | "close_bean"
+            | "update_bean"
+            | "add_dependency"
+            | "remove_dependency"
+            | "create_fact"
+            | "verify_facts"
+            | "fail_bean"
+            | "adopt_beans"
+    )
+}
+
+fn call_tool(name: &str, args: &Value, beans_dir: &Path) -> Result<String> {
+    match name {
         "list_beans" => handle_list_beans(args, beans_dir),
         "show_bean" => handle_show_bean(args, beans_dir),
         "ready_beans" => handle_ready_beans(beans_dir),
@@ -216,17 +550,20 @@ pub fn handle_tool_call(name: &str, args: &Value, beans_dir: &Path) -> Value {
         "context_bean" => handle_context_bean(args, beans_dir),
         "status" => handle_status(beans_dir),
         "tree" => handle_tree(args, beans_dir),
+        "update_bean" => handle_update_bean(args, beans_dir),
+        "add_dependency" => handle_add_dependency(args, beans_dir),
+        "remove_dependency" => handle_remove_dependency(args, beans_dir),
+        "list_dependencies" => handle_list_dependencies(args, beans_dir),
+        "recall" => handle_recall(args, beans_dir),
+        "trace_bean" => handle_trace_bean(args, beans_dir),
+        "create_fact" => handle_create_fact(args, beans_dir),
+        "verify_facts" => handle_verify_facts(beans_dir),
+        "fail_bean" => handle_fail_bean(args, beans_dir),
+        "bean_logs" => handle_bean_logs(args, beans_dir),
+        "adopt_beans" => handle_adopt_beans(args, beans_dir),
+        "run_bean" => handle_background(args, beans_dir, "run"),
+        "plan_bean" => handle_background(args, beans_dir, "plan"),
         _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
-    };
-
-    match result {
-        Ok(text) => json!({
-            "content": [{ "type": "text", "text": text }]
-        }),
-        Err(e) => json!({
-            "content": [{ "type": "text", "text": format!("Error: {}", e) }],
-            "isError": true
-        }),
     }
 }
 
@@ -249,6 +586,13 @@ fn handle_list_beans(args: &Value, beans_dir: &Path) -> Result<String> {
 
     let parent_filter = args.get("parent").and_then(|v| v.as_str());
 
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .map(|q| Filter::load(beans_dir, q))
+        .transpose()?;
+    let query_decides_status = query.as_ref().is_some_and(Filter::decides_status);
+
     let filtered: Vec<&IndexEntry> = index
         .beans
         .iter()
@@ -257,8 +601,8 @@ fn handle_list_beans(args: &Value, beans_dir: &Path) -> Result<String> {
                 if entry.status != status {
                     return false;
                 }
-            } else if entry.status == Status::Closed {
-                // Exclude closed by default
+            } else if entry.status.is_terminal() && !query_decides_status {
+                // Exclude closed and cancelled by default
                 return false;
             }
             if let Some(priority) = priority_filter {
@@ -271,6 +615,11 @@ fn handle_list_beans(args: &Value, beans_dir: &Path) -> Result<String> {
                     return false;
                 }
             }
+            if let Some(ref query) = query {
+                if !query.matches(entry, beans_dir) {
+                    return false;
+                }
+            }
             true
         })
         .collect();
@@ -286,6 +635,7 @@ fn handle_list_beans(args: &Value, beans_dir: &Path) -> Result<String> {
                 "parent": e.parent,
                 "has_verify": e.has_verify,
                 "claimed_by": e.claimed_by,
+                "blocked_reason": e.blocked_reason,
             })
         })
         .collect();
@@ -397,6 +747,7 @@ fn handle_create_bean(args: &Value, beans_dir: &Path) -> Result<String> {
     // Write bean file
     let bean_path = beans_dir.join(format!("{}-{}.md", bean_id, slug));
     bean.to_file(&bean_path)?;
+    events::record(beans_dir, Action::Create, &bean_id, None, Some(&bean));
 
     // Rebuild index
     let index = Index::build(beans_dir)?;
@@ -411,32 +762,18 @@ fn handle_claim_bean(args: &Value, beans_dir: &Path) -> Result<String> {
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing required parameter: id"))?;
     let by = args.get("by").and_then(|v| v.as_str());
+    let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
 
     crate::util::validate_bean_id(id)?;
-    let bean_path = find_bean_file(beans_dir, id)?;
-    let mut bean = Bean::from_file(&bean_path)?;
-
-    if bean.status != Status::Open {
-        anyhow::bail!(
-            "Bean {} is {} — only open beans can be claimed",
-            id,
-            bean.status
-        );
-    }
-
-    let now = Utc::now();
-    bean.status = Status::InProgress;
-    bean.claimed_by = by.map(|s| s.to_string());
-    bean.claimed_at = Some(now);
-    bean.updated_at = now;
-
-    bean.to_file(&bean_path)?;
-
-    // Rebuild index
-    let index = Index::build(beans_dir)?;
-    index.save(beans_dir)?;
-
-    let claimer = by.unwrap_or("anonymous");
+    let bean = claim::claim_bean(
+        beans_dir,
+        id,
+        by.map(str::to_string),
+        force,
+        &mut std::io::stderr(),
+    )?;
+
+    let claimer = bean.claimed_by.as_deref().unwrap_or("anonymous");
     Ok(format!(
         "Claimed bean {}: {} (by {})",
         id, bean.title, claimer
@@ -452,97 +789,61 @@ fn handle_close_bean(args: &Value, beans_dir: &Path) -> Result<String> {
     let reason = args.get("reason").and_then(|v| v.as_str());
 
     crate::util::validate_bean_id(id)?;
-    let bean_path = find_bean_file(beans_dir, id)?;
-    let mut bean = Bean::from_file(&bean_path)?;
-
-    // Run verify if configured and not forced
-    if let Some(ref verify_cmd) = bean.verify {
-        if !force {
-            let project_root = beans_dir
-                .parent()
-                .ok_or_else(|| anyhow::anyhow!("Cannot determine project root"))?;
-
-            let output = std::process::Command::new("sh")
-                .args(["-c", verify_cmd])
-                .current_dir(project_root)
-                .output()
-                .with_context(|| format!("Failed to execute verify: {}", verify_cmd))?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let combined = format!("{}{}", stdout, stderr);
-                let snippet = if combined.len() > 2000 {
-                    format!("...{}", &combined[combined.len() - 2000..])
-                } else {
-                    combined.to_string()
-                };
-
-                bean.attempts += 1;
-                bean.updated_at = Utc::now();
-                bean.to_file(&bean_path)?;
-
-                // Rebuild index to reflect attempt count
-                let index = Index::build(beans_dir)?;
-                index.save(beans_dir)?;
-
-                anyhow::bail!(
-                    "Verify failed for bean {} (attempt {})\nCommand: {}\nOutput:\n{}",
-                    id,
-                    bean.attempts,
-                    verify_cmd,
-                    snippet.trim()
-                );
-            }
-        }
-    }
-
-    // Close the bean
-    let now = Utc::now();
-    bean.status = Status::Closed;
-    bean.closed_at = Some(now);
-    bean.close_reason = reason.map(|s| s.to_string());
-    bean.updated_at = now;
-
-    bean.to_file(&bean_path)?;
-
-    // Archive the bean
-    let slug = bean
-        .slug
-        .clone()
-        .unwrap_or_else(|| title_to_slug(&bean.title));
-    let ext = bean_path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("md");
-    let today = chrono::Local::now().naive_local().date();
-    let archive_path = crate::discovery::archive_path_for_bean(beans_dir, id, &slug, ext, today);
-
-    if let Some(parent) = archive_path.parent() {
-        std::fs::create_dir_all(parent)?;
+    let outcome = close::close_bean(
+        beans_dir,
+        id,
+        reason.map(str::to_string),
+        force,
+        false,
+        &mut std::io::sink(),
+        &mut std::io::stderr(),
+    )?;
+    // Worktree cleanup can remove the beans dir
+    if beans_dir.exists() {
+        let index = Index::build(beans_dir)?;
+        index.save(beans_dir)?;
     }
-    std::fs::rename(&bean_path, &archive_path)?;
 
-    bean.is_archived = true;
-    bean.to_file(&archive_path)?;
-
-    // Rebuild index
-    let index = Index::build(beans_dir)?;
-    index.save(beans_dir)?;
-
-    // Check auto-close parent
-    if let Some(parent_id) = &bean.parent {
-        let auto_close = Config::load(beans_dir)
-            .map(|c| c.auto_close_parent)
-            .unwrap_or(true);
-        if auto_close {
-            if let Ok(true) = all_children_closed(beans_dir, parent_id) {
-                let _ = auto_close_parent(beans_dir, parent_id);
+    match outcome {
+        close::CloseOutcome::Closed { bean } => Ok(format!("Closed bean {}: {}", id, bean.title)),
+        close::CloseOutcome::RejectedByHook => {
+            Err(BeansError::HookRejected { hook: "pre-close" }.into())
+        }
+        close::CloseOutcome::VerifyFailed {
+            exit_code, output, ..
+        } => {
+            Err(BeansError::VerifyFailed {
+                id: id.to_string(),
+                exit_code,
+                output: truncate_str(&output, 2000).trim().to_string(),
             }
+            .into())
+        }
+        close::CloseOutcome::CircuitBreakerTripped {
+            subtree_attempts,
+            max_loops,
+            root_id,
+        } => anyhow::bail!(
+            "Verify failed for bean {}; circuit breaker tripped ({} attempts in subtree {}, max_loops {}), escalated to P0",
+            id,
+            subtree_attempts,
+            root_id,
+            max_loops
+        ),
+        close::CloseOutcome::MergeConflict { files } => Err(BeansError::Conflict {
+            id: id.to_string(),
+            files,
+        }
+        .into()),
+        close::CloseOutcome::VerifyTampered { findings } => Err(BeansError::VerifyTampered {
+            id: id.to_string(),
+            findings,
+        }
+        .into()),
+        close::CloseOutcome::NeedsReview => {
+            anyhow::bail!("Bean {} is a feature; close it with bn close", id)
         }
     }
-
-    Ok(format!("Closed bean {}: {}", id, bean.title))
 }
 
 fn handle_verify_bean(args: &Value, beans_dir: &Path) -> Result<String> {
@@ -563,10 +864,9 @@ fn handle_verify_bean(args: &Value, beans_dir: &Path) -> Result<String> {
     let project_root = beans_dir
         .parent()
         .ok_or_else(|| anyhow::anyhow!("Cannot determine project root"))?;
+    crate::hooks::check_bean_verify(project_root, id, &verify_cmd)?;
 
-    let output = std::process::Command::new("sh")
-        .args(["-c", &verify_cmd])
-        .current_dir(project_root)
+    let output = crate::sandbox::shell(project_root, &verify_cmd)
         .output()
         .with_context(|| format!("Failed to execute verify: {}", verify_cmd))?;
 
@@ -615,6 +915,7 @@ fn handle_status(beans_dir: &Path) -> Result<String> {
     let index = Index::load_or_rebuild(beans_dir)?;
 
     let mut claimed = Vec::new();
+    let mut in_review = Vec::new();
     let mut ready = Vec::new();
     let mut goals = Vec::new();
     let mut blocked: Vec<(&IndexEntry, String)> = Vec::new();
@@ -622,7 +923,8 @@ fn handle_status(beans_dir: &Path) -> Result<String> {
     for entry in &index.beans {
         match entry.status {
             Status::InProgress => claimed.push(entry),
-            Status::Open => {
+            Status::InReview => in_review.push(entry),
+            Status::Open | Status::Blocked => {
                 if let Some(reason) = check_blocked(entry, &index) {
                     blocked.push((entry, reason.to_string()));
                 } else if entry.has_verify {
@@ -631,7 +933,7 @@ fn handle_status(beans_dir: &Path) -> Result<String> {
                     goals.push(entry);
                 }
             }
-            Status::Closed => {}
+            Status::Closed | Status::Cancelled => {}
         }
     }
 
@@ -664,12 +966,13 @@ fn handle_status(beans_dir: &Path) -> Result<String> {
 
     serde_json::to_string_pretty(&json!({
         "claimed": format_entries(&claimed),
+        "in_review": format_entries(&in_review),
         "ready": format_entries(&ready),
         "goals": format_entries(&goals),
         "blocked": blocked_entries,
         "summary": format!(
-            "{} claimed, {} ready, {} goals, {} blocked",
-            claimed.len(), ready.len(), goals.len(), blocked.len()
+            "{} claimed, {} in review, {} ready, {} goals, {} blocked",
+            claimed.len(), in_review.len(), ready.len(), goals.len(), blocked.len()
         )
     }))
     .context("Failed to serialize status")
@@ -705,73 +1008,300 @@ fn handle_tree(args: &Value, beans_dir: &Path) -> Result<String> {
     }
 }
 
-// ---------------------------------------------------------------------------
-// Helper Functions
-// ---------------------------------------------------------------------------
+fn handle_update_bean(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
+
+    let priority = match args.get("priority").and_then(|v| v.as_u64()) {
+        Some(p) => Some(u8::try_from(p).map_err(|_| anyhow::anyhow!("Invalid priority: {}", p))?),
+        None => None,
+    };
+    let update = BeanUpdate {
+        title: optional_string(args, "title"),
+        description: optional_string(args, "description"),
+        acceptance: optional_string(args, "acceptance"),
+        notes: optional_string(args, "note"),
+        design: optional_string(args, "design"),
+        status: optional_string(args, "status"),
+        blocked_reason: optional_string(args, "blocked_reason"),
+        priority,
+        assignee: optional_string(args, "assignee"),
+        add_label: optional_string(args, "add_label"),
+        remove_label: optional_string(args, "remove_label"),
+    };
+
+    let bean = update_bean(beans_dir, id, update, &mut std::io::stderr())?;
+    Ok(format!("Updated bean {}: {}", id, bean.title))
+}
+
+fn handle_add_dependency(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    let depends_on = required_str(args, "depends_on")?;
+    crate::util::validate_bean_id(id)?;
+    crate::util::validate_bean_id(depends_on)?;
+
+    dep::add_dependency(beans_dir, id, depends_on)?;
+    Ok(format!("{} now depends on {}", id, depends_on))
+}
+
+fn handle_remove_dependency(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    let depends_on = required_str(args, "depends_on")?;
+    crate::util::validate_bean_id(id)?;
+    crate::util::validate_bean_id(depends_on)?;
+
+    dep::remove_dependency(beans_dir, id, depends_on)?;
+    Ok(format!("{} no longer depends on {}", id, depends_on))
+}
+
+fn handle_list_dependencies(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
 
-/// Check if all children of a parent bean are closed.
-fn all_children_closed(beans_dir: &Path, parent_id: &str) -> Result<bool> {
     let index = Index::load_or_rebuild(beans_dir)?;
-    let children: Vec<&IndexEntry> = index
+    let entry = index
         .beans
         .iter()
-        .filter(|e| e.parent.as_deref() == Some(parent_id))
+        .find(|e| e.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Bean {} not found", id))?;
+
+    let summary = |dep_id: &str| match index.beans.iter().find(|e| e.id == dep_id) {
+        Some(e) => json!({ "id": e.id, "title": e.title, "status": e.status }),
+        None => json!({ "id": dep_id, "title": null, "status": "not_found" }),
+    };
+
+    let dependencies: Vec<Value> = entry.dependencies.iter().map(|d| summary(d)).collect();
+    let dependents: Vec<Value> = index
+        .beans
+        .iter()
+        .filter(|e| e.dependencies.iter().any(|d| d == id))
+        .map(|e| summary(&e.id))
         .collect();
 
-    if children.is_empty() {
-        return Ok(false);
+    Ok(serde_json::to_string_pretty(&json!({
+        "id": id,
+        "dependencies": dependencies,
+        "dependents": dependents,
+    }))?)
+}
+
+fn handle_recall(args: &Value, beans_dir: &Path) -> Result<String> {
+    let query = required_str(args, "query")?;
+    let all = args.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let matches = recall::search_beans(beans_dir, query, all)?;
+    if matches.is_empty() {
+        return Ok(format!("No matches for \"{}\"", query));
     }
 
-    Ok(children.iter().all(|c| c.status == Status::Closed))
+    let results: Vec<Value> = matches
+        .iter()
+        .map(|(bean, score)| {
+            let failed_attempts: Vec<&str> = bean
+                .attempt_log
+                .iter()
+                .filter(|a| a.outcome == AttemptOutcome::Failed)
+                .filter_map(|a| a.notes.as_deref())
+                .collect();
+            json!({
+                "id": bean.id,
+                "title": bean.title,
+                "type": bean.bean_type,
+                "status": bean.status,
+                "score": score,
+                "close_reason": bean.close_reason,
+                "failed_attempts": failed_attempts,
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&results)?)
 }
 
-/// Auto-close a parent bean when all children are closed.
-fn auto_close_parent(beans_dir: &Path, parent_id: &str) -> Result<()> {
-    let bean_path = find_bean_file(beans_dir, parent_id)?;
-    let mut bean = Bean::from_file(&bean_path)?;
+fn handle_trace_bean(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
 
-    if bean.status == Status::Closed {
-        return Ok(());
-    }
+    let trace = trace::build_trace(id, beans_dir)?;
+    Ok(serde_json::to_string_pretty(&trace)?)
+}
 
-    let now = Utc::now();
-    bean.status = Status::Closed;
-    bean.closed_at = Some(now);
-    bean.close_reason = Some("All children closed".to_string());
-    bean.updated_at = now;
-    bean.to_file(&bean_path)?;
+fn handle_create_fact(args: &Value, beans_dir: &Path) -> Result<String> {
+    let title = required_str(args, "title")?;
+    let verify = required_str(args, "verify")?;
+    let ttl_days = args.get("ttl_days").and_then(|v| v.as_i64());
+    let pass_ok = args
+        .get("pass_ok")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let id = fact::cmd_fact(
+        beans_dir,
+        title.to_string(),
+        verify.to_string(),
+        optional_string(args, "description"),
+        optional_string(args, "paths"),
+        ttl_days,
+        pass_ok,
+    )?;
+    Ok(format!("Created fact {}: {}", id, title))
+}
 
-    // Archive
-    let slug = bean
-        .slug
-        .clone()
-        .unwrap_or_else(|| title_to_slug(&bean.title));
-    let ext = bean_path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("md");
-    let today = chrono::Local::now().naive_local().date();
-    let archive_path =
-        crate::discovery::archive_path_for_bean(beans_dir, parent_id, &slug, ext, today);
-    if let Some(parent) = archive_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    std::fs::rename(&bean_path, &archive_path)?;
-    bean.is_archived = true;
-    bean.to_file(&archive_path)?;
+fn handle_verify_facts(beans_dir: &Path) -> Result<String> {
+    let report = fact::verify_facts(beans_dir)?;
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+fn handle_fail_bean(args: &Value, beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
+
+    let bean = close::mark_failed(beans_dir, id, optional_string(args, "reason"))?;
 
     // Rebuild index
     let index = Index::build(beans_dir)?;
     index.save(beans_dir)?;
 
-    Ok(())
+    Ok(format!(
+        "Marked bean {} as failed (attempt #{}): {}. Bean remains open for retry.",
+        id,
+        bean.attempt_log.len(),
+        bean.title
+    ))
+}
+
+fn handle_bean_logs(args: &Value, _beans_dir: &Path) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
+    let lines = args.get("lines").and_then(|v| v.as_u64()).unwrap_or(100) as usize;
+
+    let path = logs::find_log_path(id)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "No logs for bean {}. Has it been dispatched with bn run?",
+            id
+        )
+    })?;
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let all: Vec<&str> = contents.lines().collect();
+    let tail = &all[all.len().saturating_sub(lines)..];
+    Ok(format!(
+        "{} (last {} of {} lines)\n\n{}",
+        path.display(),
+        tail.len(),
+        all.len(),
+        tail.join("\n")
+    ))
+}
+
+fn handle_adopt_beans(args: &Value, beans_dir: &Path) -> Result<String> {
+    let parent = required_str(args, "parent")?;
+    crate::util::validate_bean_id(parent)?;
+    let children: Vec<String> = args
+        .get("children")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Missing required parameter: children"))?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow::anyhow!("children must be bean ID strings"))
+        })
+        .collect::<Result<_>>()?;
+    if children.is_empty() {
+        anyhow::bail!("At least one child bean ID is required");
+    }
+    for child in &children {
+        crate::util::validate_bean_id(child)?;
+    }
+
+    let id_map = adopt::adopt_beans(beans_dir, parent, &children)?;
+    let lines: Vec<String> = children
+        .iter()
+        .filter_map(|old| id_map.get(old).map(|new| (old, new)))
+        .map(|(old, new)| format!("Adopted {} -> {} (under {})", old, new, parent))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Start `bn <command> <id>` as a detached background process.
+///
+/// MCP tool calls must return promptly, so long-running commands are launched
+/// with their output going to the bean's log file and only the handle (pid and
+/// log path) is returned.
+fn handle_background(args: &Value, beans_dir: &Path, command: &str) -> Result<String> {
+    let id = required_str(args, "id")?;
+    crate::util::validate_bean_id(id)?;
+
+    let bean_path = find_bean_file(beans_dir, id)?;
+    let bean = Bean::from_file(&bean_path)?;
+    if matches!(bean.status, Status::Closed | Status::Cancelled) {
+        anyhow::bail!("Bean {} is {} — nothing to {}", id, bean.status, command);
+    }
+
+    let project_root = beans_dir
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine project root"))?;
+    let exe = std::env::current_exe().context("Cannot locate the bn executable")?;
+
+    let mut cmd_args = vec![command, id];
+    if command == "plan" {
+        cmd_args.push("--auto");
+    }
+
+    let log_path = crate::spawner::build_log_path(id)?;
+    let log = std::fs::File::create(&log_path)
+        .with_context(|| format!("Failed to create log file {}", log_path.display()))?;
+    let mut child = std::process::Command::new(&exe)
+        .args(&cmd_args)
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()
+        .with_context(|| format!("Failed to start bn {}", cmd_args.join(" ")))?;
+    let pid = child.id();
+
+    // Reap the process when it exits so it does not linger as a zombie
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "id": id,
+        "command": format!("bn {}", cmd_args.join(" ")),
+        "pid": pid,
+        "log_path": log_path,
+    }))?)
+}
+
+// ---------------------------------------------------------------------------
+// Helper Functions
+// ---------------------------------------------------------------------------
+
+/// Read a required string argument.
+fn required_str<'a>(args: &'a Value, name: &str) -> Result<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required parameter: {}", name))
+}
+
+/// Read an optional string argument as an owned value.
+fn optional_string(args: &Value, name: &str) -> Option<String> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
 }
 
 fn status_icon(status: Status) -> &'static str {
     match status {
         Status::Open => "[ ]",
         Status::InProgress => "[-]",
+        Status::InReview => "[~]",
+        Status::Blocked => "[!]",
         Status::Closed => "[x]",
+        Status::Cancelled => "[/]",
     }
 }
 
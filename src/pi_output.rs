@@ -177,12 +177,14 @@ pub fn parse_agent_event(raw: &serde_json::Value) -> Option<AgentEvent> {
 
 /// Extract the most relevant file path from a tool call's arguments.
 ///
-/// For `Read`, `Write`, and `Edit` the `"path"` field is returned directly.
+/// For `Read`, `Write`, and `Edit` the `"path"` field (`"file_path"` for
+/// Claude Code) is returned directly.
 /// For `Bash` we scan the command string for tokens that look like file paths.
 pub fn extract_file_path(tool_name: &str, arguments: &serde_json::Value) -> Option<String> {
     match tool_name {
         "Read" | "Write" | "Edit" => arguments
             .get("path")
+            .or_else(|| arguments.get("file_path"))
             .and_then(|p| p.as_str())
             .map(|s| s.to_string()),
         "Bash" => {
@@ -420,6 +422,12 @@ mod tests {
         assert_eq!(extract_file_path("Edit", &args), Some("Cargo.toml".into()));
     }
 
+    #[test]
+    fn pi_output_extract_claude_file_path() {
+        let args = json!({ "file_path": "src/lib.rs", "old_string": "a" });
+        assert_eq!(extract_file_path("Edit", &args), Some("src/lib.rs".into()));
+    }
+
     #[test]
     fn pi_output_extract_bash_with_file() {
         let args = json!({ "command": "cat src/main.rs" });
@@ -25,7 +25,7 @@ use anyhow::Result;
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::bean::{AttemptOutcome, Bean, Status};
+use crate::bean::{AttemptOutcome, Bean, RunResult, Status};
 use crate::config::Config;
 use crate::ctx_assembler::{extract_paths, read_file};
 use crate::discovery::find_bean_file;
@@ -53,6 +53,8 @@ pub struct PromptOptions {
     pub instructions: Option<String>,
     /// Beans running concurrently that share files with this bean.
     pub concurrent_overlaps: Option<Vec<FileOverlap>>,
+    /// Failure summary of the previous attempt, when `bn run` retries a bean.
+    pub previous_failure: Option<String>,
 }
 
 /// Describes a concurrent bean that overlaps on files.
@@ -163,6 +165,17 @@ pub fn build_agent_prompt(bean: &Bean, options: &PromptOptions) -> Result<Prompt
     if bean.attempts > 0 {
         sections.push(format_previous_attempts(bean));
     }
+    if let Some(ref summary) = options.previous_failure {
+        // Failure summaries are also appended to the notes shown above
+        let in_notes = bean.attempts > 0
+            && bean
+                .notes
+                .as_deref()
+                .is_some_and(|notes| notes.contains(summary.trim()));
+        if !in_notes {
+            sections.push(format!("# Last Failure\n\n{}", summary.trim()));
+        }
+    }
 
     // 10. Approach
     sections.push(format_approach(&bean.id));
@@ -452,6 +465,19 @@ fn format_previous_attempts(bean: &Bean) -> String {
         }
     }
 
+    // Structured results of the last verify run, if it failed
+    if let Some(tests) = bean
+        .history
+        .last()
+        .filter(|r| r.result != RunResult::Pass)
+        .and_then(|r| r.tests.as_ref())
+    {
+        section.push_str(&format!("\n\nLast verify run: {}", tests));
+        if let Some(ref message) = tests.message {
+            section.push_str(&format!("\nFirst failure:\n```\n{}\n```", message));
+        }
+    }
+
     section.push_str(
         "\n\nIMPORTANT: Do NOT repeat the same approach. \
          The notes above explain what was tried.\n\
@@ -609,7 +635,7 @@ fn load_bean(beans_dir: &Path, id: &str) -> Option<Bean> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bean::{AttemptOutcome, AttemptRecord, Bean};
+    use crate::bean::{AttemptOutcome, AttemptRecord, Bean, RunRecord, TestSummary};
     use std::fs;
     use tempfile::TempDir;
 
@@ -1013,6 +1039,8 @@ mod tests {
             agent: Some("agent-1".to_string()),
             started_at: None,
             finished_at: None,
+            verify: None,
+            base: None,
         }];
 
         let result = format_previous_attempts(&bean);
@@ -1033,6 +1061,41 @@ mod tests {
         assert!(result.contains("Do NOT repeat"));
     }
 
+    #[test]
+    fn previous_attempts_with_test_summary() {
+        let mut bean = Bean::new("1", "Test");
+        bean.attempts = 1;
+        bean.history = vec![RunRecord {
+            attempt: 1,
+            started_at: chrono::Utc::now(),
+            finished_at: None,
+            duration_secs: None,
+            agent: None,
+            result: RunResult::Fail,
+            exit_code: Some(101),
+            tokens: None,
+            cost: None,
+            output_snippet: None,
+            reason: None,
+            cached: false,
+            tests: Some(TestSummary {
+                passed: 9,
+                failed: 1,
+                ignored: 0,
+                failing: vec!["parser::rejects_empty".to_string()],
+                message: Some("assertion failed: input.is_empty()".to_string()),
+            }),
+        }];
+
+        let result = format_previous_attempts(&bean);
+        assert!(result.contains("Last verify run: 1 of 10 tests failed: parser::rejects_empty"));
+        assert!(result.contains("```\nassertion failed: input.is_empty()\n```"));
+
+        // Nothing to report once verify passes
+        bean.history[0].result = RunResult::Pass;
+        assert!(!format_previous_attempts(&bean).contains("Last verify run"));
+    }
+
     // -- format_approach --
 
     #[test]
@@ -1096,6 +1159,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1131,6 +1195,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: Some("Focus on performance".to_string()),
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1150,6 +1215,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1169,6 +1235,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1192,6 +1259,7 @@ mod tests {
                 title: "Other".to_string(),
                 shared_files: vec!["src/shared.rs".to_string()],
             }]),
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1214,6 +1282,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1222,6 +1291,33 @@ mod tests {
         assert!(result.system_prompt.contains("Do NOT repeat"));
     }
 
+    #[test]
+    fn build_prompt_with_previous_failure() {
+        let (_dir, beans_dir) = setup_test_env();
+
+        let mut bean = Bean::new("1", "Retry Task");
+        write_test_bean(&beans_dir, &bean);
+
+        let summary = "## Attempt 1 Failed (0:42, 1k tokens, $0.010)\n\nExit code 1";
+        let options = PromptOptions {
+            beans_dir: beans_dir.clone(),
+            instructions: None,
+            concurrent_overlaps: None,
+            previous_failure: Some(summary.to_string()),
+        };
+
+        let result = build_agent_prompt(&bean, &options).unwrap();
+        assert!(result.system_prompt.contains("# Last Failure"));
+        assert!(result.system_prompt.contains("Attempt 1 Failed"));
+
+        // Already shown under Previous Attempts: not repeated
+        bean.attempts = 1;
+        bean.notes = Some(format!("Earlier notes\n{}", summary));
+        let result = build_agent_prompt(&bean, &options).unwrap();
+        assert!(!result.system_prompt.contains("# Last Failure"));
+        assert_eq!(result.system_prompt.matches("Attempt 1 Failed").count(), 1);
+    }
+
     #[test]
     fn build_prompt_no_verify() {
         let (_dir, beans_dir) = setup_test_env();
@@ -1233,6 +1329,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1262,6 +1359,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
@@ -1304,6 +1402,7 @@ mod tests {
             beans_dir: beans_dir.clone(),
             instructions: None,
             concurrent_overlaps: None,
+            previous_failure: None,
         };
 
         let result = build_agent_prompt(&bean, &options).unwrap();
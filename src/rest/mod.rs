@@ -0,0 +1,352 @@
+//! Local REST/JSON API: `bn serve`.
+//!
+//! Serves the same operations as the CLI (list, show, create, update, close,
+//! claim, dependencies, status, stats and logs) as versioned JSON endpoints
+//! under `/v1`. Every route is declared once in [`routes::ROUTES`]; the
+//! dispatcher matches requests against that table and `/v1/openapi.json` is
+//! generated from it.
+//!
+//! Mutations go through the CLI's own command functions, so they get the same
+//! validation and hooks, and each one holds the index lock
+//! ([`LockedIndex`](crate::index::LockedIndex)) while it runs so concurrent
+//! requests (and `bn` processes) don't race on `.beans/`.
+
+pub mod openapi;
+pub mod routes;
+
+use std::io::{BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use serde_json::json;
+
+use crate::error::BeansError;
+use crate::http::{self, Request, Response};
+
+/// How long a mutation waits for another one to release the index lock.
+const MUTATION_LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often a followed log is checked for new output.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A bound, not yet running, REST server.
+pub struct RestServer {
+    listener: TcpListener,
+}
+
+impl RestServer {
+    /// Bind to `addr` (e.g. `127.0.0.1:8765`; port 0 picks a free port).
+    pub fn bind(addr: &str) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to bind {}", addr))?;
+        Ok(Self { listener })
+    }
+
+    /// The address actually bound.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accept connections forever, one thread per connection.
+    pub fn run(self, beans_dir: &Path) -> Result<()> {
+        let beans_dir = Arc::new(beans_dir.to_path_buf());
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let beans_dir = Arc::clone(&beans_dir);
+            std::thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &beans_dir) {
+                    eprintln!("HTTP connection error: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// What a route handler produces.
+pub enum Reply {
+    /// A complete response.
+    Response(Response),
+    /// A server-sent event stream of the lines of a log file, following it
+    /// as it grows until the client disconnects.
+    FollowLog(PathBuf),
+}
+
+impl Reply {
+    /// A JSON reply.
+    pub fn json(status: u16, body: &impl serde::Serialize) -> Self {
+        Reply::Response(Response::json(status, body))
+    }
+}
+
+/// An error reply: `{"error": message}` with an HTTP status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(400, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(404, message)
+    }
+
+    /// The request was well-formed but the operation was refused (failed
+    /// validation, a hook rejected it, ...). Missing beans are 404, state
+    /// conflicts 409 and a held index lock 503.
+    pub fn unprocessable(err: anyhow::Error) -> Self {
+        let status = match crate::error::find(&err) {
+            Some(BeansError::NotFound { .. }) => 404,
+            Some(
+                BeansError::Conflict { .. }
+                | BeansError::UndoConflict { .. }
+                | BeansError::NotClaimable { .. }
+                | BeansError::DependencyExists { .. }
+                | BeansError::RunInProgress,
+            ) => 409,
+            Some(BeansError::Locked { .. }) => 503,
+            _ => 422,
+        };
+        Self::new(status, format!("{:#}", err))
+    }
+
+    pub fn internal(err: anyhow::Error) -> Self {
+        Self::new(500, format!("{:#}", err))
+    }
+
+    fn into_response(self) -> Response {
+        Response::json(self.status, &json!({ "error": self.message }))
+    }
+}
+
+/// Per-request state handed to route handlers.
+pub struct Context<'a> {
+    pub beans_dir: &'a Path,
+    pub request: &'a Request,
+    /// Values of the `{name}` segments of the matched route path.
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl Context<'_> {
+    /// A path parameter of the matched route.
+    pub fn param(&self, name: &str) -> &str {
+        self.params
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The last value of query parameter `name`.
+    pub fn query(&self, name: &str) -> Option<String> {
+        self.request
+            .query_pairs()
+            .into_iter()
+            .rev()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v)
+    }
+
+    /// A boolean query flag: present without a value, or `true`/`1`.
+    pub fn query_flag(&self, name: &str) -> Result<bool, ApiError> {
+        match self.query(name).as_deref() {
+            None | Some("false") | Some("0") => Ok(false),
+            Some("") | Some("true") | Some("1") => Ok(true),
+            Some(other) => Err(ApiError::bad_request(format!(
+                "Invalid value for {}: '{}' (expected true or false)",
+                name, other
+            ))),
+        }
+    }
+
+    /// Parse the JSON request body. An empty body parses as `{}`.
+    pub fn body<T: serde::de::DeserializeOwned>(&self) -> Result<T, ApiError> {
+        let body = if self.request.body.iter().all(u8::is_ascii_whitespace) {
+            b"{}".as_slice()
+        } else {
+            &self.request.body
+        };
+        serde_json::from_slice(body)
+            .map_err(|e| ApiError::bad_request(format!("Invalid request body: {}", e)))
+    }
+
+    /// Run a mutation while holding the index lock.
+    pub fn mutate<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T, ApiError> {
+        let _lock =
+            crate::index::LockedIndex::acquire_with_timeout(self.beans_dir, MUTATION_LOCK_TIMEOUT)
+                .map_err(|e| ApiError::new(503, format!("{:#}", e)))?;
+        f().map_err(ApiError::unprocessable)
+    }
+}
+
+fn handle_connection(stream: TcpStream, beans_dir: &Path) -> Result<()> {
+    let mut writer = stream.try_clone()?;
+    let request = match http::read_request(&mut BufReader::new(stream)) {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            let error = ApiError::bad_request(e.to_string());
+            error.into_response().write_to(&mut writer)?;
+            return Ok(());
+        }
+    };
+
+    match dispatch(beans_dir, &request) {
+        Reply::Response(response) => response.write_to(&mut writer)?,
+        Reply::FollowLog(path) => follow_log(&path, &mut writer)?,
+    }
+    Ok(())
+}
+
+/// Route a request through [`routes::ROUTES`].
+pub fn dispatch(beans_dir: &Path, request: &Request) -> Reply {
+    if !http::is_local_origin(request.header("origin")) {
+        return Reply::Response(ApiError::new(403, "Origin not allowed").into_response());
+    }
+
+    let mut allowed = Vec::new();
+    for route in routes::ROUTES {
+        let params = match match_path(route.path, &request.path) {
+            Some(params) => params,
+            None => continue,
+        };
+        if route.method != request.method {
+            allowed.push(route.method);
+            continue;
+        }
+        let ctx = Context {
+            beans_dir,
+            request,
+            params,
+        };
+        return match (route.handler)(&ctx) {
+            Ok(reply) => reply,
+            Err(error) => Reply::Response(error.into_response()),
+        };
+    }
+
+    let response = if allowed.is_empty() {
+        ApiError::not_found(format!("No route for {}", request.path)).into_response()
+    } else {
+        ApiError::new(405, format!("{} not allowed here", request.method))
+            .into_response()
+            .with_header("Allow", allowed.join(", "))
+    };
+    Reply::Response(response)
+}
+
+/// Match a route pattern like `/v1/beans/{id}` against a request path,
+/// returning the decoded `{name}` segments.
+fn match_path(pattern: &'static str, path: &str) -> Option<Vec<(&'static str, String)>> {
+    let mut pattern_segments = pattern.trim_end_matches('/').split('/');
+    let mut path_segments = path.trim_end_matches('/').split('/');
+    let mut params = Vec::new();
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return Some(params),
+            (Some(p), Some(s)) => {
+                if let Some(name) = p.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                    if s.is_empty() {
+                        return None;
+                    }
+                    params.push((name, http::percent_decode(s)));
+                } else if p != s {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Stream a log file as `log` events, then follow it as it grows.
+fn follow_log(path: &Path, writer: &mut impl Write) -> Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    http::start_event_stream(writer, &[])?;
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut pending = String::new();
+    let mut idle = Duration::ZERO;
+    loop {
+        let mut chunk = String::new();
+        file.read_to_string(&mut chunk)?;
+        if chunk.is_empty() {
+            std::thread::sleep(LOG_POLL_INTERVAL);
+            idle += LOG_POLL_INTERVAL;
+            // Detect a vanished client even when the log is quiet
+            if idle >= Duration::from_secs(15) {
+                http::write_keepalive(writer)?;
+                idle = Duration::ZERO;
+            }
+            // A rotated or truncated log starts over
+            let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if len < file.stream_position()? {
+                file.seek(SeekFrom::Start(0))?;
+            }
+            continue;
+        }
+        idle = Duration::ZERO;
+        pending.push_str(&chunk);
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            http::write_event(writer, "log", line.trim_end_matches(['\r', '\n']))?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_path_extracts_params() {
+        assert_eq!(match_path("/v1/beans", "/v1/beans"), Some(vec![]));
+        assert_eq!(match_path("/v1/beans", "/v1/beans/"), Some(vec![]));
+        assert_eq!(
+            match_path("/v1/beans/{id}/deps/{depends_on}", "/v1/beans/3.1/deps/2"),
+            Some(vec![
+                ("id", "3.1".to_string()),
+                ("depends_on", "2".to_string())
+            ])
+        );
+        assert_eq!(match_path("/v1/beans/{id}", "/v1/beans"), None);
+        assert_eq!(match_path("/v1/beans/{id}", "/v1/beans/1/close"), None);
+        assert_eq!(match_path("/v1/stats", "/v1/status"), None);
+    }
+
+    #[test]
+    fn every_route_is_reachable() {
+        // Two routes with the same method and shape would shadow each other
+        for (i, a) in routes::ROUTES.iter().enumerate() {
+            for b in &routes::ROUTES[i + 1..] {
+                assert!(
+                    !(a.method == b.method && a.path == b.path),
+                    "duplicate route {} {}",
+                    a.method,
+                    a.path
+                );
+            }
+        }
+    }
+}
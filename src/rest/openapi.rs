@@ -0,0 +1,342 @@
+//! OpenAPI 3 document for the REST API, generated from [`ROUTES`].
+//!
+//! Paths, parameters and operations come from the route table; the schemas
+//! the routes refer to by name are declared in [`schemas`].
+
+use serde_json::{json, Map, Value};
+
+use super::routes::{Route, ROUTES};
+
+/// The OpenAPI document served at `/v1/openapi.json`.
+pub fn document() -> Value {
+    let schemas = schemas();
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let item = paths
+            .entry(route.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[route.method.to_ascii_lowercase()] = operation(route, &schemas);
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "beans",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Local REST API served by `bn serve`.",
+        },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+fn operation(route: &Route, schemas: &Value) -> Value {
+    let mut parameters: Vec<Value> = path_params(route.path)
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+    parameters.extend(route.query.iter().map(|q| {
+        json!({
+            "name": q.name,
+            "in": "query",
+            "required": false,
+            "description": q.description,
+            "schema": { "type": q.kind },
+        })
+    }));
+
+    let success = match route.response {
+        Some(schema) => json!({
+            "description": "OK",
+            "content": { "application/json": { "schema": schema_ref(schema) } },
+        }),
+        None => json!({
+            "description": "OK",
+            "content": { "text/plain": { "schema": { "type": "string" } } },
+        }),
+    };
+    let error = json!({
+        "description": "Error",
+        "content": { "application/json": { "schema": schema_ref("Error") } },
+    });
+
+    let mut op = json!({
+        "operationId": route.operation_id,
+        "summary": route.summary,
+        "responses": {
+            route.status.to_string(): success,
+            "default": error,
+        },
+    });
+    if !parameters.is_empty() {
+        op["parameters"] = Value::Array(parameters);
+    }
+    if let Some(body) = route.body {
+        op["requestBody"] = json!({
+            // Bodies whose fields are all optional may be omitted
+            "required": schemas[body].get("required").is_some(),
+            "content": { "application/json": { "schema": schema_ref(body) } },
+        });
+    }
+    op
+}
+
+/// Names of the `{name}` segments of a route path.
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+/// Schemas referenced by the route table.
+fn schemas() -> Value {
+    let string = json!({ "type": "string" });
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    let boolean = json!({ "type": "boolean" });
+    let priority = json!({ "type": "integer", "minimum": 0, "maximum": 4 });
+    let status = json!({
+        "type": "string",
+        "enum": ["open", "in_progress", "in_review", "blocked", "closed", "cancelled"],
+    });
+
+    json!({
+        "Error": {
+            "type": "object",
+            "required": ["error"],
+            "properties": { "error": string },
+        },
+        "Bean": {
+            "type": "object",
+            "description": "A bean as stored in its file (see the beans file format).",
+            "required": ["id", "title", "status", "priority"],
+            "properties": {
+                "id": string,
+                "title": string,
+                "status": status,
+                "priority": priority,
+                "description": string,
+                "acceptance": string,
+                "notes": string,
+                "verify": string,
+                "labels": strings,
+                "assignee": string,
+                "claimed_by": string,
+                "parent": string,
+                "dependencies": strings,
+                "attempts": { "type": "integer" },
+            },
+            "additionalProperties": true,
+        },
+        "IndexEntry": {
+            "type": "object",
+            "required": ["id", "title", "status", "priority"],
+            "properties": {
+                "id": string,
+                "title": string,
+                "status": status,
+                "priority": priority,
+                "parent": string,
+                "dependencies": strings,
+                "labels": strings,
+                "assignee": string,
+            },
+            "additionalProperties": true,
+        },
+        "IndexEntryList": { "type": "array", "items": schema_ref("IndexEntry") },
+        "CreateBean": {
+            "type": "object",
+            "required": ["title"],
+            "additionalProperties": false,
+            "properties": {
+                "title": string,
+                "description": string,
+                "acceptance": string,
+                "notes": string,
+                "design": string,
+                "verify": string,
+                "priority": priority,
+                "labels": strings,
+                "assignee": string,
+                "deps": strings,
+                "parent": string,
+                "produces": strings,
+                "requires": strings,
+                "paths": strings,
+                "on_fail": {
+                    "type": "string",
+                    "description": "retry, retry:N, escalate or escalate:P0-P4",
+                },
+                "pass_ok": boolean,
+                "claim": boolean,
+                "by": string,
+                "verify_timeout": { "type": "integer", "minimum": 0 },
+                "feature": boolean,
+            },
+        },
+        "UpdateBean": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "title": string,
+                "description": string,
+                "acceptance": string,
+                "notes": { "type": "string", "description": "Appended to the existing notes" },
+                "design": string,
+                "status": status,
+                "blocked_reason": string,
+                "priority": priority,
+                "assignee": string,
+                "add_label": string,
+                "remove_label": string,
+            },
+        },
+        "CloseBean": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "reason": string,
+                "force": { "type": "boolean", "description": "Skip the verify command" },
+            },
+        },
+        "CloseResult": {
+            "type": "object",
+            "required": ["closed", "bean"],
+            "properties": {
+                "closed": boolean,
+                "error": string,
+                "bean": schema_ref("Bean"),
+            },
+        },
+        "ClaimBean": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "by": string,
+                "force": {
+                    "type": "boolean",
+                    "description": "Claim without first checking that verify fails",
+                },
+            },
+        },
+        "AddDependency": {
+            "type": "object",
+            "required": ["depends_on"],
+            "additionalProperties": false,
+            "properties": { "depends_on": string },
+        },
+        "DependencyRef": {
+            "type": "object",
+            "required": ["id"],
+            "properties": { "id": string, "title": string, "status": status },
+        },
+        "DependencyList": {
+            "type": "object",
+            "required": ["id", "dependencies", "dependents"],
+            "properties": {
+                "id": string,
+                "dependencies": { "type": "array", "items": schema_ref("DependencyRef") },
+                "dependents": { "type": "array", "items": schema_ref("DependencyRef") },
+            },
+        },
+        "Status": {
+            "type": "object",
+            "required": ["claimed", "in_review", "ready", "goals", "blocked"],
+            "properties": {
+                "claimed": { "type": "array", "items": { "type": "object" } },
+                "in_review": schema_ref("IndexEntryList"),
+                "ready": schema_ref("IndexEntryList"),
+                "goals": schema_ref("IndexEntryList"),
+                "blocked": { "type": "array", "items": { "type": "object" } },
+            },
+        },
+        "Stats": {
+            "type": "object",
+            "properties": {
+                "total": { "type": "integer" },
+                "open": { "type": "integer" },
+                "in_progress": { "type": "integer" },
+                "in_review": { "type": "integer" },
+                "closed": { "type": "integer" },
+                "cancelled": { "type": "integer" },
+                "blocked": { "type": "integer" },
+                "completion_pct": { "type": "number" },
+                "priority_counts": { "type": "array", "items": { "type": "integer" } },
+                "cost": { "type": "object", "nullable": true },
+            },
+        },
+        "OpenApi": { "type": "object" },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::routes::{ClaimBody, CloseBody, CreateBody, DependencyBody, UpdateBody};
+    use super::*;
+
+    #[test]
+    fn every_route_is_documented() {
+        let doc = document();
+        for route in ROUTES {
+            let op = &doc["paths"][route.path][route.method.to_ascii_lowercase()];
+            assert_eq!(op["operationId"], route.operation_id);
+        }
+        let params = doc["paths"]["/v1/beans/{id}/deps/{depends_on}"]["delete"]["parameters"]
+            .as_array()
+            .unwrap();
+        let names: Vec<&str> = params.iter().map(|p| p["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["id", "depends_on"]);
+    }
+
+    #[test]
+    fn every_referenced_schema_exists() {
+        let doc = document();
+        let text = doc.to_string();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        for part in text.split("#/components/schemas/").skip(1) {
+            let name = part.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "missing schema {}", name);
+        }
+    }
+
+    /// A request body with every property the schema documents.
+    fn full_body(schema: &str) -> Value {
+        let doc = document();
+        let props = doc["components"]["schemas"][schema]["properties"]
+            .as_object()
+            .unwrap()
+            .clone();
+        let body: Map<String, Value> = props
+            .into_iter()
+            .map(|(name, prop)| {
+                let value = match prop["type"].as_str() {
+                    Some("boolean") => json!(true),
+                    Some("integer") => json!(1),
+                    Some("array") => json!(["x"]),
+                    _ => json!("open"),
+                };
+                (name, value)
+            })
+            .collect();
+        Value::Object(body)
+    }
+
+    #[test]
+    fn body_schemas_match_body_types() {
+        // The bodies deny unknown fields, so a documented field they don't
+        // have fails here
+        serde_json::from_value::<CreateBody>(full_body("CreateBean")).unwrap();
+        serde_json::from_value::<UpdateBody>(full_body("UpdateBean")).unwrap();
+        serde_json::from_value::<CloseBody>(full_body("CloseBean")).unwrap();
+        serde_json::from_value::<ClaimBody>(full_body("ClaimBean")).unwrap();
+        serde_json::from_value::<DependencyBody>(full_body("AddDependency")).unwrap();
+    }
+}
@@ -0,0 +1,566 @@
+//! The route table and its handlers.
+//!
+//! Each [`Route`] carries enough metadata (parameters, body and response
+//! schemas) for [`openapi::document`](super::openapi::document) to describe it.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{ApiError, Context, Reply};
+use crate::api;
+use crate::bean::{Bean, Status};
+use crate::commands::create::{self, parse_on_fail, CreateArgs};
+use crate::commands::update::{update_bean, BeanUpdate};
+use crate::commands::{claim, dep, list, logs, stats, status};
+use crate::http::Response;
+
+/// Handler signature shared by every route.
+pub type Handler = fn(&Context) -> Result<Reply, ApiError>;
+
+/// One endpoint.
+pub struct Route {
+    pub method: &'static str,
+    /// Path pattern; `{name}` segments are path parameters.
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    pub query: &'static [QueryParam],
+    /// Name of the request body schema, if the route takes one.
+    pub body: Option<&'static str>,
+    /// Success status code.
+    pub status: u16,
+    /// Name of the success response schema; `None` for plain text.
+    pub response: Option<&'static str>,
+    pub handler: Handler,
+}
+
+/// A query string parameter.
+pub struct QueryParam {
+    pub name: &'static str,
+    /// JSON schema type: `string`, `integer` or `boolean`.
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+const fn query(name: &'static str, kind: &'static str, description: &'static str) -> QueryParam {
+    QueryParam {
+        name,
+        kind,
+        description,
+    }
+}
+
+/// Every endpoint the server answers.
+pub static ROUTES: &[Route] = &[
+    Route {
+        method: "GET",
+        path: "/v1/beans",
+        operation_id: "listBeans",
+        summary: "List beans (excludes closed unless status or all says otherwise)",
+        query: &[
+            query("status", "string", "Only beans with this status (open, in_progress, in_review, blocked, closed, cancelled)"),
+            query("priority", "integer", "Only this priority (0-4)"),
+            query("parent", "string", "Only children of this bean"),
+            query("label", "string", "Only beans with this label"),
+            query("assignee", "string", "Only beans assigned to this person"),
+            query("mine", "boolean", "Only beans claimed by the current identity"),
+            query("all", "boolean", "Include closed and archived beans"),
+            query("q", "string", "Filter expression, e.g. `priority<=1 and label:backend and not claimed`"),
+        ],
+        body: None,
+        status: 200,
+        response: Some("IndexEntryList"),
+        handler: list_beans,
+    },
+    Route {
+        method: "POST",
+        path: "/v1/beans",
+        operation_id: "createBean",
+        summary: "Create a bean",
+        query: &[],
+        body: Some("CreateBean"),
+        status: 201,
+        response: Some("Bean"),
+        handler: create_bean,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/beans/{id}",
+        operation_id: "getBean",
+        summary: "Get a bean, including archived ones",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("Bean"),
+        handler: get_bean,
+    },
+    Route {
+        method: "PATCH",
+        path: "/v1/beans/{id}",
+        operation_id: "updateBean",
+        summary: "Update a bean's fields",
+        query: &[],
+        body: Some("UpdateBean"),
+        status: 200,
+        response: Some("Bean"),
+        handler: patch_bean,
+    },
+    Route {
+        method: "POST",
+        path: "/v1/beans/{id}/close",
+        operation_id: "closeBean",
+        summary: "Close a bean, running its pre-close hook and verify command",
+        query: &[],
+        body: Some("CloseBean"),
+        status: 200,
+        response: Some("CloseResult"),
+        handler: close_bean,
+    },
+    Route {
+        method: "POST",
+        path: "/v1/beans/{id}/claim",
+        operation_id: "claimBean",
+        summary: "Claim a bean for work",
+        query: &[],
+        body: Some("ClaimBean"),
+        status: 200,
+        response: Some("Bean"),
+        handler: claim_bean,
+    },
+    Route {
+        method: "POST",
+        path: "/v1/beans/{id}/release",
+        operation_id: "releaseBean",
+        summary: "Release a claim on a bean",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("Bean"),
+        handler: release_bean,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/beans/{id}/deps",
+        operation_id: "listDependencies",
+        summary: "List a bean's dependencies and dependents",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("DependencyList"),
+        handler: list_deps,
+    },
+    Route {
+        method: "POST",
+        path: "/v1/beans/{id}/deps",
+        operation_id: "addDependency",
+        summary: "Make a bean depend on another",
+        query: &[],
+        body: Some("AddDependency"),
+        status: 200,
+        response: Some("DependencyList"),
+        handler: add_dep,
+    },
+    Route {
+        method: "DELETE",
+        path: "/v1/beans/{id}/deps/{depends_on}",
+        operation_id: "removeDependency",
+        summary: "Remove a dependency",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("DependencyList"),
+        handler: remove_dep,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/beans/{id}/logs",
+        operation_id: "getLogs",
+        summary: "Latest agent log for a bean; follow=true streams it as server-sent events",
+        query: &[query(
+            "follow",
+            "boolean",
+            "Stream new lines as `log` events until the client disconnects",
+        )],
+        body: None,
+        status: 200,
+        response: None,
+        handler: get_logs,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/status",
+        operation_id: "getStatus",
+        summary: "Claimed, in-review, ready, goal and blocked beans",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("Status"),
+        handler: get_status,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/stats",
+        operation_id: "getStats",
+        summary: "Project statistics",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("Stats"),
+        handler: get_stats,
+    },
+    Route {
+        method: "GET",
+        path: "/v1/openapi.json",
+        operation_id: "getOpenApi",
+        summary: "This API's OpenAPI document",
+        query: &[],
+        body: None,
+        status: 200,
+        response: Some("OpenApi"),
+        handler: get_openapi,
+    },
+];
+
+// ---------------------------------------------------------------------------
+// Request bodies
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateBody {
+    pub title: String,
+    pub description: Option<String>,
+    pub acceptance: Option<String>,
+    pub notes: Option<String>,
+    pub design: Option<String>,
+    pub verify: Option<String>,
+    pub priority: Option<u8>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub deps: Vec<String>,
+    pub parent: Option<String>,
+    #[serde(default)]
+    pub produces: Vec<String>,
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// `retry`, `retry:N`, `escalate` or `escalate:P0`, as for `--on-fail`.
+    pub on_fail: Option<String>,
+    #[serde(default)]
+    pub pass_ok: bool,
+    #[serde(default)]
+    pub claim: bool,
+    pub by: Option<String>,
+    pub verify_timeout: Option<u64>,
+    #[serde(default)]
+    pub feature: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateBody {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub acceptance: Option<String>,
+    /// Appended to the existing notes, like `bn update --note`.
+    #[serde(alias = "note")]
+    pub notes: Option<String>,
+    pub design: Option<String>,
+    pub status: Option<String>,
+    pub blocked_reason: Option<String>,
+    pub priority: Option<u8>,
+    pub assignee: Option<String>,
+    pub add_label: Option<String>,
+    pub remove_label: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloseBody {
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClaimBody {
+    pub by: Option<String>,
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyBody {
+    pub depends_on: String,
+}
+
+/// Join a list the way the CLI's comma-separated flags expect it.
+fn joined(values: Vec<String>) -> Option<String> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(","))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// The `{id}` path parameter, validated like a CLI argument.
+fn bean_id<'a>(ctx: &'a Context) -> Result<&'a str, ApiError> {
+    let id = ctx.param("id");
+    crate::util::validate_bean_id(id).map_err(|e| ApiError::bad_request(format!("{:#}", e)))?;
+    Ok(id)
+}
+
+/// Load an active bean, or 404.
+fn active_bean(ctx: &Context, id: &str) -> Result<Bean, ApiError> {
+    api::get_bean(ctx.beans_dir, id)
+        .map_err(|_| ApiError::not_found(format!("Bean not found: {}", id)))
+}
+
+fn list_beans(ctx: &Context) -> Result<Reply, ApiError> {
+    let priority = ctx
+        .query("priority")
+        .map(|p| {
+            p.parse::<u8>()
+                .map_err(|_| ApiError::bad_request(format!("Invalid priority: '{}'", p)))
+        })
+        .transpose()?;
+    let filter = list::ListFilter {
+        status: ctx.query("status"),
+        priority,
+        parent: ctx.query("parent"),
+        label: ctx.query("label"),
+        assignee: ctx.query("assignee"),
+        mine: ctx.query_flag("mine")?,
+        all: ctx.query_flag("all")?,
+        query: ctx.query("q"),
+    };
+    let entries = list::list_entries(ctx.beans_dir, &filter).map_err(ApiError::unprocessable)?;
+    Ok(Reply::json(200, &entries))
+}
+
+fn get_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let bean = api::get_bean(ctx.beans_dir, id)
+        .or_else(|_| api::get_archived_bean(ctx.beans_dir, id))
+        .map_err(|_| ApiError::not_found(format!("Bean not found: {}", id)))?;
+    Ok(Reply::json(200, &bean))
+}
+
+fn create_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let body: CreateBody = ctx.body()?;
+    if body.title.trim().is_empty() {
+        return Err(ApiError::new(422, "Title cannot be empty"));
+    }
+    let on_fail = body
+        .on_fail
+        .as_deref()
+        .map(parse_on_fail)
+        .transpose()
+        .map_err(ApiError::unprocessable)?;
+    let args = CreateArgs {
+        title: body.title,
+        description: body.description,
+        acceptance: body.acceptance,
+        notes: body.notes,
+        design: body.design,
+        verify: body.verify,
+        priority: body.priority,
+        labels: joined(body.labels),
+        assignee: body.assignee,
+        deps: joined(body.deps),
+        parent: body.parent,
+        produces: joined(body.produces),
+        requires: joined(body.requires),
+        paths: joined(body.paths),
+        on_fail,
+        pass_ok: body.pass_ok,
+        claim: body.claim,
+        by: body.by,
+        verify_timeout: body.verify_timeout,
+        feature: body.feature,
+    };
+    // Tips and warnings go to the server's stderr, never into the response
+    let bean = ctx.mutate(|| create::create_bean(ctx.beans_dir, args, &mut std::io::stderr()))?;
+    Ok(Reply::json(201, &bean))
+}
+
+fn patch_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let body: UpdateBody = ctx.body()?;
+    active_bean(ctx, id)?;
+    let update = BeanUpdate {
+        title: body.title,
+        description: body.description,
+        acceptance: body.acceptance,
+        notes: body.notes,
+        design: body.design,
+        status: body.status,
+        blocked_reason: body.blocked_reason,
+        priority: body.priority,
+        assignee: body.assignee,
+        add_label: body.add_label,
+        remove_label: body.remove_label,
+    };
+    let bean = ctx.mutate(|| update_bean(ctx.beans_dir, id, update, &mut std::io::stderr()))?;
+    Ok(Reply::json(200, &bean))
+}
+
+fn close_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let body: CloseBody = ctx.body()?;
+    let before = match api::get_bean(ctx.beans_dir, id) {
+        Ok(bean) => bean,
+        Err(_) if api::get_archived_bean(ctx.beans_dir, id).is_ok() => {
+            return Err(ApiError::new(409, format!("Bean {} is already closed", id)));
+        }
+        Err(_) => return Err(ApiError::not_found(format!("Bean not found: {}", id))),
+    };
+    // `bn close` asks a human at the terminal before closing a feature
+    if before.feature {
+        return Err(ApiError::new(
+            409,
+            format!("Bean {} is a feature; close it with bn close", id),
+        ));
+    }
+
+    ctx.mutate(|| {
+        crate::commands::cmd_close(ctx.beans_dir, vec![id.to_string()], body.reason, body.force)
+    })?;
+
+    if let Ok(bean) = api::get_archived_bean(ctx.beans_dir, id) {
+        return Ok(Reply::json(200, &json!({ "closed": true, "bean": bean })));
+    }
+    let after = active_bean(ctx, id)?;
+    if after.status == Status::Closed {
+        return Ok(Reply::json(200, &json!({ "closed": true, "bean": after })));
+    }
+    let error = if after.attempts > before.attempts {
+        "Verify failed"
+    } else {
+        "Rejected by pre-close hook"
+    };
+    Ok(Reply::json(
+        422,
+        &json!({ "closed": false, "error": error, "bean": after }),
+    ))
+}
+
+fn claim_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let body: ClaimBody = ctx.body()?;
+    active_bean(ctx, id)?;
+    let bean = ctx.mutate(|| {
+        claim::claim_bean(
+            ctx.beans_dir,
+            id,
+            body.by,
+            body.force,
+            &mut std::io::stderr(),
+        )
+    })?;
+    Ok(Reply::json(200, &bean))
+}
+
+fn release_bean(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    active_bean(ctx, id)?;
+    let bean = ctx.mutate(|| claim::release_bean(ctx.beans_dir, id, &mut std::io::stderr()))?;
+    Ok(Reply::json(200, &bean))
+}
+
+fn list_deps(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    active_bean(ctx, id)?;
+    let deps = dep::list_dependencies(ctx.beans_dir, id).map_err(ApiError::unprocessable)?;
+    Ok(Reply::json(200, &deps))
+}
+
+fn add_dep(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let body: DependencyBody = ctx.body()?;
+    active_bean(ctx, id)?;
+    let deps = ctx.mutate(|| {
+        dep::add_dependency(ctx.beans_dir, id, &body.depends_on)?;
+        dep::list_dependencies(ctx.beans_dir, id)
+    })?;
+    Ok(Reply::json(200, &deps))
+}
+
+fn remove_dep(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let depends_on = ctx.param("depends_on");
+    active_bean(ctx, id)?;
+    let deps = ctx.mutate(|| {
+        dep::remove_dependency(ctx.beans_dir, id, depends_on)?;
+        dep::list_dependencies(ctx.beans_dir, id)
+    })?;
+    Ok(Reply::json(200, &deps))
+}
+
+fn get_logs(ctx: &Context) -> Result<Reply, ApiError> {
+    let id = bean_id(ctx)?;
+    let follow = ctx.query_flag("follow")?;
+    let path = logs::find_log_path(id)
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::not_found(format!("No logs for bean {}", id)))?;
+    if follow {
+        return Ok(Reply::FollowLog(path));
+    }
+    let contents = std::fs::read(&path)
+        .map_err(|e| ApiError::internal(anyhow::anyhow!("{}: {}", path.display(), e)))?;
+    Ok(Reply::Response(
+        Response::new(200)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(contents),
+    ))
+}
+
+fn get_status(ctx: &Context) -> Result<Reply, ApiError> {
+    let output = status::build_status(ctx.beans_dir).map_err(ApiError::internal)?;
+    Ok(Reply::json(200, &output))
+}
+
+fn get_stats(ctx: &Context) -> Result<Reply, ApiError> {
+    let output = stats::build_stats(ctx.beans_dir).map_err(ApiError::internal)?;
+    Ok(Reply::json(200, &output))
+}
+
+fn get_openapi(_ctx: &Context) -> Result<Reply, ApiError> {
+    Ok(Reply::json(200, &super::openapi::document()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bodies_reject_unknown_fields() {
+        assert!(serde_json::from_str::<CloseBody>(r#"{"reason": "done"}"#).is_ok());
+        assert!(serde_json::from_str::<CloseBody>(r#"{"reasn": "done"}"#).is_err());
+        assert!(serde_json::from_str::<CreateBody>(r#"{"description": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn update_accepts_note_alias() {
+        let body: UpdateBody = serde_json::from_str(r#"{"note": "progress"}"#).unwrap();
+        assert_eq!(body.notes.as_deref(), Some("progress"));
+    }
+
+    #[test]
+    fn lists_join_like_cli_flags() {
+        assert_eq!(joined(vec![]), None);
+        assert_eq!(
+            joined(vec!["a".to_string(), "b".to_string()]).as_deref(),
+            Some("a,b")
+        );
+    }
+}
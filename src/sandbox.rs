@@ -0,0 +1,485 @@
+//! Sandboxed execution for verify commands, fact checks and hooks.
+//!
+//! Verify commands come from bean files that agents write themselves, and
+//! hooks come from the repository, so both can be confined by a `sandbox:`
+//! profile in `.beans/config.yaml`:
+//!
+//! ```yaml
+//! sandbox:
+//!   writable: [target]   # everything else under the project root is read-only
+//!   network: false       # no network (the default)
+//!   cpu_secs: 600
+//!   memory_mb: 4096
+//!   max_processes: 512
+//!   env: [CARGO_HOME, RUSTUP_HOME]
+//! ```
+//!
+//! The environment is scrubbed down to a few basic variables plus `env`,
+//! and resource limits are applied with `setrlimit`. The read-only project
+//! root and the network cut-off use Linux user, mount and network
+//! namespaces; where those are unavailable the command still runs, with
+//! a message saying which protections are missing.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Once;
+
+use crate::config::{Config, SandboxConfig};
+
+/// Environment variables that are always passed through.
+const BASE_ENV: [&str; 6] = ["PATH", "HOME", "USER", "LANG", "TERM", "TMPDIR"];
+
+/// The sandbox profile configured for the project at `project_root`, if any.
+pub fn profile(project_root: &Path) -> Option<SandboxConfig> {
+    Config::load_with_extends(&project_root.join(".beans"))
+        .ok()
+        .and_then(|config| config.sandbox)
+}
+
+/// A `sh -c <command>` process running in `project_root`, confined by the
+/// project's sandbox profile when one is configured.
+pub fn shell(project_root: &Path, command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]).current_dir(project_root);
+    if let Some(profile) = profile(project_root) {
+        confine(&mut cmd, project_root, &profile);
+    }
+    cmd
+}
+
+/// A process running `program` directly in `project_root`, confined like
+/// [`shell`].
+pub fn program(project_root: &Path, program: &Path) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.current_dir(project_root);
+    if let Some(profile) = profile(project_root) {
+        confine(&mut cmd, project_root, &profile);
+    }
+    cmd
+}
+
+/// Apply `profile` to `cmd`, which must run in `project_root`.
+pub fn confine(cmd: &mut Command, project_root: &Path, profile: &SandboxConfig) {
+    cmd.env_clear();
+    for key in BASE_ENV
+        .iter()
+        .copied()
+        .chain(profile.env.iter().map(String::as_str))
+    {
+        if let Some(value) = std::env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let isolate = isolation_available();
+        if !isolate {
+            warn_no_isolation();
+        }
+        imp::confine(cmd, project_root, profile, isolate);
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = project_root;
+        warn_no_isolation();
+    }
+}
+
+/// Whether the read-only project root and network cut-off can be applied
+/// here (Linux with unprivileged user namespaces).
+pub fn isolation_available() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *AVAILABLE.get_or_init(imp::probe)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+fn warn_no_isolation() {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| {
+        eprintln!(
+            "sandbox: filesystem and network isolation need Linux user namespaces, which are not \
+             available here; running with a scrubbed environment and resource limits only"
+        );
+    });
+}
+
+/// Writable paths from the profile that exist, resolved against the
+/// project root.
+fn writable_paths(project_root: &Path, profile: &SandboxConfig) -> Vec<PathBuf> {
+    profile
+        .writable
+        .iter()
+        .map(|p| project_root.join(p))
+        .filter(|p| p.exists())
+        .filter_map(|p| p.canonicalize().ok())
+        .collect()
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::Command;
+
+    use crate::config::SandboxConfig;
+
+    /// Everything the child needs, prepared before `fork` so the `pre_exec`
+    /// hook does not allocate.
+    struct Plan {
+        cpu_secs: Option<libc::rlim_t>,
+        memory_bytes: Option<libc::rlim_t>,
+        max_processes: Option<libc::rlim_t>,
+        #[cfg(target_os = "linux")]
+        namespaces: Option<linux::Namespaces>,
+    }
+
+    pub(super) fn confine(
+        cmd: &mut Command,
+        project_root: &Path,
+        profile: &SandboxConfig,
+        isolate: bool,
+    ) {
+        #[cfg(target_os = "linux")]
+        let namespaces = if isolate {
+            linux::Namespaces::new(project_root, profile)
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "linux"))]
+        let _ = (project_root, isolate);
+
+        let plan = Plan {
+            cpu_secs: profile.cpu_secs.map(|n| n as libc::rlim_t),
+            memory_bytes: profile
+                .memory_mb
+                .map(|mb| mb.saturating_mul(1024 * 1024) as libc::rlim_t),
+            max_processes: profile.max_processes.map(|n| n as libc::rlim_t),
+            #[cfg(target_os = "linux")]
+            namespaces,
+        };
+
+        // SAFETY: the hook only makes system calls on data prepared above.
+        unsafe {
+            cmd.pre_exec(move || {
+                #[cfg(target_os = "linux")]
+                if let Some(ref namespaces) = plan.namespaces {
+                    namespaces.enter()?;
+                }
+                if let Some(value) = plan.cpu_secs {
+                    set_limit(libc::RLIMIT_CPU, value)?;
+                }
+                if let Some(value) = plan.memory_bytes {
+                    set_limit(libc::RLIMIT_AS, value)?;
+                }
+                if let Some(value) = plan.max_processes {
+                    set_limit(libc::RLIMIT_NPROC, value)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    type Resource = libc::c_int;
+
+    /// Set both the soft and hard limit of `resource`.
+    unsafe fn set_limit(resource: Resource, value: libc::rlim_t) -> std::io::Result<()> {
+        let limit = libc::rlimit {
+            rlim_cur: value,
+            rlim_max: value,
+        };
+        if libc::setrlimit(resource, &limit) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn cstring(path: &Path) -> Option<CString> {
+        CString::new(path.as_os_str().as_bytes()).ok()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(super) fn probe() -> bool {
+        let root = std::env::temp_dir();
+        let profile = SandboxConfig::default();
+        let Some(namespaces) = linux::Namespaces::new(&root, &profile) else {
+            return false;
+        };
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", ":"])
+            .current_dir(&root)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        // SAFETY: as in `confine`.
+        unsafe {
+            cmd.pre_exec(move || namespaces.enter());
+        }
+        cmd.status().is_ok_and(|s| s.success())
+    }
+
+    #[cfg(target_os = "linux")]
+    mod linux {
+        use std::ffi::CString;
+        use std::io;
+        use std::path::Path;
+        use std::ptr;
+
+        use super::super::writable_paths;
+        use super::{cstring, SandboxConfig};
+
+        pub(in super::super) struct Namespaces {
+            flags: libc::c_int,
+            uid_map: Vec<u8>,
+            gid_map: Vec<u8>,
+            root: CString,
+            /// Flags the project root's mount already has; a remount inside
+            /// a user namespace must keep them.
+            root_flags: libc::c_ulong,
+            writable: Vec<CString>,
+        }
+
+        impl Namespaces {
+            pub(in super::super) fn new(
+                project_root: &Path,
+                profile: &SandboxConfig,
+            ) -> Option<Self> {
+                let root_path = project_root.canonicalize().ok()?;
+                let root = cstring(&root_path)?;
+                let mut flags = libc::CLONE_NEWUSER | libc::CLONE_NEWNS;
+                if !profile.network {
+                    flags |= libc::CLONE_NEWNET;
+                }
+                // SAFETY: plain getters.
+                let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                Some(Namespaces {
+                    flags,
+                    uid_map: format!("{} {} 1", uid, uid).into_bytes(),
+                    gid_map: format!("{} {} 1", gid, gid).into_bytes(),
+                    root_flags: mount_flags(&root),
+                    root,
+                    writable: writable_paths(&root_path, profile)
+                        .iter()
+                        .filter_map(|p| cstring(p))
+                        .collect(),
+                })
+            }
+
+            /// Runs in the child between `fork` and `exec`.
+            pub(in super::super) fn enter(&self) -> io::Result<()> {
+                // SAFETY: system calls on NUL-terminated strings owned by self.
+                unsafe {
+                    check(libc::unshare(self.flags))?;
+                    write_proc(c"/proc/self/setgroups", b"deny")?;
+                    write_proc(c"/proc/self/uid_map", &self.uid_map)?;
+                    write_proc(c"/proc/self/gid_map", &self.gid_map)?;
+
+                    // Keep our mounts out of the parent namespace
+                    check(libc::mount(
+                        ptr::null(),
+                        c"/".as_ptr(),
+                        ptr::null(),
+                        libc::MS_REC | libc::MS_PRIVATE,
+                        ptr::null(),
+                    ))?;
+                    // Writable paths become mounts of their own, which the
+                    // recursive bind of the root below carries along
+                    for path in &self.writable {
+                        bind(path)?;
+                    }
+                    bind(&self.root)?;
+                    check(libc::mount(
+                        ptr::null(),
+                        self.root.as_ptr(),
+                        ptr::null(),
+                        libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | self.root_flags,
+                        ptr::null(),
+                    ))?;
+                    // The working directory still points into the old mount
+                    check(libc::chdir(self.root.as_ptr()))?;
+                }
+                Ok(())
+            }
+        }
+
+        unsafe fn bind(path: &CString) -> io::Result<()> {
+            check(libc::mount(
+                path.as_ptr(),
+                path.as_ptr(),
+                ptr::null(),
+                libc::MS_BIND | libc::MS_REC,
+                ptr::null(),
+            ))
+        }
+
+        unsafe fn write_proc(path: &std::ffi::CStr, data: &[u8]) -> io::Result<()> {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let written = libc::write(fd, data.as_ptr().cast(), data.len());
+            libc::close(fd);
+            if written < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        fn check(ret: libc::c_int) -> io::Result<()> {
+            if ret == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+
+        /// The `MS_*` flags of the mount holding `path`.
+        fn mount_flags(path: &CString) -> libc::c_ulong {
+            // SAFETY: statvfs fills the zeroed struct.
+            let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+            if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+                return 0;
+            }
+            [
+                (libc::ST_NOSUID, libc::MS_NOSUID),
+                (libc::ST_NODEV, libc::MS_NODEV),
+                (libc::ST_NOEXEC, libc::MS_NOEXEC),
+                (libc::ST_NOATIME, libc::MS_NOATIME),
+                (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+                (libc::ST_RELATIME, libc::MS_RELATIME),
+            ]
+            .iter()
+            .filter(|(st, _)| stat.f_flag & st != 0)
+            .fold(0, |flags, (_, ms)| flags | ms)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(project_root: &Path, profile: &SandboxConfig, script: &str) -> (bool, String) {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]).current_dir(project_root);
+        confine(&mut cmd, project_root, profile);
+        let output = cmd.output().unwrap();
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )
+    }
+
+    #[test]
+    fn environment_is_scrubbed() {
+        let dir = TempDir::new().unwrap();
+        // Cargo sets CARGO_MANIFEST_DIR for test binaries
+        let script = "echo ${CARGO_MANIFEST_DIR:-unset}; command -v sh >/dev/null && echo path";
+
+        let (_, out) = run(dir.path(), &SandboxConfig::default(), script);
+        assert_eq!(out, "unset\npath");
+
+        let profile = SandboxConfig {
+            env: vec!["CARGO_MANIFEST_DIR".to_string()],
+            ..Default::default()
+        };
+        let (_, out) = run(dir.path(), &profile, script);
+        assert_eq!(out.lines().next(), Some(env!("CARGO_MANIFEST_DIR")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resource_limits_apply() {
+        let dir = TempDir::new().unwrap();
+        let profile = SandboxConfig {
+            cpu_secs: Some(90),
+            memory_mb: Some(2048),
+            ..Default::default()
+        };
+        let (ok, out) = run(dir.path(), &profile, "ulimit -t; ulimit -v");
+        assert!(ok);
+        assert_eq!(out, format!("90\n{}", 2048 * 1024));
+    }
+
+    #[test]
+    fn project_root_is_read_only_except_writable_paths() {
+        if !isolation_available() {
+            eprintln!("skipping: user namespaces unavailable");
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("target")).unwrap();
+        let profile = SandboxConfig {
+            writable: vec!["target".to_string(), "missing".to_string()],
+            ..Default::default()
+        };
+
+        let (ok, _) = run(root, &profile, "touch src.rs");
+        assert!(!ok);
+        assert!(!root.join("src.rs").exists());
+
+        let (ok, _) = run(root, &profile, "touch target/out && cat target/out");
+        assert!(ok);
+        assert!(root.join("target").join("out").exists());
+    }
+
+    #[test]
+    fn network_is_cut_off_unless_allowed() {
+        if !isolation_available() {
+            eprintln!("skipping: user namespaces unavailable");
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        // Interfaces other than loopback
+        let script = "grep -v -c -e 'lo:' -e '|' /proc/net/dev || true";
+
+        let (_, out) = run(dir.path(), &SandboxConfig::default(), script);
+        assert_eq!(out, "0");
+
+        let profile = SandboxConfig {
+            network: true,
+            ..Default::default()
+        };
+        let (ok, _) = run(dir.path(), &profile, "cat /proc/net/dev");
+        assert!(ok);
+    }
+
+    #[test]
+    fn shell_is_unconfined_without_profile() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".beans")).unwrap();
+        fs::write(
+            dir.path().join(".beans").join("config.yaml"),
+            "project: test\nnext_id: 1\n",
+        )
+        .unwrap();
+        assert!(profile(dir.path()).is_none());
+        let status = shell(dir.path(), "touch made").status().unwrap();
+        assert!(status.success());
+        assert!(dir.path().join("made").exists());
+
+        fs::write(
+            dir.path().join(".beans").join("config.yaml"),
+            "project: test\nnext_id: 1\nsandbox:\n  writable: [target]\n  cpu_secs: 60\n",
+        )
+        .unwrap();
+        let profile = profile(dir.path()).unwrap();
+        assert_eq!(profile.writable, vec!["target"]);
+        assert_eq!(profile.cpu_secs, Some(60));
+        assert!(!profile.network);
+    }
+}
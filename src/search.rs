@@ -0,0 +1,860 @@
+//! Full-text search index for `bn recall`.
+//!
+//! `.beans/search.json` is an inverted index of every active and archived
+//! bean: the title, description, notes, close reason, paths, labels and
+//! attempt notes are split into lowercase tokens, and each token maps to the
+//! beans and fields it occurs in with its positions. Results are ranked with
+//! BM25, weighted per field.
+//!
+//! Like `index.yaml`, the file is a regenerable cache. Each indexed bean
+//! records the mtime and size of its file; [`SearchIndex::load_or_update`]
+//! re-reads only the files that changed, appeared or disappeared since the
+//! last query, so beans edited by any command (or by hand) are picked up
+//! without rebuilding the whole index.
+//!
+//! Query syntax, see [`parse_query`]:
+//!
+//! ```text
+//! parser error              both words, in any field
+//! "parse error"             the exact phrase
+//! pars*                     any word starting with "pars"
+//! title:parser              a word in one field (title, description, notes, reason, path)
+//! title:"parse error"       a phrase in one field
+//! status:closed label:bug   filters (also type:fact)
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bean::{Bean, Status};
+use crate::error::BeansError;
+use crate::index::is_bean_filename;
+use crate::util::{atomic_write, ensure_gitignored, natural_cmp, parse_status};
+
+/// File name of the search index inside `.beans/`.
+pub const SEARCH_FILE: &str = "search.json";
+
+/// Bumped whenever the tokenizer or the file layout changes, so an index
+/// written by another version is rebuilt instead of misread.
+const FORMAT_VERSION: u32 = 1;
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 length normalisation.
+const B: f64 = 0.75;
+
+/// The text fields of a bean that are indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Title,
+    Description,
+    Notes,
+    CloseReason,
+    Paths,
+    Labels,
+    Attempts,
+}
+
+impl Field {
+    const ALL: [Field; 7] = [
+        Field::Title,
+        Field::Description,
+        Field::Notes,
+        Field::CloseReason,
+        Field::Paths,
+        Field::Labels,
+        Field::Attempts,
+    ];
+
+    /// How much a match in this field counts towards the score.
+    fn weight(self) -> f64 {
+        match self {
+            Field::Title => 3.0,
+            Field::Description => 1.5,
+            Field::Paths | Field::Attempts => 1.2,
+            Field::Notes | Field::CloseReason => 1.0,
+            Field::Labels => 0.6,
+        }
+    }
+
+    /// The field named by a `field:` query prefix.
+    fn from_query_name(name: &str) -> Option<Field> {
+        match name {
+            "title" => Some(Field::Title),
+            "description" | "desc" => Some(Field::Description),
+            "notes" => Some(Field::Notes),
+            "reason" | "close_reason" => Some(Field::CloseReason),
+            "path" | "paths" => Some(Field::Paths),
+            "attempt" | "attempts" => Some(Field::Attempts),
+            _ => None,
+        }
+    }
+
+    fn slot(self) -> usize {
+        self as usize
+    }
+}
+
+/// Split text into lowercase alphanumeric tokens.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Index
+// ---------------------------------------------------------------------------
+
+/// Modification time and size of a bean file when it was indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Stamp {
+    modified: u64,
+    size: u64,
+}
+
+impl Stamp {
+    fn of(path: &Path) -> Option<Stamp> {
+        let meta = fs::metadata(path).ok()?;
+        let modified = meta
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+        Some(Stamp {
+            modified,
+            size: meta.len(),
+        })
+    }
+}
+
+/// One indexed bean file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Doc {
+    /// Path relative to `.beans/`, with `/` separators.
+    path: String,
+    stamp: Stamp,
+    id: String,
+    status: Status,
+    bean_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    archived: bool,
+    updated_at: DateTime<Utc>,
+    /// Token count per [`Field`].
+    lengths: [u32; 7],
+}
+
+/// Where a term occurs: one field of one document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Posting {
+    doc: u32,
+    field: Field,
+    positions: Vec<u32>,
+}
+
+/// The inverted index stored in `.beans/search.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    next_doc: u32,
+    docs: BTreeMap<u32, Doc>,
+    terms: BTreeMap<String, Vec<Posting>>,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        SearchIndex {
+            version: FORMAT_VERSION,
+            next_doc: 0,
+            docs: BTreeMap::new(),
+            terms: BTreeMap::new(),
+        }
+    }
+}
+
+/// A search result: the bean's ID, its file and its score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub id: String,
+    pub path: PathBuf,
+    pub score: f64,
+    pub archived: bool,
+}
+
+impl SearchIndex {
+    /// Index every active and archived bean from scratch.
+    pub fn build(beans_dir: &Path) -> Result<Self> {
+        let mut index = SearchIndex::default();
+        index.update(beans_dir)?;
+        Ok(index)
+    }
+
+    /// Load `.beans/search.json`, or an empty index if it is missing,
+    /// unreadable or from another format version.
+    pub fn load(beans_dir: &Path) -> Self {
+        fs::read_to_string(beans_dir.join(SEARCH_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<SearchIndex>(&contents).ok())
+            .filter(|index| index.version == FORMAT_VERSION)
+            .unwrap_or_default()
+    }
+
+    /// Save the index to `.beans/search.json`.
+    ///
+    /// The first write also adds the file to `.beans/.gitignore`, for projects
+    /// initialized before the index existed.
+    pub fn save(&self, beans_dir: &Path) -> Result<()> {
+        let path = beans_dir.join(SEARCH_FILE);
+        let first_write = !path.exists();
+        let json = serde_json::to_string(self).context("Failed to serialize search index")?;
+        atomic_write(&path, &json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        if first_write {
+            ensure_gitignored(beans_dir, SEARCH_FILE);
+        }
+        Ok(())
+    }
+
+    /// Load the index and bring it up to date with the bean files, saving
+    /// it if anything changed. This is the entry point for queries.
+    pub fn load_or_update(beans_dir: &Path) -> Result<Self> {
+        let mut index = Self::load(beans_dir);
+        if index.update(beans_dir)? {
+            index.save(beans_dir)?;
+        }
+        Ok(index)
+    }
+
+    /// Re-index the bean files whose mtime or size changed since they were
+    /// indexed, add new ones and drop deleted ones. Returns whether the
+    /// index changed.
+    pub fn update(&mut self, beans_dir: &Path) -> Result<bool> {
+        let on_disk = scan(beans_dir)?;
+
+        let stale: HashSet<u32> = self
+            .docs
+            .iter()
+            .filter(|(_, doc)| on_disk.get(&doc.path) != Some(&doc.stamp))
+            .map(|(num, _)| *num)
+            .collect();
+        let indexed: HashSet<String> = self
+            .docs
+            .iter()
+            .filter(|(num, _)| !stale.contains(num))
+            .map(|(_, doc)| doc.path.clone())
+            .collect();
+
+        let mut changed = !stale.is_empty();
+        self.remove_docs(&stale);
+
+        let mut fresh: Vec<(&String, &Stamp)> = on_disk
+            .iter()
+            .filter(|(path, _)| !indexed.contains(*path))
+            .collect();
+        fresh.sort_by(|a, b| a.0.cmp(b.0));
+        for (rel, stamp) in fresh {
+            let Ok(bean) = Bean::from_file(beans_dir.join(rel)) else {
+                continue;
+            };
+            self.insert(rel, *stamp, &bean);
+            changed = true;
+        }
+
+        Ok(changed)
+    }
+
+    /// Number of indexed bean files.
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    fn insert(&mut self, rel: &str, stamp: Stamp, bean: &Bean) {
+        let num = self.next_doc;
+        self.next_doc += 1;
+
+        let mut lengths = [0u32; 7];
+        let mut occurrences: HashMap<(String, Field), Vec<u32>> = HashMap::new();
+        let texts = field_texts(bean);
+        for (field, values) in &texts {
+            // Leave a gap between values so a phrase cannot span two paths
+            let mut position = 0u32;
+            for value in values {
+                for token in tokenize(value) {
+                    occurrences
+                        .entry((token, *field))
+                        .or_default()
+                        .push(position);
+                    position += 1;
+                    lengths[field.slot()] += 1;
+                }
+                position += 1;
+            }
+        }
+
+        for ((token, field), positions) in occurrences {
+            self.terms.entry(token).or_default().push(Posting {
+                doc: num,
+                field,
+                positions,
+            });
+        }
+
+        self.docs.insert(
+            num,
+            Doc {
+                path: rel.to_string(),
+                stamp,
+                id: bean.id.clone(),
+                status: bean.status,
+                bean_type: bean.bean_type.clone(),
+                labels: bean.labels.clone(),
+                archived: rel.starts_with("archive/"),
+                updated_at: bean.updated_at,
+                lengths,
+            },
+        );
+    }
+
+    fn remove_docs(&mut self, docs: &HashSet<u32>) {
+        if docs.is_empty() {
+            return;
+        }
+        for num in docs {
+            self.docs.remove(num);
+        }
+        self.terms.retain(|_, postings| {
+            postings.retain(|p| !docs.contains(&p.doc));
+            !postings.is_empty()
+        });
+    }
+
+    /// Run `query` and return the matching beans, best first.
+    ///
+    /// Closed and archived beans are only included when `all` is set or the
+    /// query filters on status.
+    pub fn search(&self, query: &Query, all: bool) -> Vec<Hit> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let all = all || !query.statuses.is_empty();
+        let in_scope = |doc: &Doc| {
+            (all || (!doc.archived && doc.status != Status::Closed))
+                && (query.statuses.is_empty() || query.statuses.contains(&doc.status))
+                && (query.types.is_empty() || query.types.contains(&doc.bean_type.to_lowercase()))
+                && query
+                    .labels
+                    .iter()
+                    .all(|label| doc.labels.iter().any(|l| l.to_lowercase() == *label))
+        };
+
+        let mut scores: HashMap<u32, f64> = self
+            .docs
+            .iter()
+            .filter(|(_, doc)| in_scope(doc))
+            .map(|(num, _)| (*num, 0.0))
+            .collect();
+
+        let averages = self.average_lengths();
+        for clause in &query.clauses {
+            let matches = self.match_clause(clause);
+            let matching_docs: HashSet<u32> = matches.keys().map(|(doc, _)| *doc).collect();
+            scores.retain(|doc, _| matching_docs.contains(doc));
+
+            let idf = self.idf(matching_docs.len());
+            for ((num, field), tf) in matches {
+                let Some(score) = scores.get_mut(&num) else {
+                    continue;
+                };
+                let length = f64::from(self.docs[&num].lengths[field.slot()]);
+                let average = averages[field.slot()].max(1.0);
+                let tf = f64::from(tf);
+                let saturated = tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / average));
+                *score += field.weight() * idf * saturated;
+            }
+        }
+
+        let mut hits: Vec<(&Doc, f64)> = scores
+            .into_iter()
+            .map(|(num, score)| (&self.docs[&num], score))
+            .collect();
+        hits.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| b.0.updated_at.cmp(&a.0.updated_at))
+                .then_with(|| natural_cmp(&a.0.id, &b.0.id))
+        });
+        hits.into_iter()
+            .map(|(doc, score)| Hit {
+                id: doc.id.clone(),
+                path: PathBuf::from(&doc.path),
+                score,
+                archived: doc.archived,
+            })
+            .collect()
+    }
+
+    /// Term frequency of `clause` per (document, field) it occurs in.
+    fn match_clause(&self, clause: &Clause) -> HashMap<(u32, Field), u32> {
+        let in_field = |p: &&Posting| clause.field.is_none_or(|f| p.field == f);
+        let mut counts: HashMap<(u32, Field), u32> = HashMap::new();
+
+        if clause.prefix && clause.tokens.len() == 1 {
+            let prefix = &clause.tokens[0];
+            for (_, postings) in self
+                .terms
+                .range::<String, _>(prefix.clone()..)
+                .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+            {
+                for p in postings.iter().filter(in_field) {
+                    *counts.entry((p.doc, p.field)).or_default() += p.positions.len() as u32;
+                }
+            }
+            return counts;
+        }
+
+        let Some(first) = clause.tokens.first().and_then(|t| self.terms.get(t)) else {
+            return counts;
+        };
+        if clause.tokens.len() == 1 {
+            for p in first.iter().filter(in_field) {
+                counts.insert((p.doc, p.field), p.positions.len() as u32);
+            }
+            return counts;
+        }
+
+        // Phrase: every following token at the next position in the same field
+        let rest: Vec<HashMap<(u32, Field), &Vec<u32>>> = clause.tokens[1..]
+            .iter()
+            .map(|token| {
+                self.terms
+                    .get(token)
+                    .map(|postings| {
+                        postings
+                            .iter()
+                            .map(|p| ((p.doc, p.field), &p.positions))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        for p in first.iter().filter(in_field) {
+            let key = (p.doc, p.field);
+            let Some(following) = rest
+                .iter()
+                .map(|postings| postings.get(&key))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let tf = p
+                .positions
+                .iter()
+                .filter(|&&start| {
+                    following.iter().zip(1u32..).all(|(positions, offset)| {
+                        positions.binary_search(&(start + offset)).is_ok()
+                    })
+                })
+                .count() as u32;
+            if tf > 0 {
+                counts.insert(key, tf);
+            }
+        }
+        counts
+    }
+
+    fn idf(&self, matching: usize) -> f64 {
+        let total = self.docs.len() as f64;
+        let matching = matching as f64;
+        (1.0 + (total - matching + 0.5) / (matching + 0.5)).ln()
+    }
+
+    fn average_lengths(&self) -> [f64; 7] {
+        let mut sums = [0f64; 7];
+        for doc in self.docs.values() {
+            for field in Field::ALL {
+                sums[field.slot()] += f64::from(doc.lengths[field.slot()]);
+            }
+        }
+        let count = self.docs.len().max(1) as f64;
+        sums.map(|sum| sum / count)
+    }
+}
+
+/// The text of each indexed field of a bean.
+fn field_texts(bean: &Bean) -> Vec<(Field, Vec<&str>)> {
+    vec![
+        (Field::Title, vec![bean.title.as_str()]),
+        (
+            Field::Description,
+            bean.description.as_deref().into_iter().collect(),
+        ),
+        (Field::Notes, bean.notes.as_deref().into_iter().collect()),
+        (
+            Field::CloseReason,
+            bean.close_reason.as_deref().into_iter().collect(),
+        ),
+        (
+            Field::Paths,
+            bean.paths.iter().map(String::as_str).collect(),
+        ),
+        (
+            Field::Labels,
+            bean.labels.iter().map(String::as_str).collect(),
+        ),
+        (
+            Field::Attempts,
+            bean.attempt_log
+                .iter()
+                .filter_map(|a| a.notes.as_deref())
+                .collect(),
+        ),
+    ]
+}
+
+/// Bean files under `.beans/` and `.beans/archive/`, keyed by their path
+/// relative to `.beans/`.
+fn scan(beans_dir: &Path) -> Result<HashMap<String, Stamp>> {
+    let mut files = HashMap::new();
+    scan_dir(beans_dir, "", &mut files, false)
+        .with_context(|| format!("Failed to read directory: {}", beans_dir.display()))?;
+    let archive = beans_dir.join("archive");
+    if archive.is_dir() {
+        scan_dir(&archive, "archive/", &mut files, true)?;
+    }
+    Ok(files)
+}
+
+fn scan_dir(
+    dir: &Path,
+    prefix: &str,
+    files: &mut HashMap<String, Stamp>,
+    recurse: bool,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            if recurse {
+                scan_dir(&path, &format!("{}{}/", prefix, name), files, true)?;
+            }
+        } else if is_bean_filename(name) {
+            if let Some(stamp) = Stamp::of(&path) {
+                files.insert(format!("{}{}", prefix, name), stamp);
+            }
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// One word, prefix or phrase that must occur in a matching bean.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clause {
+    /// Restrict the match to this field.
+    pub field: Option<Field>,
+    /// One token, or several for a phrase.
+    pub tokens: Vec<String>,
+    /// Match any token starting with the (single) token.
+    pub prefix: bool,
+}
+
+/// A parsed search query: every clause must match, and the bean must pass
+/// every filter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+    /// `status:` filters; a bean matches any of them.
+    pub statuses: Vec<Status>,
+    /// `label:` filters (lowercase); a bean must have all of them.
+    pub labels: Vec<String>,
+    /// `type:` filters (lowercase); a bean matches any of them.
+    pub types: Vec<String>,
+}
+
+impl Query {
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+            && self.statuses.is_empty()
+            && self.labels.is_empty()
+            && self.types.is_empty()
+    }
+}
+
+/// Parse a `bn recall` query.
+///
+/// Words are separated by whitespace and all must match. `"..."` quotes a
+/// phrase, a trailing `*` matches a prefix, and `field:value` restricts a
+/// word or phrase to one field (`title`, `description`, `notes`, `reason`,
+/// `path`, `attempts`). `status:`, `label:` and `type:` filter instead of
+/// matching text. A word the tokenizer splits (`src/auth.rs`) is matched as
+/// a phrase.
+pub fn parse_query(input: &str) -> Result<Query> {
+    let mut query = Query::default();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut word = String::new();
+        let mut quoted = false;
+        let mut name = None;
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() && !quoted {
+                break;
+            }
+            chars.next();
+            if c == '"' {
+                quoted = !quoted;
+            } else if c == ':' && !quoted && name.is_none() && is_filter_name(&word) {
+                name = Some(std::mem::take(&mut word).to_lowercase());
+            } else {
+                word.push(c);
+            }
+        }
+
+        match name.as_deref() {
+            Some("status") => {
+                let status = parse_status(&word.to_lowercase()).ok_or_else(|| {
+                    BeansError::Invalid(format!("Invalid status '{}' in query", word))
+                })?;
+                query.statuses.push(status);
+            }
+            Some("label") | Some("labels") => query.labels.push(word.to_lowercase()),
+            Some("type") => query.types.push(word.to_lowercase()),
+            Some(field) => {
+                let field = Field::from_query_name(field);
+                query.clauses.extend(clause(&word, field));
+            }
+            None => query.clauses.extend(clause(&word, None)),
+        }
+    }
+
+    Ok(query)
+}
+
+fn is_filter_name(word: &str) -> bool {
+    let word = word.to_lowercase();
+    matches!(word.as_str(), "status" | "label" | "labels" | "type")
+        || Field::from_query_name(&word).is_some()
+}
+
+fn clause(word: &str, field: Option<Field>) -> Option<Clause> {
+    let prefix = word.len() > 1 && word.ends_with('*');
+    let tokens = tokenize(word);
+    if tokens.is_empty() {
+        return None;
+    }
+    Some(Clause {
+        field,
+        prefix: prefix && tokens.len() == 1,
+        tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::title_to_slug;
+    use tempfile::TempDir;
+
+    fn write_bean(beans_dir: &Path, bean: &Bean) -> PathBuf {
+        let path = beans_dir.join(format!("{}-{}.md", bean.id, title_to_slug(&bean.title)));
+        bean.to_file(&path).unwrap();
+        path
+    }
+
+    fn ids(index: &SearchIndex, query: &str, all: bool) -> Vec<String> {
+        index
+            .search(&parse_query(query).unwrap(), all)
+            .into_iter()
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    fn setup() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir(&beans_dir).unwrap();
+
+        let mut parser = Bean::new("1", "Fix parser error recovery");
+        parser.labels = vec!["bug".to_string()];
+        parser.paths = vec!["src/parser.rs".to_string()];
+        write_bean(&beans_dir, &parser);
+
+        let mut docs = Bean::new("2", "Document the config format");
+        docs.description = Some("The parser reads YAML. Error messages list the line.".into());
+        write_bean(&beans_dir, &docs);
+
+        let mut closed = Bean::new("3", "Parse error in lexer");
+        closed.status = Status::Closed;
+        closed.close_reason = Some("Fixed by rewriting the tokenizer".into());
+        write_bean(&beans_dir, &closed);
+
+        (dir, beans_dir)
+    }
+
+    #[test]
+    fn tokenize_splits_and_lowercases() {
+        assert_eq!(
+            tokenize("Fix src/Auth.rs: RS256"),
+            vec!["fix", "src", "auth", "rs", "rs256"]
+        );
+    }
+
+    #[test]
+    fn parse_query_fields_phrases_and_filters() {
+        let query =
+            parse_query(r#"title:parser "parse error" label:Bug status:closed pars* desc:"a b""#)
+                .unwrap();
+        assert_eq!(query.statuses, vec![Status::Closed]);
+        assert_eq!(query.labels, vec!["bug"]);
+        assert_eq!(query.clauses.len(), 4);
+        assert_eq!(query.clauses[0].field, Some(Field::Title));
+        assert_eq!(query.clauses[1].tokens, vec!["parse", "error"]);
+        assert!(query.clauses[2].prefix);
+        assert_eq!(query.clauses[3].field, Some(Field::Description));
+        assert_eq!(query.clauses[3].tokens, vec!["a", "b"]);
+
+        // Unknown prefixes are plain text
+        let query = parse_query("http://example.com").unwrap();
+        assert_eq!(query.clauses[0].tokens, vec!["http", "example", "com"]);
+
+        assert!(parse_query("status:done").is_err());
+    }
+
+    #[test]
+    fn first_save_gitignores_the_index() {
+        let (_dir, beans_dir) = setup();
+        fs::write(beans_dir.join(".gitignore"), "index.yaml\n").unwrap();
+
+        SearchIndex::build(&beans_dir)
+            .unwrap()
+            .save(&beans_dir)
+            .unwrap();
+
+        let gitignore = fs::read_to_string(beans_dir.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|line| line == SEARCH_FILE));
+    }
+
+    #[test]
+    fn title_matches_rank_first() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        assert_eq!(ids(&index, "parser", false), vec!["1", "2"]);
+        assert_eq!(ids(&index, "title:parser", false), vec!["1"]);
+    }
+
+    #[test]
+    fn all_terms_must_match() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        assert_eq!(ids(&index, "parser yaml", false), vec!["2"]);
+        assert!(ids(&index, "parser kubernetes", false).is_empty());
+    }
+
+    #[test]
+    fn phrase_requires_adjacent_tokens() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        assert_eq!(ids(&index, "\"parser error\"", false), vec!["1"]);
+        // "parser" and "error" are in different sentences of bean 2
+        assert_eq!(ids(&index, "parser error", false), vec!["1", "2"]);
+        assert_eq!(ids(&index, "path:src/parser.rs", false), vec!["1"]);
+    }
+
+    #[test]
+    fn closed_beans_need_all_or_status_filter() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        assert!(!ids(&index, "lexer", false).contains(&"3".to_string()));
+        assert_eq!(ids(&index, "lexer", true), vec!["3"]);
+        assert_eq!(ids(&index, "status:closed", false), vec!["3"]);
+        assert_eq!(ids(&index, "label:bug", false), vec!["1"]);
+        assert_eq!(ids(&index, "reason:tokenizer", true), vec!["3"]);
+    }
+
+    #[test]
+    fn prefix_matches_word_starts() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        // "parser" and "parse" in titles, then "parser" in a description
+        assert_eq!(ids(&index, "pars*", true), vec!["1", "3", "2"]);
+    }
+
+    #[test]
+    fn update_reindexes_only_changed_files() {
+        let (_dir, beans_dir) = setup();
+        let index = SearchIndex::load_or_update(&beans_dir).unwrap();
+        assert_eq!(index.len(), 3);
+        assert!(beans_dir.join(SEARCH_FILE).exists());
+
+        let mut index = SearchIndex::load(&beans_dir);
+        assert!(!index.update(&beans_dir).unwrap());
+
+        // Edit one bean, delete another, add a third
+        let path = beans_dir.join("2-document-the-config-format.md");
+        let mut bean = Bean::from_file(&path).unwrap();
+        bean.title = "Document the TOML format".to_string();
+        bean.description = None;
+        bean.to_file(&path).unwrap();
+        fs::remove_file(beans_dir.join("1-fix-parser-error-recovery.md")).unwrap();
+        write_bean(&beans_dir, &Bean::new("4", "Parser benchmarks"));
+
+        assert!(index.update(&beans_dir).unwrap());
+        assert_eq!(index.len(), 3);
+        assert_eq!(ids(&index, "toml", false), vec!["2"]);
+        assert_eq!(ids(&index, "parser", false), vec!["4"]);
+        assert!(!index.terms.contains_key("recovery"));
+    }
+
+    #[test]
+    fn indexes_archived_beans() {
+        let (_dir, beans_dir) = setup();
+        let archive = beans_dir.join("archive/2026/01");
+        fs::create_dir_all(&archive).unwrap();
+        let mut old = Bean::new("7", "Old parser prototype");
+        old.status = Status::Closed;
+        old.to_file(archive.join("7-old-parser-prototype.md"))
+            .unwrap();
+
+        let index = SearchIndex::build(&beans_dir).unwrap();
+        let hits = index.search(&parse_query("prototype").unwrap(), true);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].archived);
+        assert_eq!(
+            hits[0].path,
+            PathBuf::from("archive/2026/01/7-old-parser-prototype.md")
+        );
+        assert!(ids(&index, "prototype", false).is_empty());
+    }
+
+    #[test]
+    fn stale_format_version_is_rebuilt() {
+        let (_dir, beans_dir) = setup();
+        fs::write(beans_dir.join(SEARCH_FILE), r#"{"version":0}"#).unwrap();
+        let index = SearchIndex::load_or_update(&beans_dir).unwrap();
+        assert_eq!(index.len(), 3);
+    }
+}
@@ -15,6 +15,7 @@ use anyhow::{anyhow, Context, Result};
 use crate::commands::agents::{save_agents, AgentEntry};
 use crate::commands::logs;
 use crate::config::{resolve_identity, Config};
+use crate::worktree::{self, AgentWorktree, MergeResult};
 
 // ---------------------------------------------------------------------------
 // Types
@@ -47,6 +48,8 @@ pub struct AgentProcess {
     pub started_at: Instant,
     pub log_path: PathBuf,
     child: Child,
+    /// Dedicated worktree the agent runs in (config `worktree: true`).
+    worktree: Option<(PathBuf, AgentWorktree)>,
 }
 
 /// Result of a completed agent process.
@@ -108,8 +111,9 @@ impl Spawner {
     /// 2. Substitutes `{id}` with the bean ID
     /// 3. Claims the bean via `bn claim`
     /// 4. Opens a log file for stdout/stderr capture
-    /// 5. Spawns the process via `sh -c <cmd>`
-    /// 6. Registers the process in the agents persistence file
+    /// 5. Creates a dedicated git worktree if `config.worktree` is set
+    /// 6. Spawns the process via `sh -c <cmd>` (inside the worktree, if any)
+    /// 7. Registers the process in the agents persistence file
     pub fn spawn(
         &mut self,
         bean_id: &str,
@@ -152,16 +156,34 @@ impl Spawner {
             .try_clone()
             .context("Failed to clone log file handle")?;
 
+        // Isolate the agent in its own worktree
+        let agent_worktree = match (config.worktree, beans_dir) {
+            (true, Some(dir)) => match worktree::prepare_agent_worktree(dir, bean_id) {
+                Ok(wt) => Some((dir.to_path_buf(), wt)),
+                Err(e) => {
+                    let _ = release_bean(bean_id);
+                    return Err(anyhow!("Failed to create worktree for {}: {}", bean_id, e));
+                }
+            },
+            _ => None,
+        };
+
         // Spawn the process
-        let child = match Command::new("sh")
+        let mut command = Command::new("sh");
+        command
             .args(["-c", &cmd])
             .stdout(log_file)
-            .stderr(log_stderr)
-            .spawn()
-        {
+            .stderr(log_stderr);
+        if let Some((_, ref wt)) = agent_worktree {
+            command.current_dir(&wt.agent_dir);
+        }
+        let child = match command.spawn() {
             Ok(child) => child,
             Err(e) => {
-                // Release claim on spawn failure
+                // Release claim (and discard the worktree) on spawn failure
+                if let Some((ref dir, ref wt)) = agent_worktree {
+                    let _ = worktree::finish_agent_worktree(dir, wt, bean_id, false);
+                }
                 let _ = release_bean(bean_id);
                 return Err(anyhow!("Failed to spawn agent for {}: {}", bean_id, e));
             }
@@ -182,6 +204,7 @@ impl Spawner {
                 started_at: Instant::now(),
                 log_path,
                 child,
+                worktree: agent_worktree,
             },
         );
 
@@ -192,7 +215,9 @@ impl Spawner {
     ///
     /// Calls `try_wait()` on each running process. Completed agents are
     /// removed from the running map and returned. On failure, the bean
-    /// claim is released.
+    /// claim is released. Agent worktrees are merged back to main on success
+    /// and discarded on failure unless they hold agent work; a merge conflict
+    /// counts as a failure.
     pub fn check_completed(&mut self) -> Vec<CompletedAgent> {
         let mut completed = Vec::new();
         let mut finished_ids = Vec::new();
@@ -200,9 +225,28 @@ impl Spawner {
         for (id, proc) in self.running.iter_mut() {
             match proc.child.try_wait() {
                 Ok(Some(status)) => {
-                    let success = status.success();
+                    let mut success = status.success();
                     let exit_code = status.code();
 
+                    if let Some((ref dir, ref wt)) = proc.worktree {
+                        match worktree::finish_agent_worktree(dir, wt, id, success) {
+                            Ok(Some(MergeResult::Conflict { files })) => {
+                                eprintln!(
+                                    "Merge conflict for {} in {:?} — resolve in {}",
+                                    id,
+                                    files,
+                                    wt.info.worktree_path.display()
+                                );
+                                success = false;
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                eprintln!("Worktree for {}: {:#}", id, e);
+                                success = false;
+                            }
+                        }
+                    }
+
                     if !success {
                         let _ = release_bean(id);
                     }
@@ -224,6 +268,9 @@ impl Spawner {
                 Ok(None) => {} // Still running
                 Err(e) => {
                     eprintln!("Error checking agent for {}: {}", id, e);
+                    if let Some((ref dir, ref wt)) = proc.worktree {
+                        let _ = worktree::finish_agent_worktree(dir, wt, id, false);
+                    }
                     let _ = release_bean(id);
                     let _ = finish_agent(id, Some(-1));
                     completed.push(CompletedAgent {
@@ -265,11 +312,17 @@ impl Spawner {
         self.running.values().collect()
     }
 
-    /// Kill all running agent processes and release their claims.
+    /// Kill all running agent processes, discard their worktrees (keeping any
+    /// with agent work), and release their claims.
     pub fn kill_all(&mut self) {
         for (id, proc) in self.running.iter_mut() {
             let _ = proc.child.kill();
             let _ = proc.child.wait(); // Reap the zombie
+            if let Some((ref dir, ref wt)) = proc.worktree {
+                if let Err(e) = worktree::finish_agent_worktree(dir, wt, id, false) {
+                    eprintln!("Worktree for {}: {:#}", id, e);
+                }
+            }
             let _ = release_bean(id);
             let _ = finish_agent(id, Some(-9));
         }
@@ -453,6 +506,7 @@ mod tests {
                 started_at: Instant::now(),
                 log_path: log_path.clone(),
                 child,
+                worktree: None,
             },
         );
 
@@ -555,6 +609,7 @@ mod tests {
                 started_at: Instant::now(),
                 log_path: log_path.clone(),
                 child,
+                worktree: None,
             },
         );
 
@@ -594,6 +649,7 @@ mod tests {
                 started_at: Instant::now(),
                 log_path: log_path.clone(),
                 child,
+                worktree: None,
             },
         );
 
@@ -632,6 +688,7 @@ mod tests {
                 started_at: Instant::now(),
                 log_path: log_path.clone(),
                 child,
+                worktree: None,
             },
         );
 
@@ -657,6 +714,7 @@ mod tests {
             extends: vec![],
             rules_file: None,
             file_locking: false,
+            worktree: false,
             on_close: None,
             on_fail: None,
             post_plan: None,
@@ -664,8 +722,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
-            user: None,
-            user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
 
         let result = spawner.spawn("1", "Test", AgentAction::Implement, &config, None);
@@ -689,6 +756,7 @@ mod tests {
             extends: vec![],
             rules_file: None,
             file_locking: false,
+            worktree: false,
             on_close: None,
             on_fail: None,
             post_plan: None,
@@ -696,8 +764,17 @@ mod tests {
             review: None,
             user: None,
             user_email: None,
-            user: None,
-            user_email: None,
+            max_cost_per_bean: None,
+            max_cost_per_run: None,
+            max_cost_per_day: None,
+            max_tokens_per_bean: None,
+            max_tokens_per_run: None,
+            max_tokens_per_day: None,
+            agent: None,
+            views: Default::default(),
+            sandbox: None,
+            verify_cache: None,
+            test_reports: Vec::new(),
         };
 
         let result = spawner.spawn("1", "Test", AgentAction::Plan, &config, None);
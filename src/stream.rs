@@ -1,3 +1,6 @@
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
 use serde::Serialize;
 
 /// JSON-line events emitted by `bn run --json-stream` for programmatic consumers.
@@ -72,6 +75,28 @@ pub enum StreamEvent {
         #[serde(skip_serializing_if = "Option::is_none")]
         failure_summary: Option<String>,
     },
+    /// Emitted when a failed bean is queued for another attempt by its
+    /// `on_fail` policy.
+    BeanRetry {
+        id: String,
+        /// The attempt that will run next.
+        attempt: u32,
+        max_attempts: u32,
+        delay_secs: u64,
+        /// Set when the bean was escalated to this priority.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        priority: Option<u8>,
+        error: Option<String>,
+    },
+    /// Emitted when a budget limit is hit: either an agent was killed (`id`
+    /// is set) or dispatch stopped because the run/day budget is spent.
+    BudgetExceeded {
+        /// Which limit was crossed: `bean`, `run` or `day`.
+        scope: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        reason: String,
+    },
     RoundEnd {
         round: usize,
         success_count: usize,
@@ -114,13 +139,43 @@ pub struct FileOverlapInfo {
     pub shared_files: Vec<String>,
 }
 
-/// Write a single JSON line to stdout for the given event.
+/// Where events go instead of stdout while a library run is active.
+static SINK: Mutex<Option<Sender<StreamEvent>>> = Mutex::new(None);
+
+/// Write a single JSON line to stdout for the given event, or send it to
+/// the channel installed by [`redirect`].
 pub fn emit(event: &StreamEvent) {
+    let sink = SINK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(ref sender) = *sink {
+        let _ = sender.send(event.clone());
+        return;
+    }
+    drop(sink);
     if let Ok(json) = serde_json::to_string(event) {
         println!("{json}");
     }
 }
 
+/// Send every event emitted in this process to `sender` until the returned
+/// guard is dropped. Returns `None` if events are already redirected.
+pub fn redirect(sender: Sender<StreamEvent>) -> Option<RedirectGuard> {
+    let mut sink = SINK.lock().unwrap_or_else(|e| e.into_inner());
+    if sink.is_some() {
+        return None;
+    }
+    *sink = Some(sender);
+    Some(RedirectGuard(()))
+}
+
+/// Restores stdout output when dropped; see [`redirect`].
+pub struct RedirectGuard(());
+
+impl Drop for RedirectGuard {
+    fn drop(&mut self) {
+        *SINK.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
 /// Convenience wrapper to emit an `Error` event.
 pub fn emit_error(message: &str) {
     emit(&StreamEvent::Error {
@@ -302,6 +357,36 @@ mod tests {
         assert_eq!(json["unblocked_by"], "2");
     }
 
+    #[test]
+    fn stream_bean_retry_serializes() {
+        let event = StreamEvent::BeanRetry {
+            id: "4".into(),
+            attempt: 2,
+            max_attempts: 3,
+            delay_secs: 30,
+            priority: None,
+            error: Some("Exit code 1".into()),
+        };
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "bean_retry");
+        assert_eq!(json["attempt"], 2);
+        assert_eq!(json["delay_secs"], 30);
+        assert!(json.get("priority").is_none());
+    }
+
+    #[test]
+    fn stream_budget_exceeded_serializes() {
+        let event = StreamEvent::BudgetExceeded {
+            scope: "run".into(),
+            id: None,
+            reason: "run cost $5.02 exceeds limit $5.00".into(),
+        };
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "budget_exceeded");
+        assert_eq!(json["scope"], "run");
+        assert!(json.get("id").is_none());
+    }
+
     #[test]
     fn stream_bean_start_with_enriched_fields() {
         let event = StreamEvent::BeanStart {
@@ -0,0 +1,590 @@
+//! Structured test results from verify output.
+//!
+//! A failed verify run leaves a truncated blob of output in the bean's
+//! history; the next agent has to dig "3 of 120 tests failed: a, b, c" out of
+//! it. This module recognises the summary output of the common test runners
+//! and turns it into a [`TestSummary`]:
+//!
+//! - `cargo test` (libtest)
+//! - `pytest`
+//! - `jest` and `vitest`
+//! - `go test`
+//! - JUnit XML, printed by the verify command or written to the files listed
+//!   under `test_reports:` in config
+//!
+//! ```yaml
+//! test_reports: ["target/nextest/ci/junit.xml"]
+//! ```
+//!
+//! Parsing is best effort: output that matches no runner yields `None` and
+//! the raw snippet is all there is, as before.
+
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+use regex::Regex;
+
+use crate::bean::TestSummary;
+
+/// Failing test names kept per summary.
+const MAX_FAILING: usize = 50;
+
+/// Assertion messages are cut to this many characters.
+const MAX_MESSAGE_CHARS: usize = 500;
+
+/// Report files written this long before the verify run started still
+/// count, to allow for coarse filesystem timestamps.
+const MTIME_SLACK: Duration = Duration::from_secs(2);
+
+static ANSI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+
+/// Parse test results out of verify output. Returns `None` if the output
+/// doesn't look like any supported runner's.
+pub fn parse(output: &str) -> Option<TestSummary> {
+    let output = ANSI.replace_all(output, "");
+    if output.contains("<testsuite") {
+        if let Some(summary) = parse_junit(&output) {
+            return Some(summary);
+        }
+    }
+    parse_cargo(&output)
+        .or_else(|| parse_pytest(&output))
+        .or_else(|| parse_jest(&output))
+        .or_else(|| parse_go(&output))
+}
+
+/// Test results for a verify run that started at `since`: the configured
+/// JUnit reports written during the run if there are any, otherwise
+/// whatever [`parse`] finds in the output.
+pub fn collect(
+    project_root: &Path,
+    reports: &[String],
+    since: SystemTime,
+    output: &str,
+) -> Option<TestSummary> {
+    let since = since.checked_sub(MTIME_SLACK).unwrap_or(since);
+    let mut combined: Option<TestSummary> = None;
+    for pattern in reports {
+        let full = project_root.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            let fresh = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified >= since);
+            if !fresh {
+                continue;
+            }
+            let Some(summary) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|xml| parse_junit(&xml))
+            else {
+                continue;
+            };
+            match combined {
+                Some(ref mut total) => merge(total, summary),
+                None => combined = Some(summary),
+            }
+        }
+    }
+    combined.or_else(|| parse(output))
+}
+
+/// Add `other`'s counts and failures to `total`.
+fn merge(total: &mut TestSummary, other: TestSummary) {
+    total.passed += other.passed;
+    total.failed += other.failed;
+    total.ignored += other.ignored;
+    for name in other.failing {
+        push_failing(total, name);
+    }
+    if total.message.is_none() {
+        total.message = other.message;
+    }
+}
+
+fn push_failing(summary: &mut TestSummary, name: String) {
+    if summary.failing.len() < MAX_FAILING && !summary.failing.contains(&name) {
+        summary.failing.push(name);
+    }
+}
+
+fn set_message(summary: &mut TestSummary, message: &str) {
+    let message = message.trim();
+    if summary.message.is_some() || message.is_empty() {
+        return;
+    }
+    let message: String = message.chars().take(MAX_MESSAGE_CHARS).collect();
+    summary.message = Some(message.trim_end().to_string());
+}
+
+// ---------------------------------------------------------------------------
+// cargo test
+// ---------------------------------------------------------------------------
+
+static CARGO_RESULT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap()
+});
+static CARGO_FAILED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap());
+static CARGO_PANIC_OLD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"panicked at '(.*)', \S+$").unwrap());
+
+/// `test result: FAILED. 1 passed; 2 failed; 0 ignored; ...`, one line per
+/// test binary.
+fn parse_cargo(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    let lines: Vec<&str> = output.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(caps) = CARGO_RESULT.captures(line) {
+            found = true;
+            summary.passed += caps[1].parse::<u32>().unwrap_or(0);
+            summary.failed += caps[2].parse::<u32>().unwrap_or(0);
+            summary.ignored += caps[3].parse::<u32>().unwrap_or(0);
+        } else if let Some(caps) = CARGO_FAILED.captures(line) {
+            push_failing(&mut summary, caps[1].to_string());
+        } else if line.starts_with("thread '") && line.contains("panicked at ") {
+            if let Some(caps) = CARGO_PANIC_OLD.captures(line) {
+                set_message(&mut summary, &caps[1]);
+            } else {
+                // Since Rust 1.73 the message follows the location line
+                let message = lines[i + 1..]
+                    .iter()
+                    .take_while(|l| {
+                        !l.trim().is_empty()
+                            && !l.starts_with("note:")
+                            && !l.starts_with("stack backtrace:")
+                    })
+                    .take(5)
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                set_message(&mut summary, &message);
+            }
+        }
+    }
+    found.then_some(summary)
+}
+
+// ---------------------------------------------------------------------------
+// pytest
+// ---------------------------------------------------------------------------
+
+static PYTEST_RESULT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^=*\s*(\d+ [a-z]+(?:, \d+ [a-z]+)*) in [\d.]+s\b").unwrap());
+static PYTEST_FAILED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:FAILED|ERROR) (\S+)(?: - (.*))?$").unwrap());
+
+/// `==== 2 failed, 10 passed, 1 skipped in 0.12s ====` (or the same without
+/// the rule under `-q`), plus the `FAILED path::test - message` lines.
+fn parse_pytest(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for line in output.lines() {
+        if let Some(caps) = PYTEST_RESULT.captures(line) {
+            for part in caps[1].split(", ") {
+                let Some((count, kind)) = part.split_once(' ') else {
+                    continue;
+                };
+                let count: u32 = count.parse().unwrap_or(0);
+                match kind {
+                    "passed" | "xpassed" => summary.passed += count,
+                    "failed" | "error" | "errors" => summary.failed += count,
+                    "skipped" | "xfailed" => summary.ignored += count,
+                    _ => {}
+                }
+            }
+            found = true;
+        } else if let Some(caps) = PYTEST_FAILED.captures(line) {
+            push_failing(&mut summary, caps[1].to_string());
+            if let Some(message) = caps.get(2) {
+                set_message(&mut summary, message.as_str());
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+    if summary.message.is_none() {
+        // The `E   assert 1 == 2` lines of the first failure
+        if let Some(line) = output.lines().find(|l| l.starts_with("E ")) {
+            set_message(&mut summary, &line[1..]);
+        }
+    }
+    Some(summary)
+}
+
+// ---------------------------------------------------------------------------
+// jest / vitest
+// ---------------------------------------------------------------------------
+
+static JEST_RESULT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*Tests:?\s+(\d+ [a-z]+.*)$").unwrap());
+static JEST_COUNT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+) (failed|passed|skipped|todo|pending)").unwrap());
+
+/// jest's `Tests: 1 failed, 5 passed, 6 total` with `● suite › name`
+/// headers, or vitest's `Tests  1 failed | 5 passed (6)` with
+/// `FAIL  file > suite > name` headers.
+fn parse_jest(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    let mut in_failure = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(caps) = JEST_RESULT.captures(line) {
+            for count in JEST_COUNT.captures_iter(&caps[1]) {
+                let n: u32 = count[1].parse().unwrap_or(0);
+                match &count[2] {
+                    "passed" => summary.passed += n,
+                    "failed" => summary.failed += n,
+                    _ => summary.ignored += n,
+                }
+            }
+            found = true;
+            in_failure = false;
+            continue;
+        }
+
+        let header = if let Some(name) = trimmed.strip_prefix("● ") {
+            (!name.starts_with("Console") && !name.starts_with("Test suite failed")).then_some(name)
+        } else if let Some(rest) = trimmed.strip_prefix("FAIL ") {
+            rest.split_once(" > ").map(|(_, name)| name)
+        } else {
+            None
+        };
+        if let Some(name) = header {
+            push_failing(&mut summary, name.trim().to_string());
+            in_failure = true;
+        } else if in_failure && !trimmed.is_empty() {
+            // The first line under a failure header is the assertion
+            set_message(&mut summary, trimmed);
+            in_failure = false;
+        }
+    }
+    found.then_some(summary)
+}
+
+// ---------------------------------------------------------------------------
+// go test
+// ---------------------------------------------------------------------------
+
+static GO_RESULT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^--- (PASS|FAIL|SKIP): (\S+)").unwrap());
+static GO_LOG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+(\S+_test\.go:\d+: .*)$").unwrap());
+
+/// `--- FAIL: TestName (0.00s)` lines. Passing tests are only listed under
+/// `go test -v`, so without it the passed count is zero.
+fn parse_go(output: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    // Under -v a test's log lines come before its `--- FAIL`, otherwise after
+    let mut logged: Option<String> = None;
+    let mut after_fail = false;
+    for line in output.lines() {
+        if let Some(caps) = GO_RESULT.captures(line) {
+            found = true;
+            after_fail = false;
+            match &caps[1] {
+                "PASS" => summary.passed += 1,
+                "FAIL" => {
+                    summary.failed += 1;
+                    push_failing(&mut summary, caps[2].to_string());
+                    match logged.take() {
+                        Some(message) => set_message(&mut summary, &message),
+                        None => after_fail = true,
+                    }
+                }
+                _ => summary.ignored += 1,
+            }
+            logged = None;
+        } else if line.starts_with("=== RUN") {
+            logged = None;
+            after_fail = false;
+        } else if let Some(caps) = GO_LOG.captures(line) {
+            if after_fail {
+                set_message(&mut summary, &caps[1]);
+            } else if logged.is_none() {
+                logged = Some(caps[1].to_string());
+            }
+        }
+    }
+    found.then_some(summary)
+}
+
+// ---------------------------------------------------------------------------
+// JUnit XML
+// ---------------------------------------------------------------------------
+
+static JUNIT_CASE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)").unwrap());
+static JUNIT_FAILURE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(failure|error)\b([^>]*?)(?:/>|>(.*?)</(?:failure|error)>)").unwrap()
+});
+static JUNIT_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bname\s*=\s*"([^"]*)""#).unwrap());
+static JUNIT_MESSAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bmessage\s*=\s*"([^"]*)""#).unwrap());
+
+/// Count the `<testcase>` elements of a JUnit XML report. A case with a
+/// `<failure>` or `<error>` child failed, one with `<skipped>` was ignored.
+pub fn parse_junit(xml: &str) -> Option<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut found = false;
+    for case in JUNIT_CASE.captures_iter(xml) {
+        found = true;
+        let body = case.get(2).map_or("", |m| m.as_str());
+        if let Some(failure) = JUNIT_FAILURE.captures(body) {
+            summary.failed += 1;
+            if let Some(name) = JUNIT_NAME.captures(&case[1]) {
+                push_failing(&mut summary, unescape(&name[1]));
+            }
+            let message = JUNIT_MESSAGE
+                .captures(&failure[2])
+                .map(|m| unescape(&m[1]))
+                .filter(|m| !m.trim().is_empty())
+                .or_else(|| {
+                    failure.get(3).and_then(|text| {
+                        let text = unescape(text.as_str());
+                        text.lines()
+                            .map(str::trim)
+                            .find(|l| !l.is_empty())
+                            .map(str::to_string)
+                    })
+                });
+            if let Some(message) = message {
+                set_message(&mut summary, &message);
+            }
+        } else if body.contains("<skipped") {
+            summary.ignored += 1;
+        } else {
+            summary.passed += 1;
+        }
+    }
+    found.then_some(summary)
+}
+
+fn unescape(s: &str) -> String {
+    let s = s.strip_prefix("<![CDATA[").unwrap_or(s);
+    let s = s.strip_suffix("]]>").unwrap_or(s);
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn cargo_test_output() {
+        let output = "\
+running 3 tests
+test parser::parses ... ok
+test parser::rejects_empty ... FAILED
+test parser::slow ... ignored
+
+failures:
+
+---- parser::rejects_empty stdout ----
+thread 'parser::rejects_empty' panicked at src/parser.rs:40:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    parser::rejects_empty
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+running 2 tests
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+        let summary = parse(output).unwrap();
+        assert_eq!(
+            summary,
+            TestSummary {
+                passed: 3,
+                failed: 1,
+                ignored: 1,
+                failing: vec!["parser::rejects_empty".to_string()],
+                message: Some("assertion `left == right` failed\n  left: 1\n right: 2".to_string()),
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "1 of 4 tests failed (1 ignored): parser::rejects_empty"
+        );
+    }
+
+    #[test]
+    fn cargo_old_panic_format() {
+        let output = "\
+test it_works ... FAILED
+thread 'it_works' panicked at 'assertion failed: ready', src/lib.rs:3:5
+test result: FAILED. 0 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        let summary = parse(output).unwrap();
+        assert_eq!(summary.message.as_deref(), Some("assertion failed: ready"));
+    }
+
+    #[test]
+    fn pytest_output() {
+        let output = "\
+tests/test_math.py .F.s                                          [100%]
+
+=================================== FAILURES ===================================
+___________________________________ test_add ___________________________________
+
+    def test_add():
+>       assert add(1, 1) == 3
+E       assert 2 == 3
+
+tests/test_math.py:4: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_math.py::test_add - assert 2 == 3
+==================== 1 failed, 2 passed, 1 skipped in 0.05s ====================
+";
+        let summary = parse(output).unwrap();
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.ignored, 1);
+        assert_eq!(summary.failing, vec!["tests/test_math.py::test_add"]);
+        assert_eq!(summary.message.as_deref(), Some("assert 2 == 3"));
+
+        let quiet = parse("3 passed in 0.01s\n").unwrap();
+        assert_eq!((quiet.passed, quiet.failed), (3, 0));
+    }
+
+    #[test]
+    fn jest_output() {
+        let output = "\
+FAIL src/math.test.js
+  math
+    ✓ subtracts (2 ms)
+    ✕ adds (3 ms)
+
+  ● math › adds
+
+    expect(received).toBe(expected) // Object.is equality
+
+    Expected: 3
+    Received: 2
+
+Test Suites: 1 failed, 1 total
+Tests:       1 failed, 1 skipped, 1 passed, 3 total
+";
+        let summary = parse(output).unwrap();
+        assert_eq!((summary.passed, summary.failed, summary.ignored), (1, 1, 1));
+        assert_eq!(summary.failing, vec!["math › adds"]);
+        assert_eq!(
+            summary.message.as_deref(),
+            Some("expect(received).toBe(expected) // Object.is equality")
+        );
+    }
+
+    #[test]
+    fn vitest_output() {
+        let output = "\
+ \x1b[31m❯\x1b[39m src/math.test.ts (2 tests | 1 failed) 5ms
+   × math > adds
+
+⎯⎯⎯⎯⎯⎯⎯ Failed Tests 1 ⎯⎯⎯⎯⎯⎯⎯
+
+ FAIL  src/math.test.ts > math > adds
+AssertionError: expected 2 to be 3 // Object.is equality
+
+ Test Files  1 failed (1)
+      Tests  1 failed | 1 passed (2)
+";
+        let summary = parse(output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.failing, vec!["math > adds"]);
+        assert_eq!(
+            summary.message.as_deref(),
+            Some("AssertionError: expected 2 to be 3 // Object.is equality")
+        );
+    }
+
+    #[test]
+    fn go_test_output() {
+        let output = "\
+=== RUN   TestSub
+--- PASS: TestSub (0.00s)
+=== RUN   TestAdd
+    add_test.go:8: got 2, want 3
+--- FAIL: TestAdd (0.00s)
+FAIL
+FAIL\texample.com/m\t0.001s
+";
+        let summary = parse(output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.failing, vec!["TestAdd"]);
+        assert_eq!(
+            summary.message.as_deref(),
+            Some("add_test.go:8: got 2, want 3")
+        );
+    }
+
+    #[test]
+    fn junit_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="pkg" tests="4" failures="1" skipped="1">
+    <testcase classname="pkg" name="passes" time="0.01"/>
+    <testcase classname="pkg" name="also_passes"></testcase>
+    <testcase classname="pkg" name="fails &amp; burns">
+      <failure message="expected &lt;3&gt; but was &lt;2&gt;" type="AssertionError">trace</failure>
+    </testcase>
+    <testcase classname="pkg" name="skipped"><skipped/></testcase>
+  </testsuite>
+</testsuites>
+"#;
+        let summary = parse(xml).unwrap();
+        assert_eq!((summary.passed, summary.failed, summary.ignored), (2, 1, 1));
+        assert_eq!(summary.failing, vec!["fails & burns"]);
+        assert_eq!(summary.message.as_deref(), Some("expected <3> but was <2>"));
+    }
+
+    #[test]
+    fn unrecognised_output() {
+        assert_eq!(parse("error: could not compile `foo`\n"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn collect_prefers_fresh_reports() {
+        let dir = TempDir::new().unwrap();
+        let started = SystemTime::now();
+        fs::create_dir_all(dir.path().join("reports")).unwrap();
+        fs::write(
+            dir.path().join("reports/a.xml"),
+            r#"<testcase name="a"/><testcase name="b"><error message="boom"/></testcase>"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("reports/b.xml"), r#"<testcase name="c"/>"#).unwrap();
+        let output = "test result: ok. 9 passed; 0 failed; 0 ignored";
+        let reports = vec!["reports/*.xml".to_string()];
+
+        let summary = collect(dir.path(), &reports, started, output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (2, 1));
+        assert_eq!(summary.failing, vec!["b"]);
+        assert_eq!(summary.message.as_deref(), Some("boom"));
+
+        // Reports left over from an earlier run are ignored
+        let later = started + Duration::from_secs(60);
+        let summary = collect(dir.path(), &reports, later, output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (9, 0));
+    }
+}
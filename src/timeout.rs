@@ -1,4 +1,5 @@
 use std::io::{BufRead, BufReader, Read};
+use std::ops::ControlFlow;
 use std::process::Child;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
@@ -21,14 +22,17 @@ pub enum MonitorResult {
     TotalTimeout,
     /// Idle timeout exceeded (no output) — process was killed.
     IdleTimeout,
+    /// `on_line` asked to stop (e.g. a budget was exceeded) — process was killed.
+    Stopped,
 }
 
 /// Monitor a child process's stdout, enforcing total and idle timeouts.
 ///
 /// Reads stdout line-by-line via a background reader thread. On each line the
 /// idle timer is reset and `on_line` is called. If the total elapsed time or
-/// idle time exceeds the configured limits the process is killed with SIGKILL
-/// and the corresponding [`MonitorResult`] is returned.
+/// idle time exceeds the configured limits, or `on_line` returns
+/// [`ControlFlow::Break`], the process is killed with SIGKILL and the
+/// corresponding [`MonitorResult`] is returned.
 ///
 /// `stdout` is passed separately so the caller can `child.stdout.take()` and
 /// hand it in while retaining ownership of the `Child` (needed to call
@@ -37,7 +41,7 @@ pub fn monitor_process<R: Read + Send + 'static>(
     child: &mut Child,
     stdout: R,
     config: &TimeoutConfig,
-    mut on_line: impl FnMut(&str),
+    mut on_line: impl FnMut(&str) -> ControlFlow<()>,
 ) -> MonitorResult {
     let start = Instant::now();
     let mut last_activity = Instant::now();
@@ -81,7 +85,10 @@ pub fn monitor_process<R: Read + Send + 'static>(
         match rx.recv_timeout(poll) {
             Ok(Some(text)) => {
                 last_activity = Instant::now();
-                on_line(&text);
+                if on_line(&text).is_break() {
+                    kill_process(child);
+                    return MonitorResult::Stopped;
+                }
             }
             Ok(None) => {
                 // EOF — process closed stdout.
@@ -142,6 +149,7 @@ mod tests {
         let mut lines = Vec::new();
         let result = monitor_process(&mut child, stdout, &config, |line| {
             lines.push(line.to_string());
+            ControlFlow::Continue(())
         });
 
         assert_eq!(result, MonitorResult::Completed);
@@ -162,7 +170,7 @@ mod tests {
             idle_timeout: Duration::ZERO,
         };
 
-        let result = monitor_process(&mut child, stdout, &config, |_| {});
+        let result = monitor_process(&mut child, stdout, &config, |_| ControlFlow::Continue(()));
         assert_eq!(result, MonitorResult::TotalTimeout);
     }
 
@@ -183,6 +191,7 @@ mod tests {
         let mut lines = Vec::new();
         let result = monitor_process(&mut child, stdout, &config, |line| {
             lines.push(line.to_string());
+            ControlFlow::Continue(())
         });
 
         assert_eq!(lines, vec!["start"]);
@@ -203,6 +212,7 @@ mod tests {
         let mut lines = Vec::new();
         let result = monitor_process(&mut child, stdout, &config, |line| {
             lines.push(line.to_string());
+            ControlFlow::Continue(())
         });
 
         assert_eq!(result, MonitorResult::Completed);
@@ -226,9 +236,34 @@ mod tests {
         let mut lines = Vec::new();
         let result = monitor_process(&mut child, stdout, &config, |line| {
             lines.push(line.to_string());
+            ControlFlow::Continue(())
         });
 
         assert_eq!(result, MonitorResult::Completed);
         assert_eq!(lines, vec!["line1", "line2", "line3", "line4", "line5"]);
     }
+
+    #[test]
+    fn timeout_callback_break_kills_process() {
+        let mut child = Command::new("bash")
+            .args(["-c", "echo one; echo two; sleep 60"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdout = child.stdout.take().unwrap();
+        let config = TimeoutConfig {
+            total_timeout: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(30),
+        };
+
+        let mut lines = Vec::new();
+        let result = monitor_process(&mut child, stdout, &config, |line| {
+            lines.push(line.to_string());
+            ControlFlow::Break(())
+        });
+
+        assert_eq!(result, MonitorResult::Stopped);
+        assert_eq!(lines, vec!["one"]);
+    }
 }
@@ -1,6 +1,7 @@
 //! Utility functions for bean ID parsing and status conversion.
 
 use crate::bean::Status;
+use crate::error::BeansError;
 use anyhow::{Context, Result};
 use std::path::Path;
 use std::str::FromStr;
@@ -18,12 +19,20 @@ use std::str::FromStr;
 /// - "../etc/passwd" ✗ (invalid)
 /// - "task/../escape" ✗ (invalid)
 pub fn validate_bean_id(id: &str) -> Result<()> {
+    let invalid = |reason: String| -> Result<()> {
+        Err(BeansError::InvalidId {
+            id: id.to_string(),
+            reason,
+        }
+        .into())
+    };
+
     if id.is_empty() {
-        return Err(anyhow::anyhow!("Bean ID cannot be empty"));
+        return invalid("Bean ID cannot be empty".to_string());
     }
 
     if id.len() > 255 {
-        return Err(anyhow::anyhow!("Bean ID too long (max 255 characters)"));
+        return invalid("Bean ID too long (max 255 characters)".to_string());
     }
 
     // Check that ID only contains safe characters: alphanumeric, dots, underscores, hyphens
@@ -31,7 +40,7 @@ pub fn validate_bean_id(id: &str) -> Result<()> {
         .chars()
         .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
     {
-        return Err(anyhow::anyhow!(
+        return invalid(format!(
             "Invalid bean ID '{}': must contain only alphanumeric characters, dots, underscores, and hyphens",
             id
         ));
@@ -39,7 +48,7 @@ pub fn validate_bean_id(id: &str) -> Result<()> {
 
     // Ensure no path traversal sequences
     if id.contains("..") {
-        return Err(anyhow::anyhow!(
+        return invalid(format!(
             "Invalid bean ID '{}': cannot contain '..' (path traversal protection)",
             id
         ));
@@ -112,12 +121,15 @@ fn parse_id_segments(id: &str) -> Vec<IdSegment> {
 
 /// Convert a status string to a Status enum, or None if invalid.
 ///
-/// Valid inputs: "open", "in_progress", "closed"
+/// Valid inputs: "open", "in_progress", "in_review", "blocked", "closed", "cancelled"
 pub fn parse_status(s: &str) -> Option<Status> {
     match s {
         "open" => Some(Status::Open),
         "in_progress" => Some(Status::InProgress),
+        "in_review" => Some(Status::InReview),
+        "blocked" => Some(Status::Blocked),
         "closed" => Some(Status::Closed),
+        "cancelled" => Some(Status::Cancelled),
         _ => None,
     }
 }
@@ -234,6 +246,25 @@ pub fn atomic_write(path: &Path, contents: &str) -> Result<()> {
     Ok(())
 }
 
+/// Add `entry` to `.beans/.gitignore` unless a line already matches it.
+///
+/// For files `bn` starts writing after a project was initialized, so older
+/// projects get the same ignore rules as new ones. Best-effort: errors are
+/// ignored.
+pub fn ensure_gitignored(beans_dir: &Path, entry: &str) {
+    let path = beans_dir.join(".gitignore");
+    let mut contents = std::fs::read_to_string(&path).unwrap_or_default();
+    if contents.lines().any(|line| line.trim() == entry) {
+        return;
+    }
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(entry);
+    contents.push('\n');
+    let _ = atomic_write(&path, &contents);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,6 +509,13 @@ mod tests {
         assert_eq!(parse_status("closed"), Some(Status::Closed));
     }
 
+    #[test]
+    fn parse_status_new_statuses() {
+        assert_eq!(parse_status("in_review"), Some(Status::InReview));
+        assert_eq!(parse_status("blocked"), Some(Status::Blocked));
+        assert_eq!(parse_status("cancelled"), Some(Status::Cancelled));
+    }
+
     #[test]
     fn parse_status_invalid() {
         assert_eq!(parse_status("invalid"), None);
@@ -649,4 +687,18 @@ mod tests {
         assert_eq!(entries.len(), 1, "only the target file should exist");
         assert_eq!(entries[0].file_name().to_str().unwrap(), "test.yaml");
     }
+
+    // ---------- ensure_gitignored tests ----------
+
+    #[test]
+    fn ensure_gitignored_appends_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "index.yaml").unwrap();
+
+        ensure_gitignored(dir.path(), "claim.lock");
+        ensure_gitignored(dir.path(), "claim.lock");
+
+        let contents = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert_eq!(contents, "index.yaml\nclaim.lock\n");
+    }
 }
@@ -0,0 +1,493 @@
+//! Verify-result cache.
+//!
+//! Verify commands on a large workspace are slow, and `bn close`,
+//! `bn verify`, `bn claim` and `bn verify-facts` often run the same command
+//! against the same code. With a `verify_cache:` section in config, each
+//! outcome is stored in `.beans/verify-cache.json` under a key made of the
+//! command and a hash of its inputs:
+//!
+//! ```yaml
+//! verify_cache:
+//!   inputs: ["src/**", "Cargo.toml", "Cargo.lock"]
+//! ```
+//!
+//! In a git repository the key always covers the working tree: the `HEAD`
+//! tree plus every modified or untracked file, so editing or deleting any
+//! tracked file (a test the command runs, say) invalidates the result. On
+//! top of that it hashes the bean's `paths` and the configured `inputs`
+//! globs (directories recursively), which also catches changes git
+//! doesn't see, such as ignored generated files. Outside git only those
+//! are hashed. `.beans/` itself is never part of a key, so recording a
+//! failed attempt doesn't invalidate the result.
+//!
+//! Passes and failures are cached; timeouts are not. `--no-cache` (see
+//! [`bypass`]) skips lookups but still stores the fresh result. Like
+//! `index.yaml`, the file is a disposable cache: concurrent writers may drop
+//! each other's entries, which only costs a rerun.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::bean::{RunRecord, RunResult};
+use crate::config::Config;
+use crate::util::{atomic_write, ensure_gitignored};
+
+/// File name of the cache inside `.beans/`.
+pub const CACHE_FILE: &str = "verify-cache.json";
+
+/// Bumped whenever the key derivation or the file layout changes, so a
+/// cache written by another version is discarded instead of misread.
+const FORMAT_VERSION: u32 = 1;
+
+/// Oldest entries are evicted beyond this many.
+const MAX_ENTRIES: usize = 500;
+
+static BYPASS: AtomicBool = AtomicBool::new(false);
+
+/// Skip cache lookups for the rest of this process (`--no-cache`). Results
+/// are still stored, so the next cached run sees them.
+pub fn bypass() {
+    BYPASS.store(true, Ordering::SeqCst);
+}
+
+/// A cached verify outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub command: String,
+    pub record: RunRecord,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdout: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+}
+
+impl Entry {
+    /// Describe a run of `command` that started at `started_at` and just
+    /// finished.
+    pub fn new(
+        command: &str,
+        passed: bool,
+        exit_code: Option<i32>,
+        started_at: DateTime<Utc>,
+        stdout: &str,
+        stderr: &str,
+    ) -> Self {
+        let finished_at = Utc::now();
+        Self {
+            command: command.to_string(),
+            record: RunRecord {
+                attempt: 0,
+                started_at,
+                finished_at: Some(finished_at),
+                duration_secs: Some((finished_at - started_at).num_milliseconds() as f64 / 1000.0),
+                agent: None,
+                result: if passed {
+                    RunResult::Pass
+                } else {
+                    RunResult::Fail
+                },
+                exit_code,
+                tokens: None,
+                cost: None,
+                output_snippet: None,
+                reason: None,
+                cached: false,
+                tests: None,
+            },
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.record.result == RunResult::Pass
+    }
+}
+
+/// Hit and miss counts, shown by `bn stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache (0.0–1.0).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    #[serde(default)]
+    hits: u64,
+    #[serde(default)]
+    misses: u64,
+    #[serde(default)]
+    entries: BTreeMap<String, Entry>,
+}
+
+impl CacheFile {
+    fn load(beans_dir: &Path) -> Self {
+        fs::read_to_string(beans_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str::<CacheFile>(&s).ok())
+            .filter(|c| c.version == FORMAT_VERSION)
+            .unwrap_or(CacheFile {
+                version: FORMAT_VERSION,
+                ..Default::default()
+            })
+    }
+
+    fn save(&self, beans_dir: &Path) {
+        if let Ok(json) = serde_json::to_string(self) {
+            let path = beans_dir.join(CACHE_FILE);
+            if !path.exists() {
+                // Results must not travel to other checkouts through git
+                ensure_gitignored(beans_dir, CACHE_FILE);
+            }
+            // A cache that can't be written just means the next run is a miss.
+            let _ = atomic_write(&path, &json);
+        }
+    }
+}
+
+/// Identifies a verify command together with the state of its inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key(String);
+
+/// Compute the cache key for `command`, run for a bean with `paths`.
+///
+/// The key covers the git working tree and the files matched by `paths`
+/// and the configured `inputs`. Returns `None` when the cache is not
+/// configured, or when the project is not in a git repository and there
+/// are no inputs to hash.
+pub fn key(beans_dir: &Path, paths: &[String], command: &str) -> Option<Key> {
+    let config = Config::load(beans_dir).ok()?;
+    let inputs = config.verify_cache?.inputs;
+    // Globbed paths are stripped back to the root, so it must be absolute
+    let beans_dir = fs::canonicalize(beans_dir).ok()?;
+    let project_root = beans_dir.parent()?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(command.as_bytes());
+    hasher.update([0]);
+    let patterns: Vec<&str> = paths.iter().chain(&inputs).map(String::as_str).collect();
+    let in_git = hash_worktree(project_root, &mut hasher).is_some();
+    if !in_git && patterns.is_empty() {
+        return None;
+    }
+    hash_inputs(project_root, &patterns, &mut hasher);
+    Some(Key(format!("{:x}", hasher.finalize())))
+}
+
+/// Return the cached outcome for `key`, counting the hit or miss. Always a
+/// miss after [`bypass`].
+pub fn lookup(beans_dir: &Path, key: &Key) -> Option<Entry> {
+    if BYPASS.load(Ordering::SeqCst) {
+        return None;
+    }
+    let mut cache = CacheFile::load(beans_dir);
+    let entry = cache.entries.get(&key.0).cloned();
+    if entry.is_some() {
+        cache.hits += 1;
+    } else {
+        cache.misses += 1;
+    }
+    cache.save(beans_dir);
+    entry
+}
+
+/// Remember the outcome of a run under `key`.
+pub fn store(beans_dir: &Path, key: &Key, entry: Entry) {
+    let mut cache = CacheFile::load(beans_dir);
+    cache.entries.insert(key.0.clone(), entry);
+    while cache.entries.len() > MAX_ENTRIES {
+        let oldest = cache
+            .entries
+            .iter()
+            .min_by_key(|(_, e)| e.record.started_at)
+            .map(|(k, _)| k.clone());
+        match oldest {
+            Some(k) => cache.entries.remove(&k),
+            None => break,
+        };
+    }
+    cache.save(beans_dir);
+}
+
+/// Cache statistics, or `None` if nothing has been cached yet.
+pub fn stats(beans_dir: &Path) -> Option<CacheStats> {
+    if !beans_dir.join(CACHE_FILE).exists() {
+        return None;
+    }
+    let cache = CacheFile::load(beans_dir);
+    Some(CacheStats {
+        entries: cache.entries.len(),
+        hits: cache.hits,
+        misses: cache.misses,
+    })
+}
+
+/// Hash every file matched by `patterns` (paths or globs relative to the
+/// project root), in a stable order.
+fn hash_inputs(project_root: &Path, patterns: &[&str], hasher: &mut Sha256) {
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        // `dir/**` is the whole directory; the glob crate matches nothing for it
+        let dir = pattern.strip_suffix("/**").unwrap_or(pattern);
+        let full = project_root.join(dir);
+        let matched: Vec<PathBuf> = if dir.contains(['*', '?', '[']) {
+            glob::glob(&full.to_string_lossy())
+                .map(|paths| paths.flatten().collect())
+                .unwrap_or_default()
+        } else if full.exists() {
+            vec![full]
+        } else {
+            Vec::new()
+        };
+        if matched.is_empty() {
+            // Keep "missing" distinct from "empty"
+            hasher.update(format!("missing:{}\0", pattern).as_bytes());
+        }
+        for path in matched {
+            collect_files(&path, &mut files);
+        }
+    }
+    for path in files {
+        let Ok(rel) = path.strip_prefix(project_root) else {
+            continue;
+        };
+        if is_excluded(rel) {
+            continue;
+        }
+        hasher.update(rel.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(fs::read(&path).unwrap_or_default()));
+    }
+}
+
+fn collect_files(path: &Path, files: &mut BTreeSet<PathBuf>) {
+    if path.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let child = entry.path();
+            if child
+                .file_name()
+                .is_some_and(|n| n == ".git" || n == ".beans")
+            {
+                continue;
+            }
+            collect_files(&child, files);
+        }
+    } else if path.is_file() {
+        files.insert(path.to_path_buf());
+    }
+}
+
+fn is_excluded(rel: &Path) -> bool {
+    rel.components()
+        .any(|c| c.as_os_str() == ".git" || c.as_os_str() == ".beans")
+}
+
+/// Hash the git working tree: the `HEAD` tree plus the content of every
+/// modified or untracked file outside `.beans/`.
+fn hash_worktree(project_root: &Path, hasher: &mut Sha256) -> Option<()> {
+    let status = Command::new("git")
+        .args([
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--",
+            ".",
+            ":(exclude).beans",
+        ])
+        .current_dir(project_root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    // A repository without commits has no HEAD tree yet
+    let head = Command::new("git")
+        .args(["rev-parse", "HEAD^{tree}"])
+        .current_dir(project_root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| o.stdout)
+        .unwrap_or_default();
+    hasher.update(&head);
+    hasher.update(&status.stdout);
+
+    let text = String::from_utf8_lossy(&status.stdout);
+    let mut records = text.split('\0');
+    while let Some(record) = records.next() {
+        if record.len() < 4 {
+            continue;
+        }
+        let path = &record[3..];
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(
+            fs::read(project_root.join(path)).unwrap_or_default(),
+        ));
+        // Renames and copies are followed by the original path
+        if matches!(record.as_bytes()[0], b'R' | b'C') {
+            records.next();
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup(cache: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let beans_dir = dir.path().join(".beans");
+        fs::create_dir_all(&beans_dir).unwrap();
+        fs::write(
+            beans_dir.join("config.yaml"),
+            format!("project: test\nnext_id: 1\n{}", cache),
+        )
+        .unwrap();
+        (dir, beans_dir)
+    }
+
+    #[test]
+    fn key_is_none_without_config() {
+        let (_dir, beans_dir) = setup("");
+        assert!(key(&beans_dir, &["src".to_string()], "true").is_none());
+    }
+
+    #[test]
+    fn key_follows_input_content() {
+        let (dir, beans_dir) = setup("verify_cache:\n  inputs: [\"Cargo.*\", \"tests/**\"]\n");
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "fn a() {}").unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(root.join("README.md"), "docs").unwrap();
+        let paths = vec!["src".to_string()];
+
+        let first = key(&beans_dir, &paths, "cargo test").unwrap();
+        assert_eq!(key(&beans_dir, &paths, "cargo test").unwrap(), first);
+        assert_ne!(key(&beans_dir, &paths, "cargo check").unwrap(), first);
+
+        // Outside git, files outside the inputs don't matter
+        fs::write(root.join("README.md"), "more docs").unwrap();
+        fs::write(beans_dir.join("1-task.md"), "attempts: 2").unwrap();
+        assert_eq!(key(&beans_dir, &paths, "cargo test").unwrap(), first);
+
+        fs::write(root.join("src/lib.rs"), "fn b() {}").unwrap();
+        let second = key(&beans_dir, &paths, "cargo test").unwrap();
+        assert_ne!(second, first);
+        fs::write(root.join("Cargo.toml"), "[workspace]").unwrap();
+        let third = key(&beans_dir, &paths, "cargo test").unwrap();
+        assert_ne!(third, second);
+        fs::create_dir_all(root.join("tests/data")).unwrap();
+        fs::write(root.join("tests/data/case.txt"), "").unwrap();
+        assert_ne!(key(&beans_dir, &paths, "cargo test").unwrap(), third);
+    }
+
+    #[test]
+    fn key_falls_back_to_git_worktree() {
+        let (dir, beans_dir) = setup("verify_cache: {}\n");
+        let root = dir.path();
+        assert!(key(&beans_dir, &[], "true").is_none());
+
+        let init = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(root)
+            .status();
+        if !init.is_ok_and(|s| s.success()) {
+            eprintln!("skipping: git unavailable");
+            return;
+        }
+        fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+        let first = key(&beans_dir, &[], "true").unwrap();
+
+        fs::write(beans_dir.join("1-task.md"), "attempts: 1").unwrap();
+        assert_eq!(key(&beans_dir, &[], "true").unwrap(), first);
+
+        fs::write(root.join("main.rs"), "fn main() { todo!() }").unwrap();
+        assert_ne!(key(&beans_dir, &[], "true").unwrap(), first);
+    }
+
+    #[test]
+    fn key_covers_git_worktree_beyond_paths() {
+        let (dir, beans_dir) = setup("verify_cache: {}\n");
+        let root = dir.path();
+        let init = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(root)
+            .status();
+        if !init.is_ok_and(|s| s.success()) {
+            eprintln!("skipping: git unavailable");
+            return;
+        }
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "fn a() {}").unwrap();
+        fs::write(root.join("check.sh"), "cargo test").unwrap();
+        let paths = vec!["src".to_string()];
+        let first = key(&beans_dir, &paths, "sh check.sh").unwrap();
+
+        // The test script is outside `paths` but still part of the key
+        fs::write(root.join("check.sh"), "true").unwrap();
+        let second = key(&beans_dir, &paths, "sh check.sh").unwrap();
+        assert_ne!(second, first);
+        fs::remove_file(root.join("check.sh")).unwrap();
+        assert_ne!(key(&beans_dir, &paths, "sh check.sh").unwrap(), second);
+    }
+
+    #[test]
+    fn lookup_counts_hits_and_misses() {
+        let (dir, beans_dir) = setup("verify_cache: {}\n");
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let key = key(&beans_dir, &["a.txt".to_string()], "true").unwrap();
+        assert!(stats(&beans_dir).is_none());
+
+        assert!(lookup(&beans_dir, &key).is_none());
+        store(
+            &beans_dir,
+            &key,
+            Entry::new("true", true, Some(0), Utc::now(), "ok\n", ""),
+        );
+        let entry = lookup(&beans_dir, &key).unwrap();
+        assert!(entry.passed());
+        assert_eq!(entry.stdout, "ok\n");
+
+        let stats = stats(&beans_dir).unwrap();
+        assert_eq!(
+            stats,
+            CacheStats {
+                entries: 1,
+                hits: 1,
+                misses: 1
+            }
+        );
+        assert_eq!(stats.hit_rate(), 0.5);
+
+        let gitignore = fs::read_to_string(beans_dir.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|line| line == CACHE_FILE));
+    }
+}
@@ -1,12 +1,43 @@
-//! Git worktree detection and merge utilities.
+//! Git worktree detection, creation, and merge utilities.
 //!
 //! This module provides functions to detect if the current directory is within
-//! a git worktree, and to merge changes back to the main branch.
+//! a git worktree, to create a dedicated worktree per bean for `bn run`, and to
+//! merge changes back to the main branch.
 
-use anyhow::{anyhow, Result};
-use std::path::PathBuf;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crate::events::EVENTS_FILE;
+
+/// Files bn writes under `.beans/` as it works, as glob patterns. They stay
+/// out of agent commits: main's copies are usually modified and uncommitted,
+/// and git refuses to merge a branch that touches them. Events recorded in a
+/// worktree reach main through [`carry_over_events`] instead.
+const RUNTIME_FILES: &[&str] = &[
+    EVENTS_FILE,
+    "index.yaml",
+    "archive.yaml",
+    "search.json",
+    "index.lock",
+    "claim.lock",
+    "locks/**",
+    "runs/**",
+    "agent_history.jsonl",
+    ".hooks-trusted",
+    "verify-cache.json",
+];
+
+/// Pathspecs that leave [`RUNTIME_FILES`] out of a git command.
+fn runtime_excludes() -> Vec<String> {
+    RUNTIME_FILES
+        .iter()
+        .map(|f| format!(":(top,exclude,glob)**/.beans/{}", f))
+        .collect()
+}
+
 /// Result of a merge operation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MergeResult {
@@ -29,6 +60,16 @@ pub struct WorktreeInfo {
     pub branch: String,
 }
 
+/// A worktree created by `bn run` for a single agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentWorktree {
+    /// Worktree and branch details.
+    pub info: WorktreeInfo,
+    /// Directory the agent should run in: the project root inside the worktree.
+    /// Differs from `info.worktree_path` when `.beans/` lives in a subdirectory of the repo.
+    pub agent_dir: PathBuf,
+}
+
 /// Parsed worktree entry from `git worktree list --porcelain` output.
 #[derive(Debug)]
 struct WorktreeEntry {
@@ -159,15 +200,28 @@ pub fn detect_worktree() -> Result<Option<WorktreeInfo>> {
 /// Check if the current directory is in the main worktree (or not in a worktree at all).
 /// Commit all changes in the current worktree.
 ///
-/// Runs `git add -A` followed by `git commit -m <message>`.
+/// Runs `git add -A` followed by `git commit -m <message>`. bn's runtime
+/// files under `.beans/` (events, index, locks, ...) are left out.
 ///
 /// Returns:
 /// - `Ok(true)` if a commit was made
 /// - `Ok(false)` if there was nothing to commit
 /// - `Err` if git commands fail
 pub fn commit_worktree_changes(message: &str) -> Result<bool> {
+    commit_changes_in(Path::new("."), message)
+}
+
+/// Commit all changes in the worktree at `dir`.
+///
+/// Same as [`commit_worktree_changes`] but does not depend on the process CWD.
+pub fn commit_changes_in(dir: &Path, message: &str) -> Result<bool> {
     // Stage all changes
-    let add_output = Command::new("git").args(["add", "-A"]).output()?;
+    let add_output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["add", "-A", "--", ":/"])
+        .args(runtime_excludes())
+        .output()?;
 
     if !add_output.status.success() {
         return Err(anyhow!(
@@ -178,6 +232,8 @@ pub fn commit_worktree_changes(message: &str) -> Result<bool> {
 
     // Commit changes
     let commit_output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
         .args(["commit", "-m", message])
         .output()?;
 
@@ -351,6 +407,323 @@ pub fn cleanup_worktree(info: &WorktreeInfo) -> Result<()> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Per-agent worktrees (`bn run` with `worktree: true`)
+// ---------------------------------------------------------------------------
+
+/// Branch name used for a bean's agent worktree.
+#[must_use]
+pub fn branch_for_bean(bean_id: &str) -> String {
+    format!("bean/{}", bean_id)
+}
+
+/// Directory where `bn run` places the worktree for a bean.
+///
+/// Worktrees live next to the main checkout in `<repo>-worktrees/<id>` so they
+/// never show up as untracked files in the main tree.
+#[must_use]
+pub fn worktree_path_for_bean(main_path: &Path, bean_id: &str) -> PathBuf {
+    let repo_name = main_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("repo");
+    let base = main_path.parent().unwrap_or(main_path);
+    base.join(format!("{}-worktrees", repo_name)).join(bean_id)
+}
+
+/// List the worktrees of the repository containing `dir`.
+///
+/// Returns an empty list if `dir` is not inside a git repository.
+fn list_worktrees_in(dir: &Path) -> Vec<WorktreeEntry> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["worktree", "list", "--porcelain"])
+        .output();
+
+    match output {
+        Ok(o) if o.status.success() => parse_worktree_list(&String::from_utf8_lossy(&o.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Create (or reuse) a dedicated worktree and branch for a bean.
+///
+/// The worktree is checked out from the main worktree's current `HEAD` on
+/// branch `bean/<id>`. If the branch already exists (e.g. from an earlier
+/// failed attempt that was interrupted), it is checked out as-is. If a
+/// worktree for the bean is already registered, it is returned unchanged.
+pub fn create_worktree(project_root: &Path, bean_id: &str) -> Result<WorktreeInfo> {
+    let entries = list_worktrees_in(project_root);
+    let main_path = entries
+        .first()
+        .map(|e| e.path.clone())
+        .ok_or_else(|| anyhow!("{} is not inside a git repository", project_root.display()))?;
+
+    let branch = branch_for_bean(bean_id);
+    let worktree_path = worktree_path_for_bean(&main_path, bean_id);
+
+    if let Some(existing) = entries.iter().find(|e| e.path == worktree_path) {
+        return Ok(WorktreeInfo {
+            main_path,
+            worktree_path,
+            branch: existing.branch.clone().unwrap_or(branch),
+        });
+    }
+
+    // Drop stale registrations (e.g. a worktree directory deleted by hand)
+    let _ = Command::new("git")
+        .arg("-C")
+        .arg(&main_path)
+        .args(["worktree", "prune"])
+        .output();
+
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let branch_exists = Command::new("git")
+        .arg("-C")
+        .arg(&main_path)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{}", branch))
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    let mut add = Command::new("git");
+    add.arg("-C").arg(&main_path).args(["worktree", "add"]);
+    if branch_exists {
+        add.arg(&worktree_path).arg(&branch);
+    } else {
+        add.args(["-b", &branch]).arg(&worktree_path);
+    }
+    let add_output = add.output()?;
+
+    if !add_output.status.success() {
+        return Err(anyhow!(
+            "git worktree add failed: {}",
+            String::from_utf8_lossy(&add_output.stderr).trim()
+        ));
+    }
+
+    Ok(WorktreeInfo {
+        main_path,
+        worktree_path,
+        branch,
+    })
+}
+
+/// Create a worktree for an agent working on `bean_id` and prepare its `.beans/`.
+///
+/// The worktree only contains committed files, so if the bean (or the project
+/// config) has not been committed yet it is copied in — the agent must be able
+/// to `bn claim` and `bn close` it from inside the worktree.
+pub fn prepare_agent_worktree(beans_dir: &Path, bean_id: &str) -> Result<AgentWorktree> {
+    let project_root = beans_dir
+        .parent()
+        .ok_or_else(|| anyhow!("Cannot determine project root from beans dir"))?;
+    let canonical_root =
+        std::fs::canonicalize(project_root).unwrap_or_else(|_| project_root.to_path_buf());
+
+    let info = create_worktree(&canonical_root, bean_id)?;
+
+    let canonical_main =
+        std::fs::canonicalize(&info.main_path).unwrap_or_else(|_| info.main_path.clone());
+    let agent_dir = match canonical_root.strip_prefix(&canonical_main) {
+        Ok(rel) => info.worktree_path.join(rel),
+        Err(_) => info.worktree_path.clone(),
+    };
+
+    let wt_beans_dir = agent_dir.join(".beans");
+    std::fs::create_dir_all(&wt_beans_dir)
+        .with_context(|| format!("Failed to create {}", wt_beans_dir.display()))?;
+
+    if crate::discovery::find_bean_file(&wt_beans_dir, bean_id).is_err() {
+        let src = crate::discovery::find_bean_file(beans_dir, bean_id)?;
+        if let Some(name) = src.file_name() {
+            std::fs::copy(&src, wt_beans_dir.join(name))
+                .with_context(|| format!("Failed to copy bean {} into worktree", bean_id))?;
+        }
+    }
+    let config_path = beans_dir.join("config.yaml");
+    if config_path.exists() && !wt_beans_dir.join("config.yaml").exists() {
+        std::fs::copy(&config_path, wt_beans_dir.join("config.yaml"))
+            .context("Failed to copy config.yaml into worktree")?;
+    }
+
+    Ok(AgentWorktree { info, agent_dir })
+}
+
+/// Finalize an agent's worktree once the agent process has exited.
+///
+/// If `bn close` inside the worktree already merged and removed it, this is a
+/// no-op. Events the agent recorded are first appended to main's log. On
+/// success, leftover changes are committed and merged to main before the
+/// worktree is removed. On failure, the worktree and its branch are discarded
+/// unless they hold agent work (commits or edits outside `.beans/`), which is
+/// kept and reported as an error. A merge conflict or failed merge also
+/// leaves the worktree in place for manual resolution.
+///
+/// Returns the merge result when a merge was attempted.
+pub fn finish_agent_worktree(
+    beans_dir: &Path,
+    wt: &AgentWorktree,
+    bean_id: &str,
+    success: bool,
+) -> Result<Option<MergeResult>> {
+    let info = &wt.info;
+    let registered = list_worktrees_in(&info.main_path)
+        .iter()
+        .any(|e| e.path == info.worktree_path);
+
+    let kept = || {
+        format!(
+            "branch {} kept in {}",
+            info.branch,
+            info.worktree_path.display()
+        )
+    };
+    if registered {
+        carry_over_events(&wt.agent_dir.join(".beans"), beans_dir)?;
+    }
+
+    let merge = if !registered {
+        None
+    } else if success {
+        commit_changes_in(
+            &info.worktree_path,
+            &format!("Agent work for bean {}", bean_id),
+        )
+        .with_context(kept)?;
+        let result = merge_to_main(info, bean_id).with_context(kept)?;
+        if matches!(result, MergeResult::Conflict { .. }) {
+            return Ok(Some(result));
+        }
+        cleanup_worktree(info)?;
+        Some(result)
+    } else if has_agent_work(info) {
+        return Err(anyhow!("Unmerged agent work: {}", kept()));
+    } else {
+        cleanup_worktree(info)?;
+        None
+    };
+
+    // A bean copied into the worktree (never committed on main) comes back
+    // through the merge as an archived file, leaving the open copy behind.
+    if crate::discovery::find_archived_bean(beans_dir, bean_id).is_ok() {
+        if let Ok(stale) = crate::discovery::find_bean_file(beans_dir, bean_id) {
+            std::fs::remove_file(&stale)
+                .with_context(|| format!("Failed to remove stale {}", stale.display()))?;
+            let index = crate::index::Index::build(beans_dir)?;
+            index.save(beans_dir)?;
+        }
+    }
+
+    Ok(merge)
+}
+
+/// Whether an agent left work that main doesn't have: commits on its
+/// branch, or uncommitted changes outside `.beans/`. Assumes there is work
+/// when git can't tell.
+fn has_agent_work(info: &WorktreeInfo) -> bool {
+    let ahead = Command::new("git")
+        .arg("-C")
+        .arg(&info.main_path)
+        .args(["rev-list", "--count"])
+        .arg(format!("HEAD..{}", info.branch))
+        .output();
+    match ahead {
+        Ok(o) if o.status.success() => {
+            if String::from_utf8_lossy(&o.stdout).trim() != "0" {
+                return true;
+            }
+        }
+        _ => return true,
+    }
+
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(&info.worktree_path)
+        .args([
+            "status",
+            "--porcelain",
+            "--",
+            ":/",
+            ":(top,exclude,glob)**/.beans/**",
+        ])
+        .output();
+    match status {
+        Ok(o) if o.status.success() => !o.stdout.is_empty(),
+        _ => true,
+    }
+}
+
+/// The `.beans/` directory in the main worktree that corresponds to
+/// `wt_beans_dir` in the worktree described by `info`.
+pub fn main_beans_dir(info: &WorktreeInfo, wt_beans_dir: &Path) -> PathBuf {
+    let canonical = std::fs::canonicalize(wt_beans_dir).unwrap_or_else(|_| wt_beans_dir.into());
+    let root =
+        std::fs::canonicalize(&info.worktree_path).unwrap_or_else(|_| info.worktree_path.clone());
+    match canonical.strip_prefix(&root) {
+        Ok(rel) => info.main_path.join(rel),
+        Err(_) => info.main_path.join(".beans"),
+    }
+}
+
+/// Append the events recorded in a worktree's `.beans/` to main's log at
+/// `beans_dir`, then reset the worktree's copy so they are not carried twice.
+///
+/// Agent commits leave `events.jsonl` out (see [`RUNTIME_FILES`]), so this is
+/// how a worktree's claims, updates and closes reach main.
+pub fn carry_over_events(wt_beans_dir: &Path, beans_dir: &Path) -> Result<()> {
+    let path = wt_beans_dir.join(EVENTS_FILE);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    // The copy committed when the worktree was created is already on main
+    let base = Command::new("git")
+        .arg("-C")
+        .arg(wt_beans_dir)
+        .args(["show", &format!("HEAD:./{}", EVENTS_FILE)])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default();
+
+    let new = match contents.strip_prefix(base.as_str()) {
+        Some(rest) => rest.to_string(),
+        None => {
+            let known: HashSet<&str> = base.lines().collect();
+            contents
+                .lines()
+                .filter(|l| !known.contains(l))
+                .map(|l| format!("{}\n", l))
+                .collect()
+        }
+    };
+    if !new.trim().is_empty() {
+        let mut log = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(beans_dir.join(EVENTS_FILE))
+            .context("Failed to open events log")?;
+        log.write_all(new.as_bytes())?;
+        if !new.ends_with('\n') {
+            log.write_all(b"\n")?;
+        }
+    }
+
+    if base.is_empty() {
+        std::fs::remove_file(&path)?;
+    } else {
+        std::fs::write(&path, base)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -517,4 +890,185 @@ CONFLICT (content): Merge conflict in file2.txt
             assert!(result.is_err()); // Expected to fail with nonexistent paths
         }
     }
+
+    // Per-agent worktree tests
+    mod agent {
+        use super::*;
+        use crate::bean::Bean;
+        use std::fs;
+        use tempfile::TempDir;
+
+        /// Run a git command in the given directory, panicking on failure.
+        fn run_git(dir: &Path, args: &[&str]) {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap_or_else(|e| unreachable!("git {:?} failed to execute: {}", args, e));
+            assert!(
+                output.status.success(),
+                "git {:?} failed: {}",
+                args,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        /// Create a repo on `main` with a committed `.beans/` and one open bean.
+        ///
+        /// Returns (tempdir, main_repo_dir, beans_dir).
+        fn setup_repo() -> (TempDir, PathBuf, PathBuf) {
+            let dir = TempDir::new().unwrap();
+            let base = fs::canonicalize(dir.path()).unwrap();
+            let main_dir = base.join("project");
+            fs::create_dir(&main_dir).unwrap();
+
+            run_git(&main_dir, &["init"]);
+            run_git(&main_dir, &["config", "user.email", "test@test.com"]);
+            run_git(&main_dir, &["config", "user.name", "Test"]);
+            run_git(&main_dir, &["checkout", "-b", "main"]);
+
+            let beans_dir = main_dir.join(".beans");
+            fs::create_dir(&beans_dir).unwrap();
+            fs::write(beans_dir.join("config.yaml"), "project: test\nnext_id: 2\n").unwrap();
+            Bean::new("1", "Task")
+                .to_file(beans_dir.join("1-task.md"))
+                .unwrap();
+            run_git(&main_dir, &["add", "-A"]);
+            run_git(&main_dir, &["commit", "-m", "Initial commit"]);
+
+            (dir, main_dir, beans_dir)
+        }
+
+        #[test]
+        fn worktree_path_is_sibling_of_main() {
+            let path = worktree_path_for_bean(Path::new("/home/user/project"), "3.1");
+            assert_eq!(path, PathBuf::from("/home/user/project-worktrees/3.1"));
+            assert_eq!(branch_for_bean("3.1"), "bean/3.1");
+        }
+
+        #[test]
+        fn create_worktree_makes_branch_and_directory() {
+            let (_dir, main_dir, _beans_dir) = setup_repo();
+
+            let info = create_worktree(&main_dir, "1").unwrap();
+            assert_eq!(info.main_path, main_dir);
+            assert_eq!(info.branch, "bean/1");
+            assert!(info.worktree_path.join(".beans/1-task.md").exists());
+
+            // Creating again reuses the registered worktree
+            let again = create_worktree(&main_dir, "1").unwrap();
+            assert_eq!(again, info);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn commit_changes_in_handles_non_utf8_paths() {
+            use std::ffi::OsStr;
+            use std::os::unix::ffi::OsStrExt;
+
+            let (dir, main_dir, _beans_dir) = setup_repo();
+            let odd = dir.path().join(OsStr::from_bytes(b"proj\xffect"));
+            fs::rename(&main_dir, &odd).unwrap();
+            fs::write(odd.join("new.txt"), "hi").unwrap();
+
+            assert!(commit_changes_in(&odd, "Add new.txt").unwrap());
+            assert!(!commit_changes_in(&odd, "Nothing").unwrap());
+        }
+
+        #[test]
+        fn create_worktree_outside_repo_errors() {
+            let dir = TempDir::new().unwrap();
+            assert!(create_worktree(dir.path(), "1").is_err());
+        }
+
+        #[test]
+        fn prepare_agent_worktree_copies_uncommitted_bean() {
+            let (_dir, _main_dir, beans_dir) = setup_repo();
+            Bean::new("2", "Fresh")
+                .to_file(beans_dir.join("2-fresh.md"))
+                .unwrap();
+
+            let wt = prepare_agent_worktree(&beans_dir, "2").unwrap();
+            assert_eq!(wt.agent_dir, wt.info.worktree_path);
+            assert!(wt.agent_dir.join(".beans/2-fresh.md").exists());
+        }
+
+        #[test]
+        fn finish_on_failure_discards_worktree() {
+            let (_dir, _main_dir, beans_dir) = setup_repo();
+            let wt = prepare_agent_worktree(&beans_dir, "1").unwrap();
+            fs::write(wt.agent_dir.join(".beans/claim.lock"), "").unwrap();
+
+            let merge = finish_agent_worktree(&beans_dir, &wt, "1", false).unwrap();
+            assert_eq!(merge, None);
+            assert!(!wt.info.worktree_path.exists());
+        }
+
+        #[test]
+        fn finish_on_failure_keeps_agent_work() {
+            let (_dir, main_dir, beans_dir) = setup_repo();
+            let wt = prepare_agent_worktree(&beans_dir, "1").unwrap();
+            fs::write(wt.agent_dir.join("half-done.txt"), "wip").unwrap();
+
+            let err = finish_agent_worktree(&beans_dir, &wt, "1", false).unwrap_err();
+            assert!(err.to_string().contains("bean/1"), "{}", err);
+            assert!(wt.agent_dir.join("half-done.txt").exists());
+            assert!(!main_dir.join("half-done.txt").exists());
+
+            // Committed work is kept too
+            commit_changes_in(&wt.agent_dir, "wip").unwrap();
+            assert!(finish_agent_worktree(&beans_dir, &wt, "1", false).is_err());
+            assert!(wt.info.worktree_path.exists());
+        }
+
+        #[test]
+        fn finish_on_success_merges_to_main() {
+            let (_dir, main_dir, beans_dir) = setup_repo();
+            let wt = prepare_agent_worktree(&beans_dir, "1").unwrap();
+            fs::write(wt.agent_dir.join("feature.txt"), "done").unwrap();
+
+            let merge = finish_agent_worktree(&beans_dir, &wt, "1", true).unwrap();
+            assert_eq!(merge, Some(MergeResult::Success));
+            assert!(!wt.info.worktree_path.exists());
+            assert_eq!(
+                fs::read_to_string(main_dir.join("feature.txt")).unwrap(),
+                "done"
+            );
+        }
+
+        #[test]
+        fn finish_merges_past_uncommitted_runtime_files_on_main() {
+            let (_dir, main_dir, beans_dir) = setup_repo();
+            fs::write(beans_dir.join(EVENTS_FILE), "{\"n\":1}\n").unwrap();
+            fs::write(beans_dir.join("index.yaml"), "beans: []\n").unwrap();
+            run_git(&main_dir, &["add", "-A"]);
+            run_git(&main_dir, &["commit", "-m", "Add events"]);
+
+            let wt = prepare_agent_worktree(&beans_dir, "1").unwrap();
+            let wt_events = wt.agent_dir.join(".beans").join(EVENTS_FILE);
+            fs::write(&wt_events, "{\"n\":1}\n{\"n\":\"agent\"}\n").unwrap();
+            fs::write(wt.agent_dir.join("feature.txt"), "done").unwrap();
+            fs::write(wt.agent_dir.join(".beans/index.yaml"), "beans: [1]\n").unwrap();
+            fs::write(beans_dir.join("index.yaml"), "beans: [2]\n").unwrap();
+            fs::write(beans_dir.join(EVENTS_FILE), "{\"n\":1}\n{\"n\":\"main\"}\n").unwrap();
+
+            let merge = finish_agent_worktree(&beans_dir, &wt, "1", true).unwrap();
+            assert_eq!(merge, Some(MergeResult::Success));
+            assert!(main_dir.join("feature.txt").exists());
+            assert_eq!(
+                fs::read_to_string(beans_dir.join(EVENTS_FILE)).unwrap(),
+                "{\"n\":1}\n{\"n\":\"main\"}\n{\"n\":\"agent\"}\n"
+            );
+        }
+
+        #[test]
+        fn finish_is_noop_when_close_already_cleaned_up() {
+            let (_dir, _main_dir, beans_dir) = setup_repo();
+            let wt = prepare_agent_worktree(&beans_dir, "1").unwrap();
+            cleanup_worktree(&wt.info).unwrap();
+
+            let merge = finish_agent_worktree(&beans_dir, &wt, "1", true).unwrap();
+            assert_eq!(merge, None);
+        }
+    }
 }
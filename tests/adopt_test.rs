@@ -37,6 +37,17 @@ fn setup_test_env() -> (TempDir, std::path::PathBuf) {
         review: None,
         user: None,
         user_email: None,
+        max_cost_per_bean: None,
+        max_cost_per_run: None,
+        max_cost_per_day: None,
+        max_tokens_per_bean: None,
+        max_tokens_per_run: None,
+        max_tokens_per_day: None,
+        agent: None,
+        views: Default::default(),
+        sandbox: None,
+        verify_cache: None,
+        test_reports: Vec::new(),
     };
     config.save(&beans_dir).unwrap();
 
@@ -10,7 +10,7 @@ fn setup_test_env() -> (TempDir, std::path::PathBuf) {
     fs::create_dir_all(&beans_dir).unwrap();
 
     // Write minimal config
-    fs::write(beans_dir.join("config.yaml"), "next_id: 2\n").unwrap();
+    fs::write(beans_dir.join("config.yaml"), "project: api-test\nnext_id: 2\n").unwrap();
 
     // Write a sample bean
     let bean = Bean::new("1", "Sample task");
@@ -88,3 +88,147 @@ fn api_graph_functions_accessible() {
     let graph = build_full_graph(&index).unwrap();
     assert!(graph.contains("Sample task"));
 }
+
+#[test]
+fn api_create_claim_close_round_trip() {
+    let (_dir, beans_dir) = setup_test_env();
+
+    let mut params = CreateParams::new("Add caching");
+    params.verify = Some("true".to_string());
+    params.pass_ok = true;
+    params.labels = vec!["perf".to_string(), "cache".to_string()];
+    let bean = create_bean(&beans_dir, params).unwrap();
+    assert_eq!(bean.id, "2");
+    assert_eq!(bean.labels, vec!["perf", "cache"]);
+
+    let claimed = claim(
+        &beans_dir,
+        "2",
+        ClaimParams {
+            by: Some("agent-7".to_string()),
+            force: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(claimed.status, Status::InProgress);
+    assert_eq!(claimed.claimed_by.as_deref(), Some("agent-7"));
+
+    match close_bean(&beans_dir, "2", CloseParams::default()).unwrap() {
+        CloseOutcome::Closed { bean } => assert_eq!(bean.status, Status::Closed),
+        other => panic!("expected Closed, got {:?}", other),
+    }
+    assert!(get_archived_bean(&beans_dir, "2").is_ok());
+    assert!(load_index(&beans_dir).unwrap().beans.iter().all(|b| b.id != "2"));
+}
+
+#[test]
+fn api_close_reports_failed_verify() {
+    let (_dir, beans_dir) = setup_test_env();
+    let mut params = CreateParams::new("Broken");
+    params.verify = Some("echo nope; exit 3".to_string());
+    let bean = create_bean(&beans_dir, params).unwrap();
+
+    match close_bean(&beans_dir, &bean.id, CloseParams::default()).unwrap() {
+        CloseOutcome::VerifyFailed {
+            exit_code, output, ..
+        } => {
+            assert_eq!(exit_code, Some(3));
+            assert!(output.contains("nope"));
+        }
+        other => panic!("expected VerifyFailed, got {:?}", other),
+    }
+    assert_eq!(get_bean(&beans_dir, &bean.id).unwrap().status, Status::Open);
+}
+
+#[test]
+fn api_errors_are_typed() {
+    let (_dir, beans_dir) = setup_test_env();
+
+    assert!(matches!(
+        update_bean(&beans_dir, "999", UpdateParams::default()),
+        Err(BeansError::NotFound { .. })
+    ));
+    assert!(matches!(
+        claim(&beans_dir, "../etc", ClaimParams::default()),
+        Err(BeansError::InvalidId { .. })
+    ));
+
+    let mut params = CreateParams::new("Already done");
+    params.verify = Some("true".to_string());
+    assert!(matches!(
+        create_bean(&beans_dir, params),
+        Err(BeansError::VerifyAlreadyPasses { id: None, .. })
+    ));
+
+    let mut params = CreateParams::new("No criteria");
+    params.claim = true;
+    assert!(matches!(
+        create_bean(&beans_dir, params),
+        Err(BeansError::Invalid(_))
+    ));
+
+    let mut params = CreateParams::new("Depends on 1");
+    params.dependencies = vec!["1".to_string()];
+    let second = create_bean(&beans_dir, params).unwrap();
+    let err = add_dependency(&beans_dir, "1", &second.id).unwrap_err();
+    assert!(matches!(err, BeansError::Cycle { .. }));
+    assert!(err.to_string().starts_with("Dependency cycle detected"));
+    assert!(matches!(
+        add_dependency(&beans_dir, &second.id, "1"),
+        Err(BeansError::DependencyExists { .. })
+    ));
+    assert!(matches!(
+        remove_dependency(&beans_dir, "1", &second.id),
+        Err(BeansError::NoSuchDependency { .. })
+    ));
+}
+
+#[test]
+fn api_ready_beans_and_dependencies() {
+    let (_dir, beans_dir) = setup_test_env();
+    for title in ["First", "Second"] {
+        let mut params = CreateParams::new(title);
+        params.verify = Some("false".to_string());
+        create_bean(&beans_dir, params).unwrap();
+    }
+    add_dependency(&beans_dir, "3", "2").unwrap();
+
+    let ready: Vec<String> = ready_beans(&beans_dir)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ready, vec!["2"]);
+
+    let deps = dependencies(&beans_dir, "3").unwrap();
+    assert_eq!(deps.dependencies[0].id, "2");
+    assert!(matches!(
+        dependencies(&beans_dir, "42"),
+        Err(BeansError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn api_run_dispatch_streams_events() {
+    let (_dir, beans_dir) = setup_test_env();
+    fs::write(
+        beans_dir.join("config.yaml"),
+        "project: api-test\nnext_id: 2\nrun: \"echo {id}\"\n",
+    )
+    .unwrap();
+    let mut bean = get_bean(&beans_dir, "1").unwrap();
+    bean.verify = Some("false".to_string());
+    bean.to_file(find_bean_file(&beans_dir, "1").unwrap()).unwrap();
+
+    let handle = run_dispatch(
+        &beans_dir,
+        RunParams {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let events: Vec<RunEvent> = handle.events().iter().collect();
+    handle.wait().unwrap();
+    assert!(events.iter().any(|e| matches!(e, RunEvent::DryRun { .. })));
+}
@@ -0,0 +1,136 @@
+//! Helpers shared by the integration tests that drive the `bn` binary.
+
+// Each test crate compiles this module and uses only some of the helpers
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::Duration;
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// A `bn` command run in `dir`, without backtraces in its output.
+pub fn bn_command(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_bn"));
+    command
+        .args(args)
+        .current_dir(dir)
+        .env("RUST_BACKTRACE", "0");
+    command
+}
+
+/// Run `bn` in `dir`.
+pub fn bn(dir: &Path, args: &[&str]) -> Output {
+    bn_command(dir, args).output().unwrap()
+}
+
+/// Run `bn` in `dir`, assert that it succeeded and return its stdout.
+pub fn bn_ok(dir: &Path, args: &[&str]) -> String {
+    let output = bn(dir, args);
+    assert!(
+        output.status.success(),
+        "bn {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Create `.beans/` under `root` for project `name`, with `config` appended
+/// to its `config.yaml`. Returns the beans directory.
+pub fn setup_project(root: &Path, name: &str, config: &str) -> PathBuf {
+    let beans_dir = root.join(".beans");
+    fs::create_dir_all(&beans_dir).unwrap();
+    fs::write(
+        beans_dir.join("config.yaml"),
+        format!("project: {}\nnext_id: 1\n{}", name, config),
+    )
+    .unwrap();
+    beans_dir
+}
+
+/// A `.beans/` directory served over HTTP on a loopback port.
+pub struct TestServer {
+    pub _dir: TempDir,
+    pub beans_dir: PathBuf,
+    pub addr: SocketAddr,
+}
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+/// Write a minimal HTTP/1.1 request and return the open stream, for tests
+/// that read the response themselves.
+pub fn send_request(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> TcpStream {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(30)))
+        .unwrap();
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\n",
+        method,
+        path,
+        addr,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).unwrap();
+    stream
+}
+
+/// Send a request and read the whole response (the servers close the
+/// connection after each one).
+pub fn request_with(
+    addr: SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> HttpResponse {
+    let mut stream = send_request(addr, method, path, headers, body);
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).unwrap();
+
+    let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+    let mut lines = head.lines();
+    let status = lines.next().unwrap().split(' ').nth(1).unwrap();
+    let headers = lines
+        .filter_map(|l| l.split_once(": "))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    HttpResponse {
+        status: status.parse().unwrap(),
+        headers,
+        body: body.to_string(),
+    }
+}
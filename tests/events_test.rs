@@ -0,0 +1,130 @@
+//! Integration test for the audit trail: bean mutations append events to
+//! `.beans/events.jsonl` and `bn log` reads them back.
+
+use std::fs;
+use std::process::Command;
+
+use bn::api::*;
+use bn::events::{load_events, Action};
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn setup_test_env() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let beans_dir = dir.path().join(".beans");
+    fs::create_dir_all(&beans_dir).unwrap();
+    fs::write(
+        beans_dir.join("config.yaml"),
+        "project: events-test\nnext_id: 1\nuser: alice\n",
+    )
+    .unwrap();
+    (dir, beans_dir)
+}
+
+#[test]
+fn mutations_are_recorded_with_actor_and_diffs() {
+    let (_dir, beans_dir) = setup_test_env();
+
+    let mut params = CreateParams::new("Add caching");
+    params.verify = Some("true".to_string());
+    params.pass_ok = true;
+    let bean = create_bean(&beans_dir, params).unwrap();
+
+    update_bean(
+        &beans_dir,
+        &bean.id,
+        UpdateParams {
+            priority: Some(0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    claim(&beans_dir, &bean.id, ClaimParams::default()).unwrap();
+    release(&beans_dir, &bean.id).unwrap();
+    close_bean(&beans_dir, &bean.id, CloseParams::default()).unwrap();
+
+    let events = load_events(&beans_dir);
+    let actions: Vec<Action> = events.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            Action::Create,
+            Action::Update,
+            Action::Claim,
+            Action::Release,
+            Action::Close
+        ]
+    );
+    assert!(events.iter().all(|e| e.id == bean.id));
+    assert!(events.iter().all(|e| e.actor.as_deref() == Some("alice")));
+
+    let priority = events[1].change("priority").unwrap();
+    assert_eq!(priority.old, Some(Value::from(2)));
+    assert_eq!(priority.new, Some(Value::from(0)));
+
+    let status = events[4].change("status").unwrap();
+    assert_eq!(status.new, Some(Value::from("closed")));
+}
+
+#[test]
+fn delete_records_full_bean_and_dependents() {
+    let (dir, beans_dir) = setup_test_env();
+    let mut params = CreateParams::new("First");
+    params.pass_ok = true;
+    let first = create_bean(&beans_dir, params).unwrap();
+    let mut params = CreateParams::new("Second");
+    params.pass_ok = true;
+    params.dependencies = vec![first.id.clone()];
+    let second = create_bean(&beans_dir, params).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bn"))
+        .args(["delete", &first.id])
+        .current_dir(dir.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let events = load_events(&beans_dir);
+    let delete = events.iter().find(|e| e.action == Action::Delete).unwrap();
+    assert_eq!(delete.id, first.id);
+    assert_eq!(
+        delete.change("title").unwrap().old,
+        Some(Value::from("First"))
+    );
+    assert!(delete.changes.iter().all(|c| c.new.is_none()));
+
+    let cleanup = events.last().unwrap();
+    assert_eq!(cleanup.action, Action::Update);
+    assert_eq!(cleanup.id, second.id);
+    assert!(cleanup.change("dependencies").is_some());
+}
+
+#[test]
+fn bn_log_filters_by_bean() {
+    let (dir, beans_dir) = setup_test_env();
+    for title in ["One", "Two"] {
+        let mut params = CreateParams::new(title);
+        params.pass_ok = true;
+        create_bean(&beans_dir, params).unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bn"))
+        .args(["log", "2", "--json", "--actor", "alice", "--since", "1h"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["id"], "2");
+    assert_eq!(events[0]["action"], "create");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bn"))
+        .args(["log"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("create   1  by alice"), "{}", text);
+    assert!(text.contains("title: \"Two\""), "{}", text);
+}
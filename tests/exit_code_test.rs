@@ -0,0 +1,47 @@
+//! Integration test for `bn` exit codes: each kind of failure exits with
+//! its own code (see `bn::error`).
+
+use std::fs;
+use std::path::Path;
+
+use bn::bean::Bean;
+use bn::error::{
+    EXIT_CONFIG_INVALID, EXIT_CONFLICT, EXIT_CYCLE, EXIT_NOT_FOUND, EXIT_USAGE, EXIT_VERIFY_FAILED,
+};
+use tempfile::TempDir;
+
+mod common;
+
+fn bn(dir: &Path, args: &[&str]) -> i32 {
+    common::bn(dir, args).status.code().unwrap()
+}
+
+#[test]
+fn failures_exit_with_their_own_code() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = root.join(".beans");
+    fs::create_dir(&beans_dir).unwrap();
+    fs::write(beans_dir.join("config.yaml"), "project: exit\nnext_id: 2\n").unwrap();
+    let mut bean = Bean::new("1", "Task");
+    bean.verify = Some("true".to_string());
+    bean.to_file(beans_dir.join("1-task.md")).unwrap();
+
+    assert_eq!(bn(root, &["show", "1"]), 0);
+    assert_eq!(bn(root, &["show", "99"]), EXIT_NOT_FOUND);
+    assert_eq!(bn(root, &["show", "a/b"]), EXIT_USAGE);
+    assert_eq!(bn(root, &["dep", "add", "1", "1"]), EXIT_CYCLE);
+    assert_eq!(bn(root, &["claim", "1"]), 0);
+    assert_eq!(bn(root, &["claim", "1"]), EXIT_CONFLICT);
+    assert_eq!(bn(root, &["claim", "1", "--release"]), 0);
+
+    bean.verify = Some("false".to_string());
+    bean.to_file(beans_dir.join("1-task.md")).unwrap();
+    assert_eq!(bn(root, &["verify", "1"]), EXIT_VERIFY_FAILED);
+
+    fs::write(beans_dir.join("config.yaml"), "project: [").unwrap();
+    assert_eq!(
+        bn(root, &["create", "Another", "--pass-ok"]),
+        EXIT_CONFIG_INVALID
+    );
+}
@@ -0,0 +1,151 @@
+//! Integration test for filter expressions: `bn list -q`, named views from
+//! config, and `bn tidy --filter`.
+
+use std::path::Path;
+
+use bn::discovery::{find_archived_bean, find_bean_file};
+use bn::error::EXIT_USAGE;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn, bn_ok};
+
+fn ids(dir: &Path, args: &[&str]) -> Vec<String> {
+    let mut ids: Vec<String> = bn_ok(dir, args).lines().map(str::to_string).collect();
+    ids.sort();
+    ids
+}
+
+fn setup_project(root: &Path) -> std::path::PathBuf {
+    let beans_dir = common::setup_project(root, "filter", "");
+
+    bn_ok(
+        root,
+        &[
+            "create",
+            "API",
+            "--verify",
+            "true",
+            "-p",
+            "--priority",
+            "0",
+            "--labels",
+            "backend",
+            "--paths",
+            "src/api/mod.rs",
+        ],
+    );
+    bn_ok(
+        root,
+        &[
+            "create",
+            "CLI",
+            "--verify",
+            "true",
+            "-p",
+            "--priority",
+            "3",
+            "--labels",
+            "frontend",
+            "--paths",
+            "src/cli.rs",
+        ],
+    );
+    bn_ok(root, &["create", "Goal", "-p", "--priority", "1"]);
+    beans_dir
+}
+
+#[test]
+fn list_query_combines_fields() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root);
+
+    assert_eq!(
+        ids(root, &["list", "--ids", "-q", "priority<=1"]),
+        vec!["1", "3"]
+    );
+    assert_eq!(
+        ids(root, &["list", "--ids", "-q", "priority<=1 and has_verify"]),
+        vec!["1"]
+    );
+    assert_eq!(
+        ids(
+            root,
+            &["list", "--ids", "-q", "label:frontend or path:src/api/**"]
+        ),
+        vec!["1", "2"]
+    );
+    assert_eq!(
+        ids(
+            root,
+            &["list", "--ids", "-q", "not claimed and not label:backend"]
+        ),
+        vec!["2", "3"]
+    );
+    assert!(ids(root, &["list", "--ids", "-q", "updated<7d"]).is_empty());
+
+    let out = bn(root, &["list", "-q", "priority<="]);
+    assert_eq!(out.status.code(), Some(EXIT_USAGE));
+}
+
+#[test]
+fn list_query_with_status_includes_archived() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root);
+
+    bn_ok(root, &["close", "1"]);
+    assert_eq!(
+        ids(root, &["list", "--ids", "-q", "label:backend"]),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        ids(
+            root,
+            &["list", "--ids", "-q", "status:closed and label:backend"]
+        ),
+        vec!["1"]
+    );
+}
+
+#[test]
+fn named_views_expand_in_queries() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root);
+
+    bn_ok(root, &["config", "set", "view.urgent", "priority<=1"]);
+    assert_eq!(
+        bn_ok(root, &["config", "get", "view.urgent"]).trim(),
+        "priority<=1"
+    );
+    assert_eq!(
+        ids(root, &["list", "--ids", "-q", "@urgent"]),
+        vec!["1", "3"]
+    );
+    assert_eq!(
+        ids(root, &["list", "--ids", "-q", "@urgent and not has_verify"]),
+        vec!["3"]
+    );
+
+    // Views are checked when saved
+    let out = bn(root, &["config", "set", "view.broken", "(priority<1"]);
+    assert!(!out.status.success());
+    let out = bn(root, &["list", "-q", "@missing"]);
+    assert_eq!(out.status.code(), Some(EXIT_USAGE));
+}
+
+#[test]
+fn tidy_filter_only_touches_matching_beans() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root);
+
+    bn_ok(root, &["update", "1", "--status", "closed"]);
+    bn_ok(root, &["update", "2", "--status", "closed"]);
+    bn_ok(root, &["tidy", "--filter", "label:backend"]);
+
+    assert!(find_archived_bean(&beans_dir, "1").is_ok());
+    assert!(find_bean_file(&beans_dir, "2").is_ok());
+}
@@ -0,0 +1,341 @@
+//! Integration tests for the MCP streamable HTTP transport.
+//!
+//! Starts an `HttpServer` on a free loopback port and drives it with a
+//! minimal HTTP/1.1 client over `TcpStream`.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+use bn::bean::Bean;
+use bn::index::Index;
+use bn::mcp::http::HttpServer;
+use bn::mcp::tools;
+
+mod common;
+use common::{bn_command, request_with, send_request, HttpResponse, TestServer};
+
+// ---------------------------------------------------------------------------
+// Test helpers
+// ---------------------------------------------------------------------------
+
+/// A .beans/ directory with one bean, served over HTTP.
+fn start_server(token: Option<&str>) -> TestServer {
+    let dir = TempDir::new().unwrap();
+    let beans_dir = dir.path().join(".beans");
+    fs::create_dir_all(&beans_dir).unwrap();
+    fs::write(
+        beans_dir.join("config.yaml"),
+        "project: mcp-http-test\nnext_id: 2\n",
+    )
+    .unwrap();
+    let mut bean = Bean::new("1", "Fix login bug");
+    bean.slug = Some("fix-login-bug".to_string());
+    bean.verify = Some("echo pass".to_string());
+    bean.to_file(beans_dir.join("1-fix-login-bug.md")).unwrap();
+
+    let server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let server_dir = beans_dir.clone();
+    let token = token.map(str::to_string);
+    // The server runs until the test process exits
+    std::thread::spawn(move || server.run(&server_dir, token));
+
+    TestServer {
+        _dir: dir,
+        beans_dir,
+        addr,
+    }
+}
+
+fn request(addr: SocketAddr, method: &str, headers: &[(&str, &str)], body: &str) -> HttpResponse {
+    request_with(addr, method, "/mcp", headers, body)
+}
+
+fn post(addr: SocketAddr, session: Option<&str>, body: &Value) -> HttpResponse {
+    let mut headers = vec![("Content-Type", "application/json")];
+    if let Some(id) = session {
+        headers.push(("Mcp-Session-Id", id));
+    }
+    request(addr, "POST", &headers, &body.to_string())
+}
+
+/// Run the initialize handshake and return the session ID.
+fn initialize(addr: SocketAddr) -> String {
+    let response = post(
+        addr,
+        None,
+        &json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+    );
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json()["result"]["serverInfo"]["name"], "beans");
+    let session = response.header("Mcp-Session-Id").unwrap().to_string();
+
+    let initialized = post(
+        addr,
+        Some(&session),
+        &json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+    );
+    assert_eq!(initialized.status, 202);
+    session
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn http_initialize_then_call_tool_in_session() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let response = post(
+        server.addr,
+        Some(&session),
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "show_bean", "arguments": {"id": "1"}}
+        }),
+    );
+    assert_eq!(response.status, 200);
+    assert_eq!(response.header("Content-Type"), Some("application/json"));
+    let body = response.json();
+    assert_eq!(body["id"], 2);
+    let text = body["result"]["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Fix login bug"));
+}
+
+#[test]
+fn http_batch_returns_array_of_responses() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let response = post(
+        server.addr,
+        Some(&session),
+        &json!([
+            {"jsonrpc": "2.0", "id": 10, "method": "ping"},
+            {"jsonrpc": "2.0", "method": "notifications/cancelled"},
+            {"jsonrpc": "2.0", "id": 11, "method": "tools/list"}
+        ]),
+    );
+    assert_eq!(response.status, 200);
+    let body = response.json();
+    let responses = body.as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 10);
+    assert!(responses[1]["result"]["tools"].is_array());
+}
+
+#[test]
+fn http_concurrent_creates_get_distinct_ids() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let threads: Vec<_> = (0..8)
+        .map(|i| {
+            let (addr, session) = (server.addr, session.clone());
+            std::thread::spawn(move || {
+                let response = post(
+                    addr,
+                    Some(&session),
+                    &json!({
+                        "jsonrpc": "2.0",
+                        "id": i,
+                        "method": "tools/call",
+                        "params": {"name": "create_bean", "arguments": {"title": format!("Task {}", i)}}
+                    }),
+                );
+                assert_eq!(response.status, 200);
+                assert!(response.json()["result"]["isError"].is_null());
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let index = Index::build(&server.beans_dir).unwrap();
+    let mut ids: Vec<&str> = index.beans.iter().map(|e| e.id.as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, ["1", "2", "3", "4", "5", "6", "7", "8", "9"]);
+}
+
+#[test]
+fn http_requires_a_known_session() {
+    let server = start_server(None);
+    let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+
+    assert_eq!(post(server.addr, None, &ping).status, 400);
+    assert_eq!(post(server.addr, Some("not-a-session"), &ping).status, 404);
+
+    // Each initialize gets its own session
+    let a = initialize(server.addr);
+    let b = initialize(server.addr);
+    assert_ne!(a, b);
+    assert_eq!(post(server.addr, Some(&a), &ping).status, 200);
+}
+
+#[test]
+fn http_rejects_malformed_bodies_and_paths() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let response = request(
+        server.addr,
+        "POST",
+        &[("Mcp-Session-Id", &session)],
+        "{not json",
+    );
+    assert_eq!(response.status, 400);
+    assert_eq!(response.json()["error"]["code"], -32700);
+
+    let mut stream = send_request(server.addr, "GET", "/elsewhere", &[], "");
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).unwrap();
+    assert!(raw.starts_with("HTTP/1.1 404"));
+
+    let response = request(server.addr, "PUT", &[], "");
+    assert_eq!(response.status, 405);
+    assert_eq!(response.header("Allow"), Some("GET, POST, DELETE"));
+}
+
+#[test]
+fn http_bearer_token_is_enforced() {
+    let server = start_server(Some("s3cret"));
+    let init = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}).to_string();
+
+    let response = request(server.addr, "POST", &[], &init);
+    assert_eq!(response.status, 401);
+    assert_eq!(response.header("WWW-Authenticate"), Some("Bearer"));
+
+    let response = request(
+        server.addr,
+        "POST",
+        &[("Authorization", "Bearer wrong")],
+        &init,
+    );
+    assert_eq!(response.status, 401);
+
+    let response = request(
+        server.addr,
+        "POST",
+        &[("Authorization", "Bearer s3cret")],
+        &init,
+    );
+    assert_eq!(response.status, 200);
+    assert!(response.header("Mcp-Session-Id").is_some());
+}
+
+#[test]
+fn serve_refuses_public_address_without_token() {
+    let server = start_server(None);
+    let output = bn_command(server._dir.path(), &["mcp", "serve", "--http", "0.0.0.0:0"])
+        .env_remove("BEANS_MCP_TOKEN")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("BEANS_MCP_TOKEN"), "{}", stderr);
+}
+
+#[test]
+fn http_rejects_foreign_origins() {
+    let server = start_server(None);
+    let init = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"}).to_string();
+
+    let response = request(
+        server.addr,
+        "POST",
+        &[("Origin", "https://evil.example")],
+        &init,
+    );
+    assert_eq!(response.status, 403);
+
+    let response = request(
+        server.addr,
+        "POST",
+        &[("Origin", "http://localhost:5173")],
+        &init,
+    );
+    assert_eq!(response.status, 200);
+}
+
+#[test]
+fn http_delete_ends_session() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let response = request(server.addr, "DELETE", &[("Mcp-Session-Id", &session)], "");
+    assert_eq!(response.status, 204);
+
+    let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+    assert_eq!(post(server.addr, Some(&session), &ping).status, 404);
+    let response = request(server.addr, "DELETE", &[("Mcp-Session-Id", &session)], "");
+    assert_eq!(response.status, 404);
+}
+
+#[test]
+fn http_event_stream_delivers_resource_updates() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+
+    let response = post(
+        server.addr,
+        Some(&session),
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/subscribe",
+            "params": {"uri": "beans://bean/1"}
+        }),
+    );
+    assert!(response.json()["result"].is_object());
+
+    let stream = send_request(
+        server.addr,
+        "GET",
+        "/mcp",
+        &[
+            ("Accept", "text/event-stream"),
+            ("Mcp-Session-Id", &session),
+        ],
+        "",
+    );
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status).unwrap();
+    assert!(status.starts_with("HTTP/1.1 200"), "{}", status);
+
+    tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "1", "note": "changed"}),
+        &server.beans_dir,
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let notification = loop {
+        assert!(Instant::now() < deadline, "timed out waiting for event");
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if let Some(data) = line.strip_prefix("data: ") {
+            break serde_json::from_str::<Value>(data).unwrap();
+        }
+    };
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "beans://bean/1");
+}
+
+#[test]
+fn http_event_stream_needs_accept_header() {
+    let server = start_server(None);
+    let session = initialize(server.addr);
+    let response = request(server.addr, "GET", &[("Mcp-Session-Id", &session)], "");
+    assert_eq!(response.status, 406);
+}
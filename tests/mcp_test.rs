@@ -1,17 +1,22 @@
 //! Integration tests for the MCP server module.
 //!
 //! Tests the MCP protocol types, tool definitions, tool handlers,
-//! resource definitions, resource handlers, and the server dispatch loop.
+//! resource definitions, resource handlers, prompts, and the server loop.
 
 use std::fs;
+use std::io::{BufReader, Read, Write};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use serde_json::{json, Value};
 use tempfile::TempDir;
 
 use bn::bean::Bean;
 use bn::index::Index;
+use bn::mcp::prompts;
 use bn::mcp::protocol::{JsonRpcRequest, JsonRpcResponse};
 use bn::mcp::resources;
+use bn::mcp::server;
 use bn::mcp::tools;
 
 // ---------------------------------------------------------------------------
@@ -115,21 +120,37 @@ fn mcp_json_rpc_response_error_serializes() {
 // ---------------------------------------------------------------------------
 
 #[test]
-fn mcp_tool_definitions_returns_all_ten_tools() {
+fn mcp_tool_definitions_returns_all_tools() {
     let defs = tools::tool_definitions();
-    assert_eq!(defs.len(), 10, "Expected 10 tools, got {}", defs.len());
-
     let names: Vec<&str> = defs.iter().map(|t| t.name.as_str()).collect();
-    assert!(names.contains(&"list_beans"));
-    assert!(names.contains(&"show_bean"));
-    assert!(names.contains(&"ready_beans"));
-    assert!(names.contains(&"create_bean"));
-    assert!(names.contains(&"claim_bean"));
-    assert!(names.contains(&"close_bean"));
-    assert!(names.contains(&"verify_bean"));
-    assert!(names.contains(&"context_bean"));
-    assert!(names.contains(&"status"));
-    assert!(names.contains(&"tree"));
+    assert_eq!(
+        names,
+        vec![
+            "list_beans",
+            "show_bean",
+            "ready_beans",
+            "create_bean",
+            "claim_bean",
+            "close_bean",
+            "verify_bean",
+            "context_bean",
+            "status",
+            "tree",
+            "update_bean",
+            "add_dependency",
+            "remove_dependency",
+            "list_dependencies",
+            "recall",
+            "trace_bean",
+            "create_fact",
+            "verify_facts",
+            "fail_bean",
+            "bean_logs",
+            "adopt_beans",
+            "run_bean",
+            "plan_bean",
+        ]
+    );
 }
 
 #[test]
@@ -162,6 +183,15 @@ fn mcp_required_tools_have_required_params() {
     let create = defs.iter().find(|t| t.name == "create_bean").unwrap();
     let required = create.input_schema["required"].as_array().unwrap();
     assert!(required.contains(&json!("title")));
+
+    let dep = defs.iter().find(|t| t.name == "add_dependency").unwrap();
+    let required = dep.input_schema["required"].as_array().unwrap();
+    assert!(required.contains(&json!("id")));
+    assert!(required.contains(&json!("depends_on")));
+
+    let fact = defs.iter().find(|t| t.name == "create_fact").unwrap();
+    let required = fact.input_schema["required"].as_array().unwrap();
+    assert!(required.contains(&json!("verify")));
 }
 
 // ---------------------------------------------------------------------------
@@ -189,6 +219,66 @@ fn mcp_list_beans_filter_by_priority() {
     assert_eq!(parsed["beans"][0]["title"], "Refactor auth module");
 }
 
+#[test]
+fn mcp_list_beans_hides_cancelled_and_filters_new_statuses() {
+    let (_dir, beans_dir) = setup_mcp_env();
+
+    let path = beans_dir.join("3-refactor-auth-module.md");
+    let mut bean3 = Bean::from_file(&path).unwrap();
+    bean3.status = bn::bean::Status::Cancelled;
+    bean3.to_file(&path).unwrap();
+
+    let path = beans_dir.join("1-fix-login-bug.md");
+    let mut bean1 = Bean::from_file(&path).unwrap();
+    bean1.status = bn::bean::Status::Blocked;
+    bean1.blocked_reason = Some("needs design sign-off".to_string());
+    bean1.to_file(&path).unwrap();
+
+    Index::build(&beans_dir).unwrap().save(&beans_dir).unwrap();
+
+    let result = tools::handle_tool_call("list_beans", &json!({}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["count"], 2);
+
+    let result = tools::handle_tool_call("list_beans", &json!({"status": "blocked"}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["count"], 1);
+    assert_eq!(parsed["beans"][0]["blocked_reason"], "needs design sign-off");
+
+    let result =
+        tools::handle_tool_call("list_beans", &json!({"status": "cancelled"}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["count"], 1);
+    assert_eq!(parsed["beans"][0]["id"], "3");
+}
+
+#[test]
+fn mcp_list_beans_filter_expression() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "list_beans",
+        &json!({"query": "priority<=1 or title~^fix"}),
+        &beans_dir,
+    );
+
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    let mut ids: Vec<&str> = parsed["beans"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["id"].as_str().unwrap())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["1", "3"]);
+
+    let result = tools::handle_tool_call("list_beans", &json!({"query": "priority <"}), &beans_dir);
+    assert_eq!(result["isError"], true);
+}
+
 // ---------------------------------------------------------------------------
 // Tool handlers: show_bean
 // ---------------------------------------------------------------------------
@@ -404,6 +494,47 @@ fn mcp_close_bean_with_failing_verify_returns_error() {
     assert_eq!(result["isError"], true);
     let text = result["content"][0]["text"].as_str().unwrap();
     assert!(text.contains("Verify failed"));
+    assert_eq!(result["_meta"]["error"]["kind"], "verify_failed");
+    assert_eq!(result["_meta"]["error"]["code"], -32011);
+}
+
+#[test]
+fn mcp_close_bean_refuses_weakened_verify() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let mut bean = Bean::new("10", "Gated bean");
+    bean.slug = Some("gated-bean".to_string());
+    bean.verify = Some("false".to_string());
+    bean.to_file(beans_dir.join("10-gated-bean.md")).unwrap();
+
+    tools::handle_tool_call("claim_bean", &json!({"id": "10", "force": true}), &beans_dir);
+    // The agent swaps in a verify that always passes
+    let path = beans_dir.join("10-gated-bean.md");
+    let mut bean = Bean::from_file(&path).unwrap();
+    bean.verify = Some("true".to_string());
+    bean.to_file(&path).unwrap();
+
+    let result = tools::handle_tool_call("close_bean", &json!({"id": "10"}), &beans_dir);
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["_meta"]["error"]["kind"], "verify_tampered");
+    let bean = Bean::from_file(&path).unwrap();
+    assert!(bean.labels.contains(&"needs-human-review".to_string()));
+}
+
+#[test]
+fn mcp_tool_errors_carry_error_kind() {
+    let (_dir, beans_dir) = setup_mcp_env();
+
+    let result = tools::handle_tool_call("show_bean", &json!({"id": "404"}), &beans_dir);
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["_meta"]["error"]["kind"], "not_found");
+    assert_eq!(result["_meta"]["error"]["code"], -32002);
+
+    let result = tools::handle_tool_call(
+        "add_dependency",
+        &json!({"id": "1", "depends_on": "1"}),
+        &beans_dir,
+    );
+    assert_eq!(result["_meta"]["error"]["kind"], "cycle");
 }
 
 #[test]
@@ -535,6 +666,304 @@ fn mcp_context_bean_no_paths() {
     assert!(text.contains("no file paths"));
 }
 
+// ---------------------------------------------------------------------------
+// Tool handlers: update_bean
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_update_bean_sets_fields_and_appends_note() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "1", "title": "Fix login redirect", "priority": 0, "note": "Tried cookie fix"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+
+    let result = tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "1", "note": "Root cause is the session TTL", "add_label": "auth"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+
+    let bean = Bean::from_file(beans_dir.join("1-fix-login-bug.md")).unwrap();
+    assert_eq!(bean.title, "Fix login redirect");
+    assert_eq!(bean.priority, 0);
+    assert_eq!(bean.labels, vec!["auth".to_string()]);
+    let notes = bean.notes.unwrap();
+    assert!(notes.contains("Tried cookie fix"));
+    assert!(notes.contains("Root cause is the session TTL"));
+}
+
+#[test]
+fn mcp_update_bean_rejects_invalid_status() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "1", "status": "done"}),
+        &beans_dir,
+    );
+    assert_eq!(result["isError"], true);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Invalid status"));
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: dependencies
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_add_and_remove_dependency() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "add_dependency",
+        &json!({"id": "3", "depends_on": "1"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+    let bean = Bean::from_file(beans_dir.join("3-refactor-auth-module.md")).unwrap();
+    assert_eq!(bean.dependencies, vec!["1".to_string()]);
+
+    let result = tools::handle_tool_call(
+        "remove_dependency",
+        &json!({"id": "3", "depends_on": "1"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+    let bean = Bean::from_file(beans_dir.join("3-refactor-auth-module.md")).unwrap();
+    assert!(bean.dependencies.is_empty());
+}
+
+#[test]
+fn mcp_add_dependency_rejects_cycle() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    // 2 already depends on 1
+    let result = tools::handle_tool_call(
+        "add_dependency",
+        &json!({"id": "1", "depends_on": "2"}),
+        &beans_dir,
+    );
+    assert_eq!(result["isError"], true);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("cycle"));
+}
+
+#[test]
+fn mcp_list_dependencies_shows_both_directions() {
+    let (_dir, beans_dir) = setup_mcp_env();
+
+    let result = tools::handle_tool_call("list_dependencies", &json!({"id": "1"}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["dependencies"].as_array().unwrap().len(), 0);
+    assert_eq!(parsed["dependents"][0]["id"], "2");
+
+    let result = tools::handle_tool_call("list_dependencies", &json!({"id": "2"}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["dependencies"][0]["id"], "1");
+    assert_eq!(parsed["dependencies"][0]["title"], "Fix login bug");
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: recall and trace_bean
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_recall_ranks_title_matches() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call("recall", &json!({"query": "login"}), &beans_dir);
+
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    let ids: Vec<&str> = parsed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&"1"));
+    assert!(ids.contains(&"2"));
+}
+
+#[test]
+fn mcp_recall_no_matches() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call("recall", &json!({"query": "kubernetes"}), &beans_dir);
+
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("No matches"));
+}
+
+#[test]
+fn mcp_trace_bean_returns_graph() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call("trace_bean", &json!({"id": "2"}), &beans_dir);
+
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["bean"]["id"], "2");
+    assert_eq!(parsed["dependencies"][0]["id"], "1");
+    assert_eq!(parsed["produces"][0], "LoginTests");
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: facts
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_create_fact_then_verify_facts() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "create_fact",
+        &json!({"title": "Auth uses JWT", "verify": "true", "pass_ok": true, "paths": "src/auth.rs"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Created fact 4"));
+
+    let fact = Bean::from_file(bn::discovery::find_bean_file(&beans_dir, "4").unwrap()).unwrap();
+    assert_eq!(fact.bean_type, "fact");
+    assert_eq!(fact.paths, vec!["src/auth.rs".to_string()]);
+
+    let result = tools::handle_tool_call("verify_facts", &json!({}), &beans_dir);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let report: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(report["total"], 1);
+    assert_eq!(report["verified"][0]["id"], "4");
+    assert_eq!(report["failing"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn mcp_create_fact_requires_verify() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call("create_fact", &json!({"title": "No proof"}), &beans_dir);
+    assert_eq!(result["isError"], true);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("verify"));
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: fail_bean
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_fail_bean_releases_claim_and_records_reason() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    tools::handle_tool_call(
+        "claim_bean",
+        &json!({"id": "1", "by": "agent-1"}),
+        &beans_dir,
+    );
+
+    let result = tools::handle_tool_call(
+        "fail_bean",
+        &json!({"id": "1", "reason": "Tests need a database"}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+
+    let bean = Bean::from_file(beans_dir.join("1-fix-login-bug.md")).unwrap();
+    assert_eq!(bean.status, bn::bean::Status::Open);
+    assert!(bean.claimed_by.is_none());
+    assert!(bean.notes.unwrap().contains("Tests need a database"));
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: bean_logs
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_bean_logs_tails_latest_log() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    // Logs live in the shared log directory, so use an ID no real bean has
+    let id = format!("9{}.77", std::process::id());
+    let log_path = bn::commands::logs::log_dir()
+        .unwrap()
+        .join(format!("{}-20990101-000000.log", id.replace('.', "_")));
+    fs::write(&log_path, "line 1\nline 2\nline 3\nline 4\n").unwrap();
+
+    let result = tools::handle_tool_call("bean_logs", &json!({"id": id, "lines": 2}), &beans_dir);
+    fs::remove_file(&log_path).unwrap();
+
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("last 2 of 4 lines"));
+    assert!(text.ends_with("line 3\nline 4"));
+    assert!(!text.contains("line 2"));
+}
+
+#[test]
+fn mcp_bean_logs_without_logs_returns_error() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let id = format!("8{}.99", std::process::id());
+    let result = tools::handle_tool_call("bean_logs", &json!({"id": id}), &beans_dir);
+    assert_eq!(result["isError"], true);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("No logs"));
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: adopt_beans
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_adopt_beans_renumbers_children_and_dependencies() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "adopt_beans",
+        &json!({"parent": "3", "children": ["1", "2"]}),
+        &beans_dir,
+    );
+    assert!(result.get("isError").is_none(), "{}", result);
+    let text = result["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains("Adopted 1 -> 3.1 (under 3)"));
+    assert!(text.contains("Adopted 2 -> 3.2 (under 3)"));
+
+    let child = Bean::from_file(bn::discovery::find_bean_file(&beans_dir, "3.2").unwrap()).unwrap();
+    assert_eq!(child.parent.as_deref(), Some("3"));
+    assert_eq!(child.dependencies, vec!["3.1".to_string()]);
+}
+
+#[test]
+fn mcp_adopt_beans_requires_children() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call(
+        "adopt_beans",
+        &json!({"parent": "3", "children": []}),
+        &beans_dir,
+    );
+    assert_eq!(result["isError"], true);
+}
+
+// ---------------------------------------------------------------------------
+// Tool handlers: run_bean and plan_bean
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_run_bean_missing_bean_returns_error() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = tools::handle_tool_call("run_bean", &json!({"id": "99"}), &beans_dir);
+    assert_eq!(result["isError"], true);
+}
+
+#[test]
+fn mcp_run_and_plan_refuse_closed_beans() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    tools::handle_tool_call("close_bean", &json!({"id": "3", "force": true}), &beans_dir);
+
+    for tool in ["run_bean", "plan_bean"] {
+        let result = tools::handle_tool_call(tool, &json!({"id": "3"}), &beans_dir);
+        assert_eq!(
+            result["isError"], true,
+            "{} should refuse a closed bean",
+            tool
+        );
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tool handlers: unknown tool
 // ---------------------------------------------------------------------------
@@ -617,6 +1046,108 @@ fn mcp_resource_read_unknown_uri_returns_error() {
     assert!(result.is_err());
 }
 
+#[test]
+fn mcp_resource_templates_cover_bean_context_and_logs() {
+    let templates = resources::resource_templates();
+    let uris: Vec<&str> = templates.iter().map(|t| t.uri_template.as_str()).collect();
+    assert_eq!(
+        uris,
+        vec![
+            "beans://bean/{id}",
+            "beans://context/{id}",
+            "beans://logs/{id}"
+        ]
+    );
+}
+
+#[test]
+fn mcp_resource_read_context() {
+    let (dir, beans_dir) = setup_mcp_env();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/auth.rs"), "pub fn login() {}\n").unwrap();
+    tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "3", "description": "Refactor src/auth.rs"}),
+        &beans_dir,
+    );
+
+    let contents = resources::handle_resource_read("beans://context/3", &beans_dir).unwrap();
+    assert_eq!(contents[0].uri, "beans://context/3");
+    assert!(contents[0].text.contains("pub fn login()"));
+
+    let contents = resources::handle_resource_read("beans://context/1", &beans_dir).unwrap();
+    assert!(contents[0].text.contains("no file paths"));
+}
+
+#[test]
+fn mcp_resource_read_logs_without_logs_returns_error() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let uri = format!("beans://logs/7{}.42", std::process::id());
+    assert!(resources::handle_resource_read(&uri, &beans_dir).is_err());
+}
+
+#[test]
+fn mcp_known_resource_uris() {
+    assert!(resources::is_known_uri("beans://status"));
+    assert!(resources::is_known_uri("beans://bean/12"));
+    assert!(resources::is_known_uri("beans://logs/3.1"));
+    assert!(!resources::is_known_uri("beans://bean/"));
+    assert!(!resources::is_known_uri("file:///etc/passwd"));
+}
+
+// ---------------------------------------------------------------------------
+// Prompts
+// ---------------------------------------------------------------------------
+
+#[test]
+fn mcp_prompt_definitions_present() {
+    let defs = prompts::prompt_definitions();
+    let names: Vec<&str> = defs.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["implement_bean", "plan_bean"]);
+    for def in &defs {
+        assert!(def.arguments.iter().any(|a| a.name == "id" && a.required));
+    }
+}
+
+#[test]
+fn mcp_prompt_get_implement_bean() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result =
+        prompts::handle_prompt_get("implement_bean", &json!({"id": "1"}), &beans_dir).unwrap();
+
+    let messages = result["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert!(messages.iter().all(|m| m["role"] == "user"));
+    let context = messages[0]["content"]["text"].as_str().unwrap();
+    assert!(context.contains("Fix login bug"));
+    assert!(context.contains("echo pass"));
+}
+
+#[test]
+fn mcp_prompt_get_plan_bean_with_strategy() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let result = prompts::handle_prompt_get(
+        "plan_bean",
+        &json!({"id": "3", "strategy": "layer"}),
+        &beans_dir,
+    )
+    .unwrap();
+
+    let text = result["messages"][0]["content"]["text"].as_str().unwrap();
+    assert!(text.contains("Refactor auth module"));
+    assert!(text.contains("Split by layer"));
+}
+
+#[test]
+fn mcp_prompt_get_errors() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    assert!(prompts::handle_prompt_get("implement_bean", &json!({}), &beans_dir).is_err());
+    assert!(
+        prompts::handle_prompt_get("implement_bean", &json!({"id": "99"}), &beans_dir).is_err()
+    );
+    assert!(prompts::handle_prompt_get("nope", &json!({"id": "1"}), &beans_dir).is_err());
+}
+
 // ---------------------------------------------------------------------------
 // Server dispatch (unit-level, no actual stdio)
 // ---------------------------------------------------------------------------
@@ -663,3 +1194,162 @@ fn mcp_error_result_has_is_error_flag() {
     assert!(result["content"].is_array());
     assert_eq!(result["content"][0]["type"], "text");
 }
+
+// ---------------------------------------------------------------------------
+// Server loop
+// ---------------------------------------------------------------------------
+
+/// Feeds lines to the server as they are sent; end of input when dropped.
+struct ChannelReader {
+    rx: mpsc::Receiver<String>,
+    pending: Vec<u8>,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            match self.rx.recv() {
+                Ok(line) => self.pending = format!("{}\n", line).into_bytes(),
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+/// Collects everything the server writes.
+#[derive(Clone, Default)]
+struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedOutput {
+    fn messages(&self) -> Vec<Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    /// Wait up to 10s for a message matching `pred`.
+    fn wait_for(&self, pred: impl Fn(&Value) -> bool) -> Value {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Some(m) = self.messages().into_iter().find(|m| pred(m)) {
+                return m;
+            }
+            assert!(Instant::now() < deadline, "timed out waiting for message");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+#[test]
+fn mcp_server_lists_prompts_and_templates() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let (tx, rx) = mpsc::channel();
+    let output = SharedOutput::default();
+    for line in [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"prompts/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"resources/templates/list"}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"prompts/get","params":{"name":"plan_bean","arguments":{"id":"3"}}}"#,
+    ] {
+        tx.send(line.to_string()).unwrap();
+    }
+    drop(tx);
+
+    let reader = BufReader::new(ChannelReader {
+        rx,
+        pending: Vec::new(),
+    });
+    server::serve(&beans_dir, reader, output.clone()).unwrap();
+
+    let messages = output.messages();
+    assert_eq!(messages.len(), 4);
+    assert_eq!(
+        messages[0]["result"]["capabilities"]["resources"]["subscribe"],
+        true
+    );
+    assert!(messages[0]["result"]["capabilities"]["prompts"].is_object());
+    assert_eq!(
+        messages[1]["result"]["prompts"][0]["name"],
+        "implement_bean"
+    );
+    assert_eq!(
+        messages[2]["result"]["resourceTemplates"][1]["uriTemplate"],
+        "beans://context/{id}"
+    );
+    assert!(messages[3]["result"]["messages"][0]["content"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("Refactor auth module"));
+}
+
+#[test]
+fn mcp_server_notifies_subscribers_when_bean_changes() {
+    let (_dir, beans_dir) = setup_mcp_env();
+    let (tx, rx) = mpsc::channel();
+    let output = SharedOutput::default();
+
+    let server_dir = beans_dir.clone();
+    let server_output = output.clone();
+    let handle = std::thread::spawn(move || {
+        let reader = BufReader::new(ChannelReader {
+            rx,
+            pending: Vec::new(),
+        });
+        server::serve(&server_dir, reader, server_output).unwrap();
+    });
+
+    tx.send(
+        r#"{"jsonrpc":"2.0","id":1,"method":"resources/subscribe","params":{"uri":"beans://bean/1"}}"#
+            .to_string(),
+    )
+    .unwrap();
+    tx.send(
+        r#"{"jsonrpc":"2.0","id":2,"method":"resources/subscribe","params":{"uri":"https://example.com"}}"#
+            .to_string(),
+    )
+    .unwrap();
+    output.wait_for(|m| m["id"] == 2);
+    let messages = output.messages();
+    assert!(messages[0]["result"].is_object());
+    assert!(messages[1]["error"].is_object());
+
+    // An edit to bean 2 must not notify the bean 1 subscriber
+    tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "2", "note": "unrelated"}),
+        &beans_dir,
+    );
+    tools::handle_tool_call(
+        "update_bean",
+        &json!({"id": "1", "note": "changed"}),
+        &beans_dir,
+    );
+    let notification = output.wait_for(|m| m["method"] == "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "beans://bean/1");
+
+    drop(tx);
+    handle.join().unwrap();
+    let updates = output
+        .messages()
+        .into_iter()
+        .filter(|m| m["method"] == "notifications/resources/updated")
+        .count();
+    assert_eq!(updates, 1);
+}
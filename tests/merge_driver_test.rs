@@ -0,0 +1,106 @@
+//! Integration test for the git merge driver: two branches edit the same bean
+//! and `git merge` goes through `bn merge-driver`.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use bn::bean::Bean;
+use bn::commands::merge_driver::install_git_merge_driver;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .unwrap()
+}
+
+fn setup_repo() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+    git(&root, &["init", "-q", "-b", "main"]);
+    install_git_merge_driver(&root).unwrap();
+    // Point the driver at the binary under test.
+    let driver = format!("{} merge-driver %O %A %B %P", env!("CARGO_BIN_EXE_bn"));
+    git(&root, &["config", "merge.beans.driver", &driver]);
+
+    fs::create_dir(root.join(".beans")).unwrap();
+    let mut bean = Bean::new("1", "Shared task");
+    bean.description = Some("Do the thing".to_string());
+    bean.labels = vec!["core".to_string()];
+    bean.to_file(root.join(".beans/1-shared-task.md")).unwrap();
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "base"]);
+    (dir, root)
+}
+
+fn edit_bean(root: &Path, f: impl FnOnce(&mut Bean)) {
+    let path = root.join(".beans/1-shared-task.md");
+    let mut bean = Bean::from_file(&path).unwrap();
+    f(&mut bean);
+    bean.to_file(&path).unwrap();
+}
+
+#[test]
+fn git_merge_combines_bean_edits_from_both_branches() {
+    let (_dir, root) = setup_repo();
+
+    git(&root, &["checkout", "-q", "-b", "feature"]);
+    edit_bean(&root, |b| {
+        b.labels.push("feature".to_string());
+        b.notes = Some("from feature".to_string());
+        b.priority = 0;
+    });
+    git(&root, &["commit", "-q", "-am", "feature edit"]);
+
+    git(&root, &["checkout", "-q", "main"]);
+    edit_bean(&root, |b| {
+        b.labels.push("main".to_string());
+        b.notes = Some("from main".to_string());
+        b.assignee = Some("alice".to_string());
+    });
+    git(&root, &["commit", "-q", "-am", "main edit"]);
+
+    let merge = git(&root, &["merge", "-q", "--no-edit", "feature"]);
+    assert!(
+        merge.status.success(),
+        "merge failed: {}",
+        String::from_utf8_lossy(&merge.stderr)
+    );
+
+    let merged = Bean::from_file(root.join(".beans/1-shared-task.md")).unwrap();
+    assert_eq!(merged.priority, 0);
+    assert_eq!(merged.assignee.as_deref(), Some("alice"));
+    assert!(merged.labels.contains(&"main".to_string()));
+    assert!(merged.labels.contains(&"feature".to_string()));
+    let notes = merged.notes.unwrap();
+    assert!(notes.contains("from main") && notes.contains("from feature"));
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn git_merge_reports_conflicting_fields() {
+    let (_dir, root) = setup_repo();
+
+    git(&root, &["checkout", "-q", "-b", "feature"]);
+    edit_bean(&root, |b| b.title = "Feature title".to_string());
+    git(&root, &["commit", "-q", "-am", "feature edit"]);
+
+    git(&root, &["checkout", "-q", "main"]);
+    edit_bean(&root, |b| b.title = "Main title".to_string());
+    git(&root, &["commit", "-q", "-am", "main edit"]);
+
+    let merge = git(&root, &["merge", "-q", "--no-edit", "feature"]);
+    assert!(!merge.status.success());
+
+    let merged = Bean::from_file(root.join(".beans/1-shared-task.md")).unwrap();
+    assert_eq!(merged.title, "Main title");
+    assert_eq!(merged.conflicts.len(), 1);
+    assert_eq!(merged.conflicts[0].field, "title");
+}
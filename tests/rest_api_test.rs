@@ -0,0 +1,392 @@
+//! Integration tests for the REST API (`bn serve`).
+//!
+//! Starts a `RestServer` on a free loopback port and drives it with a
+//! minimal HTTP/1.1 client over `TcpStream`.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+use bn::bean::Bean;
+use bn::index::Index;
+use bn::rest::RestServer;
+
+mod common;
+use common::{request_with, send_request, HttpResponse, TestServer};
+
+// ---------------------------------------------------------------------------
+// Test helpers
+// ---------------------------------------------------------------------------
+
+/// A .beans/ directory with two open beans, served over HTTP.
+fn start_server() -> TestServer {
+    let dir = TempDir::new().unwrap();
+    let beans_dir = dir.path().join(".beans");
+    fs::create_dir_all(&beans_dir).unwrap();
+    fs::write(
+        beans_dir.join("config.yaml"),
+        "project: rest-test\nnext_id: 3\n",
+    )
+    .unwrap();
+
+    let mut first = Bean::new("1", "Fix login bug");
+    first.slug = Some("fix-login-bug".to_string());
+    first.verify = Some("true".to_string());
+    first.labels = vec!["auth".to_string()];
+    first.to_file(beans_dir.join("1-fix-login-bug.md")).unwrap();
+
+    let mut second = Bean::new("2", "Write docs");
+    second.slug = Some("write-docs".to_string());
+    second.priority = 3;
+    second.verify = Some("false".to_string());
+    second.to_file(beans_dir.join("2-write-docs.md")).unwrap();
+
+    Index::build(&beans_dir).unwrap().save(&beans_dir).unwrap();
+
+    let server = RestServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let server_dir = beans_dir.clone();
+    // The server runs until the test process exits
+    std::thread::spawn(move || server.run(&server_dir));
+
+    TestServer {
+        _dir: dir,
+        beans_dir,
+        addr,
+    }
+}
+
+fn get(addr: SocketAddr, path: &str) -> HttpResponse {
+    request_with(addr, "GET", path, &[], "")
+}
+
+fn send_json(addr: SocketAddr, method: &str, path: &str, body: &Value) -> HttpResponse {
+    request_with(
+        addr,
+        method,
+        path,
+        &[("Content-Type", "application/json")],
+        &body.to_string(),
+    )
+}
+
+fn ids(list: &Value) -> Vec<&str> {
+    list.as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["id"].as_str().unwrap())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn list_and_filter_beans() {
+    let server = start_server();
+
+    let response = get(server.addr, "/v1/beans");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.header("Content-Type"), Some("application/json"));
+    assert_eq!(ids(&response.json()), ["1", "2"]);
+
+    let response = get(server.addr, "/v1/beans?label=auth");
+    assert_eq!(ids(&response.json()), ["1"]);
+    let response = get(server.addr, "/v1/beans?priority=3");
+    assert_eq!(ids(&response.json()), ["2"]);
+
+    assert_eq!(get(server.addr, "/v1/beans?priority=high").status, 400);
+    assert_eq!(get(server.addr, "/v1/beans?all=maybe").status, 400);
+}
+
+#[test]
+fn get_bean_and_unknown_bean() {
+    let server = start_server();
+
+    let response = get(server.addr, "/v1/beans/1");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json()["title"], "Fix login bug");
+
+    let response = get(server.addr, "/v1/beans/99");
+    assert_eq!(response.status, 404);
+    assert!(response.json()["error"]
+        .as_str()
+        .unwrap()
+        .contains("not found"));
+
+    assert_eq!(get(server.addr, "/v2/beans").status, 404);
+}
+
+#[test]
+fn create_bean_validates_like_the_cli() {
+    let server = start_server();
+
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans",
+        &json!({
+            "title": "Add search",
+            "verify": "false",
+            "labels": ["search", "ui"],
+            "deps": ["1"],
+            "priority": 1,
+        }),
+    );
+    assert_eq!(response.status, 201, "{}", response.body);
+    let bean = response.json();
+    assert_eq!(bean["id"], "3");
+    assert_eq!(bean["labels"], json!(["search", "ui"]));
+    assert_eq!(bean["dependencies"], json!(["1"]));
+    assert!(server.beans_dir.join("3-add-search.md").exists());
+
+    // Fail-first: a verify command that already passes is refused
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans",
+        &json!({"title": "Cheat", "verify": "true"}),
+    );
+    assert_eq!(response.status, 422);
+    assert!(response.json()["error"]
+        .as_str()
+        .unwrap()
+        .contains("already passes"));
+
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans",
+        &json!({"title": "Bad", "priority": 9}),
+    );
+    assert_eq!(response.status, 422);
+
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans",
+        &json!({"title": "Typo", "lables": ["x"]}),
+    );
+    assert_eq!(response.status, 400);
+
+    let response = request_with(server.addr, "POST", "/v1/beans", &[], "{not json");
+    assert_eq!(response.status, 400);
+}
+
+#[test]
+fn update_claim_and_release() {
+    let server = start_server();
+
+    let response = send_json(
+        server.addr,
+        "PATCH",
+        "/v1/beans/2",
+        &json!({"priority": 1, "note": "started", "add_label": "docs"}),
+    );
+    assert_eq!(response.status, 200, "{}", response.body);
+    let bean = response.json();
+    assert_eq!(bean["priority"], 1);
+    assert!(bean["notes"].as_str().unwrap().contains("started"));
+    assert_eq!(bean["labels"], json!(["docs"]));
+
+    let response = send_json(
+        server.addr,
+        "PATCH",
+        "/v1/beans/2",
+        &json!({"status": "sideways"}),
+    );
+    assert_eq!(response.status, 422);
+
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans/2/claim",
+        &json!({"by": "alice"}),
+    );
+    assert_eq!(response.status, 200, "{}", response.body);
+    assert_eq!(response.json()["claimed_by"], "alice");
+    assert_eq!(response.json()["status"], "in_progress");
+
+    let response = request_with(server.addr, "POST", "/v1/beans/2/release", &[], "");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json()["status"], "open");
+
+    assert_eq!(
+        send_json(server.addr, "PATCH", "/v1/beans/42", &json!({"title": "x"})).status,
+        404
+    );
+}
+
+#[test]
+fn close_runs_verify() {
+    let server = start_server();
+
+    // Bean 2's verify fails
+    let response = send_json(server.addr, "POST", "/v1/beans/2/close", &json!({}));
+    assert_eq!(response.status, 422, "{}", response.body);
+    let body = response.json();
+    assert_eq!(body["closed"], false);
+    assert_eq!(body["error"], "Verify failed");
+    assert_eq!(body["bean"]["attempts"], 1);
+
+    // Bean 1's verify passes
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans/1/close",
+        &json!({"reason": "fixed"}),
+    );
+    assert_eq!(response.status, 200, "{}", response.body);
+    let body = response.json();
+    assert_eq!(body["closed"], true);
+    assert_eq!(body["bean"]["status"], "closed");
+    assert_eq!(body["bean"]["close_reason"], "fixed");
+
+    // Archived beans are still readable, and can't be closed twice
+    assert_eq!(get(server.addr, "/v1/beans/1").status, 200);
+    let response = send_json(server.addr, "POST", "/v1/beans/1/close", &json!({}));
+    assert_eq!(response.status, 409);
+}
+
+#[test]
+fn dependencies_round_trip() {
+    let server = start_server();
+
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans/2/deps",
+        &json!({"depends_on": "1"}),
+    );
+    assert_eq!(response.status, 200, "{}", response.body);
+    assert_eq!(response.json()["dependencies"][0]["id"], "1");
+
+    let response = get(server.addr, "/v1/beans/1/deps");
+    assert_eq!(response.json()["dependents"][0]["id"], "2");
+
+    // A cycle is rejected
+    let response = send_json(
+        server.addr,
+        "POST",
+        "/v1/beans/1/deps",
+        &json!({"depends_on": "2"}),
+    );
+    assert_eq!(response.status, 422);
+
+    let response = request_with(server.addr, "DELETE", "/v1/beans/2/deps/1", &[], "");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json()["dependencies"], json!([]));
+}
+
+#[test]
+fn status_and_stats() {
+    let server = start_server();
+
+    let response = get(server.addr, "/v1/status");
+    assert_eq!(response.status, 200);
+    assert_eq!(ids(&response.json()["ready"]), ["1", "2"]);
+
+    let response = get(server.addr, "/v1/stats");
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json()["total"], 2);
+    assert_eq!(response.json()["open"], 2);
+}
+
+#[test]
+fn logs_plain_and_followed() {
+    let server = start_server();
+    let id = format!("9{}.78", std::process::id());
+    let log_dir = bn::commands::logs::log_dir().unwrap();
+    let log = log_dir.join(format!("{}-20990101-000000.log", id.replace('.', "_")));
+    fs::write(&log, "first line\n").unwrap();
+
+    let response = get(server.addr, &format!("/v1/beans/{}/logs", id));
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.header("Content-Type"),
+        Some("text/plain; charset=utf-8")
+    );
+    assert_eq!(response.body, "first line\n");
+
+    let stream = send_request(
+        server.addr,
+        "GET",
+        &format!("/v1/beans/{}/logs?follow=true", id),
+        &[],
+        "",
+    );
+    let mut reader = BufReader::new(stream);
+    let mut data = Vec::new();
+    let mut appended = false;
+    while data.len() < 2 {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        if let Some(d) = line.strip_prefix("data: ") {
+            data.push(d.trim_end().to_string());
+            if !appended {
+                fs::OpenOptions::new()
+                    .append(true)
+                    .open(&log)
+                    .unwrap()
+                    .write_all(b"second line\n")
+                    .unwrap();
+                appended = true;
+            }
+        }
+    }
+    fs::remove_file(&log).ok();
+    assert_eq!(data, ["first line", "second line"]);
+
+    assert_eq!(get(server.addr, "/v1/beans/404404/logs").status, 404);
+}
+
+#[test]
+fn openapi_describes_every_route() {
+    let server = start_server();
+    let response = get(server.addr, "/v1/openapi.json");
+    assert_eq!(response.status, 200);
+    let doc = response.json();
+    assert_eq!(doc["openapi"], "3.0.3");
+    assert_eq!(
+        doc["paths"]["/v1/beans"]["post"]["operationId"],
+        "createBean"
+    );
+    assert_eq!(
+        doc["paths"]["/v1/beans/{id}/close"]["post"]["requestBody"]["content"]["application/json"]
+            ["schema"]["$ref"],
+        "#/components/schemas/CloseBean"
+    );
+}
+
+#[test]
+fn rejects_foreign_origins_and_wrong_methods() {
+    let server = start_server();
+
+    let response = request_with(
+        server.addr,
+        "GET",
+        "/v1/beans",
+        &[("Origin", "https://evil.example")],
+        "",
+    );
+    assert_eq!(response.status, 403);
+
+    let response = request_with(
+        server.addr,
+        "GET",
+        "/v1/beans",
+        &[("Origin", "http://localhost:3000")],
+        "",
+    );
+    assert_eq!(response.status, 200);
+
+    let response = request_with(server.addr, "PUT", "/v1/beans/1", &[], "");
+    assert_eq!(response.status, 405);
+    assert_eq!(response.header("Allow"), Some("GET, PATCH"));
+}
@@ -0,0 +1,83 @@
+//! Integration test for the `sandbox:` profile: verify commands run with a
+//! scrubbed environment and a read-only project root.
+
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+use bn::error::EXIT_VERIFY_FAILED;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn_command, bn_ok, setup_project};
+
+/// Run `bn verify` with a variable in the environment that a sandbox scrubs.
+fn verify(dir: &Path, id: &str) -> Output {
+    bn_command(dir, &["verify", id])
+        .env("BEANS_SANDBOX_SECRET", "leaked")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn verify_runs_with_scrubbed_environment() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "sandbox", "sandbox: {}\n");
+
+    bn_ok(
+        root,
+        &[
+            "create",
+            "Env",
+            "--verify",
+            "test -z \"$BEANS_SANDBOX_SECRET\"",
+            "-p",
+        ],
+    );
+    assert!(verify(root, "1").status.success());
+
+    // Without a profile the environment is inherited
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "sandbox", "");
+    bn_ok(
+        root,
+        &[
+            "create",
+            "Env",
+            "--verify",
+            "test -z \"$BEANS_SANDBOX_SECRET\"",
+            "-p",
+        ],
+    );
+    let out = verify(root, "1");
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_FAILED));
+}
+
+#[test]
+fn verify_cannot_write_outside_writable_paths() {
+    if !bn::sandbox::isolation_available() {
+        eprintln!("skipping: namespaces unavailable");
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "sandbox", "sandbox:\n  writable: [target]\n");
+    fs::create_dir_all(root.join("target")).unwrap();
+
+    bn_ok(
+        root,
+        &["create", "Write", "--verify", "touch escaped", "-p"],
+    );
+    let out = verify(root, "1");
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_FAILED));
+    assert!(!root.join("escaped").exists());
+
+    bn_ok(
+        root,
+        &["create", "Build", "--verify", "touch target/out", "-p"],
+    );
+    assert!(verify(root, "2").status.success());
+    assert!(root.join("target/out").exists());
+}
@@ -0,0 +1,90 @@
+//! Integration test for structured test results: a failed `bn close` records
+//! the parsed test summary in the bean's history and `bn show` displays it.
+
+use std::fs;
+
+use bn::bean::Bean;
+use bn::discovery::find_bean_file;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn_ok, setup_project};
+
+const CARGO_OUTPUT: &str = "\
+test parser::parses ... ok
+test parser::rejects_empty ... FAILED
+
+---- parser::rejects_empty stdout ----
+thread 'parser::rejects_empty' panicked at src/parser.rs:40:9:
+assertion failed: input.is_empty()
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+#[test]
+fn failed_close_records_test_summary() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "results", "");
+    fs::write(root.join("out.txt"), CARGO_OUTPUT).unwrap();
+    bn_ok(
+        root,
+        &[
+            "create",
+            "Parser",
+            "--verify",
+            "cat out.txt; exit 101",
+            "-p",
+        ],
+    );
+
+    // The bean stays open
+    let stdout = bn_ok(root, &["close", "1"]);
+    assert!(
+        stdout.contains("1 of 2 tests failed: parser::rejects_empty"),
+        "{}",
+        stdout
+    );
+
+    let bean = Bean::from_file(find_bean_file(&root.join(".beans"), "1").unwrap()).unwrap();
+    let tests = bean.history.last().unwrap().tests.clone().unwrap();
+    assert_eq!((tests.passed, tests.failed), (1, 1));
+    assert_eq!(
+        tests.message.as_deref(),
+        Some("assertion failed: input.is_empty()")
+    );
+
+    let shown = bn_ok(root, &["show", "1", "--history"]);
+    assert!(shown.contains("1 of 2 tests failed: parser::rejects_empty"));
+}
+
+#[test]
+fn junit_reports_are_read_after_verify() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "results", "test_reports: [\"reports/*.xml\"]\n");
+    fs::create_dir_all(root.join("reports")).unwrap();
+    fs::write(
+        root.join("report.xml"),
+        r#"<testsuite><testcase name="a"/><testcase name="b"><failure message="b broke"/></testcase></testsuite>"#,
+    )
+    .unwrap();
+    bn_ok(
+        root,
+        &[
+            "create",
+            "Suite",
+            "--verify",
+            "cp report.xml reports/junit.xml; exit 1",
+            "-p",
+        ],
+    );
+
+    bn_ok(root, &["close", "1"]);
+
+    let bean = Bean::from_file(find_bean_file(&root.join(".beans"), "1").unwrap()).unwrap();
+    let tests = bean.history.last().unwrap().tests.clone().unwrap();
+    assert_eq!((tests.passed, tests.failed), (1, 1));
+    assert_eq!(tests.failing, vec!["b"]);
+    assert_eq!(tests.message.as_deref(), Some("b broke"));
+}
@@ -0,0 +1,158 @@
+//! Integration test for `bn undo`: destructive commands are reverted from
+//! the event log, and undo refuses when a later change conflicts.
+
+use std::path::Path;
+
+use bn::bean::{Bean, Status};
+use bn::discovery::{find_archived_bean, find_bean_file};
+use bn::error::EXIT_CONFLICT;
+use bn::index::Index;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn, bn_ok, setup_project};
+
+fn load(beans_dir: &Path, id: &str) -> Bean {
+    Bean::from_file(find_bean_file(beans_dir, id).unwrap()).unwrap()
+}
+
+#[test]
+fn undo_restores_deleted_bean_and_dependencies() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root, "undo-delete", "");
+
+    bn_ok(
+        root,
+        &[
+            "create",
+            "First",
+            "--verify",
+            "true",
+            "-p",
+            "--description",
+            "Line one\n\nLine two\n",
+        ],
+    );
+    bn_ok(
+        root,
+        &["create", "Second", "--verify", "true", "-p", "--deps", "1"],
+    );
+    bn_ok(root, &["delete", "1"]);
+    assert!(find_bean_file(&beans_dir, "1").is_err());
+    assert!(load(&beans_dir, "2").dependencies.is_empty());
+
+    let out = bn_ok(root, &["undo"]);
+    assert!(out.contains("Undid delete of bean 1"), "{}", out);
+    assert!(out.contains("1 related change"), "{}", out);
+
+    let first = load(&beans_dir, "1");
+    assert_eq!(first.title, "First");
+    assert!(first.description.unwrap().contains("Line two"));
+    assert_eq!(load(&beans_dir, "2").dependencies, vec!["1"]);
+    let index = Index::load(&beans_dir).unwrap();
+    assert!(index.beans.iter().any(|b| b.id == "1"));
+}
+
+#[test]
+fn undo_reverts_adopt() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root, "undo-adopt", "");
+
+    bn_ok(root, &["create", "Parent", "--verify", "true", "-p"]);
+    bn_ok(root, &["create", "Child", "--verify", "true", "-p"]);
+    bn_ok(root, &["adopt", "1", "2"]);
+    assert_eq!(load(&beans_dir, "1.1").title, "Child");
+
+    bn_ok(root, &["undo"]);
+    let child = load(&beans_dir, "2");
+    assert_eq!(child.title, "Child");
+    assert!(child.parent.is_none());
+    assert!(find_bean_file(&beans_dir, "1.1").is_err());
+}
+
+#[test]
+fn undo_reverts_move_in_both_projects() {
+    let dir = TempDir::new().unwrap();
+    let src_root = dir.path().join("src");
+    let dest_root = dir.path().join("dest");
+    let src_beans = setup_project(&src_root, "src", "");
+    let dest_beans = setup_project(&dest_root, "dest", "");
+
+    bn_ok(&src_root, &["create", "Portable", "--verify", "true", "-p"]);
+    bn_ok(
+        &dest_root,
+        &["move", "--from", src_root.to_str().unwrap(), "1"],
+    );
+    assert!(find_bean_file(&src_beans, "1").is_err());
+    assert_eq!(load(&dest_beans, "1").title, "Portable");
+
+    bn_ok(&dest_root, &["undo"]);
+    assert_eq!(load(&src_beans, "1").title, "Portable");
+    assert!(find_bean_file(&dest_beans, "1").is_err());
+
+    // The move was undone in the source project too
+    let out = bn(&src_root, &["undo"]);
+    assert!(out.status.success());
+    assert!(find_bean_file(&src_beans, "1").is_err());
+}
+
+#[test]
+fn undo_reopens_a_closed_bean() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root, "undo-close", "");
+
+    bn_ok(root, &["create", "Task", "--verify", "true", "-p"]);
+    bn_ok(root, &["update", "1", "--status", "closed"]);
+    bn_ok(root, &["undo"]);
+    assert_eq!(load(&beans_dir, "1").status, Status::Open);
+
+    bn_ok(root, &["close", "1"]);
+    assert!(find_archived_bean(&beans_dir, "1").is_ok());
+    bn_ok(root, &["undo"]);
+    assert_eq!(load(&beans_dir, "1").status, Status::Open);
+    assert!(find_archived_bean(&beans_dir, "1").is_err());
+}
+
+#[test]
+fn undo_steps_and_outside_edits() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root, "undo-conflict", "");
+
+    bn_ok(root, &["create", "Task", "--verify", "true", "-p"]);
+    bn_ok(root, &["update", "1", "--priority", "0"]);
+    bn_ok(root, &["update", "1", "--title", "Renamed"]);
+
+    // Several operations are reverted newest first, down to the create
+    bn_ok(root, &["undo", "--steps", "3"]);
+    assert!(find_bean_file(&beans_dir, "1").is_err());
+
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_project(root, "undo-conflict", "");
+    bn_ok(root, &["create", "Task", "--verify", "true", "-p"]);
+    bn_ok(root, &["update", "1", "--title", "Renamed"]);
+
+    let path = find_bean_file(&beans_dir, "1").unwrap();
+    let mut bean = Bean::from_file(&path).unwrap();
+    bean.title = "Edited by hand".to_string();
+    bean.to_file(&path).unwrap();
+
+    let out = bn(root, &["undo"]);
+    assert_eq!(out.status.code(), Some(EXIT_CONFLICT));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Cannot undo changes to bean 1"));
+    assert_eq!(load(&beans_dir, "1").title, "Edited by hand");
+}
+
+#[test]
+fn nothing_to_undo() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root, "undo-empty", "");
+    let out = bn(root, &["undo"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Nothing to undo"));
+}
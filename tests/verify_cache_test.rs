@@ -0,0 +1,129 @@
+//! Integration test for the verify cache: unchanged inputs replay the
+//! previous result, changed inputs and `--no-cache` rerun the command.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use bn::bean::{Bean, RunResult};
+use bn::discovery::find_archived_bean;
+use bn::error::EXIT_VERIFY_FAILED;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn, bn_ok};
+
+/// How many times the verify command has actually run.
+fn runs(root: &Path) -> usize {
+    fs::read_to_string(root.join("runs.log"))
+        .map(|s| s.lines().count())
+        .unwrap_or(0)
+}
+
+const VERIFY: &str = "echo run >> runs.log; test -f src/done";
+
+fn setup_project(root: &Path) {
+    common::setup_project(root, "cache", "verify_cache:\n  inputs: [\"src/**\"]\n");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "").unwrap();
+    bn_ok(root, &["create", "First", "--verify", VERIFY, "-p"]);
+    bn_ok(root, &["create", "Second", "--verify", VERIFY, "-p"]);
+}
+
+#[test]
+fn unchanged_inputs_replay_the_result() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root);
+
+    let out = bn(root, &["verify", "1"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_FAILED));
+    assert_eq!(runs(root), 1);
+
+    // Same inputs: the failure is replayed without running again
+    let out = bn(root, &["verify", "1"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_FAILED));
+    assert_eq!(runs(root), 1);
+
+    let out = bn(root, &["verify", "1", "--no-cache"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_FAILED));
+    assert_eq!(runs(root), 2);
+
+    // Changed inputs run the command again
+    fs::write(root.join("src/done"), "").unwrap();
+    bn_ok(root, &["close", "1"]);
+    assert_eq!(runs(root), 3);
+
+    // The second bean has the same command and inputs
+    let out = bn_ok(root, &["close", "2"]);
+    assert!(out.contains("cached"), "{}", out);
+    assert_eq!(runs(root), 3);
+    let beans_dir = root.join(".beans");
+    let bean = Bean::from_file(find_archived_bean(&beans_dir, "2").unwrap()).unwrap();
+    let record = bean.history.last().unwrap();
+    assert_eq!(record.result, RunResult::Pass);
+    assert!(record.cached);
+
+    let stats: serde_json::Value =
+        serde_json::from_str(&bn_ok(root, &["stats", "--json"])).unwrap();
+    assert_eq!(stats["verify_cache"]["hits"], 2);
+    assert_eq!(stats["verify_cache"]["misses"], 2);
+    assert_eq!(stats["verify_cache"]["entries"], 2);
+}
+
+#[test]
+fn verify_always_runs_without_cache_config() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    setup_project(root);
+    fs::write(
+        root.join(".beans/config.yaml"),
+        "project: cache\nnext_id: 3\n",
+    )
+    .unwrap();
+
+    bn(root, &["verify", "1"]);
+    bn(root, &["verify", "1"]);
+    assert_eq!(runs(root), 2);
+    assert!(!root.join(".beans/verify-cache.json").exists());
+}
+
+#[test]
+fn files_outside_paths_invalidate_in_git() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    common::setup_project(root, "cache", "verify_cache: {}\n");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "").unwrap();
+    fs::write(root.join("check.sh"), "test -f src/lib.rs\n").unwrap();
+    fs::write(root.join(".gitignore"), "runs.log\n").unwrap();
+    let init = Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(root)
+        .status();
+    if !init.is_ok_and(|s| s.success()) {
+        eprintln!("skipping: git unavailable");
+        return;
+    }
+    bn_ok(
+        root,
+        &[
+            "create",
+            "Lib",
+            "--verify",
+            "echo run >> runs.log; sh check.sh",
+            "--paths",
+            "src/lib.rs",
+            "-p",
+        ],
+    );
+
+    bn_ok(root, &["verify", "1"]);
+    bn_ok(root, &["verify", "1"]);
+    assert_eq!(runs(root), 1);
+
+    // The test script isn't in the bean's paths, but editing it is a miss
+    fs::write(root.join("check.sh"), "true\n").unwrap();
+    bn_ok(root, &["verify", "1"]);
+    assert_eq!(runs(root), 2);
+}
@@ -0,0 +1,112 @@
+//! Integration test for verify-gate tamper detection: `bn close` refuses a
+//! bean whose verify command or test script was weakened after the claim.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use bn::bean::{Bean, Status};
+use bn::discovery::{find_archived_bean, find_bean_file};
+use bn::error::EXIT_VERIFY_TAMPERED;
+use tempfile::TempDir;
+
+mod common;
+use common::{bn, bn_ok, setup_project};
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// A git project whose test script fails until `feature` exists, with a
+/// bean claimed against it.
+fn setup_claimed(root: &Path) -> std::path::PathBuf {
+    let beans_dir = setup_project(root, "gate", "");
+    fs::write(
+        root.join("check.sh"),
+        "#!/bin/sh\n# The feature must exist before this bean can close\ntest -f feature\n",
+    )
+    .unwrap();
+    git(root, &["init", "-q"]);
+    git(root, &["add", "check.sh"]);
+    git(root, &["commit", "-qm", "init"]);
+
+    bn_ok(root, &["create", "Feature", "--verify", "sh check.sh"]);
+    bn_ok(root, &["claim", "1"]);
+    beans_dir
+}
+
+fn load(beans_dir: &Path) -> Bean {
+    Bean::from_file(find_bean_file(beans_dir, "1").unwrap()).unwrap()
+}
+
+#[test]
+fn weakened_verify_command_is_held_for_review() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_claimed(root);
+
+    // Agents edit bean files directly
+    let path = find_bean_file(&beans_dir, "1").unwrap();
+    let mut bean = Bean::from_file(&path).unwrap();
+    bean.verify = Some("true".to_string());
+    bean.to_file(&path).unwrap();
+    let out = bn(root, &["close", "1"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_TAMPERED));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("verify command changed"));
+
+    let bean = load(&beans_dir);
+    assert_eq!(bean.status, Status::InReview);
+    assert!(bean.labels.contains(&"needs-human-review".to_string()));
+
+    // A human can still close it
+    bn_ok(root, &["close", "1", "--force"]);
+    assert!(find_archived_bean(&beans_dir, "1").is_ok());
+}
+
+#[test]
+fn gutted_test_script_is_held_for_review() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_claimed(root);
+
+    fs::write(root.join("check.sh"), "exit 0\n").unwrap();
+    let out = bn(root, &["close", "1"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_TAMPERED));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("check.sh shrank"));
+    assert_eq!(load(&beans_dir).status, Status::InReview);
+}
+
+#[test]
+fn honest_work_closes() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_claimed(root);
+
+    fs::write(root.join("feature"), "done").unwrap();
+    bn_ok(root, &["close", "1"]);
+    assert!(find_archived_bean(&beans_dir, "1").is_ok());
+}
+
+#[test]
+fn release_and_reclaim_keeps_the_claim_time_gate() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    let beans_dir = setup_claimed(root);
+
+    bn_ok(root, &["claim", "1", "--release"]);
+    let path = find_bean_file(&beans_dir, "1").unwrap();
+    let mut bean = Bean::from_file(&path).unwrap();
+    bean.verify = Some("true".to_string());
+    bean.to_file(&path).unwrap();
+    bn_ok(root, &["claim", "1", "--force"]);
+
+    let out = bn(root, &["close", "1"]);
+    assert_eq!(out.status.code(), Some(EXIT_VERIFY_TAMPERED));
+    assert_eq!(load(&beans_dir).status, Status::InReview);
+}
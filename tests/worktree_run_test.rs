@@ -0,0 +1,72 @@
+//! Integration test for `bn run` with `worktree: true`: the agent claims and
+//! closes its bean inside its own worktree, and the work is merged to main
+//! while main has uncommitted `.beans/` runtime changes.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use bn::discovery::{find_archived_bean, find_bean_file};
+use tempfile::TempDir;
+
+mod common;
+use common::bn_ok;
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn run_merges_agent_work_past_uncommitted_events() {
+    let dir = TempDir::new().unwrap();
+    let root = fs::canonicalize(dir.path()).unwrap().join("project");
+    fs::create_dir(&root).unwrap();
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+
+    bn_ok(&root, &["init", "wt", "--no-agent"]);
+    let bn = env!("CARGO_BIN_EXE_bn");
+    let config = root.join(".beans/config.yaml");
+    let mut yaml = fs::read_to_string(&config).unwrap();
+    yaml.push_str(&format!(
+        "run: \"{bn} claim {{id}} && echo done > feature.txt && {bn} close {{id}}\"\nworktree: true\n",
+        bn = bn
+    ));
+    fs::write(&config, yaml).unwrap();
+    bn_ok(
+        &root,
+        &["create", "Task", "--verify", "test -f feature.txt"],
+    );
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-q", "-m", "base"]);
+
+    // Work on main while the agent runs leaves events.jsonl modified
+    bn_ok(&root, &["create", "Other"]);
+
+    let output = bn_ok(&root, &["run"]);
+    let beans_dir = root.join(".beans");
+    assert!(root.join("feature.txt").exists(), "{}", output);
+    assert!(find_archived_bean(&beans_dir, "1").is_ok());
+    assert!(find_bean_file(&beans_dir, "1").is_err());
+    assert!(!root.parent().unwrap().join("project-worktrees/1").exists());
+
+    let events = fs::read_to_string(beans_dir.join("events.jsonl")).unwrap();
+    for action in [
+        "\"create\",\"id\":\"2\"",
+        "\"claim\",\"id\":\"1\"",
+        "\"close\",\"id\":\"1\"",
+    ] {
+        assert!(events.contains(action), "missing {} in {}", action, events);
+    }
+}